@@ -0,0 +1,255 @@
+//! Build-script gate: fails compilation of a contract while cosmwasm-guard
+//! reports findings at or above a severity (High by default).
+//!
+//! Add `cosmwasm-guard-build` to the contract's `[build-dependencies]` and
+//! call the gate from `main` in `build.rs`:
+//!
+//! ```no_run
+//! cosmwasm_guard_build::Gate::new().run();
+//! ```
+//!
+//! Parsed files are cached in `OUT_DIR`, and cargo reruns the check only when
+//! `src/` or the config changes. Set `COSMWASM_GUARD_SKIP=1` to build anyway.
+//!
+//! Findings go through the same pipeline as `cosmwasm-guard analyze`, so the
+//! gate fails on what `analyze --severity high` reports. A config or crate
+//! the gate cannot analyze fails the build too.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use cosmwasm_guard::ast::{analyze_crate_with_options, DiscoveryOptions};
+use cosmwasm_guard::cache::CacheManager;
+use cosmwasm_guard::config::Config;
+use cosmwasm_guard::finding::{Finding, Severity};
+use cosmwasm_guard::pipeline::{build_report, AnalysisOptions};
+
+/// Environment variable that disables the gate when set to anything but `0`
+pub const SKIP_ENV: &str = "COSMWASM_GUARD_SKIP";
+
+const CONFIG_FILE: &str = ".cosmwasm-guard.toml";
+
+/// Analysis of a contract crate from its build script
+#[derive(Debug, Clone)]
+pub struct Gate {
+    root: PathBuf,
+    config: Option<PathBuf>,
+    cache_dir: Option<PathBuf>,
+    min_severity: Severity,
+}
+
+impl Default for Gate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Gate {
+    /// Gate on High findings in the crate being built
+    pub fn new() -> Self {
+        Self {
+            root: std::env::var_os("CARGO_MANIFEST_DIR")
+                .map_or_else(|| PathBuf::from("."), PathBuf::from),
+            config: None,
+            cache_dir: std::env::var_os("OUT_DIR")
+                .map(|d| PathBuf::from(d).join("cosmwasm-guard-cache")),
+            min_severity: Severity::High,
+        }
+    }
+
+    /// Analyze `root` instead of the crate being built
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = root.into();
+        self
+    }
+
+    /// Config file to load. By default the nearest `.cosmwasm-guard.toml` in
+    /// the crate or one of its parent directories, e.g. the workspace root.
+    pub fn with_config(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config = Some(path.into());
+        self
+    }
+
+    /// Fail on findings of this severity or worse
+    pub fn with_min_severity(mut self, severity: Severity) -> Self {
+        self.min_severity = severity;
+        self
+    }
+
+    fn config_path(&self) -> Option<PathBuf> {
+        self.config.clone().or_else(|| {
+            self.root
+                .ancestors()
+                .map(|dir| dir.join(CONFIG_FILE))
+                .find(|path| path.is_file())
+        })
+    }
+
+    /// Findings that fail the gate: what `analyze` reports at the gate's
+    /// severity, with the config's detector selection, packs, suppressions,
+    /// severity overrides and correlations applied
+    pub fn check(&self) -> Result<Vec<Finding>> {
+        let config = match self.config_path() {
+            Some(path) => Config::load(&path)?,
+            None => Config::default(),
+        };
+        let discovery = DiscoveryOptions {
+            exclude: config.suppressions.files.clone(),
+            follow_symlinks: config.global.follow_symlinks,
+            ..Default::default()
+        };
+        let mut cache = self
+            .cache_dir
+            .as_ref()
+            .and_then(|dir| CacheManager::open(dir.clone()).ok());
+        let analysis = analyze_crate_with_options(&self.root, cache.as_mut(), &discovery)?;
+
+        let options = AnalysisOptions {
+            detectors: None,
+            exclude: None,
+            min_severity: self.min_severity.clone(),
+            experimental: false,
+            schema_dir: None,
+            rank: false,
+            function: None,
+            only_reachable: false,
+            filter_crate: None,
+        };
+        let report = build_report(
+            &analysis,
+            &config,
+            &options,
+            cosmwasm_guard_detectors::all_detectors(),
+        );
+        Ok(report.findings)
+    }
+
+    /// Run the gate from `build.rs`, exiting with an error that fails the
+    /// build when findings remain or the crate cannot be analyzed
+    pub fn run(self) {
+        println!("cargo:rerun-if-env-changed={SKIP_ENV}");
+        println!("cargo:rerun-if-changed={}", self.root.join("src").display());
+        if let Some(config) = self.config_path() {
+            println!("cargo:rerun-if-changed={}", config.display());
+        }
+        if std::env::var_os(SKIP_ENV).is_some_and(|v| v != "0") {
+            return;
+        }
+
+        let findings = match self.check() {
+            Ok(findings) => findings,
+            Err(e) => {
+                eprintln!(
+                    "cosmwasm-guard could not analyze the crate: {e:#}\n\
+                     fix the config or set {SKIP_ENV}=1 to build anyway"
+                );
+                std::process::exit(1);
+            }
+        };
+        if findings.is_empty() {
+            return;
+        }
+        for finding in &findings {
+            let location = finding
+                .locations
+                .first()
+                .map(|loc| format!("{}:{}", loc.file.display(), loc.start_line))
+                .unwrap_or_default();
+            eprintln!(
+                "{}: {} [{}] {location}",
+                finding.severity, finding.title, finding.detector_name
+            );
+        }
+        eprintln!(
+            "cosmwasm-guard: {} finding(s) at {} or above; fix or suppress them, \
+             or set {SKIP_ENV}=1 to build anyway",
+            findings.len(),
+            self.min_severity
+        );
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_crate(name: &str, source: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"vault\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("src/lib.rs"), source).unwrap();
+        dir
+    }
+
+    const UNGUARDED: &str = r#"
+        pub const CONFIG: Item<Config> = Item::new("config");
+
+        #[entry_point]
+        pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+            -> StdResult<Response> {
+            match msg {
+                ExecuteMsg::UpdateConfig { owner } => {
+                    CONFIG.save(deps.storage, &Config { owner })?;
+                    Ok(Response::new())
+                }
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_gate_reports_high_findings() {
+        let dir = write_crate("cosmwasm-guard-build-test-gate", UNGUARDED);
+        let findings = Gate::new().with_root(&dir).check().unwrap();
+        assert!(!findings.is_empty());
+        assert!(findings.iter().all(|f| f.severity <= Severity::High));
+        assert!(findings
+            .iter()
+            .any(|f| f.detector_name == "missing-access-control"));
+    }
+
+    #[test]
+    fn test_gate_respects_config_suppressions() {
+        let dir = write_crate("cosmwasm-guard-build-test-config", UNGUARDED);
+        std::fs::write(
+            dir.join(CONFIG_FILE),
+            "[detectors.missing-access-control]\nenabled = false\n",
+        )
+        .unwrap();
+        let findings = Gate::new().with_root(&dir).check().unwrap();
+        assert!(findings
+            .iter()
+            .all(|f| f.detector_name != "missing-access-control"));
+    }
+
+    #[test]
+    fn test_gate_applies_severity_overrides() {
+        let dir = write_crate("cosmwasm-guard-build-test-retier", UNGUARDED);
+        std::fs::write(
+            dir.join(CONFIG_FILE),
+            "[detectors.missing-access-control]\nseverity = \"low\"\n",
+        )
+        .unwrap();
+        let findings = Gate::new().with_root(&dir).check().unwrap();
+        assert!(findings
+            .iter()
+            .all(|f| f.detector_name != "missing-access-control"));
+    }
+
+    #[test]
+    fn test_gate_fails_on_unloadable_config() {
+        let dir = write_crate("cosmwasm-guard-build-test-extends", UNGUARDED);
+        std::fs::write(
+            dir.join(CONFIG_FILE),
+            "extends = \"https://config.invalid/org-guard.toml\"\n",
+        )
+        .unwrap();
+        assert!(Gate::new().with_root(&dir).check().is_err());
+    }
+}
@@ -0,0 +1,38 @@
+//! `cargo cosmwasm-guard <args>`: cargo runs this binary as
+//! `cargo-cosmwasm-guard cosmwasm-guard <args>`, so drop the subcommand name
+//! and hand the rest to the `cosmwasm-guard` binary installed alongside.
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::{Command, ExitCode};
+
+fn main() -> ExitCode {
+    let mut args: Vec<OsString> = std::env::args_os().skip(1).collect();
+    if args.first().is_some_and(|a| a == "cosmwasm-guard") {
+        args.remove(0);
+    }
+
+    // Prefer the binary next to this one, so both come from the same install
+    let binary = format!("cosmwasm-guard{}", std::env::consts::EXE_SUFFIX);
+    let program = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(&binary)))
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| PathBuf::from(&binary));
+
+    match Command::new(&program).args(&args).status() {
+        Ok(status) => ExitCode::from(
+            status
+                .code()
+                .and_then(|c| u8::try_from(c).ok())
+                .unwrap_or(1),
+        ),
+        Err(e) => {
+            eprintln!(
+                "cargo-cosmwasm-guard: failed to run {}: {e}",
+                program.display()
+            );
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Report bundles: one archive holding the report, the config, the tool
+//! version and the analyzed sources (or only their hashes), as an immutable
+//! record of what was scanned.
+
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use cosmwasm_guard::config::Config;
+use cosmwasm_guard::report::AnalysisReport;
+
+use crate::output::pdf::utc_date;
+
+/// What goes into a bundle besides the report
+pub struct BundleContents<'a> {
+    /// Analyzed files at their reported paths, with the text analyzed
+    pub sources: Vec<(PathBuf, String)>,
+    /// Config in effect, with the configs it extends and command-line
+    /// overrides applied
+    pub config: &'a Config,
+    /// Record only the hashes of the sources, not the sources themselves
+    pub hashes_only: bool,
+    /// Revision analyzed with `--rev`
+    pub rev: Option<&'a str>,
+    /// Branch whose policy selected the threshold
+    pub branch: Option<&'a str>,
+}
+
+/// Path of `file` inside `sources/`. Roots and `..` are percent-encoded into
+/// plain components (`%2F`, `%2E%2E`, and `%25` for a literal `%`), so no
+/// entry lands outside the bundle and distinct paths never share an entry.
+fn bundle_path(file: &Path) -> PathBuf {
+    let escape = |part: &std::ffi::OsStr| part.to_string_lossy().replace('%', "%25");
+    file.components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(escape(part)),
+            Component::ParentDir => Some("%2E%2E".to_string()),
+            Component::RootDir => Some("%2F".to_string()),
+            Component::Prefix(prefix) => Some(escape(prefix.as_os_str()).replace(':', "%3A")),
+            Component::CurDir => None,
+        })
+        .collect()
+}
+
+/// Write `report` and `contents` to the archive `out`, compressed as its
+/// extension says (`.tar.zst`, `.tar.gz`, `.tar`...)
+pub fn write(out: &Path, report: &AnalysisReport, contents: &BundleContents) -> Result<()> {
+    let staging = tempfile::Builder::new()
+        .prefix("cosmwasm-guard-bundle-")
+        .tempdir()
+        .context("Failed to create a staging directory")?;
+    stage(staging.path(), report, contents)?;
+    archive(staging.path(), out)
+}
+
+fn stage(dir: &Path, report: &AnalysisReport, contents: &BundleContents) -> Result<()> {
+    std::fs::write(
+        dir.join("report.json"),
+        serde_json::to_string_pretty(report)?,
+    )?;
+    std::fs::write(dir.join("config.toml"), contents.config.effective_toml()?)?;
+
+    let mut files = Vec::new();
+    let mut sources = contents.sources.iter().collect::<Vec<_>>();
+    sources.sort_by(|a, b| a.0.cmp(&b.0));
+    for (path, text) in sources {
+        let entry = Path::new("sources").join(bundle_path(path));
+        if !contents.hashes_only {
+            let dest = dir.join(&entry);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&dest, text)?;
+        }
+        files.push(json!({
+            "path": path,
+            "sha256": format!("{:x}", Sha256::digest(text.as_bytes())),
+            "bytes": text.len(),
+            "bundled": (!contents.hashes_only).then(|| entry.to_string_lossy().into_owned()),
+        }));
+    }
+
+    let now = SystemTime::now();
+    let manifest = json!({
+        "tool": "cosmwasm-guard",
+        "version": env!("CARGO_PKG_VERSION"),
+        "created": utc_date(now),
+        "created_unix": now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        "arguments": std::env::args().skip(1).collect::<Vec<_>>(),
+        "rev": contents.rev,
+        "branch": contents.branch,
+        "config": "config.toml",
+        "report": "report.json",
+        "files": files,
+    });
+    std::fs::write(
+        dir.join("manifest.json"),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    Ok(())
+}
+
+fn archive(staging: &Path, out: &Path) -> Result<()> {
+    if let Some(parent) = out.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let out = std::path::absolute(out)?;
+    let output = Command::new("tar")
+        .arg("--auto-compress")
+        .arg("-cf")
+        .arg(&out)
+        .arg("-C")
+        .arg(staging)
+        .arg(".")
+        .output()
+        .context("Failed to run tar")?;
+    if !output.status.success() {
+        bail!(
+            "Failed to write bundle {}: {}",
+            out.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_path_stays_inside_bundle() {
+        assert_eq!(
+            bundle_path(Path::new("./src/lib.rs")),
+            PathBuf::from("src/lib.rs")
+        );
+        assert_eq!(
+            bundle_path(Path::new("/home/ci/contract/src/lib.rs")),
+            PathBuf::from("%2F/home/ci/contract/src/lib.rs")
+        );
+        assert_eq!(
+            bundle_path(Path::new("./../contract/src/lib.rs")),
+            PathBuf::from("%2E%2E/contract/src/lib.rs")
+        );
+    }
+
+    #[test]
+    fn test_bundle_path_keeps_distinct_paths_apart() {
+        let paths = [
+            "contract/src/lib.rs",
+            "../contract/src/lib.rs",
+            "/contract/src/lib.rs",
+            "%2E%2E/contract/src/lib.rs",
+            "%2F/contract/src/lib.rs",
+        ];
+        let entries: std::collections::HashSet<PathBuf> =
+            paths.iter().map(|p| bundle_path(Path::new(p))).collect();
+        assert_eq!(entries.len(), paths.len());
+    }
+}
@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use cosmwasm_guard::advisories;
+use cosmwasm_guard::finding::Severity;
+use cosmwasm_guard::pipeline::{select_detectors, AnalysisOptions};
+
+/// List the advisories and incident writeups of the knowledge base, and which
+/// of them the detectors selected by the config cover
+pub fn run(config_path: Option<PathBuf>, experimental: bool) -> Result<()> {
+    let config_file = config_path.unwrap_or_else(|| PathBuf::from(".cosmwasm-guard.toml"));
+    let config = super::load_config(&config_file)?;
+    let options = AnalysisOptions {
+        detectors: None,
+        exclude: None,
+        min_severity: Severity::Informational,
+        experimental,
+        schema_dir: None,
+        rank: false,
+        function: None,
+        only_reachable: false,
+        filter_crate: None,
+    };
+    let selected: Vec<String> =
+        select_detectors(cosmwasm_guard_detectors::all_detectors(), &config, &options)
+            .iter()
+            .map(|d| d.name().to_string())
+            .collect();
+
+    println!("{:<14} {:<9} {:<32} Title", "ID", "Kind", "Covered by");
+    println!("{}", "-".repeat(100));
+    let mut covered = 0;
+    for advisory in advisories::all() {
+        let by: Vec<&str> = advisory
+            .detectors
+            .iter()
+            .filter(|d| selected.contains(d))
+            .map(String::as_str)
+            .collect();
+        let coverage = if by.is_empty() {
+            format!("- (needs {})", advisory.detectors.join(", "))
+        } else {
+            covered += 1;
+            by.join(", ")
+        };
+        println!(
+            "{:<14} {:<9} {:<32} {}",
+            advisory.id, advisory.kind, coverage, advisory.title
+        );
+        println!("{:<14} {}", "", advisory.url);
+    }
+
+    println!(
+        "\n{covered} of {} advisories covered by the selected detectors.",
+        advisories::all().len()
+    );
+    println!("`cosmwasm-guard explain <CODE>` lists the advisories of one detector.");
+    Ok(())
+}
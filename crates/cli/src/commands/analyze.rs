@@ -1,122 +1,563 @@
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 
-use cosmwasm_guard::ast::analyze_crate_cached;
+use cosmwasm_guard::ast::{
+    analyze_crate_with_options, analyze_sources, CrateAnalysis, DiscoveryOptions, TargetSelection,
+};
 use cosmwasm_guard::cache::CacheManager;
-use cosmwasm_guard::config::{self, Config};
-use cosmwasm_guard::detector::{AnalysisContext, DetectorRegistry};
+use cosmwasm_guard::config::Config;
 use cosmwasm_guard::finding::Severity;
-use cosmwasm_guard::report::AnalysisReport;
+use cosmwasm_guard::pipeline::{assign_owners, build_report, same_crate, AnalysisOptions};
+use cosmwasm_guard::report::{self, AnalysisError, AnalysisReport, LinkTemplate, PathRewriter};
+use cosmwasm_guard::{examples, repro};
 
+use crate::bundle::{self, BundleContents};
+use crate::git::{containing_dir, current_branch, git, NewCode, Worktree};
 use crate::output;
+use crate::output::html::HtmlModel;
+use crate::output::text::{self, Hyperlinks, TextOptions, Theme};
 use crate::{OutputFormat, SeverityFilter};
 
-#[allow(clippy::too_many_arguments)]
-pub fn run(
-    path: &Path,
-    format: OutputFormat,
-    severity: SeverityFilter,
-    detectors: Option<Vec<String>>,
-    exclude: Option<Vec<String>>,
-    config_path: Option<PathBuf>,
-    audit: bool,
-    no_cache: bool,
-    quiet: bool,
-    no_color: bool,
-) -> Result<()> {
-    // 1. Load config
-    let config_file = config_path.unwrap_or_else(|| PathBuf::from(".cosmwasm-guard.toml"));
-    let config = Config::load(&config_file)?;
-
-    // 2. Set up optional cache
-    let mut cache = if no_cache {
-        None
-    } else {
-        let cache_dir = path.join(".cosmwasm-guard-cache");
-        CacheManager::open(cache_dir).ok()
-    };
-
-    // 3. Parse, merge, and build IR (with caching when enabled)
-    let analysis = analyze_crate_cached(path, cache.as_mut())?;
-    let files: Vec<PathBuf> = analysis.source_map.keys().cloned().collect();
-
-    if !quiet {
-        eprintln!("Analyzing {} files...", files.len());
-    }
-
-    // 4. Build detector registry
-    let mut all_dets = cosmwasm_guard_detectors::all_detectors();
+/// Options of `cosmwasm-guard analyze`, gathered from its command line
+pub struct AnalyzeArgs {
+    pub paths: Vec<PathBuf>,
+    pub files_from: Option<PathBuf>,
+    pub rev: Option<String>,
+    pub format: OutputFormat,
+    pub severity: Option<SeverityFilter>,
+    pub branch: Option<String>,
+    pub detectors: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub packs: Option<Vec<String>>,
+    pub target_chain: Option<String>,
+    pub exclude_paths: Vec<String>,
+    pub follow_symlinks: bool,
+    pub strict_parse: bool,
+    pub strict: bool,
+    pub target: TargetSelection,
+    pub include_deps: Vec<String>,
+    pub include_examples: bool,
+    pub schema_dir: Option<PathBuf>,
+    pub rank: bool,
+    pub function: Option<String>,
+    pub only_reachable: bool,
+    pub only_new_code: Option<String>,
+    pub filter_crate: Option<String>,
+    pub gen_repro: Option<PathBuf>,
+    pub bundle: Option<PathBuf>,
+    pub bundle_hashes_only: bool,
+    pub explain_finding: Option<String>,
+    pub config_path: Option<PathBuf>,
+    pub audit: bool,
+    pub experimental: bool,
+    pub no_cache: bool,
+    pub quiet: bool,
+    pub no_color: bool,
+    pub theme: Option<Theme>,
+    pub width: Option<usize>,
+    pub compact: bool,
+    pub verbose: bool,
+    pub hyperlinks: Hyperlinks,
+    pub link_template: Option<String>,
+    pub path_rewriter: PathRewriter,
+}
 
-    // Apply config-based detector filtering
-    all_dets.retain(|d| config.is_detector_enabled(d.name()));
+pub fn run(args: AnalyzeArgs) -> Result<()> {
+    let AnalyzeArgs {
+        paths,
+        files_from,
+        rev,
+        format,
+        severity,
+        branch,
+        detectors,
+        exclude,
+        packs,
+        target_chain,
+        exclude_paths,
+        follow_symlinks,
+        strict_parse,
+        strict,
+        target,
+        include_deps,
+        include_examples,
+        schema_dir,
+        rank,
+        function,
+        only_reachable,
+        only_new_code,
+        filter_crate,
+        gen_repro,
+        bundle,
+        bundle_hashes_only,
+        explain_finding,
+        config_path,
+        audit,
+        experimental,
+        no_cache,
+        quiet,
+        no_color,
+        theme,
+        width,
+        compact,
+        verbose,
+        hyperlinks,
+        link_template,
+        path_rewriter,
+    } = args;
 
-    if let Some(ref names) = detectors {
-        all_dets.retain(|d| names.iter().any(|n| n == d.name()));
+    // 1. Load config
+    let config_file = config_path.unwrap_or_else(|| PathBuf::from(".cosmwasm-guard.toml"));
+    let mut config = super::load_config(&config_file)?;
+    if let Some(packs) = packs {
+        cosmwasm_guard::packs::resolve(&packs)?;
+        config.global.packs = packs;
     }
-    if let Some(ref names) = exclude {
-        all_dets.retain(|d| !names.iter().any(|n| n == d.name()));
+    if let Some(chain) = target_chain {
+        if !config.chains.contains_key(&chain) {
+            anyhow::bail!(
+                "Unknown target chain `{chain}`: add a [chains.{chain}] profile to {}",
+                config_file.display()
+            );
+        }
+        config.global.target_chain = Some(chain);
     }
 
-    let mut registry = DetectorRegistry::new();
-    registry.register_all(all_dets);
+    // Explicit paths are analyzed whole; listed files are analyzed within
+    // their crates, keeping only findings located in them
+    let mut targets: Vec<(PathBuf, Option<HashSet<PathBuf>>)> =
+        paths.into_iter().map(|path| (path, None)).collect();
+    if let Some(list) = files_from {
+        let files = read_file_list(&list)?;
+        let listed: HashSet<PathBuf> = files.iter().map(|f| canonical(f)).collect();
+        for root in file_list_roots(&files) {
+            targets.push((root, Some(listed.clone())));
+        }
+    }
 
-    // 5. Run detectors (parallel when >= 4 detectors)
-    let ctx = AnalysisContext::new(&analysis.contract, &analysis.ir, &analysis.source_map);
-    let mut all_findings = registry.run_all(&ctx);
+    let links = link_template
+        .map(|template| {
+            let path = targets.first().map_or(Path::new("."), |(p, _)| p.as_path());
+            link_template_for(path, rev.as_deref(), &template)
+        })
+        .transpose()?;
 
-    // Enrich findings with source snippets
-    for finding in &mut all_findings {
-        for loc in &mut finding.locations {
-            if loc.snippet.is_none() {
-                if let Some(source) = analysis.source_map.get(&loc.file) {
-                    loc.snippet = get_snippet(source, loc.start_line, loc.end_line);
+    // A revision is analyzed in a temporary worktree, leaving the working tree
+    // alone; findings are reported at their working-tree paths
+    let mut worktrees = Vec::new();
+    let mut checkout_paths = Vec::new();
+    if let Some(rev) = &rev {
+        for (path, _) in &mut targets {
+            // One checkout per repository
+            let checkout = match worktrees
+                .iter()
+                .find_map(|w: &Worktree| w.locate(path).ok())
+            {
+                Some(checkout) => checkout,
+                None => {
+                    let worktree = Worktree::checkout(path, rev)?;
+                    let checkout = worktree.locate(path)?;
+                    worktrees.push(worktree);
+                    checkout
                 }
-            }
+            };
+            checkout_paths.push((checkout.clone(), path.clone()));
+            *path = checkout;
         }
     }
 
-    // 6. Apply inline suppressions
-    let inline_suppressions = config::parse_inline_suppressions(&analysis.source_map);
-    all_findings = config::apply_suppressions(all_findings, &config, &inline_suppressions);
+    if let Some(dir) = schema_dir.as_deref().filter(|d| !d.is_dir()) {
+        anyhow::bail!("Schema directory not found: {}", dir.display());
+    }
 
-    // 7. Filter by severity (CLI flag overrides config, audit mode lowers to informational)
-    let min_severity = if audit {
-        Severity::Informational
-    } else {
-        match severity {
+    let discovery = DiscoveryOptions {
+        exclude: config
+            .suppressions
+            .files
+            .iter()
+            .cloned()
+            .chain(exclude_paths)
+            .collect(),
+        follow_symlinks: follow_symlinks || config.global.follow_symlinks,
+        target,
+        include_deps,
+        ..Default::default()
+    };
+    let mut policy_branch = None;
+    let min_severity = match (audit, severity) {
+        (true, _) => Severity::Informational,
+        (false, Some(severity)) => match severity {
+            SeverityFilter::Critical => Severity::Critical,
             SeverityFilter::High => Severity::High,
             SeverityFilter::Medium => Severity::Medium,
             SeverityFilter::Low => Severity::Low,
             SeverityFilter::Info => Severity::Informational,
+        },
+        // Gate by the branch's policy, so CI needs no per-branch flags
+        (false, None) => {
+            policy_branch = branch.or_else(|| {
+                (!config.branches.is_empty())
+                    .then(|| current_branch(targets.first().map_or(Path::new("."), |(p, _)| p)))
+                    .flatten()
+            });
+            let policy = policy_branch
+                .as_deref()
+                .and_then(|branch| config.branch_threshold(branch));
+            let matched = policy.is_some();
+            let threshold = policy.unwrap_or(Severity::Low);
+            if !quiet && (policy_branch.is_some() || !config.branches.is_empty()) {
+                match (&policy_branch, matched) {
+                    (Some(branch), true) => {
+                        eprintln!("Branch `{branch}`: reporting {threshold} and above")
+                    }
+                    (Some(branch), false) => eprintln!(
+                        "Branch `{branch}` matches no [[branches]] policy: reporting {threshold} and above"
+                    ),
+                    (None, _) => eprintln!(
+                        "No branch checked out for [[branches]] policies: reporting {threshold} and above"
+                    ),
+                }
+            }
+            threshold
         }
     };
-    all_findings.retain(|f| f.severity <= min_severity);
+    let options = AnalysisOptions {
+        detectors,
+        exclude,
+        min_severity,
+        // Audit mode wants maximum coverage, so it includes experimental detectors
+        experimental: experimental || audit,
+        schema_dir,
+        rank,
+        function,
+        only_reachable,
+        filter_crate,
+    };
+    if include_examples && options.min_severity != Severity::Informational && !quiet {
+        eprintln!("Example findings are Informational; use --severity info to report them");
+    }
+    // Findings on legacy lines are dropped whether or not a baseline accepts them
+    let new_code_since = only_new_code.map(|rev| match rev.as_str() {
+        "" => config
+            .global
+            .new_code_since
+            .clone()
+            .unwrap_or_else(|| "HEAD".to_string()),
+        _ => rev,
+    });
+    if let (Some(since), false) = (&new_code_since, quiet) {
+        eprintln!("Reporting findings on code changed since `{since}`");
+    }
+
+    let mut reports = Vec::new();
+    let mut html = matches!(format, OutputFormat::Html).then(HtmlModel::default);
+    let mut bundled_sources = Vec::new();
+    let mut function_found = false;
+    let mut crate_found = false;
+    for (path, only) in &targets {
+        // 2. Set up optional cache
+        let mut cache = if no_cache {
+            None
+        } else {
+            let cache_dir = path.join(".cosmwasm-guard-cache");
+            CacheManager::open(cache_dir).ok()
+        };
+
+        // 3. Parse, merge, and build IR (with caching when enabled), skipping
+        //    excluded and gitignored files before they are parsed
+        let analysis = analyze_crate_with_options(path, cache.as_mut(), &discovery)?;
+
+        if strict_parse && !analysis.errors.is_empty() {
+            let details: Vec<String> = analysis.errors.iter().map(format_error).collect();
+            anyhow::bail!(
+                "{} file(s) failed to parse (--strict-parse):\n  {}",
+                analysis.errors.len(),
+                details.join("\n  ")
+            );
+        }
+
+        if !quiet {
+            eprintln!("Analyzing {} files...", analysis.source_map.len());
+        }
+        function_found |= options
+            .function
+            .as_ref()
+            .is_some_and(|name| analysis.contract.functions.iter().any(|f| &f.name == name));
+
+        // 4. Run detectors, suppress and filter findings, build report
+        let mut report = build_report(
+            &analysis,
+            &config,
+            &options,
+            cosmwasm_guard_detectors::all_detectors(),
+        );
+        let mut example_sources = Vec::new();
+        if include_examples && options.min_severity == Severity::Informational {
+            let example_analysis = analyze_sources(
+                path,
+                examples::collect(path, &analysis.source_map, &discovery),
+            );
+            let example_report = build_example_report(&example_analysis, &config, &options);
+            report.findings.extend(example_report.findings);
+            for file in example_report.files_analyzed {
+                if !report.files_analyzed.contains(&file) {
+                    report.files_analyzed.push(file);
+                }
+            }
+            // Doc-test code is held at its file's path; only `examples/`
+            // files are sources of their own
+            example_sources.extend(
+                example_analysis
+                    .source_map
+                    .into_iter()
+                    .filter(|(file, _)| !analysis.source_map.contains_key(file)),
+            );
+        }
+        crate_found |= options
+            .filter_crate
+            .as_ref()
+            .is_some_and(|name| report.packages.iter().any(|p| same_crate(&p.name, name)));
+        if let Some(only) = only {
+            report.findings.retain(|f| {
+                f.locations
+                    .first()
+                    .is_some_and(|loc| only.contains(&canonical(&loc.file)))
+            });
+        }
+        if let Some(since) = &new_code_since {
+            let new_code = NewCode::since(path, since)?;
+            report.findings.retain(|f| {
+                f.locations.first().is_some_and(|loc| {
+                    new_code.touches(&canonical(&loc.file), loc.start_line, loc.end_line)
+                })
+            });
+        }
+        if let Some(model) = &mut html {
+            let name = match report.packages.as_slice() {
+                [package] => package.name.clone(),
+                _ => path.display().to_string(),
+            };
+            model.add(name, &analysis, &config, &report.findings);
+        }
+        if let Some(dir) = &gen_repro {
+            let written = write_repro(dir, &analysis, &report)?;
+            if !quiet && written > 0 {
+                eprintln!("Wrote {written} reproduction test(s) to {}", dir.display());
+            }
+        }
+        if bundle.is_some() {
+            bundled_sources.extend(analysis.source_map);
+            bundled_sources.extend(example_sources);
+        }
+        reports.push(report);
+    }
+    if let Some(name) = options.function.as_ref().filter(|_| !function_found) {
+        anyhow::bail!("No function named `{name}` in the analyzed code");
+    }
+    if let Some(name) = options.filter_crate.as_ref().filter(|_| !crate_found) {
+        anyhow::bail!("No crate named `{name}` in the analyzed code");
+    }
+    drop(worktrees);
+    let mut report = AnalysisReport::merge(reports);
+    if !checkout_paths.is_empty() {
+        let rewriter = PathRewriter::new(Vec::new(), checkout_paths);
+        report.rewrite_paths(&rewriter);
+        for (path, _) in &mut bundled_sources {
+            *path = rewriter.rewrite(path);
+        }
+        if let Some(model) = &mut html {
+            model.rewrite_paths(&rewriter);
+        }
+    }
+    if !path_rewriter.is_empty() {
+        report.rewrite_paths(&path_rewriter);
+        for (path, _) in &mut bundled_sources {
+            *path = path_rewriter.rewrite(path);
+        }
+        if let Some(model) = &mut html {
+            model.rewrite_paths(&path_rewriter);
+        }
+    }
+    // Owner patterns anchor to the reported paths, not the checkout's
+    assign_owners(&mut report.findings, &config);
 
-    // 8. Build report
-    let report = AnalysisReport::from_findings(files, all_findings);
+    if let Some(out) = &bundle {
+        let contents = BundleContents {
+            sources: bundled_sources,
+            config: &config,
+            hashes_only: bundle_hashes_only,
+            rev: rev.as_deref(),
+            branch: policy_branch.as_deref(),
+        };
+        bundle::write(out, &report, &contents)?;
+        if !quiet {
+            eprintln!("Wrote report bundle to {}", out.display());
+        }
+    }
 
-    // 9. Output
+    // 5. Output
+    if let Some(id) = explain_finding {
+        let Some(finding) = report
+            .findings
+            .iter()
+            .find(|f| f.id.as_deref() == Some(&id))
+        else {
+            anyhow::bail!("No finding with id `{id}` in the analyzed code");
+        };
+        return text::explain(finding, no_color);
+    }
     match format {
         OutputFormat::Json => output::json::print(&report)?,
         OutputFormat::Sarif => output::sarif::print(&report)?,
-        OutputFormat::Text => output::text::print(&report, quiet, no_color)?,
+        OutputFormat::Text => {
+            let options = TextOptions {
+                quiet,
+                no_color,
+                theme: theme.unwrap_or_else(|| Theme::from_name(&config.global.theme)),
+                width: text::resolve_width(width),
+                compact,
+                verbose,
+                hyperlinks: hyperlinks.enabled(),
+                links,
+            };
+            text::print(&report, &options)?
+        }
+        OutputFormat::Summary => output::summary::print(&report, no_color, strict)?,
+        OutputFormat::JsonSummary => output::summary::print_json(&report, strict)?,
+        OutputFormat::Html => output::html::print(&report, &html.unwrap_or_default())?,
+        OutputFormat::RustcJson => output::rustc_json::print(&report)?,
     }
 
-    // 10. Exit code
-    if report.total_findings > 0 {
-        std::process::exit(1);
+    // 6. Exit code
+    if strict && report.unverified() > 0 && !quiet {
+        eprintln!(
+            "{} file(s) or construct(s) could not be analyzed (--strict); run with --verbose to list them",
+            report.unverified()
+        );
+    }
+    let code = output::summary::exit_code(&report, strict);
+    if code != 0 {
+        std::process::exit(code);
     }
 
     Ok(())
 }
 
-fn get_snippet(source: &str, start_line: usize, end_line: usize) -> Option<String> {
-    let lines: Vec<&str> = source.lines().collect();
-    let start = start_line.saturating_sub(1);
-    let end = end_line.min(lines.len());
-    if start >= lines.len() {
-        return None;
+/// Findings in example code, all at Informational: it does not ship in the
+/// contract, but integrators copy it
+fn build_example_report(
+    analysis: &CrateAnalysis,
+    config: &Config,
+    options: &AnalysisOptions,
+) -> AnalysisReport {
+    let mut report = build_report(
+        analysis,
+        config,
+        options,
+        cosmwasm_guard_detectors::all_detectors(),
+    );
+    for finding in &mut report.findings {
+        finding.severity = Severity::Informational;
+        finding.severity_level = config
+            .severity_levels
+            .level_of(&finding.severity)
+            .map(String::from);
+        finding
+            .description
+            .push_str(" Found in example code, which integrators may copy into their contracts.");
+    }
+    report
+}
+
+/// Cargo package containing the analyzed sources
+pub fn contract_package(analysis: &CrateAnalysis) -> Option<report::packages::Package> {
+    let files: Vec<PathBuf> = analysis.source_map.keys().cloned().collect();
+    report::packages::group_by_package(&files)
+        .into_iter()
+        .next()
+}
+
+/// Library crate name of `package` as imported from its integration tests
+pub fn crate_name(package: Option<&report::packages::Package>) -> String {
+    package.map_or_else(|| "contract".to_string(), |p| p.name.replace('-', "_"))
+}
+
+/// Write `cw-multi-test` reproductions of the report's supported findings to
+/// `dir`, returning how many files were written
+fn write_repro(dir: &Path, analysis: &CrateAnalysis, report: &AnalysisReport) -> Result<usize> {
+    let crate_name = crate_name(contract_package(analysis).as_ref());
+    let cases = repro::generate(&report.findings, &analysis.contract, &crate_name);
+    if cases.is_empty() {
+        return Ok(0);
+    }
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    for case in &cases {
+        let path = dir.join(&case.file_name);
+        std::fs::write(&path, &case.source)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(cases.len())
+}
+
+/// Newline-separated file list from `list`, or stdin for `-`, keeping the
+/// `.rs` files that exist (deleted files show up in `git diff --name-only`)
+fn read_file_list(list: &Path) -> Result<Vec<PathBuf>> {
+    let content = if list == Path::new("-") {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        std::fs::read_to_string(list)
+            .with_context(|| format!("Failed to read file list: {}", list.display()))?
+    };
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .filter(|f| f.extension().is_some_and(|e| e == "rs") && f.is_file())
+        .collect())
+}
+
+/// What to analyze for a list of files: the root of each file's Cargo
+/// package, so cross-file context is intact, or the file itself when it is
+/// outside any package
+pub fn file_list_roots(files: &[PathBuf]) -> Vec<PathBuf> {
+    let grouped = report::packages::group_by_package(files);
+    let packaged: HashSet<&PathBuf> = grouped.iter().flat_map(|p| &p.files).collect();
+    grouped
+        .iter()
+        .map(|p| p.root.clone())
+        .chain(files.iter().filter(|f| !packaged.contains(f)).cloned())
+        .collect()
+}
+
+/// `path` canonicalized for comparing finding locations, or as-is when it
+/// does not exist
+pub fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Link template with `{rev}` set to the analyzed commit (`--rev`, or the one
+/// checked out at `path`) and paths made relative to its repository (`HEAD`
+/// and as-is outside git)
+fn link_template_for(path: &Path, rev: Option<&str>, template: &str) -> Result<LinkTemplate> {
+    let dir = containing_dir(path);
+    let rev =
+        git(dir, &["rev-parse", rev.unwrap_or("HEAD")]).unwrap_or_else(|_| "HEAD".to_string());
+    let root = git(dir, &["rev-parse", "--show-toplevel"])
+        .ok()
+        .map(PathBuf::from);
+    LinkTemplate::new(template, &rev, root)
+}
+
+/// `file:line:col: message`, for errors reported outside the normal output
+fn format_error(error: &AnalysisError) -> String {
+    match (error.line, error.column) {
+        (Some(line), Some(col)) => {
+            let file = error.file.display();
+            format!("{file}:{line}:{}: {}", col + 1, error.message)
+        }
+        _ => format!("{}: {}", error.file.display(), error.message),
     }
-    Some(lines[start..end].join("\n"))
 }
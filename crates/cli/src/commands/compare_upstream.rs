@@ -0,0 +1,111 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+
+use cosmwasm_guard::ast::{analyze_crate_with_options, DiscoveryOptions};
+use cosmwasm_guard::detector::AnalysisContext;
+use cosmwasm_guard::drift;
+
+use crate::fetch;
+use crate::OutputFormat;
+
+/// Compare a forked contract with the upstream it was copied from
+pub fn run(
+    path: &Path,
+    upstream: &str,
+    upstream_rev: Option<&str>,
+    upstream_dir: Option<&Path>,
+    format: OutputFormat,
+    config_path: Option<PathBuf>,
+    no_color: bool,
+) -> Result<()> {
+    let config_file = config_path.unwrap_or_else(|| PathBuf::from(".cosmwasm-guard.toml"));
+    let config = super::load_config(&config_file)?;
+
+    // Removed on drop, so kept until the comparison is done
+    let clone = if fetch::is_git_url(upstream) {
+        Some(fetch::git_repo(upstream, upstream_rev)?)
+    } else {
+        None
+    };
+    let mut upstream_path = match &clone {
+        Some(clone) => clone.root.clone(),
+        None => {
+            if upstream_rev.is_some() {
+                bail!("--upstream-rev needs a git URL for --upstream");
+            }
+            PathBuf::from(upstream)
+        }
+    };
+    let mut label = upstream.to_string();
+    if let Some(dir) = upstream_dir {
+        upstream_path = upstream_path.join(dir);
+        label = format!("{label} ({})", dir.display());
+    }
+    if !upstream_path.exists() {
+        bail!("Upstream not found: {}", upstream_path.display());
+    }
+
+    let discovery = DiscoveryOptions {
+        exclude: config.suppressions.files.clone(),
+        follow_symlinks: config.global.follow_symlinks,
+        ..Default::default()
+    };
+    let fork = analyze_crate_with_options(path, None, &discovery)?;
+    let upstream = analyze_crate_with_options(&upstream_path, None, &DiscoveryOptions::default())?;
+    let fork_ctx =
+        AnalysisContext::new(&fork.contract, &fork.ir, &fork.source_map).with_config(&config);
+    let upstream_ctx = AnalysisContext::new(&upstream.contract, &upstream.ir, &upstream.source_map)
+        .with_config(&config);
+    let divergences = drift::compare(&fork_ctx, &upstream_ctx);
+
+    match format {
+        OutputFormat::Sarif
+        | OutputFormat::Summary
+        | OutputFormat::JsonSummary
+        | OutputFormat::Html
+        | OutputFormat::RustcJson => {
+            bail!("compare-upstream supports text and json output")
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&divergences)?);
+        }
+        OutputFormat::Text => {
+            if no_color {
+                colored::control::set_override(false);
+            }
+            if divergences.is_empty() {
+                println!("No divergences from {label}");
+                return Ok(());
+            }
+            let relevant = divergences.iter().filter(|d| d.security_relevant).count();
+            println!();
+            println!(
+                "{}",
+                format!("  Divergences from {label}").bold().underline()
+            );
+            for divergence in &divergences {
+                let kind = serde_json::to_value(divergence.kind)?;
+                let line = format!(
+                    "    {:<24} {:<32} {}",
+                    kind.as_str().unwrap_or_default(),
+                    divergence.subject,
+                    divergence.detail
+                );
+                if divergence.security_relevant {
+                    println!("{}", line.red().bold());
+                } else {
+                    println!("{line}");
+                }
+            }
+            println!();
+            println!(
+                "  {} divergences, {} security-relevant",
+                divergences.len(),
+                relevant
+            );
+        }
+    }
+    Ok(())
+}
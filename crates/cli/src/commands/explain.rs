@@ -0,0 +1,46 @@
+use anyhow::{bail, Result};
+use cosmwasm_guard::{advisories, codes, packs};
+
+/// Describe a detector given its code or name, and how to select, suppress
+/// and configure it
+pub fn run(detector: &str) -> Result<()> {
+    let name = codes::resolve(detector);
+    let Some(d) = cosmwasm_guard_detectors::all_detectors()
+        .into_iter()
+        .find(|d| d.name() == name)
+    else {
+        bail!("unknown detector or code: {detector} (see `cosmwasm-guard list`)");
+    };
+    let id = codes::code_of(name).unwrap_or(name);
+
+    match codes::code_of(name) {
+        Some(code) => println!("{code}: {name}"),
+        None => println!("{name}"),
+    }
+    println!();
+    println!("{}", d.description());
+    println!();
+    println!("  Severity:   {}", d.severity());
+    println!("  Confidence: {}", d.confidence());
+    println!("  Maturity:   {}", d.maturity());
+    let in_packs: Vec<&str> = packs::PACKS
+        .iter()
+        .filter(|pack| pack.includes(name))
+        .map(|pack| pack.name)
+        .collect();
+    println!("  Rule packs: {}", in_packs.join(", "));
+    let linked: Vec<&advisories::Advisory> = advisories::for_detector(name).collect();
+    if !linked.is_empty() {
+        println!("  Advisories:");
+        for advisory in linked {
+            println!("    {} ({}): {}", advisory.id, advisory.kind, advisory.title);
+            println!("      {}", advisory.url);
+        }
+    }
+    println!();
+    println!("  Run only it:      cosmwasm-guard analyze <path> --detectors {id}");
+    println!("  Skip it:          cosmwasm-guard analyze <path> --exclude {id}");
+    println!("  Suppress a line:  // cosmwasm-guard-ignore: {id}");
+    println!("  Configure:        [detectors.{id}] in .cosmwasm-guard.toml");
+    Ok(())
+}
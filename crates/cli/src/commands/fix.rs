@@ -0,0 +1,169 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+
+use cosmwasm_guard::ast::{analyze_crate_with_options, DiscoveryOptions};
+use cosmwasm_guard::finding::{Finding, Severity};
+use cosmwasm_guard::fix;
+use cosmwasm_guard::pipeline::{build_report, AnalysisOptions};
+
+/// `file:line` of a finding's first location
+fn position(finding: &Finding) -> String {
+    finding
+        .locations
+        .first()
+        .map(|loc| format!("{}:{}", loc.file.display(), loc.start_line))
+        .unwrap_or_default()
+}
+
+/// Edition of the crate `file` belongs to: `package.edition` of the nearest
+/// manifest, looked up in the workspace manifest when inherited. A manifest
+/// without one means 2015, as for cargo; a loose file is taken as 2021.
+fn edition(file: &Path) -> String {
+    let file = std::fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf());
+    let mut inherited = false;
+    for dir in file.ancestors().skip(1) {
+        let Some(manifest) = std::fs::read_to_string(dir.join("Cargo.toml"))
+            .ok()
+            .and_then(|text| text.parse::<toml::Table>().ok())
+        else {
+            continue;
+        };
+        let table = if inherited {
+            manifest.get("workspace").and_then(|w| w.as_table())
+        } else {
+            Some(&manifest)
+        };
+        let Some(package) = table.and_then(|t| t.get("package")) else {
+            continue;
+        };
+        match package.get("edition") {
+            Some(toml::Value::String(edition)) => return edition.clone(),
+            // `edition.workspace = true`
+            Some(_) => inherited = true,
+            None => return "2015".to_string(),
+        }
+    }
+    "2021".to_string()
+}
+
+/// `text` of `file` formatted by rustfmt, with the edition and rustfmt.toml
+/// that apply to the file
+fn rustfmt(file: &Path, text: &str) -> Result<String> {
+    let dir = file
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let mut child = Command::new("rustfmt")
+        .args(["--edition", &edition(file), "--emit", "stdout", "--quiet"])
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run rustfmt")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+pub fn run(
+    path: &Path,
+    detectors: Option<Vec<String>>,
+    dry_run: bool,
+    format: bool,
+    config_path: Option<PathBuf>,
+) -> Result<()> {
+    let config_file = config_path.unwrap_or_else(|| PathBuf::from(".cosmwasm-guard.toml"));
+    let config = super::load_config(&config_file)?;
+
+    let discovery = DiscoveryOptions {
+        exclude: config.suppressions.files.clone(),
+        follow_symlinks: config.global.follow_symlinks,
+        ..Default::default()
+    };
+    let analysis = analyze_crate_with_options(path, None, &discovery)?;
+
+    let options = AnalysisOptions {
+        detectors,
+        exclude: None,
+        min_severity: Severity::Informational,
+        experimental: false,
+        schema_dir: None,
+        rank: false,
+        function: None,
+        only_reachable: false,
+        filter_crate: None,
+    };
+    let report = build_report(
+        &analysis,
+        &config,
+        &options,
+        cosmwasm_guard_detectors::all_detectors(),
+    );
+
+    let mut plan = fix::plan(&report.findings, &analysis.source_map);
+    plan.verify(&report.findings, &analysis.source_map);
+    for &idx in &plan.applied {
+        let finding = &report.findings[idx];
+        if let Some(fix) = &finding.fix {
+            println!(
+                "{}: {} ({})",
+                position(finding),
+                fix.description,
+                finding.detector_name
+            );
+        }
+    }
+    for skipped in &plan.skipped {
+        let finding = &report.findings[skipped.finding];
+        eprintln!(
+            "{}: skipped fix for `{}`: {}",
+            position(finding),
+            finding.title,
+            skipped.reason
+        );
+    }
+
+    let files = plan.files().count();
+    if dry_run {
+        println!(
+            "Would apply {} fixes to {} files",
+            plan.applied.len(),
+            files
+        );
+        return Ok(());
+    }
+    let mut fixed = plan.apply(&analysis.source_map);
+    if format {
+        for (file, text) in &mut fixed {
+            match rustfmt(file, text) {
+                Ok(formatted) => *text = formatted,
+                Err(err) => eprintln!(
+                    "{}: left unformatted, rustfmt failed: {err:#}",
+                    file.display()
+                ),
+            }
+        }
+    }
+    fix::write(&fixed)?;
+    println!("Applied {} fixes to {} files", plan.applied.len(), files);
+    let conflicts = plan
+        .skipped
+        .iter()
+        .filter(|s| s.reason != fix::BREAKS_PARSING)
+        .count();
+    if conflicts > 0 {
+        eprintln!(
+            "{conflicts} fixes conflicted with others; run `fix` again to apply them to the updated code"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use cosmwasm_guard::ast::{analyze_crate_with_options, DiscoveryOptions};
+use cosmwasm_guard::cache::CacheManager;
+use cosmwasm_guard::config::Config;
+use cosmwasm_guard::detector::Maturity;
+use cosmwasm_guard::finding::Severity;
+use cosmwasm_guard::pipeline::{build_report, AnalysisOptions};
+use cosmwasm_guard::report::AnalysisReport;
+
+use super::analyze::{canonical, file_list_roots};
+use crate::git::git;
+use crate::output::text::{self, Hyperlinks, TextOptions, Theme};
+
+const PRE_COMMIT_SCRIPT: &str = "#!/bin/sh\n\
+# Installed by `cosmwasm-guard hook install`\n\
+exec cosmwasm-guard hook run\n";
+
+const PRE_COMMIT_HOOKS_YAML: &str = r"- id: cosmwasm-guard
+  name: cosmwasm-guard
+  description: Static analysis of changed CosmWasm contract files
+  entry: cosmwasm-guard hook run
+  language: system
+  files: \.rs$
+  pass_filenames: true
+";
+
+/// Write the git pre-commit script, or with `pre_commit_yaml` a
+/// `.pre-commit-hooks.yaml` entry for the pre-commit framework
+pub fn install(pre_commit_yaml: bool, force: bool) -> Result<()> {
+    let (target, content) = if pre_commit_yaml {
+        (
+            PathBuf::from(git(Path::new("."), &["rev-parse", "--show-toplevel"])?)
+                .join(".pre-commit-hooks.yaml"),
+            PRE_COMMIT_HOOKS_YAML,
+        )
+    } else {
+        (
+            PathBuf::from(git(Path::new("."), &["rev-parse", "--git-path", "hooks"])?)
+                .join("pre-commit"),
+            PRE_COMMIT_SCRIPT,
+        )
+    };
+    if target.exists() && !force {
+        bail!(
+            "{} already exists; use --force to overwrite it",
+            target.display()
+        );
+    }
+    if let Some(dir) = target.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&target, content)
+        .with_context(|| format!("Failed to write: {}", target.display()))?;
+    #[cfg(unix)]
+    if !pre_commit_yaml {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o755))?;
+    }
+    println!("Installed {}", target.display());
+    Ok(())
+}
+
+/// Analyze the crates containing `files` (default: the staged `.rs` files)
+/// with the fast profile, reporting only findings in those files
+pub fn run(files: Vec<PathBuf>, config_path: Option<PathBuf>, no_color: bool) -> Result<()> {
+    let files = if files.is_empty() {
+        staged_files()?
+    } else {
+        files
+    };
+    let files: Vec<PathBuf> = files
+        .into_iter()
+        .filter(|f| f.extension().is_some_and(|e| e == "rs") && f.is_file())
+        .collect();
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let config_file = config_path.unwrap_or_else(|| PathBuf::from(".cosmwasm-guard.toml"));
+    let config = super::load_config(&config_file)?;
+    let options = fast_profile(&config);
+    let discovery = DiscoveryOptions {
+        exclude: config.suppressions.files.clone(),
+        follow_symlinks: config.global.follow_symlinks,
+        ..Default::default()
+    };
+
+    let roots = file_list_roots(&files);
+    let changed: HashSet<PathBuf> = files.iter().map(|f| canonical(f)).collect();
+
+    let mut findings = Vec::new();
+    let mut analyzed = Vec::new();
+    for root in &roots {
+        let mut cache = root
+            .is_dir()
+            .then(|| CacheManager::open(root.join(".cosmwasm-guard-cache")).ok())
+            .flatten();
+        let analysis = analyze_crate_with_options(root, cache.as_mut(), &discovery)?;
+        let report = build_report(
+            &analysis,
+            &config,
+            &options,
+            cosmwasm_guard_detectors::all_detectors(),
+        );
+        analyzed.extend(report.files_analyzed);
+        findings.extend(report.findings.into_iter().filter(|f| {
+            f.locations
+                .first()
+                .is_some_and(|loc| changed.contains(&canonical(&loc.file)))
+        }));
+    }
+
+    let report = AnalysisReport::from_findings(analyzed, findings);
+    let text_options = TextOptions {
+        quiet: true,
+        no_color,
+        theme: Theme::from_name(&config.global.theme),
+        width: text::resolve_width(None),
+        compact: false,
+        verbose: false,
+        hyperlinks: Hyperlinks::Auto.enabled(),
+        links: None,
+    };
+    text::print(&report, &text_options)?;
+    if report.total_findings > 0 {
+        eprintln!(
+            "cosmwasm-guard: {} finding(s) in changed files; commit with --no-verify to skip",
+            report.total_findings
+        );
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Detectors and threshold for commit-time checks: Stable-tier detectors
+/// only, reporting Medium and High findings
+fn fast_profile(config: &Config) -> AnalysisOptions {
+    let stable = cosmwasm_guard_detectors::all_detectors()
+        .into_iter()
+        .filter(|d| d.maturity() == Maturity::Stable)
+        .map(|d| d.name().to_string())
+        .collect();
+    AnalysisOptions {
+        detectors: Some(stable),
+        exclude: None,
+        min_severity: config.severity_threshold().min(Severity::Medium),
+        experimental: false,
+        schema_dir: None,
+        rank: false,
+        function: None,
+        only_reachable: false,
+        filter_crate: None,
+    }
+}
+
+/// Files added, copied, modified or renamed in the index
+fn staged_files() -> Result<Vec<PathBuf>> {
+    let out = git(
+        Path::new("."),
+        &["diff", "--cached", "--name-only", "--diff-filter=ACMR"],
+    )?;
+    Ok(out.lines().map(PathBuf::from).collect())
+}
@@ -0,0 +1,188 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use serde_json::json;
+
+use cosmwasm_guard::ast::{analyze_crate_with_options, DiscoveryOptions};
+use cosmwasm_guard::detector::AnalysisContext;
+use cosmwasm_guard::roles::RoleKind;
+use cosmwasm_guard::storage_metrics::{storage_access, ItemAccess};
+
+use crate::OutputFormat;
+
+/// Print the contract model: entry points, state, roles and the permission
+/// matrix, or with `storage` the storage access hot spots
+pub fn run(
+    path: &Path,
+    format: OutputFormat,
+    storage: bool,
+    config_path: Option<PathBuf>,
+    no_color: bool,
+) -> Result<()> {
+    let config_file = config_path.unwrap_or_else(|| PathBuf::from(".cosmwasm-guard.toml"));
+    let config = super::load_config(&config_file)?;
+
+    let discovery = DiscoveryOptions {
+        exclude: config.suppressions.files.clone(),
+        follow_symlinks: config.global.follow_symlinks,
+        ..Default::default()
+    };
+    let analysis = analyze_crate_with_options(path, None, &discovery)?;
+    let ctx = AnalysisContext::new(&analysis.contract, &analysis.ir, &analysis.source_map)
+        .with_config(&config);
+    let matrix = ctx.permissions();
+    let contract = &analysis.contract;
+
+    if storage {
+        return print_storage(&storage_access(&ctx), format, no_color);
+    }
+
+    match format {
+        OutputFormat::Sarif
+        | OutputFormat::Summary
+        | OutputFormat::JsonSummary
+        | OutputFormat::Html
+        | OutputFormat::RustcJson => {
+            bail!("inspect supports text and json output")
+        }
+        OutputFormat::Json => {
+            let entry_points: Vec<_> = contract
+                .entry_points
+                .iter()
+                .map(|ep| json!({ "name": ep.name, "kind": ep.kind }))
+                .collect();
+            let model = json!({
+                "entry_points": entry_points,
+                "state_items": contract.state_items,
+                "roles": matrix.roles,
+                "permissions": matrix.variants,
+            });
+            println!("{}", serde_json::to_string_pretty(&model)?);
+        }
+        OutputFormat::Text => {
+            if no_color {
+                colored::control::set_override(false);
+            }
+            println!();
+            println!("{}", "  Entry points".bold().underline());
+            for ep in &contract.entry_points {
+                println!("    {:<24} {:?}", ep.name, ep.kind);
+            }
+
+            println!();
+            println!("{}", "  State".bold().underline());
+            for item in &contract.state_items {
+                println!(
+                    "    {:<24} {:?}<{}>",
+                    item.name,
+                    item.storage_type,
+                    item.key_type
+                        .iter()
+                        .chain([&item.value_type])
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+
+            println!();
+            println!("{}", "  Roles".bold().underline());
+            if matrix.roles.is_empty() {
+                println!("    (none: no sender checks found)");
+            }
+            for role in &matrix.roles {
+                let kind = match role.kind {
+                    RoleKind::Address => "address",
+                    RoleKind::Membership => "membership",
+                    RoleKind::Helper => "helper",
+                };
+                println!(
+                    "    {:<24} {:<12} {}",
+                    role.name,
+                    kind,
+                    role.item.as_deref().unwrap_or("-")
+                );
+            }
+
+            println!();
+            println!("{}", "  Permission matrix".bold().underline());
+            println!("    {:<40} {:<24} Privileged writes", "Message", "Allowed");
+            for variant in &matrix.variants {
+                let message = format!("{}::{}", variant.message, variant.variant);
+                let allowed = if variant.callable_by_anyone() {
+                    "anyone".to_string()
+                } else {
+                    variant.roles.join(", ")
+                };
+                let line = format!(
+                    "    {:<40} {:<24} {}",
+                    message,
+                    allowed,
+                    variant.privileged_writes.join(", ")
+                );
+                if variant.is_flagged() {
+                    println!("{}", line.red().bold());
+                } else {
+                    println!("{line}");
+                }
+            }
+            let flagged = matrix.flagged().count();
+            if flagged > 0 {
+                println!(
+                    "    {} variant(s) callable by anyone mutate privileged state",
+                    flagged
+                );
+            }
+            println!();
+        }
+    }
+    Ok(())
+}
+
+/// Storage items, those touched by the most handlers first
+fn print_storage(items: &[ItemAccess], format: OutputFormat, no_color: bool) -> Result<()> {
+    match format {
+        OutputFormat::Text => {}
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&json!({ "storage": items }))?);
+            return Ok(());
+        }
+        _ => bail!("inspect supports text and json output"),
+    }
+    if no_color {
+        colored::control::set_override(false);
+    }
+    let mut items: Vec<&ItemAccess> = items.iter().collect();
+    items.sort_by_key(|item| std::cmp::Reverse((item.handlers(), item.paths)));
+
+    println!();
+    println!("{}", "  Storage access".bold().underline());
+    println!(
+        "    {:<24} {:>8} {:>6} {:>6} {:>6}",
+        "Item", "Handlers", "Loads", "Stores", "Paths"
+    );
+    for item in items {
+        let line = format!(
+            "    {:<24} {:>8} {:>6} {:>6} {:>6}",
+            item.item,
+            item.handlers(),
+            item.loads,
+            item.stores,
+            item.paths
+        );
+        if item.handlers() == 0 {
+            println!("{}", line.dimmed());
+            continue;
+        }
+        println!("{line}");
+        if !item.readers.is_empty() {
+            println!("      read by     {}", item.readers.join(", "));
+        }
+        if !item.writers.is_empty() {
+            println!("      written by  {}", item.writers.join(", "));
+        }
+    }
+    println!();
+    Ok(())
+}
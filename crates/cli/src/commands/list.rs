@@ -1,24 +1,41 @@
 use anyhow::Result;
+use cosmwasm_guard::{codes, packs};
 
 pub fn run() -> Result<()> {
     let detectors = cosmwasm_guard_detectors::all_detectors();
 
     println!(
-        "{:<30} {:<10} {:<12} Description",
-        "Name", "Severity", "Confidence"
+        "{:<8} {:<30} {:<10} {:<12} {:<14} Description",
+        "Code", "Name", "Severity", "Confidence", "Maturity"
     );
-    println!("{}", "-".repeat(90));
+    println!("{}", "-".repeat(114));
 
     for d in &detectors {
         println!(
-            "{:<30} {:<10} {:<12} {}",
+            "{:<8} {:<30} {:<10} {:<12} {:<14} {}",
+            codes::code_of(d.name()).unwrap_or("-"),
             d.name(),
             d.severity(),
             d.confidence(),
+            d.maturity(),
             d.description()
         );
     }
 
     println!("\nTotal: {} detectors", detectors.len());
+    println!("Experimental detectors run only with --experimental or `experimental = true`.");
+    println!("Codes work wherever names do; `cosmwasm-guard explain <CODE>` describes one.");
+
+    println!("\nRule packs (--packs, or `packs = [...]` under [global]):");
+    for pack in packs::PACKS {
+        let detectors = match pack.detectors {
+            ["*"] => "all".to_string(),
+            names => names.len().to_string(),
+        };
+        println!(
+            "  {:<8} {:>3} detectors  {}",
+            pack.name, detectors, pack.description
+        );
+    }
     Ok(())
 }
@@ -1,3 +1,23 @@
+pub mod advisories;
 pub mod analyze;
+pub mod compare_upstream;
+pub mod explain;
+pub mod fix;
+pub mod hook;
 pub mod init;
+pub mod inspect;
 pub mod list;
+pub mod report;
+pub mod scaffold;
+pub mod serve;
+pub mod suppress;
+
+/// Load the project config, reporting remote configs that could only be read
+/// from the cache on stderr
+pub fn load_config(path: &std::path::Path) -> anyhow::Result<cosmwasm_guard::config::Config> {
+    let (config, warnings) = crate::remote_config::load(path)?;
+    for warning in warnings {
+        eprintln!("warning: {warning}");
+    }
+    Ok(config)
+}
@@ -0,0 +1,319 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use serde_json::json;
+
+use cosmwasm_guard::ast::{analyze_crate_with_options, CrateAnalysis, DiscoveryOptions};
+use cosmwasm_guard::codes;
+use cosmwasm_guard::config::Config;
+use cosmwasm_guard::detector::AnalysisContext;
+use cosmwasm_guard::pipeline::{build_report, AnalysisOptions};
+use cosmwasm_guard::report::AnalysisReport;
+use cosmwasm_guard::surface;
+
+use super::analyze::contract_package;
+use crate::git;
+use crate::output::html::HtmlModel;
+use crate::output::{pdf, text};
+use crate::OutputFormat;
+
+/// How `report` organizes findings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportView {
+    /// Counts per owning team from the config's `[[owners]]` rules
+    ByOwner,
+    /// Findings under the entry point and execute variant they affect
+    ByEntryPoint,
+    /// Detectors with findings per execute and query variant, and variants
+    /// whose handler was not resolved
+    Coverage,
+}
+
+/// Config, analysis and report of the contract at `path`, with the config's
+/// severity threshold
+fn load(
+    path: &Path,
+    config_path: Option<PathBuf>,
+) -> Result<(Config, CrateAnalysis, AnalysisReport)> {
+    let config_file = config_path.unwrap_or_else(|| PathBuf::from(".cosmwasm-guard.toml"));
+    let config = super::load_config(&config_file)?;
+
+    let discovery = DiscoveryOptions {
+        exclude: config.suppressions.files.clone(),
+        follow_symlinks: config.global.follow_symlinks,
+        ..Default::default()
+    };
+    let analysis = analyze_crate_with_options(path, None, &discovery)?;
+
+    let options = AnalysisOptions {
+        detectors: None,
+        exclude: None,
+        min_severity: config.severity_threshold(),
+        experimental: false,
+        schema_dir: None,
+        rank: false,
+        function: None,
+        only_reachable: false,
+        filter_crate: None,
+    };
+    let report = build_report(
+        &analysis,
+        &config,
+        &options,
+        cosmwasm_guard_detectors::all_detectors(),
+    );
+    Ok((config, analysis, report))
+}
+
+/// Write a print-ready audit report of the contract at `path` to `out`
+pub fn pdf(path: &Path, out: &Path, config_path: Option<PathBuf>) -> Result<()> {
+    let (config, analysis, report) = load(path, config_path)?;
+    let contract = contract_package(&analysis)
+        .map_or_else(|| path.display().to_string(), |package| package.name);
+    let mut model = HtmlModel::default();
+    model.add(contract.clone(), &analysis, &config, &report.findings);
+    let meta = pdf::Metadata {
+        contract,
+        path: path.display().to_string(),
+        revision: git::git(git::containing_dir(path), &["rev-parse", "--short", "HEAD"]).ok(),
+        entry_points: analysis
+            .contract
+            .entry_points
+            .iter()
+            .map(|ep| ep.name.clone())
+            .collect(),
+        state_items: analysis.contract.state_items.len(),
+        generated: pdf::utc_date(SystemTime::now()),
+    };
+    std::fs::write(out, pdf::render(&report, &model, &meta))
+        .with_context(|| format!("Failed to write {}", out.display()))?;
+    println!("Wrote {}", out.display());
+    Ok(())
+}
+
+/// Summarize findings per owning team, list them per entry point, or show
+/// detector coverage per message variant
+pub fn run(
+    path: &Path,
+    view: ReportView,
+    format: OutputFormat,
+    config_path: Option<PathBuf>,
+    no_color: bool,
+) -> Result<()> {
+    let (config, analysis, report) = load(path, config_path)?;
+    if no_color {
+        colored::control::set_override(false);
+    }
+    if view != ReportView::ByOwner {
+        let ctx = AnalysisContext::new(&analysis.contract, &analysis.ir, &analysis.source_map)
+            .with_config(&config);
+        return match view {
+            ReportView::ByEntryPoint => print_by_entry_point(&report, &ctx, format),
+            _ => print_coverage(&report, &ctx, format),
+        };
+    }
+    let by_owner = report.counts_by_owner();
+
+    match format {
+        OutputFormat::Sarif
+        | OutputFormat::Summary
+        | OutputFormat::JsonSummary
+        | OutputFormat::Html
+        | OutputFormat::RustcJson => {
+            bail!("report supports text and json output")
+        }
+        OutputFormat::Json => {
+            let owners: serde_json::Map<String, serde_json::Value> = by_owner
+                .iter()
+                .map(|(owner, counts)| {
+                    let mut entry = serde_json::to_value(counts)?;
+                    entry["total"] = json!(counts.total());
+                    Ok((owner.to_string(), entry))
+                })
+                .collect::<Result<_>>()?;
+            let summary = json!({
+                "total_findings": report.total_findings,
+                "findings_by_owner": owners,
+            });
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+        }
+        OutputFormat::Text => {
+            println!();
+            println!("{}", "  Findings by owner".bold().underline());
+            println!(
+                "    {:<32} {:>8} {:>6} {:>6} {:>6} {:>6} {:>6}",
+                "Owner", "Critical", "High", "Medium", "Low", "Info", "Total"
+            );
+            for (owner, counts) in &by_owner {
+                println!(
+                    "    {:<32} {:>8} {:>6} {:>6} {:>6} {:>6} {:>6}",
+                    owner,
+                    counts.critical,
+                    counts.high,
+                    counts.medium,
+                    counts.low,
+                    counts.informational,
+                    counts.total()
+                );
+            }
+            println!("    Total: {}", report.total_findings);
+            println!();
+        }
+    }
+    Ok(())
+}
+
+/// Findings listed under each entry point and execute variant they affect
+fn print_by_entry_point(
+    report: &AnalysisReport,
+    ctx: &AnalysisContext,
+    format: OutputFormat,
+) -> Result<()> {
+    let groups = surface::group_by_entry_point(&report.findings, ctx);
+    match format {
+        OutputFormat::Sarif
+        | OutputFormat::Summary
+        | OutputFormat::JsonSummary
+        | OutputFormat::Html
+        | OutputFormat::RustcJson => {
+            bail!("report supports text and json output")
+        }
+        OutputFormat::Json => {
+            let groups: Vec<_> = groups
+                .iter()
+                .map(|g| {
+                    let findings: Vec<_> =
+                        g.findings.iter().map(|&i| &report.findings[i]).collect();
+                    json!({
+                        "entry_point": g.entry_point,
+                        "variant": g.variant,
+                        "findings": findings,
+                    })
+                })
+                .collect();
+            let summary = json!({
+                "total_findings": report.total_findings,
+                "findings_by_entry_point": groups,
+            });
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+        }
+        OutputFormat::Text => {
+            println!();
+            println!("{}", "  Findings by entry point".bold().underline());
+            let mut entry_point = None;
+            for group in &groups {
+                if entry_point != Some(&group.entry_point) {
+                    entry_point = Some(&group.entry_point);
+                    println!();
+                    match &group.entry_point {
+                        Some(name) => println!("    {}", name.bold()),
+                        None => println!("    {}", "Not reachable from an entry point".bold()),
+                    }
+                }
+                if let (Some(entry), variant) = (&group.entry_point, &group.variant) {
+                    let heading = match variant {
+                        Some(variant) => variant.clone(),
+                        None => format!("Other code reached from {entry}"),
+                    };
+                    println!("      {} ({})", heading.underline(), group.findings.len());
+                }
+                for &index in &group.findings {
+                    let finding = &report.findings[index];
+                    let code = codes::code_of(&finding.detector_name)
+                        .map(|code| format!("[{code}]"))
+                        .unwrap_or_default();
+                    let location = finding
+                        .locations
+                        .first()
+                        .map(|loc| format!("{}:{}", loc.file.display(), loc.start_line))
+                        .unwrap_or_default();
+                    println!(
+                        "        [{}]{code} {} {}",
+                        text::severity_label(&finding.severity),
+                        finding.title,
+                        location.dimmed()
+                    );
+                }
+            }
+            println!();
+            println!("    Total: {}", report.total_findings);
+            println!();
+        }
+    }
+    Ok(())
+}
+
+/// Detectors with findings per message variant, then the variants no match
+/// arm dispatches
+fn print_coverage(
+    report: &AnalysisReport,
+    ctx: &AnalysisContext,
+    format: OutputFormat,
+) -> Result<()> {
+    let coverage = surface::coverage(&report.findings, ctx);
+    let (resolved, gaps): (Vec<_>, Vec<_>) = coverage.iter().partition(|c| c.resolved());
+    match format {
+        OutputFormat::Sarif
+        | OutputFormat::Summary
+        | OutputFormat::JsonSummary
+        | OutputFormat::Html
+        | OutputFormat::RustcJson => {
+            bail!("report supports text and json output")
+        }
+        OutputFormat::Json => {
+            let summary = json!({
+                "total_findings": report.total_findings,
+                "variants": resolved,
+                "unresolved_variants": gaps.iter().map(|c| &c.variant).collect::<Vec<_>>(),
+            });
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+        }
+        OutputFormat::Text => {
+            println!();
+            println!(
+                "{}",
+                "  Detector coverage by message variant".bold().underline()
+            );
+            for variant in &resolved {
+                let handlers = match variant.handlers.is_empty() {
+                    true => "handled in its match arm".to_string(),
+                    false => variant.handlers.join(", "),
+                };
+                println!();
+                println!("    {} {}", variant.variant.bold(), handlers.dimmed());
+                if variant.detectors.is_empty() {
+                    println!("      {}", "no findings".dimmed());
+                }
+                for (detector, count) in &variant.detectors {
+                    let code = codes::code_of(detector)
+                        .map(|code| format!("[{code}] "))
+                        .unwrap_or_default();
+                    println!("      {code}{detector} ({count})");
+                }
+            }
+            if !gaps.is_empty() {
+                println!();
+                println!(
+                    "{}",
+                    "  Coverage gaps: variants with no resolved handler"
+                        .bold()
+                        .underline()
+                );
+                for variant in &gaps {
+                    println!("    {}", variant.variant.yellow());
+                }
+            }
+            println!();
+            println!(
+                "    {} of {} variants resolved, Total: {}",
+                resolved.len(),
+                coverage.len(),
+                report.total_findings
+            );
+            println!();
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use cosmwasm_guard::ast::{analyze_crate_with_options, DiscoveryOptions};
+use cosmwasm_guard::fuzz;
+
+use super::analyze::{contract_package, crate_name};
+
+/// Write a proptest harness fuzzing the contract's execute and query
+/// messages, by default to `tests/fuzz_messages.rs` in the contract package
+pub fn fuzz(
+    path: &Path,
+    output: Option<PathBuf>,
+    config_path: Option<PathBuf>,
+    force: bool,
+) -> Result<()> {
+    let config_file = config_path.unwrap_or_else(|| PathBuf::from(".cosmwasm-guard.toml"));
+    let config = super::load_config(&config_file)?;
+
+    let discovery = DiscoveryOptions {
+        exclude: config.suppressions.files.clone(),
+        follow_symlinks: config.global.follow_symlinks,
+        ..Default::default()
+    };
+    let analysis = analyze_crate_with_options(path, None, &discovery)?;
+    let package = contract_package(&analysis);
+    let Some(harness) = fuzz::generate(&analysis.contract, &crate_name(package.as_ref())) else {
+        bail!("no ExecuteMsg or QueryMsg enum found in {}", path.display());
+    };
+
+    let target = output.unwrap_or_else(|| {
+        package
+            .as_ref()
+            .map_or_else(|| PathBuf::from("."), |p| p.root.clone())
+            .join("tests/fuzz_messages.rs")
+    });
+    if target.exists() && !force {
+        bail!(
+            "{} already exists; use --force to overwrite it",
+            target.display()
+        );
+    }
+    if let Some(dir) = target.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&target, &harness.source)
+        .with_context(|| format!("Failed to write: {}", target.display()))?;
+
+    println!(
+        "Wrote {} ({} execute, {} query variants)",
+        target.display(),
+        harness.execute_variants,
+        harness.query_variants
+    );
+    println!("Add to [dev-dependencies]: proptest = \"1\", cw-multi-test = \"2\"");
+    Ok(())
+}
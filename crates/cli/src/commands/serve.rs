@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+
+use cosmwasm_guard::ast::{
+    analyze_crate_with_options, parse_source, ContractVisitor, CrateAnalysis, DiscoveryOptions,
+};
+use cosmwasm_guard::cache::CacheManager;
+use cosmwasm_guard::config::{self, Config};
+use cosmwasm_guard::finding::Severity;
+use cosmwasm_guard::ir::builder::IrBuilder;
+use cosmwasm_guard::pipeline::{build_report, AnalysisOptions};
+use cosmwasm_guard::report::AnalysisReport;
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const ANALYSIS_FAILED: i64 = -32000;
+
+/// Largest HTTP request body accepted, in bytes
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Longest HTTP request or header line accepted, in bytes
+const MAX_LINE_BYTES: u64 = 8 * 1024;
+
+/// How long one HTTP connection may stall a read or write before it is dropped
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Long-running analyzer answering JSON-RPC 2.0 requests. Config and per-crate
+/// caches stay loaded between requests.
+///
+/// Methods:
+/// - `analyze` `{path, detectors?, exclude?, severity?, experimental?, checkSchema?, rank?, function?, onlyReachable?, filterCrate?}` -> report
+/// - `analyzeSource` `{source, file?, detectors?, exclude?, severity?, experimental?, checkSchema?, rank?, function?, onlyReachable?, filterCrate?}` -> report
+/// - `listDetectors` -> `[{name, description, severity, confidence, maturity}]`
+/// - `explain` `{detector}` -> detector metadata
+///
+/// Over HTTP only requests addressed to localhost without an `Origin` header
+/// are served, so web pages cannot reach the server, and `analyze` paths must
+/// lie under the directory the server was started in.
+struct Server {
+    config: Config,
+    no_cache: bool,
+    caches: HashMap<PathBuf, CacheManager>,
+    /// Directory `analyze` paths are confined to; None on stdio
+    root: Option<PathBuf>,
+}
+
+pub fn run(listen: &str, stdio: bool, config_path: Option<PathBuf>, no_cache: bool) -> Result<()> {
+    let config_file = config_path.unwrap_or_else(|| PathBuf::from(".cosmwasm-guard.toml"));
+    let mut server = Server {
+        config: super::load_config(&config_file)?,
+        no_cache,
+        caches: HashMap::new(),
+        root: None,
+    };
+
+    if stdio {
+        return server.serve_stdio();
+    }
+
+    server.root = Some(std::env::current_dir()?.canonicalize()?);
+    let listener =
+        TcpListener::bind(listen).with_context(|| format!("Failed to listen on {listen}"))?;
+    eprintln!(
+        "cosmwasm-guard serving JSON-RPC on http://{}",
+        listener.local_addr()?
+    );
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("connection failed: {e}");
+                continue;
+            }
+        };
+        if let Err(e) = server.serve_http(stream) {
+            eprintln!("request failed: {e:#}");
+        }
+    }
+    Ok(())
+}
+
+impl Server {
+    /// Newline-delimited JSON-RPC over stdin/stdout, until stdin closes
+    fn serve_stdio(&mut self) -> Result<()> {
+        let stdin = std::io::stdin();
+        let mut stdout = std::io::stdout();
+        for line in stdin.lock().lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_message(&line) {
+                writeln!(stdout, "{response}")?;
+                stdout.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// One HTTP/1.1 request per connection; the JSON-RPC message is the POST body
+    fn serve_http(&mut self, mut stream: TcpStream) -> Result<()> {
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        let Some(request_line) = read_http_line(&mut reader)? else {
+            return write_http(&mut stream, "431 Request Header Fields Too Large", "");
+        };
+        let mut content_length = 0usize;
+        let mut local_host = false;
+        let mut has_origin = false;
+        loop {
+            let Some(header) = read_http_line(&mut reader)? else {
+                return write_http(&mut stream, "431 Request Header Fields Too Large", "");
+            };
+            if header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                let name = name.trim();
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                } else if name.eq_ignore_ascii_case("host") {
+                    local_host = is_local_host(value.trim());
+                } else if name.eq_ignore_ascii_case("origin") {
+                    has_origin = true;
+                }
+            }
+        }
+
+        // Browsers always send Origin on cross-origin POSTs, and a rebound DNS
+        // name still shows up in Host
+        if !local_host || has_origin {
+            return write_http(&mut stream, "403 Forbidden", "");
+        }
+        if !request_line.starts_with("POST ") {
+            return write_http(&mut stream, "405 Method Not Allowed", "");
+        }
+        if content_length > MAX_BODY_BYTES {
+            return write_http(&mut stream, "413 Payload Too Large", "");
+        }
+
+        let mut body = vec![0u8; content_length];
+        reader.read_exact(&mut body)?;
+        let body = String::from_utf8_lossy(&body);
+        match self.handle_message(&body) {
+            Some(response) => write_http(&mut stream, "200 OK", &response.to_string()),
+            None => write_http(&mut stream, "204 No Content", ""),
+        }
+    }
+
+    /// Handle a raw JSON-RPC message. Returns None for notifications (no `id`).
+    fn handle_message(&mut self, message: &str) -> Option<Value> {
+        let request: Value = match serde_json::from_str(message) {
+            Ok(v) => v,
+            Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+        };
+        let id = request.get("id").cloned();
+        let method = request.get("method").and_then(Value::as_str);
+        let (Some(method), Some("2.0")) = (method, request.get("jsonrpc").and_then(Value::as_str))
+        else {
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "expected a JSON-RPC 2.0 request with a method",
+            ));
+        };
+
+        let params = request.get("params").cloned().unwrap_or(json!({}));
+        let result = self.dispatch(method, &params);
+        let id = id?;
+        Some(match result {
+            Ok(value) => json!({ "jsonrpc": "2.0", "id": id, "result": value }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    fn dispatch(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "analyze" => {
+                let path = params
+                    .get("path")
+                    .and_then(Value::as_str)
+                    .ok_or((INVALID_PARAMS, "missing string param `path`".to_string()))?;
+                let options = analysis_options(params)?;
+                let path = self.confine(Path::new(path))?;
+                let analysis = self
+                    .analyze_path(&path)
+                    .map_err(|e| (ANALYSIS_FAILED, format!("{e:#}")))?;
+                report_value(&build_report(
+                    &analysis,
+                    &self.config,
+                    &options,
+                    cosmwasm_guard_detectors::all_detectors(),
+                ))
+            }
+            "analyzeSource" => {
+                let source = params
+                    .get("source")
+                    .and_then(Value::as_str)
+                    .ok_or((INVALID_PARAMS, "missing string param `source`".to_string()))?;
+                let file = params
+                    .get("file")
+                    .and_then(Value::as_str)
+                    .unwrap_or("input.rs");
+                let options = analysis_options(params)?;
+                let analysis = analyze_source(source, PathBuf::from(file))
+                    .map_err(|e| (ANALYSIS_FAILED, format!("{e:#}")))?;
+                report_value(&build_report(
+                    &analysis,
+                    &self.config,
+                    &options,
+                    cosmwasm_guard_detectors::all_detectors(),
+                ))
+            }
+            "listDetectors" => Ok(Value::Array(
+                cosmwasm_guard_detectors::all_detectors()
+                    .iter()
+                    .map(|d| detector_metadata(d.as_ref()))
+                    .collect(),
+            )),
+            "explain" => {
+                let name = params.get("detector").and_then(Value::as_str).ok_or((
+                    INVALID_PARAMS,
+                    "missing string param `detector`".to_string(),
+                ))?;
+                cosmwasm_guard_detectors::all_detectors()
+                    .iter()
+                    .find(|d| d.name() == name)
+                    .map(|d| detector_metadata(d.as_ref()))
+                    .ok_or((INVALID_PARAMS, format!("unknown detector `{name}`")))
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method `{method}`"))),
+        }
+    }
+
+    /// `path` if it lies under the server root, resolved against it
+    fn confine(&self, path: &Path) -> Result<PathBuf, (i64, String)> {
+        let Some(root) = &self.root else {
+            return Ok(path.to_path_buf());
+        };
+        match root.join(path).canonicalize() {
+            Ok(resolved) if resolved.starts_with(root) => Ok(resolved),
+            _ => Err((
+                INVALID_PARAMS,
+                format!("`{}` is not under {}", path.display(), root.display()),
+            )),
+        }
+    }
+
+    /// Analyze a crate, reusing the cache opened for it by an earlier request
+    fn analyze_path(&mut self, path: &Path) -> Result<CrateAnalysis> {
+        let discovery = DiscoveryOptions {
+            exclude: self.config.suppressions.files.clone(),
+            follow_symlinks: self.config.global.follow_symlinks,
+            ..Default::default()
+        };
+        if self.no_cache {
+            return analyze_crate_with_options(path, None, &discovery);
+        }
+        if !self.caches.contains_key(path) {
+            if let Ok(cache) = CacheManager::open(path.join(".cosmwasm-guard-cache")) {
+                self.caches.insert(path.to_path_buf(), cache);
+            }
+        }
+        let cache = self.caches.get_mut(path);
+        analyze_crate_with_options(path, cache, &discovery)
+    }
+}
+
+/// Build a single-file analysis from source text
+fn analyze_source(source: &str, file: PathBuf) -> Result<CrateAnalysis> {
+    let ast = parse_source(source)?;
+    let contract = ContractVisitor::extract(file.clone(), ast);
+    let ir = IrBuilder::build_contract(&contract);
+    let source_map = HashMap::from([(file, source.to_string())]);
+    Ok(CrateAnalysis {
+        contract,
+        ir,
+        source_map,
+        errors: Vec::new(),
+    })
+}
+
+fn analysis_options(params: &Value) -> Result<AnalysisOptions, (i64, String)> {
+    let names = |key: &str| -> Option<Vec<String>> {
+        params.get(key).and_then(Value::as_array).map(|a| {
+            a.iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+    };
+    let min_severity = match params.get("severity").and_then(Value::as_str) {
+        Some(s) => {
+            config::parse_severity(s).ok_or((INVALID_PARAMS, format!("unknown severity `{s}`")))?
+        }
+        None => Severity::Low,
+    };
+    Ok(AnalysisOptions {
+        detectors: names("detectors"),
+        exclude: names("exclude"),
+        min_severity,
+        experimental: params
+            .get("experimental")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        schema_dir: params
+            .get("checkSchema")
+            .and_then(Value::as_str)
+            .map(PathBuf::from),
+        rank: params.get("rank").and_then(Value::as_bool).unwrap_or(false),
+        function: params
+            .get("function")
+            .and_then(Value::as_str)
+            .map(String::from),
+        only_reachable: params
+            .get("onlyReachable")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        filter_crate: params
+            .get("filterCrate")
+            .and_then(Value::as_str)
+            .map(String::from),
+    })
+}
+
+fn detector_metadata(d: &dyn cosmwasm_guard::detector::Detector) -> Value {
+    json!({
+        "name": d.name(),
+        "description": d.description(),
+        "severity": d.severity(),
+        "confidence": d.confidence(),
+        "maturity": d.maturity().to_string(),
+    })
+}
+
+fn report_value(report: &AnalysisReport) -> Result<Value, (i64, String)> {
+    serde_json::to_value(report).map_err(|e| (ANALYSIS_FAILED, e.to_string()))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// Next line of an HTTP head, or None if it is longer than [`MAX_LINE_BYTES`]
+fn read_http_line(reader: &mut BufReader<TcpStream>) -> Result<Option<String>> {
+    let mut line = String::new();
+    let read = reader.by_ref().take(MAX_LINE_BYTES).read_line(&mut line)?;
+    if read as u64 == MAX_LINE_BYTES && !line.ends_with('\n') {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+/// Whether a Host header names the loopback interface, with or without a port
+fn is_local_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or(""),
+        None => host.split(':').next().unwrap_or(""),
+    };
+    matches!(name, "localhost" | "127.0.0.1" | "::1")
+}
+
+fn write_http(stream: &mut TcpStream, status: &str, body: &str) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()?;
+    Ok(())
+}
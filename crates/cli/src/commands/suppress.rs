@@ -0,0 +1,98 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+
+use cosmwasm_guard::ast::{analyze_crate_with_options, DiscoveryOptions};
+use cosmwasm_guard::baseline::{DEFAULT_BASELINE, TODO_REASON};
+use cosmwasm_guard::finding::Severity;
+use cosmwasm_guard::pipeline::{build_report, AnalysisOptions};
+
+/// Which findings `suppress` accepts
+pub enum SuppressMode {
+    /// Every current finding; the baseline is rewritten to match them
+    AllCurrent,
+    /// The findings with these ids, added to the existing baseline
+    Findings(Vec<String>),
+}
+
+pub fn run(
+    path: &Path,
+    mode: SuppressMode,
+    output: Option<PathBuf>,
+    config_path: Option<PathBuf>,
+) -> Result<()> {
+    let config_file = config_path.unwrap_or_else(|| PathBuf::from(".cosmwasm-guard.toml"));
+    let mut config = super::load_config(&config_file)?;
+    let default_output = config_file
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(DEFAULT_BASELINE);
+    let output = output
+        .or_else(|| config.baseline_path(&config_file))
+        .unwrap_or_else(|| default_output.clone());
+
+    // Keep triaged reasons, but analyze as if nothing were baselined yet
+    let previous = std::mem::take(&mut config.baseline);
+
+    let discovery = DiscoveryOptions {
+        exclude: config.suppressions.files.clone(),
+        follow_symlinks: config.global.follow_symlinks,
+        ..Default::default()
+    };
+    let analysis = analyze_crate_with_options(path, None, &discovery)?;
+
+    // Widest selection `analyze` can run, so no later invocation reports
+    // something the baseline missed
+    let options = AnalysisOptions {
+        detectors: None,
+        exclude: None,
+        min_severity: Severity::Informational,
+        experimental: true,
+        schema_dir: None,
+        rank: false,
+        function: None,
+        only_reachable: false,
+        filter_crate: None,
+    };
+    let report = build_report(
+        &analysis,
+        &config,
+        &options,
+        cosmwasm_guard_detectors::all_detectors(),
+    );
+
+    let baseline = match mode {
+        SuppressMode::AllCurrent => previous.regenerate(&report.findings),
+        SuppressMode::Findings(ids) => {
+            let mut baseline = previous;
+            for id in &ids {
+                let Some(finding) = report.findings.iter().find(|f| f.id.as_deref() == Some(id))
+                else {
+                    bail!("No finding with id `{id}` in the analyzed code");
+                };
+                baseline.accept(finding);
+            }
+            baseline
+        }
+    };
+    baseline.save(&output)?;
+
+    let todo = baseline
+        .entries
+        .iter()
+        .filter(|e| e.reason == TODO_REASON)
+        .count();
+    println!(
+        "Wrote {} baseline entries ({} to triage) to {}",
+        baseline.entries.len(),
+        todo,
+        output.display()
+    );
+    if config.suppressions.baseline.is_none() && output != default_output {
+        eprintln!(
+            "Set `baseline = \"{}\"` under [suppressions] so analyze picks it up",
+            output.display()
+        );
+    }
+    Ok(())
+}
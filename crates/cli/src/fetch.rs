@@ -0,0 +1,207 @@
+//! Contract sources analyzed without a checkout: a crates.io release, a
+//! source archive or a remote git repository, unpacked or cloned (never
+//! built) into a temporary directory.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use tempfile::TempDir;
+
+/// Base URL of crate downloads; override it to use an https or `file://` mirror
+pub const CRATES_URL_ENV: &str = "COSMWASM_GUARD_CRATES_URL";
+
+const CRATES_URL: &str = "https://static.crates.io/crates";
+
+/// Fetched sources in a temporary directory, removed again on drop
+pub struct Fetched {
+    /// Source root within the directory
+    pub root: PathBuf,
+    _dir: TempDir,
+}
+
+/// Download the crates.io release `NAME@VERSION`
+pub fn crates_io(spec: &str) -> Result<Fetched> {
+    let Some((name, version)) = spec.split_once('@') else {
+        bail!("Expected NAME@VERSION, e.g. cw20-base@1.1.2, got `{spec}`");
+    };
+    let valid = |s: &str| {
+        !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+'))
+    };
+    if !valid(name) || !valid(version) {
+        bail!("Invalid crate name or version in `{spec}`");
+    }
+
+    let dir = scratch_dir(&format!("{name}-{version}"))?;
+    let base = std::env::var(CRATES_URL_ENV).unwrap_or_else(|_| CRATES_URL.to_string());
+    let url = format!(
+        "{}/{name}/{name}-{version}.crate",
+        base.trim_end_matches('/')
+    );
+    let archive = dir.path().join(format!("{name}-{version}.crate"));
+    let package = match url.strip_prefix("file://") {
+        Some(local) => std::fs::read(local).map_err(anyhow::Error::from),
+        None => download(&url),
+    }
+    .with_context(|| format!("Failed to download {name}@{version}"))?;
+    std::fs::write(&archive, package)?;
+
+    let root = unpack(&archive, &dir.path().join("src"))?;
+    std::fs::remove_file(&archive)?;
+    Ok(Fetched { root, _dir: dir })
+}
+
+/// Unpack a .tar, .tar.gz, .tgz, .crate or .zip source archive
+pub fn archive(path: &Path) -> Result<Fetched> {
+    if !path.is_file() {
+        bail!("Archive not found: {}", path.display());
+    }
+    let name = path.file_name().map_or_else(
+        || "archive".to_string(),
+        |n| n.to_string_lossy().into_owned(),
+    );
+    let dir = scratch_dir(&name)?;
+    let root = unpack(path, dir.path())?;
+    Ok(Fetched { root, _dir: dir })
+}
+
+/// `source` names a remote git repository rather than a local path
+pub fn is_git_url(source: &str) -> bool {
+    ["https://", "http://", "ssh://", "git://", "file://", "git@"]
+        .iter()
+        .any(|scheme| source.starts_with(scheme))
+}
+
+/// Shallow-clone the git repository at `url`, at the branch or tag `rev` or
+/// the default branch
+pub fn git_repo(url: &str, rev: Option<&str>) -> Result<Fetched> {
+    let name = url
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or("repo");
+    let dir = scratch_dir(&format!("{name}-{}", rev.unwrap_or("HEAD")))?;
+    let mut command = Command::new("git");
+    command.args(["clone", "--quiet", "--depth", "1"]);
+    if let Some(rev) = rev {
+        command.args(["--branch", rev]);
+    }
+    command.arg(url).arg(dir.path());
+    run(&mut command, "git").with_context(|| format!("Failed to clone {url}"))?;
+    Ok(Fetched {
+        root: dir.path().to_path_buf(),
+        _dir: dir,
+    })
+}
+
+/// A fresh temporary directory for one source, named after `key`
+fn scratch_dir(key: &str) -> Result<TempDir> {
+    let key: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    tempfile::Builder::new()
+        .prefix(&format!("cosmwasm-guard-{key}-"))
+        .tempdir()
+        .context("Failed to create a temporary directory")
+}
+
+/// Extract `archive` into `dest`. Archives wrapping everything in one
+/// top-level directory (as crate packages and GitHub downloads do) are
+/// rooted at that directory.
+fn unpack(archive: &Path, dest: &Path) -> Result<PathBuf> {
+    std::fs::create_dir_all(dest)?;
+    let is_zip = archive
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    if is_zip {
+        run(
+            Command::new("unzip")
+                .args(["-q", "-o"])
+                .arg(archive)
+                .arg("-d")
+                .arg(dest),
+            "unzip",
+        )
+    } else {
+        run(
+            Command::new("tar")
+                .arg("-xf")
+                .arg(archive)
+                .arg("-C")
+                .arg(dest),
+            "tar",
+        )
+    }
+    .with_context(|| format!("Failed to unpack {}", archive.display()))?;
+
+    let entries: Vec<PathBuf> = std::fs::read_dir(dest)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    match entries.as_slice() {
+        [single] if single.is_dir() => Ok(single.clone()),
+        _ => Ok(dest.to_path_buf()),
+    }
+}
+
+/// Body of `url`, fetched with curl over https only, redirects included
+pub fn download(url: &str) -> Result<Vec<u8>> {
+    run(
+        Command::new("curl")
+            .args([
+                "--fail",
+                "--silent",
+                "--show-error",
+                "--proto",
+                "=https",
+                "--proto-redir",
+                "=https",
+                "--location",
+                "--max-time",
+                "300",
+            ])
+            .arg(url),
+        "curl",
+    )
+    .with_context(|| format!("Failed to fetch {url}"))
+}
+
+/// Run `command` and return its standard output
+fn run(command: &mut Command, program: &str) -> Result<Vec<u8>> {
+    let output = command
+        .output()
+        .with_context(|| format!("Failed to run {program}"))?;
+    if !output.status.success() {
+        bail!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_download_refuses_plain_http() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let url = format!("http://{}/guard.toml", listener.local_addr().unwrap());
+
+        let err = download(&url).unwrap_err();
+        assert!(format!("{err:#}").contains("\"http\""), "{err:#}");
+        // curl gave up before connecting
+        assert!(listener.accept().is_err());
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+
+/// Run git in `dir` and return its trimmed stdout
+pub fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?.trim_end().to_string())
+}
+
+/// Directory to run git in for `path`: the path itself, or a file's parent
+pub fn containing_dir(path: &Path) -> &Path {
+    let dir = if path.is_file() {
+        path.parent().unwrap_or(Path::new("."))
+    } else {
+        path
+    };
+    if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    }
+}
+
+/// Branch checked out for `path`. CI jobs usually check out a detached
+/// commit, so the branch CI names in its environment is used then.
+pub fn current_branch(path: &Path) -> Option<String> {
+    git(containing_dir(path), &["rev-parse", "--abbrev-ref", "HEAD"])
+        .ok()
+        .filter(|branch| branch != "HEAD")
+        .or_else(|| {
+            ["GITHUB_HEAD_REF", "GITHUB_REF_NAME", "CI_COMMIT_REF_NAME"]
+                .iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .find(|branch| !branch.is_empty())
+        })
+}
+
+/// Lines added or modified since a base revision, by canonical file path:
+/// those of `git diff` hunks against the working tree, and every line of
+/// untracked files
+pub struct NewCode {
+    lines: HashMap<PathBuf, Vec<RangeInclusive<usize>>>,
+}
+
+impl NewCode {
+    /// Code changed since `since` in the repository containing `path`: since
+    /// its merge base with `HEAD` for a revision, between the two ends for a
+    /// `A..B` range
+    pub fn since(path: &Path, since: &str) -> Result<Self> {
+        // `since` may come from a shared config; keep git from reading it as
+        // an option such as `--output=<file>`
+        if since.starts_with('-') {
+            bail!("Invalid revision `{since}`: revisions cannot start with `-`");
+        }
+        let repo = PathBuf::from(git(
+            containing_dir(path),
+            &["rev-parse", "--show-toplevel"],
+        )?);
+        let repo = repo.canonicalize().unwrap_or(repo);
+        let diff_args = ["diff", "-U0", "--no-color", "--no-ext-diff", "--no-renames"];
+        let (diff, untracked) = if since.contains("..") {
+            (git(&repo, &[&diff_args[..], &[since]].concat())?, String::new())
+        } else {
+            let base = git(&repo, &["merge-base", since, "HEAD"])
+                .with_context(|| format!("Unknown revision `{since}`"))?;
+            (
+                git(&repo, &[&diff_args[..], &[base.as_str()]].concat())?,
+                git(&repo, &["ls-files", "--others", "--exclude-standard"])?,
+            )
+        };
+        let mut new_code = Self::parse(&repo, &diff);
+        for file in untracked.lines() {
+            new_code.lines.insert(repo.join(file), vec![1..=usize::MAX]);
+        }
+        Ok(new_code)
+    }
+
+    /// Added lines of `-U0` diff hunks (`@@ -12,3 +14,5 @@`) under `+++ b/...` headers
+    fn parse(repo: &Path, diff: &str) -> Self {
+        let mut lines: HashMap<PathBuf, Vec<RangeInclusive<usize>>> = HashMap::new();
+        let mut file = None;
+        for line in diff.lines() {
+            if let Some(name) = line.strip_prefix("+++ ") {
+                file = name.strip_prefix("b/").map(|name| repo.join(name));
+            } else if let Some(hunk) = line.strip_prefix("@@ ") {
+                let Some(file) = &file else { continue };
+                let Some(added) = hunk.split(' ').find_map(|part| part.strip_prefix('+')) else {
+                    continue;
+                };
+                let (start, count) = match added.split_once(',') {
+                    Some((start, count)) => (start.parse().ok(), count.parse().ok()),
+                    None => (added.parse().ok(), Some(1)),
+                };
+                if let (Some(start), Some(count)) = (start, count) {
+                    if count > 0 {
+                        lines
+                            .entry(file.clone())
+                            .or_default()
+                            .push(start..=start + count - 1);
+                    }
+                }
+            }
+        }
+        Self { lines }
+    }
+
+    /// Whether lines `start..=end` of `file` (canonical) touch new code
+    pub fn touches(&self, file: &Path, start: usize, end: usize) -> bool {
+        self.lines.get(file).is_some_and(|ranges| {
+            ranges
+                .iter()
+                .any(|r| *r.start() <= end.max(start) && start <= *r.end())
+        })
+    }
+}
+
+/// A detached checkout of one revision in a temporary directory, removed
+/// again on drop; the working tree it was made from is left untouched
+pub struct Worktree {
+    repo: PathBuf,
+    path: PathBuf,
+}
+
+impl Worktree {
+    /// Check out `rev` of the repository containing `path`
+    pub fn checkout(path: &Path, rev: &str) -> Result<Self> {
+        let repo = PathBuf::from(git(
+            containing_dir(path),
+            &["rev-parse", "--show-toplevel"],
+        )?)
+        .canonicalize()?;
+        let commit = git(
+            &repo,
+            &["rev-parse", "--verify", &format!("{rev}^{{commit}}")],
+        )
+        .with_context(|| format!("Unknown revision `{rev}`"))?;
+        let name = repo.file_name().unwrap_or_default().to_string_lossy();
+        let path = std::env::temp_dir().join(format!(
+            "cosmwasm-guard-worktree-{name}-{}-{}",
+            &commit[..12.min(commit.len())],
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        let target = path.to_string_lossy();
+        git(
+            &repo,
+            &["worktree", "add", "--detach", "--quiet", &target, &commit],
+        )?;
+        Ok(Self { repo, path })
+    }
+
+    /// `path` in the working tree, as it is in this checkout; fails for paths
+    /// outside the repository
+    pub fn locate(&self, path: &Path) -> Result<PathBuf> {
+        let absolute = path
+            .canonicalize()
+            .with_context(|| format!("Path not found: {}", path.display()))?;
+        let relative = absolute
+            .strip_prefix(&self.repo)
+            .with_context(|| format!("{} is outside the repository", path.display()))?;
+        Ok(self.path.join(relative))
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        let target = self.path.to_string_lossy();
+        let _ = git(&self.repo, &["worktree", "remove", "--force", &target]);
+    }
+}
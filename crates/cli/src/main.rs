@@ -1,9 +1,19 @@
+mod bundle;
 mod commands;
+mod fetch;
+mod git;
 mod output;
+mod remote_config;
 
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand, ValueEnum};
+use commands::analyze::AnalyzeArgs;
+use commands::report::ReportView;
+use commands::suppress::SuppressMode;
+use cosmwasm_guard::ast::TargetSelection;
+use cosmwasm_guard::report::PathRewriter;
+use output::text::{Hyperlinks, Theme};
 
 #[derive(Parser)]
 #[command(name = "cosmwasm-guard")]
@@ -14,20 +24,47 @@ struct Cli {
     command: Commands,
 }
 
+// Parsed once per run, so the size of the `analyze` variant doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Analyze CosmWasm contract(s) for vulnerabilities
     Analyze {
-        /// Path to .rs file or directory containing CosmWasm contract
-        path: PathBuf,
+        /// Paths to .rs files or directories containing CosmWasm contracts
+        #[arg(required_unless_present_any = ["files_from", "from_crates_io", "from_archive"])]
+        paths: Vec<PathBuf>,
+
+        /// Also analyze the files listed in this file, one per line (`-` for stdin),
+        /// e.g. the output of `git diff --name-only`
+        #[arg(long, value_name = "FILE")]
+        files_from: Option<PathBuf>,
+
+        /// Download this crates.io release and analyze its source, e.g. cw20-base@1.1.2
+        #[arg(long, value_name = "NAME@VERSION")]
+        from_crates_io: Option<String>,
+
+        /// Unpack this source archive (.tar, .tar.gz, .tgz, .crate or .zip) and analyze it
+        #[arg(long, value_name = "FILE")]
+        from_archive: Option<PathBuf>,
+
+        /// Analyze this git revision (commit, tag or branch) from a temporary
+        /// worktree instead of the working tree
+        #[arg(long, value_name = "REV", conflicts_with_all = ["files_from", "from_crates_io", "from_archive"])]
+        rev: Option<String>,
 
         /// Output format
         #[arg(short, long, default_value = "text")]
         format: OutputFormat,
 
-        /// Minimum severity to report
-        #[arg(short, long, default_value = "low")]
-        severity: SeverityFilter,
+        /// Minimum severity to report [default: the threshold of the branch's
+        /// `[[branches]]` policy, else low]
+        #[arg(short, long)]
+        severity: Option<SeverityFilter>,
+
+        /// Branch selecting the `[[branches]]` policy, instead of the one
+        /// checked out
+        #[arg(long, value_name = "NAME")]
+        branch: Option<String>,
 
         /// Run only these detectors (comma-separated)
         #[arg(short, long, value_delimiter = ',')]
@@ -37,6 +74,111 @@ enum Commands {
         #[arg(short, long, value_delimiter = ',')]
         exclude: Option<Vec<String>>,
 
+        /// Run these rule packs (comma-separated): core, defi, nft, ibc, governance, staking, gas, strict
+        #[arg(long, value_name = "PACKS", value_delimiter = ',')]
+        packs: Option<Vec<String>>,
+
+        /// Check the contract against this `[chains.<name>]` profile of the
+        /// config: its CosmWasm version, capabilities and address prefix
+        #[arg(long, value_name = "NAME")]
+        target_chain: Option<String>,
+
+        /// Skip files matching this glob before parsing (repeatable, comma-separated)
+        #[arg(long, value_name = "GLOB", value_delimiter = ',')]
+        exclude_path: Vec<String>,
+
+        /// Follow symlinks when discovering source files
+        #[arg(long)]
+        follow_symlinks: bool,
+
+        /// Fail if any source file cannot be parsed instead of skipping it
+        #[arg(long)]
+        strict_parse: bool,
+
+        /// Exit non-zero when the analysis could not verify code: files that
+        /// failed to parse, macros it could not analyze or message variants
+        /// with no resolved handler
+        #[arg(long)]
+        strict: bool,
+
+        /// Analyze only the lib target (default when the crate has one)
+        #[arg(long, conflicts_with_all = ["bin", "all_targets"])]
+        lib: bool,
+
+        /// Analyze only the named binary target
+        #[arg(long, value_name = "NAME", conflicts_with = "all_targets")]
+        bin: Option<String>,
+
+        /// Analyze every target, including auxiliary binaries
+        #[arg(long)]
+        all_targets: bool,
+
+        /// Also load message and struct types from this dependency crate: a
+        /// directory or the name of a path dependency (repeatable)
+        #[arg(long, value_name = "PATH|CRATE")]
+        include_dep: Vec<String>,
+
+        /// Also run the detectors over `examples/` and doc-test code blocks,
+        /// reporting what they find at Informational (shown with
+        /// `--severity info` or `--audit`)
+        #[arg(long)]
+        include_examples: bool,
+
+        /// Flag drift between message types and the cosmwasm-schema JSON in this directory
+        #[arg(long, value_name = "DIR")]
+        check_schema: Option<PathBuf>,
+
+        /// Order findings within each severity by exploitability heuristics
+        #[arg(long)]
+        rank: bool,
+
+        /// Report only findings inside this function and the functions it calls
+        #[arg(long, value_name = "NAME")]
+        function: Option<String>,
+
+        /// Hide findings in functions no entry point reaches (dead code,
+        /// test helpers and utilities compiled into the lib)
+        #[arg(long)]
+        only_reachable: bool,
+
+        /// Report only findings on lines added or modified since REV (its merge
+        /// base with HEAD, uncommitted changes and untracked files included) or
+        /// within a `A..B` range, as `--only-new-code=REV` [default:
+        /// `new_code_since` under [global], else HEAD]
+        #[arg(
+            long,
+            value_name = "REV",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "",
+            conflicts_with = "rev"
+        )]
+        only_new_code: Option<String>,
+
+        /// Report only findings in this crate of a workspace (its package name)
+        #[arg(long, value_name = "NAME")]
+        filter_crate: Option<String>,
+
+        /// Write skeleton cw-multi-test tests reproducing access-control and
+        /// uninitialized-state findings to DIR
+        #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = "guard_repro")]
+        gen_repro: Option<PathBuf>,
+
+        /// Also write an archive with the JSON report, the config, the tool
+        /// version and the analyzed sources, compressed per its extension
+        /// (e.g. out.tar.zst)
+        #[arg(long, value_name = "FILE")]
+        bundle: Option<PathBuf>,
+
+        /// Record only SHA-256 hashes of the sources in the bundle
+        #[arg(long, requires = "bundle")]
+        bundle_hashes_only: bool,
+
+        /// Print the evidence trace of the finding with this id (shown next
+        /// to traced findings and as `id` in JSON output) instead of a report
+        #[arg(long, value_name = "ID")]
+        explain_finding: Option<String>,
+
         /// Path to config file (default: .cosmwasm-guard.toml)
         #[arg(short, long)]
         config: Option<PathBuf>,
@@ -45,6 +187,10 @@ enum Commands {
         #[arg(long)]
         audit: bool,
 
+        /// Also run Experimental-tier detectors
+        #[arg(long)]
+        experimental: bool,
+
         /// Disable file-level caching of parsed AST and IR
         #[arg(long)]
         no_cache: bool,
@@ -56,47 +202,512 @@ enum Commands {
         /// Disable colored output
         #[arg(long)]
         no_color: bool,
+
+        /// Color theme of text output (default: the config's `theme`)
+        #[arg(long)]
+        theme: Option<Theme>,
+
+        /// Wrap text output to this many columns; 0 disables wrapping (default: terminal width)
+        #[arg(long, value_name = "COLUMNS")]
+        width: Option<usize>,
+
+        /// Print one `file:line:col: severity: title [detector]` line per finding
+        #[arg(long)]
+        compact: bool,
+
+        /// Show each finding's confidence and the signals that adjusted it, and
+        /// code the analysis could not look into (closures, macros, unresolved handlers)
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Render file:line in text output as terminal hyperlinks
+        #[arg(long, default_value = "auto")]
+        hyperlinks: Hyperlinks,
+
+        /// Web URL for findings, e.g. "https://github.com/org/repo/blob/{rev}/{path}#L{line}"
+        #[arg(long, value_name = "TEMPLATE")]
+        link_template: Option<String>,
+
+        /// Remove this prefix from reported file paths (repeatable)
+        #[arg(long, value_name = "PREFIX")]
+        path_prefix_strip: Vec<PathBuf>,
+
+        /// Replace prefix FROM with TO in reported file paths (repeatable)
+        #[arg(long, value_name = "FROM=TO", value_parser = parse_prefix_map)]
+        path_prefix_map: Vec<(PathBuf, PathBuf)>,
+    },
+    /// Serve analysis over JSON-RPC 2.0 (HTTP POST, or newline-delimited on stdio)
+    Serve {
+        /// Address to listen on for HTTP
+        #[arg(long, default_value = "127.0.0.1:7878")]
+        listen: String,
+
+        /// Read requests from stdin and write responses to stdout instead of HTTP
+        #[arg(long)]
+        stdio: bool,
+
+        /// Path to config file (default: .cosmwasm-guard.toml)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+
+        /// Disable file-level caching of parsed AST and IR
+        #[arg(long)]
+        no_cache: bool,
+    },
+    /// Record findings as accepted in a baseline file
+    #[command(group(clap::ArgGroup::new("mode").required(true)))]
+    Suppress {
+        /// Path to .rs file or directory containing CosmWasm contract
+        path: PathBuf,
+
+        /// Baseline every current finding, keeping reasons already triaged
+        /// and dropping entries whose finding is gone
+        #[arg(long, group = "mode")]
+        all_current: bool,
+
+        /// Add the findings with these ids (as in JSON output) to the
+        /// baseline, keeping its other entries (comma-separated)
+        #[arg(long, group = "mode", value_delimiter = ',', value_name = "ID")]
+        finding: Vec<String>,
+
+        /// Baseline file to write (default: suppressions.baseline, else .cosmwasm-guard-baseline.toml)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Path to config file (default: .cosmwasm-guard.toml)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+    },
+    /// Apply the fixes suggested for findings to the source files
+    Fix {
+        /// Path to .rs file or directory containing CosmWasm contract
+        path: PathBuf,
+
+        /// Apply only these detectors' fixes (comma-separated)
+        #[arg(short, long, value_delimiter = ',')]
+        detectors: Option<Vec<String>>,
+
+        /// List the fixes without changing any file
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Run rustfmt on each changed file (the whole file) before writing it
+        #[arg(long)]
+        rustfmt: bool,
+
+        /// Path to config file (default: .cosmwasm-guard.toml)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+    },
+    /// Summarize findings per owning team, entry point or message variant
+    #[command(group(clap::ArgGroup::new("view").required(true)))]
+    Report {
+        /// Path to .rs file or directory containing CosmWasm contract
+        path: PathBuf,
+
+        /// Group finding counts by the team from the config's [[owners]] rules
+        #[arg(long, group = "view")]
+        by_owner: bool,
+
+        /// List findings under the entry point and ExecuteMsg variant they affect
+        #[arg(long, group = "view")]
+        by_entry_point: bool,
+
+        /// List every ExecuteMsg/QueryMsg variant with the detectors that found
+        /// something in its handler, and variants whose handler was not resolved
+        #[arg(long, group = "view")]
+        coverage: bool,
+
+        /// Write a print-ready audit report with a title page, summary tables
+        /// and every finding with its snippet to this PDF file
+        #[arg(long, group = "view", value_name = "FILE")]
+        pdf: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+
+        /// Path to config file (default: .cosmwasm-guard.toml)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+    },
+    /// Show the contract model: entry points, state, roles and who may call each message
+    Inspect {
+        /// Path to .rs file or directory containing CosmWasm contract
+        path: PathBuf,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+
+        /// Show storage access hot spots instead: per state item, the
+        /// handlers reading and writing it and the code paths reaching it
+        #[arg(long)]
+        storage: bool,
+
+        /// Path to config file (default: .cosmwasm-guard.toml)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+    },
+    /// Diff a forked contract's messages, state and guards against its upstream
+    CompareUpstream {
+        /// Path to .rs file or directory containing the forked contract
+        path: PathBuf,
+
+        /// Upstream contract: a local path or a git URL
+        #[arg(long)]
+        upstream: String,
+
+        /// Branch or tag to clone when --upstream is a git URL
+        #[arg(long)]
+        upstream_rev: Option<String>,
+
+        /// Contract directory inside the upstream, e.g. contracts/cw20-base
+        #[arg(long)]
+        upstream_dir: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+
+        /// Path to config file (default: .cosmwasm-guard.toml)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+    },
+    /// Generate test scaffolding from the contract model
+    Scaffold {
+        #[command(subcommand)]
+        action: ScaffoldAction,
+    },
+    /// Run cosmwasm-guard as a git pre-commit hook
+    Hook {
+        #[command(subcommand)]
+        action: HookAction,
     },
     /// List all available detectors
     List,
+    /// Describe a detector by code (e.g. CWG0002) or name
+    Explain {
+        /// Detector code or name
+        detector: String,
+    },
+    /// List the CosmWasm advisories and incident writeups the selected
+    /// detectors cover
+    Advisories {
+        /// Count experimental detectors as selected
+        #[arg(long)]
+        experimental: bool,
+
+        /// Path to config file (default: .cosmwasm-guard.toml)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+    },
     /// Generate a default .cosmwasm-guard.toml config file
     Init,
 }
 
+#[derive(Subcommand)]
+enum ScaffoldAction {
+    /// Generate a proptest harness sending random ExecuteMsg/QueryMsg values
+    /// to the contract in cw-multi-test
+    Fuzz {
+        /// Path to .rs file or directory containing CosmWasm contract
+        path: PathBuf,
+
+        /// File to write (default: tests/fuzz_messages.rs in the contract package)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Path to config file (default: .cosmwasm-guard.toml)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+
+        /// Overwrite an existing file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum HookAction {
+    /// Install a git pre-commit script that runs `hook run`
+    Install {
+        /// Write a .pre-commit-hooks.yaml entry for the pre-commit framework instead
+        #[arg(long)]
+        pre_commit_yaml: bool,
+
+        /// Overwrite an existing hook
+        #[arg(long)]
+        force: bool,
+    },
+    /// Analyze staged files with the fast detector profile, using the cache
+    Run {
+        /// Files to check (default: staged .rs files)
+        files: Vec<PathBuf>,
+
+        /// Path to config file (default: .cosmwasm-guard.toml)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+
+        /// Disable colored output
+        #[arg(long)]
+        no_color: bool,
+    },
+}
+
 #[derive(ValueEnum, Clone)]
 enum OutputFormat {
     Text,
     Json,
     Sarif,
+    /// Per-severity counts and the exit status only
+    Summary,
+    /// The counts object of the JSON report, without finding bodies
+    JsonSummary,
+    /// A standalone interactive page with the permission matrix, metrics and sources
+    Html,
+    /// rustc JSON diagnostics, one per line, for editors' `cargo check` integrations
+    RustcJson,
 }
 
 #[derive(ValueEnum, Clone)]
 enum SeverityFilter {
+    Critical,
     High,
     Medium,
     Low,
     Info,
 }
 
+fn target_selection(lib: bool, bin: Option<String>, all_targets: bool) -> TargetSelection {
+    match (lib, bin, all_targets) {
+        (true, _, _) => TargetSelection::Lib,
+        (_, Some(name), _) => TargetSelection::Bin(name),
+        (_, _, true) => TargetSelection::All,
+        _ => TargetSelection::Auto,
+    }
+}
+
+fn parse_prefix_map(spec: &str) -> Result<(PathBuf, PathBuf), String> {
+    PathRewriter::parse_map(spec).map_err(|e| e.to_string())
+}
+
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
         Commands::Analyze {
-            path,
+            mut paths,
+            files_from,
+            from_crates_io,
+            from_archive,
+            rev,
             format,
             severity,
+            branch,
             detectors,
             exclude,
+            packs,
+            target_chain,
+            exclude_path,
+            follow_symlinks,
+            strict_parse,
+            strict,
+            lib,
+            bin,
+            all_targets,
+            include_dep,
+            include_examples,
+            check_schema,
+            rank,
+            function,
+            only_reachable,
+            only_new_code,
+            filter_crate,
+            gen_repro,
+            bundle,
+            bundle_hashes_only,
+            explain_finding,
             config,
             audit,
+            experimental,
             no_cache,
             quiet,
             no_color,
-        } => commands::analyze::run(
-            &path, format, severity, detectors, exclude, config, audit, no_cache, quiet, no_color,
+            theme,
+            width,
+            compact,
+            verbose,
+            hyperlinks,
+            link_template,
+            mut path_prefix_strip,
+            path_prefix_map,
+        } => {
+            // Fetched sources are reported relative to their crate root
+            let fetched = [
+                from_crates_io.as_deref().map(fetch::crates_io),
+                from_archive.as_deref().map(fetch::archive),
+            ];
+            let fetched = fetched
+                .into_iter()
+                .flatten()
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            for source in &fetched {
+                paths.push(source.root.clone());
+                path_prefix_strip.push(source.root.clone());
+            }
+            commands::analyze::run(AnalyzeArgs {
+                paths,
+                files_from,
+                rev,
+                format,
+                severity,
+                branch,
+                detectors,
+                exclude,
+                packs,
+                target_chain,
+                exclude_paths: exclude_path,
+                follow_symlinks,
+                strict_parse,
+                strict,
+                target: target_selection(lib, bin, all_targets),
+                include_deps: include_dep,
+                include_examples,
+                schema_dir: check_schema,
+                rank,
+                function,
+                only_reachable,
+                only_new_code,
+                filter_crate,
+                gen_repro,
+                bundle,
+                bundle_hashes_only,
+                explain_finding,
+                config_path: config,
+                audit,
+                experimental,
+                no_cache,
+                quiet,
+                no_color,
+                theme,
+                width,
+                compact,
+                verbose,
+                hyperlinks,
+                link_template,
+                path_rewriter: PathRewriter::new(path_prefix_strip, path_prefix_map),
+            })
+        }
+        Commands::Serve {
+            listen,
+            stdio,
+            config,
+            no_cache,
+        } => commands::serve::run(&listen, stdio, config, no_cache),
+        Commands::Suppress {
+            path,
+            all_current,
+            finding,
+            output,
+            config,
+        } => {
+            let mode = if all_current {
+                SuppressMode::AllCurrent
+            } else {
+                SuppressMode::Findings(finding)
+            };
+            commands::suppress::run(&path, mode, output, config)
+        }
+        Commands::Fix {
+            path,
+            detectors,
+            dry_run,
+            rustfmt,
+            config,
+        } => commands::fix::run(&path, detectors, dry_run, rustfmt, config),
+        Commands::Report {
+            path,
+            by_owner,
+            by_entry_point,
+            coverage,
+            pdf,
+            format,
+            config,
+            no_color,
+        } => {
+            if let Some(out) = pdf {
+                return commands::report::pdf(&path, &out, config);
+            }
+            // The `view` group makes exactly one of the flags set
+            let view = match (by_owner, by_entry_point, coverage) {
+                (true, _, _) => ReportView::ByOwner,
+                (_, true, _) => ReportView::ByEntryPoint,
+                _ => ReportView::Coverage,
+            };
+            commands::report::run(&path, view, format, config, no_color)
+        }
+        Commands::Inspect {
+            path,
+            format,
+            storage,
+            config,
+            no_color,
+        } => commands::inspect::run(&path, format, storage, config, no_color),
+        Commands::CompareUpstream {
+            path,
+            upstream,
+            upstream_rev,
+            upstream_dir,
+            format,
+            config,
+            no_color,
+        } => commands::compare_upstream::run(
+            &path,
+            &upstream,
+            upstream_rev.as_deref(),
+            upstream_dir.as_deref(),
+            format,
+            config,
+            no_color,
         ),
+        Commands::Scaffold { action } => match action {
+            ScaffoldAction::Fuzz {
+                path,
+                output,
+                config,
+                force,
+            } => commands::scaffold::fuzz(&path, output, config, force),
+        },
+        Commands::Hook { action } => match action {
+            HookAction::Install {
+                pre_commit_yaml,
+                force,
+            } => commands::hook::install(pre_commit_yaml, force),
+            HookAction::Run {
+                files,
+                config,
+                no_color,
+            } => commands::hook::run(files, config, no_color),
+        },
         Commands::List => commands::list::run(),
+        Commands::Explain { detector } => commands::explain::run(&detector),
+        Commands::Advisories {
+            experimental,
+            config,
+        } => commands::advisories::run(config, experimental),
         Commands::Init => commands::init::run(),
     }
 }
@@ -0,0 +1,763 @@
+//! Single-file interactive HTML report: findings with severity, detector and
+//! file filters, the permission matrix and function metrics of each analyzed
+//! contract, and the source of every file with findings, highlighted and
+//! marked at the findings' lines. Everything is inline, so the file can be
+//! shared as it is.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use cosmwasm_guard::ast::CrateAnalysis;
+use cosmwasm_guard::codes;
+use cosmwasm_guard::config::Config;
+use cosmwasm_guard::detector::AnalysisContext;
+use cosmwasm_guard::finding::{Finding, Severity};
+use cosmwasm_guard::report::{AnalysisReport, PathRewriter};
+use cosmwasm_guard::roles::{PermissionMatrix, RoleKind};
+use cosmwasm_guard::snippets::redact_string_literals;
+
+/// Size and complexity of one function
+pub struct FunctionMetrics {
+    pub name: String,
+    pub file: PathBuf,
+    pub line: usize,
+    pub lines: usize,
+    pub complexity: usize,
+    pub storage_writes: usize,
+}
+
+/// Contract-level sections of one analysis
+pub struct ContractModel {
+    pub name: String,
+    pub permissions: PermissionMatrix,
+    /// Most complex first
+    pub metrics: Vec<FunctionMetrics>,
+}
+
+/// What the report shows beyond the findings
+#[derive(Default)]
+pub struct HtmlModel {
+    pub contracts: Vec<ContractModel>,
+    /// Text of the files with findings, redacted per `[snippets]`
+    pub sources: BTreeMap<PathBuf, String>,
+}
+
+impl HtmlModel {
+    /// Add the contract of one analysis and the source of its files with
+    /// findings. No source is embedded when `[snippets]` disables snippets.
+    pub fn add(
+        &mut self,
+        name: String,
+        analysis: &CrateAnalysis,
+        config: &Config,
+        findings: &[Finding],
+    ) {
+        let ctx = AnalysisContext::new(&analysis.contract, &analysis.ir, &analysis.source_map)
+            .with_config(config);
+        let mut metrics: Vec<FunctionMetrics> = analysis
+            .contract
+            .functions
+            .iter()
+            .filter_map(|func| {
+                let ir = analysis.ir.get_function(&func.name)?;
+                let span = func.body_span.as_ref()?;
+                Some(FunctionMetrics {
+                    name: func.name.clone(),
+                    file: func.span.file.clone(),
+                    line: func.span.start_line,
+                    lines: span.end_line.saturating_sub(span.start_line) + 1,
+                    complexity: ir.cfg.cyclomatic_complexity(),
+                    storage_writes: ctx.storage_writes(&func.name).len(),
+                })
+            })
+            .collect();
+        metrics.sort_by(|a, b| {
+            b.complexity
+                .cmp(&a.complexity)
+                .then(b.lines.cmp(&a.lines))
+                .then(a.name.cmp(&b.name))
+        });
+        self.contracts.push(ContractModel {
+            name,
+            permissions: ctx.permissions().clone(),
+            metrics,
+        });
+
+        if config.snippets.max_lines == Some(0) {
+            return;
+        }
+        for loc in findings.iter().flat_map(|f| &f.locations) {
+            if self.sources.contains_key(&loc.file) {
+                continue;
+            }
+            if let Some(source) = analysis.source_map.get(&loc.file) {
+                let text = if config.snippets.redact_strings {
+                    redact_string_literals(source)
+                } else {
+                    source.clone()
+                };
+                self.sources.insert(loc.file.clone(), text);
+            }
+        }
+    }
+
+    /// Rewrite file paths the way the report's were
+    pub fn rewrite_paths(&mut self, rewriter: &PathRewriter) {
+        self.sources = std::mem::take(&mut self.sources)
+            .into_iter()
+            .map(|(file, text)| (rewriter.rewrite(&file), text))
+            .collect();
+        for contract in &mut self.contracts {
+            for func in &mut contract.metrics {
+                func.file = rewriter.rewrite(&func.file);
+            }
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn severity_class(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "critical",
+        Severity::High => "high",
+        Severity::Medium => "medium",
+        Severity::Low => "low",
+        Severity::Informational => "info",
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+/// Where a line starts: in code, or inside a comment or string literal left
+/// open on an earlier line
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Lex {
+    Code,
+    /// Nesting depth of `/* */` comments
+    BlockComment(usize),
+    Str,
+    /// Number of `#` closing the raw string
+    RawStr(usize),
+}
+
+/// End of a block comment starting at `i` with `depth` open, and the depth
+/// left open at the end of the line
+fn scan_block_comment(chars: &[char], mut i: usize, mut depth: usize) -> (usize, usize) {
+    while i < chars.len() && depth > 0 {
+        if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+            depth += 1;
+            i += 2;
+        } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+            depth -= 1;
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    (i.min(chars.len()), depth)
+}
+
+/// End of a string literal whose contents start at `i`, and whether it
+/// closes on this line
+fn scan_str(chars: &[char], mut i: usize) -> (usize, bool) {
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '"' => return (i + 1, true),
+            _ => i += 1,
+        }
+    }
+    (chars.len(), false)
+}
+
+/// End of a raw string closed by `"` and `hashes` `#`s, from `i`
+fn scan_raw_str(chars: &[char], mut i: usize, hashes: usize) -> (usize, bool) {
+    while i < chars.len() {
+        if chars[i] == '"' && chars[i + 1..].iter().take_while(|c| **c == '#').count() >= hashes {
+            return (i + 1 + hashes, true);
+        }
+        i += 1;
+    }
+    (chars.len(), false)
+}
+
+fn push_span(out: &mut String, class: &str, chars: &[char]) {
+    let text: String = chars.iter().collect();
+    let _ = write!(out, "<span class=\"{class}\">{}</span>", escape(&text));
+}
+
+/// One line of Rust as HTML with keywords, types, macros, literals and
+/// comments wrapped in classed spans; `state` carries comments and strings
+/// across lines
+fn highlight(line: &str, state: &mut Lex) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        match *state {
+            Lex::BlockComment(depth) => {
+                let (end, open) = scan_block_comment(&chars, i, depth);
+                push_span(&mut out, "c", &chars[start..end]);
+                *state = if open == 0 {
+                    Lex::Code
+                } else {
+                    Lex::BlockComment(open)
+                };
+                i = end;
+            }
+            Lex::Str => {
+                let (end, closed) = scan_str(&chars, i);
+                push_span(&mut out, "s", &chars[start..end]);
+                if closed {
+                    *state = Lex::Code;
+                }
+                i = end;
+            }
+            Lex::RawStr(hashes) => {
+                let (end, closed) = scan_raw_str(&chars, i, hashes);
+                push_span(&mut out, "s", &chars[start..end]);
+                if closed {
+                    *state = Lex::Code;
+                }
+                i = end;
+            }
+            Lex::Code => {
+                let c = chars[i];
+                let next = chars.get(i + 1).copied();
+                if c == '/' && next == Some('/') {
+                    push_span(&mut out, "c", &chars[start..]);
+                    i = chars.len();
+                } else if c == '/' && next == Some('*') {
+                    let (end, open) = scan_block_comment(&chars, i + 2, 1);
+                    push_span(&mut out, "c", &chars[start..end]);
+                    *state = if open == 0 {
+                        Lex::Code
+                    } else {
+                        Lex::BlockComment(open)
+                    };
+                    i = end;
+                } else if c == '"' || (c == 'b' && next == Some('"')) {
+                    let open = if c == '"' { i + 1 } else { i + 2 };
+                    let (end, closed) = scan_str(&chars, open);
+                    push_span(&mut out, "s", &chars[start..end]);
+                    if !closed {
+                        *state = Lex::Str;
+                    }
+                    i = end;
+                } else if let Some(hashes) = raw_str_start(&chars, i) {
+                    let prefix = chars[i..].iter().position(|c| *c == '"').unwrap_or(0) + 1;
+                    let (end, closed) = scan_raw_str(&chars, i + prefix, hashes);
+                    push_span(&mut out, "s", &chars[start..end]);
+                    if !closed {
+                        *state = Lex::RawStr(hashes);
+                    }
+                    i = end;
+                } else if c == '\'' && char_literal_end(&chars, i).is_some() {
+                    let end = char_literal_end(&chars, i).unwrap_or(i + 1);
+                    push_span(&mut out, "s", &chars[start..end]);
+                    i = end;
+                } else if c.is_ascii_digit() {
+                    while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                        i += 1;
+                    }
+                    push_span(&mut out, "n", &chars[start..i]);
+                } else if c.is_alphabetic() || c == '_' {
+                    while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                        i += 1;
+                    }
+                    let ident: String = chars[start..i].iter().collect();
+                    if chars.get(i) == Some(&'!') && chars.get(i + 1) != Some(&'=') {
+                        i += 1;
+                        push_span(&mut out, "m", &chars[start..i]);
+                    } else if KEYWORDS.contains(&ident.as_str()) {
+                        push_span(&mut out, "k", &chars[start..i]);
+                    } else if c.is_uppercase() {
+                        push_span(&mut out, "t", &chars[start..i]);
+                    } else {
+                        out.push_str(&escape(&ident));
+                    }
+                } else {
+                    out.push_str(&escape(&c.to_string()));
+                    i += 1;
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Number of `#`s of a raw string (`r"`, `r#"`, `br##"`) starting at `i`
+fn raw_str_start(chars: &[char], i: usize) -> Option<usize> {
+    // Not the tail of an identifier such as `for`
+    if i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_') {
+        return None;
+    }
+    let mut j = i;
+    if chars.get(j) == Some(&'b') {
+        j += 1;
+    }
+    if chars.get(j) != Some(&'r') {
+        return None;
+    }
+    let hashes = chars[j + 1..].iter().take_while(|c| **c == '#').count();
+    (chars.get(j + 1 + hashes) == Some(&'"')).then_some(hashes)
+}
+
+/// End of a char literal (`'a'`, `'\n'`) starting at `i`; none for lifetimes
+fn char_literal_end(chars: &[char], i: usize) -> Option<usize> {
+    match chars.get(i + 1)? {
+        '\\' => chars[i + 2..]
+            .iter()
+            .position(|c| *c == '\'')
+            .map(|p| i + 3 + p),
+        _ => (chars.get(i + 2) == Some(&'\'')).then_some(i + 3),
+    }
+}
+
+const STYLE: &str = r#"
+:root { --critical: #6a1b9a; --high: #c62828; --medium: #ef6c00; --low: #f9a825; --info: #1565c0; --border: #d0d7de; }
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 0 auto; max-width: 1200px; padding: 0 24px 48px; color: #1f2328; }
+header { border-bottom: 1px solid var(--border); margin-bottom: 16px; }
+nav a { margin-right: 16px; }
+h2 { border-bottom: 1px solid var(--border); padding-bottom: 4px; margin-top: 32px; }
+.cards { display: flex; gap: 12px; flex-wrap: wrap; }
+.card { border: 1px solid var(--border); border-radius: 6px; padding: 8px 16px; min-width: 96px; }
+.card strong { display: block; font-size: 24px; }
+.badge { display: inline-block; border-radius: 4px; color: #fff; font-size: 12px; font-weight: 600; padding: 1px 6px; margin-right: 6px; }
+.badge.critical, .marker.critical { background: var(--critical); }
+.badge.high, .marker.high { background: var(--high); }
+.badge.medium, .marker.medium { background: var(--medium); }
+.badge.low, .marker.low { background: var(--low); }
+.badge.info, .marker.info { background: var(--info); }
+.filters { display: flex; gap: 16px; flex-wrap: wrap; align-items: center; margin-bottom: 12px; }
+.finding { border: 1px solid var(--border); border-radius: 6px; margin-bottom: 8px; }
+.finding > summary { cursor: pointer; padding: 8px 12px; }
+.finding .body { padding: 0 12px 8px; border-top: 1px solid var(--border); }
+.detector, .loc { color: #656d76; font-size: 13px; margin-left: 8px; }
+pre, .code { font-family: ui-monospace, SFMono-Regular, Menlo, monospace; font-size: 12px; }
+pre { background: #f6f8fa; padding: 8px; overflow-x: auto; }
+table { border-collapse: collapse; margin-bottom: 16px; }
+th, td { border: 1px solid var(--border); padding: 4px 8px; text-align: left; }
+tr.flagged td { color: var(--high); font-weight: 600; }
+.source { margin-bottom: 8px; }
+.source > summary { cursor: pointer; font-family: ui-monospace, SFMono-Regular, Menlo, monospace; }
+table.code td { border: none; padding: 0 8px; white-space: pre; vertical-align: top; }
+table.code td.ln { color: #8c959f; text-align: right; user-select: none; }
+table.code tr.marked { background: #fff8c5; }
+table.code tr:target { outline: 2px solid var(--info); }
+.marker { display: inline-block; width: 10px; height: 10px; border-radius: 5px; margin-right: 2px; }
+.k { color: #cf222e; } .t { color: #8250df; } .m { color: #0550ae; } .s { color: #0a3069; } .n { color: #0550ae; } .c { color: #6e7781; font-style: italic; }
+"#;
+
+const SCRIPT: &str = r#"
+const filters = ['severity', 'detector', 'file'].map(k => [k, document.getElementById('filter-' + k)]);
+const findings = Array.from(document.querySelectorAll('.finding'));
+function applyFilters() {
+  let shown = 0;
+  for (const f of findings) {
+    const match = filters.every(([k, select]) => !select.value || f.dataset[k] === select.value);
+    f.hidden = !match;
+    if (match) shown++;
+  }
+  document.getElementById('shown').textContent = shown + ' of ' + findings.length + ' shown';
+}
+for (const [, select] of filters) select.addEventListener('change', applyFilters);
+document.getElementById('expand').addEventListener('click', () => findings.forEach(f => f.open = !f.hidden));
+document.getElementById('collapse').addEventListener('click', () => findings.forEach(f => f.open = false));
+// Open the finding or source file a link points into
+function reveal() {
+  const target = location.hash && document.getElementById(decodeURIComponent(location.hash.slice(1)));
+  if (!target) return;
+  const details = target.closest('details');
+  if (details) details.open = true;
+  target.scrollIntoView({ block: 'center' });
+}
+window.addEventListener('hashchange', reveal);
+applyFilters();
+reveal();
+"#;
+
+fn select(out: &mut String, id: &str, label: &str, values: &BTreeSet<String>) {
+    let _ = write!(
+        out,
+        "<label>{label} <select id=\"filter-{id}\"><option value=\"\">All</option>"
+    );
+    for value in values {
+        let value = escape(value);
+        let _ = write!(out, "<option value=\"{value}\">{value}</option>");
+    }
+    out.push_str("</select></label>");
+}
+
+/// The report as a standalone HTML document
+pub fn render(report: &AnalysisReport, model: &HtmlModel) -> String {
+    // Source viewer ids by file; `src-N-LINE` anchors each line
+    let source_ids: BTreeMap<&Path, usize> = model
+        .sources
+        .keys()
+        .enumerate()
+        .map(|(n, file)| (file.as_path(), n))
+        .collect();
+    let anchor =
+        |file: &Path, line: usize| source_ids.get(file).map(|n| format!("#src-{n}-{line}"));
+
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>cosmwasm-guard report</title>\n<style>{STYLE}</style>\n</head>\n<body>\n"
+    );
+    let _ = writeln!(
+        out,
+        "<header><h1>cosmwasm-guard report</h1><p>cosmwasm-guard {} &middot; {} files analyzed</p>\
+         <nav><a href=\"#findings\">Findings</a><a href=\"#permissions\">Permission matrix</a>\
+         <a href=\"#metrics\">Metrics</a><a href=\"#sources\">Sources</a></nav></header>",
+        env!("CARGO_PKG_VERSION"),
+        report.files_analyzed.len()
+    );
+
+    // Summary
+    let counts = &report.findings_by_severity;
+    out.push_str("<section id=\"summary\"><h2>Summary</h2><div class=\"cards\">");
+    for (class, label, count) in [
+        ("critical", "Critical", counts.critical),
+        ("high", "High", counts.high),
+        ("medium", "Medium", counts.medium),
+        ("low", "Low", counts.low),
+        ("info", "Informational", counts.informational),
+    ] {
+        let _ = write!(
+            out,
+            "<div class=\"card\"><span class=\"badge {class}\">{label}</span><strong>{count}</strong></div>"
+        );
+    }
+    let _ = writeln!(
+        out,
+        "<div class=\"card\">Files that failed to parse<strong>{}</strong></div>\
+         <div class=\"card\">Blind spots<strong>{}</strong></div></div></section>",
+        report.analysis_errors.len(),
+        report.analysis_warnings.len()
+    );
+
+    // Findings
+    out.push_str("<section id=\"findings\"><h2>Findings</h2>\n<div class=\"filters\">");
+    let values = |f: fn(&Finding) -> Option<String>| -> BTreeSet<String> {
+        report.findings.iter().filter_map(f).collect()
+    };
+    select(
+        &mut out,
+        "severity",
+        "Severity",
+        &values(|f| Some(f.severity.to_string())),
+    );
+    select(
+        &mut out,
+        "detector",
+        "Detector",
+        &values(|f| Some(f.detector_name.clone())),
+    );
+    select(
+        &mut out,
+        "file",
+        "File",
+        &values(|f| f.locations.first().map(|l| l.file.display().to_string())),
+    );
+    out.push_str(
+        "<button id=\"expand\">Expand all</button><button id=\"collapse\">Collapse all</button>\
+         <span id=\"shown\"></span></div>\n",
+    );
+    if report.findings.is_empty() {
+        out.push_str("<p>No findings.</p>");
+    }
+    for (k, finding) in report.findings.iter().enumerate() {
+        let class = severity_class(&finding.severity);
+        let file = finding
+            .locations
+            .first()
+            .map(|l| l.file.display().to_string())
+            .unwrap_or_default();
+        let code = codes::code_of(&finding.detector_name)
+            .map(|c| format!(" {c}"))
+            .unwrap_or_default();
+        let _ = write!(
+            out,
+            "<details class=\"finding\" id=\"f-{k}\" data-severity=\"{}\" data-detector=\"{}\" data-file=\"{}\">\
+             <summary><span class=\"badge {class}\">{}</span>{}<span class=\"detector\">{}{code}</span>",
+            finding.severity,
+            escape(&finding.detector_name),
+            escape(&file),
+            finding.severity,
+            escape(&finding.title),
+            escape(&finding.detector_name),
+        );
+        if let Some(loc) = finding.locations.first() {
+            let position = format!("{}:{}", loc.file.display(), loc.start_line);
+            match anchor(&loc.file, loc.start_line) {
+                Some(href) => {
+                    let _ = write!(
+                        out,
+                        "<a class=\"loc\" href=\"{href}\">{}</a>",
+                        escape(&position)
+                    );
+                }
+                None => {
+                    let _ = write!(out, "<span class=\"loc\">{}</span>", escape(&position));
+                }
+            }
+        }
+        let _ = write!(
+            out,
+            "</summary><div class=\"body\"><p>{}</p>",
+            escape(&finding.description)
+        );
+        if let Some(snippet) = finding.locations.first().and_then(|l| l.snippet.as_ref()) {
+            let _ = write!(out, "<pre>{}</pre>", escape(snippet));
+        }
+        if let Some(rec) = &finding.recommendation {
+            let _ = write!(
+                out,
+                "<p><strong>Recommendation:</strong> {}</p>",
+                escape(rec)
+            );
+        }
+        if let Some(fix) = &finding.fix {
+            let _ = write!(
+                out,
+                "<p><strong>Fix:</strong> {}</p>",
+                escape(&fix.description)
+            );
+        }
+        if finding.locations.len() > 1 {
+            out.push_str("<p><strong>Also at:</strong></p><ul>");
+            for loc in &finding.locations[1..] {
+                let position = escape(&format!("{}:{}", loc.file.display(), loc.start_line));
+                match anchor(&loc.file, loc.start_line) {
+                    Some(href) => {
+                        let _ = write!(out, "<li><a href=\"{href}\">{position}</a></li>");
+                    }
+                    None => {
+                        let _ = write!(out, "<li>{position}</li>");
+                    }
+                }
+            }
+            out.push_str("</ul>");
+        }
+        if let Some(owner) = &finding.owner {
+            let _ = write!(out, "<p><strong>Owner:</strong> {}</p>", escape(owner));
+        }
+        if let Some(origin) = &finding.inherited_from {
+            let _ = write!(
+                out,
+                "<p><strong>Inherited from</strong> {}</p>",
+                escape(origin)
+            );
+        }
+        out.push_str("</div></details>\n");
+    }
+    out.push_str("</section>\n");
+
+    // Permission matrix
+    out.push_str("<section id=\"permissions\"><h2>Permission matrix</h2>\n");
+    for contract in &model.contracts {
+        let matrix = &contract.permissions;
+        if model.contracts.len() > 1 {
+            let _ = write!(out, "<h3>{}</h3>", escape(&contract.name));
+        }
+        if matrix.roles.is_empty() {
+            out.push_str("<p>No roles: no sender checks found.</p>");
+        } else {
+            out.push_str("<table><tr><th>Role</th><th>Kind</th><th>State</th></tr>");
+            for role in &matrix.roles {
+                let kind = match role.kind {
+                    RoleKind::Address => "address",
+                    RoleKind::Membership => "membership",
+                    RoleKind::Helper => "helper",
+                };
+                let _ = write!(
+                    out,
+                    "<tr><td>{}</td><td>{kind}</td><td>{}</td></tr>",
+                    escape(&role.name),
+                    escape(role.item.as_deref().unwrap_or("-"))
+                );
+            }
+            out.push_str("</table>");
+        }
+        if !matrix.variants.is_empty() {
+            out.push_str(
+                "<table><tr><th>Message</th><th>Allowed</th><th>Privileged writes</th></tr>",
+            );
+            for variant in &matrix.variants {
+                let allowed = if variant.callable_by_anyone() {
+                    "anyone".to_string()
+                } else {
+                    variant.roles.join(", ")
+                };
+                let _ = write!(
+                    out,
+                    "<tr{}><td>{}::{}</td><td>{}</td><td>{}</td></tr>",
+                    if variant.is_flagged() {
+                        " class=\"flagged\""
+                    } else {
+                        ""
+                    },
+                    escape(&variant.message),
+                    escape(&variant.variant),
+                    escape(&allowed),
+                    escape(&variant.privileged_writes.join(", "))
+                );
+            }
+            out.push_str("</table>");
+        }
+        let flagged = matrix.flagged().count();
+        if flagged > 0 {
+            let _ = write!(
+                out,
+                "<p>{flagged} variant(s) callable by anyone mutate privileged state.</p>"
+            );
+        }
+    }
+    out.push_str("</section>\n");
+
+    // Metrics
+    out.push_str("<section id=\"metrics\"><h2>Metrics</h2>\n");
+    for contract in &model.contracts {
+        if model.contracts.len() > 1 {
+            let _ = write!(out, "<h3>{}</h3>", escape(&contract.name));
+        }
+        out.push_str(
+            "<table><tr><th>Function</th><th>Location</th><th>Lines</th>\
+             <th>Cyclomatic complexity</th><th>Storage writes</th></tr>",
+        );
+        for func in &contract.metrics {
+            let _ = write!(
+                out,
+                "<tr><td>{}</td><td>{}:{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&func.name),
+                escape(&func.file.display().to_string()),
+                func.line,
+                func.lines,
+                func.complexity,
+                func.storage_writes
+            );
+        }
+        out.push_str("</table>");
+    }
+    out.push_str("</section>\n");
+
+    // Sources, with a marker per finding on its first line
+    let mut markers: BTreeMap<(&Path, usize), Vec<(usize, &Finding)>> = BTreeMap::new();
+    for (k, finding) in report.findings.iter().enumerate() {
+        for loc in &finding.locations {
+            markers
+                .entry((loc.file.as_path(), loc.start_line))
+                .or_default()
+                .push((k, finding));
+        }
+    }
+    out.push_str("<section id=\"sources\"><h2>Sources</h2>\n");
+    if model.sources.is_empty() {
+        out.push_str("<p>No source embedded.</p>");
+    }
+    for (file, source) in &model.sources {
+        let n = source_ids[file.as_path()];
+        let count = report
+            .findings
+            .iter()
+            .filter(|f| f.locations.iter().any(|l| &l.file == file))
+            .count();
+        let _ = write!(
+            out,
+            "<details class=\"source\" id=\"src-{n}\"><summary>{} ({count} findings)</summary>\
+             <table class=\"code\">",
+            escape(&file.display().to_string())
+        );
+        let mut state = Lex::Code;
+        for (idx, line) in source.lines().enumerate() {
+            let number = idx + 1;
+            let marks = markers.get(&(file.as_path(), number));
+            let _ = write!(
+                out,
+                "<tr id=\"src-{n}-{number}\"{}><td class=\"ln\">{number}</td><td>",
+                if marks.is_some() {
+                    " class=\"marked\""
+                } else {
+                    ""
+                }
+            );
+            for (k, finding) in marks.into_iter().flatten() {
+                let _ = write!(
+                    out,
+                    "<a class=\"marker {}\" href=\"#f-{k}\" title=\"{}\"></a>",
+                    severity_class(&finding.severity),
+                    escape(&finding.title)
+                );
+            }
+            let _ = write!(out, "</td><td>{}</td></tr>", highlight(line, &mut state));
+        }
+        out.push_str("</table></details>\n");
+    }
+    out.push_str("</section>\n");
+
+    let _ = write!(out, "<script>{SCRIPT}</script>\n</body>\n</html>\n");
+    out
+}
+
+pub fn print(report: &AnalysisReport, model: &HtmlModel) -> Result<()> {
+    print!("{}", render(report, model));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_carries_state_across_lines() {
+        let mut state = Lex::Code;
+        assert_eq!(
+            highlight("let x = \"a<b\"; // done", &mut state),
+            "<span class=\"k\">let</span> x = <span class=\"s\">&quot;a&lt;b&quot;</span>; \
+             <span class=\"c\">// done</span>"
+        );
+        assert_eq!(
+            highlight("ensure!(x > 1, Error::Fail); /* open", &mut state),
+            "<span class=\"m\">ensure!</span>(x &gt; <span class=\"n\">1</span>, \
+             <span class=\"t\">Error</span>::<span class=\"t\">Fail</span>); \
+             <span class=\"c\">/* open</span>"
+        );
+        assert_eq!(state, Lex::BlockComment(1));
+        assert_eq!(
+            highlight("still */ r#\"raw", &mut state),
+            "<span class=\"c\">still */</span> <span class=\"s\">r#&quot;raw</span>"
+        );
+        assert_eq!(state, Lex::RawStr(1));
+        assert_eq!(
+            highlight("\"# 'a' 'b", &mut state),
+            "<span class=\"s\">&quot;#</span> <span class=\"s\">&#39;a&#39;</span> &#39;b"
+        );
+        assert_eq!(state, Lex::Code);
+    }
+}
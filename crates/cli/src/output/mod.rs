@@ -1,3 +1,7 @@
+pub mod html;
 pub mod json;
+pub mod pdf;
+pub mod rustc_json;
 pub mod sarif;
+pub mod summary;
 pub mod text;
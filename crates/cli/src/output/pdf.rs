@@ -0,0 +1,696 @@
+//! Print-ready PDF audit report: a title page with the contract's metadata,
+//! summary tables and every finding with its snippet and recommendation.
+//!
+//! The document is written directly, without a PDF library: text is laid
+//! out on A4 pages in the base-14 fonts (Helvetica and Courier) every viewer
+//! provides, so no font is embedded. Characters outside Windows-1252 print
+//! as `?`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cosmwasm_guard::codes;
+use cosmwasm_guard::finding::Severity;
+use cosmwasm_guard::report::AnalysisReport;
+
+use super::html::HtmlModel;
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+
+/// Advance widths of `' '..='~'` in thousandths of the font size, from the
+/// Adobe font metrics
+const HELVETICA: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+const HELVETICA_BOLD: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Mono => "F3",
+        }
+    }
+
+    fn width(self, text: &str, size: f32) -> f32 {
+        let units: u32 = text
+            .chars()
+            .map(|c| {
+                let table = match self {
+                    Font::Mono => return 600,
+                    Font::Regular => &HELVETICA,
+                    Font::Bold => &HELVETICA_BOLD,
+                };
+                match c {
+                    ' '..='~' => u32::from(table[c as usize - 32]),
+                    _ => 556,
+                }
+            })
+            .sum();
+        units as f32 * size / 1000.0
+    }
+}
+
+type Rgb = (f32, f32, f32);
+
+const BLACK: Rgb = (0.0, 0.0, 0.0);
+const GRAY: Rgb = (0.4, 0.4, 0.4);
+
+fn severity_color(severity: &Severity) -> Rgb {
+    match severity {
+        Severity::Critical => (0.42, 0.11, 0.60),
+        Severity::High => (0.78, 0.16, 0.16),
+        Severity::Medium => (0.94, 0.42, 0.0),
+        Severity::Low => (0.75, 0.55, 0.0),
+        Severity::Informational => (0.08, 0.40, 0.75),
+    }
+}
+
+/// Byte of `c` in WinAnsiEncoding
+fn win_ansi(c: char) -> u8 {
+    match c {
+        ' '..='~' => c as u8,
+        '\u{a0}'..='\u{ff}' => c as u32 as u8,
+        '€' => 0x80,
+        '…' => 0x85,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '™' => 0x99,
+        _ => b'?',
+    }
+}
+
+/// `text` as a PDF literal string
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut out = vec![b'('];
+    for c in text.chars() {
+        let byte = win_ansi(c);
+        if matches!(byte, b'(' | b')' | b'\\') {
+            out.push(b'\\');
+        }
+        out.push(byte);
+    }
+    out.push(b')');
+    out
+}
+
+/// Split `text` into lines no wider than `width`, breaking at spaces and
+/// inside words longer than a line
+fn wrap(text: &str, font: Font, size: f32, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{line} {word}")
+            };
+            if font.width(&candidate, size) <= width {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for c in word.chars() {
+                if !line.is_empty() && font.width(&format!("{line}{c}"), size) > width {
+                    lines.push(std::mem::take(&mut line));
+                }
+                line.push(c);
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Lays out text, code and tables top to bottom, starting a new page when
+/// the current one is full
+struct PdfWriter {
+    pages: Vec<Vec<u8>>,
+    /// Top of the free space on the current page
+    y: f32,
+}
+
+impl PdfWriter {
+    fn new() -> Self {
+        let mut writer = Self {
+            pages: Vec::new(),
+            y: 0.0,
+        };
+        writer.new_page();
+        writer
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(Vec::new());
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn ensure(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn op(&mut self, op: &str) {
+        let page = self.pages.last_mut().expect("a page is open");
+        page.extend_from_slice(op.as_bytes());
+        page.push(b'\n');
+    }
+
+    fn text_at(&mut self, x: f32, baseline: f32, font: Font, size: f32, color: Rgb, text: &str) {
+        let page = self.pages.last_mut().expect("a page is open");
+        show_text(page, x, baseline, font, size, color, text);
+    }
+
+    fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, gray: f32) {
+        self.op(&format!(
+            "{gray:.2} g {x:.2} {y:.2} {width:.2} {height:.2} re f 0 g"
+        ));
+    }
+
+    fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.op(&format!(
+            "0.5 w 0.75 G {x:.2} {y:.2} {width:.2} {height:.2} re S 0 G"
+        ));
+    }
+
+    fn space(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    /// Wrapped lines of `text` across the content width, indented by `indent`
+    fn paragraph(&mut self, text: &str, font: Font, size: f32, color: Rgb, indent: f32) {
+        let leading = size * 1.35;
+        for line in wrap(text, font, size, CONTENT_WIDTH - indent) {
+            self.ensure(leading);
+            self.y -= leading;
+            self.text_at(
+                MARGIN + indent,
+                self.y + size * 0.3,
+                font,
+                size,
+                color,
+                &line,
+            );
+        }
+    }
+
+    fn heading(&mut self, text: &str, size: f32) {
+        // Keep a heading with at least a few lines of what follows
+        self.ensure(size * 2.0 + 40.0);
+        self.space(size * 0.6);
+        self.paragraph(text, Font::Bold, size, BLACK, 0.0);
+        self.space(size * 0.4);
+    }
+
+    /// Monospaced lines on a shaded background
+    fn code(&mut self, text: &str) {
+        let size = 8.0;
+        let leading = size * 1.3;
+        for line in text.lines() {
+            let line = line.replace('\t', "    ");
+            for part in wrap(&line, Font::Mono, size, CONTENT_WIDTH - 8.0) {
+                self.ensure(leading);
+                self.y -= leading;
+                self.fill_rect(MARGIN, self.y, CONTENT_WIDTH, leading, 0.95);
+                self.text_at(
+                    MARGIN + 4.0,
+                    self.y + size * 0.35,
+                    Font::Mono,
+                    size,
+                    BLACK,
+                    &part,
+                );
+            }
+        }
+        self.space(4.0);
+    }
+
+    /// A bordered table; `widths` are fractions of the content width, and
+    /// the header, if any, is repeated on each page the table continues on
+    fn table(&mut self, headers: &[&str], widths: &[f32], rows: &[(Vec<String>, Rgb)]) {
+        let size = 9.0;
+        let leading = size * 1.3;
+        let pad = 4.0;
+        let widths: Vec<f32> = widths.iter().map(|w| w * CONTENT_WIDTH).collect();
+        let header: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+        if !header.is_empty() {
+            self.table_row(&header, &widths, Font::Bold, BLACK, Some(0.9));
+        }
+        for (cells, color) in rows {
+            let height = row_height(cells, &widths, size, leading, pad);
+            if self.y - height < MARGIN {
+                self.new_page();
+                if !header.is_empty() {
+                    self.table_row(&header, &widths, Font::Bold, BLACK, Some(0.9));
+                }
+            }
+            self.table_row(cells, &widths, Font::Regular, *color, None);
+        }
+        self.space(10.0);
+    }
+
+    fn table_row(
+        &mut self,
+        cells: &[String],
+        widths: &[f32],
+        font: Font,
+        color: Rgb,
+        fill: Option<f32>,
+    ) {
+        let size = 9.0;
+        let leading = size * 1.3;
+        let pad = 4.0;
+        let height = row_height(cells, widths, size, leading, pad);
+        self.ensure(height);
+        let top = self.y;
+        let mut x = MARGIN;
+        for (cell, width) in cells.iter().zip(widths) {
+            if let Some(gray) = fill {
+                self.fill_rect(x, top - height, *width, height, gray);
+            }
+            self.stroke_rect(x, top - height, *width, height);
+            let mut baseline = top - pad - size;
+            for line in wrap(cell, font, size, width - 2.0 * pad) {
+                self.text_at(x + pad, baseline + size * 0.15, font, size, color, &line);
+                baseline -= leading;
+            }
+            x += width;
+        }
+        self.y = top - height;
+    }
+
+    /// The finished document, with `footer` and the page number at the
+    /// bottom of every page but the first
+    fn finish(mut self, title: &str, footer: &str) -> Vec<u8> {
+        let count = self.pages.len();
+        for (n, page) in self.pages.iter_mut().enumerate().skip(1) {
+            let label = format!("Page {} of {count}", n + 1);
+            let x = PAGE_WIDTH - MARGIN - Font::Regular.width(&label, 8.0);
+            show_text(page, MARGIN, MARGIN / 2.0, Font::Regular, 8.0, GRAY, footer);
+            show_text(page, x, MARGIN / 2.0, Font::Regular, 8.0, GRAY, &label);
+        }
+
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+        let kids: Vec<String> = (0..count).map(|i| format!("{} 0 R", 7 + 2 * i)).collect();
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        objects.push(
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {count} >>",
+                kids.join(" ")
+            )
+            .into_bytes(),
+        );
+        for font in ["Helvetica", "Helvetica-Bold", "Courier"] {
+            objects.push(
+                format!(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /{font} /Encoding /WinAnsiEncoding >>"
+                )
+                .into_bytes(),
+            );
+        }
+        let mut info = b"<< /Title ".to_vec();
+        info.extend(pdf_string(title));
+        info.extend(b" /Producer ");
+        info.extend(pdf_string(&format!(
+            "cosmwasm-guard {}",
+            env!("CARGO_PKG_VERSION")
+        )));
+        info.extend(b" >>");
+        objects.push(info);
+        for (i, content) in self.pages.iter().enumerate() {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                     /Resources << /Font << /F1 3 0 R /F2 4 0 R /F3 5 0 R >> >> /Contents {} 0 R >>",
+                    8 + 2 * i
+                )
+                .into_bytes(),
+            );
+            let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+            stream.extend(content);
+            stream.extend(b"endstream");
+            objects.push(stream);
+        }
+
+        let mut out = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+            out.extend(object);
+            out.extend(b"\nendobj\n");
+        }
+        let xref = out.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(table, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            table,
+            "trailer\n<< /Size {} /Root 1 0 R /Info 6 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+        out.extend(table.into_bytes());
+        out
+    }
+}
+
+/// Draw `text` on `page` with its baseline starting at `x`, `baseline`
+fn show_text(
+    page: &mut Vec<u8>,
+    x: f32,
+    baseline: f32,
+    font: Font,
+    size: f32,
+    color: Rgb,
+    text: &str,
+) {
+    let (r, g, b) = color;
+    page.extend(
+        format!(
+            "BT {r:.3} {g:.3} {b:.3} rg /{} {size:.1} Tf {x:.2} {baseline:.2} Td ",
+            font.resource()
+        )
+        .into_bytes(),
+    );
+    page.extend(pdf_string(text));
+    page.extend_from_slice(b" Tj ET\n");
+}
+
+fn row_height(cells: &[String], widths: &[f32], size: f32, leading: f32, pad: f32) -> f32 {
+    let lines = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| wrap(cell, Font::Regular, size, width - 2.0 * pad).len())
+        .max()
+        .unwrap_or(1);
+    lines as f32 * leading + 2.0 * pad
+}
+
+/// `YYYY-MM-DD` of `time` in UTC
+pub fn utc_date(time: SystemTime) -> String {
+    let days = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() / 86_400) as i64;
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// What the title page says about the audited contract
+pub struct Metadata {
+    pub contract: String,
+    pub path: String,
+    /// Commit of the analyzed code, when it is in a git repository
+    pub revision: Option<String>,
+    pub entry_points: Vec<String>,
+    pub state_items: usize,
+    pub generated: String,
+}
+
+/// The report as a PDF document
+pub fn render(report: &AnalysisReport, model: &HtmlModel, meta: &Metadata) -> Vec<u8> {
+    let mut pdf = PdfWriter::new();
+
+    // Title page
+    pdf.space(160.0);
+    pdf.paragraph("Security Audit Report", Font::Bold, 28.0, BLACK, 0.0);
+    pdf.space(8.0);
+    pdf.paragraph(&meta.contract, Font::Regular, 18.0, GRAY, 0.0);
+    pdf.space(48.0);
+    let counts = &report.findings_by_severity;
+    let mut rows = vec![
+        ("Contract", meta.contract.clone()),
+        ("Path", meta.path.clone()),
+    ];
+    if let Some(revision) = &meta.revision {
+        rows.push(("Revision", revision.clone()));
+    }
+    rows.extend([
+        ("Files analyzed", report.files_analyzed.len().to_string()),
+        ("Entry points", meta.entry_points.join(", ")),
+        ("State items", meta.state_items.to_string()),
+        (
+            "Findings",
+            format!(
+                "{} ({} critical, {} high, {} medium, {} low, {} informational)",
+                report.total_findings,
+                counts.critical,
+                counts.high,
+                counts.medium,
+                counts.low,
+                counts.informational
+            ),
+        ),
+        ("Generated", meta.generated.clone()),
+        (
+            "Tool",
+            format!("cosmwasm-guard {}", env!("CARGO_PKG_VERSION")),
+        ),
+    ]);
+    let rows: Vec<(Vec<String>, Rgb)> = rows
+        .into_iter()
+        .map(|(key, value)| (vec![key.to_string(), value], BLACK))
+        .collect();
+    pdf.table(&[], &[0.3, 0.7], &rows);
+
+    // Summary
+    pdf.new_page();
+    pdf.heading("Summary", 18.0);
+    let rows: Vec<(Vec<String>, Rgb)> = [
+        (Severity::Critical, counts.critical),
+        (Severity::High, counts.high),
+        (Severity::Medium, counts.medium),
+        (Severity::Low, counts.low),
+        (Severity::Informational, counts.informational),
+    ]
+    .iter()
+    .map(|(severity, count)| {
+        (
+            vec![severity.to_string(), count.to_string()],
+            severity_color(severity),
+        )
+    })
+    .collect();
+    pdf.table(&["Severity", "Findings"], &[0.5, 0.5], &rows);
+
+    let mut by_detector: BTreeMap<&str, [usize; 5]> = BTreeMap::new();
+    for finding in &report.findings {
+        let counts = by_detector.entry(&finding.detector_name).or_default();
+        counts[finding.severity.clone() as usize] += 1;
+    }
+    if !by_detector.is_empty() {
+        pdf.heading("Findings by detector", 13.0);
+        let rows: Vec<(Vec<String>, Rgb)> = by_detector
+            .iter()
+            .map(|(name, counts)| {
+                let mut cells = vec![
+                    name.to_string(),
+                    codes::code_of(name).unwrap_or("-").to_string(),
+                ];
+                cells.extend(counts.iter().map(|n| n.to_string()));
+                (cells, BLACK)
+            })
+            .collect();
+        pdf.table(
+            &["Detector", "Code", "Crit", "High", "Medium", "Low", "Info"],
+            &[0.34, 0.14, 0.1, 0.1, 0.12, 0.1, 0.1],
+            &rows,
+        );
+    }
+
+    for contract in &model.contracts {
+        let matrix = &contract.permissions;
+        if matrix.variants.is_empty() {
+            continue;
+        }
+        if model.contracts.len() > 1 {
+            pdf.heading(&format!("Permission matrix: {}", contract.name), 13.0);
+        } else {
+            pdf.heading("Permission matrix", 13.0);
+        }
+        let rows: Vec<(Vec<String>, Rgb)> = matrix
+            .variants
+            .iter()
+            .map(|variant| {
+                let allowed = if variant.callable_by_anyone() {
+                    "anyone".to_string()
+                } else {
+                    variant.roles.join(", ")
+                };
+                let color = if variant.is_flagged() {
+                    severity_color(&Severity::High)
+                } else {
+                    BLACK
+                };
+                (
+                    vec![
+                        format!("{}::{}", variant.message, variant.variant),
+                        allowed,
+                        variant.privileged_writes.join(", "),
+                    ],
+                    color,
+                )
+            })
+            .collect();
+        pdf.table(
+            &["Message", "Allowed", "Privileged writes"],
+            &[0.45, 0.25, 0.3],
+            &rows,
+        );
+        let flagged = matrix.flagged().count();
+        if flagged > 0 {
+            pdf.paragraph(
+                &format!("{flagged} variant(s) callable by anyone mutate privileged state."),
+                Font::Regular,
+                10.0,
+                BLACK,
+                0.0,
+            );
+            pdf.space(6.0);
+        }
+    }
+
+    // Findings
+    pdf.new_page();
+    pdf.heading("Findings", 18.0);
+    if report.findings.is_empty() {
+        pdf.paragraph("No findings.", Font::Regular, 10.0, BLACK, 0.0);
+    }
+    for (k, finding) in report.findings.iter().enumerate() {
+        pdf.ensure(80.0);
+        pdf.space(6.0);
+        pdf.paragraph(
+            &format!("F-{}  {}", k + 1, finding.title),
+            Font::Bold,
+            12.0,
+            BLACK,
+            0.0,
+        );
+        let mut details = vec![
+            finding.severity.to_string(),
+            format!("{} confidence", finding.confidence),
+            match codes::code_of(&finding.detector_name) {
+                Some(code) => format!("{} ({code})", finding.detector_name),
+                None => finding.detector_name.clone(),
+            },
+        ];
+        if let Some(loc) = finding.locations.first() {
+            details.push(format!("{}:{}", loc.file.display(), loc.start_line));
+        }
+        if let Some(origin) = &finding.inherited_from {
+            details.push(format!("inherited from {origin}"));
+        }
+        pdf.paragraph(
+            &details.join("  •  "),
+            Font::Regular,
+            9.0,
+            severity_color(&finding.severity),
+            0.0,
+        );
+        pdf.space(4.0);
+        pdf.paragraph(&finding.description, Font::Regular, 10.0, BLACK, 0.0);
+        if let Some(snippet) = finding.locations.first().and_then(|l| l.snippet.as_ref()) {
+            pdf.space(4.0);
+            pdf.code(snippet);
+        }
+        if let Some(rec) = &finding.recommendation {
+            pdf.space(2.0);
+            pdf.paragraph("Recommendation", Font::Bold, 10.0, BLACK, 0.0);
+            pdf.paragraph(rec, Font::Regular, 10.0, BLACK, 0.0);
+        }
+        pdf.space(8.0);
+    }
+
+    let title = format!("Security Audit Report: {}", meta.contract);
+    pdf.finish(&title, &title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_structure_is_consistent() {
+        let mut pdf = PdfWriter::new();
+        pdf.paragraph("Balance (€) \\ paren", Font::Regular, 10.0, BLACK, 0.0);
+        for _ in 0..80 {
+            pdf.code("let x = 1;");
+        }
+        let bytes = pdf.finish("Title", "Footer");
+        assert!(bytes.starts_with(b"%PDF-1.4"));
+        assert!(bytes.ends_with(b"%%EOF\n"));
+        let literal = b"(Balance \\(\x80\\) \\\\ paren)";
+        assert!(bytes.windows(literal.len()).any(|w| w == literal));
+
+        // Every xref entry points at its object
+        let find = |needle: &[u8]| {
+            bytes
+                .windows(needle.len())
+                .rposition(|w| w == needle)
+                .unwrap()
+        };
+        let tail = std::str::from_utf8(&bytes[find(b"startxref\n")..]).unwrap();
+        let startxref: usize = tail.lines().nth(1).unwrap().parse().unwrap();
+        assert!(bytes[startxref..].starts_with(b"xref\n"));
+        let xref = std::str::from_utf8(&bytes[startxref..]).unwrap();
+        let entries: Vec<&str> = xref
+            .lines()
+            .skip(3)
+            .take_while(|l| l.ends_with(" n "))
+            .collect();
+        let text = String::from_utf8_lossy(&bytes);
+        assert_eq!(entries.len(), 6 + 2 * text.matches("/Type /Page ").count());
+        for (i, entry) in entries.iter().enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(bytes[offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+        assert!(text.contains("(Page 2 of 2) Tj"));
+    }
+
+    #[test]
+    fn test_utc_date() {
+        assert_eq!(utc_date(UNIX_EPOCH), "1970-01-01");
+        let leap = UNIX_EPOCH + std::time::Duration::from_secs(951_782_400);
+        assert_eq!(utc_date(leap), "2000-02-29");
+    }
+}
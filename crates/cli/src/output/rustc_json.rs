@@ -0,0 +1,160 @@
+use anyhow::Result;
+use cosmwasm_guard::codes;
+use cosmwasm_guard::finding::{Finding, Severity, SourceLocation};
+use cosmwasm_guard::report::{AnalysisError, AnalysisReport};
+use serde_json::json;
+
+/// Print findings as rustc JSON diagnostics (`--error-format=json`), one per
+/// line, so editor integrations built for `cargo check` show them natively:
+/// rust-analyzer as a `check.overrideCommand`, vim and IntelliJ through the
+/// `rendered` text. Files that could not be analyzed are reported as warnings.
+pub fn print(report: &AnalysisReport) -> Result<()> {
+    for finding in &report.findings {
+        println!("{}", serde_json::to_string(&diagnostic(finding))?);
+    }
+    for error in &report.analysis_errors {
+        println!("{}", serde_json::to_string(&error_diagnostic(error))?);
+    }
+    Ok(())
+}
+
+fn level(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium | Severity::Low => "warning",
+        Severity::Informational => "note",
+    }
+}
+
+/// A rustc span; columns are 1-based, its source lines come from the snippet
+fn span(loc: &SourceLocation, is_primary: bool) -> serde_json::Value {
+    let lines: Vec<&str> = loc.snippet.as_deref().map_or(Vec::new(), |s| s.lines().collect());
+    let last = lines.len().saturating_sub(1);
+    let text: Vec<serde_json::Value> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let start = if i == 0 { loc.start_col + 1 } else { 1 };
+            let end = if i == last && loc.start_line + i == loc.end_line {
+                loc.end_col + 1
+            } else {
+                line.chars().count() + 1
+            };
+            json!({
+                "text": line,
+                "highlight_start": start,
+                "highlight_end": end.max(start),
+            })
+        })
+        .collect();
+    let bytes = loc.byte_range.clone().unwrap_or_default();
+    json!({
+        "file_name": loc.file.to_string_lossy().replace('\\', "/"),
+        "byte_start": bytes.start,
+        "byte_end": bytes.end,
+        "line_start": loc.start_line,
+        "line_end": loc.end_line,
+        "column_start": loc.start_col + 1,
+        "column_end": loc.end_col + 1,
+        "is_primary": is_primary,
+        "text": text,
+        "label": null,
+        "suggested_replacement": null,
+        "suggestion_applicability": null,
+        "expansion": null,
+    })
+}
+
+/// A child note or help without spans
+fn child(level: &str, message: &str) -> serde_json::Value {
+    json!({
+        "message": message,
+        "code": null,
+        "level": level,
+        "spans": [],
+        "children": [],
+        "rendered": null,
+    })
+}
+
+fn diagnostic(finding: &Finding) -> serde_json::Value {
+    let level = level(&finding.severity);
+    let code = codes::code_of(&finding.detector_name).unwrap_or(&finding.detector_name);
+    let spans: Vec<serde_json::Value> = finding
+        .locations
+        .iter()
+        .enumerate()
+        .map(|(i, loc)| span(loc, i == 0))
+        .collect();
+
+    let mut children = vec![child("note", &finding.description)];
+    if let Some(recommendation) = &finding.recommendation {
+        children.push(child("help", recommendation));
+    }
+    for advisory in &finding.advisories {
+        children.push(child(
+            "note",
+            &format!("advisory {}: {}", advisory.id, advisory.url),
+        ));
+    }
+
+    let mut rendered = format!("{level}[{code}]: {}\n", finding.title);
+    if let Some(loc) = finding.locations.first() {
+        rendered.push_str(&format!(
+            "  --> {}:{}:{}\n",
+            loc.file.display(),
+            loc.start_line,
+            loc.start_col + 1
+        ));
+    }
+    rendered.push_str(&format!("  = note: {}\n", finding.description));
+    if let Some(recommendation) = &finding.recommendation {
+        rendered.push_str(&format!("  = help: {recommendation}\n"));
+    }
+
+    json!({
+        "$message_type": "diagnostic",
+        "message": finding.title,
+        "code": { "code": code, "explanation": null },
+        "level": level,
+        "spans": spans,
+        "children": children,
+        "rendered": rendered,
+    })
+}
+
+fn error_diagnostic(error: &AnalysisError) -> serde_json::Value {
+    let message = format!("cosmwasm-guard could not analyze this file: {}", error.message);
+    let spans: Vec<serde_json::Value> = error
+        .line
+        .map(|line| {
+            let col = error.column.unwrap_or(0);
+            span(
+                &SourceLocation {
+                    file: error.file.clone(),
+                    start_line: line,
+                    end_line: line,
+                    start_col: col,
+                    end_col: col,
+                    snippet: None,
+                    byte_range: None,
+                },
+                true,
+            )
+        })
+        .into_iter()
+        .collect();
+    let location = match (error.line, error.column) {
+        (Some(line), col) => format!("{}:{line}:{}", error.file.display(), col.unwrap_or(0) + 1),
+        (None, _) => error.file.display().to_string(),
+    };
+    json!({
+        "$message_type": "diagnostic",
+        "message": message,
+        "code": null,
+        "level": "warning",
+        "spans": spans,
+        "children": [],
+        "rendered": format!("warning: {message}\n  --> {location}\n"),
+    })
+}
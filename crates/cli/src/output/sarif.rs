@@ -1,22 +1,86 @@
+use std::path::Path;
+
 use anyhow::Result;
-use cosmwasm_guard::finding::Severity;
-use cosmwasm_guard::report::AnalysisReport;
+use cosmwasm_guard::finding::{Finding, Severity, SourceLocation};
+use cosmwasm_guard::report::packages::package_of;
+use cosmwasm_guard::report::{AnalysisError, AnalysisReport};
 use serde_json::json;
 
-/// Print SARIF 2.1.0 output for GitHub Code Scanning integration
+/// Print SARIF 2.1.0 output for GitHub Code Scanning integration.
+///
+/// A workspace yields one run per Cargo package, each with its own
+/// `automationDetails.id` category, so uploading the results of one contract
+/// crate does not close the open alerts of the others.
 pub fn print(report: &AnalysisReport) -> Result<()> {
+    let runs: Vec<serde_json::Value> = if report.packages.is_empty() {
+        vec![run(report, None, |_| true)]
+    } else {
+        let mut runs: Vec<serde_json::Value> = report
+            .packages
+            .iter()
+            .enumerate()
+            .map(|(i, package)| {
+                let category = format!("cosmwasm-guard/{}/", package.name);
+                run(report, Some(category), |file| {
+                    package_of(&report.packages, file) == Some(i)
+                })
+            })
+            .collect();
+        let outside = |file: &Path| package_of(&report.packages, file).is_none();
+        if report
+            .findings
+            .iter()
+            .any(|f| f.locations.first().is_some_and(|l| outside(&l.file)))
+            || report.analysis_errors.iter().any(|e| outside(&e.file))
+        {
+            runs.push(run(
+                report,
+                Some("cosmwasm-guard/unpackaged/".to_string()),
+                outside,
+            ));
+        }
+        runs
+    };
+
+    let sarif = json!({
+        "$schema": "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/main/sarif-2.1/schema/sarif-schema-2.1.0.json",
+        "version": "2.1.0",
+        "runs": runs
+    });
+
+    let json = serde_json::to_string_pretty(&sarif)?;
+    println!("{json}");
+    Ok(())
+}
+
+/// One SARIF run covering the findings and errors whose file satisfies `includes`
+fn run(
+    report: &AnalysisReport,
+    category: Option<String>,
+    includes: impl Fn(&Path) -> bool,
+) -> serde_json::Value {
+    let findings: Vec<&Finding> = report
+        .findings
+        .iter()
+        .filter(|f| f.locations.first().is_none_or(|l| includes(&l.file)))
+        .collect();
+    let errors: Vec<&AnalysisError> = report
+        .analysis_errors
+        .iter()
+        .filter(|e| includes(&e.file))
+        .collect();
+
     // Build stable rule descriptions from detector metadata (not per-finding titles)
     let all_dets = cosmwasm_guard_detectors::all_detectors();
-    let rules: Vec<serde_json::Value> = report
-        .findings
+    let rules: Vec<serde_json::Value> = findings
         .iter()
         .map(|f| &f.detector_name)
         .collect::<std::collections::HashSet<_>>()
         .into_iter()
         .map(|name| {
             let det = all_dets.iter().find(|d| d.name() == name);
-            let finding = report.findings.iter().find(|f| &f.detector_name == name);
-            json!({
+            let finding = findings.iter().find(|f| &f.detector_name == name);
+            let mut rule = json!({
                 "id": name,
                 "shortDescription": {
                     "text": det.map_or_else(
@@ -27,12 +91,18 @@ pub fn print(report: &AnalysisReport) -> Result<()> {
                 "defaultConfiguration": {
                     "level": finding.map_or("warning", |f| severity_to_sarif_level(&f.severity))
                 }
-            })
+            });
+            // Code scanning links a rule's help to one page; the rest are listed
+            let advisories = finding.map_or(&[][..], |f| f.advisories.as_slice());
+            if let Some(first) = advisories.first() {
+                rule["helpUri"] = json!(first.url);
+                rule["properties"]["advisories"] = json!(advisories);
+            }
+            rule
         })
         .collect();
 
-    let results: Vec<serde_json::Value> = report
-        .findings
+    let results: Vec<serde_json::Value> = findings
         .iter()
         .map(|f| {
             let locations: Vec<serde_json::Value> = f
@@ -42,14 +112,9 @@ pub fn print(report: &AnalysisReport) -> Result<()> {
                     json!({
                         "physicalLocation": {
                             "artifactLocation": {
-                                "uri": loc.file.display().to_string()
+                                "uri": artifact_uri(&loc.file)
                             },
-                            "region": {
-                                "startLine": loc.start_line,
-                                "startColumn": loc.start_col + 1,
-                                "endLine": loc.end_line,
-                                "endColumn": loc.end_col + 1
-                            }
+                            "region": region(loc)
                         }
                     })
                 })
@@ -64,28 +129,51 @@ pub fn print(report: &AnalysisReport) -> Result<()> {
                 "locations": locations
             });
 
+            if let Some(name) = &f.crate_name {
+                result["properties"]["crate"] = json!(name);
+            }
+            if let Some(level) = &f.severity_level {
+                result["properties"]["severityLevel"] = json!(level);
+            }
+            if let Some(origin) = &f.inherited_from {
+                result["properties"]["inheritedFrom"] = json!(origin);
+            }
+
             // Add fix suggestions if present
             if let Some(fix) = &f.fix {
+                // One artifact change per file, in the order of their first edit
+                let mut changes: Vec<(&Path, Vec<serde_json::Value>)> = Vec::new();
+                for edit in &fix.edits {
+                    let replacement = json!({
+                        "deletedRegion": region(&edit.location),
+                        "insertedContent": {
+                            "text": edit.replacement_text
+                        }
+                    });
+                    match changes
+                        .iter_mut()
+                        .find(|(file, _)| *file == edit.location.file)
+                    {
+                        Some((_, replacements)) => replacements.push(replacement),
+                        None => changes.push((&edit.location.file, vec![replacement])),
+                    }
+                }
+                let changes: Vec<serde_json::Value> = changes
+                    .into_iter()
+                    .map(|(file, replacements)| {
+                        json!({
+                            "artifactLocation": {
+                                "uri": artifact_uri(file)
+                            },
+                            "replacements": replacements
+                        })
+                    })
+                    .collect();
                 result["fixes"] = json!([{
                     "description": {
                         "text": fix.description
                     },
-                    "artifactChanges": [{
-                        "artifactLocation": {
-                            "uri": fix.location.file.display().to_string()
-                        },
-                        "replacements": [{
-                            "deletedRegion": {
-                                "startLine": fix.location.start_line,
-                                "startColumn": fix.location.start_col + 1,
-                                "endLine": fix.location.end_line,
-                                "endColumn": fix.location.end_col + 1
-                            },
-                            "insertedContent": {
-                                "text": fix.replacement_text
-                            }
-                        }]
-                    }]
+                    "artifactChanges": changes
                 }]);
             }
 
@@ -93,30 +181,79 @@ pub fn print(report: &AnalysisReport) -> Result<()> {
         })
         .collect();
 
-    let sarif = json!({
-        "$schema": "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/main/sarif-2.1/schema/sarif-schema-2.1.0.json",
-        "version": "2.1.0",
-        "runs": [{
-            "tool": {
-                "driver": {
-                    "name": "cosmwasm-guard",
-                    "version": env!("CARGO_PKG_VERSION"),
-                    "informationUri": "https://github.com/safestackai/cosmwasm-guard",
-                    "rules": rules
-                }
-            },
-            "results": results
-        }]
+    let mut run = json!({
+        "tool": {
+            "driver": {
+                "name": "cosmwasm-guard",
+                "version": env!("CARGO_PKG_VERSION"),
+                "informationUri": "https://github.com/safestackai/cosmwasm-guard",
+                "rules": rules
+            }
+        },
+        "invocations": [invocation(&errors)],
+        "columnKind": "unicodeCodePoints",
+        "results": results
     });
+    if let Some(id) = category {
+        run["automationDetails"] = json!({ "id": id });
+    }
+    run
+}
 
-    let json = serde_json::to_string_pretty(&sarif)?;
-    println!("{json}");
-    Ok(())
+/// Region of a location. Columns count characters, matching the run's
+/// `columnKind`; the byte range, when known, pins the exact text.
+fn region(loc: &SourceLocation) -> serde_json::Value {
+    let mut region = json!({
+        "startLine": loc.start_line,
+        "startColumn": loc.start_col + 1,
+        "endLine": loc.end_line,
+        "endColumn": loc.end_col + 1
+    });
+    if let Some(bytes) = &loc.byte_range {
+        region["byteOffset"] = json!(bytes.start);
+        region["byteLength"] = json!(bytes.len());
+    }
+    if let Some(snippet) = &loc.snippet {
+        region["snippet"] = json!({ "text": snippet });
+    }
+    region
+}
+
+/// Run invocation; files skipped for read or parse errors become error notifications
+fn invocation(errors: &[&AnalysisError]) -> serde_json::Value {
+    let notifications: Vec<serde_json::Value> = errors
+        .iter()
+        .map(|e| {
+            let mut location = json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": artifact_uri(&e.file) }
+                }
+            });
+            if let (Some(line), Some(col)) = (e.line, e.column) {
+                location["physicalLocation"]["region"] =
+                    json!({ "startLine": line, "startColumn": col + 1 });
+            }
+            json!({
+                "level": "error",
+                "message": { "text": e.message },
+                "locations": [location]
+            })
+        })
+        .collect();
+    json!({
+        "executionSuccessful": errors.is_empty(),
+        "toolExecutionNotifications": notifications
+    })
+}
+
+/// SARIF artifact URIs always use `/`, whatever platform produced the path
+fn artifact_uri(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
 }
 
 fn severity_to_sarif_level(severity: &Severity) -> &'static str {
     match severity {
-        Severity::High => "error",
+        Severity::Critical | Severity::High => "error",
         Severity::Medium => "warning",
         Severity::Low => "note",
         Severity::Informational => "note",
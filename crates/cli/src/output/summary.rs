@@ -0,0 +1,65 @@
+use anyhow::Result;
+use colored::Colorize;
+use cosmwasm_guard::report::AnalysisReport;
+use serde_json::json;
+
+/// Exit code `analyze` finishes with for this report; with `strict`, code
+/// the analysis could not verify fails it too
+pub fn exit_code(report: &AnalysisReport, strict: bool) -> i32 {
+    i32::from(report.total_findings > 0 || (strict && report.unverified() > 0))
+}
+
+/// Per-severity counts, per crate when several were analyzed together, and
+/// the exit status, one line each
+pub fn print(report: &AnalysisReport, no_color: bool, strict: bool) -> Result<()> {
+    if no_color {
+        colored::control::set_override(false);
+    }
+    let counts = &report.findings_by_severity;
+    println!(
+        "critical={} high={} medium={} low={} info={} total={}",
+        counts.critical,
+        counts.high,
+        counts.medium,
+        counts.low,
+        counts.informational,
+        report.total_findings
+    );
+    if report.packages.len() > 1 {
+        for (name, counts) in report.counts_by_crate() {
+            println!(
+                "crate {name}: critical={} high={} medium={} low={} info={} total={}",
+                counts.critical,
+                counts.high,
+                counts.medium,
+                counts.low,
+                counts.informational,
+                counts.total()
+            );
+        }
+    }
+    let status = match exit_code(report, strict) {
+        0 => "pass".green().bold(),
+        _ => "fail".red().bold(),
+    };
+    if strict && report.unverified() > 0 {
+        println!("unverified: {}", report.unverified());
+    }
+    println!("status: {status} (exit {})", exit_code(report, strict));
+    Ok(())
+}
+
+/// The counts object of the JSON report, without finding bodies
+pub fn print_json(report: &AnalysisReport, strict: bool) -> Result<()> {
+    let summary = json!({
+        "files_analyzed": report.files_analyzed.len(),
+        "analysis_errors": report.analysis_errors.len(),
+        "analysis_warnings": report.analysis_warnings.len(),
+        "total_findings": report.total_findings,
+        "findings_by_severity": report.findings_by_severity,
+        "findings_by_crate": report.counts_by_crate(),
+        "exit_code": exit_code(report, strict),
+    });
+    println!("{}", serde_json::to_string_pretty(&summary)?);
+    Ok(())
+}
@@ -1,64 +1,301 @@
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::Path;
+
 use anyhow::Result;
-use colored::Colorize;
-use cosmwasm_guard::finding::Severity;
-use cosmwasm_guard::report::AnalysisReport;
+use clap::ValueEnum;
+use colored::{ColoredString, Colorize};
+use cosmwasm_guard::codes;
+use cosmwasm_guard::finding::{Finding, Severity};
+use cosmwasm_guard::report::links::file_url;
+use cosmwasm_guard::report::{AnalysisReport, LinkTemplate};
+
+/// Color palette of text output
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Theme {
+    /// Red/yellow/blue severities
+    #[default]
+    Default,
+    /// Okabe-Ito palette, distinguishable with red-green color blindness
+    Colorblind,
+    /// No colors; bold and dim only
+    None,
+}
+
+impl Theme {
+    /// Theme named in config; unknown names fall back to the default
+    pub fn from_name(name: &str) -> Self {
+        Self::from_str(name, true).unwrap_or_default()
+    }
+
+    /// `label` in the color of `severity`
+    fn severity(self, severity: &Severity, label: &str) -> ColoredString {
+        match (self, severity) {
+            (_, Severity::Informational) => label.dimmed(),
+            (Self::Default, Severity::Critical) => label.magenta().bold(),
+            (Self::Default, Severity::High) => label.red().bold(),
+            (Self::Default, Severity::Medium) => label.yellow().bold(),
+            (Self::Default, Severity::Low) => label.blue(),
+            (Self::Colorblind, Severity::Critical) => label.truecolor(204, 121, 167).bold(),
+            (Self::Colorblind, Severity::High) => label.truecolor(213, 94, 0).bold(),
+            (Self::Colorblind, Severity::Medium) => label.truecolor(230, 159, 0).bold(),
+            (Self::Colorblind, Severity::Low) => label.truecolor(86, 180, 233),
+            (Self::None, Severity::Critical) => label.bold().underline(),
+            (Self::None, Severity::High | Severity::Medium) => label.bold(),
+            (Self::None, Severity::Low) => label.normal(),
+        }
+    }
+
+    fn ok(self, text: &str) -> ColoredString {
+        match self {
+            Self::Default => text.green(),
+            Self::Colorblind => text.truecolor(0, 158, 115),
+            Self::None => text.normal(),
+        }
+    }
+
+    fn warn(self, text: &str) -> ColoredString {
+        match self {
+            Self::Default => text.yellow(),
+            Self::Colorblind => text.truecolor(230, 159, 0),
+            Self::None => text.normal(),
+        }
+    }
+}
+
+/// When to render locations as OSC-8 terminal hyperlinks
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Hyperlinks {
+    /// When stdout is a terminal other than `TERM=dumb`
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl Hyperlinks {
+    pub fn enabled(self) -> bool {
+        match self {
+            Self::Auto => {
+                std::io::stdout().is_terminal()
+                    && std::env::var("TERM").map_or(true, |term| term != "dumb")
+            }
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+pub fn severity_label(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "CRITICAL",
+        Severity::High => "HIGH",
+        Severity::Medium => "MEDIUM",
+        Severity::Low => "LOW",
+        Severity::Informational => "INFO",
+    }
+}
+
+/// Presentation settings of text output
+#[derive(Debug, Clone, Default)]
+pub struct TextOptions {
+    /// Suppress banner and summary
+    pub quiet: bool,
+    pub no_color: bool,
+    pub theme: Theme,
+    /// Wrap prose and truncate snippets to this many columns
+    pub width: Option<usize>,
+    /// One `file:line:col: severity: title [detector]` line per finding
+    pub compact: bool,
+    /// Show confidence and the recalibration signals behind it
+    pub verbose: bool,
+    /// Render `file:line` as OSC-8 hyperlinks
+    pub hyperlinks: bool,
+    /// Web URL of each location for hyperlinks; local `file://` URLs otherwise
+    pub links: Option<LinkTemplate>,
+}
+
+/// Output width: an explicit `--width` (0 disables wrapping), else the
+/// terminal's width when stdout is a terminal
+pub fn resolve_width(width: Option<usize>) -> Option<usize> {
+    match width {
+        Some(0) => None,
+        Some(width) => Some(width),
+        None if std::io::stdout().is_terminal() => {
+            terminal_size::terminal_size().map(|(w, _)| usize::from(w.0))
+        }
+        None => None,
+    }
+}
 
-pub fn print(report: &AnalysisReport, quiet: bool, no_color: bool) -> Result<()> {
-    if no_color {
+pub fn print(report: &AnalysisReport, options: &TextOptions) -> Result<()> {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if options.no_color || no_color_env || options.theme == Theme::None {
         colored::control::set_override(false);
     }
+    let theme = options.theme;
 
-    if !quiet {
+    if options.compact {
+        print_compact(report, theme);
+        return Ok(());
+    }
+
+    if !options.quiet {
         println!();
         println!("{}", "  cosmwasm-guard - CosmWasm Static Analysis".bold());
         println!("  Files analyzed: {}", report.files_analyzed.len());
         println!();
     }
 
+    if !report.analysis_errors.is_empty() {
+        println!("{}", "  Analysis errors (files skipped)".bold().underline());
+        for error in &report.analysis_errors {
+            let location = match (error.line, error.column) {
+                (Some(line), Some(col)) => format!("{}:{}:{}", error.file.display(), line, col + 1),
+                _ => error.file.display().to_string(),
+            };
+            println!("    {} {}", theme.warn(&location), error.message);
+        }
+        println!();
+    }
+
+    if options.verbose && !report.analysis_warnings.is_empty() {
+        println!(
+            "{}",
+            "  Analysis warnings (code not analyzed)".bold().underline()
+        );
+        for warning in &report.analysis_warnings {
+            let location = match (&warning.file, warning.line) {
+                (Some(file), Some(line)) => format!("{}:{}", file.display(), line),
+                (Some(file), None) => file.display().to_string(),
+                _ => String::new(),
+            };
+            println!("    {} {}", theme.warn(&location), warning.message);
+        }
+        println!();
+    }
+
     if report.findings.is_empty() {
-        if !quiet {
-            println!("  {} No issues found.", "✓".green().bold());
+        if !options.quiet {
+            println!("  {} No issues found.", theme.ok("✓").bold());
             println!();
         }
         return Ok(());
     }
 
+    let width = options.width;
+    // Name each finding's crate when a workspace's contracts share the report
+    let several_crates = report.packages.len() > 1;
     for finding in &report.findings {
-        let severity_label = match finding.severity {
-            Severity::High => "HIGH".red().bold(),
-            Severity::Medium => "MEDIUM".yellow().bold(),
-            Severity::Low => "LOW".blue(),
-            Severity::Informational => "INFO".dimmed(),
-        };
-
-        println!(
-            "  [{}] {} ({})",
-            severity_label, finding.title, finding.detector_name
+        let label = severity_label(&finding.severity);
+        let code = codes::code_of(&finding.detector_name)
+            .map(|code| format!("[{code}]"))
+            .unwrap_or_default();
+        let heading = format!(
+            "[{label}]{code} {} ({})",
+            finding.title, finding.detector_name
         );
-        println!("    {}", finding.description);
+        let mut lines = wrap(&heading, "  ", "    ", width).into_iter();
+        // The label is colored after wrapping so escapes don't count towards
+        // the width
+        if let Some(first) = lines.next() {
+            let colored = theme.severity(&finding.severity, label);
+            println!("{}", first.replacen(label, &colored.to_string(), 1));
+        }
+        for line in lines {
+            println!("{line}");
+        }
+        for line in wrap(&finding.description, "    ", "    ", width) {
+            println!("{line}");
+        }
 
         for loc in &finding.locations {
-            println!(
-                "    {} {}:{}",
-                "-->".dimmed(),
-                loc.file.display(),
-                loc.start_line
-            );
+            let mut location = format!("{}:{}", loc.file.display(), loc.start_line);
+            if options.hyperlinks {
+                let url = match &options.links {
+                    Some(links) => links.url(&loc.file, loc.start_line),
+                    None => file_url(&loc.file),
+                };
+                location = hyperlink(&location, &url);
+            }
+            println!("    {} {}", "-->".dimmed(), location);
             if let Some(snippet) = &loc.snippet {
                 for line in snippet.lines() {
+                    let line = match width {
+                        Some(width) => truncate(line, width.saturating_sub(6)),
+                        None => line.to_string(),
+                    };
                     println!("    {} {}", "|".dimmed(), line);
                 }
             }
         }
 
+        if let Some(level) = &finding.severity_level {
+            println!("    {} {}", "Level:".dimmed(), level);
+        }
+        if let Some(owner) = &finding.owner {
+            println!("    {} {}", "Owner:".dimmed(), owner);
+        }
+        if let Some(origin) = &finding.inherited_from {
+            println!("    {} inherited from {}", "Upstream:".dimmed(), origin);
+        }
+        for advisory in &finding.advisories {
+            println!(
+                "    {} {} {}",
+                "Advisory:".dimmed(),
+                advisory.id,
+                advisory.url
+            );
+        }
+        if let Some(name) = finding.crate_name.as_ref().filter(|_| several_crates) {
+            println!("    {} {}", "Crate:".dimmed(), name);
+        }
+        if let Some(id) = finding.id.as_ref().filter(|_| !finding.trace.is_empty()) {
+            println!(
+                "    {} {} steps (--explain-finding {})",
+                "Trace:".dimmed(),
+                finding.trace.len(),
+                id
+            );
+        }
+        if options.verbose {
+            println!("    {} {}", "Confidence:".dimmed(), finding.confidence);
+            for signal in &finding.confidence_signals {
+                let sign = if signal.delta > 0 { "+" } else { "-" };
+                println!("      {} {}", sign.dimmed(), signal.reason);
+            }
+            match finding.reachable_from.as_deref() {
+                Some([]) => println!(
+                    "    {} no entry point reaches this code",
+                    "Reachable:".dimmed()
+                ),
+                Some(entries) => {
+                    println!("    {} from {}", "Reachable:".dimmed(), entries.join(", "))
+                }
+                None => {}
+            }
+        }
         if let Some(rec) = &finding.recommendation {
-            println!("    {} {}", "Fix:".green(), rec);
+            let mut lines = wrap(rec, "    Fix: ", "         ", width).into_iter();
+            if let Some(first) = lines.next() {
+                println!(
+                    "{}",
+                    first.replacen("Fix:", &theme.ok("Fix:").to_string(), 1)
+                );
+            }
+            for line in lines {
+                println!("{line}");
+            }
         }
         println!();
     }
 
-    if !quiet {
+    if !options.quiet {
         println!("{}", "  Summary".bold().underline());
+        println!(
+            "    Critical:      {}",
+            report.findings_by_severity.critical
+        );
         println!("    High:          {}", report.findings_by_severity.high);
         println!("    Medium:        {}", report.findings_by_severity.medium);
         println!("    Low:           {}", report.findings_by_severity.low);
@@ -72,3 +309,180 @@ pub fn print(report: &AnalysisReport, quiet: bool, no_color: bool) -> Result<()>
 
     Ok(())
 }
+
+/// Step-by-step evidence behind `finding`, each step with the source line it
+/// points at
+pub fn explain(finding: &Finding, no_color: bool) -> Result<()> {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if no_color || no_color_env {
+        colored::control::set_override(false);
+    }
+
+    let label = severity_label(&finding.severity);
+    let code = codes::code_of(&finding.detector_name)
+        .map(|code| format!("[{code}]"))
+        .unwrap_or_default();
+    println!(
+        "[{}]{code} {} ({})",
+        Theme::Default.severity(&finding.severity, label),
+        finding.title,
+        finding.detector_name
+    );
+    if let Some(loc) = finding.locations.first() {
+        println!(
+            "  {} {}:{}",
+            "-->".dimmed(),
+            loc.file.display(),
+            loc.start_line
+        );
+    }
+    for advisory in &finding.advisories {
+        println!(
+            "  {} {} {}",
+            "Advisory:".dimmed(),
+            advisory.id,
+            advisory.url
+        );
+    }
+    println!();
+
+    if finding.trace.is_empty() {
+        println!("  No evidence trace recorded; the detector reports:");
+        println!("  {}", finding.description);
+        return Ok(());
+    }
+
+    let mut sources: HashMap<&Path, Option<String>> = HashMap::new();
+    for (i, step) in finding.trace.iter().enumerate() {
+        println!(
+            "  {}. {} {}",
+            i + 1,
+            format!("{}:", step.kind).bold(),
+            step.message
+        );
+        let Some(loc) = &step.location else {
+            continue;
+        };
+        println!(
+            "     {} {}:{}",
+            "-->".dimmed(),
+            loc.file.display(),
+            loc.start_line
+        );
+        let source = sources
+            .entry(loc.file.as_path())
+            .or_insert_with(|| std::fs::read_to_string(&loc.file).ok());
+        let excerpt = source
+            .as_deref()
+            .and_then(|s| s.lines().nth(loc.start_line.saturating_sub(1)));
+        if let Some(line) = excerpt {
+            println!(
+                "     {} {}",
+                format!("{:>4} |", loc.start_line).dimmed(),
+                line.trim_end()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// `file:line:col: severity: title [detector]`, one line per finding and per
+/// analysis error, never wrapped so the output stays greppable
+fn print_compact(report: &AnalysisReport, theme: Theme) {
+    for error in &report.analysis_errors {
+        let location = match (error.line, error.column) {
+            (Some(line), Some(col)) => format!("{}:{}:{}", error.file.display(), line, col + 1),
+            _ => error.file.display().to_string(),
+        };
+        println!("{location}: {}: {}", theme.warn("error"), error.message);
+    }
+    for finding in &report.findings {
+        println!(
+            "{}: {}: {} [{}]",
+            compact_location(finding),
+            theme.severity(
+                &finding.severity,
+                &severity_label(&finding.severity).to_lowercase()
+            ),
+            finding.title,
+            finding.detector_name
+        );
+    }
+}
+
+fn compact_location(finding: &Finding) -> String {
+    match finding.locations.first() {
+        Some(loc) => format!(
+            "{}:{}:{}",
+            loc.file.display(),
+            loc.start_line,
+            loc.start_col + 1
+        ),
+        None => "-".to_string(),
+    }
+}
+
+/// Greedy word wrap of `text` to `width` columns, prefixing the first line
+/// with `first` and the rest with `rest`. Words longer than a line are kept
+/// whole; without a width the text stays on one line.
+fn wrap(text: &str, first: &str, rest: &str, width: Option<usize>) -> Vec<String> {
+    let Some(width) = width else {
+        return vec![format!("{first}{text}")];
+    };
+    let mut lines = Vec::new();
+    let mut line = first.to_string();
+    let mut empty = true;
+    for word in text.split_whitespace() {
+        let len = line.chars().count();
+        if !empty && len + 1 + word.chars().count() > width {
+            lines.push(std::mem::replace(&mut line, rest.to_string()));
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+        }
+        line.push_str(word);
+        empty = false;
+    }
+    lines.push(line);
+    lines
+}
+
+/// OSC-8 escape making `text` a link to `url` in supporting terminals
+fn hyperlink(text: &str, url: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// `line` cut to `max` characters, marking the cut with an ellipsis
+fn truncate(line: &str, max: usize) -> String {
+    if line.chars().count() <= max {
+        return line.to_string();
+    }
+    let mut cut: String = line.chars().take(max.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_and_truncate_to_width() {
+        let lines = wrap(
+            "Unbounded iteration over user balances",
+            "  ",
+            "    ",
+            Some(20),
+        );
+        assert_eq!(
+            lines,
+            ["  Unbounded", "    iteration over", "    user balances"]
+        );
+        assert!(lines.iter().all(|l| l.chars().count() <= 20));
+        assert_eq!(wrap("a b", "> ", "  ", None), ["> a b"]);
+
+        assert_eq!(truncate("let total = BALANCES.load()?;", 10), "let total…");
+        assert_eq!(truncate("short", 10), "short");
+    }
+}
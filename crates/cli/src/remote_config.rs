@@ -0,0 +1,109 @@
+//! Remote configs named by `extends`, downloaded and cached for
+//! [`Config::load_with`].
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use cosmwasm_guard::config::{Config, RemoteConfig};
+use sha2::{Digest, Sha256};
+
+use crate::fetch;
+
+/// Load the config at `path`, fetching the remote configs it extends. Also
+/// returns warnings about remote configs read from the cache because they
+/// could not be fetched.
+pub fn load(path: &Path) -> Result<(Config, Vec<String>)> {
+    let mut warnings = Vec::new();
+    let config = Config::load_with(path, &mut |remote| fetch_cached(remote, &mut warnings))?;
+    Ok((config, warnings))
+}
+
+/// Fetch a remote config, caching it under `.cosmwasm-guard-cache/config`
+/// next to the local config. A pinned (`extends_sha256`) config is served from
+/// the cache without touching the network; an unpinned one is refetched every
+/// run and falls back to the cached copy when offline.
+fn fetch_cached(remote: &RemoteConfig, warnings: &mut Vec<String>) -> Result<String> {
+    let cache_file = remote.config_dir.map(|dir| cache_file(dir, remote.url));
+    let cached = cache_file
+        .as_ref()
+        .and_then(|f| std::fs::read_to_string(f).ok())
+        .filter(|c| remote.matches(c));
+
+    if remote.sha256.is_some() {
+        if let Some(content) = cached {
+            return Ok(content);
+        }
+    }
+
+    let downloaded = fetch::download(remote.url).and_then(|body| {
+        String::from_utf8(body).with_context(|| format!("{} is not valid UTF-8", remote.url))
+    });
+    match downloaded {
+        Ok(content) => {
+            if let Some(file) = cache_file.filter(|_| remote.matches(&content)) {
+                if let Some(dir) = file.parent() {
+                    let _ = std::fs::create_dir_all(dir);
+                }
+                let _ = std::fs::write(file, &content);
+            }
+            Ok(content)
+        }
+        Err(err) => match cached {
+            Some(content) => {
+                warnings.push(format!("{err:#}; using cached copy of {}", remote.url));
+                Ok(content)
+            }
+            None => Err(err),
+        },
+    }
+}
+
+fn cache_file(config_dir: &Path, url: &str) -> PathBuf {
+    let key = format!("{:x}", Sha256::digest(url.as_bytes()));
+    config_dir
+        .join(".cosmwasm-guard-cache")
+        .join("config")
+        .join(format!("{}.toml", &key[..16]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinned_remote_served_from_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = "https://config.invalid/org-guard.toml";
+        let remote = "[global]\nseverity_threshold = \"high\"\n";
+        let cached = cache_file(dir.path(), url);
+        std::fs::create_dir_all(cached.parent().unwrap()).unwrap();
+        std::fs::write(&cached, remote).unwrap();
+        let path = dir.path().join(".cosmwasm-guard.toml");
+        let pin = format!("{:x}", Sha256::digest(remote.as_bytes()));
+        std::fs::write(
+            &path,
+            format!("extends = \"{url}\"\nextends_sha256 = \"{pin}\"\n"),
+        )
+        .unwrap();
+
+        let (config, warnings) = load(&path).unwrap();
+        assert_eq!(config.global.severity_threshold, "high");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_unpinned_remote_falls_back_to_cache_with_warning() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = "https://config.invalid/org-guard.toml";
+        let cached = cache_file(dir.path(), url);
+        std::fs::create_dir_all(cached.parent().unwrap()).unwrap();
+        std::fs::write(&cached, "[global]\nseverity_threshold = \"high\"\n").unwrap();
+        let path = dir.path().join(".cosmwasm-guard.toml");
+        std::fs::write(&path, format!("extends = \"{url}\"\n")).unwrap();
+
+        let (config, warnings) = load(&path).unwrap();
+        assert_eq!(config.global.severity_threshold, "high");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("using cached copy"));
+    }
+}
@@ -1,7 +1,7 @@
 // TEST FIXTURE — safe contract patterns. All detectors should return zero findings.
 
 use cosmwasm_std::{
-    entry_point, DepsMut, Deps, Env, MessageInfo, Response,
+    entry_point, Addr, DepsMut, Deps, Env, MessageInfo, Response,
     StdResult, StdError, Uint128, Order,
 };
 use cw_storage_plus::{Item, Map};
@@ -12,7 +12,7 @@ const BALANCES: Map<&str, Uint128> = Map::new("balances");
 
 #[derive(Serialize, Deserialize)]
 pub struct Config {
-    pub owner: String,
+    pub owner: Addr,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -25,8 +25,8 @@ pub enum ExecuteMsg {
 pub fn instantiate(
     deps: DepsMut, _env: Env, info: MessageInfo, _msg: InstantiateMsg,
 ) -> StdResult<Response> {
-    // SAFE: state initialized in instantiate
-    CONFIG.save(deps.storage, &Config { owner: info.sender.to_string() })?;
+    // SAFE: state initialized in instantiate, owner stored as a validated Addr
+    CONFIG.save(deps.storage, &Config { owner: info.sender.clone() })?;
     Ok(Response::new())
 }
 
@@ -1,11 +1,12 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use cosmwasm_guard::ast::{parse_source, ContractVisitor};
 use cosmwasm_guard::config::{self, Config};
 use cosmwasm_guard::detector::{AnalysisContext, DetectorRegistry};
 use cosmwasm_guard::ir::builder::IrBuilder;
 use cosmwasm_guard_detectors::all_detectors;
+use tempfile::TempDir;
 
 fn analyze_source(source: &str) -> Vec<cosmwasm_guard::finding::Finding> {
     let ast = parse_source(source).unwrap();
@@ -20,6 +21,40 @@ fn analyze_source(source: &str) -> Vec<cosmwasm_guard::finding::Finding> {
     registry.run_all(&ctx)
 }
 
+/// Scratch project for a CLI run, removed when dropped
+struct Project(TempDir);
+
+impl Project {
+    fn new() -> Self {
+        let dir = tempfile::Builder::new()
+            .prefix("cosmwasm-guard-test-")
+            .tempdir()
+            .unwrap();
+        Self(dir)
+    }
+
+    /// Project with the vulnerable fixture contract as `src/lib.rs`
+    fn vulnerable() -> Self {
+        let project = Self::new();
+        project.write(
+            "src/lib.rs",
+            include_str!("fixtures/vulnerable_contract.rs"),
+        );
+        project
+    }
+
+    fn path(&self) -> &Path {
+        self.0.path()
+    }
+
+    /// Write `content` to `file` in the project, creating its directories
+    fn write(&self, file: &str, content: impl AsRef<[u8]>) {
+        let path = self.path().join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+}
+
 #[test]
 fn test_vulnerable_contract_has_findings() {
     let source = include_str!("fixtures/vulnerable_contract.rs");
@@ -115,3 +150,2726 @@ enabled = false
     assert!(!config.is_detector_enabled("missing-access-control"));
     assert!(config.is_detector_enabled("unsafe-unwrap"));
 }
+
+mod advisories {
+    use super::*;
+    use std::process::Command;
+
+    fn run(args: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(args)
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    #[test]
+    fn test_findings_link_advisories_and_coverage_follows_config() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write(
+            "src/lib.rs",
+            "pub fn negate(value: Int256) -> Int256 {\n    value.neg()\n}\n",
+        );
+
+        let output = run(&[
+            "analyze",
+            "--no-cache",
+            "--quiet",
+            "--format",
+            "json",
+            "--detectors",
+            "arithmetic-overflow",
+            dir.to_str().unwrap(),
+        ]);
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let advisories = &report["findings"][0]["advisories"];
+        assert_eq!(advisories[0]["id"], "CWA-2024-002");
+        assert!(advisories[0]["url"]
+            .as_str()
+            .unwrap()
+            .ends_with("CWA-2024-002.md"));
+
+        let output = run(&["advisories", "--config", "/nonexistent.toml"]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(output.status.success());
+        let line = stdout
+            .lines()
+            .find(|l| l.starts_with("CWA-2024-002"))
+            .unwrap();
+        assert!(line.contains("arithmetic-overflow"), "{stdout}");
+        assert!(!line.contains("needs"), "{stdout}");
+
+        project.write(
+            "disabled.toml",
+            "[detectors.arithmetic-overflow]\nenabled = false\n",
+        );
+        let config = dir.join("disabled.toml");
+        let output = run(&["advisories", "--config", config.to_str().unwrap()]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let line = stdout
+            .lines()
+            .find(|l| l.starts_with("CWA-2024-002"))
+            .unwrap();
+        assert!(line.contains("- (needs arithmetic-overflow)"), "{stdout}");
+    }
+}
+
+mod analysis_warnings {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_blind_spots_reported_as_warnings() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write(
+            "src/lib.rs",
+            r#"
+pub enum ExecuteMsg { Deposit {}, Sweep {} }
+
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Deposit {} => {
+            BALANCES.update(deps.storage, &info.sender, |b| -> StdResult<_> {
+                Ok(b.unwrap_or_default() + info.funds[0].amount)
+            })?;
+            Ok(Response::new())
+        }
+    }
+}
+"#,
+        );
+        let run = |args: &[&str]| {
+            Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+                .arg("analyze")
+                .arg(dir)
+                .arg("--no-cache")
+                .args(args)
+                .output()
+                .expect("failed to run cosmwasm-guard")
+        };
+
+        let json = run(&["--format", "json"]);
+        let report: serde_json::Value = serde_json::from_slice(&json.stdout).unwrap();
+        let kinds: Vec<&str> = report["analysis_warnings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|w| w["kind"].as_str().unwrap())
+            .collect();
+        assert_eq!(kinds, vec!["unresolved_handler", "closure_skipped"]);
+
+        let text = String::from_utf8_lossy(&run(&["--no-color"]).stdout).to_string();
+        assert!(!text.contains("Analysis warnings"), "{text}");
+        let verbose =
+            String::from_utf8_lossy(&run(&["--no-color", "--verbose"]).stdout).to_string();
+        assert!(verbose.contains("Analysis warnings"), "{verbose}");
+        assert!(verbose.contains("`ExecuteMsg::Sweep`"), "{verbose}");
+    }
+
+    #[test]
+    fn test_strict_fails_on_unresolved_dispatch() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write(
+            "src/lib.rs",
+            r#"
+pub enum QueryMsg { Config {}, Orphan {} }
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+    }
+}
+"#,
+        );
+        let run = |args: &[&str]| {
+            Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+                .arg("analyze")
+                .arg(dir)
+                .args(["--no-cache", "--quiet"])
+                .args(args)
+                .output()
+                .expect("failed to run cosmwasm-guard")
+        };
+
+        assert_eq!(run(&[]).status.code(), Some(0));
+        let strict = run(&["--strict", "--format", "json-summary"]);
+        assert_eq!(strict.status.code(), Some(1));
+        let summary: serde_json::Value = serde_json::from_slice(&strict.stdout).unwrap();
+        assert_eq!(summary["total_findings"], 0);
+        assert_eq!(summary["exit_code"], 1);
+    }
+}
+
+mod audited {
+    use super::*;
+    use std::process::Command;
+
+    fn analyze(dir: &Path) -> Vec<serde_json::Value> {
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--format", "json"])
+            .arg(dir.join("src"))
+            .arg("--config")
+            .arg(dir.join(".cosmwasm-guard.toml"))
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        report["findings"].as_array().unwrap().clone()
+    }
+
+    #[test]
+    fn test_findings_in_unchanged_cw_plus_code_are_labeled() {
+        let project = Project::new();
+        let dir = project.path();
+        let source = include_str!("fixtures/real-world/cw20_base_contract.rs");
+        project.write("src/contract.rs", source);
+
+        let findings = analyze(dir);
+        let inherited: Vec<&serde_json::Value> = findings
+            .iter()
+            .filter(|f| f["inherited_from"].is_string())
+            .collect();
+        assert!(!inherited.is_empty());
+        assert!(inherited.iter().all(|f| f["inherited_from"]
+            .as_str()
+            .unwrap()
+            .starts_with("cw-plus v2.0.0 cw20-base::")));
+        let custom = findings.len() - inherited.len();
+
+        // A modified handler is custom code again
+        let modified = source.replace("\"0.14.0\"", "\"0.15.0\"");
+        project.write("src/contract.rs", modified);
+        let modified_findings = analyze(dir);
+        assert!(!modified_findings.is_empty());
+        assert!(!modified_findings.iter().any(|f| f["inherited_from"]
+            .as_str()
+            .is_some_and(|o| o.ends_with("::migrate"))));
+
+        // Or hidden entirely
+        project.write("src/contract.rs", source);
+        project.write(
+            ".cosmwasm-guard.toml",
+            "[suppressions]\nhide_inherited = true\n",
+        );
+        let hidden = analyze(dir);
+        assert_eq!(hidden.len(), custom);
+        assert!(hidden.iter().all(|f| f["inherited_from"].is_null()));
+    }
+}
+
+mod branch_policy {
+    use super::*;
+    use std::process::{Command, Output};
+
+    fn guard(dir: &Path, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .current_dir(dir)
+            .args(["analyze", "--no-cache", "--format", "json"])
+            .args(args)
+            .arg(".")
+            .env_remove("GITHUB_HEAD_REF")
+            .env_remove("GITHUB_REF_NAME")
+            .env_remove("CI_COMMIT_REF_NAME")
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .expect("failed to run git");
+        assert!(output.status.success());
+    }
+
+    /// Lowest severity reported, and the number of findings
+    fn reported(output: &Output) -> (String, u64) {
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let lowest = ["informational", "low", "medium", "high", "critical"]
+            .iter()
+            .find(|s| report["findings_by_severity"][**s].as_u64() > Some(0))
+            .unwrap();
+        (
+            lowest.to_string(),
+            report["total_findings"].as_u64().unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_branch_policy_selects_threshold() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+        project.write(
+            ".cosmwasm-guard.toml",
+            "[global]\nseverity_threshold = \"critical\"\n\n\
+             [[branches]]\npattern = \"main\"\nseverity_threshold = \"high\"\n\n\
+             [[branches]]\npattern = \"release/*\"\nseverity_threshold = \"low\"\n",
+        );
+        git(dir, &["init", "-q"]);
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "init"]);
+        git(dir, &["checkout", "-q", "-b", "release/1.0"]);
+
+        // The checked-out branch picks the policy
+        let output = guard(dir, &[]);
+        assert!(String::from_utf8_lossy(&output.stderr)
+            .contains("Branch `release/1.0`: reporting Low and above"));
+        let (lowest, release_total) = reported(&output);
+        assert_eq!(lowest, "low");
+
+        // --branch overrides the checkout
+        let (lowest, main_total) = reported(&guard(dir, &["--branch", "main"]));
+        assert_eq!(lowest, "high");
+        assert!(main_total < release_total);
+
+        // Unmatched branches report from low, as without policies, and say so
+        let output = guard(dir, &["--branch", "feature/x"]);
+        assert!(String::from_utf8_lossy(&output.stderr).contains(
+            "Branch `feature/x` matches no [[branches]] policy: reporting Low and above"
+        ));
+        let (lowest, _) = reported(&output);
+        assert_eq!(lowest, "low");
+
+        // An explicit --severity wins over every policy
+        let (lowest, _) = reported(&guard(dir, &["--branch", "main", "--severity", "low"]));
+        assert_eq!(lowest, "low");
+    }
+}
+
+mod bundle {
+    use super::*;
+    use std::process::Command;
+
+    fn analyze(dir: &Path, args: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .current_dir(dir)
+            .args(["analyze", "--no-cache", "--quiet", "--format", "summary"])
+            .args(args)
+            .arg("contract")
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    fn unpack(archive: &Path, dest: &Path) -> serde_json::Value {
+        std::fs::create_dir_all(dest).unwrap();
+        let status = Command::new("tar")
+            .arg("-xf")
+            .arg(archive)
+            .arg("-C")
+            .arg(dest)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        serde_json::from_str(&std::fs::read_to_string(dest.join("manifest.json")).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_bundle_records_report_config_and_sources() {
+        let project = Project::new();
+        let dir = project.path();
+        let source = include_str!("fixtures/vulnerable_contract.rs");
+        project.write("contract/src/lib.rs", source);
+        project.write("org.toml", "[global]\nseverity_threshold = \"low\"\n");
+        project.write(
+            ".cosmwasm-guard.toml",
+            "extends = \"org.toml\"\n\n[global]\npacks = [\"defi\"]\n",
+        );
+
+        analyze(dir, &["--bundle", "out/scan.tar.gz"]);
+        let manifest = unpack(&dir.join("out/scan.tar.gz"), &dir.join("full"));
+        assert_eq!(manifest["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(manifest["config"], "config.toml");
+        // The config in effect, with the config it extends merged in
+        let config: toml::Table =
+            toml::from_str(&std::fs::read_to_string(dir.join("full/config.toml")).unwrap())
+                .unwrap();
+        assert!(!config.contains_key("extends"));
+        assert_eq!(config["global"]["severity_threshold"].as_str(), Some("low"));
+        assert_eq!(config["global"]["packs"][0].as_str(), Some("defi"));
+        assert_eq!(manifest["files"][0]["path"], "contract/src/lib.rs");
+        assert_eq!(
+            std::fs::read_to_string(dir.join("full/sources/contract/src/lib.rs")).unwrap(),
+            source
+        );
+        let report: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("full/report.json")).unwrap())
+                .unwrap();
+        assert!(report["total_findings"].as_u64().unwrap() > 0);
+
+        // Hashes only: the manifest still pins every file, no source is copied
+        analyze(
+            dir,
+            &["--bundle", "out/hashes.tar.gz", "--bundle-hashes-only"],
+        );
+        let manifest = unpack(&dir.join("out/hashes.tar.gz"), &dir.join("hashes"));
+        assert_eq!(manifest["files"][0]["bundled"], serde_json::Value::Null);
+        assert_eq!(manifest["files"][0]["sha256"].as_str().unwrap().len(), 64);
+        assert!(!dir.join("hashes/sources").exists());
+    }
+}
+
+mod cargo_subcommand {
+    use std::process::Command;
+
+    #[test]
+    fn test_cargo_subcommand_forwards_args() {
+        // cargo passes the subcommand name as the first argument
+        let output = Command::new(env!("CARGO_BIN_EXE_cargo-cosmwasm-guard"))
+            .args(["cosmwasm-guard", "list"])
+            .output()
+            .expect("failed to run cargo-cosmwasm-guard");
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("missing-access-control"));
+
+        let output = Command::new(env!("CARGO_BIN_EXE_cargo-cosmwasm-guard"))
+            .args(["cosmwasm-guard", "no-such-command"])
+            .output()
+            .expect("failed to run cargo-cosmwasm-guard");
+        assert_eq!(output.status.code(), Some(2));
+    }
+}
+
+mod codes {
+    use super::*;
+    use std::process::Command;
+
+    fn run(args: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(args)
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    #[test]
+    fn test_codes_select_detectors_and_label_findings() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+
+        let output = run(&[
+            "analyze",
+            "--no-cache",
+            "--quiet",
+            "--detectors",
+            "CWG0002",
+            dir.to_str().unwrap(),
+        ]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("[CRITICAL][CWG0002] "), "{stdout}");
+        assert!(stdout.contains("(missing-access-control)"));
+        assert!(!stdout.contains("(missing-addr-validate)"));
+    }
+
+    #[test]
+    fn test_explain() {
+        let output = run(&["explain", "cwg0002"]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("CWG0002: missing-access-control"));
+        assert!(stdout.contains("// cosmwasm-guard-ignore: CWG0002"));
+
+        assert!(!run(&["explain", "CWG9999"]).status.success());
+    }
+}
+
+mod compare_upstream {
+    use super::*;
+    use std::process::Command;
+
+    const UPSTREAM: &str = r#"
+pub const OWNER: Item<Addr> = Item::new("owner");
+
+pub enum ExecuteMsg { SetOwner { owner: String } }
+
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::SetOwner { owner } => set_owner(deps, info, owner),
+    }
+}
+
+fn set_owner(deps: DepsMut, info: MessageInfo, owner: String) -> StdResult<Response> {
+    if info.sender != OWNER.load(deps.storage)? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    OWNER.save(deps.storage, &deps.api.addr_validate(&owner)?)?;
+    Ok(Response::new())
+}
+"#;
+
+    fn compare(fork: &Path, args: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .arg("compare-upstream")
+            .arg(fork.join("src"))
+            .args(args)
+            .args(["--format", "json", "--config"])
+            .arg(fork.join(".cosmwasm-guard.toml"))
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    #[test]
+    fn test_compare_upstream_flags_removed_check() {
+        let project = Project::new();
+        let upstream = project.path().join("upstream");
+        let fork = project.path().join("fork");
+        project.write("upstream/src/lib.rs", UPSTREAM);
+        project.write(
+        "fork/src/lib.rs",
+        UPSTREAM.replace(
+            "    if info.sender != OWNER.load(deps.storage)? {\n        return Err(StdError::generic_err(\"unauthorized\"));\n    }\n",
+            "",
+        ),
+    );
+
+        let upstream_src = upstream.join("src");
+        let output = compare(&fork, &["--upstream", upstream_src.to_str().unwrap()]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let divergences: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let divergences = divergences.as_array().unwrap();
+        assert!(divergences.iter().any(|d| d["kind"] == "role-check-removed"
+            && d["subject"] == "ExecuteMsg::SetOwner"
+            && d["security_relevant"] == true));
+
+        // The same upstream cloned from a git repository
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .current_dir(&upstream)
+                .args(args)
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false)
+        };
+        let committed = git(&["init", "--quiet"])
+            && git(&["add", "."])
+            && git(&[
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                "upstream",
+            ]);
+        if !committed {
+            return;
+        }
+        let url = format!("file://{}", upstream.display());
+        let cloned = compare(&fork, &["--upstream", &url, "--upstream-dir", "src"]);
+        assert!(
+            cloned.status.success(),
+            "{}",
+            String::from_utf8_lossy(&cloned.stderr)
+        );
+        assert_eq!(cloned.stdout, output.stdout);
+    }
+}
+
+mod correlation {
+    use super::*;
+    use std::process::Command;
+
+    fn analyze(dir: &std::path::Path, severity: &str) -> serde_json::Value {
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args([
+                "analyze",
+                "--no-cache",
+                "--format",
+                "json",
+                "--severity",
+                severity,
+            ])
+            .arg("--config")
+            .arg(dir.join(".cosmwasm-guard.toml"))
+            .arg(dir)
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        serde_json::from_slice(&output.stdout).unwrap()
+    }
+
+    fn severities(report: &serde_json::Value, detector: &str) -> Vec<String> {
+        report["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|f| f["detector_name"] == detector)
+            .map(|f| f["severity"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_unguarded_privileged_write_escalates_to_critical() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+
+        let report = analyze(dir, "low");
+        assert_eq!(
+            severities(&report, "missing-access-control"),
+            vec!["Critical"]
+        );
+        assert_eq!(report["findings_by_severity"]["critical"], 1);
+
+        // Only the escalated finding passes a critical threshold
+        let report = analyze(dir, "critical");
+        assert_eq!(report["total_findings"], 1);
+
+        // A configured rule replaces the built-in one of the same name
+        project.write(
+            ".cosmwasm-guard.toml",
+            "[global]\n\n[[correlations]]\nname = \"unguarded-privileged-write\"\n\
+             detector = \"CWG0002\"\nrequires = [\"privileged-write\"]\nescalate_to = \"high\"\n",
+        );
+        let report = analyze(dir, "low");
+        assert_eq!(severities(&report, "missing-access-control"), vec!["High"]);
+    }
+}
+
+mod crate_name {
+    use super::*;
+    use std::process::{Command, Output};
+
+    const SOURCE: &str = r#"
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    let supply = SUPPLY.load(deps.storage).unwrap();
+    Ok(Response::new())
+}
+"#;
+
+    /// Workspace with the contracts `vault` and `dex-pair`
+    fn write_workspace() -> Project {
+        let project = Project::new();
+        for (file, content) in [
+            ("Cargo.toml", "[workspace]\nmembers = [\"contracts/*\"]\n"),
+            (
+                "contracts/vault/Cargo.toml",
+                "[package]\nname = \"vault\"\n",
+            ),
+            ("contracts/vault/src/lib.rs", SOURCE),
+            (
+                "contracts/dex/Cargo.toml",
+                "[package]\nname = \"dex-pair\"\n",
+            ),
+            ("contracts/dex/src/lib.rs", SOURCE),
+        ] {
+            project.write(file, content);
+        }
+        project
+    }
+
+    fn analyze(dir: &Path, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .current_dir(dir)
+            .args([
+                "analyze",
+                "--no-cache",
+                "--quiet",
+                "--no-color",
+                "--detectors",
+                "unsafe-unwrap",
+                "contracts/vault",
+                "contracts/dex",
+            ])
+            .args(args)
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    #[test]
+    fn test_findings_name_their_crate() {
+        let project = write_workspace();
+        let dir = project.path();
+
+        let output = analyze(dir, &["--format", "json"]);
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let mut crates: Vec<&str> = report["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["crate_name"].as_str().unwrap())
+            .collect();
+        crates.sort_unstable();
+        assert_eq!(crates, vec!["dex-pair", "vault"]);
+
+        let output = analyze(dir, &[]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Crate: vault"), "{stdout}");
+        assert!(stdout.contains("Crate: dex-pair"), "{stdout}");
+
+        let output = analyze(dir, &["--format", "summary"]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains("crate vault: critical=0 high=0 medium=1"),
+            "{stdout}"
+        );
+    }
+
+    #[test]
+    fn test_filter_crate() {
+        let project = write_workspace();
+        let dir = project.path();
+
+        // `-` and `_` are interchangeable, as in cargo
+        let output = analyze(dir, &["--compact", "--filter-crate", "dex_pair"]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 1, "{stdout}");
+        assert!(
+            lines[0].starts_with("contracts/dex/src/lib.rs:4:"),
+            "{stdout}"
+        );
+
+        let output = analyze(dir, &["--filter-crate", "staking"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("No crate named `staking`"));
+    }
+}
+
+mod examples {
+    use super::*;
+    use std::process::Command;
+
+    fn unwrap_findings(contract: &Path, extra: &[&str]) -> Vec<serde_json::Value> {
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--quiet", "--format", "json"])
+            .args(["--detectors", "unsafe-unwrap"])
+            .args(extra)
+            .arg(contract)
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        assert!(
+            output.status.code().is_some_and(|c| c <= 1),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        report["findings"].as_array().unwrap().clone()
+    }
+
+    #[test]
+    fn test_include_examples_scans_examples_and_doc_tests_at_informational() {
+        let project = Project::new();
+        let dir = project.path();
+        for (file, content) in [
+            (
+                "src/lib.rs",
+                "/// Instantiate the vault.\n\
+                 ///\n\
+                 /// ```no_run\n\
+                 /// let owner = deps.api.addr_validate(&msg.owner).unwrap();\n\
+                 /// ```\n\
+                 pub fn instantiate() -> Result<(), ()> {\n    Ok(())\n}\n",
+            ),
+            (
+                "examples/deploy.rs",
+                "fn main() {\n    let code_id = upload(&wasm).expect(\"upload\");\n}\n",
+            ),
+        ] {
+            project.write(file, content);
+        }
+
+        let base = ["--severity", "info"];
+        assert!(unwrap_findings(dir, &base).is_empty());
+
+        let findings = unwrap_findings(dir, &["--severity", "info", "--include-examples"]);
+        let mut locations: Vec<(String, u64)> = findings
+            .iter()
+            .map(|f| {
+                assert_eq!(f["severity"], "Informational");
+                let loc = &f["locations"][0];
+                (
+                    loc["file"].as_str().unwrap().replace('\\', "/"),
+                    loc["start_line"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        locations.sort();
+        assert_eq!(locations.len(), 2, "{findings:?}");
+        assert!(locations[0].0.ends_with("examples/deploy.rs"));
+        assert_eq!(locations[0].1, 2);
+        // The doc-test finding points at its line of the doc comment
+        assert!(locations[1].0.ends_with("src/lib.rs"));
+        assert_eq!(locations[1].1, 4);
+
+        // Example findings are Informational, so stricter thresholds hide them
+        assert!(unwrap_findings(dir, &["--include-examples"]).is_empty());
+    }
+}
+
+mod explain {
+    use super::*;
+    use std::process::Command;
+
+    const CONTRACT: &str = r#"
+use cosmwasm_std::{DepsMut, MessageInfo, Response, StdResult, Uint128};
+use cw_storage_plus::Map;
+
+const BALANCES: Map<&str, Uint128> = Map::new("balances");
+
+pub fn withdraw(deps: DepsMut, info: MessageInfo, amount: Uint128) -> StdResult<Response> {
+    let balance = BALANCES.load(deps.storage, info.sender.as_str())?;
+    BALANCES.save(deps.storage, info.sender.as_str(), &(balance - amount))?;
+    Ok(Response::new())
+}
+"#;
+
+    fn analyze(dir: &std::path::Path, args: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--no-color"])
+            .args(args)
+            .arg(dir)
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    #[test]
+    fn test_explain_finding_prints_trace_with_excerpts() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write("src/lib.rs", CONTRACT);
+
+        let output = analyze(dir, &["--format", "json"]);
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let finding = report["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["detector_name"] == "unchecked-subtraction")
+            .unwrap();
+        let kinds: Vec<&str> = finding["trace"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["kind"].as_str().unwrap())
+            .collect();
+        assert_eq!(kinds, vec!["source", "missing_guard", "sink"]);
+        let id = finding["id"].as_str().unwrap();
+
+        let output = analyze(dir, &["--explain-finding", id]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("1. source: `balance` holds a balance-like value"));
+        assert!(stdout.contains("let balance = BALANCES.load(deps.storage"));
+        assert!(stdout.contains("3. sink: `amount` is subtracted from `balance`"));
+
+        let output = analyze(dir, &["--explain-finding", "00000000"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("No finding with id `00000000`"));
+    }
+}
+
+mod fetch {
+    use super::*;
+    use std::process::Command;
+
+    const SOURCE: &str = r#"
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    let supply = SUPPLY.load(deps.storage).unwrap();
+    Ok(Response::new())
+}
+"#;
+
+    /// `dir/vault-0.1.0.crate` in `project`: a gzipped tarball wrapping the
+    /// crate in `vault-0.1.0/`, like crates.io packages
+    fn write_package(project: &Project, dir: &str) -> PathBuf {
+        project.write(
+            &format!("{dir}/vault-0.1.0/Cargo.toml"),
+            "[package]\nname = \"vault\"\nversion = \"0.1.0\"\n",
+        );
+        project.write(&format!("{dir}/vault-0.1.0/src/contract.rs"), SOURCE);
+        let dir = project.path().join(dir);
+        let archive = dir.join("vault-0.1.0.crate");
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(&dir)
+            .arg("vault-0.1.0")
+            .status()
+            .unwrap();
+        assert!(status.success());
+        archive
+    }
+
+    fn analyze(args: &[&str], env: Option<(&str, &str)>) -> std::process::Output {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"));
+        command
+            .args([
+                "analyze",
+                "--quiet",
+                "--compact",
+                "--detectors",
+                "unsafe-unwrap",
+            ])
+            .args(args)
+            .env("NO_COLOR", "1");
+        if let Some((key, value)) = env {
+            command.env(key, value);
+        }
+        command.output().expect("failed to run cosmwasm-guard")
+    }
+
+    #[test]
+    fn test_analyze_from_archive() {
+        let project = Project::new();
+        let archive = write_package(&project, ".");
+
+        let output = analyze(&["--from-archive", archive.to_str().unwrap()], None);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("src/contract.rs:4:"), "{stdout}");
+
+        let output = analyze(&["--from-archive", "missing.tar.gz"], None);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Archive not found"));
+    }
+
+    #[test]
+    fn test_analyze_from_crates_io_mirror() {
+        let project = Project::new();
+        write_package(&project, "mirror/vault");
+        let mirror = project.path().join("mirror");
+        let url = format!("file://{}", mirror.display());
+        let mirror = Some(("COSMWASM_GUARD_CRATES_URL", url.as_str()));
+
+        let output = analyze(&["--from-crates-io", "vault@0.1.0"], mirror);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("src/contract.rs:4:"), "{stdout}");
+
+        let output = analyze(&["--from-crates-io", "vault"], mirror);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Expected NAME@VERSION"));
+    }
+}
+
+mod files_from {
+    use super::*;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    fn write_crate(project: &Project, name: &str, source: &str) {
+        project.write(
+            &format!("{name}/Cargo.toml"),
+            "[package]\nname = \"c\"\nversion = \"0.1.0\"\n",
+        );
+        project.write(&format!("{name}/src/lib.rs"), source);
+    }
+
+    fn analyze_json(args: &[&str], stdin: Option<&str>) -> serde_json::Value {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--quiet", "--format", "json"])
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to run cosmwasm-guard");
+        if let Some(input) = stdin {
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(input.as_bytes())
+                .unwrap();
+        }
+        let output = child.wait_with_output().unwrap();
+        serde_json::from_slice(&output.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_multiple_paths_and_files_from_merge_into_one_report() {
+        let project = Project::new();
+        let dir = project.path();
+        let vulnerable = dir.join("vulnerable");
+        let safe = dir.join("safe");
+        write_crate(
+            &project,
+            "vulnerable",
+            include_str!("fixtures/vulnerable_contract.rs"),
+        );
+        write_crate(&project, "safe", include_str!("fixtures/safe_contract.rs"));
+        let vulnerable_path = vulnerable.to_str().unwrap();
+        let safe_path = safe.to_str().unwrap();
+
+        let single = analyze_json(&[vulnerable_path], None);
+        let merged = analyze_json(&[vulnerable_path, safe_path], None);
+        assert_eq!(merged["files_analyzed"].as_array().unwrap().len(), 2);
+        assert_eq!(merged["total_findings"], single["total_findings"]);
+
+        // Listed files are analyzed within their crates; deleted and non-Rust
+        // entries are skipped
+        let list = format!(
+            "{}/src/lib.rs\n{}/src/gone.rs\n{}/Cargo.toml\n",
+            vulnerable_path, safe_path, safe_path
+        );
+        let listed = analyze_json(&["--files-from", "-"], Some(&list));
+        assert_eq!(listed["total_findings"], single["total_findings"]);
+        assert_eq!(listed["files_analyzed"].as_array().unwrap().len(), 1);
+
+        let empty = analyze_json(&["--files-from", "-"], Some(""));
+        assert_eq!(empty["total_findings"], 0);
+    }
+}
+
+mod fix {
+    use super::*;
+    use std::process::Command;
+
+    fn guard(args: &[&str], dir: &Path) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(args)
+            .arg(dir.join("src"))
+            .arg("--config")
+            .arg(dir.join(".cosmwasm-guard.toml"))
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    const CONTRACT: &str = r#"
+fn load_config(deps: Deps) -> StdResult<Config> {
+    let config = CONFIG.load(deps.storage).unwrap();
+    Ok(config)
+}
+
+fn save(deps: DepsMut, config: &Config) {
+    let _ = CONFIG.save(deps.storage, config);
+}
+"#;
+
+    #[test]
+    fn test_fix_applies_suggested_edits() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write("src/lib.rs", CONTRACT);
+        let lib = dir.join("src/lib.rs");
+
+        let dry_run = guard(&["fix", "--dry-run"], dir);
+        assert!(
+            dry_run.status.success(),
+            "{}",
+            String::from_utf8_lossy(&dry_run.stderr)
+        );
+        assert!(String::from_utf8_lossy(&dry_run.stdout).contains("Would apply 2 fixes to 1 files"));
+        assert_eq!(std::fs::read_to_string(&lib).unwrap(), CONTRACT);
+
+        let fix = guard(&["fix"], dir);
+        assert!(fix.status.success());
+        let fixed = std::fs::read_to_string(&lib).unwrap();
+        assert!(fixed.contains("let config = CONFIG.load(deps.storage)?;"));
+        assert!(fixed.contains("let _ = CONFIG.save(deps.storage, config).ok();"));
+        // Nothing staged is left behind
+        assert_eq!(std::fs::read_dir(dir.join("src")).unwrap().count(), 1);
+
+        // Only one detector's fixes
+        std::fs::write(&lib, CONTRACT).unwrap();
+        let only = guard(&["fix", "--detectors", "unsafe-unwrap"], dir);
+        assert!(only.status.success());
+        let fixed = std::fs::read_to_string(&lib).unwrap();
+        assert!(fixed.contains("CONFIG.load(deps.storage)?;"));
+        assert!(fixed.contains("let _ = CONFIG.save(deps.storage, config);"));
+    }
+
+    #[test]
+    fn test_fix_rustfmt_formats_changed_files() {
+        if Command::new("rustfmt").arg("--version").output().is_err() {
+            return;
+        }
+        let project = Project::new();
+        let dir = project.path();
+        let contract = CONTRACT.replace("Ok(config)", "Ok(  config  )");
+        project.write("src/lib.rs", &contract);
+        let lib = dir.join("src/lib.rs");
+
+        let fix = guard(&["fix", "--rustfmt", "--detectors", "unsafe-unwrap"], dir);
+        assert!(
+            fix.status.success(),
+            "{}",
+            String::from_utf8_lossy(&fix.stderr)
+        );
+        let fixed = std::fs::read_to_string(&lib).unwrap();
+        assert!(fixed.contains("let config = CONFIG.load(deps.storage)?;"));
+        assert!(fixed.contains("    Ok(config)\n"));
+    }
+
+    #[test]
+    fn test_fix_rustfmt_uses_the_crate_edition() {
+        if Command::new("rustfmt").arg("--version").output().is_err() {
+            return;
+        }
+        let project = Project::new();
+        let dir = project.path();
+        project.write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"vault\"]\n[workspace.package]\nedition = \"2024\"\n",
+        );
+        project.write(
+            "vault/Cargo.toml",
+            "[package]\nname = \"vault\"\nedition.workspace = true\n",
+        );
+        project.write(
+            "vault/src/lib.rs",
+            format!("use x::{{A10, A9}};\n{CONTRACT}"),
+        );
+
+        let fix = guard(
+            &["fix", "--rustfmt", "--detectors", "unsafe-unwrap"],
+            &dir.join("vault"),
+        );
+        assert!(
+            fix.status.success(),
+            "{}",
+            String::from_utf8_lossy(&fix.stderr)
+        );
+        // The 2024 style edition sorts imports by version
+        let fixed = std::fs::read_to_string(dir.join("vault/src/lib.rs")).unwrap();
+        assert!(fixed.starts_with("use x::{A9, A10};\n"), "{fixed}");
+    }
+}
+
+mod function_scope {
+    use super::*;
+    use std::process::Command;
+
+    const SOURCE: &str = r#"
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Transfer { amount } => execute_transfer(deps, amount),
+        ExecuteMsg::Burn { amount } => execute_burn(deps, amount),
+    }
+}
+
+fn execute_transfer(deps: DepsMut, amount: Uint128) -> StdResult<Response> {
+    let balance = load_balance(deps.storage);
+    Ok(Response::new())
+}
+
+fn load_balance(storage: &dyn Storage) -> Uint128 {
+    BALANCE.load(storage).unwrap()
+}
+
+fn execute_burn(deps: DepsMut, amount: Uint128) -> StdResult<Response> {
+    let supply = SUPPLY.load(deps.storage).unwrap();
+    Ok(Response::new())
+}
+"#;
+
+    fn analyze(dir: &std::path::Path, function: &str) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args([
+                "analyze",
+                "--no-cache",
+                "--quiet",
+                "--compact",
+                "--detectors",
+                "unsafe-unwrap",
+                "--function",
+                function,
+            ])
+            .arg(dir)
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    #[test]
+    fn test_function_scope_includes_callees_only() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write("src/lib.rs", SOURCE);
+
+        let output = analyze(dir, "execute_transfer");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 1, "{stdout}");
+        assert!(lines[0].contains("lib.rs:16:"), "{stdout}");
+
+        let output = analyze(dir, "no_such_function");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr)
+            .contains("No function named `no_such_function`"));
+    }
+
+    #[test]
+    fn test_only_reachable_hides_dead_code() {
+        let project = Project::new();
+        let dir = project.path();
+        let source = format!(
+            "{SOURCE}\nfn test_helper() -> Uint128 {{\n    BALANCE.load(&storage).unwrap()\n}}\n"
+        );
+        project.write("src/lib.rs", source);
+
+        let run = |extra: &[&str]| {
+            let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+                .args(["analyze", "--no-cache", "--quiet", "--format", "json"])
+                .args(["--detectors", "unsafe-unwrap"])
+                .args(extra)
+                .arg(dir)
+                .output()
+                .expect("failed to run cosmwasm-guard");
+            let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+            report["findings"].as_array().unwrap().clone()
+        };
+
+        let all = run(&[]);
+        assert_eq!(all.len(), 3);
+        assert!(all
+            .iter()
+            .any(|f| f["reachable_from"] == serde_json::json!([])));
+        assert!(all
+            .iter()
+            .any(|f| f["reachable_from"] == serde_json::json!(["execute"])));
+
+        let reachable = run(&["--only-reachable"]);
+        assert_eq!(reachable.len(), 2);
+    }
+}
+
+mod gen_repro {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_gen_repro_writes_multi_test_cases() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write(
+            "Cargo.toml",
+            "[package]\nname = \"my-vault\"\nversion = \"0.1.0\"\n",
+        );
+        project.write(
+            "src/contract.rs",
+            include_str!("fixtures/vulnerable_contract.rs"),
+        );
+
+        let out = dir.join("guard_repro");
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--quiet", "--gen-repro"])
+            .arg(&out)
+            .arg(dir)
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        assert!(output.status.code().is_some());
+
+        let source = std::fs::read_to_string(out.join("missing_access_control_1.rs")).unwrap();
+        assert!(source.contains("use cw_multi_test::{App, Contract, ContractWrapper, Executor};"));
+        assert!(source.contains("my_vault::contract::execute,"));
+        assert!(source.contains("fn withdraw_rejects_random_sender()"));
+        assert!(source.contains("my_vault::contract::ExecuteMsg::Withdraw {};"));
+    }
+}
+
+mod hook {
+    use super::*;
+    use std::process::{Command, Output};
+
+    fn guard(dir: &Path, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .current_dir(dir)
+            .args(args)
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .status()
+            .expect("failed to run git");
+        assert!(status.success());
+    }
+
+    #[test]
+    fn test_hook_run_reports_only_staged_files() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write("contract/Cargo.toml", "[package]\nname = \"contract\"\n");
+        project.write(
+            "contract/src/lib.rs",
+            format!(
+                "mod helpers;\n{}",
+                include_str!("fixtures/vulnerable_contract.rs")
+            ),
+        );
+        project.write(
+            "contract/src/helpers.rs",
+            "pub fn double(x: u64) -> u64 { x.saturating_mul(2) }\n",
+        );
+        git(dir, &["init", "-q"]);
+
+        // Nothing staged: nothing to check
+        let output = guard(dir, &["hook", "run"]);
+        assert_eq!(output.status.code(), Some(0));
+
+        git(dir, &["add", "contract/src/helpers.rs"]);
+        let output = guard(dir, &["hook", "run", "--no-color"]);
+        assert_eq!(
+            output.status.code(),
+            Some(0),
+            "{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+
+        git(dir, &["add", "contract/src/lib.rs"]);
+        let output = guard(dir, &["hook", "run", "--no-color"]);
+        assert_eq!(output.status.code(), Some(1));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("contract/src/lib.rs"), "{stdout}");
+        assert!(!stdout.contains("helpers.rs"));
+        assert!(dir.join("contract/.cosmwasm-guard-cache").is_dir());
+    }
+
+    #[test]
+    fn test_hook_install_writes_script_and_yaml() {
+        let project = Project::new();
+        let dir = project.path();
+        git(dir, &["init", "-q"]);
+
+        let output = guard(dir, &["hook", "install"]);
+        assert!(output.status.success());
+        let script = std::fs::read_to_string(dir.join(".git/hooks/pre-commit")).unwrap();
+        assert!(script.contains("cosmwasm-guard hook run"));
+
+        // Refuses to clobber an existing hook without --force
+        let output = guard(dir, &["hook", "install"]);
+        assert!(!output.status.success());
+        assert!(guard(dir, &["hook", "install", "--force"]).status.success());
+
+        let output = guard(dir, &["hook", "install", "--pre-commit-yaml"]);
+        assert!(output.status.success());
+        let yaml = std::fs::read_to_string(dir.join(".pre-commit-hooks.yaml")).unwrap();
+        assert!(yaml.contains("id: cosmwasm-guard"));
+        assert!(yaml.contains("entry: cosmwasm-guard hook run"));
+    }
+}
+
+mod html_report {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_html_report_embeds_findings_sections_and_sources() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+        let run = |config: &str| {
+            project.write(".cosmwasm-guard.toml", config);
+            let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+                .args(["analyze", "--no-cache", "--quiet", "--format", "html"])
+                .arg(dir.join("src"))
+                .arg("--config")
+                .arg(dir.join(".cosmwasm-guard.toml"))
+                .output()
+                .expect("failed to run cosmwasm-guard");
+            // Findings fail the run whatever the format
+            assert_eq!(output.status.code(), Some(1));
+            String::from_utf8(output.stdout).unwrap()
+        };
+
+        let html = run("");
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.trim_end().ends_with("</html>"));
+        for id in ["filter-severity", "filter-detector", "filter-file"] {
+            assert!(html.contains(&format!("<select id=\"{id}\">")));
+        }
+        assert!(html.contains("data-detector=\"missing-access-control\""));
+        // The unguarded config update is flagged in the permission matrix
+        assert!(html.contains("<tr class=\"flagged\"><td>ExecuteMsg::UpdateConfig</td>"));
+        assert!(html.contains("<th>Cyclomatic complexity</th>"));
+        // Findings link to marked, highlighted source lines
+        assert!(html.contains("<a class=\"loc\" href=\"#src-0-"));
+        assert!(html.contains("<tr id=\"src-0-"));
+        assert!(html.contains("class=\"marked\""));
+        assert!(html.contains("<span class=\"k\">pub</span>"));
+
+        // Disabled snippets keep the source out of the report
+        let html = run("[snippets]\nmax_lines = 0\n");
+        assert!(html.contains("No source embedded."));
+        assert!(!html.contains("href=\"#src-"));
+    }
+}
+
+mod include_dep {
+    use super::*;
+    use std::process::Command;
+
+    fn unvalidated_addr_titles(contract: &Path, extra: &[&str]) -> Vec<String> {
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--format", "json"])
+            .args(["--detectors", "missing-addr-validate", "--config"])
+            .arg(contract.join(".cosmwasm-guard.toml"))
+            .args(extra)
+            .arg(contract)
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        assert!(
+            output.status.code().is_some_and(|c| c <= 1),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        report["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["title"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_include_dep_loads_message_enums_from_types_package() {
+        let project = Project::new();
+        let dir = project.path();
+        for (file, content) in [
+            (
+                "contracts/vault/Cargo.toml",
+                "[package]\nname = \"vault\"\n\n[dependencies]\n\
+                 vault-types = { path = \"../../packages/vault-types\" }\n",
+            ),
+            (
+                "contracts/vault/src/lib.rs",
+                r#"
+use vault_types::HookMsg;
+
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: Binary)
+    -> Result<Response, ContractError> {
+    match from_json(&msg)? {
+        HookMsg::SetRecipient { recipient } => {
+            RECIPIENT.save(deps.storage, &recipient)?;
+            Ok(Response::new())
+        }
+    }
+}
+"#,
+            ),
+            (
+                "packages/vault-types/Cargo.toml",
+                "[package]\nname = \"vault-types\"\n",
+            ),
+            (
+                "packages/vault-types/src/lib.rs",
+                r#"
+#[cw_serde]
+pub enum HookMsg {
+    SetRecipient { recipient: String },
+}
+"#,
+            ),
+        ] {
+            project.write(file, content);
+        }
+        let contract = dir.join("contracts/vault");
+
+        assert!(unvalidated_addr_titles(&contract, &[]).is_empty());
+
+        let titles = unvalidated_addr_titles(&contract, &["--include-dep", "vault-types"]);
+        assert_eq!(
+            titles,
+            ["Unvalidated address: `recipient` in HookMsg::SetRecipient"]
+        );
+
+        let by_path = dir.join("packages/vault-types");
+        let titles =
+            unvalidated_addr_titles(&contract, &["--include-dep", by_path.to_str().unwrap()]);
+        assert_eq!(titles.len(), 1);
+    }
+}
+
+mod inspect {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_inspect_json_reports_permission_matrix() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write(
+            "contract.rs",
+            r#"
+        pub const OWNER: Item<Addr> = Item::new("owner");
+        pub const CONFIG: Item<Config> = Item::new("config");
+
+        #[cw_serde]
+        pub enum ExecuteMsg {
+            UpdateConfig { config: Config },
+            TransferOwnership { owner: String },
+        }
+
+        #[entry_point]
+        pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+            -> StdResult<Response> {
+            match msg {
+                ExecuteMsg::UpdateConfig { config } => {
+                    CONFIG.save(deps.storage, &config)?;
+                    Ok(Response::new())
+                }
+                ExecuteMsg::TransferOwnership { owner } => {
+                    if info.sender != OWNER.load(deps.storage)? {
+                        return Err(StdError::generic_err("unauthorized"));
+                    }
+                    OWNER.save(deps.storage, &deps.api.addr_validate(&owner)?)?;
+                    Ok(Response::new())
+                }
+            }
+        }
+        "#,
+        );
+
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["inspect", "--format", "json"])
+            .arg(dir.join("contract.rs"))
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let model: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(model["roles"][0]["name"], "owner");
+        let permissions = model["permissions"].as_array().unwrap();
+        assert_eq!(permissions.len(), 2);
+        assert_eq!(permissions[0]["variant"], "UpdateConfig");
+        assert_eq!(permissions[0]["roles"], serde_json::json!([]));
+        assert_eq!(
+            permissions[0]["privileged_writes"],
+            serde_json::json!(["CONFIG"])
+        );
+        assert_eq!(permissions[1]["roles"], serde_json::json!(["owner"]));
+    }
+
+    #[test]
+    fn test_inspect_storage_lists_hot_spots_first() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write(
+            "contract.rs",
+            r#"
+        pub const CONFIG: Item<Config> = Item::new("config");
+        pub const CLAIMS: Map<&Addr, Uint128> = Map::new("claims");
+
+        pub enum ExecuteMsg { Claim {}, Pause {} }
+
+        #[entry_point]
+        pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+            -> StdResult<Response> {
+            match msg {
+                ExecuteMsg::Claim {} => claim(deps, info),
+                ExecuteMsg::Pause {} => {
+                    let mut config = CONFIG.load(deps.storage)?;
+                    config.paused = true;
+                    CONFIG.save(deps.storage, &config)?;
+                    Ok(Response::new())
+                }
+            }
+        }
+
+        fn claim(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+            let config = CONFIG.load(deps.storage)?;
+            CLAIMS.save(deps.storage, &info.sender, &Uint128::zero())?;
+            Ok(Response::new())
+        }
+        "#,
+        );
+
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["inspect", "--storage", "--no-color"])
+            .arg(dir.join("contract.rs"))
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let rows: Vec<Vec<&str>> = stdout
+            .lines()
+            .map(|l| l.split_whitespace().collect::<Vec<_>>())
+            .filter(|cols| matches!(cols.first(), Some(&"CONFIG" | &"CLAIMS")))
+            .collect();
+        // Item, handlers, loads, stores, paths
+        assert_eq!(rows[0], ["CONFIG", "2", "2", "1", "2"], "{stdout}");
+        assert_eq!(rows[1], ["CLAIMS", "1", "0", "1", "1"], "{stdout}");
+        assert!(stdout.contains("read by     ExecuteMsg::Claim, ExecuteMsg::Pause"));
+    }
+}
+
+mod only_new_code {
+    use super::*;
+    use std::process::{Command, Output};
+
+    const LEGACY: &str = r#"
+pub fn legacy(deps: Deps) -> StdResult<Uint128> {
+    let supply = SUPPLY.load(deps.storage).unwrap();
+    Ok(supply)
+}
+"#;
+
+    const ADDED: &str = r#"
+pub fn added(deps: Deps) -> StdResult<Uint128> {
+    let cap = CAP.load(deps.storage).unwrap();
+    Ok(cap)
+}
+"#;
+
+    fn guard(dir: &Path, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .current_dir(dir)
+            .args([
+                "analyze",
+                "--quiet",
+                "--no-cache",
+                "--compact",
+                "--detectors",
+                "unsafe-unwrap",
+            ])
+            .args(args)
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .expect("failed to run git");
+        assert!(output.status.success());
+    }
+
+    fn reported_lines(output: &Output) -> Vec<String> {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|l| l.split(": ").next())
+            .filter(|l| l.contains(".rs:"))
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_only_new_code_keeps_findings_on_changed_lines() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write("contract/src/lib.rs", LEGACY);
+        git(dir, &["init", "-q"]);
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "legacy"]);
+        git(dir, &["tag", "legacy"]);
+        project.write("contract/src/lib.rs", format!("{LEGACY}{ADDED}"));
+        git(dir, &["commit", "-q", "-am", "added"]);
+        // Uncommitted and untracked code counts as new too
+        project.write(
+            "contract/src/wip.rs",
+            "pub fn wip() {\n    parse().unwrap();\n}\n",
+        );
+
+        let all = reported_lines(&guard(dir, &["contract"]));
+        assert_eq!(all.len(), 3, "{all:?}");
+
+        let output = guard(dir, &["--only-new-code=legacy", "contract"]);
+        assert_eq!(
+            reported_lines(&output),
+            ["contract/src/lib.rs:8:38", "contract/src/wip.rs:2:13"],
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        // Without a revision: changes not committed yet, or since `new_code_since`
+        let output = guard(dir, &["--only-new-code", "contract"]);
+        assert_eq!(reported_lines(&output), ["contract/src/wip.rs:2:13"]);
+        project.write("guard.toml", "[global]\nnew_code_since = \"legacy\"\n");
+        let output = guard(
+            dir,
+            &["--config", "guard.toml", "--only-new-code", "contract"],
+        );
+        assert_eq!(reported_lines(&output).len(), 2);
+
+        // A range compares two commits, leaving the working tree out
+        let output = guard(dir, &["--only-new-code=legacy..HEAD", "contract"]);
+        assert_eq!(reported_lines(&output), ["contract/src/lib.rs:8:38"]);
+
+        // Revisions are never passed to git as options
+        project.write(
+            "guard.toml",
+            "[global]\nnew_code_since = \"--output=out\"\n",
+        );
+        let output = guard(
+            dir,
+            &["--config", "guard.toml", "--only-new-code", "contract"],
+        );
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid revision `--output=out`"));
+        assert!(!dir.join("out").exists());
+    }
+}
+
+mod packs {
+    use super::*;
+    use std::process::Command;
+
+    use cosmwasm_guard::packs;
+
+    fn detectors_reported(dir: &Path, args: &[&str]) -> Vec<String> {
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--format", "json", "--config"])
+            .arg(dir.join(".cosmwasm-guard.toml"))
+            .args(args)
+            .arg(dir)
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        assert!(
+            output.status.success() || output.status.code() == Some(1),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        report["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["detector_name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_packs_select_their_detectors() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+        let core = packs::resolve(&["core".to_string()]).unwrap()[0];
+
+        let all = detectors_reported(dir, &[]);
+        assert!(all.iter().any(|d| !core.includes(d)), "{all:?}");
+
+        let found = detectors_reported(dir, &["--packs", "core"]);
+        assert!(!found.is_empty());
+        assert!(found.iter().all(|d| core.includes(d)), "{found:?}");
+
+        // The config selects packs too; --packs replaces them
+        project.write(".cosmwasm-guard.toml", "[global]\npacks = [\"gas\"]\n");
+        let gas = packs::resolve(&["gas".to_string()]).unwrap()[0];
+        let found = detectors_reported(dir, &[]);
+        assert!(found.iter().all(|d| gas.includes(d)), "{found:?}");
+        let found = detectors_reported(dir, &["--packs", "core"]);
+        assert!(found.iter().all(|d| core.includes(d)), "{found:?}");
+    }
+
+    #[test]
+    fn test_unknown_pack_fails() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--packs", "defi,lending"])
+            .arg(dir)
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Unknown rule pack `lending`"), "{stderr}");
+    }
+}
+
+mod parse_errors {
+    use super::*;
+    use std::process::Command;
+
+    fn scratch_crate() -> Project {
+        let project = Project::vulnerable();
+        project.write("src/broken.rs", "fn broken( {\n");
+        project
+    }
+
+    fn analyze(dir: &Path, extra: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .arg("analyze")
+            .arg(dir)
+            .args(["--no-cache", "--format", "json"])
+            .args(extra)
+            .output()
+            .expect("failed to run analyze")
+    }
+
+    #[test]
+    fn test_unparseable_file_is_skipped_and_reported() {
+        let project = scratch_crate();
+        let dir = project.path();
+        let output = analyze(dir, &[]);
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+        let errors = report["analysis_errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0]["file"].as_str().unwrap().ends_with("broken.rs"));
+        assert_eq!(errors[0]["line"], 1);
+        // The rest of the crate is still analyzed
+        assert_eq!(report["files_analyzed"].as_array().unwrap().len(), 1);
+        assert!(report["total_findings"].as_u64().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_strict_parse_fails_hard() {
+        let project = scratch_crate();
+        let dir = project.path();
+        let output = analyze(dir, &["--strict-parse"]);
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("broken.rs:1:"), "{stderr}");
+    }
+}
+
+mod report {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_report_by_owner_groups_findings_per_team() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write(
+            "src/vault/contract.rs",
+            include_str!("fixtures/vulnerable_contract.rs"),
+        );
+        project.write(
+            ".cosmwasm-guard.toml",
+            r#"
+[[owners]]
+pattern = "src/vault/"
+team = "@org/vault"
+"#,
+        );
+        let run = |args: &[&str]| {
+            Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+                .args(args)
+                .arg(dir.join("src"))
+                .arg("--config")
+                .arg(dir.join(".cosmwasm-guard.toml"))
+                .output()
+                .expect("failed to run cosmwasm-guard")
+        };
+
+        let analyze = run(&["analyze", "--no-cache", "--format", "json"]);
+        let report: serde_json::Value = serde_json::from_slice(&analyze.stdout).unwrap();
+        let findings = report["findings"].as_array().unwrap();
+        assert!(!findings.is_empty());
+        assert!(findings.iter().all(|f| f["owner"] == "@org/vault"));
+
+        let by_owner = run(&["report", "--by-owner", "--format", "json"]);
+        assert!(
+            by_owner.status.success(),
+            "{}",
+            String::from_utf8_lossy(&by_owner.stderr)
+        );
+        let summary: serde_json::Value = serde_json::from_slice(&by_owner.stdout).unwrap();
+        let owners = summary["findings_by_owner"].as_object().unwrap();
+        assert_eq!(owners.len(), 1);
+        assert_eq!(owners["@org/vault"]["total"], summary["total_findings"]);
+    }
+
+    #[test]
+    fn test_anchored_owner_rules_match_rewritten_paths() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write(
+            "src/vault/contract.rs",
+            include_str!("fixtures/vulnerable_contract.rs"),
+        );
+        project.write(
+            ".cosmwasm-guard.toml",
+            "[[owners]]\npattern = \"/src/vault/\"\nteam = \"@org/vault\"\n",
+        );
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--format", "json", "--config"])
+            .arg(dir.join(".cosmwasm-guard.toml"))
+            .arg("--path-prefix-strip")
+            .arg(dir)
+            .arg(dir.join("src"))
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let findings = report["findings"].as_array().unwrap();
+        assert!(!findings.is_empty());
+        assert!(findings
+            .iter()
+            .all(|f| f["locations"][0]["file"] == "src/vault/contract.rs"
+                && f["owner"] == "@org/vault"));
+    }
+
+    #[test]
+    fn test_report_by_entry_point_groups_findings_per_variant() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["report", "--by-entry-point", "--format", "json"])
+            .arg(dir)
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        assert!(output.status.success());
+
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let groups = report["findings_by_entry_point"].as_array().unwrap();
+        let update = groups
+            .iter()
+            .find(|g| g["variant"] == "ExecuteMsg::UpdateConfig")
+            .unwrap();
+        assert_eq!(update["entry_point"], "execute");
+        assert!(update["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|f| f["detector_name"] == "unguarded-parameter-update"));
+    }
+
+    #[test]
+    fn test_report_coverage_lists_variants_and_gaps() {
+        let project = Project::new();
+        let dir = project.path();
+        let source = format!(
+            "{}\npub enum QueryMsg {{ Orphan {{}} }}\n",
+            include_str!("fixtures/vulnerable_contract.rs")
+        );
+        project.write("src/lib.rs", source);
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["report", "--coverage", "--format", "json"])
+            .arg(dir)
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let variants = report["variants"].as_array().unwrap();
+        let update = variants
+            .iter()
+            .find(|v| v["variant"] == "ExecuteMsg::UpdateConfig")
+            .unwrap();
+        assert_eq!(update["dispatcher"], "execute");
+        assert!(update["detectors"]["unguarded-parameter-update"].as_u64() >= Some(1));
+        let gaps = report["unresolved_variants"].as_array().unwrap();
+        assert!(gaps.contains(&serde_json::json!("QueryMsg::Orphan")));
+    }
+
+    #[test]
+    fn test_report_pdf_writes_audit_document() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+        let out = dir.join("audit.pdf");
+
+        let run = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["report", "--pdf"])
+            .arg(&out)
+            .arg(dir.join("src"))
+            .arg("--config")
+            .arg(dir.join(".cosmwasm-guard.toml"))
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        assert!(
+            run.status.success(),
+            "{}",
+            String::from_utf8_lossy(&run.stderr)
+        );
+
+        let bytes = std::fs::read(&out).unwrap();
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.matches("/Type /Page ").count() >= 3);
+        assert!(text.contains("(Security Audit Report) Tj"));
+        assert!(text.contains("(Recommendation) Tj"));
+    }
+}
+
+mod rev {
+    use super::*;
+    use std::process::{Command, Output};
+
+    const VULNERABLE: &str = r#"
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    let supply = SUPPLY.load(deps.storage).unwrap();
+    Ok(Response::new())
+}
+"#;
+
+    const FIXED: &str = r#"
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    let supply = SUPPLY.load(deps.storage)?;
+    Ok(Response::new())
+}
+"#;
+
+    fn guard(dir: &Path, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .current_dir(dir)
+            .args([
+                "analyze",
+                "--quiet",
+                "--compact",
+                "--detectors",
+                "unsafe-unwrap",
+            ])
+            .args(args)
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .expect("failed to run git");
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    #[test]
+    fn test_analyze_rev_uses_temporary_worktree() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write("contract/src/lib.rs", VULNERABLE);
+        git(dir, &["init", "-q"]);
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "v1"]);
+        git(dir, &["tag", "v1"]);
+        project.write("contract/src/lib.rs", FIXED);
+        git(dir, &["commit", "-q", "-am", "v2"]);
+        // Uncommitted work the analysis must not touch
+        project.write("contract/src/lib.rs", "// wip\n");
+
+        let output = guard(dir, &["--rev", "v1", "contract"]);
+        assert_eq!(output.status.code(), Some(1));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("contract/src/lib.rs:4:"), "{stdout}");
+
+        let output = guard(dir, &["--rev", "HEAD", "contract"]);
+        assert_eq!(
+            output.status.code(),
+            Some(0),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let output = guard(dir, &["--rev", "no-such-tag", "contract"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown revision `no-such-tag`"));
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("contract/src/lib.rs")).unwrap(),
+            "// wip\n"
+        );
+        assert_eq!(git(dir, &["worktree", "list"]).lines().count(), 1);
+    }
+
+    #[test]
+    fn test_analyze_rev_ids_do_not_depend_on_worktree() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write("contract/src/lib.rs", VULNERABLE);
+        git(dir, &["init", "-q"]);
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "v1"]);
+
+        let ids = || {
+            let output = guard(dir, &["--rev", "HEAD", "--format", "json", "contract"]);
+            let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+            assert!(!stdout.contains("cosmwasm-guard-worktree"), "{stdout}");
+            let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+            report["findings"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|f| f["id"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        let first = ids();
+        assert!(!first.is_empty());
+        assert_eq!(first, ids());
+    }
+}
+
+mod rustc_json {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_rustc_json_emits_one_diagnostic_per_line() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write(
+            "src/lib.rs",
+            "pub fn supply(deps: Deps) -> Uint128 {\n    SUPPLY.load(deps.storage).unwrap()\n}\n",
+        );
+        project.write("src/broken.rs", "pub fn broken( {\n");
+
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--quiet", "--format", "rustc-json"])
+            .args(["--detectors", "unsafe-unwrap"])
+            .arg(dir)
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        assert_eq!(output.status.code(), Some(1));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let diagnostics: Vec<serde_json::Value> = stdout
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(diagnostics.len(), 2, "{stdout}");
+
+        let finding = &diagnostics[0];
+        assert_eq!(finding["$message_type"], "diagnostic");
+        assert_eq!(finding["code"]["code"], "CWG0005");
+        assert_eq!(finding["level"], "warning");
+        let span = &finding["spans"][0];
+        assert!(span["file_name"].as_str().unwrap().ends_with("src/lib.rs"));
+        assert_eq!(span["line_start"], 2);
+        assert_eq!(span["is_primary"], true);
+        assert_eq!(span["column_start"], span["text"][0]["highlight_start"]);
+        assert!(span["text"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains(".unwrap()"));
+        assert_eq!(finding["children"][1]["level"], "help");
+        let rendered = finding["rendered"].as_str().unwrap();
+        assert!(rendered.starts_with("warning[CWG0005]: "), "{rendered}");
+        assert!(rendered.contains("src/lib.rs:2:"), "{rendered}");
+
+        let error = &diagnostics[1];
+        assert_eq!(error["level"], "warning");
+        assert!(error["spans"][0]["file_name"]
+            .as_str()
+            .unwrap()
+            .ends_with("src/broken.rs"));
+    }
+}
+
+mod sarif {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_sarif_run_per_package_has_automation_category() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write("Cargo.toml", "[package]\nname = \"vault\"\n");
+        project.write(
+            "src/lib.rs",
+            include_str!("fixtures/vulnerable_contract.rs"),
+        );
+
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--format", "sarif", "--config"])
+            .arg(dir.join(".cosmwasm-guard.toml"))
+            .arg("--path-prefix-strip")
+            .arg(dir)
+            .arg(dir)
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let runs = sarif["runs"].as_array().unwrap();
+
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0]["automationDetails"]["id"], "cosmwasm-guard/vault/");
+        let results = runs[0]["results"].as_array().unwrap();
+        assert!(!results.is_empty());
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "src/lib.rs"
+        );
+    }
+
+    #[test]
+    fn test_sarif_regions_on_multibyte_lines() {
+        let project = Project::new();
+        let dir = project.path();
+        let source = "#[entry_point]\npub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {\n    let pair = (\"€uro\", SUPPLY.load(deps.storage).unwrap());\n    Ok(Response::new())\n}\n";
+        project.write("src/lib.rs", source);
+
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args([
+                "analyze",
+                "--no-cache",
+                "--format",
+                "sarif",
+                "--detectors",
+                "unsafe-unwrap",
+            ])
+            .arg(dir)
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let run = &sarif["runs"][0];
+        assert_eq!(run["columnKind"], "unicodeCodePoints");
+
+        let result = &run["results"][0];
+        let region = &result["locations"][0]["physicalLocation"]["region"];
+        let offset = region["byteOffset"].as_u64().unwrap() as usize;
+        assert!(source[offset..].starts_with("unwrap"));
+        let line_start = source[..offset].rfind('\n').unwrap() + 1;
+        assert_eq!(
+            region["startColumn"].as_u64().unwrap() as usize,
+            source[line_start..offset].chars().count() + 1
+        );
+
+        let replacement = &result["fixes"][0]["artifactChanges"][0]["replacements"][0];
+        let deleted = &replacement["deletedRegion"];
+        let start = deleted["byteOffset"].as_u64().unwrap() as usize;
+        let end = start + deleted["byteLength"].as_u64().unwrap() as usize;
+        let mut fixed = source.to_string();
+        fixed.replace_range(
+            start..end,
+            replacement["insertedContent"]["text"].as_str().unwrap(),
+        );
+        assert!(fixed.contains("(\"€uro\", SUPPLY.load(deps.storage)?);"));
+    }
+
+    #[test]
+    fn test_sarif_results_carry_severity_level() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write("Cargo.toml", "[package]\nname = \"vault\"\n");
+        project.write(
+            "src/lib.rs",
+            include_str!("fixtures/vulnerable_contract.rs"),
+        );
+        project.write(
+            ".cosmwasm-guard.toml",
+            "[severity_levels]\ncritical = \"P1\"\nhigh = \"P1\"\n",
+        );
+
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--format", "sarif", "--config"])
+            .arg(dir.join(".cosmwasm-guard.toml"))
+            .arg(dir)
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+
+        for result in results {
+            assert_eq!(result["properties"]["crate"], "vault");
+            let expected = match result["level"].as_str() {
+                Some("error") => serde_json::json!("P1"),
+                _ => serde_json::Value::Null,
+            };
+            assert_eq!(result["properties"]["severityLevel"], expected, "{result}");
+        }
+        assert!(results.iter().any(|r| r["level"] == "error"));
+    }
+}
+
+mod scaffold {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_scaffold_fuzz_writes_harness() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write(
+            "Cargo.toml",
+            "[package]\nname = \"my-vault\"\nversion = \"0.1.0\"\n",
+        );
+        project.write(
+            "src/contract.rs",
+            include_str!("fixtures/vulnerable_contract.rs"),
+        );
+
+        let run = || {
+            Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+                .args(["scaffold", "fuzz"])
+                .arg(dir)
+                .output()
+                .expect("failed to run cosmwasm-guard")
+        };
+        let output = run();
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("proptest"));
+
+        let source = std::fs::read_to_string(dir.join("tests/fuzz_messages.rs")).unwrap();
+        assert!(source.contains("use proptest::prelude::*;"));
+        assert!(source.contains("my_vault::contract::execute,"));
+        assert!(source
+            .contains("fn execute_msg() -> impl Strategy<Value = my_vault::contract::ExecuteMsg>"));
+        assert!(source.contains("fn execute_sequences_do_not_panic("));
+
+        // Refuses to overwrite without --force
+        assert!(!run().status.success());
+    }
+}
+
+mod serve {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    /// Send newline-delimited JSON-RPC requests to `serve --stdio` and collect the responses
+    fn rpc(requests: &[serde_json::Value]) -> Vec<serde_json::Value> {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["serve", "--stdio", "--no-cache"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to start serve");
+
+        let mut stdin = child.stdin.take().unwrap();
+        for request in requests {
+            writeln!(stdin, "{request}").unwrap();
+        }
+        drop(stdin);
+
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_serve_stdio_methods() {
+        let source = include_str!("fixtures/vulnerable_contract.rs");
+        let responses = rpc(&[
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "listDetectors"}),
+            serde_json::json!({
+                "jsonrpc": "2.0", "id": 2, "method": "analyzeSource",
+                "params": {"source": source, "detectors": ["missing-addr-validate"]}
+            }),
+            serde_json::json!({
+                "jsonrpc": "2.0", "id": 3, "method": "explain",
+                "params": {"detector": "unsafe-unwrap"}
+            }),
+            serde_json::json!({"jsonrpc": "2.0", "id": 4, "method": "nope"}),
+            // Notifications get no response
+            serde_json::json!({"jsonrpc": "2.0", "method": "listDetectors"}),
+        ]);
+
+        assert_eq!(responses.len(), 4);
+        assert!(responses[0]["result"].as_array().unwrap().len() > 10);
+
+        let findings = responses[1]["result"]["findings"].as_array().unwrap();
+        assert!(!findings.is_empty());
+        assert!(findings
+            .iter()
+            .all(|f| f["detector_name"] == "missing-addr-validate"));
+
+        assert_eq!(responses[2]["result"]["name"], "unsafe-unwrap");
+        assert_eq!(responses[3]["error"]["code"], -32601);
+    }
+
+    #[test]
+    fn test_serve_stdio_reports_parse_errors() {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["serve", "--stdio", "--no-cache"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        writeln!(child.stdin.take().unwrap(), "not json").unwrap();
+        let output = child.wait_with_output().unwrap();
+        let response: serde_json::Value =
+            serde_json::from_str(String::from_utf8(output.stdout).unwrap().trim()).unwrap();
+        assert_eq!(response["error"]["code"], -32700);
+    }
+
+    /// POST `body` to the server at `addr` with extra `headers`, returning the raw response
+    fn post(addr: &str, headers: &str, body: &serde_json::Value) -> String {
+        let mut stream = std::net::TcpStream::connect(addr).unwrap();
+        let body = body.to_string();
+        write!(
+            stream,
+            "POST / HTTP/1.1\r\n{headers}Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        std::io::Read::read_to_string(&mut stream, &mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_serve_http_only_answers_local_requests() {
+        let project = super::Project::vulnerable();
+        let mut child = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["serve", "--listen", "127.0.0.1:0", "--no-cache"])
+            .current_dir(project.path())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut banner = String::new();
+        std::io::BufRead::read_line(
+            &mut std::io::BufReader::new(child.stderr.take().unwrap()),
+            &mut banner,
+        )
+        .unwrap();
+        let addr = banner.trim().rsplit("http://").next().unwrap().to_string();
+        let analyze = |path: &str| {
+            serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "method": "analyze", "params": {"path": path}
+            })
+        };
+
+        let rebound = post(&addr, "Host: attacker.example\r\n", &analyze("."));
+        assert!(rebound.starts_with("HTTP/1.1 403"), "{rebound}");
+        let cross_origin = post(
+            &addr,
+            "Host: localhost\r\nOrigin: https://attacker.example\r\n",
+            &analyze("."),
+        );
+        assert!(cross_origin.starts_with("HTTP/1.1 403"), "{cross_origin}");
+
+        let outside = post(&addr, "Host: 127.0.0.1\r\n", &analyze("/"));
+        assert!(outside.contains("\"code\":-32602"), "{outside}");
+        let local = post(&addr, &format!("Host: {addr}\r\n"), &analyze("src"));
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(local.starts_with("HTTP/1.1 200"), "{local}");
+        assert!(local.contains("\"findings\""), "{local}");
+    }
+}
+
+mod severity {
+    use super::*;
+    use std::process::Command;
+
+    fn analyze(dir: &std::path::Path, severity: &str) -> serde_json::Value {
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--format", "json", "--severity"])
+            .arg(severity)
+            .arg("--config")
+            .arg(dir.join(".cosmwasm-guard.toml"))
+            .arg(dir)
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        serde_json::from_slice(&output.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_detectors_retiered_from_config() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+        project.write(
+            ".cosmwasm-guard.toml",
+            "[global]\n\n[detectors.missing-addr-validate]\nseverity = \"critical\"\n\n\
+             [detectors.CWG0002]\nseverity = \"low\"\n",
+        );
+
+        let report = analyze(dir, "critical");
+        let mut detectors: Vec<&str> = report["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["detector_name"].as_str().unwrap())
+            .collect();
+        detectors.dedup();
+        // missing-access-control is re-tiered to Low, then escalated again
+        // because its handler writes CONFIG
+        assert_eq!(
+            detectors,
+            vec!["missing-access-control", "missing-addr-validate"]
+        );
+        assert_eq!(
+            report["findings_by_severity"]["critical"],
+            report["total_findings"]
+        );
+    }
+}
+
+mod summary {
+    use super::*;
+    use std::process::Command;
+
+    fn analyze(dir: &std::path::Path, format: &str) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--format", format, "--config"])
+            .arg(dir.join(".cosmwasm-guard.toml"))
+            .arg(dir)
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    #[test]
+    fn test_summary_formats_print_counts_only() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+
+        let output = analyze(dir, "summary");
+        assert_eq!(output.status.code(), Some(1));
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 2, "{stdout}");
+        assert!(lines[0].starts_with("critical="));
+        assert!(lines[0].contains(" total="));
+        assert!(lines[1].contains("fail"));
+        assert!(lines[1].ends_with("(exit 1)"));
+
+        let output = analyze(dir, "json-summary");
+        assert_eq!(output.status.code(), Some(1));
+        let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(summary["exit_code"], 1);
+        assert_eq!(summary["files_analyzed"], 1);
+        assert!(summary["total_findings"].as_u64().unwrap() > 0);
+        assert!(summary["findings_by_severity"]["high"].is_u64());
+        assert!(summary["findings_by_severity"]["critical"].is_u64());
+        assert!(summary.get("findings").is_none());
+    }
+}
+
+mod suppress {
+    use super::*;
+    use std::process::Command;
+
+    fn guard(args: &[&str], dir: &Path) -> std::process::Output {
+        let config = dir.join(".cosmwasm-guard.toml");
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(args)
+            .arg(dir.join("src"))
+            .arg("--config")
+            .arg(&config)
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    #[test]
+    fn test_suppress_all_current_baselines_existing_findings() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+
+        let before = guard(&["analyze", "--no-cache", "--quiet"], dir);
+        assert_eq!(before.status.code(), Some(1));
+
+        let suppress = guard(&["suppress", "--all-current"], dir);
+        assert!(
+            suppress.status.success(),
+            "{}",
+            String::from_utf8_lossy(&suppress.stderr)
+        );
+        let baseline = std::fs::read_to_string(dir.join(".cosmwasm-guard-baseline.toml")).unwrap();
+        assert!(baseline.contains("[[finding]]"));
+        assert!(baseline.contains("reason = \"TODO: triage"));
+
+        // Everything currently present is accepted, even in audit mode
+        let after = guard(
+            &["analyze", "--no-cache", "--audit", "--format", "json"],
+            dir,
+        );
+        assert!(after.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&after.stdout).unwrap();
+        assert_eq!(report["total_findings"], 0);
+
+        // A newly introduced finding is still reported
+        project.write(
+            "src/extra.rs",
+            include_str!("fixtures/vulnerable_contract.rs"),
+        );
+        let new = guard(&["analyze", "--no-cache", "--quiet"], dir);
+        assert_eq!(new.status.code(), Some(1));
+    }
+
+    #[test]
+    fn test_suppress_finding_adds_one_entry() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+        let findings = || {
+            let output = guard(&["analyze", "--no-cache", "--format", "json"], dir);
+            let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+            report["findings"].as_array().unwrap().clone()
+        };
+
+        // A mode is required
+        assert!(!guard(&["suppress"], dir).status.success());
+
+        let before = findings();
+        let id = before[0]["id"].as_str().unwrap();
+        let suppress = guard(&["suppress", "--finding", id], dir);
+        assert!(
+            suppress.status.success(),
+            "{}",
+            String::from_utf8_lossy(&suppress.stderr)
+        );
+        let after = findings();
+        assert_eq!(after.len(), before.len() - 1);
+        assert!(after.iter().all(|f| f["id"] != before[0]["id"]));
+
+        let unknown = guard(&["suppress", "--finding", "deadbeef"], dir);
+        assert!(!unknown.status.success());
+        assert!(String::from_utf8_lossy(&unknown.stderr).contains("No finding with id `deadbeef`"));
+    }
+}
+
+mod target_chain {
+    use super::*;
+    use std::process::Command;
+
+    fn run(args: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(args)
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("failed to run cosmwasm-guard")
+    }
+
+    #[test]
+    fn test_target_chain_flags_apis_the_profile_lacks() {
+        let project = Project::new();
+        let dir = project.path();
+        project.write(
+            "src/lib.rs",
+            "pub fn delegate(validator: String, amount: Coin) -> CosmosMsg {\n    \
+             CosmosMsg::Staking(StakingMsg::Delegate { validator, amount })\n}\n",
+        );
+        project.write(
+            "guard.toml",
+            "[chains.appchain]\ncosmwasm = \"1.2\"\ncapabilities = [\"iterator\"]\n\n\
+             [chains.hub]\ncosmwasm = \"1.5\"\ncapabilities = [\"iterator\", \"staking\"]\n",
+        );
+        let config = dir.join("guard.toml");
+
+        let analyze = |chain: &str| {
+            run(&[
+                "analyze",
+                "--no-cache",
+                "--quiet",
+                "--format",
+                "json",
+                "--config",
+                config.to_str().unwrap(),
+                "--detectors",
+                "unsupported-capability",
+                "--target-chain",
+                chain,
+                dir.to_str().unwrap(),
+            ])
+        };
+
+        let report: serde_json::Value =
+            serde_json::from_slice(&analyze("appchain").stdout).unwrap();
+        let findings = report["findings"].as_array().unwrap();
+        assert_eq!(findings.len(), 1, "{findings:?}");
+        assert_eq!(
+            findings[0]["title"],
+            "`CosmosMsg::Staking` needs the `staking` capability, which chain `appchain` lacks"
+        );
+        assert_eq!(findings[0]["locations"][0]["start_line"], 2);
+
+        let report: serde_json::Value = serde_json::from_slice(&analyze("hub").stdout).unwrap();
+        assert!(report["findings"].as_array().unwrap().is_empty());
+
+        let output = analyze("osmosis");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("[chains.osmosis]"));
+    }
+}
+
+mod text_output {
+    use super::*;
+    use std::process::Command;
+
+    fn analyze(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new(env!("CARGO_BIN_EXE_cosmwasm-guard"))
+            .args(["analyze", "--no-cache", "--config"])
+            .arg(dir.join(".cosmwasm-guard.toml"))
+            .args(args)
+            .arg(dir)
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("failed to run cosmwasm-guard");
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_compact_prints_one_line_per_finding() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+        let stdout = analyze(dir, &["--compact", "--theme", "colorblind"]);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert!(!lines.is_empty());
+        for line in &lines {
+            let (location, rest) = line.split_once(": ").unwrap();
+            assert!(location.ends_with(char::is_numeric), "{line}");
+            assert!(
+                ["critical: ", "high: ", "medium: ", "low: ", "info: "]
+                    .iter()
+                    .any(|s| rest.starts_with(s)),
+                "{line}"
+            );
+            assert!(line.ends_with(']'), "{line}");
+        }
+        // NO_COLOR wins over the theme
+        assert!(!stdout.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_width_wraps_prose_and_truncates_snippets() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+        let stdout = analyze(dir, &["--quiet", "--width", "60"]);
+        assert!(!stdout.is_empty());
+        for line in stdout.lines() {
+            // Single words longer than a line may overflow; none here are that long
+            assert!(line.chars().count() <= 60, "{line:?}");
+        }
+
+        let unwrapped = analyze(dir, &["--quiet", "--width", "0"]);
+        assert!(unwrapped.lines().any(|l| l.chars().count() > 60));
+    }
+
+    #[test]
+    fn test_locations_rendered_as_hyperlinks_from_template() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+        let stdout = analyze(
+            dir,
+            &[
+                "--quiet",
+                "--hyperlinks",
+                "always",
+                "--link-template",
+                "https://github.com/org/repo/blob/{rev}/{path}#L{line}",
+                "--path-prefix-strip",
+                dir.to_str().unwrap(),
+            ],
+        );
+        // {rev} is HEAD outside a git checkout, else the checked-out commit
+        assert!(
+            stdout.contains("\x1b]8;;https://github.com/org/repo/blob/"),
+            "{stdout:?}"
+        );
+        assert!(stdout.contains("/src/lib.rs#L"));
+        assert!(stdout.contains("\x1b\\src/lib.rs:"));
+
+        let plain = analyze(dir, &["--quiet", "--hyperlinks", "never"]);
+        assert!(!plain.contains("\x1b]8;;"));
+    }
+
+    #[test]
+    fn test_verbose_explains_confidence_signals() {
+        let project = Project::vulnerable();
+        let dir = project.path();
+        let quiet = analyze(dir, &[]);
+        assert!(!quiet.contains("Confidence:"));
+
+        let stdout = analyze(dir, &["--verbose"]);
+        assert!(stdout.contains("Confidence: High"));
+        assert!(stdout.contains("+ also reported by `"));
+        assert!(stdout.contains("Reachable: from execute"));
+    }
+}
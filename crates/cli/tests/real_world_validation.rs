@@ -15,7 +15,7 @@ struct FixtureResult {
 }
 
 fn analyze_fixture(name: &str, source: &str) -> FixtureResult {
-    let ast = parse_source(source).expect(&format!("Failed to parse {}", name));
+    let ast = parse_source(source).unwrap_or_else(|_| panic!("Failed to parse {}", name));
     let path = PathBuf::from(name);
     let contract = ContractVisitor::extract(path.clone(), ast);
     let ir = IrBuilder::build_contract(&contract);
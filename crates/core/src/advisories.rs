@@ -0,0 +1,104 @@
+//! Knowledge base of published CosmWasm advisories (`CWA-2024-002`) and chain
+//! incident writeups, mapped to the detectors that catch their root cause.
+//! The mapping lives in `data/advisories.toml` and is bundled into the binary;
+//! findings link to the entries of their detector.
+
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::finding::{AdvisoryLink, Finding};
+
+/// Whether an entry is a security advisory or a writeup of an exploit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AdvisoryKind {
+    Advisory,
+    Incident,
+}
+
+impl std::fmt::Display for AdvisoryKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            AdvisoryKind::Advisory => "advisory",
+            AdvisoryKind::Incident => "incident",
+        };
+        f.pad(label)
+    }
+}
+
+/// One entry of the knowledge base
+#[derive(Debug, Deserialize)]
+pub struct Advisory {
+    pub id: String,
+    pub kind: AdvisoryKind,
+    pub title: String,
+    pub url: String,
+    /// Names of the detectors that catch the root cause
+    pub detectors: Vec<String>,
+}
+
+impl Advisory {
+    pub fn link(&self) -> AdvisoryLink {
+        AdvisoryLink {
+            id: self.id.clone(),
+            url: self.url.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct KnowledgeBase {
+    advisory: Vec<Advisory>,
+}
+
+/// Every entry, in the order of `data/advisories.toml`
+pub fn all() -> &'static [Advisory] {
+    static ADVISORIES: OnceLock<Vec<Advisory>> = OnceLock::new();
+    ADVISORIES.get_or_init(|| {
+        toml::from_str::<KnowledgeBase>(include_str!("../data/advisories.toml"))
+            .expect("bundled advisories.toml is valid")
+            .advisory
+    })
+}
+
+/// Entries whose root cause the detector `name` catches
+pub fn for_detector(name: &str) -> impl Iterator<Item = &'static Advisory> + '_ {
+    all()
+        .iter()
+        .filter(move |a| a.detectors.iter().any(|d| d == name))
+}
+
+/// Link each finding to the advisories about its detector's vulnerability class
+pub fn annotate(findings: &mut [Finding]) {
+    for finding in findings {
+        finding.advisories = for_detector(&finding.detector_name)
+            .map(Advisory::link)
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::CODES;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_knowledge_base_names_known_detectors() {
+        let ids: HashSet<&str> = all().iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids.len(), all().len());
+        for advisory in all() {
+            assert!(advisory.url.starts_with("https://"), "{}", advisory.id);
+            assert!(!advisory.detectors.is_empty(), "{}", advisory.id);
+            for name in &advisory.detectors {
+                assert!(
+                    CODES.iter().any(|(_, n)| n == name),
+                    "{} names unknown detector {name}",
+                    advisory.id
+                );
+            }
+        }
+        assert!(for_detector("arithmetic-overflow").any(|a| a.id == "CWA-2024-002"));
+    }
+}
@@ -1,15 +1,22 @@
+use std::collections::HashSet;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
-/// Source location in a file
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use super::utils;
+use super::visitor::ContractVisitor;
+
+/// Source location in a file. Lines are 1-based, columns count characters
+/// from 0, and the byte offsets are into the file's UTF-8 text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SourceSpan {
     pub file: PathBuf,
     pub start_line: usize,
     pub end_line: usize,
     pub start_col: usize,
     pub end_col: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
 }
 
 /// Parameter info for functions/entry points
@@ -63,6 +70,8 @@ pub struct FieldInfo {
 pub struct MessageVariant {
     pub name: String,
     pub fields: Vec<FieldInfo>,
+    /// Response type declared with cosmwasm-schema's `#[returns(T)]`
+    pub returns: Option<String>,
 }
 
 /// A message enum (ExecuteMsg, QueryMsg, etc.)
@@ -80,6 +89,10 @@ pub enum StorageType {
     Item,
     Map,
     IndexedMap,
+    /// `SnapshotItem`, whose past values are kept by height
+    SnapshotItem,
+    /// `SnapshotMap`, whose past values are kept by height
+    SnapshotMap,
 }
 
 /// A state storage declaration
@@ -93,6 +106,60 @@ pub struct StateItem {
     pub span: SourceSpan,
 }
 
+/// A field of a struct definition. Tuple struct fields are named `_0`, `_1`, ...
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructField {
+    pub name: String,
+    pub type_name: String,
+    /// `#[serde(...)]` options on the field, e.g. `skip`, `default`
+    pub serde: Vec<String>,
+    pub span: SourceSpan,
+}
+
+/// A struct definition (stored value types, message payloads, responses)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructInfo {
+    pub name: String,
+    pub fields: Vec<StructField>,
+    /// Derived traits by last path segment, e.g. `Serialize`. `#[cw_serde]`
+    /// contributes the derives it expands to.
+    pub derives: Vec<String>,
+    /// `#[serde(...)]` options on the struct, e.g. `deny_unknown_fields`
+    pub serde: Vec<String>,
+    pub span: SourceSpan,
+}
+
+impl StructInfo {
+    pub fn field(&self, name: &str) -> Option<&StructField> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    pub fn derives(&self, name: &str) -> bool {
+        self.derives.iter().any(|d| d == name)
+    }
+}
+
+/// Check a helper function is trusted to enforce, declared with
+/// `#[cosmwasm_guard::assume(sender)]` or configured under `[auth]`/`[guards]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrustedCheck {
+    /// Authorizes `info.sender`, e.g. an in-house `assert_operator`
+    Sender,
+    /// Validates `info.funds`, e.g. a `check_payment` wrapper around `must_pay`
+    Funds,
+}
+
+impl TrustedCheck {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sender" => Some(Self::Sender),
+            "funds" => Some(Self::Funds),
+            _ => None,
+        }
+    }
+}
+
 /// Generic function info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionInfo {
@@ -100,6 +167,12 @@ pub struct FunctionInfo {
     pub params: Vec<ParamInfo>,
     pub return_type: Option<String>,
     pub span: SourceSpan,
+    /// Checks callers may assume this function performs
+    #[serde(default)]
+    pub assumes: Vec<TrustedCheck>,
+    /// Body from its opening to its closing brace
+    #[serde(default)]
+    pub body_span: Option<SourceSpan>,
     /// syn::Block is not serializable — skipped during caching, re-parsed on cache hit
     #[serde(skip)]
     pub body: Option<syn::Block>,
@@ -114,6 +187,7 @@ pub struct ContractInfo {
     pub entry_points: Vec<EntryPoint>,
     pub message_enums: Vec<MessageEnum>,
     pub state_items: Vec<StateItem>,
+    pub structs: Vec<StructInfo>,
     pub functions: Vec<FunctionInfo>,
     /// syn::File is not serializable — skipped during caching, re-populated on cache hit
     #[serde(skip)]
@@ -128,26 +202,49 @@ impl ContractInfo {
             entry_points: Vec::new(),
             message_enums: Vec::new(),
             state_items: Vec::new(),
+            structs: Vec::new(),
             functions: Vec::new(),
             raw_asts: Vec::new(),
         }
     }
 
+    /// Struct definition by name
+    pub fn find_struct(&self, name: &str) -> Option<&StructInfo> {
+        self.structs.iter().find(|s| s.name == name)
+    }
+
+    /// Names of types persisted in storage: state item value types plus,
+    /// transitively, the field types of crate structs among them
+    pub fn stored_type_names(&self) -> HashSet<String> {
+        let mut stored: HashSet<String> = self
+            .state_items
+            .iter()
+            .flat_map(|item| utils::type_idents(&item.value_type))
+            .collect();
+        let mut pending: Vec<String> = stored.iter().cloned().collect();
+        while let Some(name) = pending.pop() {
+            let Some(def) = self.find_struct(&name) else {
+                continue;
+            };
+            for field in &def.fields {
+                for ident in utils::type_idents(&field.type_name) {
+                    if stored.insert(ident.clone()) {
+                        pending.push(ident);
+                    }
+                }
+            }
+        }
+        stored
+    }
+
     /// Merge results from a visitor into this ContractInfo
-    pub fn merge_from_visitor(
-        &mut self,
-        entry_points: Vec<EntryPoint>,
-        message_enums: Vec<MessageEnum>,
-        state_items: Vec<StateItem>,
-        functions: Vec<FunctionInfo>,
-        file_path: PathBuf,
-        ast: syn::File,
-    ) {
-        self.source_files.push(file_path.clone());
-        self.entry_points.extend(entry_points);
-        self.message_enums.extend(message_enums);
-        self.state_items.extend(state_items);
-        self.functions.extend(functions);
-        self.raw_asts.push((file_path, ast));
+    pub fn merge_from_visitor(&mut self, visitor: ContractVisitor, ast: syn::File) {
+        self.source_files.push(visitor.file_path.clone());
+        self.entry_points.extend(visitor.entry_points);
+        self.message_enums.extend(visitor.message_enums);
+        self.state_items.extend(visitor.state_items);
+        self.structs.extend(visitor.structs);
+        self.functions.extend(visitor.functions);
+        self.raw_asts.push((visitor.file_path, ast));
     }
 }
@@ -1,108 +1,75 @@
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
-use walkdir::WalkDir;
+use anyhow::Result;
 
 use super::contract_info::ContractInfo;
+use super::deps::resolve_dependency;
+use super::discovery::{discover_rs_files, DiscoveryOptions};
+use super::imports;
 use super::visitor::ContractVisitor;
 use crate::cache::{CacheManager, CachedFileArtifact};
 use crate::ir::builder::IrBuilder;
 use crate::ir::types::ContractIr;
+use crate::ir::FunctionAnalyses;
+use crate::report::AnalysisError;
 
-/// Result of analyzing a crate: contract info, IR, and source map
+/// Result of analyzing a crate: contract info, IR, source map, and files that
+/// were skipped because they failed to read or parse
 pub struct CrateAnalysis {
     pub contract: ContractInfo,
     pub ir: ContractIr,
     pub source_map: std::collections::HashMap<PathBuf, String>,
+    pub errors: Vec<AnalysisError>,
 }
 
 /// Analyze an entire CosmWasm crate with optional file-level caching.
 /// Returns merged ContractInfo, ContractIr, and source map.
 pub fn analyze_crate_cached(
+    crate_path: &Path,
+    cache: Option<&mut CacheManager>,
+) -> Result<CrateAnalysis> {
+    analyze_crate_with_options(crate_path, cache, &DiscoveryOptions::default())
+}
+
+/// Like [`analyze_crate_cached`], with control over which files are discovered
+pub fn analyze_crate_with_options(
     crate_path: &Path,
     mut cache: Option<&mut CacheManager>,
+    discovery: &DiscoveryOptions,
 ) -> Result<CrateAnalysis> {
-    let rs_files = discover_rs_files(crate_path)?;
+    let rs_files = discover_rs_files(crate_path, discovery)?;
     let mut merged = ContractInfo::new(crate_path.to_path_buf());
     let mut ir = ContractIr::new();
     let mut source_map = std::collections::HashMap::new();
+    let mut errors = Vec::new();
 
     for file_path in &rs_files {
-        let source = std::fs::read_to_string(file_path)
-            .with_context(|| format!("Failed to read: {}", file_path.display()))?;
-        let hash = CacheManager::hash_contents(&source);
-
-        // Parse once — used for raw_asts AND visitor/cache
-        let ast = syn::parse_file(&source)
-            .with_context(|| format!("Failed to parse: {}", file_path.display()))?;
-
-        // Try cache lookup
-        let cached = cache
-            .as_deref()
-            .and_then(|c| c.lookup(file_path, &hash));
-
-        if let Some(artifact) = cached {
-            // Cache hit — merge cached data (skips visitor + IR build)
-            CacheManager::merge_cached_into(&artifact, &mut merged, &mut ir, file_path.clone());
-
-            // Re-visit AST to populate FunctionInfo.body fields (not serializable,
-            // but detectors need them for pattern matching)
-            let mut visitor = ContractVisitor::new(file_path.clone());
-            syn::visit::visit_file(&mut visitor, &ast);
-            repopulate_function_bodies(&mut merged, &visitor);
-
-            // Push raw AST for detectors
-            merged.raw_asts.push((file_path.clone(), ast));
-        } else {
-            // Cache miss — full visitor + IR build
-            let mut visitor = ContractVisitor::new(file_path.clone());
-            syn::visit::visit_file(&mut visitor, &ast);
-
-            // Build per-file IR
-            let file_contract = build_file_contract(file_path, &visitor);
-            let file_ir = IrBuilder::build_contract(&file_contract);
-
-            // Store to cache
-            if let Some(ref mut c) = cache {
-                let artifact = CachedFileArtifact {
-                    entry_points: visitor.entry_points.clone(),
-                    message_enums: visitor.message_enums.clone(),
-                    state_items: visitor.state_items.clone(),
-                    functions: visitor.functions.clone(),
-                    ir_functions: file_ir.functions.clone(),
-                    ir_entry_points: file_ir.entry_points.clone(),
-                };
-                // Non-fatal: log but don't fail on cache write errors
-                let _ = c.store(file_path, &hash, &artifact);
-            }
-
-            // Merge into main structures
-            merged.merge_from_visitor(
-                visitor.entry_points,
-                visitor.message_enums,
-                visitor.state_items,
-                visitor.functions,
-                file_path.clone(),
-                ast,
-            );
-            ir.functions.extend(file_ir.functions);
-            for ep in file_ir.entry_points {
-                if !ir.entry_points.contains(&ep) {
-                    ir.entry_points.push(ep);
-                }
+        // Unreadable or unparseable files (nightly syntax, fixtures) are
+        // recorded and skipped so the rest of the crate is still analyzed
+        let source = match std::fs::read_to_string(file_path) {
+            Ok(source) => source,
+            Err(e) => {
+                errors.push(AnalysisError {
+                    file: file_path.clone(),
+                    message: format!("failed to read: {e}"),
+                    line: None,
+                    column: None,
+                });
+                continue;
             }
+        };
+        if add_file(file_path, &source, cache.as_deref_mut(), &mut merged, &mut ir, &mut errors) {
+            source_map.insert(file_path.clone(), source);
         }
-
-        source_map.insert(file_path.clone(), source);
     }
 
-    // Fix up entry point flags on IR functions (cached files may not know about
-    // entry points from other files)
-    let ep_names: Vec<String> = merged.entry_points.iter().map(|ep| ep.name.clone()).collect();
-    ir.entry_points = ep_names.clone();
-    for func in &mut ir.functions {
-        func.is_entry_point = ep_names.contains(&func.name);
+    for spec in &discovery.include_deps {
+        let dep_root = resolve_dependency(crate_path, spec)?;
+        include_dependency(&dep_root, discovery, &mut merged, &mut source_map, &mut errors)?;
     }
+    imports::resolve_imported_messages(crate_path, &mut merged, &mut source_map);
+
+    mark_entry_points(&merged, &mut ir);
 
     // Flush cache manifest
     if let Some(c) = cache {
@@ -113,15 +80,198 @@ pub fn analyze_crate_cached(
         contract: merged,
         ir,
         source_map,
+        errors,
     })
 }
 
+/// Analyze sources held in memory, such as code extracted from doc comments,
+/// as one crate rooted at `crate_path`. Nothing is cached.
+pub fn analyze_sources(crate_path: &Path, sources: Vec<(PathBuf, String)>) -> CrateAnalysis {
+    let mut merged = ContractInfo::new(crate_path.to_path_buf());
+    let mut ir = ContractIr::new();
+    let mut source_map = std::collections::HashMap::new();
+    let mut errors = Vec::new();
+    for (file_path, source) in sources {
+        if add_file(&file_path, &source, None, &mut merged, &mut ir, &mut errors) {
+            source_map.insert(file_path, source);
+        }
+    }
+    mark_entry_points(&merged, &mut ir);
+    CrateAnalysis {
+        contract: merged,
+        ir,
+        source_map,
+        errors,
+    }
+}
+
+/// Parse `source` and merge the items of `file_path` into `merged` and `ir`,
+/// reusing cached artifacts when possible. Returns false, recording the
+/// error, when the file fails to parse.
+fn add_file(
+    file_path: &Path,
+    source: &str,
+    mut cache: Option<&mut CacheManager>,
+    merged: &mut ContractInfo,
+    ir: &mut ContractIr,
+    errors: &mut Vec<AnalysisError>,
+) -> bool {
+    let file_path = file_path.to_path_buf();
+    let hash = CacheManager::hash_contents(source);
+
+    // Parse once — used for raw_asts AND visitor/cache
+    let ast = match syn::parse_file(source) {
+        Ok(ast) => ast,
+        Err(e) => {
+            let start = e.span().start();
+            errors.push(AnalysisError {
+                file: file_path,
+                message: format!("failed to parse: {e}"),
+                line: Some(start.line),
+                column: Some(start.column),
+            });
+            return false;
+        }
+    };
+
+    // Try cache lookup
+    let cached = cache
+        .as_deref()
+        .and_then(|c| c.lookup(&file_path, &hash));
+
+    if let Some(artifact) = cached {
+        // Cache hit — merge cached data (skips visitor + IR build)
+        CacheManager::merge_cached_into(&artifact, merged, ir, file_path.clone());
+
+        // Re-visit AST to populate FunctionInfo.body fields (not serializable,
+        // but detectors need them for pattern matching)
+        let mut visitor = ContractVisitor::new(file_path.clone());
+        syn::visit::visit_file(&mut visitor, &ast);
+        repopulate_function_bodies(merged, &visitor);
+
+        // Push raw AST for detectors
+        merged.raw_asts.push((file_path.clone(), ast));
+    } else {
+        // Cache miss — full visitor + IR build
+        let mut visitor = ContractVisitor::new(file_path.clone());
+        syn::visit::visit_file(&mut visitor, &ast);
+
+        // Build per-file IR
+        let file_contract = build_file_contract(&file_path, &visitor);
+        let mut file_ir = IrBuilder::build_contract(&file_contract);
+
+        // Store to cache
+        if let Some(ref mut c) = cache {
+            let ir_analyses: Vec<FunctionAnalyses> = file_ir
+                .functions
+                .iter()
+                .map(FunctionAnalyses::compute)
+                .collect();
+            let artifact = CachedFileArtifact {
+                entry_points: visitor.entry_points.clone(),
+                message_enums: visitor.message_enums.clone(),
+                state_items: visitor.state_items.clone(),
+                structs: visitor.structs.clone(),
+                functions: visitor.functions.clone(),
+                ir_functions: file_ir.functions.clone(),
+                ir_analyses,
+                ir_entry_points: file_ir.entry_points.clone(),
+            };
+            // Non-fatal: log but don't fail on cache write errors
+            let _ = c.store(&file_path, &hash, &artifact);
+            // Computed anyway, so this run reuses them too
+            for (func, analyses) in file_ir.functions.iter_mut().zip(artifact.ir_analyses) {
+                func.analyses = Some(analyses);
+            }
+        }
+
+        // Merge into main structures
+        merged.merge_from_visitor(visitor, ast);
+        ir.functions.extend(file_ir.functions);
+        for ep in file_ir.entry_points {
+            if !ir.entry_points.contains(&ep) {
+                ir.entry_points.push(ep);
+            }
+        }
+    }
+    true
+}
+
+/// Fix up entry point flags on IR functions (cached files may not know about
+/// entry points from other files)
+fn mark_entry_points(merged: &ContractInfo, ir: &mut ContractIr) {
+    let ep_names: Vec<String> = merged.entry_points.iter().map(|ep| ep.name.clone()).collect();
+    ir.entry_points = ep_names.clone();
+    for func in &mut ir.functions {
+        func.is_entry_point = ep_names.contains(&func.name);
+    }
+}
+
+/// Add the message enums and structs of the dependency crate at `dep_root`.
+/// Its functions, entry points and state stay out of the model so detectors
+/// only report on the dependency's types, not its code.
+fn include_dependency(
+    dep_root: &Path,
+    discovery: &DiscoveryOptions,
+    merged: &mut ContractInfo,
+    source_map: &mut std::collections::HashMap<PathBuf, String>,
+    errors: &mut Vec<AnalysisError>,
+) -> Result<()> {
+    let dep_discovery = DiscoveryOptions {
+        exclude: discovery.exclude.clone(),
+        respect_gitignore: discovery.respect_gitignore,
+        follow_symlinks: discovery.follow_symlinks,
+        ..Default::default()
+    };
+    for file_path in discover_rs_files(dep_root, &dep_discovery)? {
+        if source_map.contains_key(&file_path) {
+            continue;
+        }
+        let source = match std::fs::read_to_string(&file_path) {
+            Ok(source) => source,
+            Err(e) => {
+                errors.push(AnalysisError {
+                    file: file_path,
+                    message: format!("failed to read: {e}"),
+                    line: None,
+                    column: None,
+                });
+                continue;
+            }
+        };
+        let ast = match syn::parse_file(&source) {
+            Ok(ast) => ast,
+            Err(e) => {
+                let start = e.span().start();
+                errors.push(AnalysisError {
+                    file: file_path,
+                    message: format!("failed to parse: {e}"),
+                    line: Some(start.line),
+                    column: Some(start.column),
+                });
+                continue;
+            }
+        };
+        let mut visitor = ContractVisitor::new(file_path.clone());
+        syn::visit::visit_file(&mut visitor, &ast);
+        merged.message_enums.extend(visitor.message_enums);
+        merged.structs.extend(visitor.structs);
+        source_map.insert(file_path, source);
+    }
+    Ok(())
+}
+
 /// On cache hit, FunctionInfo.body is None (not serializable). Re-populate
-/// by matching function names from a fresh visitor pass.
+/// by matching functions from a fresh visitor pass on name and span, since
+/// methods of different impls in one file can share a name.
 fn repopulate_function_bodies(merged: &mut ContractInfo, visitor: &ContractVisitor) {
     for func in &mut merged.functions {
         if func.body.is_none() {
-            if let Some(fresh) = visitor.functions.iter().find(|f| f.name == func.name) {
+            if let Some(fresh) = visitor
+                .functions
+                .iter()
+                .find(|f| f.name == func.name && f.span == func.span)
+            {
                 func.body = fresh.body.clone();
             }
         }
@@ -143,29 +293,3 @@ pub fn analyze_crate(
     let result = analyze_crate_cached(crate_path, None)?;
     Ok((result.contract, result.source_map))
 }
-
-/// Discover all .rs files in a crate directory
-fn discover_rs_files(path: &Path) -> Result<Vec<PathBuf>> {
-    // If path is a single file, return it directly
-    if path.is_file() {
-        return Ok(vec![path.to_path_buf()]);
-    }
-
-    // Look for src/ directory
-    let src_dir = path.join("src");
-    let search_dir = if src_dir.exists() { &src_dir } else { path };
-
-    let files: Vec<PathBuf> = WalkDir::new(search_dir)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"))
-        .filter(|e| !e.path().to_string_lossy().contains("/target/"))
-        .map(|e| e.path().to_path_buf())
-        .collect();
-
-    if files.is_empty() {
-        anyhow::bail!("No .rs files found in: {}", path.display());
-    }
-
-    Ok(files)
-}
@@ -0,0 +1,143 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+/// Dependency tables of a manifest that may declare path dependencies
+const DEPENDENCY_TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Resolve an `--include-dep` argument to the root of a dependency crate.
+///
+/// An existing directory is used as is. Anything else is looked up by crate
+/// name among the path dependencies of the crate at `crate_path`.
+pub fn resolve_dependency(crate_path: &Path, spec: &str) -> Result<PathBuf> {
+    let as_path = Path::new(spec);
+    if as_path.is_dir() {
+        return Ok(as_path.to_path_buf());
+    }
+
+    path_dependency(crate_path, spec)
+}
+
+/// Root of the path dependency `name` of the crate at `crate_path`, following
+/// `{ workspace = true }` into the workspace's `[workspace.dependencies]`
+pub fn path_dependency(crate_path: &Path, name: &str) -> Result<PathBuf> {
+    let crate_root = if crate_path.is_file() {
+        crate_path.parent().unwrap_or(Path::new("."))
+    } else {
+        crate_path
+    };
+    let Some(root) = crate_root
+        .ancestors()
+        .find(|dir| dir.join("Cargo.toml").is_file())
+    else {
+        bail!(
+            "{} has no Cargo.toml to look up `{name}` in",
+            crate_path.display()
+        );
+    };
+    let manifest = read_manifest(root)?;
+    for table in DEPENDENCY_TABLES {
+        let Some(dep) = manifest.get(*table).and_then(|t| find_dep(t, name)) else {
+            continue;
+        };
+        if let Some(path) = dep.get("path").and_then(|p| p.as_str()) {
+            return Ok(root.join(path));
+        }
+        if dep.get("workspace").and_then(|w| w.as_bool()) == Some(true) {
+            if let Some(path) = workspace_dep_path(root, name)? {
+                return Ok(path);
+            }
+        }
+        bail!(
+            "dependency `{name}` of {} is not a path dependency",
+            root.display()
+        );
+    }
+    bail!("`{name}` is not a dependency of {}", root.display())
+}
+
+/// Entry for `name` in a dependency table; Cargo treats `-` and `_` alike
+fn find_dep<'a>(table: &'a toml::Value, name: &str) -> Option<&'a toml::Value> {
+    let normalize = |s: &str| s.replace('-', "_");
+    table
+        .as_table()?
+        .iter()
+        .find(|(key, _)| normalize(key) == normalize(name))
+        .map(|(_, dep)| dep)
+}
+
+/// Path of `name` in the nearest `[workspace.dependencies]` above `crate_root`
+fn workspace_dep_path(crate_root: &Path, name: &str) -> Result<Option<PathBuf>> {
+    for dir in crate_root.ancestors() {
+        if !dir.join("Cargo.toml").is_file() {
+            continue;
+        }
+        let manifest = read_manifest(dir)?;
+        let Some(workspace) = manifest.get("workspace") else {
+            continue;
+        };
+        return Ok(workspace
+            .get("dependencies")
+            .and_then(|t| find_dep(t, name))
+            .and_then(|dep| dep.get("path")?.as_str())
+            .map(|path| dir.join(path)));
+    }
+    Ok(None)
+}
+
+fn read_manifest(dir: &Path) -> Result<toml::Table> {
+    let manifest_path = dir.join("Cargo.toml");
+    let content = std::fs::read_to_string(&manifest_path)
+        .with_context(|| format!("Failed to read: {}", manifest_path.display()))?;
+    toml::from_str(&content)
+        .with_context(|| format!("Failed to parse: {}", manifest_path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolves_path_and_workspace_dependencies() {
+        let dir = std::env::temp_dir().join("cosmwasm-guard-test-deps");
+        let _ = std::fs::remove_dir_all(&dir);
+        for (file, content) in [
+            (
+                "Cargo.toml",
+                "[workspace]\nmembers = [\"contracts/*\", \"packages/*\"]\n\n\
+                 [workspace.dependencies]\nvault-types = { path = \"packages/vault-types\" }\n",
+            ),
+            (
+                "contracts/vault/Cargo.toml",
+                "[package]\nname = \"vault\"\n\n[dependencies]\n\
+                 vault-types = { workspace = true }\nshared = { path = \"../../packages/shared\" }\n\
+                 cosmwasm-std = \"1.5\"\n",
+            ),
+            ("packages/vault-types/src/lib.rs", ""),
+            ("packages/shared/src/lib.rs", ""),
+        ] {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let vault = dir.join("contracts/vault");
+
+        assert_eq!(
+            resolve_dependency(&vault, "vault_types").unwrap(),
+            dir.join("packages/vault-types")
+        );
+        assert_eq!(
+            resolve_dependency(&vault, "shared").unwrap(),
+            vault.join("../../packages/shared")
+        );
+        let dep_dir = dir.join("packages/shared");
+        assert_eq!(
+            resolve_dependency(&vault, dep_dir.to_str().unwrap()).unwrap(),
+            dep_dir
+        );
+
+        let err = resolve_dependency(&vault, "cosmwasm-std").unwrap_err();
+        assert!(err.to_string().contains("not a path dependency"));
+        assert!(resolve_dependency(&vault, "unknown").is_err());
+    }
+}
@@ -0,0 +1,405 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use walkdir::WalkDir;
+
+use super::targets::{CargoTargets, TargetSelection};
+
+/// Controls which `.rs` files are picked up for analysis
+#[derive(Debug, Clone)]
+pub struct DiscoveryOptions {
+    /// Glob patterns of files to skip before parsing, matched against the path
+    /// relative to the crate root and against the full path
+    pub exclude: Vec<String>,
+    /// Skip files ignored by `.gitignore` files in the crate and its parent repo
+    pub respect_gitignore: bool,
+    /// Descend into symlinked directories and pick up symlinked files.
+    /// Symlink cycles are detected and skipped.
+    pub follow_symlinks: bool,
+    /// Cargo target whose sources are analyzed
+    pub target: TargetSelection,
+    /// Dependency crates (directories or path-dependency names) whose message
+    /// enums and structs are added to the model, e.g. a shared `packages/`
+    /// types crate
+    pub include_deps: Vec<String>,
+}
+
+impl Default for DiscoveryOptions {
+    fn default() -> Self {
+        Self {
+            exclude: Vec::new(),
+            respect_gitignore: true,
+            follow_symlinks: false,
+            target: TargetSelection::Auto,
+            include_deps: Vec::new(),
+        }
+    }
+}
+
+/// One line of a `.gitignore` file
+#[derive(Debug)]
+struct IgnoreRule {
+    pattern: glob::Pattern,
+    negated: bool,
+    dir_only: bool,
+    /// Patterns containing `/` match the path relative to the .gitignore;
+    /// others match the file name at any depth
+    anchored: bool,
+}
+
+/// Rules from one `.gitignore`, applying below `base`
+#[derive(Debug)]
+struct IgnoreFile {
+    base: PathBuf,
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreFile {
+    fn load(dir: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(dir.join(".gitignore")).ok()?;
+        let rules = content.lines().filter_map(parse_ignore_line).collect();
+        Some(Self {
+            base: dir.to_path_buf(),
+            rules,
+        })
+    }
+}
+
+fn parse_ignore_line(line: &str) -> Option<IgnoreRule> {
+    let line = line.trim_end();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (negated, line) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let (dir_only, line) = match line.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let anchored = line.contains('/');
+    let line = line.strip_prefix('/').unwrap_or(line);
+    Some(IgnoreRule {
+        pattern: glob::Pattern::new(line).ok()?,
+        negated,
+        dir_only,
+        anchored,
+    })
+}
+
+const GITIGNORE_MATCH: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Whether `path` is ignored. Deeper .gitignore files and later lines take
+/// precedence, so the last matching rule decides.
+fn is_gitignored(ignore_files: &[IgnoreFile], path: &Path, is_dir: bool) -> bool {
+    let mut ignored = false;
+    for file in ignore_files {
+        let Ok(rel) = path.strip_prefix(&file.base) else {
+            continue;
+        };
+        let rel = normalize_separators(rel);
+        let name = rel.rsplit('/').next().unwrap_or(&rel);
+        for rule in &file.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let target = if rule.anchored { rel.as_str() } else { name };
+            if rule.pattern.matches_with(target, GITIGNORE_MATCH) {
+                ignored = !rule.negated;
+            }
+        }
+    }
+    ignored
+}
+
+/// Render a path with `/` separators so globs behave the same on every platform.
+/// Non-UTF-8 components are replaced lossily; they can still match `*` wildcards.
+pub fn normalize_separators(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Whether `path` matches one of the exclude globs
+fn is_excluded(patterns: &[glob::Pattern], root: &Path, path: &Path) -> bool {
+    let full = normalize_separators(path);
+    let rel = relative_path(root, path);
+    patterns
+        .iter()
+        .any(|p| p.matches(&full) || rel.as_deref().is_some_and(|r| p.matches(r)))
+}
+
+/// `path` relative to `root`, with `/` separators
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let root = normalize_separators(root);
+    normalize_separators(path)
+        .strip_prefix(root.trim_end_matches('/'))
+        .and_then(|r| r.strip_prefix('/'))
+        .map(String::from)
+}
+
+/// `.gitignore` files in the ancestors of `dir`, up to the repository root
+fn ancestor_ignore_files(dir: &Path) -> Vec<IgnoreFile> {
+    let mut files = Vec::new();
+    for ancestor in dir.ancestors().skip(1) {
+        files.extend(IgnoreFile::load(ancestor));
+        if ancestor.join(".git").exists() {
+            break;
+        }
+    }
+    // Outermost first, so deeper files are evaluated later and win
+    files.reverse();
+    files
+}
+
+/// Discover all .rs files in a crate directory
+pub fn discover_rs_files(path: &Path, options: &DiscoveryOptions) -> Result<Vec<PathBuf>> {
+    // If path is a single file, return it directly
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let target_filter = match CargoTargets::load(path)? {
+        Some(targets) => targets.filter(&options.target)?,
+        None if matches!(
+            options.target,
+            TargetSelection::Lib | TargetSelection::Bin(_)
+        ) =>
+        {
+            anyhow::bail!("Target selection needs a Cargo.toml in: {}", path.display());
+        }
+        None => None,
+    };
+
+    // Look for src/ directory
+    let src_dir = path.join("src");
+    let search_dir = if src_dir.exists() { &src_dir } else { path };
+
+    let excludes: Vec<glob::Pattern> = options
+        .exclude
+        .iter()
+        .filter_map(|p| glob::Pattern::new(p).ok())
+        .collect();
+
+    // Ignore files are keyed by canonical directory; walked entries are mapped
+    // onto the canonical root before matching so relative inputs work too
+    let canonical_root = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let canonical = |p: &Path| match p.strip_prefix(path) {
+        Ok(rel) => canonical_root.join(rel),
+        Err(_) => p.to_path_buf(),
+    };
+    let ignore_files = RefCell::new(Vec::new());
+    if options.respect_gitignore {
+        let mut initial = ancestor_ignore_files(&canonical_root);
+        initial.extend(IgnoreFile::load(&canonical_root));
+        if search_dir != path {
+            initial.extend(IgnoreFile::load(&canonical(search_dir)));
+        }
+        *ignore_files.borrow_mut() = initial;
+    }
+
+    let mut files: Vec<PathBuf> = WalkDir::new(search_dir)
+        .follow_links(options.follow_symlinks)
+        .into_iter()
+        .filter_entry(|e| {
+            if e.depth() == 0 {
+                return true;
+            }
+            let is_dir = e.file_type().is_dir();
+            if is_dir && e.file_name() == "target" {
+                return false;
+            }
+            if !options.respect_gitignore {
+                return true;
+            }
+            let entry_path = canonical(e.path());
+            let keep = !is_gitignored(&ignore_files.borrow(), &entry_path, is_dir);
+            if keep && is_dir {
+                ignore_files
+                    .borrow_mut()
+                    .extend(IgnoreFile::load(&entry_path));
+            }
+            keep
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"))
+        .filter(|e| !is_excluded(&excludes, path, e.path()))
+        .filter(|e| {
+            target_filter
+                .as_ref()
+                .is_none_or(|f| relative_path(path, e.path()).is_some_and(|rel| f.keeps(&rel)))
+        })
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    // A selected binary may live outside the searched directory
+    if let Some(filter) = &target_filter {
+        for root in filter.required_roots() {
+            let file = path.join(root);
+            if file.is_file() && !files.contains(&file) {
+                files.push(file);
+            }
+        }
+    }
+
+    if files.is_empty() {
+        anyhow::bail!("No .rs files found in: {}", path.display());
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_crate(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cosmwasm-guard-test-{name}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        for (rel, content) in files {
+            let file = dir.join(rel);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, content).unwrap();
+        }
+        dir
+    }
+
+    fn discovered(dir: &Path, options: &DiscoveryOptions) -> Vec<String> {
+        let mut files: Vec<String> = discover_rs_files(dir, options)
+            .unwrap()
+            .iter()
+            .map(|f| normalize_separators(f.strip_prefix(dir).unwrap()))
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_gitignore_is_respected() {
+        let dir = scratch_crate(
+            "discovery-gitignore",
+            &[
+                (".gitignore", "generated/\n*.gen.rs\n!keep.gen.rs\n"),
+                ("src/lib.rs", ""),
+                ("src/generated/schema.rs", ""),
+                ("src/types.gen.rs", ""),
+                ("src/keep.gen.rs", ""),
+                ("src/nested/.gitignore", "/local.rs\n"),
+                ("src/nested/local.rs", ""),
+                ("src/nested/mod.rs", ""),
+            ],
+        );
+        assert_eq!(
+            discovered(&dir, &DiscoveryOptions::default()),
+            vec!["src/keep.gen.rs", "src/lib.rs", "src/nested/mod.rs"]
+        );
+
+        let all = DiscoveryOptions {
+            respect_gitignore: false,
+            ..Default::default()
+        };
+        assert_eq!(discovered(&dir, &all).len(), 6);
+    }
+
+    #[test]
+    fn test_exclude_globs_skip_files_before_parsing() {
+        let dir = scratch_crate(
+            "discovery-exclude",
+            &[
+                ("src/lib.rs", ""),
+                ("src/testing/mock.rs", "this is not rust {"),
+                ("src/contract.rs", ""),
+            ],
+        );
+        let options = DiscoveryOptions {
+            exclude: vec!["src/testing/**".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            discovered(&dir, &options),
+            vec!["src/contract.rs", "src/lib.rs"]
+        );
+    }
+
+    #[test]
+    fn test_target_dirs_pruned_by_component() {
+        let dir = scratch_crate(
+            "discovery-target",
+            &[
+                ("lib.rs", ""),
+                ("target_utils.rs", ""),
+                ("target/debug/build/out.rs", ""),
+            ],
+        );
+        assert_eq!(
+            discovered(&dir, &DiscoveryOptions::default()),
+            vec!["lib.rs", "target_utils.rs"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_skipped_unless_followed() {
+        let shared = scratch_crate("discovery-symlink-shared", &[("shared.rs", "")]);
+        let dir = scratch_crate("discovery-symlink", &[("src/lib.rs", "")]);
+        std::os::unix::fs::symlink(&shared, dir.join("src/linked")).unwrap();
+        // A cycle back to the crate root must not recurse forever
+        std::os::unix::fs::symlink(&dir, dir.join("src/cycle")).unwrap();
+
+        assert_eq!(
+            discovered(&dir, &DiscoveryOptions::default()),
+            vec!["src/lib.rs"]
+        );
+        let follow = DiscoveryOptions {
+            follow_symlinks: true,
+            ..Default::default()
+        };
+        let files = discovered(&dir, &follow);
+        assert!(files.contains(&"src/linked/shared.rs".to_string()));
+        assert!(files.contains(&"src/lib.rs".to_string()));
+    }
+
+    #[test]
+    fn test_globs_match_normalized_separators() {
+        let patterns = vec![glob::Pattern::new("tests/**").unwrap()];
+        let root = Path::new("crate");
+        assert!(is_excluded(
+            &patterns,
+            root,
+            Path::new("crate\\tests\\integration.rs")
+        ));
+        assert!(is_excluded(
+            &patterns,
+            root,
+            Path::new("crate/tests/integration.rs")
+        ));
+        assert!(!is_excluded(&patterns, root, Path::new("crate/src/lib.rs")));
+    }
+
+    #[test]
+    fn test_auxiliary_binaries_skipped_by_default() {
+        let dir = scratch_crate(
+            "discovery-targets",
+            &[
+                ("Cargo.toml", "[package]\nname = \"contract\"\n"),
+                ("src/lib.rs", ""),
+                ("src/contract.rs", ""),
+                ("src/bin/schema.rs", ""),
+            ],
+        );
+        assert_eq!(
+            discovered(&dir, &DiscoveryOptions::default()),
+            vec!["src/contract.rs", "src/lib.rs"]
+        );
+        let schema = DiscoveryOptions {
+            target: TargetSelection::Bin("schema".into()),
+            ..Default::default()
+        };
+        assert_eq!(discovered(&dir, &schema), vec!["src/bin/schema.rs"]);
+    }
+}
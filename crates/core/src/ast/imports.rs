@@ -0,0 +1,362 @@
+//! Resolution of message enums that entry points import from sibling crates,
+//! as in the common workspace layout where `contracts/vault` does
+//! `use my_protocol::vault::ExecuteMsg;` and the enum lives in `packages/`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::contract_info::{ContractInfo, EntryPointKind, MessageKind};
+use super::deps::path_dependency;
+use super::discovery::{discover_rs_files, DiscoveryOptions};
+use super::visitor::{message_enum, ContractVisitor};
+
+/// Entry point parameter types that are not the message
+const CONTEXT_TYPES: &[&str] = &["Deps", "DepsMut", "Env", "MessageInfo", "Reply"];
+
+/// Bound on `pub use` hops followed to find a re-exported enum
+const MAX_REEXPORT_DEPTH: usize = 8;
+
+/// An enum definition found in a dependency, with its file's path, source and AST
+type Located = (PathBuf, String, syn::File, syn::ItemEnum);
+
+/// A name brought into scope by a `use` item. Glob imports have alias `*`
+/// and the path of the globbed module.
+#[derive(Debug, Clone)]
+struct Import {
+    alias: String,
+    path: Vec<String>,
+}
+
+/// Imports of a file, flattened out of `use` trees
+fn file_imports(ast: &syn::File) -> Vec<Import> {
+    let mut imports = Vec::new();
+    for item in &ast.items {
+        if let syn::Item::Use(item) = item {
+            flatten_use(&item.tree, &mut Vec::new(), &mut imports);
+        }
+    }
+    imports
+}
+
+fn flatten_use(tree: &syn::UseTree, prefix: &mut Vec<String>, out: &mut Vec<Import>) {
+    match tree {
+        syn::UseTree::Path(p) => {
+            prefix.push(p.ident.to_string());
+            flatten_use(&p.tree, prefix, out);
+            prefix.pop();
+        }
+        syn::UseTree::Name(n) => {
+            let mut path = prefix.clone();
+            path.push(n.ident.to_string());
+            out.push(Import {
+                alias: n.ident.to_string(),
+                path,
+            });
+        }
+        syn::UseTree::Rename(r) => {
+            let mut path = prefix.clone();
+            path.push(r.ident.to_string());
+            out.push(Import {
+                alias: r.rename.to_string(),
+                path,
+            });
+        }
+        syn::UseTree::Glob(_) => out.push(Import {
+            alias: "*".to_string(),
+            path: prefix.clone(),
+        }),
+        syn::UseTree::Group(g) => {
+            for tree in &g.items {
+                flatten_use(tree, prefix, out);
+            }
+        }
+    }
+}
+
+/// Message kind an entry point gives its msg parameter
+fn message_kind(kind: &EntryPointKind) -> MessageKind {
+    match kind {
+        EntryPointKind::Instantiate => MessageKind::Instantiate,
+        EntryPointKind::Execute => MessageKind::Execute,
+        EntryPointKind::Query => MessageKind::Query,
+        EntryPointKind::Migrate => MessageKind::Migrate,
+        _ => MessageKind::Unknown,
+    }
+}
+
+/// Add the message enums that entry points take but the crate doesn't define,
+/// resolved through the crate's `use` items into its path dependencies. Each
+/// enum is recorded under the name the contract uses for it, with the kind of
+/// the entry point that takes it, together with the structs of its file.
+pub fn resolve_imported_messages(
+    crate_path: &Path,
+    contract: &mut ContractInfo,
+    source_map: &mut HashMap<PathBuf, String>,
+) {
+    let imports: Vec<Import> = contract
+        .raw_asts
+        .iter()
+        .flat_map(|(_, ast)| file_imports(ast))
+        .collect();
+
+    let mut wanted: Vec<(String, Vec<Vec<String>>, MessageKind)> = Vec::new();
+    for ep in &contract.entry_points {
+        let Some(msg) = ep
+            .params
+            .iter()
+            .rev()
+            .find(|p| !CONTEXT_TYPES.contains(&p.type_name.trim_start_matches('&')))
+        else {
+            continue;
+        };
+        let segments: Vec<String> = msg
+            .type_name
+            .trim_start_matches('&')
+            .split("::")
+            .map(String::from)
+            .collect();
+        let Some(name) = segments.last().cloned() else {
+            continue;
+        };
+        if contract.message_enums.iter().any(|e| e.name == name)
+            || wanted.iter().any(|(n, _, _)| *n == name)
+        {
+            continue;
+        }
+        let candidates = if segments.len() > 1 {
+            // Qualified type: expand a leading imported module alias
+            match imports.iter().find(|i| i.alias == segments[0]) {
+                Some(import) => vec![[&import.path[..], &segments[1..]].concat()],
+                None => vec![segments],
+            }
+        } else {
+            imports
+                .iter()
+                .filter_map(|i| match i.alias.as_str() {
+                    "*" => Some([&i.path[..], &segments[..]].concat()),
+                    alias if alias == name => Some(i.path.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+        wanted.push((name, candidates, message_kind(&ep.kind)));
+    }
+
+    for (name, candidates, kind) in wanted {
+        let Some((file, source, ast, item)) = candidates
+            .iter()
+            .find_map(|path| locate_enum(crate_path, path))
+        else {
+            continue;
+        };
+        let mut msg = message_enum(&item, &file);
+        msg.name = name;
+        if kind != MessageKind::Unknown {
+            msg.kind = kind;
+        }
+        contract.message_enums.push(msg);
+
+        let mut visitor = ContractVisitor::new(file.clone());
+        syn::visit::visit_file(&mut visitor, &ast);
+        for def in visitor.structs {
+            if contract.find_struct(&def.name).is_none() {
+                contract.structs.push(def);
+            }
+        }
+        source_map.entry(file).or_insert(source);
+    }
+}
+
+/// Enum at `path` (`crate_name::module::Enum`) in a path dependency of the crate
+fn locate_enum(crate_path: &Path, path: &[String]) -> Option<Located> {
+    let (krate, rest) = path.split_first()?;
+    let (name, module) = rest.split_last()?;
+    if matches!(krate.as_str(), "crate" | "self" | "super") {
+        return None;
+    }
+    let root = path_dependency(crate_path, krate).ok()?;
+    let lib = root.join("src").join("lib.rs");
+    find_enum(&lib, module.to_vec(), name, 0).or_else(|| find_unique_enum(&root, name))
+}
+
+/// Enum `name` declared in, or re-exported by, the module at `module` below
+/// the crate root file `lib`
+fn find_enum(lib: &Path, module: Vec<String>, name: &str, depth: usize) -> Option<Located> {
+    if depth > MAX_REEXPORT_DEPTH {
+        return None;
+    }
+    let file = module_file(lib, &module)?;
+    let source = std::fs::read_to_string(&file).ok()?;
+    let ast = syn::parse_file(&source).ok()?;
+    let declared = ast.items.iter().find_map(|item| match item {
+        syn::Item::Enum(e) if e.ident == name => Some(e.clone()),
+        _ => None,
+    });
+    if let Some(item) = declared {
+        return Some((file, source, ast, item));
+    }
+
+    // Follow `pub use` re-exports within the crate
+    for import in file_imports(&ast) {
+        let (mut target, target_name) = match import.alias.as_str() {
+            "*" => (import.path, name.to_string()),
+            alias if alias == name => {
+                let mut path = import.path;
+                let target_name = path.pop()?;
+                (path, target_name)
+            }
+            _ => continue,
+        };
+        let target_module = match target.first().map(String::as_str) {
+            Some("crate") => target.split_off(1),
+            Some("self") => [&module[..], &target[1..]].concat(),
+            Some("super") if !module.is_empty() => {
+                [&module[..module.len() - 1], &target[1..]].concat()
+            }
+            Some(first)
+                if module_file(lib, &[&module[..], &[first.to_string()]].concat()).is_some() =>
+            {
+                [&module[..], &target[..]].concat()
+            }
+            // Another crate, or an empty path
+            _ => continue,
+        };
+        if let Some(found) = find_enum(lib, target_module, &target_name, depth + 1) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// File of the module at `module` below the crate root file `lib`
+fn module_file(lib: &Path, module: &[String]) -> Option<PathBuf> {
+    let mut file = lib.to_path_buf();
+    for segment in module {
+        let is_dir_owner = matches!(
+            file.file_name().and_then(|n| n.to_str()),
+            Some("lib.rs" | "main.rs" | "mod.rs")
+        );
+        let dir = if is_dir_owner {
+            file.parent()?.to_path_buf()
+        } else {
+            file.with_extension("")
+        };
+        let flat = dir.join(format!("{segment}.rs"));
+        file = if flat.is_file() {
+            flat
+        } else {
+            dir.join(segment).join("mod.rs")
+        };
+    }
+    file.is_file().then_some(file)
+}
+
+/// The only top-level enum named `name` anywhere in the crate at `root`, for
+/// module layouts the path walk can't follow (inline modules, `#[path]`)
+fn find_unique_enum(root: &Path, name: &str) -> Option<Located> {
+    let mut found = Vec::new();
+    for file in discover_rs_files(root, &DiscoveryOptions::default()).ok()? {
+        let Ok(source) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let Ok(ast) = syn::parse_file(&source) else {
+            continue;
+        };
+        let item = ast.items.iter().find_map(|item| match item {
+            syn::Item::Enum(e) if e.ident == name => Some(e.clone()),
+            _ => None,
+        });
+        if let Some(item) = item {
+            found.push((file, source, ast, item));
+        }
+    }
+    if found.len() == 1 {
+        found.pop()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{analyze_crate_cached, MessageKind};
+
+    #[test]
+    fn test_entry_point_messages_resolved_from_sibling_package() {
+        let dir = std::env::temp_dir().join("cosmwasm-guard-test-imports");
+        let _ = std::fs::remove_dir_all(&dir);
+        for (file, content) in [
+            (
+                "contracts/vault/Cargo.toml",
+                "[package]\nname = \"vault\"\n\n[dependencies]\n\
+                 my-protocol = { path = \"../../packages/my-protocol\" }\n",
+            ),
+            (
+                "contracts/vault/src/contract.rs",
+                r#"
+use my_protocol::vault::{ExecuteMsg, InstantiateMsg};
+use my_protocol::VaultQuery as QueryMsg;
+
+#[entry_point]
+pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg)
+    -> StdResult<Response> { Ok(Response::new()) }
+
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+    -> StdResult<Response> { Ok(Response::new()) }
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> { todo!() }
+"#,
+            ),
+            (
+                "packages/my-protocol/Cargo.toml",
+                "[package]\nname = \"my-protocol\"\n",
+            ),
+            (
+                "packages/my-protocol/src/lib.rs",
+                "pub mod staking;\npub mod vault;\n\npub use crate::vault::QueryMsg as VaultQuery;\n",
+            ),
+            (
+                "packages/my-protocol/src/vault.rs",
+                r#"
+pub struct InstantiateMsg { pub owner: String }
+pub enum ExecuteMsg { Deposit {}, Withdraw { amount: Uint128 } }
+pub enum QueryMsg { Balance { address: String } }
+"#,
+            ),
+            (
+                "packages/my-protocol/src/staking.rs",
+                "pub enum ExecuteMsg { Bond {}, Unbond {} }\n",
+            ),
+        ] {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let analysis = analyze_crate_cached(&dir.join("contracts/vault"), None).unwrap();
+        let enums = &analysis.contract.message_enums;
+
+        let execute = enums.iter().find(|e| e.name == "ExecuteMsg").unwrap();
+        let variants: Vec<&str> = execute.variants.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(variants, ["Deposit", "Withdraw"]);
+        assert_eq!(execute.kind, MessageKind::Execute);
+        assert!(execute
+            .span
+            .file
+            .ends_with("packages/my-protocol/src/vault.rs"));
+
+        let query = enums.iter().find(|e| e.name == "QueryMsg").unwrap();
+        assert_eq!(query.kind, MessageKind::Query);
+        assert_eq!(query.variants[0].name, "Balance");
+
+        // InstantiateMsg is a struct, picked up with the file of ExecuteMsg
+        assert_eq!(enums.len(), 2);
+        assert!(analysis.contract.find_struct("InstantiateMsg").is_some());
+        assert!(analysis
+            .source_map
+            .keys()
+            .any(|f| f.ends_with("packages/my-protocol/src/vault.rs")));
+    }
+}
@@ -1,10 +1,20 @@
 pub mod contract_info;
 pub mod crate_analyzer;
+pub mod deps;
+pub mod discovery;
+pub mod imports;
 pub mod parser;
+pub mod spans;
+pub mod targets;
 pub mod utils;
 pub mod visitor;
 
 pub use contract_info::*;
-pub use crate_analyzer::{analyze_crate, analyze_crate_cached, CrateAnalysis};
+pub use crate_analyzer::{
+    analyze_crate, analyze_crate_cached, analyze_crate_with_options, analyze_sources,
+    CrateAnalysis,
+};
+pub use discovery::DiscoveryOptions;
+pub use targets::TargetSelection;
 pub use parser::{parse_file, parse_source};
 pub use visitor::ContractVisitor;
@@ -0,0 +1,118 @@
+//! Source positions of syn nodes.
+//!
+//! With `span-locations`, a proc-macro2 span is a character range into a
+//! source map private to the thread that parsed the file: `Span::start()`
+//! or `Span::byte_range()` anywhere else panics. [`SpanIndex`] resolves spans
+//! on that thread; [`crate::detector::AnalysisContext::span_of`] forwards
+//! requests from detectors running on other threads to it.
+
+use std::path::PathBuf;
+
+use proc_macro2::Span;
+
+use super::contract_info::SourceSpan;
+
+/// Byte offset of the 1-based `line` and 0-based character `column` in
+/// `source`. Columns past the end of the line are clamped to it.
+pub fn byte_offset(source: &str, line: usize, column: usize) -> Option<usize> {
+    let start = match line {
+        0 => return None,
+        1 => 0,
+        _ => source.match_indices('\n').nth(line - 2)?.0 + 1,
+    };
+    let text = &source[start..];
+    let text = &text[..text.find('\n').unwrap_or(text.len())];
+    let within = text
+        .char_indices()
+        .nth(column)
+        .map_or(text.len(), |(i, _)| i);
+    Some(start + within)
+}
+
+/// Resolves spans of the parsed files. Only usable on the thread that
+/// parsed them, and so neither `Send` nor `Sync`.
+#[derive(Default)]
+pub struct SpanIndex {
+    /// A span inside each file, which tells the file other spans belong to
+    files: Vec<(PathBuf, Span)>,
+}
+
+impl SpanIndex {
+    /// Index the files of `asts`. Must be called on the thread that parsed them.
+    pub fn new(asts: &[(PathBuf, syn::File)]) -> Self {
+        let files = asts
+            .iter()
+            .filter_map(|(path, ast)| {
+                let anchor = match ast.attrs.first() {
+                    Some(attr) => attr.pound_token.span,
+                    None => syn::spanned::Spanned::span(ast.items.first()?),
+                };
+                Some((path.clone(), anchor))
+            })
+            .collect();
+        Self { files }
+    }
+
+    /// Location from the start of `first` to the end of `last`; None for
+    /// generated tokens and files that were not indexed
+    pub fn locate(&self, first: Span, last: Span) -> Option<SourceSpan> {
+        let (path, _) = self
+            .files
+            .iter()
+            .find(|(_, anchor)| anchor.join(first).is_some() && anchor.join(last).is_some())?;
+        let (start, end) = (first.start(), last.end());
+        Some(SourceSpan {
+            file: path.clone(),
+            start_line: start.line,
+            end_line: end.line,
+            start_col: start.column,
+            end_col: end.column,
+            start_byte: first.byte_range().start,
+            end_byte: last.byte_range().end,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::parse_source;
+    use quote::ToTokens;
+    use syn::spanned::Spanned;
+
+    #[test]
+    fn test_locates_spans_across_files() {
+        let first = "// ñ\nfn a() {}\n";
+        let second = "// héllo\nfn main() {\n    let x = vec.len();\n}\n";
+        let ast_a = parse_source(first).unwrap();
+        let ast_b = parse_source(second).unwrap();
+        let asts = vec![
+            (PathBuf::from("a.rs"), ast_a),
+            (PathBuf::from("b.rs"), ast_b),
+        ];
+        let index = SpanIndex::new(&asts);
+
+        let syn::Item::Fn(main) = &asts[1].1.items[0] else {
+            panic!("expected fn");
+        };
+        let syn::Stmt::Local(local) = &main.block.stmts[0] else {
+            panic!("expected let");
+        };
+        let init = &local.init.as_ref().unwrap().expr;
+        let tokens: Vec<_> = init.to_token_stream().into_iter().collect();
+        let span = index
+            .locate(tokens[0].span(), tokens.last().unwrap().span())
+            .unwrap();
+
+        assert_eq!(span.file, PathBuf::from("b.rs"));
+        let (start, end) = (init.span().start(), init.span().end());
+        assert_eq!((span.start_line, span.start_col), (3, 12));
+        assert_eq!(
+            (span.start_line, span.start_col),
+            (start.line, start.column)
+        );
+        assert_eq!((span.end_line, span.end_col), (end.line, end.column));
+        assert_eq!(&second[span.start_byte..span.end_byte], "vec.len()");
+        assert_eq!(index.locate(Span::call_site(), Span::call_site()), None);
+    }
+}
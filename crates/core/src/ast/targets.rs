@@ -0,0 +1,295 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// Which Cargo target of a crate to analyze
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TargetSelection {
+    /// The lib target when the crate has one (contracts are libs), otherwise everything
+    #[default]
+    Auto,
+    /// Every source file, whatever target it belongs to
+    All,
+    /// Only the lib target; binaries (schema generators, scripts) are skipped
+    Lib,
+    /// Only the named binary target
+    Bin(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BinTarget {
+    name: String,
+    /// Root file, relative to the crate root with `/` separators
+    root: String,
+}
+
+/// Targets declared in, or auto-discovered for, a crate's `Cargo.toml`,
+/// following Cargo's target auto-discovery rules
+#[derive(Debug, Clone, Default)]
+pub struct CargoTargets {
+    lib: Option<String>,
+    bins: Vec<BinTarget>,
+}
+
+/// Files belonging to the selected target, by crate-relative path
+#[derive(Debug, Clone)]
+pub enum TargetFilter {
+    /// Keep only these root files and files below these module directories
+    Only {
+        roots: Vec<String>,
+        dirs: Vec<String>,
+    },
+    /// Drop these root files and files below these module directories
+    Except {
+        roots: Vec<String>,
+        dirs: Vec<String>,
+    },
+}
+
+impl TargetFilter {
+    /// Whether the file at crate-relative path `rel` belongs to the selection
+    pub fn keeps(&self, rel: &str) -> bool {
+        let covered = |roots: &[String], dirs: &[String]| {
+            roots.iter().any(|r| r == rel)
+                || dirs.iter().any(|d| {
+                    rel.strip_prefix(d.as_str())
+                        .is_some_and(|r| r.starts_with('/'))
+                })
+        };
+        match self {
+            Self::Only { roots, dirs } => covered(roots, dirs),
+            Self::Except { roots, dirs } => !covered(roots, dirs),
+        }
+    }
+
+    /// Root files that must be analyzed even if the directory walk did not reach them
+    pub fn required_roots(&self) -> &[String] {
+        match self {
+            Self::Only { roots, .. } => roots,
+            Self::Except { .. } => &[],
+        }
+    }
+}
+
+impl CargoTargets {
+    /// Read the targets of the crate at `crate_root`. Returns None when there is
+    /// no `Cargo.toml` (e.g. a loose directory of sources).
+    pub fn load(crate_root: &Path) -> Result<Option<Self>> {
+        let manifest_path = crate_root.join("Cargo.toml");
+        if !manifest_path.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Failed to read: {}", manifest_path.display()))?;
+        let manifest: toml::Table = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse: {}", manifest_path.display()))?;
+        Ok(Some(Self::from_manifest(&manifest, crate_root)))
+    }
+
+    fn from_manifest(manifest: &toml::Table, crate_root: &Path) -> Self {
+        let exists = |rel: &str| crate_root.join(rel).is_file();
+        let package = manifest.get("package").and_then(|p| p.as_table());
+        let package_name = package
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .unwrap_or_default();
+
+        let lib = match manifest.get("lib").and_then(|l| l.as_table()) {
+            Some(lib) => Some(
+                lib.get("path")
+                    .and_then(|p| p.as_str())
+                    .unwrap_or("src/lib.rs")
+                    .to_string(),
+            ),
+            None => exists("src/lib.rs").then(|| "src/lib.rs".to_string()),
+        };
+
+        let mut bins = Vec::new();
+        for bin in manifest
+            .get("bin")
+            .and_then(|b| b.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|b| b.as_table())
+        {
+            let Some(name) = bin.get("name").and_then(|n| n.as_str()) else {
+                continue;
+            };
+            let root = match bin.get("path").and_then(|p| p.as_str()) {
+                Some(path) => path.trim_start_matches("./").to_string(),
+                None => [
+                    format!("src/bin/{name}.rs"),
+                    format!("src/bin/{name}/main.rs"),
+                ]
+                .into_iter()
+                .find(|p| exists(p))
+                .unwrap_or_else(|| "src/main.rs".to_string()),
+            };
+            bins.push(BinTarget {
+                name: name.to_string(),
+                root,
+            });
+        }
+
+        let autobins = package
+            .and_then(|p| p.get("autobins"))
+            .and_then(|a| a.as_bool())
+            .unwrap_or(true);
+        if autobins {
+            let mut discovered = Vec::new();
+            if exists("src/main.rs") {
+                discovered.push((package_name.to_string(), "src/main.rs".to_string()));
+            }
+            if let Ok(entries) = std::fs::read_dir(crate_root.join("src/bin")) {
+                let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
+                entries.sort_by_key(|e| e.file_name());
+                for entry in entries {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    if let Some(stem) = name.strip_suffix(".rs") {
+                        discovered.push((stem.to_string(), format!("src/bin/{name}")));
+                    } else if exists(&format!("src/bin/{name}/main.rs")) {
+                        discovered.push((name.clone(), format!("src/bin/{name}/main.rs")));
+                    }
+                }
+            }
+            for (name, root) in discovered {
+                if !bins.iter().any(|b| b.root == root || b.name == name) {
+                    bins.push(BinTarget { name, root });
+                }
+            }
+        }
+
+        Self { lib, bins }
+    }
+
+    /// Filter for `selection`, or None when every file should be analyzed
+    pub fn filter(&self, selection: &TargetSelection) -> Result<Option<TargetFilter>> {
+        match selection {
+            TargetSelection::All => Ok(None),
+            TargetSelection::Auto if self.lib.is_none() => Ok(None),
+            TargetSelection::Auto | TargetSelection::Lib => {
+                if self.lib.is_none() {
+                    anyhow::bail!("Crate has no lib target; use --bin <NAME> or --all-targets");
+                }
+                Ok(Some(self.except_bins(None)))
+            }
+            TargetSelection::Bin(name) => {
+                let Some(bin) = self.bins.iter().find(|b| &b.name == name) else {
+                    let known: Vec<&str> = self.bins.iter().map(|b| b.name.as_str()).collect();
+                    anyhow::bail!(
+                        "No bin target named `{name}` (available: {})",
+                        if known.is_empty() {
+                            "none".to_string()
+                        } else {
+                            known.join(", ")
+                        }
+                    );
+                };
+                // A `src/main.rs` binary shares `src/` with the lib, so select it
+                // by dropping everything that belongs to the other targets
+                match module_dir(&bin.root) {
+                    Some(dir) => Ok(Some(TargetFilter::Only {
+                        roots: vec![bin.root.clone()],
+                        dirs: vec![dir],
+                    })),
+                    None => {
+                        let mut filter = self.except_bins(Some(name));
+                        if let (Some(lib), TargetFilter::Except { roots, .. }) =
+                            (&self.lib, &mut filter)
+                        {
+                            roots.push(lib.clone());
+                        }
+                        Ok(Some(filter))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Drop every binary except `keep`, along with their module directories
+    fn except_bins(&self, keep: Option<&str>) -> TargetFilter {
+        let others = self.bins.iter().filter(|b| Some(b.name.as_str()) != keep);
+        let mut roots = Vec::new();
+        let mut dirs = vec!["src/bin".to_string()];
+        for bin in others {
+            roots.push(bin.root.clone());
+            dirs.extend(module_dir(&bin.root));
+        }
+        TargetFilter::Except { roots, dirs }
+    }
+}
+
+/// Directory holding a target root's submodules, unless that is `src/` itself
+fn module_dir(root: &str) -> Option<String> {
+    let (parent, file) = root.rsplit_once('/').unwrap_or(("", root));
+    let dir = match file {
+        "main.rs" | "mod.rs" | "lib.rs" => parent.to_string(),
+        _ => root.strip_suffix(".rs")?.to_string(),
+    };
+    (!dir.is_empty() && dir != "src").then_some(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(manifest: &str, files: &[&str]) -> CargoTargets {
+        let dir = tempfile::tempdir().unwrap();
+        for rel in files {
+            let file = dir.path().join(rel);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, "").unwrap();
+        }
+        CargoTargets::from_manifest(&toml::from_str(manifest).unwrap(), dir.path())
+    }
+
+    #[test]
+    fn test_auto_selects_lib_and_skips_bins() {
+        let t = targets(
+            "[package]\nname = \"contract\"\n",
+            &["src/lib.rs", "src/bin/schema.rs", "src/main.rs"],
+        );
+        let filter = t.filter(&TargetSelection::Auto).unwrap().unwrap();
+        assert!(filter.keeps("src/lib.rs"));
+        assert!(filter.keeps("src/contract.rs"));
+        assert!(!filter.keeps("src/bin/schema.rs"));
+        assert!(!filter.keeps("src/main.rs"));
+
+        let schema = t
+            .filter(&TargetSelection::Bin("schema".into()))
+            .unwrap()
+            .unwrap();
+        assert!(schema.keeps("src/bin/schema.rs"));
+        assert!(!schema.keeps("src/contract.rs"));
+
+        let main = t
+            .filter(&TargetSelection::Bin("contract".into()))
+            .unwrap()
+            .unwrap();
+        assert!(main.keeps("src/main.rs"));
+        assert!(!main.keeps("src/lib.rs"));
+        assert!(!main.keeps("src/bin/schema.rs"));
+    }
+
+    #[test]
+    fn test_explicit_targets_and_errors() {
+        let t = targets(
+            "[package]\nname = \"c\"\n[[bin]]\nname = \"gen\"\npath = \"tools/gen.rs\"\n",
+            &["tools/gen.rs"],
+        );
+        assert!(t.filter(&TargetSelection::Auto).unwrap().is_none());
+        assert!(t.filter(&TargetSelection::Lib).is_err());
+        let err = t
+            .filter(&TargetSelection::Bin("nope".into()))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("available: gen"), "{err}");
+
+        let gen = t
+            .filter(&TargetSelection::Bin("gen".into()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(gen.required_roots(), ["tools/gen.rs"]);
+        assert!(gen.keeps("tools/gen/helpers.rs"));
+    }
+}
@@ -1,6 +1,8 @@
 use std::path::Path;
 
-use super::contract_info::{EntryPointKind, SourceSpan, StorageType};
+use syn::punctuated::Punctuated;
+
+use super::contract_info::{EntryPointKind, SourceSpan, StorageType, TrustedCheck};
 
 /// Extract a type name from a syn::Type as a string
 pub fn type_to_string(ty: &syn::Type) -> String {
@@ -15,6 +17,187 @@ pub fn span_to_source_span(span: proc_macro2::Span, file: &Path) -> SourceSpan {
         end_line: span.end().line,
         start_col: span.start().column,
         end_col: span.end().column,
+        start_byte: span.byte_range().start,
+        end_byte: span.byte_range().end,
+    }
+}
+
+/// Span of a block from its opening to its closing brace
+pub fn block_to_source_span(block: &syn::Block, file: &Path) -> SourceSpan {
+    let (open, close) = (
+        block.brace_token.span.open(),
+        block.brace_token.span.close(),
+    );
+    SourceSpan {
+        file: file.to_path_buf(),
+        start_line: open.start().line,
+        end_line: close.end().line,
+        start_col: open.start().column,
+        end_col: close.end().column,
+        start_byte: open.byte_range().start,
+        end_byte: close.byte_range().end,
+    }
+}
+
+/// Derives generated by `#[cw_serde]`
+const CW_SERDE_DERIVES: [&str; 6] = [
+    "Serialize",
+    "Deserialize",
+    "Clone",
+    "Debug",
+    "PartialEq",
+    "JsonSchema",
+];
+
+/// Traits derived by a set of attributes, by last path segment.
+/// `#[cw_serde]` is expanded to the derives it generates.
+pub fn extract_derives(attrs: &[syn::Attribute]) -> Vec<String> {
+    let mut derives = Vec::new();
+    for attr in attrs {
+        if attr.path().is_ident("derive") {
+            let paths = attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+            );
+            for path in paths.into_iter().flatten() {
+                if let Some(last) = path.segments.last() {
+                    derives.push(last.ident.to_string());
+                }
+            }
+        } else if attr
+            .path()
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "cw_serde")
+        {
+            derives.extend(CW_SERDE_DERIVES.map(String::from));
+        }
+    }
+    derives
+}
+
+/// Options set by `#[serde(...)]` attributes, by name: `skip`, `untagged`,
+/// `rename` (values are dropped)
+pub fn extract_serde_attrs(attrs: &[syn::Attribute]) -> Vec<String> {
+    let mut options = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                options.push(ident.to_string());
+            }
+            if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                content.parse::<proc_macro2::TokenStream>()?;
+            }
+            Ok(())
+        });
+    }
+    options
+}
+
+/// Checks declared by `#[cosmwasm_guard::assume(sender, funds)]`, its
+/// `#[cfg_attr(<cfg>, cosmwasm_guard::assume(..))]` form that compiles without
+/// this crate, or a `/// cosmwasm-guard: assume(sender)` doc comment line
+pub fn extract_assumed_checks(attrs: &[syn::Attribute]) -> Vec<TrustedCheck> {
+    let mut checks = Vec::new();
+    for attr in attrs {
+        let tokens = if is_assume_path(attr.path()) {
+            attr.meta.require_list().ok().map(|l| l.tokens.to_string())
+        } else if attr.path().is_ident("cfg_attr") {
+            attr.meta.require_list().ok().and_then(|l| {
+                let tokens = l.tokens.to_string();
+                let (_, rest) = tokens.split_once("assume")?;
+                Some(rest.to_string())
+            })
+        } else if attr.path().is_ident("doc") {
+            match &attr.meta {
+                syn::Meta::NameValue(syn::MetaNameValue {
+                    value:
+                        syn::Expr::Lit(syn::ExprLit {
+                            lit: syn::Lit::Str(doc),
+                            ..
+                        }),
+                    ..
+                }) => doc
+                    .value()
+                    .trim()
+                    .strip_prefix("cosmwasm-guard:")
+                    .and_then(|rest| rest.trim().strip_prefix("assume"))
+                    .map(String::from),
+                _ => None,
+            }
+        } else {
+            None
+        };
+        let Some(tokens) = tokens else { continue };
+        let names = tokens.trim().trim_start_matches('(').trim_end_matches(')');
+        checks.extend(
+            names
+                .split(',')
+                .filter_map(|name| TrustedCheck::from_name(name.trim())),
+        );
+    }
+    checks.dedup();
+    checks
+}
+
+fn is_assume_path(path: &syn::Path) -> bool {
+    let segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    segments == ["cosmwasm_guard", "assume"]
+}
+
+/// Type names mentioned in a rendered type: `Vec<(Addr,Config)>` -> [Vec, Addr, Config]
+pub fn type_idents(type_name: &str) -> Vec<String> {
+    type_name
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Whether an attribute marks test-only code: `#[test]`, or a `#[cfg(..)]`
+/// whose predicate cannot hold outside `cfg(test)`, as `test` and
+/// `all(test, ..)` do. `not(test)`, `any(test, ..)` and options merely named
+/// like it (`feature = "testnet"`) leave the code in the contract.
+pub fn is_test_attr(attr: &syn::Attribute) -> bool {
+    attr.path().is_ident("test")
+        || attr.path().is_ident("cfg")
+            && attr
+                .parse_args::<syn::Meta>()
+                .is_ok_and(|predicate| cfg_value(&predicate, false) == Some(false))
+}
+
+/// Value of a cfg predicate for a given `test`; None when it also depends on
+/// other options (features, the target)
+fn cfg_value(predicate: &syn::Meta, test: bool) -> Option<bool> {
+    let syn::Meta::List(list) = predicate else {
+        return predicate.path().is_ident("test").then_some(test);
+    };
+    let args = list
+        .parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)
+        .ok()?;
+    let values: Vec<Option<bool>> = args.iter().map(|arg| cfg_value(arg, test)).collect();
+    if list.path.is_ident("not") {
+        match values[..] {
+            [value] => value.map(|v| !v),
+            _ => None,
+        }
+    } else if list.path.is_ident("all") {
+        if values.contains(&Some(false)) {
+            Some(false)
+        } else {
+            values.iter().all(|v| *v == Some(true)).then_some(true)
+        }
+    } else if list.path.is_ident("any") {
+        if values.contains(&Some(true)) {
+            Some(true)
+        } else {
+            values.iter().all(|v| *v == Some(false)).then_some(false)
+        }
+    } else {
+        None
     }
 }
 
@@ -88,6 +271,8 @@ pub fn detect_storage_type(path: &syn::Path) -> Option<StorageType> {
         "Item" => Some(StorageType::Item),
         "Map" => Some(StorageType::Map),
         "IndexedMap" => Some(StorageType::IndexedMap),
+        "SnapshotItem" => Some(StorageType::SnapshotItem),
+        "SnapshotMap" => Some(StorageType::SnapshotMap),
         _ => None,
     }
 }
@@ -126,3 +311,28 @@ pub fn infer_message_kind(enum_name: &str) -> super::contract_info::MessageKind
         MessageKind::Unknown
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_test_attr_reads_cfg_predicates() {
+        let is_test = |attr: syn::Attribute| is_test_attr(&attr);
+        assert!(is_test(syn::parse_quote!(#[test])));
+        assert!(is_test(syn::parse_quote!(#[cfg(test)])));
+        assert!(is_test(
+            syn::parse_quote!(#[cfg(all(test, feature = "mock"))])
+        ));
+        assert!(is_test(syn::parse_quote!(#[cfg(not(not(test)))])));
+        assert!(is_test(syn::parse_quote!(#[cfg(any(test, test))])));
+        assert!(!is_test(syn::parse_quote!(#[cfg(not(test))])));
+        assert!(!is_test(syn::parse_quote!(#[cfg(feature = "testnet")])));
+        assert!(!is_test(
+            syn::parse_quote!(#[cfg(any(test, feature = "mock"))])
+        ));
+        assert!(!is_test(syn::parse_quote!(#[cfg(all(not(test), unix))])));
+        assert!(!is_test(syn::parse_quote!(#[cfg(testing)])));
+        assert!(!is_test(syn::parse_quote!(#[allow(test)])));
+    }
+}
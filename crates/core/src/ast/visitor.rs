@@ -1,5 +1,6 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use syn::spanned::Spanned;
 use syn::visit::Visit;
 
 use super::contract_info::*;
@@ -7,10 +8,11 @@ use super::utils;
 
 /// AST visitor that extracts CosmWasm contract information from a parsed file
 pub struct ContractVisitor {
-    file_path: PathBuf,
+    pub(crate) file_path: PathBuf,
     pub entry_points: Vec<EntryPoint>,
     pub message_enums: Vec<MessageEnum>,
     pub state_items: Vec<StateItem>,
+    pub structs: Vec<StructInfo>,
     pub functions: Vec<FunctionInfo>,
 }
 
@@ -21,6 +23,7 @@ impl ContractVisitor {
             entry_points: Vec::new(),
             message_enums: Vec::new(),
             state_items: Vec::new(),
+            structs: Vec::new(),
             functions: Vec::new(),
         }
     }
@@ -31,15 +34,8 @@ impl ContractVisitor {
         let mut visitor = ContractVisitor::new(file_path.clone());
         syn::visit::visit_file(&mut visitor, &ast);
 
-        let mut info = ContractInfo::new(file_path.clone());
-        info.merge_from_visitor(
-            visitor.entry_points,
-            visitor.message_enums,
-            visitor.state_items,
-            visitor.functions,
-            file_path,
-            ast,
-        );
+        let mut info = ContractInfo::new(file_path);
+        info.merge_from_visitor(visitor, ast);
         info
     }
 }
@@ -101,6 +97,8 @@ impl<'ast> Visit<'ast> for ContractVisitor {
             params,
             return_type,
             span,
+            assumes: utils::extract_assumed_checks(&node.attrs),
+            body_span: Some(utils::block_to_source_span(&node.block, &self.file_path)),
             body: Some((*node.block).clone()),
         });
 
@@ -117,53 +115,45 @@ impl<'ast> Visit<'ast> for ContractVisitor {
             return;
         }
 
-        let kind = utils::infer_message_kind(&enum_name);
-        let span = utils::span_to_source_span(node.ident.span(), &self.file_path);
+        self.message_enums.push(message_enum(node, &self.file_path));
+
+        syn::visit::visit_item_enum(self, node);
+    }
 
-        let variants: Vec<MessageVariant> = node
-            .variants
+    /// Visit struct items — record fields and derives of every struct definition
+    fn visit_item_struct(&mut self, node: &'ast syn::ItemStruct) {
+        let fields = node
+            .fields
             .iter()
-            .map(|v| {
-                let fields: Vec<FieldInfo> = match &v.fields {
-                    syn::Fields::Named(named) => named
-                        .named
-                        .iter()
-                        .map(|f| FieldInfo {
-                            name: f.ident.as_ref().map_or_else(String::new, |i| i.to_string()),
-                            type_name: utils::type_to_string(&f.ty),
-                        })
-                        .collect(),
-                    syn::Fields::Unnamed(unnamed) => unnamed
-                        .unnamed
-                        .iter()
-                        .enumerate()
-                        .map(|(i, f)| FieldInfo {
-                            name: format!("_{i}"),
-                            type_name: utils::type_to_string(&f.ty),
-                        })
-                        .collect(),
-                    syn::Fields::Unit => Vec::new(),
+            .enumerate()
+            .map(|(i, f)| {
+                let (name, span) = match &f.ident {
+                    Some(ident) => (ident.to_string(), ident.span()),
+                    None => (format!("_{i}"), f.ty.span()),
                 };
-                MessageVariant {
-                    name: v.ident.to_string(),
-                    fields,
+                StructField {
+                    name,
+                    type_name: utils::type_to_string(&f.ty),
+                    serde: utils::extract_serde_attrs(&f.attrs),
+                    span: utils::span_to_source_span(span, &self.file_path),
                 }
             })
             .collect();
 
-        self.message_enums.push(MessageEnum {
-            name: enum_name,
-            kind,
-            variants,
-            span,
+        self.structs.push(StructInfo {
+            name: node.ident.to_string(),
+            fields,
+            derives: utils::extract_derives(&node.attrs),
+            serde: utils::extract_serde_attrs(&node.attrs),
+            span: utils::span_to_source_span(node.ident.span(), &self.file_path),
         });
 
-        syn::visit::visit_item_enum(self, node);
+        syn::visit::visit_item_struct(self, node);
     }
 
     /// Visit const items — detect Item<T> and Map<K,V> storage declarations
     fn visit_item_const(&mut self, node: &'ast syn::ItemConst) {
-        // Check if type is Item<_>, Map<_, _>, or IndexedMap<_, _>
+        // Check if type is Item<_>, Map<_, _>, IndexedMap<_, _> or a Snapshot variant
         if let syn::Type::Path(type_path) = node.ty.as_ref() {
             if let Some(storage_type) = utils::detect_storage_type(&type_path.path) {
                 let const_name = node.ident.to_string();
@@ -171,8 +161,10 @@ impl<'ast> Visit<'ast> for ContractVisitor {
                 let generic_args = utils::extract_generic_args(&type_path.path);
 
                 let (key_type, value_type) = match storage_type {
-                    StorageType::Item => (None, generic_args.first().cloned().unwrap_or_default()),
-                    StorageType::Map | StorageType::IndexedMap => {
+                    StorageType::Item | StorageType::SnapshotItem => {
+                        (None, generic_args.first().cloned().unwrap_or_default())
+                    }
+                    StorageType::Map | StorageType::IndexedMap | StorageType::SnapshotMap => {
                         let key = generic_args.first().cloned();
                         let val = generic_args.get(1).cloned().unwrap_or_default();
                         (key, val)
@@ -232,6 +224,8 @@ impl<'ast> Visit<'ast> for ContractVisitor {
                     params,
                     return_type,
                     span,
+                    assumes: utils::extract_assumed_checks(&method.attrs),
+                    body_span: Some(utils::block_to_source_span(&method.block, &self.file_path)),
                     body: Some(method.block.clone()),
                 });
             }
@@ -243,6 +237,57 @@ impl<'ast> Visit<'ast> for ContractVisitor {
 
 /// Try to extract a string literal storage key from a constructor expression
 /// e.g., `Item::new("config")` -> Some("config")
+/// Message enum described by an enum item, with its kind inferred from the name
+pub(crate) fn message_enum(node: &syn::ItemEnum, file: &Path) -> MessageEnum {
+    let enum_name = node.ident.to_string();
+    let kind = utils::infer_message_kind(&enum_name);
+    let span = utils::span_to_source_span(node.ident.span(), file);
+
+    let variants: Vec<MessageVariant> = node
+        .variants
+        .iter()
+        .map(|v| {
+            let fields: Vec<FieldInfo> = match &v.fields {
+                syn::Fields::Named(named) => named
+                    .named
+                    .iter()
+                    .map(|f| FieldInfo {
+                        name: f.ident.as_ref().map_or_else(String::new, |i| i.to_string()),
+                        type_name: utils::type_to_string(&f.ty),
+                    })
+                    .collect(),
+                syn::Fields::Unnamed(unnamed) => unnamed
+                    .unnamed
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| FieldInfo {
+                        name: format!("_{i}"),
+                        type_name: utils::type_to_string(&f.ty),
+                    })
+                    .collect(),
+                syn::Fields::Unit => Vec::new(),
+            };
+            MessageVariant {
+                name: v.ident.to_string(),
+                fields,
+                returns: v
+                    .attrs
+                    .iter()
+                    .find(|a| a.path().is_ident("returns"))
+                    .and_then(|a| a.parse_args::<syn::Type>().ok())
+                    .map(|ty| utils::type_to_string(&ty)),
+            }
+        })
+        .collect();
+
+    MessageEnum {
+        name: enum_name,
+        kind,
+        variants,
+        span,
+    }
+}
+
 fn extract_storage_key_from_expr(expr: &syn::Expr) -> Option<String> {
     if let syn::Expr::Call(call) = expr {
         // Look for the first string literal argument
@@ -299,6 +344,25 @@ mod tests {
         assert_eq!(info.message_enums[0].variants[0].fields.len(), 2);
     }
 
+    #[test]
+    fn test_detect_query_returns_attribute() {
+        let source = r#"
+            #[derive(QueryResponses)]
+            pub enum QueryMsg {
+                #[returns(crate::msg::ConfigResponse)]
+                Config {},
+                Legacy {},
+            }
+        "#;
+        let info = parse_and_visit(source);
+        let variants = &info.message_enums[0].variants;
+        assert_eq!(
+            variants[0].returns.as_deref(),
+            Some("crate::msg::ConfigResponse")
+        );
+        assert_eq!(variants[1].returns, None);
+    }
+
     #[test]
     fn test_detect_state_items() {
         let source = r#"
@@ -368,4 +432,62 @@ mod tests {
         assert_eq!(info.entry_points.len(), 1);
         assert_eq!(info.entry_points[0].kind, EntryPointKind::Query);
     }
+
+    #[test]
+    fn test_extract_struct_definitions() {
+        let source = r#"
+            #[derive(Serialize, Deserialize, Clone)]
+            pub struct Config {
+                pub owner: Addr,
+                pub fee: Option<Decimal>,
+            }
+
+            #[cw_serde]
+            pub struct Wrapper(pub Uint128);
+        "#;
+        let info = parse_and_visit(source);
+        assert_eq!(info.structs.len(), 2);
+
+        let config = info.find_struct("Config").unwrap();
+        assert_eq!(config.derives, vec!["Serialize", "Deserialize", "Clone"]);
+        assert_eq!(config.field("owner").unwrap().type_name, "Addr");
+        assert_eq!(config.field("fee").unwrap().type_name, "Option<Decimal>");
+
+        let wrapper = info.find_struct("Wrapper").unwrap();
+        assert!(wrapper.derives("JsonSchema"));
+        assert_eq!(wrapper.fields[0].name, "_0");
+    }
+
+    #[test]
+    fn test_extract_assumed_checks() {
+        let source = r#"
+            #[cosmwasm_guard::assume(sender, funds)]
+            fn assert_operator_paid(deps: Deps, info: &MessageInfo) -> StdResult<()> { Ok(()) }
+
+            #[cfg_attr(cosmwasm_guard, cosmwasm_guard::assume(sender))]
+            fn assert_operator(deps: Deps, info: &MessageInfo) -> StdResult<()> { Ok(()) }
+
+            /// Only the operator may call this.
+            /// cosmwasm-guard: assume(funds)
+            fn check_payment(info: &MessageInfo) -> StdResult<Uint128> { Ok(Uint128::zero()) }
+
+            fn helper() {}
+        "#;
+        let info = parse_and_visit(source);
+        let assumes = |name: &str| {
+            info.functions
+                .iter()
+                .find(|f| f.name == name)
+                .unwrap()
+                .assumes
+                .clone()
+        };
+        assert_eq!(
+            assumes("assert_operator_paid"),
+            [TrustedCheck::Sender, TrustedCheck::Funds]
+        );
+        assert_eq!(assumes("assert_operator"), [TrustedCheck::Sender]);
+        assert_eq!(assumes("check_payment"), [TrustedCheck::Funds]);
+        assert!(assumes("helper").is_empty());
+    }
 }
@@ -0,0 +1,165 @@
+//! Code copied unchanged from widely audited upstream contracts. Functions are
+//! fingerprinted by their signature and body tokens (so formatting and
+//! comments do not matter) and matched against the bundled fingerprints of
+//! the cw-plus contracts. Findings inside a match are labeled as inherited, so
+//! reviewers can focus on the custom delta.
+
+use quote::ToTokens;
+use sha2::{Digest, Sha256};
+
+use crate::ast::FunctionInfo;
+use crate::detector::AnalysisContext;
+use crate::finding::Finding;
+use crate::ranking::enclosing_function_info;
+
+/// Release the bundled fingerprints were taken from
+pub const CW_PLUS_RELEASE: &str = "cw-plus v2.0.0";
+
+/// `(fingerprint, package, function)` of the non-test functions of the
+/// cw-plus contracts
+const CW_PLUS: &[(&str, &str, &str)] = &[
+    ("def9c346844ec624", "cw1-whitelist", "instantiate"),
+    ("d32151bb66984442", "cw1-whitelist", "map_validate"),
+    ("2fe057c716d2e28d", "cw1-whitelist", "execute"),
+    ("02a827c11ddf5286", "cw1-whitelist", "execute_execute"),
+    ("c3e2292aa95780a0", "cw1-whitelist", "execute_freeze"),
+    ("811422e6c151c67d", "cw1-whitelist", "execute_update_admins"),
+    ("470c7658b86b59e0", "cw1-whitelist", "can_execute"),
+    ("4b316dd8d6655777", "cw1-whitelist", "query"),
+    ("8de4b8b5f6c5f90c", "cw1-whitelist", "query_admin_list"),
+    ("34a8f65271e61df0", "cw1-whitelist", "query_can_execute"),
+    ("de48e0d992fab9b1", "cw20-base", "verify_xml_preamble"),
+    ("8c9950bcbe02fb4d", "cw20-base", "verify_xml_logo"),
+    ("fa2d6a41c8d50cad", "cw20-base", "verify_png_logo"),
+    ("7ada302125e71802", "cw20-base", "verify_logo"),
+    ("f3aa4622c57d25e1", "cw20-base", "instantiate"),
+    ("eef77806b0f23d61", "cw20-base", "create_accounts"),
+    ("4553490e9e6afe6f", "cw20-base", "validate_accounts"),
+    ("35158c754d308604", "cw20-base", "execute"),
+    ("4a71558890719c7f", "cw20-base", "execute_transfer"),
+    ("8994b0ade43afaee", "cw20-base", "execute_burn"),
+    ("082c89f441970dad", "cw20-base", "execute_mint"),
+    ("50936362e5c4ae18", "cw20-base", "execute_send"),
+    ("25440a79e106e473", "cw20-base", "execute_update_minter"),
+    ("0453b0ecb846dd75", "cw20-base", "execute_update_marketing"),
+    ("419dd4f7d38af00f", "cw20-base", "execute_upload_logo"),
+    ("03b480d00b8003c1", "cw20-base", "query"),
+    ("32662a43c7e1224b", "cw20-base", "query_balance"),
+    ("5497212132e4a1c4", "cw20-base", "query_token_info"),
+    ("09d8208134ea218a", "cw20-base", "query_minter"),
+    ("ecf33e744536df26", "cw20-base", "query_marketing_info"),
+    ("c00d43451923775b", "cw20-base", "query_download_logo"),
+    ("4982d83ee94558fa", "cw20-base", "migrate"),
+    ("526f8d706d3daf36", "cw20-ics20", "instantiate"),
+    ("cb060657fcc28892", "cw20-ics20", "execute"),
+    ("d17a2cd82e1cbe52", "cw20-ics20", "execute_receive"),
+    ("282df99bdd879574", "cw20-ics20", "execute_transfer"),
+    ("298f9354be74eb6e", "cw20-ics20", "execute_allow"),
+    ("cf1adf01bc55d7e9", "cw20-ics20", "migrate"),
+    ("69c22d9ec4cb23ef", "cw20-ics20", "from_semver"),
+    ("ffec4c62171553d3", "cw20-ics20", "query"),
+    ("e5b25c80b17d0900", "cw20-ics20", "query_port"),
+    ("a656cf5b40e728d0", "cw20-ics20", "query_list"),
+    ("1135757570cace66", "cw20-ics20", "query_channel"),
+    ("8a15671bccacd6de", "cw20-ics20", "query_config"),
+    ("528cdf5323215263", "cw20-ics20", "query_allowed"),
+    ("d58343ab8b47813a", "cw20-ics20", "list_allowed"),
+    ("0eb4ea295167caef", "cw3-fixed-multisig", "instantiate"),
+    ("56f4220865969da4", "cw3-fixed-multisig", "execute"),
+    ("50d83ba3da51d45a", "cw3-fixed-multisig", "execute_propose"),
+    ("98b8a62c9b5b7094", "cw3-fixed-multisig", "execute_vote"),
+    ("61f74d665274742a", "cw3-fixed-multisig", "execute_execute"),
+    ("60adf919154dd343", "cw3-fixed-multisig", "execute_close"),
+    ("48be276d09be5c19", "cw3-fixed-multisig", "query"),
+    ("aa241747ef2a61f8", "cw3-fixed-multisig", "query_threshold"),
+    ("3240500b204f1b2e", "cw3-fixed-multisig", "query_proposal"),
+    ("0612a22f7eac2bb8", "cw3-fixed-multisig", "list_proposals"),
+    (
+        "47f79b41e42d359c",
+        "cw3-fixed-multisig",
+        "reverse_proposals",
+    ),
+    ("0f727ae1c264c33c", "cw3-fixed-multisig", "map_proposal"),
+    ("233c048c8394234f", "cw3-fixed-multisig", "query_vote"),
+    ("8ceb74b6acf27766", "cw3-fixed-multisig", "list_votes"),
+    ("e9132f4b02a99ffd", "cw3-fixed-multisig", "query_voter"),
+    ("c553727dfee82c2a", "cw3-fixed-multisig", "list_voters"),
+    ("3712dc522f83a07c", "cw4-group", "instantiate"),
+    ("85713686179de582", "cw4-group", "create"),
+    ("4bbf84a554fa467f", "cw4-group", "execute"),
+    ("525f98ad26008a33", "cw4-group", "execute_update_members"),
+    ("88452c66e5b3328f", "cw4-group", "update_members"),
+    ("9154fc1d1c4e6280", "cw4-group", "query"),
+    ("77f25bf02124c0ba", "cw4-group", "query_total_weight"),
+    ("1d3b427de391c777", "cw4-group", "query_member"),
+    ("b79d719a358c7e4b", "cw4-group", "query_list_members"),
+    ("65a3760eb943f5e3", "cw4-stake", "instantiate"),
+    ("66b2258b6ec8ec62", "cw4-stake", "execute"),
+    ("7cc43dba782520aa", "cw4-stake", "execute_bond"),
+    ("5e9b4c220f2c8ebe", "cw4-stake", "execute_receive"),
+    ("b9b4386d3f8017f4", "cw4-stake", "execute_unbond"),
+    ("be84f1dff6ebe5b8", "cw4-stake", "must_pay_funds"),
+    ("070b459837fa0005", "cw4-stake", "update_membership"),
+    ("702623349dacc3f3", "cw4-stake", "calc_weight"),
+    ("144b3deef1f2ea09", "cw4-stake", "execute_claim"),
+    ("410810fae86d04f5", "cw4-stake", "coin_to_string"),
+    ("c64687bdbde334e2", "cw4-stake", "query"),
+    ("2a55f40d74b06836", "cw4-stake", "query_total_weight"),
+    ("86d581e49d18ef5c", "cw4-stake", "query_staked"),
+    ("1d3b427de391c777", "cw4-stake", "query_member"),
+    ("d39140a3918a17ee", "cw4-stake", "list_members"),
+];
+
+/// Fingerprint of `func`: its name, parameters, return type and body tokens
+pub fn fingerprint(func: &FunctionInfo) -> Option<String> {
+    let body = func.body.as_ref()?;
+    let mut hasher = Sha256::new();
+    hasher.update(func.name.as_bytes());
+    for param in &func.params {
+        hasher.update(format!("|{}:{}", param.name, param.type_name).as_bytes());
+    }
+    hasher.update(format!("|{}|", func.return_type.as_deref().unwrap_or("")).as_bytes());
+    hasher.update(body.to_token_stream().to_string().as_bytes());
+    Some(format!("{:x}", hasher.finalize())[..16].to_string())
+}
+
+/// Audited upstream function `func` is identical to, e.g.
+/// `cw-plus v2.0.0 cw20-base::execute_transfer`
+pub fn origin(func: &FunctionInfo) -> Option<String> {
+    let fingerprint = fingerprint(func)?;
+    CW_PLUS
+        .iter()
+        .find(|(known, _, _)| *known == fingerprint)
+        .map(|(_, package, function)| format!("{CW_PLUS_RELEASE} {package}::{function}"))
+}
+
+/// Label findings inside functions identical to audited upstream code
+pub fn annotate(findings: &mut [Finding], ctx: &AnalysisContext) {
+    for finding in findings {
+        finding.inherited_from = enclosing_function_info(finding, ctx).and_then(origin);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use std::path::PathBuf;
+
+    fn fingerprints(source: &str) -> Vec<Option<String>> {
+        let contract =
+            ContractVisitor::extract(PathBuf::from("contract.rs"), parse_source(source).unwrap());
+        contract.functions.iter().map(fingerprint).collect()
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_formatting_and_comments() {
+        let original =
+            fingerprints("fn bump(count: u64) -> u64 {\n    // one more\n    count + 1\n}");
+        let reformatted = fingerprints("fn bump(count: u64) -> u64 { count+1 }");
+        let changed = fingerprints("fn bump(count: u64) -> u64 { count + 2 }");
+        assert!(original[0].is_some());
+        assert_eq!(original, reformatted);
+        assert_ne!(original, changed);
+    }
+}
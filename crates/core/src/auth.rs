@@ -0,0 +1,76 @@
+//! Registry of access-control helpers shared by detectors and guard analysis.
+
+use serde::{Deserialize, Serialize};
+
+/// Ecosystem helpers that authorize the sender, by function or method name
+const BUILTIN_HELPERS: &[&str] = &["assert_owner", "is_owner", "check_owner", "validate_owner"];
+
+/// Crates whose functions are all access-control checks
+const BUILTIN_CRATES: &[&str] = &["cw_ownable"];
+
+/// Assertion macros whose arguments may compare the sender
+const ASSERT_MACROS: &[&str] = &["ensure_eq", "ensure", "require", "assert_eq"];
+
+/// `[auth]` config: project helpers to recognize alongside the built-in ones
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// Function or method names, e.g. `["assert_operator", "only_gov"]`
+    pub helpers: Vec<String>,
+}
+
+/// Recognizes calls to helpers that authorize `info.sender`: the built-in
+/// `assert_owner` family and `cw_ownable`, `[auth] helpers` from the config,
+/// and functions annotated with `cosmwasm_guard::assume(sender)`
+#[derive(Debug, Clone, Default)]
+pub struct AuthRegistry {
+    helpers: Vec<String>,
+}
+
+impl AuthRegistry {
+    pub fn new(helpers: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            helpers: helpers.into_iter().collect(),
+        }
+    }
+
+    /// Whether a call to `path` (e.g. `cw_ownable::assert_owner`) is a helper
+    pub fn is_helper_call(&self, path: &str) -> bool {
+        let name = path.rsplit("::").next().unwrap_or(path);
+        self.is_helper_method(name)
+            || path
+                .split("::")
+                .any(|segment| BUILTIN_CRATES.contains(&segment))
+    }
+
+    /// Whether a method named `method` is a helper
+    pub fn is_helper_method(&self, method: &str) -> bool {
+        BUILTIN_HELPERS.contains(&method) || self.helpers.iter().any(|h| h == method)
+    }
+
+    /// Whether `name` is an assertion macro that may check the sender
+    pub fn is_assert_macro(&self, name: &str) -> bool {
+        ASSERT_MACROS.contains(&name)
+    }
+
+    /// Project helpers, without the built-in ones
+    pub fn helpers(&self) -> &[String] {
+        &self.helpers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_recognizes_builtin_and_configured_helpers() {
+        let registry = AuthRegistry::new(["only_gov".to_string()]);
+        assert!(registry.is_helper_call("assert_owner"));
+        assert!(registry.is_helper_call("cw_ownable::initialize_owner"));
+        assert!(registry.is_helper_call("crate::auth::only_gov"));
+        assert!(registry.is_helper_method("only_gov"));
+        assert!(!registry.is_helper_call("load_config"));
+        assert!(!AuthRegistry::default().is_helper_method("only_gov"));
+    }
+}
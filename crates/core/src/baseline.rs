@@ -0,0 +1,189 @@
+//! Accepted findings recorded in bulk so an existing codebase can adopt the
+//! analyzer at zero findings and only new findings fail CI afterwards.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::codes;
+use crate::finding::Finding;
+
+/// Baseline file picked up next to the config when `suppressions.baseline` is unset
+pub const DEFAULT_BASELINE: &str = ".cosmwasm-guard-baseline.toml";
+
+/// Reason written for entries that nobody has triaged yet
+pub const TODO_REASON: &str = "TODO: triage (added by `suppress --all-current`)";
+
+/// One accepted finding. Matched by detector, file and title rather than line
+/// number, so entries survive unrelated edits to the file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub detector: String,
+    pub file: String,
+    pub title: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    #[serde(default, rename = "finding")]
+    pub entries: Vec<BaselineEntry>,
+}
+
+/// Path as recorded in entries: `/` separators, no leading `./`
+fn entry_path(path: &Path) -> String {
+    let path = crate::ast::discovery::normalize_separators(path);
+    path.trim_start_matches("./").to_string()
+}
+
+impl BaselineEntry {
+    /// Untriaged entry accepting `finding`
+    fn todo(finding: &Finding) -> Self {
+        Self {
+            detector: finding.detector_name.clone(),
+            file: finding
+                .locations
+                .first()
+                .map(|loc| entry_path(&loc.file))
+                .unwrap_or_default(),
+            title: finding.title.clone(),
+            reason: TODO_REASON.to_string(),
+        }
+    }
+
+    fn matches(&self, finding: &Finding) -> bool {
+        codes::resolve(&self.detector) == finding.detector_name
+            && self.title == finding.title
+            && finding
+                .locations
+                .first()
+                .is_some_and(|loc| entry_path(&loc.file) == self.file)
+    }
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse baseline {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let body = toml::to_string(self)?;
+        let content = format!(
+            "# Accepted findings. Each entry suppresses one finding until it is fixed;\n\
+             # replace TODO reasons after triage. Regenerate with\n\
+             # `cosmwasm-guard suppress --all-current`, or add single findings\n\
+             # with `cosmwasm-guard suppress --finding <ID>`.\n\n{body}"
+        );
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write baseline {}", path.display()))
+    }
+
+    pub fn contains(&self, finding: &Finding) -> bool {
+        self.entries.iter().any(|e| e.matches(finding))
+    }
+
+    /// Baseline covering exactly `findings`. Reasons of entries already in
+    /// `self` are kept; new entries get a TODO reason and stale ones are dropped.
+    pub fn regenerate(&self, findings: &[Finding]) -> Self {
+        let mut entries: Vec<BaselineEntry> = Vec::new();
+        for finding in findings {
+            if entries.iter().any(|e| e.matches(finding)) {
+                continue;
+            }
+            let entry = match self.entries.iter().find(|e| e.matches(finding)) {
+                Some(existing) => existing.clone(),
+                None => BaselineEntry::todo(finding),
+            };
+            entries.push(entry);
+        }
+        let mut baseline = Self { entries };
+        baseline.sort();
+        baseline
+    }
+
+    /// Add an untriaged entry for `finding` unless one already covers it.
+    /// Returns whether an entry was added.
+    pub fn accept(&mut self, finding: &Finding) -> bool {
+        if self.contains(finding) {
+            return false;
+        }
+        self.entries.push(BaselineEntry::todo(finding));
+        self.sort();
+        true
+    }
+
+    fn sort(&mut self) {
+        self.entries.sort_by(|a, b| {
+            (&a.file, &a.detector, &a.title).cmp(&(&b.file, &b.detector, &b.title))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finding::{test_finding, Confidence, Severity};
+    use std::path::PathBuf;
+
+    fn finding(detector: &str, file: &str, title: &str) -> Finding {
+        let mut finding = test_finding(detector, Severity::Medium, Confidence::High, 10);
+        finding.title = title.to_string();
+        finding.locations[0].file = PathBuf::from(file);
+        finding
+    }
+
+    #[test]
+    fn test_regenerate_keeps_triaged_reasons() {
+        let old = Baseline {
+            entries: vec![
+                BaselineEntry {
+                    detector: "unsafe-unwrap".into(),
+                    file: "src/contract.rs".into(),
+                    title: "unwrap in execute".into(),
+                    reason: "guarded by instantiate".into(),
+                },
+                BaselineEntry {
+                    detector: "unsafe-unwrap".into(),
+                    file: "src/gone.rs".into(),
+                    title: "fixed".into(),
+                    reason: "stale".into(),
+                },
+            ],
+        };
+        let current = [
+            finding("unsafe-unwrap", "./src/contract.rs", "unwrap in execute"),
+            finding("missing-addr-validate", "src/msg.rs", "unvalidated owner"),
+        ];
+        let new = old.regenerate(&current);
+        assert_eq!(new.entries.len(), 2);
+        assert_eq!(new.entries[0].reason, "guarded by instantiate");
+        assert_eq!(new.entries[1].reason, TODO_REASON);
+        assert!(current.iter().all(|f| new.contains(f)));
+        assert!(!new.contains(&finding("unsafe-unwrap", "src/contract.rs", "other")));
+    }
+
+    #[test]
+    fn test_accept_adds_one_entry() {
+        let mut baseline = Baseline::default().regenerate(&[finding("d", "src/b.rs", "t")]);
+        assert!(baseline.accept(&finding("d", "src/a.rs", "t")));
+        assert!(!baseline.accept(&finding("d", "./src/a.rs", "t")));
+        let files: Vec<&str> = baseline.entries.iter().map(|e| e.file.as_str()).collect();
+        assert_eq!(files, ["src/a.rs", "src/b.rs"]);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let dir = std::env::temp_dir().join("cosmwasm-guard-test-baseline");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(DEFAULT_BASELINE);
+
+        let baseline = Baseline::default().regenerate(&[finding("d", "src/a.rs", "t")]);
+        baseline.save(&path).unwrap();
+        assert_eq!(Baseline::load(&path).unwrap().entries, baseline.entries);
+    }
+}
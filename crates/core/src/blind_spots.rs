@@ -0,0 +1,150 @@
+//! Blind spots of the analysis, reported as warnings next to the findings:
+//! message variants no `match` arm dispatches, closure bodies the IR lowers
+//! to an opaque value, and macros whose arguments are not expressions. Code in
+//! these places produced no findings because nothing looked at it, which a
+//! clean report would otherwise hide.
+
+use syn::visit::Visit;
+
+use crate::detector::AnalysisContext;
+use crate::report::{AnalysisWarning, WarningKind};
+use crate::surface;
+
+/// Closures and unparsable macros in one function body
+struct OpaqueFinder<'c> {
+    ctx: &'c AnalysisContext<'c>,
+    function: &'c str,
+    warnings: Vec<AnalysisWarning>,
+}
+
+impl OpaqueFinder<'_> {
+    fn push(&mut self, kind: WarningKind, message: String, node: &impl quote::ToTokens) {
+        let span = self.ctx.span_of(node);
+        self.warnings.push(AnalysisWarning {
+            kind,
+            message,
+            file: span.as_ref().map(|s| s.file.clone()),
+            line: span.map(|s| s.start_line),
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for OpaqueFinder<'_> {
+    fn visit_expr_closure(&mut self, node: &'ast syn::ExprClosure) {
+        // The whole closure is opaque, closures nested in it included
+        let message = format!(
+            "closure body in `{}` skipped: IR-based detectors do not look inside it",
+            self.function
+        );
+        self.push(WarningKind::ClosureSkipped, message, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        let parses = node.tokens.is_empty()
+            || node
+                .parse_body_with(
+                    syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated,
+                )
+                .is_ok();
+        if !parses {
+            let name = node
+                .path
+                .segments
+                .last()
+                .map(|s| s.ident.to_string())
+                .unwrap_or_default();
+            let message = format!(
+                "`{name}!` in `{}` could not be analyzed: its arguments are not expressions",
+                self.function
+            );
+            self.push(WarningKind::MacroUnanalyzed, message, node);
+        }
+        syn::visit::visit_macro(self, node);
+    }
+}
+
+/// Blind spots of the analysis of `ctx`'s contract, in source order per kind
+pub fn collect(ctx: &AnalysisContext) -> Vec<AnalysisWarning> {
+    let mut warnings: Vec<AnalysisWarning> = surface::coverage(&[], ctx)
+        .into_iter()
+        .filter(|variant| !variant.resolved())
+        .map(|variant| {
+            let message_enum = variant
+                .variant
+                .split("::")
+                .next()
+                .and_then(|name| ctx.contract.message_enums.iter().find(|e| e.name == name));
+            AnalysisWarning {
+                kind: WarningKind::UnresolvedHandler,
+                message: format!(
+                    "couldn't resolve the handler for `{}`: no match arm dispatches it",
+                    variant.variant
+                ),
+                file: message_enum.map(|e| e.span.file.clone()),
+                line: message_enum.map(|e| e.span.start_line),
+            }
+        })
+        .collect();
+
+    for func in &ctx.contract.functions {
+        let Some(body) = &func.body else { continue };
+        let mut finder = OpaqueFinder {
+            ctx,
+            function: &func.name,
+            warnings: Vec::new(),
+        };
+        finder.visit_block(body);
+        warnings.extend(finder.warnings);
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_collects_blind_spots() {
+        let source = r#"
+pub enum ExecuteMsg { Deposit {}, Sweep {} }
+
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Deposit {} => {
+            ensure!(!info.funds.is_empty(), StdError::generic_err("no funds"));
+            BALANCES.update(deps.storage, &info.sender, |b| -> StdResult<_> {
+                Ok(b.unwrap_or_default() + info.funds[0].amount)
+            })?;
+            let ok = matches!(msg, ExecuteMsg::Deposit {} if info.funds.len() > 1);
+            Ok(Response::new())
+        }
+    }
+}
+"#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([(PathBuf::from("test.rs"), source.to_string())]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+
+        let warnings = collect(&ctx);
+        let summary: Vec<(WarningKind, Option<usize>)> =
+            warnings.iter().map(|w| (w.kind, w.line)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (WarningKind::UnresolvedHandler, Some(2)),
+                (WarningKind::ClosureSkipped, Some(9)),
+                (WarningKind::MacroUnanalyzed, Some(12)),
+            ]
+        );
+        assert!(warnings[0].message.contains("`ExecuteMsg::Sweep`"));
+        assert!(warnings[1].message.contains("in `execute`"));
+        assert!(warnings[2].message.starts_with("`matches!`"));
+    }
+}
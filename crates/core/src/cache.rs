@@ -7,21 +7,32 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
 use crate::ast::contract_info::{
-    EntryPoint, FunctionInfo, MessageEnum, StateItem,
+    EntryPoint, FunctionInfo, MessageEnum, StateItem, StructInfo,
 };
 use crate::ir::types::{ContractIr, FunctionIr};
+use crate::ir::FunctionAnalyses;
 
-/// Schema version — bump when cached struct layouts change
-const SCHEMA_VERSION: u32 = 1;
+/// Schema version — bump when cached struct layouts or IR lowering change
+const SCHEMA_VERSION: u32 = 15;
 
-/// Per-file cached artifact: visitor output + IR functions for one source file
+/// Per-file cached artifact: visitor output + IR functions for one source file,
+/// with the file-local analyses of each IR function.
+///
+/// An artifact is keyed by the hash of its file alone, so it only holds what
+/// that file determines. Analyses depending on other files (call targets,
+/// access-control helpers, entry point flags) are resolved again on every run
+/// against the merged contract; editing one file never leaves stale results
+/// in another file's artifact.
 #[derive(Serialize, Deserialize)]
 pub struct CachedFileArtifact {
     pub entry_points: Vec<EntryPoint>,
     pub message_enums: Vec<MessageEnum>,
     pub state_items: Vec<StateItem>,
+    pub structs: Vec<StructInfo>,
     pub functions: Vec<FunctionInfo>,
     pub ir_functions: Vec<FunctionIr>,
+    /// Analyses of `ir_functions`, in the same order
+    pub ir_analyses: Vec<FunctionAnalyses>,
     pub ir_entry_points: Vec<String>,
 }
 
@@ -156,9 +167,16 @@ impl CacheManager {
             .message_enums
             .extend(artifact.message_enums.clone());
         contract.state_items.extend(artifact.state_items.clone());
+        contract.structs.extend(artifact.structs.clone());
         contract.functions.extend(artifact.functions.clone());
 
-        ir.functions.extend(artifact.ir_functions.clone());
+        ir.functions
+            .extend(artifact.ir_functions.iter().zip(&artifact.ir_analyses).map(
+                |(func, analyses)| FunctionIr {
+                    analyses: Some(analyses.clone()),
+                    ..func.clone()
+                },
+            ));
         for ep in &artifact.ir_entry_points {
             if !ir.entry_points.contains(ep) {
                 ir.entry_points.push(ep.clone());
@@ -193,8 +211,13 @@ mod tests {
             entry_points: vec![],
             message_enums: vec![],
             state_items: vec![],
+            structs: vec![],
             functions: vec![],
             ir_functions: vec![],
+            ir_analyses: vec![FunctionAnalyses {
+                calls: ["check".to_string()].into(),
+                ..Default::default()
+            }],
             ir_entry_points: vec!["execute".to_string()],
         };
 
@@ -206,8 +229,9 @@ mod tests {
 
         // Lookup should hit
         let hit = cache.lookup(&file, &hash);
-        assert!(hit.is_some());
-        assert_eq!(hit.unwrap().ir_entry_points, vec!["execute".to_string()]);
+        let hit = hit.unwrap();
+        assert_eq!(hit.ir_entry_points, vec!["execute".to_string()]);
+        assert!(hit.ir_analyses[0].calls.contains("check"));
 
         // Different hash should miss
         let different = CacheManager::hash_contents("different source");
@@ -221,4 +245,89 @@ mod tests {
 
         let _ = fs::remove_dir_all(&dir);
     }
+
+    /// Every CLI fixture, as (path, source)
+    fn fixture_sources() -> Vec<(PathBuf, String)> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../cli/tests/fixtures");
+        let mut dirs = vec![root];
+        let mut sources = Vec::new();
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if path.extension().is_some_and(|e| e == "rs") {
+                    let source = fs::read_to_string(&path).unwrap();
+                    sources.push((path, source));
+                }
+            }
+        }
+        sources.sort();
+        sources
+    }
+
+    #[test]
+    fn test_ir_roundtrip_is_lossless() {
+        use crate::ast::{parse_source, ContractVisitor};
+        use crate::ir::builder::IrBuilder;
+
+        let fixtures = fixture_sources();
+        assert!(fixtures.len() >= 8);
+        for (path, source) in fixtures {
+            let ast = parse_source(&source).unwrap();
+            let contract = ContractVisitor::extract(path.clone(), ast);
+            let ir = IrBuilder::build_contract(&contract);
+            let restored: ContractIr = bincode::deserialize(&bincode::serialize(&ir).unwrap())
+                .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+            assert_eq!(restored, ir, "{}", path.display());
+
+            for func in &ir.functions {
+                let analyses = FunctionAnalyses::compute(func);
+                let restored: FunctionAnalyses =
+                    bincode::deserialize(&bincode::serialize(&analyses).unwrap()).unwrap();
+                assert_eq!(restored, analyses, "{}: {}", path.display(), func.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_warm_run_matches_cold_run() {
+        use crate::ast::analyze_crate_cached;
+        use quote::ToTokens;
+
+        let dir = std::env::temp_dir().join("cosmwasm-guard-test-cache-warm");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        let (_, mut source) = fixture_sources()
+            .into_iter()
+            .find(|(p, _)| p.ends_with("vulnerable_contract.rs"))
+            .unwrap();
+        // Methods sharing a name must get their own bodies back on a hit
+        source.push_str(
+            "\nimpl Config { fn new() -> Self { Config { a: 1 } } }\n\
+             impl State { fn new() -> Self { State { b: 2 } } }\n",
+        );
+        fs::write(dir.join("src/lib.rs"), source).unwrap();
+
+        let mut cache = CacheManager::open(dir.join("cache")).unwrap();
+        let cold = analyze_crate_cached(&dir, Some(&mut cache)).unwrap();
+        let warm = analyze_crate_cached(&dir, Some(&mut cache)).unwrap();
+
+        assert_eq!(warm.ir, cold.ir);
+        assert!(warm.ir.functions.iter().all(|f| f.analyses.is_some()));
+        let bodies = |analysis: &crate::ast::CrateAnalysis| -> Vec<(String, Option<String>)> {
+            analysis
+                .contract
+                .functions
+                .iter()
+                .map(|f| {
+                    let body = f.body.as_ref().map(|b| b.to_token_stream().to_string());
+                    (f.name.clone(), body)
+                })
+                .collect()
+        };
+        assert_eq!(bodies(&warm), bodies(&cold));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,93 @@
+//! Stable short codes for the built-in detectors, e.g. `CWG0002` for
+//! `missing-access-control`. Codes are accepted wherever a detector name is.
+
+/// Code -> detector name. Append only: a code is never reassigned, and a
+/// removed detector keeps its code retired.
+pub const CODES: &[(&str, &str)] = &[
+    ("CWG0001", "missing-addr-validate"),
+    ("CWG0002", "missing-access-control"),
+    ("CWG0003", "unbounded-iteration"),
+    ("CWG0004", "storage-key-collision"),
+    ("CWG0005", "unsafe-unwrap"),
+    ("CWG0006", "arithmetic-overflow"),
+    ("CWG0007", "missing-error-propagation"),
+    ("CWG0008", "submessage-reply-unvalidated"),
+    ("CWG0009", "nondeterministic-iteration"),
+    ("CWG0010", "incorrect-permission-hierarchy"),
+    ("CWG0011", "missing-funds-validation"),
+    ("CWG0012", "uninitialized-state-access"),
+    ("CWG0013", "missing-migration-version"),
+    ("CWG0014", "instantiate-msg-validation"),
+    ("CWG0015", "unchecked-fee-bounds"),
+    ("CWG0016", "storage-collect-in-execute"),
+    ("CWG0017", "complexity-metrics"),
+    ("CWG0018", "unbounded-message-loop"),
+    ("CWG0019", "unbounded-message-field"),
+    ("CWG0020", "entry-point-signature"),
+    ("CWG0021", "reply-result-unchecked"),
+    ("CWG0022", "addr-stored-as-string"),
+    ("CWG0023", "stored-type-serde"),
+    ("CWG0024", "query-response-mismatch"),
+    ("CWG0025", "unverified-cw20-receive"),
+    ("CWG0026", "unguarded-parameter-update"),
+    ("CWG0027", "pause-consistency"),
+    ("CWG0028", "rounding-direction"),
+    ("CWG0029", "unchecked-subtraction"),
+    ("CWG0030", "pagination-bound-order"),
+    ("CWG0031", "role-check-bypass"),
+    ("CWG0032", "dead-code"),
+    ("CWG0033", "share-inflation"),
+    ("CWG0034", "oracle-staleness"),
+    ("CWG0035", "voting-power-snapshot"),
+    ("CWG0036", "unchecked-claim-maturity"),
+    ("CWG0037", "missing-status-check"),
+    ("CWG0038", "invariant-drift"),
+    ("CWG0039", "query-toctou"),
+    ("CWG0040", "test-code-in-contract"),
+    ("CWG0041", "floating-point"),
+    ("CWG0042", "foreign-address-prefix"),
+    ("CWG0043", "unsupported-capability"),
+    ("CWG0044", "repeated-storage-load"),
+    ("CWG0045", "reentrancy-state-after-msg"),
+];
+
+/// Code of the detector `name`, if it has one
+pub fn code_of(name: &str) -> Option<&'static str> {
+    CODES
+        .iter()
+        .find(|(_, n)| *n == name)
+        .map(|(code, _)| *code)
+}
+
+/// Detector name for a code (case-insensitive); anything else is returned
+/// unchanged, so names and codes can be mixed
+pub fn resolve(name_or_code: &str) -> &str {
+    CODES
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(name_or_code))
+        .map_or(name_or_code, |(_, name)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_codes_unique() {
+        let codes: HashSet<&str> = CODES.iter().map(|(c, _)| *c).collect();
+        let names: HashSet<&str> = CODES.iter().map(|(_, n)| *n).collect();
+        assert_eq!(codes.len(), CODES.len());
+        assert_eq!(names.len(), CODES.len());
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(resolve("CWG0002"), "missing-access-control");
+        assert_eq!(resolve("cwg0002"), "missing-access-control");
+        assert_eq!(resolve("unsafe-unwrap"), "unsafe-unwrap");
+        assert_eq!(resolve("CWG9999"), "CWG9999");
+        assert_eq!(code_of("unsafe-unwrap"), Some("CWG0005"));
+        assert_eq!(code_of("my-detector"), None);
+    }
+}
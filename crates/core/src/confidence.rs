@@ -0,0 +1,274 @@
+//! Confidence recalibration: moves each finding's confidence up or down one
+//! level per corroborating or contradicting signal around it, so filtering by
+//! confidence reflects more than the detector's fixed estimate.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::ast::EntryPointKind;
+use crate::detector::AnalysisContext;
+use crate::finding::{ConfidenceSignal, Finding};
+use crate::ir::slice::{Defs, CONTEXT_NAMES};
+use crate::ir::{Instruction, Operand};
+use crate::ranking::{enclosing_function, unguarded_reach};
+
+/// Variable and field names whose values flow into a chain message field,
+/// mapped to the message type, e.g. `recipient` -> `BankMsg::Send`
+fn message_inputs(ctx: &AnalysisContext) -> HashMap<String, String> {
+    let mut inputs = HashMap::new();
+    for func in &ctx.ir.functions {
+        let defs = Defs::new(func);
+        for inst in func.cfg.blocks.iter().flat_map(|b| &b.instructions) {
+            let Instruction::SendMsg { msg_type, fields } = inst else {
+                continue;
+            };
+            for (_, operand) in fields {
+                for name in operand_names(&defs, operand) {
+                    if name.starts_with('_') || CONTEXT_NAMES.contains(&name.as_str()) {
+                        continue;
+                    }
+                    inputs.entry(name).or_insert_with(|| msg_type.clone());
+                }
+            }
+        }
+    }
+    inputs
+}
+
+/// Names `operand` is computed from, following assignments and calls
+fn operand_names<'a>(defs: &Defs<'a>, operand: &'a Operand) -> HashSet<String> {
+    let calls_and_copies = |inst: &Instruction| {
+        !matches!(
+            inst,
+            Instruction::BinaryOp { .. } | Instruction::UnaryOp { .. }
+        )
+    };
+    defs.slice_through(operand, calls_and_copies)
+        .filter_map(|op| match op {
+            Operand::Var(var) => Some(var.name.clone()),
+            Operand::FieldAccess { field, .. } => Some(field.clone()),
+            Operand::Literal(_) => None,
+        })
+        .collect()
+}
+
+/// Identifiers quoted in backticks in a finding title
+fn quoted_names(title: &str) -> impl Iterator<Item = &str> {
+    title.split('`').skip(1).step_by(2)
+}
+
+/// Adjust the confidence of `findings` and record the signals behind each change
+pub fn recalibrate(findings: &mut [Finding], ctx: &AnalysisContext) {
+    let functions: Vec<Option<&str>> = findings
+        .iter()
+        .map(|f| enclosing_function(f, ctx))
+        .collect();
+
+    // Function -> detectors reporting inside it
+    let mut detectors: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    for (finding, function) in findings.iter().zip(&functions) {
+        if let Some(function) = function {
+            detectors
+                .entry(function)
+                .or_default()
+                .insert(&finding.detector_name);
+        }
+    }
+    let detectors: HashMap<&str, Vec<String>> = detectors
+        .into_iter()
+        .map(|(f, names)| (f, names.into_iter().map(String::from).collect()))
+        .collect();
+
+    let entries: Vec<&str> = ctx
+        .contract
+        .entry_points
+        .iter()
+        .map(|ep| ep.name.as_str())
+        .collect();
+    let reachable = ctx.call_graph().reachable_from(&entries);
+    // Functions users can reach, and those they reach without a sender check
+    let user_entries: Vec<&str> = ctx
+        .contract
+        .entry_points
+        .iter()
+        .filter(|ep| {
+            !matches!(
+                ep.kind,
+                EntryPointKind::Migrate | EntryPointKind::Sudo | EntryPointKind::Reply
+            )
+        })
+        .map(|ep| ep.name.as_str())
+        .collect();
+    let user_reach = ctx.call_graph().reachable_from(&user_entries);
+    let unguarded = unguarded_reach(ctx);
+    let inputs = message_inputs(ctx);
+
+    for (finding, function) in findings.iter_mut().zip(functions) {
+        let mut signals = Vec::new();
+
+        // A value the finding names reaches a chain message, e.g. an
+        // unvalidated address used as a `BankMsg::Send` recipient
+        let flow =
+            quoted_names(&finding.title).find_map(|name| inputs.get(name).map(|msg| (name, msg)));
+        if let Some((name, msg)) = flow {
+            signals.push(ConfidenceSignal {
+                reason: format!("`{name}` flows into a `{msg}`"),
+                delta: 1,
+            });
+        }
+
+        let Some(function) = function else {
+            apply(finding, signals);
+            continue;
+        };
+
+        let others: Vec<String> = detectors
+            .get(function)
+            .into_iter()
+            .flatten()
+            .filter(|d| **d != finding.detector_name)
+            .map(|d| format!("`{d}`"))
+            .collect();
+        if !others.is_empty() {
+            signals.push(ConfidenceSignal {
+                reason: format!("also reported by {}", others.join(", ")),
+                delta: 1,
+            });
+        }
+
+        let message = ctx
+            .call_graph()
+            .reachable_from(&[function])
+            .iter()
+            .find_map(|f| ctx.emitted_messages(f).first())
+            .map(|m| m.msg_type.clone());
+        if let Some(message) = message.filter(|_| flow.is_none()) {
+            signals.push(ConfidenceSignal {
+                reason: format!("`{function}` leads to a `{message}`"),
+                delta: 1,
+            });
+        }
+
+        if !reachable.contains(function) {
+            signals.push(ConfidenceSignal {
+                reason: format!("`{function}` is not reachable from an entry point"),
+                delta: -1,
+            });
+        } else if user_reach.contains(function) && !unguarded.contains(function) {
+            signals.push(ConfidenceSignal {
+                reason: format!("`{function}` is only reached after an `info.sender` check"),
+                delta: -1,
+            });
+        }
+
+        apply(finding, signals);
+    }
+}
+
+/// Move confidence one level per net signal and record the signals
+fn apply(finding: &mut Finding, signals: Vec<ConfidenceSignal>) {
+    let net: i8 = signals.iter().map(|s| s.delta).sum();
+    for _ in 0..net.unsigned_abs() {
+        finding.confidence = if net > 0 {
+            finding.confidence.raised()
+        } else {
+            finding.confidence.lowered()
+        };
+    }
+    finding.confidence_signals = signals;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::finding::{test_finding, Confidence, Severity};
+    use crate::ir::builder::IrBuilder;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_recalibrates_from_signals() {
+        let source = r#"
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Send { to } => send(deps, info, to),
+        ExecuteMsg::SetFee { fee } => set_fee(deps, info, fee),
+    }
+}
+
+fn send(deps: DepsMut, info: MessageInfo, to: String) -> Result<Response, ContractError> {
+    let msg = BankMsg::Send { to_address: to, amount: info.funds };
+    Ok(Response::new().add_message(msg))
+}
+
+fn set_fee(deps: DepsMut, info: MessageInfo, fee: u64) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    CONFIG.save(deps.storage, &fee)?;
+    Ok(Response::new())
+}
+
+fn unused(amount: Uint128) -> u128 {
+    amount.u128()
+}
+"#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([(PathBuf::from("test.rs"), source.to_string())]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+
+        let mut findings = vec![
+            test_finding(
+                "missing-addr-validate",
+                Severity::Medium,
+                Confidence::Medium,
+                11,
+            ), // send: corroborated, sends funds
+            test_finding("unsafe-unwrap", Severity::Medium, Confidence::Medium, 11),
+            test_finding(
+                "arithmetic-overflow",
+                Severity::Medium,
+                Confidence::Medium,
+                20,
+            ), // set_fee: behind a sender check
+            test_finding(
+                "arithmetic-overflow",
+                Severity::Medium,
+                Confidence::Medium,
+                26,
+            ), // unused: unreachable
+        ];
+        findings[3].title = "`amount` overflows".to_string();
+        let mut addr = test_finding(
+            "missing-addr-validate",
+            Severity::Medium,
+            Confidence::Medium,
+            2,
+        );
+        addr.title = "Unvalidated address: `to` in ExecuteMsg::Send".to_string();
+        findings.push(addr);
+        recalibrate(&mut findings, &ctx);
+
+        assert_eq!(findings[0].confidence, Confidence::High);
+        assert_eq!(findings[0].confidence_signals.len(), 2);
+        assert!(findings[0].confidence_signals[0]
+            .reason
+            .contains("`unsafe-unwrap`"));
+        assert!(findings[0].confidence_signals[1]
+            .reason
+            .contains("BankMsg::Send"));
+        assert_eq!(findings[2].confidence, Confidence::Low);
+        assert_eq!(findings[3].confidence, Confidence::Low);
+        assert!(findings[3].confidence_signals[0]
+            .reason
+            .contains("not reachable"));
+        assert_eq!(findings[4].confidence, Confidence::High);
+        assert_eq!(
+            findings[4].confidence_signals[0].reason,
+            "`to` flows into a `BankMsg::Send`"
+        );
+    }
+}
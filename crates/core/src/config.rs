@@ -1,26 +1,92 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 
-use serde::Deserialize;
+use anyhow::{bail, Context};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
+use crate::auth::AuthConfig;
+use crate::baseline::{Baseline, DEFAULT_BASELINE};
+use crate::codes;
+use crate::correlation::CorrelationRule;
+use crate::detector::Maturity;
 use crate::finding::{Finding, Severity};
+use crate::owners::{self, OwnerRule};
+use crate::packs::{self, RulePack};
+use crate::snippets::SnippetConfig;
 
 /// Project-level configuration loaded from `.cosmwasm-guard.toml`.
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Config {
+    /// Base config to inherit from: a path (relative to this file) or an https URL;
+    /// other URLs need `extends_sha256`. Keys set in this file override the base.
+    pub extends: Option<String>,
+    /// Expected SHA-256 of the `extends` target, as hex or `sha256:<hex>`
+    pub extends_sha256: Option<String>,
     pub global: GlobalConfig,
     #[serde(default)]
     pub detectors: HashMap<String, DetectorConfig>,
     #[serde(default)]
     pub suppressions: SuppressionConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub guards: GuardsConfig,
+    #[serde(default)]
+    pub taint: TaintConfig,
+    /// `[[state_machines]]` whose transitions handlers must respect
+    #[serde(default)]
+    pub state_machines: Vec<StateMachine>,
+    /// Named invariants between state items, e.g.
+    /// `supply = "TOTAL_SUPPLY == sum(BALANCES)"` (see [`crate::invariants`])
+    #[serde(default)]
+    pub invariants: BTreeMap<String, String>,
+    /// Limits and redaction for source snippets embedded in findings
+    #[serde(default)]
+    pub snippets: SnippetConfig,
+    /// Organizational levels reported next to each severity
+    #[serde(default)]
+    pub severity_levels: SeverityLevels,
+    /// `CODEOWNERS`-style `[[owners]]` rules routing findings to teams
+    #[serde(default)]
+    pub owners: Vec<OwnerRule>,
+    /// `[[correlations]]` rules escalating findings that co-occur in a
+    /// handler, on top of the built-in ones
+    #[serde(default)]
+    pub correlations: Vec<CorrelationRule>,
+    /// `[[branches]]` gating policies selected by the branch being analyzed
+    #[serde(default)]
+    pub branches: Vec<BranchPolicy>,
+    /// `[chains.<name>]` profiles of target chains, one selected with
+    /// `--target-chain` or `target_chain` under `[global]`
+    #[serde(default)]
+    pub chains: BTreeMap<String, ChainProfile>,
+    /// Accepted findings, loaded from `suppressions.baseline`
+    #[serde(skip)]
+    pub baseline: Baseline,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct GlobalConfig {
     pub severity_threshold: String,
     pub output_format: String,
+    /// Run Experimental-tier detectors
+    pub experimental: bool,
+    /// Descend into symlinked files and directories during file discovery
+    pub follow_symlinks: bool,
+    /// Color theme of text output: `default`, `colorblind` or `none`
+    pub theme: String,
+    /// Rule packs to run instead of the maturity-based default selection
+    pub packs: Vec<String>,
+    /// `[chains.<name>]` profile the contract is checked against
+    pub target_chain: Option<String>,
+    /// Base revision of `--only-new-code` when the flag names none
+    pub new_code_since: Option<String>,
 }
 
 impl Default for GlobalConfig {
@@ -28,34 +94,301 @@ impl Default for GlobalConfig {
         Self {
             severity_threshold: "low".to_string(),
             output_format: "text".to_string(),
+            experimental: false,
+            follow_symlinks: false,
+            theme: "default".to_string(),
+            packs: Vec::new(),
+            target_chain: None,
+            new_code_since: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct DetectorConfig {
     pub enabled: Option<bool>,
+    /// Severity the detector's findings are re-tiered to, e.g. "critical"
     pub severity: Option<String>,
+    /// Detector-specific settings, e.g. `field_patterns = ["fee"]`
+    #[serde(flatten)]
+    pub options: toml::Table,
 }
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct SuppressionConfig {
     pub files: Vec<String>,
+    /// Baseline of accepted findings, relative to this file. Defaults to
+    /// `.cosmwasm-guard-baseline.toml` when that file exists.
+    pub baseline: Option<String>,
+    /// Drop findings inside code identical to audited cw-plus releases
+    /// instead of labeling them as inherited
+    pub hide_inherited: bool,
+}
+
+/// In-house helpers trusted to validate `info.funds`, by function name (last
+/// path segment). Calls to them count as the check in the funds detector, like
+/// `cw_utils::must_pay`. Sender checks are configured under `[auth]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuardsConfig {
+    pub funds: Vec<String>,
+}
+
+/// Project helpers the taint analysis sees through, by function or method
+/// name (last path segment). Results of `sources` are sender-controlled, like
+/// a message parameter (e.g. a helper parsing a batch out of the message).
+/// Calls to `sinks` move funds and count as a `BankMsg::Send` built in the
+/// calling function (e.g. an in-house `send_tokens` wrapper).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaintConfig {
+    pub sources: Vec<String>,
+    pub sinks: Vec<String>,
+}
+
+/// A status the contract keeps in `item` (or in its `field`), with the states
+/// each execute variant may run in, e.g. "Deposit only while the pool is
+/// Open". Checked by the `missing-status-check` detector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateMachine {
+    /// State item holding the status, e.g. `POOL`
+    pub item: String,
+    /// Field of the stored struct holding the status; none when the item
+    /// itself is the status
+    #[serde(default)]
+    pub field: Option<String>,
+    /// Every state. Lets a handler reject the disallowed states instead of
+    /// requiring an allowed one.
+    #[serde(default)]
+    pub states: Vec<String>,
+    /// Execute variant -> states it may run in
+    #[serde(default)]
+    pub requires: BTreeMap<String, Vec<String>>,
+}
+
+/// Gating policy of the branches matching `pattern`, e.g. `release/*`: findings
+/// below `severity_threshold` are not reported and do not fail the run.
+/// Findings accepted in the baseline never gate. The first matching policy
+/// applies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchPolicy {
+    pub pattern: String,
+    pub severity_threshold: String,
+}
+
+/// What the CosmWasm runtime of a chain supports. APIs needing anything else
+/// are flagged by the `unsupported-capability` detector.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChainProfile {
+    /// CosmWasm version of the chain's wasmvm, e.g. `"1.5"`; enables the
+    /// `cosmwasm_1_1`... capabilities up to it. Unset skips version checks.
+    pub cosmwasm: Option<String>,
+    /// Capabilities the chain enables, as wasmd names them: `iterator`,
+    /// `staking`, `stargate` (which IBC also needs)
+    pub capabilities: Vec<String>,
+    /// Bech32 address prefix, e.g. `osmo`
+    pub prefix: Option<String>,
+}
+
+impl ChainProfile {
+    /// Whether the chain supports `capability`, e.g. `iterator` or `cosmwasm_1_3`
+    pub fn supports(&self, capability: &str) -> bool {
+        if self.capabilities.iter().any(|c| c == capability) {
+            return true;
+        }
+        let Some(needed) = capability.strip_prefix("cosmwasm_") else {
+            return false;
+        };
+        let Some(version) = &self.cosmwasm else {
+            return true;
+        };
+        parse_version(&needed.replace('_', ".")) <= parse_version(version)
+    }
+}
+
+/// `major.minor` of a version string, missing parts counting as 0
+fn parse_version(version: &str) -> (u32, u32) {
+    let mut parts = version
+        .trim_start_matches('v')
+        .split('.')
+        .map(|p| p.parse().unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
+
+/// `*` stays within one `/`-separated part of a branch name, as in branch
+/// protection rules; `**` crosses them
+const BRANCH_MATCH: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+impl BranchPolicy {
+    fn matches(&self, branch: &str) -> bool {
+        glob::Pattern::new(&self.pattern).is_ok_and(|p| p.matches_with(branch, BRANCH_MATCH))
+    }
+}
+
+/// Thresholds for the `complexity-metrics` detector. A function exceeding any
+/// threshold is reported as an Informational finding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    pub max_cyclomatic_complexity: usize,
+    pub max_function_lines: usize,
+    pub max_storage_writes: usize,
+    pub max_match_arms: usize,
+}
+
+/// Organizational level reported next to each severity, e.g. `P1`–`P4` or a
+/// CVSS-like band, so findings slot into an existing triage process.
+/// Severities without a level are reported without one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeverityLevels {
+    pub critical: Option<String>,
+    pub high: Option<String>,
+    pub medium: Option<String>,
+    pub low: Option<String>,
+    pub informational: Option<String>,
+}
+
+impl SeverityLevels {
+    /// Configured level of `severity`
+    pub fn level_of(&self, severity: &Severity) -> Option<&str> {
+        match severity {
+            Severity::Critical => &self.critical,
+            Severity::High => &self.high,
+            Severity::Medium => &self.medium,
+            Severity::Low => &self.low,
+            Severity::Informational => &self.informational,
+        }
+        .as_deref()
+    }
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            max_cyclomatic_complexity: 15,
+            max_function_lines: 100,
+            max_storage_writes: 6,
+            max_match_arms: 25,
+        }
+    }
 }
 
 impl Config {
     /// Load config from a TOML file path. Returns default config if file doesn't exist.
+    /// Remote `extends` targets are refused; see [`Config::load_with`].
     pub fn load(path: &Path) -> anyhow::Result<Self> {
-        if !path.exists() {
-            return Ok(Self::default());
+        Self::load_with(path, &mut |remote| {
+            bail!("cannot fetch remote config `{}` here", remote.url)
+        })
+    }
+
+    /// Load config from a TOML file path, fetching remote `extends` targets
+    /// with `fetch`. Their `extends_sha256` is checked on what it returns.
+    pub fn load_with(
+        path: &Path,
+        fetch: &mut dyn FnMut(&RemoteConfig) -> anyhow::Result<String>,
+    ) -> anyhow::Result<Self> {
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        let mut config = if path.exists() {
+            let content = std::fs::read_to_string(path)?;
+            let origin = ConfigOrigin::Local(base_dir.to_path_buf());
+            let table = resolve_extends(&content, &origin, fetch, 0)
+                .with_context(|| format!("Failed to load config {}", path.display()))?;
+            table.try_into()?
+        } else {
+            Self::default()
+        };
+        // `[detectors.CWG0005]` configures `unsafe-unwrap`
+        config.detectors = std::mem::take(&mut config.detectors)
+            .into_iter()
+            .map(|(name, detector)| (codes::resolve(&name).to_string(), detector))
+            .collect();
+        packs::resolve(&config.global.packs)
+            .with_context(|| format!("Invalid config {}", path.display()))?;
+        if parse_severity(&config.global.severity_threshold).is_none() {
+            bail!(
+                "Invalid config {}: unknown severity_threshold `{}`",
+                path.display(),
+                config.global.severity_threshold
+            );
+        }
+        for (name, detector) in &config.detectors {
+            if let Some(severity) = &detector.severity {
+                if parse_severity(severity).is_none() {
+                    bail!(
+                        "Invalid config {}: unknown severity `{severity}` for detector `{name}`",
+                        path.display()
+                    );
+                }
+            }
+        }
+        let variant_pattern: Option<String> =
+            config.detector_option("unguarded-parameter-update", "variant_pattern");
+        if let Some(Err(e)) = variant_pattern.map(|p| regex::Regex::new(&p)) {
+            bail!(
+                "Invalid config {}: bad variant_pattern for detector `unguarded-parameter-update`: {e}",
+                path.display()
+            );
+        }
+        for policy in &config.branches {
+            if let Err(e) = glob::Pattern::new(&policy.pattern) {
+                bail!(
+                    "Invalid config {}: bad branch pattern `{}`: {e}",
+                    path.display(),
+                    policy.pattern
+                );
+            }
+            if parse_severity(&policy.severity_threshold).is_none() {
+                bail!(
+                    "Invalid config {}: unknown severity `{}` for branches `{}`",
+                    path.display(),
+                    policy.severity_threshold,
+                    policy.pattern
+                );
+            }
+        }
+        if let Some(chain) = &config.global.target_chain {
+            if !config.chains.contains_key(chain) {
+                bail!(
+                    "Invalid config {}: target_chain `{chain}` has no [chains.{chain}] profile",
+                    path.display()
+                );
+            }
+        }
+        if let Some(baseline) = config.baseline_path(path) {
+            config.baseline = Baseline::load(&baseline)?;
         }
-        let content = std::fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&content)?;
         Ok(config)
     }
 
+    /// This config as TOML, with the configs it extends merged in
+    pub fn effective_toml(&self) -> anyhow::Result<String> {
+        let effective = Self {
+            extends: None,
+            extends_sha256: None,
+            ..self.clone()
+        };
+        Ok(toml::to_string(&effective)?)
+    }
+
+    /// Baseline file in effect for a config loaded from `config_path`: the
+    /// configured one, else the default file if it exists
+    pub fn baseline_path(&self, config_path: &Path) -> Option<PathBuf> {
+        let base_dir = config_path.parent().unwrap_or(Path::new("."));
+        match &self.suppressions.baseline {
+            Some(path) => Some(base_dir.join(path)),
+            None => Some(base_dir.join(DEFAULT_BASELINE)).filter(|p| p.is_file()),
+        }
+    }
+
     /// Check if a detector is enabled according to config.
     pub fn is_detector_enabled(&self, name: &str) -> bool {
         self.detectors
@@ -64,47 +397,345 @@ impl Config {
             .unwrap_or(true)
     }
 
+    /// Severity configured for a detector's findings under `[detectors.<name>]`,
+    /// replacing its default
+    pub fn severity_override(&self, name: &str) -> Option<Severity> {
+        self.detectors
+            .get(name)
+            .and_then(|d| d.severity.as_deref())
+            .and_then(parse_severity)
+    }
+
+    /// Check if a detector's maturity tier is allowed to run. Experimental
+    /// detectors need `experimental` (CLI flag), `global.experimental`, or an
+    /// explicit `enabled = true` for that detector.
+    pub fn allows_maturity(&self, name: &str, maturity: Maturity, experimental: bool) -> bool {
+        maturity != Maturity::Experimental
+            || experimental
+            || self.global.experimental
+            || self.detectors.get(name).and_then(|d| d.enabled) == Some(true)
+    }
+
+    /// Read a detector-specific option from `[detectors.<name>]`, else from
+    /// the presets of the selected rule packs (the first pack setting it wins).
+    /// Returns None when unset or when the value doesn't match the expected type.
+    pub fn detector_option<T: DeserializeOwned>(&self, detector: &str, key: &str) -> Option<T> {
+        self.detectors
+            .get(detector)
+            .and_then(|d| d.options.get(key).cloned())
+            .or_else(|| {
+                self.packs()
+                    .iter()
+                    .find_map(|pack| pack.preset(detector, key))
+            })?
+            .try_into()
+            .ok()
+    }
+
+    /// Selected rule packs, in the order listed; names are checked when the
+    /// config is loaded
+    pub fn packs(&self) -> Vec<&'static RulePack> {
+        self.global
+            .packs
+            .iter()
+            .filter_map(|name| packs::PACKS.iter().find(|pack| pack.name == name.as_str()))
+            .collect()
+    }
+
     /// Parse the global severity threshold into a Severity value.
     pub fn severity_threshold(&self) -> Severity {
         parse_severity(&self.global.severity_threshold).unwrap_or(Severity::Low)
     }
 
+    /// Name and profile of the chain the contract targets, if one is selected
+    pub fn target_chain(&self) -> Option<(&str, &ChainProfile)> {
+        let name = self.global.target_chain.as_deref()?;
+        self.chains.get(name).map(|profile| (name, profile))
+    }
+
+    /// Threshold of the first `[[branches]]` policy matching `branch`, if any.
+    /// Thresholds are checked when the config is loaded.
+    pub fn branch_threshold(&self, branch: &str) -> Option<Severity> {
+        let policy = self.branches.iter().find(|p| p.matches(branch))?;
+        parse_severity(&policy.severity_threshold)
+    }
+
     /// Check if a file path should be excluded based on suppression glob patterns.
     pub fn is_file_excluded(&self, file_path: &Path) -> bool {
-        let path_str = file_path.to_string_lossy();
+        let path_str = crate::ast::discovery::normalize_separators(file_path);
         self.suppressions
             .files
             .iter()
             .any(|pattern| glob::Pattern::new(pattern).is_ok_and(|p| p.matches(&path_str)))
     }
 
+    /// Team owning `file` per the `[[owners]]` rules; the last matching rule wins.
+    pub fn owner_of(&self, file: &Path) -> Option<&str> {
+        owners::owner_of(&self.owners, file)
+    }
+
     /// Generate default config file content.
     pub fn default_toml() -> &'static str {
         r#"# cosmwasm-guard configuration
 # See: https://github.com/safestackai/cosmwasm-guard
 
+# Inherit from an organization-wide config (path or URL); keys below override it
+# extends = "https://example.com/org-guard.toml"
+# extends_sha256 = "<sha256 of the file>"
+
 [global]
-# Minimum severity to report: "high", "medium", "low", "informational"
+# Minimum severity to report: "critical", "high", "medium", "low", "informational"
 severity_threshold = "low"
 # Output format: "text", "json", "sarif"
 output_format = "text"
+# Also run Experimental-tier detectors (same as --experimental)
+experimental = false
+# Follow symlinks when discovering source files (same as --follow-symlinks)
+follow_symlinks = false
+# Text output colors: "default", "colorblind", "none" (same as --theme; NO_COLOR disables color)
+theme = "default"
+# Run curated detector bundles with their option presets instead of every
+# Stable and Beta detector: "core", "defi", "nft", "ibc", "governance",
+# "staking", "gas", "strict"
+# (same as --packs; `cosmwasm-guard list` shows what each one runs)
+# packs = ["core", "defi"]
+# Chain profile (see [chains.<name>] below) to check the contract against
+# (same as --target-chain)
+# target_chain = "osmosis"
+# Base revision of --only-new-code without one: only findings on lines changed
+# since its merge base with HEAD are reported (default: HEAD, uncommitted changes)
+# new_code_since = "origin/main"
 
-# Per-detector overrides
+# Per-detector overrides, by name or code (`cosmwasm-guard list`)
 # [detectors.unsafe-unwrap]
 # enabled = false
 
+# Re-tier a detector: "critical", "high", "medium", "low", "informational"
 # [detectors.missing-addr-validate]
 # severity = "low"
 
+# Execute variants callable by anyone by design, reported at Informational
+# [detectors.missing-access-control]
+# permissionless = ["Deposit", "Claim", "Receive", "Vote", "Stake", "Bond", "Swap"]
+
+# Bech32 prefix of the target chain; hard-coded addresses and prefix checks
+# for other chains are flagged
+# [detectors.foreign-address-prefix]
+# prefix = "osmo"
+
 [suppressions]
 # Glob patterns for files to skip entirely
 files = ["tests/**", "examples/**"]
+# Accepted findings written by `cosmwasm-guard suppress --all-current`
+# baseline = ".cosmwasm-guard-baseline.toml"
+# Drop findings in functions identical to audited cw-plus code, instead of
+# labeling them "inherited from cw-plus vX"
+# hide_inherited = true
+
+[metrics]
+# Thresholds for informational complexity findings (complexity-metrics detector)
+max_cyclomatic_complexity = 15
+max_function_lines = 100
+max_storage_writes = 6
+max_match_arms = 25
+
+# In-house access-control helpers, recognized alongside assert_owner and
+# cw_ownable by every detector and the guard analysis. Functions can also be
+# marked in code with `#[cfg_attr(cosmwasm_guard, cosmwasm_guard::assume(sender))]`.
+# [auth]
+# helpers = ["assert_operator", "only_gov"]
+
+# In-house helpers that validate info.funds (or `assume(funds)` in code)
+# [guards]
+# funds = ["check_payment"]
+
+# In-house helpers returning sender-controlled data, and wrappers that send
+# funds (treated like a BankMsg::Send built where they are called)
+# [taint]
+# sources = ["parse_batch"]
+# sinks = ["send_tokens"]
+
+# Contract state machines: each execute variant under `requires` may only run
+# in the listed states, and its handler must check the status before acting
+# [[state_machines]]
+# item = "POOL"
+# field = "status"
+# states = ["Initialized", "Open", "Closed"]
+# requires = { Deposit = ["Open"], Open = ["Initialized"], Withdraw = ["Open", "Closed"] }
+
+# Invariants between state items: a handler writing one side must update the
+# other. Also declared in code as `// guard-invariant: <expr>` comments.
+# [invariants]
+# supply = "TOTAL_SUPPLY == sum(BALANCES)"
+
+# Source snippets embedded in reports (JSON and SARIF are often uploaded to
+# third-party services)
+# [snippets]
+# max_lines = 10
+# max_line_length = 200
+# redact_strings = true
+
+# Target chains: the CosmWasm version and capabilities of each chain's runtime.
+# APIs the selected chain lacks (`range` without `iterator`, IBC entry points
+# or `CosmosMsg::Stargate` without `stargate`...) are flagged, and `prefix`
+# checks hard-coded addresses. Select one with target_chain under [global]
+# or --target-chain
+# [chains.osmosis]
+# cosmwasm = "1.5"
+# capabilities = ["iterator", "staking", "stargate"]
+# prefix = "osmo"
+
+# Branch policies for `analyze` without --severity: the current branch (or
+# --branch) picks the first matching pattern's threshold, and other branches
+# report low and above; findings accepted in the baseline never fail the run
+# [[branches]]
+# pattern = "main"
+# severity_threshold = "medium"
+#
+# [[branches]]
+# pattern = "release/*"
+# severity_threshold = "low"
+
+# Organizational levels added to findings (`severity_level` in JSON and SARIF)
+# [severity_levels]
+# critical = "P0"
+# high = "P1"
+# medium = "P2"
+# low = "P3"
+# informational = "P4"
+
+# Route findings to owning teams, CODEOWNERS-style (last matching rule wins)
+# [[owners]]
+# pattern = "contracts/vault/"
+# team = "@org/vault-team"
+
+# Escalate findings when they co-occur in the same handler. Built in:
+# missing-access-control in a handler writing admin/config state is Critical,
+# missing-addr-validate in a handler sending a BankMsg is High.
+# `requires` takes "privileged-write" and "bank-send".
+# [[correlations]]
+# name = "unguarded-unwrap"
+# detector = "missing-access-control"
+# with = ["unsafe-unwrap"]
+# escalate_to = "critical"
 "#
     }
 }
 
-fn parse_severity(s: &str) -> Option<Severity> {
+/// Longest allowed `extends` chain; also stops cycles
+const MAX_EXTENDS_DEPTH: usize = 8;
+
+/// Where a config file came from, used to resolve a relative `extends`
+enum ConfigOrigin {
+    Local(PathBuf),
+    Remote,
+}
+
+/// A remote config named by `extends`, for the caller of [`Config::load_with`]
+/// to fetch
+pub struct RemoteConfig<'a> {
+    pub url: &'a str,
+    /// Expected SHA-256 from `extends_sha256`, as hex or `sha256:<hex>`
+    pub sha256: Option<&'a str>,
+    /// Directory of the local config extending it; None when a remote config
+    /// extends it
+    pub config_dir: Option<&'a Path>,
+}
+
+impl RemoteConfig<'_> {
+    /// Whether `content` matches the pinned hash; true when unpinned
+    pub fn matches(&self, content: &str) -> bool {
+        verify_integrity(self.url, content, self.sha256).is_ok()
+    }
+}
+
+fn is_remote(target: &str) -> bool {
+    target.contains("://")
+}
+
+/// Parse a config and merge it over the chain of configs it extends
+fn resolve_extends(
+    content: &str,
+    origin: &ConfigOrigin,
+    fetch: &mut dyn FnMut(&RemoteConfig) -> anyhow::Result<String>,
+    depth: usize,
+) -> anyhow::Result<toml::Table> {
+    let table: toml::Table = toml::from_str(content)?;
+    let Some(target) = table.get("extends").and_then(|v| v.as_str()) else {
+        return Ok(table);
+    };
+    if depth >= MAX_EXTENDS_DEPTH {
+        bail!("config `extends` chain is deeper than {MAX_EXTENDS_DEPTH} (cycle?)");
+    }
+    let integrity = table.get("extends_sha256").and_then(|v| v.as_str());
+
+    let (base_content, base_origin) = if is_remote(target) {
+        if !target.starts_with("https://") && integrity.is_none() {
+            bail!("remote config `{target}` is not https: pin it with `extends_sha256`");
+        }
+        let config_dir = match origin {
+            ConfigOrigin::Local(dir) => Some(dir.as_path()),
+            ConfigOrigin::Remote => None,
+        };
+        let content = fetch(&RemoteConfig {
+            url: target,
+            sha256: integrity,
+            config_dir,
+        })?;
+        verify_integrity(target, &content, integrity)?;
+        (content, ConfigOrigin::Remote)
+    } else {
+        let ConfigOrigin::Local(dir) = origin else {
+            bail!("remote config cannot extend local path `{target}`");
+        };
+        let path = dir.join(target);
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read extended config {}", path.display()))?;
+        verify_integrity(target, &content, integrity)?;
+        let base_dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        (content, ConfigOrigin::Local(base_dir))
+    };
+
+    let mut merged = resolve_extends(&base_content, &base_origin, fetch, depth + 1)
+        .with_context(|| format!("Failed to load extended config `{target}`"))?;
+    merge_tables(&mut merged, table);
+    Ok(merged)
+}
+
+/// Deep-merge `overrides` into `base`: tables merge key by key, anything else replaces
+fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(override_table)) => {
+                merge_tables(base_table, override_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn sha256_hex(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+fn verify_integrity(target: &str, content: &str, expected: Option<&str>) -> anyhow::Result<()> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+    let expected = expected.strip_prefix("sha256:").unwrap_or(expected);
+    let actual = sha256_hex(content);
+    if !actual.eq_ignore_ascii_case(expected) {
+        bail!("integrity check failed for `{target}`: expected sha256 {expected}, got {actual}");
+    }
+    Ok(())
+}
+
+/// Parse a severity name ("critical", "high", "medium", "low", "informational"/"info")
+pub fn parse_severity(s: &str) -> Option<Severity> {
     match s.to_lowercase().as_str() {
+        "critical" => Some(Severity::Critical),
         "high" => Some(Severity::High),
         "medium" => Some(Severity::Medium),
         "low" => Some(Severity::Low),
@@ -130,7 +761,9 @@ pub fn parse_inline_suppressions(
                 let detectors = if rest.is_empty() {
                     vec!["*".to_string()] // wildcard = suppress all
                 } else {
-                    rest.split(',').map(|s| s.trim().to_string()).collect()
+                    rest.split(',')
+                        .map(|s| codes::resolve(s.trim()).to_string())
+                        .collect()
                 };
                 suppressions.insert((path.clone(), target_line), detectors);
             }
@@ -177,6 +810,11 @@ pub fn apply_suppressions(
                 }
             }
 
+            // Check accepted findings
+            if config.baseline.contains(f) {
+                return false;
+            }
+
             // Check inline suppression
             for loc in &f.locations {
                 let key = (loc.file.clone(), loc.start_line);
@@ -224,6 +862,405 @@ files = ["tests/**"]
         assert!(!config.is_file_excluded(Path::new("src/contract.rs")));
     }
 
+    #[test]
+    fn test_detector_options() {
+        let toml = r#"
+[detectors.unchecked-fee-bounds]
+enabled = true
+field_patterns = ["fee", "spread"]
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let patterns: Option<Vec<String>> =
+            config.detector_option("unchecked-fee-bounds", "field_patterns");
+        assert_eq!(patterns.unwrap(), vec!["fee", "spread"]);
+        assert!(config
+            .detector_option::<Vec<String>>("unchecked-fee-bounds", "missing")
+            .is_none());
+        assert!(config.is_detector_enabled("unchecked-fee-bounds"));
+    }
+
+    #[test]
+    fn test_experimental_detectors_opt_in() {
+        let config = Config::default();
+        assert!(config.allows_maturity("x", Maturity::Beta, false));
+        assert!(!config.allows_maturity("x", Maturity::Experimental, false));
+        assert!(config.allows_maturity("x", Maturity::Experimental, true));
+
+        let toml = r#"
+[global]
+experimental = true
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert!(config.allows_maturity("x", Maturity::Experimental, false));
+
+        let toml = r#"
+[detectors.noisy]
+enabled = true
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert!(config.allows_maturity("noisy", Maturity::Experimental, false));
+        assert!(!config.allows_maturity("other", Maturity::Experimental, false));
+    }
+
+    /// Fresh scratch directory under the system temp dir
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cosmwasm-guard-test-{name}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_extends_local_path_merges_overrides() {
+        let dir = scratch_dir("extends-local");
+        std::fs::write(
+            dir.join("org.toml"),
+            r#"
+[global]
+severity_threshold = "medium"
+
+[detectors.unsafe-unwrap]
+enabled = false
+severity = "low"
+
+[suppressions]
+files = ["vendor/**"]
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join(".cosmwasm-guard.toml"),
+            r#"
+extends = "org.toml"
+
+[detectors.unsafe-unwrap]
+enabled = true
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load(&dir.join(".cosmwasm-guard.toml")).unwrap();
+        assert_eq!(config.severity_threshold(), Severity::Medium);
+        assert!(config.is_detector_enabled("unsafe-unwrap"));
+        assert_eq!(
+            config.detectors["unsafe-unwrap"].severity.as_deref(),
+            Some("low")
+        );
+        assert_eq!(config.suppressions.files, vec!["vendor/**"]);
+
+        let effective: Config = toml::from_str(&config.effective_toml().unwrap()).unwrap();
+        assert_eq!(effective.extends, None);
+        assert_eq!(effective.severity_threshold(), Severity::Medium);
+        assert_eq!(
+            effective.detectors["unsafe-unwrap"].severity.as_deref(),
+            Some("low")
+        );
+        assert_eq!(effective.suppressions.files, vec!["vendor/**"]);
+    }
+
+    #[test]
+    fn test_extends_integrity_mismatch_fails() {
+        let dir = scratch_dir("extends-integrity");
+        std::fs::write(
+            dir.join("org.toml"),
+            "[global]\nseverity_threshold = \"high\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join(".cosmwasm-guard.toml"),
+            "extends = \"org.toml\"\nextends_sha256 = \"sha256:00\"\n",
+        )
+        .unwrap();
+        let err = Config::load(&dir.join(".cosmwasm-guard.toml")).unwrap_err();
+        assert!(format!("{err:#}").contains("integrity check failed"));
+    }
+
+    #[test]
+    fn test_extends_remote_fetched_by_caller() {
+        let dir = scratch_dir("extends-remote");
+        let url = "https://config.invalid/org-guard.toml";
+        let remote = "[global]\nseverity_threshold = \"high\"\n";
+        let path = dir.join(".cosmwasm-guard.toml");
+        std::fs::write(&path, format!("extends = \"{url}\"\n")).unwrap();
+
+        let err = Config::load(&path).unwrap_err();
+        assert!(format!("{err:#}").contains("cannot fetch remote config"));
+
+        let mut fetched = Vec::new();
+        let config = Config::load_with(&path, &mut |r| {
+            fetched.push((r.url.to_string(), r.config_dir.map(Path::to_path_buf)));
+            Ok(remote.to_string())
+        })
+        .unwrap();
+        assert_eq!(config.severity_threshold(), Severity::High);
+        assert_eq!(fetched, vec![(url.to_string(), Some(dir.clone()))]);
+    }
+
+    #[test]
+    fn test_extends_plain_http_needs_pin() {
+        let dir = scratch_dir("extends-http");
+        let url = "http://config.invalid/org-guard.toml";
+        let remote = "[global]\nseverity_threshold = \"high\"\n";
+        let path = dir.join(".cosmwasm-guard.toml");
+        let serve = |_: &RemoteConfig| Ok(remote.to_string());
+
+        std::fs::write(&path, format!("extends = \"{url}\"\n")).unwrap();
+        let err = Config::load_with(&path, &mut serve.clone()).unwrap_err();
+        assert!(format!("{err:#}").contains("not https"));
+
+        std::fs::write(
+            &path,
+            format!(
+                "extends = \"{url}\"\nextends_sha256 = \"{}\"\n",
+                sha256_hex(remote)
+            ),
+        )
+        .unwrap();
+        let config = Config::load_with(&path, &mut serve.clone()).unwrap();
+        assert_eq!(config.severity_threshold(), Severity::High);
+    }
+
+    #[test]
+    fn test_parse_metrics_thresholds() {
+        let toml = r#"
+[metrics]
+max_cyclomatic_complexity = 5
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.metrics.max_cyclomatic_complexity, 5);
+        assert_eq!(config.metrics.max_function_lines, 100);
+    }
+
+    #[test]
+    fn test_parse_taint_helpers() {
+        let toml = r#"
+[taint]
+sources = ["parse_batch"]
+sinks = ["send_tokens", "refund"]
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.taint.sources, vec!["parse_batch"]);
+        assert_eq!(config.taint.sinks, vec!["send_tokens", "refund"]);
+        assert!(Config::default().taint.sinks.is_empty());
+    }
+
+    #[test]
+    fn test_parse_state_machines() {
+        let toml = r#"
+[[state_machines]]
+item = "POOL"
+field = "status"
+states = ["Initialized", "Open", "Closed"]
+requires = { Deposit = ["Open"], Withdraw = ["Open", "Closed"] }
+
+[[state_machines]]
+item = "PHASE"
+requires = { Claim = ["Finished"] }
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.state_machines.len(), 2);
+        let pool = &config.state_machines[0];
+        assert_eq!(pool.field.as_deref(), Some("status"));
+        assert_eq!(pool.requires["Withdraw"], vec!["Open", "Closed"]);
+        assert!(config.state_machines[1].field.is_none());
+        assert!(config.state_machines[1].states.is_empty());
+    }
+
+    #[test]
+    fn test_parse_invariants() {
+        let toml = r#"
+[invariants]
+supply = "TOTAL_SUPPLY == sum(BALANCES)"
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.invariants["supply"], "TOTAL_SUPPLY == sum(BALANCES)");
+    }
+
+    #[test]
+    fn test_parse_snippet_limits() {
+        let toml = r#"
+[snippets]
+max_lines = 3
+redact_strings = true
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.snippets.max_lines, Some(3));
+        assert_eq!(config.snippets.max_line_length, None);
+        assert!(config.snippets.redact_strings);
+        assert!(!Config::default().snippets.redact_strings);
+    }
+
+    #[test]
+    fn test_parse_severity_levels() {
+        let toml = r#"
+[severity_levels]
+high = "P1"
+medium = "CVSS 4.0-6.9"
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let levels = &config.severity_levels;
+        assert_eq!(levels.level_of(&Severity::High), Some("P1"));
+        assert_eq!(levels.level_of(&Severity::Medium), Some("CVSS 4.0-6.9"));
+        assert_eq!(levels.level_of(&Severity::Low), None);
+    }
+
+    #[test]
+    fn test_pack_presets_beneath_detector_options() {
+        let toml = r#"
+[global]
+packs = ["gas", "defi"]
+
+[detectors.unchecked-fee-bounds]
+field_patterns = ["spread"]
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(
+            config.detector_option::<u64>("storage-collect-in-execute", "max_collect_limit"),
+            Some(30)
+        );
+        assert_eq!(
+            config.detector_option::<Vec<String>>("unchecked-fee-bounds", "field_patterns"),
+            Some(vec!["spread".to_string()])
+        );
+        assert_eq!(
+            Config::default()
+                .detector_option::<u64>("storage-collect-in-execute", "max_collect_limit"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_unknown_pack_rejected() {
+        let dir = scratch_dir("unknown-pack");
+        let path = dir.join(".cosmwasm-guard.toml");
+        std::fs::write(&path, "[global]\npacks = [\"defi\", \"lending\"]\n").unwrap();
+        let err = format!("{:#}", Config::load(&path).unwrap_err());
+        assert!(err.contains("Unknown rule pack `lending`"), "{err}");
+    }
+
+    #[test]
+    fn test_detector_severity_override() {
+        let dir = scratch_dir("severity-override");
+        let path = dir.join(".cosmwasm-guard.toml");
+        std::fs::write(
+            &path,
+            "[global]\n\n[detectors.CWG0001]\nseverity = \"Critical\"\n",
+        )
+        .unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(
+            config.severity_override("missing-addr-validate"),
+            Some(Severity::Critical)
+        );
+        assert_eq!(config.severity_override("unsafe-unwrap"), None);
+
+        std::fs::write(&path, "[detectors.unsafe-unwrap]\nseverity = \"urgent\"\n").unwrap();
+        let err = format!("{:#}", Config::load(&path).unwrap_err());
+        assert!(
+            err.contains("unknown severity `urgent` for detector `unsafe-unwrap`"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_branch_policies_pick_threshold() {
+        let dir = scratch_dir("branch-policies");
+        let path = dir.join(".cosmwasm-guard.toml");
+        std::fs::write(
+            &path,
+            "[global]\nseverity_threshold = \"high\"\n\n\
+             [[branches]]\npattern = \"main\"\nseverity_threshold = \"medium\"\n\n\
+             [[branches]]\npattern = \"release/*\"\nseverity_threshold = \"low\"\n",
+        )
+        .unwrap();
+        let config = Config::load(&path).unwrap();
+        let threshold = |branch| config.branch_threshold(branch);
+        assert_eq!(threshold("main"), Some(Severity::Medium));
+        assert_eq!(threshold("release/1.4"), Some(Severity::Low));
+        assert_eq!(threshold("release/1.4/hotfix"), None);
+        assert_eq!(threshold("feature/x"), None);
+
+        std::fs::write(
+            &path,
+            "[[branches]]\npattern = \"main\"\nseverity_threshold = \"urgent\"\n",
+        )
+        .unwrap();
+        let err = format!("{:#}", Config::load(&path).unwrap_err());
+        assert!(
+            err.contains("unknown severity `urgent` for branches `main`"),
+            "{err}"
+        );
+
+        std::fs::write(&path, "[global]\nseverity_threshold = \"hgih\"\n").unwrap();
+        let err = format!("{:#}", Config::load(&path).unwrap_err());
+        assert!(err.contains("unknown severity_threshold `hgih`"), "{err}");
+    }
+
+    #[test]
+    fn test_invalid_variant_pattern_rejected_at_load() {
+        let dir = scratch_dir("variant-pattern");
+        let path = dir.join(".cosmwasm-guard.toml");
+        std::fs::write(
+            &path,
+            "[detectors.unguarded-parameter-update]\nvariant_pattern = \"^(Set\"\n",
+        )
+        .unwrap();
+        let err = format!("{:#}", Config::load(&path).unwrap_err());
+        assert!(err.contains("bad variant_pattern"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_owner_rules() {
+        let toml = r#"
+[[owners]]
+pattern = "contracts/"
+team = "@org/contracts"
+
+[[owners]]
+pattern = "contracts/vault/"
+team = "@org/vault"
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(
+            config.owner_of(Path::new("contracts/vault/src/lib.rs")),
+            Some("@org/vault")
+        );
+        assert_eq!(
+            config.owner_of(Path::new("contracts/dex/src/lib.rs")),
+            Some("@org/contracts")
+        );
+        assert_eq!(Config::default().owner_of(Path::new("src/lib.rs")), None);
+    }
+
+    #[test]
+    fn test_default_toml_parses() {
+        let config: Config = toml::from_str(Config::default_toml()).unwrap();
+        assert_eq!(config.metrics.max_storage_writes, 6);
+    }
+
+    #[test]
+    fn test_detector_codes_accepted() {
+        let dir = scratch_dir("codes");
+        std::fs::write(
+            dir.join(".cosmwasm-guard.toml"),
+            "[detectors.CWG0005]\nenabled = false\n",
+        )
+        .unwrap();
+        let config = Config::load(&dir.join(".cosmwasm-guard.toml")).unwrap();
+        assert!(!config.is_detector_enabled("unsafe-unwrap"));
+
+        let source_map = HashMap::from([(
+            PathBuf::from("test.rs"),
+            "// cosmwasm-guard-ignore: CWG0005, missing-addr-validate\nlet x = foo.unwrap();\n"
+                .to_string(),
+        )]);
+        let suppressions = parse_inline_suppressions(&source_map);
+        assert_eq!(
+            suppressions[&(PathBuf::from("test.rs"), 2)],
+            vec!["unsafe-unwrap", "missing-addr-validate"]
+        );
+    }
+
     #[test]
     fn test_inline_suppression_parsing() {
         let mut source_map = HashMap::new();
@@ -267,9 +1304,11 @@ files = ["tests/**"]
                     start_col: 0,
                     end_col: 0,
                     snippet: None,
+                    byte_range: None,
                 }],
                 recommendation: None,
                 fix: None,
+                ..Default::default()
             },
             Finding {
                 detector_name: "missing-addr-validate".to_string(),
@@ -284,9 +1323,11 @@ files = ["tests/**"]
                     start_col: 0,
                     end_col: 0,
                     snippet: None,
+                    byte_range: None,
                 }],
                 recommendation: None,
                 fix: None,
+                ..Default::default()
             },
         ];
 
@@ -0,0 +1,299 @@
+//! Severity escalation when findings combine into something worse than each
+//! alone, e.g. a missing access check in a handler that also writes the admin.
+//! Rules are matched per handler: the execute variant whose match arm or
+//! handler functions contain the finding.
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::codes;
+use crate::config::parse_severity;
+use crate::detector::AnalysisContext;
+use crate::finding::{Finding, Severity};
+use crate::ranking::enclosing_function;
+use crate::roles::VariantPermission;
+
+/// Fact about a handler a rule can require
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Condition {
+    /// The handler writes privileged state: roles, admin or config items
+    PrivilegedWrite,
+    /// The handler sends funds with a `BankMsg`
+    BankSend,
+}
+
+/// One `[[correlations]]` rule: findings of `detector` in a handler where
+/// every detector in `with` also reports and every condition in `requires`
+/// holds are raised to `escalate_to`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrelationRule {
+    pub name: String,
+    /// Detector name or code whose findings are escalated
+    pub detector: String,
+    /// Detectors that must also report in the same handler
+    #[serde(default)]
+    pub with: Vec<String>,
+    #[serde(default)]
+    pub requires: Vec<Condition>,
+    #[serde(deserialize_with = "severity_name")]
+    pub escalate_to: Severity,
+}
+
+fn severity_name<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Severity, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    parse_severity(&name)
+        .ok_or_else(|| serde::de::Error::custom(format!("unknown severity `{name}`")))
+}
+
+/// Rules applied to every analysis; a configured rule with the same name
+/// replaces one
+pub fn builtin_rules() -> Vec<CorrelationRule> {
+    vec![
+        CorrelationRule {
+            name: "unguarded-privileged-write".to_string(),
+            detector: "missing-access-control".to_string(),
+            with: Vec::new(),
+            requires: vec![Condition::PrivilegedWrite],
+            escalate_to: Severity::Critical,
+        },
+        CorrelationRule {
+            name: "unvalidated-address-paid".to_string(),
+            detector: "missing-addr-validate".to_string(),
+            with: Vec::new(),
+            requires: vec![Condition::BankSend],
+            escalate_to: Severity::High,
+        },
+    ]
+}
+
+/// Execute variants whose arm or handler functions contain `finding`. A
+/// finding on a dispatcher outside its arms (e.g. on the whole `execute`
+/// entry point) is in every variant it dispatches.
+fn handlers_of<'c>(finding: &Finding, ctx: &'c AnalysisContext) -> Vec<&'c VariantPermission> {
+    let Some(loc) = finding.locations.first() else {
+        return Vec::new();
+    };
+    let function = enclosing_function(finding, ctx);
+    let variants = &ctx.permissions().variants;
+    let within: Vec<&VariantPermission> = variants
+        .iter()
+        .filter(|variant| {
+            variant.arm.as_ref().is_some_and(|arm| {
+                arm.file == loc.file && (arm.start_line..=arm.end_line).contains(&loc.start_line)
+            }) || function.is_some_and(|f| variant.handlers.iter().any(|h| h == f))
+        })
+        .collect();
+    if !within.is_empty() {
+        return within;
+    }
+    variants
+        .iter()
+        .filter(|variant| function.is_some_and(|f| variant.dispatcher.as_deref() == Some(f)))
+        .collect()
+}
+
+/// Why `condition` holds for `variant`, if it does
+fn check(
+    condition: Condition,
+    variant: &VariantPermission,
+    ctx: &AnalysisContext,
+) -> Option<String> {
+    match condition {
+        Condition::PrivilegedWrite => (!variant.privileged_writes.is_empty())
+            .then(|| format!("writes {}", variant.privileged_writes.join(", "))),
+        Condition::BankSend => variant
+            .handlers
+            .iter()
+            .any(|h| {
+                ctx.emitted_messages(h)
+                    .iter()
+                    .any(|m| m.msg_type.starts_with("BankMsg"))
+            })
+            .then(|| "sends funds with a BankMsg".to_string()),
+    }
+}
+
+/// Raise the severity of findings matched by a rule, noting the rule and
+/// the co-occurring evidence in their description
+pub fn escalate(findings: &mut [Finding], ctx: &AnalysisContext, configured: &[CorrelationRule]) {
+    let mut rules = builtin_rules();
+    rules.retain(|rule| !configured.iter().any(|c| c.name == rule.name));
+    rules.extend(configured.iter().cloned());
+
+    let handlers: Vec<Vec<&VariantPermission>> =
+        findings.iter().map(|f| handlers_of(f, ctx)).collect();
+    let reported_in = |detector: &str, variant: &VariantPermission| {
+        findings.iter().zip(&handlers).any(|(f, hs)| {
+            f.detector_name == detector && hs.iter().any(|h| std::ptr::eq(*h, variant))
+        })
+    };
+
+    let mut escalations = Vec::new();
+    for (i, finding) in findings.iter().enumerate() {
+        for rule in &rules {
+            if codes::resolve(&rule.detector) != finding.detector_name
+                || rule.escalate_to >= finding.severity
+            {
+                continue;
+            }
+            let matched = handlers[i].iter().find_map(|variant| {
+                let mut evidence = Vec::new();
+                for other in &rule.with {
+                    let other = codes::resolve(other);
+                    if !reported_in(other, variant) {
+                        return None;
+                    }
+                    evidence.push(format!("{other} also reports in it"));
+                }
+                for condition in &rule.requires {
+                    evidence.push(check(*condition, variant, ctx)?);
+                }
+                Some(format!(
+                    "the `{}::{}` handler {}",
+                    variant.message,
+                    variant.variant,
+                    evidence.join(" and ")
+                ))
+            });
+            if let Some(reason) = matched {
+                escalations.push((i, rule, reason));
+                break;
+            }
+        }
+    }
+
+    for (i, rule, reason) in escalations {
+        let finding = &mut findings[i];
+        finding.severity = rule.escalate_to.clone();
+        finding.description.push_str(&format!(
+            " Escalated to {} by correlation rule `{}`: {reason}.",
+            rule.escalate_to, rule.name
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::finding::{test_finding, Confidence};
+    use crate::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    const SOURCE: &str = r#"
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::SetAdmin { admin } => set_admin(deps, admin),
+        ExecuteMsg::Withdraw { to } => withdraw(deps, to),
+        ExecuteMsg::Ping {} => ping(deps),
+    }
+}
+
+fn set_admin(deps: DepsMut, admin: String) -> Result<Response, ContractError> {
+    ADMIN.save(deps.storage, &admin)?;
+    Ok(Response::new())
+}
+
+fn withdraw(deps: DepsMut, to: String) -> Result<Response, ContractError> {
+    let msg = BankMsg::Send { to_address: to, amount: coins(100, "uatom") };
+    Ok(Response::new().add_message(msg))
+}
+
+fn ping(deps: DepsMut) -> Result<Response, ContractError> {
+    COUNT.save(deps.storage, &1)?;
+    Ok(Response::new())
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    SetAdmin { admin: String },
+    Withdraw { to: String },
+    Ping {},
+}
+
+pub const ADMIN: Item<String> = Item::new("admin");
+pub const COUNT: Item<u64> = Item::new("count");
+"#;
+
+    fn escalated(findings: &mut [Finding], rules: &[CorrelationRule]) -> Vec<Severity> {
+        let contract =
+            ContractVisitor::extract(PathBuf::from("test.rs"), parse_source(SOURCE).unwrap());
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([(PathBuf::from("test.rs"), SOURCE.to_string())]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        escalate(findings, &ctx, rules);
+        findings.iter().map(|f| f.severity.clone()).collect()
+    }
+
+    #[test]
+    fn test_builtin_rules_escalate_by_handler_facts() {
+        let mut findings = vec![
+            test_finding(
+                "missing-access-control",
+                Severity::High,
+                Confidence::Medium,
+                11,
+            ),
+            test_finding(
+                "missing-access-control",
+                Severity::High,
+                Confidence::Medium,
+                22,
+            ),
+            test_finding(
+                "missing-addr-validate",
+                Severity::Medium,
+                Confidence::Medium,
+                17,
+            ),
+            test_finding(
+                "missing-addr-validate",
+                Severity::Medium,
+                Confidence::Medium,
+                12,
+            ),
+        ];
+        assert_eq!(
+            escalated(&mut findings, &[]),
+            vec![
+                Severity::Critical,
+                Severity::High,
+                Severity::High,
+                Severity::Medium
+            ]
+        );
+        assert!(findings[0].description.contains(
+            "Escalated to Critical by correlation rule `unguarded-privileged-write`: \
+             the `ExecuteMsg::SetAdmin` handler writes ADMIN."
+        ));
+    }
+
+    #[test]
+    fn test_configured_rule_requires_co_occurring_detector() {
+        let rules: Vec<CorrelationRule> = toml::from_str::<toml::Table>(
+            r#"
+            [[correlations]]
+            name = "unchecked-ping"
+            detector = "CWG0001"
+            with = ["unsafe-unwrap"]
+            escalate_to = "critical"
+            "#,
+        )
+        .unwrap()["correlations"]
+            .clone()
+            .try_into()
+            .unwrap();
+        let detector = codes::resolve("CWG0001");
+        let mut findings = vec![
+            test_finding(detector, Severity::Medium, Confidence::Medium, 22),
+            test_finding(detector, Severity::Medium, Confidence::Medium, 11),
+            test_finding("unsafe-unwrap", Severity::Low, Confidence::Medium, 23),
+        ];
+        assert_eq!(
+            escalated(&mut findings, &rules),
+            vec![Severity::Critical, Severity::Medium, Severity::Low]
+        );
+    }
+}
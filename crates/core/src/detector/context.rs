@@ -1,20 +1,79 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::thread::{self, ThreadId};
 
-use crate::ast::ContractInfo;
-use crate::ir::ContractIr;
+use proc_macro2::Span;
+
+use crate::ast::spans::SpanIndex;
+use crate::ast::{ContractInfo, SourceSpan, TrustedCheck};
+use crate::auth::AuthRegistry;
+use crate::config::Config;
+use crate::ir::{
+    CallGraph, ContractIr, DefUse, EmittedMessage, FunctionSummary, Guard, SsaVar, StorageAccess,
+};
+use crate::roles::PermissionMatrix;
 
 /// Provides detectors with access to parsed contract info, SSA IR, and source code.
+///
+/// Whole-contract analyses (call graph, def-use chains, function summaries) are
+/// computed on first use and memoized, so every detector after the first one
+/// that asks gets them for free. Their file-local parts are taken from the
+/// cache when the IR carries them (see [`crate::ir::FunctionAnalyses`]).
 pub struct AnalysisContext<'a> {
     pub contract: &'a ContractInfo,
     pub ir: &'a ContractIr,
     source_files: &'a HashMap<PathBuf, String>,
+    config: &'a Config,
+    call_graph: OnceLock<CallGraph>,
+    def_use: OnceLock<HashMap<String, HashMap<SsaVar, DefUse>>>,
+    summaries: OnceLock<HashMap<String, FunctionSummary>>,
+    auth: OnceLock<AuthRegistry>,
+    permissions: OnceLock<PermissionMatrix>,
+    spans: OnceLock<SpanIndex>,
+    /// Thread that parsed the raw ASTs, the only one that can resolve spans
+    span_thread: ThreadId,
+    /// Where other threads send spans to resolve while [`Self::serve_spans`] runs
+    span_requests: Mutex<Option<mpsc::Sender<SpanRequest>>>,
+}
+
+/// First and last token span of a node, resolved on the parsing thread
+struct SpanRequest {
+    first: Span,
+    last: Span,
+    reply: mpsc::Sender<Option<SourceSpan>>,
+}
+
+// SAFETY: outside a proc macro a span is a plain character range; only
+// resolving it reads the parsing thread's source map, which happens on that
+// thread after the request has been sent there.
+unsafe impl Send for SpanRequest {}
+
+/// Stops [`AnalysisContext::serve_spans`] once its work is done, even by a panic
+struct CloseRequests<'c>(&'c Mutex<Option<mpsc::Sender<SpanRequest>>>);
+
+impl Drop for CloseRequests<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut requests) = self.0.lock() {
+            requests.take();
+        }
+    }
+}
+
+/// Shared default config for contexts built without an explicit one (tests, library use)
+fn default_config() -> &'static Config {
+    static DEFAULT: OnceLock<Config> = OnceLock::new();
+    DEFAULT.get_or_init(Config::default)
 }
 
 // SAFETY: AnalysisContext holds only shared references to immutable data.
-// The sole !Send/!Sync component is proc_macro2::Span inside syn::File,
-// but proc-macro2 stores spans in a global Mutex-protected map, making
-// read access thread-safe. Detectors never mutate the context.
+// The sole !Send/!Sync component is proc_macro2::Span, inside syn::File and
+// the span index. Outside a proc macro a span is a plain character range;
+// only resolving it through proc-macro2 (`start()`, `byte_range()`, `join()`)
+// reads the parsing thread's source map. The span index is only touched on
+// that thread, and detectors resolve spans through `span_of` (enforced by the
+// detectors crate's clippy.toml), which sends them there. Detectors never
+// mutate the context.
 unsafe impl Send for AnalysisContext<'_> {}
 unsafe impl Sync for AnalysisContext<'_> {}
 
@@ -28,7 +87,179 @@ impl<'a> AnalysisContext<'a> {
             contract,
             ir,
             source_files,
+            config: default_config(),
+            call_graph: OnceLock::new(),
+            def_use: OnceLock::new(),
+            summaries: OnceLock::new(),
+            auth: OnceLock::new(),
+            permissions: OnceLock::new(),
+            spans: OnceLock::new(),
+            span_thread: thread::current().id(),
+            span_requests: Mutex::new(None),
+        }
+    }
+
+    /// Attach the project config so detectors can read their thresholds
+    pub fn with_config(mut self, config: &'a Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Project config (defaults when none was attached)
+    pub fn config(&self) -> &Config {
+        self.config
+    }
+
+    /// Names of project helpers trusted to perform `check`: those configured
+    /// under `[auth]` or `[guards]` and functions annotated with
+    /// `cosmwasm_guard::assume`
+    pub fn trusted_validators(&self, check: TrustedCheck) -> Vec<String> {
+        let configured = match check {
+            TrustedCheck::Sender => &self.config.auth.helpers,
+            TrustedCheck::Funds => &self.config.guards.funds,
+        };
+        let annotated = self
+            .contract
+            .functions
+            .iter()
+            .filter(|f| f.assumes.contains(&check))
+            .map(|f| f.name.clone());
+        configured.iter().cloned().chain(annotated).collect()
+    }
+
+    /// Access-control helpers recognized as sender checks
+    pub fn auth(&self) -> &AuthRegistry {
+        self.auth
+            .get_or_init(|| AuthRegistry::new(self.trusted_validators(TrustedCheck::Sender)))
+    }
+
+    /// Roles of the contract and the roles allowed to call each execute variant
+    pub fn permissions(&self) -> &PermissionMatrix {
+        self.permissions.get_or_init(|| PermissionMatrix::build(self))
+    }
+
+    /// Direct call edges between contract functions
+    pub fn call_graph(&self) -> &CallGraph {
+        self.call_graph.get_or_init(|| CallGraph::build(self.ir))
+    }
+
+    /// Def-use chains of a function's SSA variables, if it has IR
+    pub fn def_use(&self, function: &str) -> Option<&HashMap<SsaVar, DefUse>> {
+        let func = self
+            .ir
+            .functions
+            .iter()
+            .rev()
+            .find(|f| f.name == function)?;
+        if let Some(analyses) = &func.analyses {
+            return Some(&analyses.def_use);
+        }
+        self.def_use
+            .get_or_init(|| {
+                self.ir
+                    .functions
+                    .iter()
+                    .filter(|f| f.analyses.is_none())
+                    .map(|f| (f.name.clone(), f.cfg.def_use_chains()))
+                    .collect()
+            })
+            .get(function)
+    }
+
+    /// Storage effects, emitted messages and guards of a function, if it has
+    /// IR, with calls to configured auth helpers and fund-moving sinks
+    pub fn summary(&self, function: &str) -> Option<&FunctionSummary> {
+        self.summaries
+            .get_or_init(|| {
+                self.ir
+                    .functions
+                    .iter()
+                    .map(|f| {
+                        let summary = match &f.analyses {
+                            Some(analyses) => analyses.summary.clone(),
+                            None => FunctionSummary::from_ir(f),
+                        };
+                        let summary = summary
+                            .trusting(f, self.auth())
+                            .sending(f, &self.config.taint.sinks);
+                        (f.name.clone(), summary)
+                    })
+                    .collect()
+            })
+            .get(function)
+    }
+
+    /// State items loaded by a function (empty for unknown functions)
+    pub fn storage_reads(&self, function: &str) -> &[StorageAccess] {
+        self.summary(function)
+            .map_or(&[], |s| s.storage_reads.as_slice())
+    }
+
+    /// State items saved or updated by a function
+    pub fn storage_writes(&self, function: &str) -> &[StorageAccess] {
+        self.summary(function)
+            .map_or(&[], |s| s.storage_writes.as_slice())
+    }
+
+    /// Chain messages (`BankMsg`, `WasmMsg`, ...) constructed by a function
+    pub fn emitted_messages(&self, function: &str) -> &[EmittedMessage] {
+        self.summary(function)
+            .map_or(&[], |s| s.emitted_messages.as_slice())
+    }
+
+    /// Sender checks and aborting assertions in a function
+    pub fn guards(&self, function: &str) -> &[Guard] {
+        self.summary(function).map_or(&[], |s| s.guards.as_slice())
+    }
+
+    /// Location of a node of the raw ASTs, from its first to its last token.
+    /// On a thread other than the one that built the context, only valid
+    /// inside [`Self::serve_spans`].
+    pub fn span_of(&self, node: &impl quote::ToTokens) -> Option<SourceSpan> {
+        let mut tokens = node.to_token_stream().into_iter();
+        let first = tokens.next()?.span();
+        let last = tokens.last().map_or(first, |t| t.span());
+        if thread::current().id() == self.span_thread {
+            return self.locate(first, last);
+        }
+        let requests = self
+            .span_requests
+            .lock()
+            .ok()?
+            .clone()
+            .expect("span_of called off the parsing thread outside serve_spans");
+        let (reply, answer) = mpsc::channel();
+        requests.send(SpanRequest { first, last, reply }).ok()?;
+        answer.recv().ok()?
+    }
+
+    /// Run `work` on another thread while this one, which must have built
+    /// the context, answers the `span_of` calls `work` makes from any thread
+    pub fn serve_spans<R: Send>(&self, work: impl FnOnce() -> R + Send) -> R {
+        if thread::current().id() != self.span_thread {
+            return work();
         }
+        let (requests, incoming) = mpsc::channel();
+        *self.span_requests.lock().unwrap() = Some(requests);
+        thread::scope(|s| {
+            let worker = s.spawn(|| {
+                let _close = CloseRequests(&self.span_requests);
+                work()
+            });
+            for SpanRequest { first, last, reply } in incoming {
+                let _ = reply.send(self.locate(first, last));
+            }
+            worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })
+    }
+
+    /// Resolve a span range; only on the parsing thread
+    fn locate(&self, first: Span, last: Span) -> Option<SourceSpan> {
+        self.spans
+            .get_or_init(|| SpanIndex::new(&self.contract.raw_asts))
+            .locate(first, last)
     }
 
     /// Get raw ASTs for pattern matching
@@ -58,3 +289,126 @@ impl<'a> AnalysisContext<'a> {
         Some(lines[start..end].join("\n"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::ir::builder::IrBuilder;
+
+    #[test]
+    fn test_analyses_are_memoized() {
+        let source = r#"
+            fn execute(deps: DepsMut) { save_it(deps); }
+            fn save_it(deps: DepsMut) { let x = 1; VALUE.save(deps.storage, &x); }
+        "#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::new();
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+
+        assert!(std::ptr::eq(ctx.call_graph(), ctx.call_graph()));
+        let reachable = ctx.call_graph().reachable_from(&["execute"]);
+        assert!(reachable.contains("save_it"));
+        assert_eq!(ctx.storage_writes("save_it").len(), 1);
+        assert!(std::ptr::eq(
+            ctx.summary("save_it").unwrap(),
+            ctx.summary("save_it").unwrap()
+        ));
+        let chains = ctx.def_use("save_it").unwrap();
+        assert!(chains.keys().any(|v| v.name == "x"));
+        assert!(ctx.storage_writes("missing").is_empty());
+    }
+
+    #[test]
+    fn test_cached_analyses_match_fresh_ones() {
+        let source = r#"
+            fn execute(deps: DepsMut, info: MessageInfo) {
+                only_owner(deps.as_ref(), &info)?;
+                let x = 1;
+                VALUE.save(deps.storage, &x);
+            }
+            #[cosmwasm_guard::assume(sender)]
+            fn only_owner(deps: Deps, info: &MessageInfo) {}
+        "#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut cached = ir.clone();
+        for func in &mut cached.functions {
+            func.analyses = Some(crate::ir::FunctionAnalyses::compute(func));
+        }
+        let sources = HashMap::new();
+        let fresh = AnalysisContext::new(&contract, &ir, &sources);
+        let warm = AnalysisContext::new(&contract, &cached, &sources);
+
+        // Callees and trusted helpers are resolved at load, not from the cache
+        let callees: Vec<&str> = warm.call_graph().callees("execute").collect();
+        assert_eq!(callees, vec!["only_owner"]);
+        assert_eq!(warm.guards("execute").len(), fresh.guards("execute").len());
+        assert!(warm
+            .guards("execute")
+            .iter()
+            .any(|g| g.kind == crate::ir::GuardKind::Sender));
+        assert_eq!(warm.storage_writes("execute").len(), 1);
+        assert_eq!(
+            warm.def_use("execute").unwrap().len(),
+            fresh.def_use("execute").unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_configured_sinks_emit_messages() {
+        let source = r#"
+            fn execute(deps: DepsMut, to: String, amount: Uint128) {
+                payout(&to, amount)?;
+            }
+        "#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::new();
+        assert!(AnalysisContext::new(&contract, &ir, &sources)
+            .emitted_messages("execute")
+            .is_empty());
+
+        let config: Config = toml::from_str("[taint]\nsinks = [\"payout\"]").unwrap();
+        let ctx = AnalysisContext::new(&contract, &ir, &sources).with_config(&config);
+        let messages = ctx.emitted_messages("execute");
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].msg_type, "BankMsg::Send");
+        assert_eq!(messages[0].fields.len(), 2);
+    }
+
+    #[test]
+    fn test_resolves_spans_for_other_threads() {
+        let source = "// héllo\nfn main() {\n    let x = vec.len();\n}\n";
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::new();
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+
+        let syn::Item::Fn(main) = &ctx.raw_asts()[0].1.items[0] else {
+            panic!("expected fn");
+        };
+        let syn::Stmt::Local(local) = &main.block.stmts[0] else {
+            panic!("expected let");
+        };
+        let init = &local.init.as_ref().unwrap().expr;
+        let here = ctx.span_of(init).unwrap();
+        assert_eq!((here.start_line, here.start_col), (3, 12));
+
+        // A detector's view of the AST, as handed to rayon
+        struct Shared<'a>(&'a syn::Expr);
+        // SAFETY: the expression is only read, as detectors read the AST
+        unsafe impl Sync for Shared<'_> {}
+        let shared = &Shared(init);
+        let ctx = &ctx;
+        let there = ctx.serve_spans(|| {
+            thread::scope(|s| s.spawn(move || ctx.span_of(shared.0)).join().unwrap())
+        });
+        assert_eq!(there, Some(here));
+    }
+}
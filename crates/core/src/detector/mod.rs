@@ -4,4 +4,4 @@ pub mod traits;
 
 pub use context::AnalysisContext;
 pub use registry::DetectorRegistry;
-pub use traits::Detector;
+pub use traits::{Detector, Maturity};
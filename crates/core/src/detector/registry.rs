@@ -1,14 +1,11 @@
-use std::sync::Mutex;
-
 use super::context::AnalysisContext;
 use super::traits::Detector;
 use crate::finding::{Finding, Severity};
 
 /// Minimum detector count before switching to parallel execution.
-/// Set high because proc-macro2 span-locations uses a global SourceMap
-/// that panics when spans are accessed across Rayon thread boundaries.
-/// Parallel detection will be enabled once detectors decouple from raw AST spans.
-const PARALLEL_THRESHOLD: usize = usize::MAX;
+/// Detectors resolve spans through `AnalysisContext::span_of`, so they may
+/// run on any thread.
+const PARALLEL_THRESHOLD: usize = 4;
 
 /// Registry that holds all detectors and runs them against contracts.
 pub struct DetectorRegistry {
@@ -35,36 +32,19 @@ impl DetectorRegistry {
     /// Run all registered detectors, return aggregated findings sorted by severity.
     /// Uses rayon::scope for parallel execution when detector count exceeds threshold.
     pub fn run_all(&self, context: &AnalysisContext) -> Vec<Finding> {
-        let mut findings = if self.detectors.len() >= PARALLEL_THRESHOLD {
-            run_parallel(&self.detectors, context)
-        } else {
-            self.detectors
-                .iter()
-                .flat_map(|d| d.detect(context))
-                .collect()
-        };
-        findings.sort_by(|a, b| a.severity.cmp(&b.severity));
-        findings
+        let all: Vec<&dyn Detector> = self.detectors.iter().map(|d| &**d).collect();
+        run_detectors(&all, context)
     }
 
     /// Run only detectors matching the given names
     pub fn run_selected(&self, names: &[&str], context: &AnalysisContext) -> Vec<Finding> {
-        let selected: Vec<&Box<dyn Detector>> = self
+        let selected: Vec<&dyn Detector> = self
             .detectors
             .iter()
             .filter(|d| names.contains(&d.name()))
+            .map(|d| &**d)
             .collect();
-        let mut findings = if selected.len() >= PARALLEL_THRESHOLD {
-            let as_refs: Vec<&dyn Detector> = selected.iter().map(|d| &***d).collect();
-            run_parallel_refs(&as_refs, context)
-        } else {
-            selected
-                .iter()
-                .flat_map(|d| d.detect(context))
-                .collect()
-        };
-        findings.sort_by(|a, b| a.severity.cmp(&b.severity));
-        findings
+        run_detectors(&selected, context)
     }
 
     /// List all registered detector names
@@ -81,36 +61,38 @@ impl DetectorRegistry {
     }
 }
 
+/// Run `detectors` and sort their findings by severity. Findings of equal
+/// severity keep registration order however the detectors were scheduled.
+fn run_detectors(detectors: &[&dyn Detector], context: &AnalysisContext) -> Vec<Finding> {
+    // A rayon worker cannot wait on span requests without starving the pool
+    let parallel = detectors.len() >= PARALLEL_THRESHOLD && rayon::current_thread_index().is_none();
+    let mut findings: Vec<Finding> = if parallel {
+        run_parallel(detectors, context)
+            .into_iter()
+            .flatten()
+            .collect()
+    } else {
+        detectors.iter().flat_map(|d| d.detect(context)).collect()
+    };
+    findings.sort_by(|a, b| a.severity.cmp(&b.severity));
+    findings
+}
+
 /// Run detectors in parallel using rayon::scope (safe scoped parallelism).
 /// rayon::scope guarantees all spawned tasks complete before returning,
 /// so references to context and detectors are valid for the entire scope.
-fn run_parallel(detectors: &[Box<dyn Detector>], context: &AnalysisContext) -> Vec<Finding> {
-    let results: Mutex<Vec<Finding>> = Mutex::new(Vec::new());
-    rayon::scope(|s| {
-        for detector in detectors {
-            let results = &results;
-            s.spawn(move |_| {
-                let findings = detector.detect(context);
-                results.lock().unwrap().extend(findings);
-            });
-        }
-    });
-    results.into_inner().unwrap()
-}
-
-/// Same as run_parallel but for a slice of trait object references
-fn run_parallel_refs(detectors: &[&dyn Detector], context: &AnalysisContext) -> Vec<Finding> {
-    let results: Mutex<Vec<Finding>> = Mutex::new(Vec::new());
-    rayon::scope(|s| {
-        for detector in detectors {
-            let results = &results;
-            s.spawn(move |_| {
-                let findings = detector.detect(context);
-                results.lock().unwrap().extend(findings);
-            });
-        }
+/// Each detector writes its own slot, so results come back in input order.
+/// The calling thread resolves the detectors' spans meanwhile.
+fn run_parallel(detectors: &[&dyn Detector], context: &AnalysisContext) -> Vec<Vec<Finding>> {
+    let mut results: Vec<Vec<Finding>> = vec![Vec::new(); detectors.len()];
+    context.serve_spans(|| {
+        rayon::scope(|s| {
+            for (slot, detector) in results.iter_mut().zip(detectors) {
+                s.spawn(move |_| *slot = detector.detect(context));
+            }
+        })
     });
-    results.into_inner().unwrap()
+    results
 }
 
 impl Default for DetectorRegistry {
@@ -153,6 +135,7 @@ mod tests {
                 locations: vec![],
                 recommendation: None,
                 fix: None,
+                ..Default::default()
             }]
         }
     }
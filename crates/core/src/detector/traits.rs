@@ -1,6 +1,26 @@
 use super::context::AnalysisContext;
 use crate::finding::{Confidence, Finding, Severity};
 
+/// How mature a detector is. Experimental detectors are noisier and only run
+/// when opted into via `--experimental` or `experimental = true` in config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Maturity {
+    Stable,
+    Beta,
+    Experimental,
+}
+
+impl std::fmt::Display for Maturity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Maturity::Stable => "Stable",
+            Maturity::Beta => "Beta",
+            Maturity::Experimental => "Experimental",
+        };
+        f.pad(label)
+    }
+}
+
 /// Core trait for all vulnerability detectors.
 /// Implementors analyze a CosmWasm contract and return findings.
 pub trait Detector: Send + Sync {
@@ -16,6 +36,11 @@ pub trait Detector: Send + Sync {
     /// Default confidence level of findings from this detector
     fn confidence(&self) -> Confidence;
 
+    /// Maturity tier; detectors are Stable unless they say otherwise
+    fn maturity(&self) -> Maturity {
+        Maturity::Stable
+    }
+
     /// Run detection on the given analysis context, return findings
     fn detect(&self, context: &AnalysisContext) -> Vec<Finding>;
 }
@@ -0,0 +1,374 @@
+//! Fork drift: how a forked contract differs from the upstream it was copied
+//! from. Message enums, state keys and the guards of each execute variant are
+//! compared by name, and the divergences that weaken the upstream's security
+//! (removed checks, variants anyone can now call, new privileged variants,
+//! moved storage keys) are marked so reviewers look at them first.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::ast::{MessageEnum, StateItem};
+use crate::detector::AnalysisContext;
+use crate::ir::summary::GuardKind;
+use crate::roles::VariantPermission;
+
+/// What changed between upstream and fork
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DivergenceKind {
+    VariantAdded,
+    VariantRemoved,
+    VariantFieldsChanged,
+    StateAdded,
+    StateRemoved,
+    StateChanged,
+    /// A role upstream checks before the variant runs is no longer checked
+    RoleCheckRemoved,
+    /// The variant runs fewer sender or condition checks than upstream
+    GuardsRemoved,
+    /// The variant writes privileged state upstream's does not
+    PrivilegedWriteAdded,
+}
+
+/// One difference between upstream and fork
+#[derive(Debug, Clone, Serialize)]
+pub struct Divergence {
+    pub kind: DivergenceKind,
+    /// What differs, e.g. `ExecuteMsg::Mint` or the state item `CONFIG`
+    pub subject: String,
+    pub detail: String,
+    /// Weakens access control or storage compared to upstream
+    pub security_relevant: bool,
+}
+
+/// Sender and condition checks the handlers of a variant run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct GuardCounts {
+    sender: usize,
+    conditions: usize,
+}
+
+fn guard_counts(variant: &VariantPermission, ctx: &AnalysisContext) -> GuardCounts {
+    let handlers: BTreeSet<&str> = variant.handlers.iter().map(String::as_str).collect();
+    let mut counts = GuardCounts::default();
+    for guard in handlers.into_iter().flat_map(|h| ctx.guards(h)) {
+        match guard.kind {
+            GuardKind::Sender => counts.sender += 1,
+            GuardKind::Condition => counts.conditions += 1,
+        }
+    }
+    counts
+}
+
+fn variants<'a>(ctx: &'a AnalysisContext) -> BTreeMap<String, &'a VariantPermission> {
+    ctx.permissions()
+        .variants
+        .iter()
+        .map(|v| (format!("{}::{}", v.message, v.variant), v))
+        .collect()
+}
+
+fn message_fields(enums: &[MessageEnum]) -> BTreeMap<String, Vec<String>> {
+    enums
+        .iter()
+        .flat_map(|e| {
+            e.variants.iter().map(move |v| {
+                let fields = v
+                    .fields
+                    .iter()
+                    .map(|f| format!("{}: {}", f.name, f.type_name))
+                    .collect();
+                (format!("{}::{}", e.name, v.name), fields)
+            })
+        })
+        .collect()
+}
+
+fn state_shape(item: &StateItem) -> String {
+    let types: Vec<&str> = item
+        .key_type
+        .iter()
+        .chain([&item.value_type])
+        .map(String::as_str)
+        .collect();
+    format!("{:?}<{}>", item.storage_type, types.join(", "))
+}
+
+/// Divergences of `fork` from `upstream`, security-relevant ones first
+pub fn compare(fork: &AnalysisContext, upstream: &AnalysisContext) -> Vec<Divergence> {
+    let mut out = Vec::new();
+    let mut push = |kind, subject: &str, detail: String, security_relevant| {
+        out.push(Divergence {
+            kind,
+            subject: subject.to_string(),
+            detail,
+            security_relevant,
+        })
+    };
+
+    // Message variants of every kind
+    let fork_fields = message_fields(&fork.contract.message_enums);
+    let upstream_fields = message_fields(&upstream.contract.message_enums);
+    let fork_access = variants(fork);
+    let upstream_access = variants(upstream);
+    for (name, fields) in &fork_fields {
+        match upstream_fields.get(name) {
+            None => {
+                let privileged = fork_access
+                    .get(name)
+                    .filter(|v| !v.privileged_writes.is_empty());
+                let detail = match privileged {
+                    Some(v) if v.callable_by_anyone() => format!(
+                        "new variant callable by anyone writes privileged state: {}",
+                        v.privileged_writes.join(", ")
+                    ),
+                    Some(v) => format!(
+                        "new variant writes privileged state: {} (checks {})",
+                        v.privileged_writes.join(", "),
+                        v.roles.join(", ")
+                    ),
+                    None => "new variant".to_string(),
+                };
+                push(
+                    DivergenceKind::VariantAdded,
+                    name,
+                    detail,
+                    privileged.is_some(),
+                );
+            }
+            Some(upstream) if upstream != fields => push(
+                DivergenceKind::VariantFieldsChanged,
+                name,
+                format!(
+                    "fields {{ {} }} were {{ {} }}",
+                    fields.join(", "),
+                    upstream.join(", ")
+                ),
+                false,
+            ),
+            Some(_) => {}
+        }
+    }
+    for name in upstream_fields.keys() {
+        if !fork_fields.contains_key(name) {
+            push(
+                DivergenceKind::VariantRemoved,
+                name,
+                "variant removed".to_string(),
+                false,
+            );
+        }
+    }
+
+    // Access control of execute variants in both versions
+    for (name, fork_variant) in &fork_access {
+        let Some(upstream_variant) = upstream_access.get(name) else {
+            continue;
+        };
+        let removed: Vec<&str> = upstream_variant
+            .roles
+            .iter()
+            .filter(|r| !fork_variant.roles.contains(r))
+            .map(String::as_str)
+            .collect();
+        if !removed.is_empty() {
+            let detail = if fork_variant.callable_by_anyone() {
+                format!(
+                    "no longer checks {}; now callable by anyone",
+                    removed.join(", ")
+                )
+            } else {
+                format!("no longer checks {}", removed.join(", "))
+            };
+            push(DivergenceKind::RoleCheckRemoved, name, detail, true);
+        }
+
+        let before = guard_counts(upstream_variant, upstream);
+        let after = guard_counts(fork_variant, fork);
+        if after.sender < before.sender || after.conditions < before.conditions {
+            push(
+                DivergenceKind::GuardsRemoved,
+                name,
+                format!(
+                    "{} sender and {} condition checks, upstream has {} and {}",
+                    after.sender, after.conditions, before.sender, before.conditions
+                ),
+                true,
+            );
+        }
+
+        let added: Vec<&str> = fork_variant
+            .privileged_writes
+            .iter()
+            .filter(|w| !upstream_variant.privileged_writes.contains(w))
+            .map(String::as_str)
+            .collect();
+        if !added.is_empty() {
+            push(
+                DivergenceKind::PrivilegedWriteAdded,
+                name,
+                format!("now writes privileged state: {}", added.join(", ")),
+                fork_variant.callable_by_anyone(),
+            );
+        }
+    }
+
+    // State items, matched by name
+    let fork_state: BTreeMap<&str, &StateItem> = fork
+        .contract
+        .state_items
+        .iter()
+        .map(|i| (i.name.as_str(), i))
+        .collect();
+    let upstream_state: BTreeMap<&str, &StateItem> = upstream
+        .contract
+        .state_items
+        .iter()
+        .map(|i| (i.name.as_str(), i))
+        .collect();
+    for (name, item) in &fork_state {
+        let Some(upstream_item) = upstream_state.get(name) else {
+            // A new item reusing an upstream key aliases its storage
+            let shared = item.storage_key.as_ref().and_then(|key| {
+                upstream_state
+                    .values()
+                    .find(|u| u.storage_key.as_ref() == Some(key))
+            });
+            let detail = match shared {
+                Some(u) => format!(
+                    "new item uses the storage key \"{}\" of upstream's {}",
+                    u.storage_key.as_deref().unwrap_or_default(),
+                    u.name
+                ),
+                None => format!("new item {}", state_shape(item)),
+            };
+            push(DivergenceKind::StateAdded, name, detail, shared.is_some());
+            continue;
+        };
+        if item.storage_key != upstream_item.storage_key {
+            push(
+                DivergenceKind::StateChanged,
+                name,
+                format!(
+                    "storage key \"{}\" was \"{}\"; existing data is no longer read",
+                    item.storage_key.as_deref().unwrap_or("?"),
+                    upstream_item.storage_key.as_deref().unwrap_or("?")
+                ),
+                true,
+            );
+        } else if state_shape(item) != state_shape(upstream_item) {
+            push(
+                DivergenceKind::StateChanged,
+                name,
+                format!("{} was {}", state_shape(item), state_shape(upstream_item)),
+                false,
+            );
+        }
+    }
+    for name in upstream_state.keys() {
+        if !fork_state.contains_key(name) {
+            push(
+                DivergenceKind::StateRemoved,
+                name,
+                "item removed".to_string(),
+                false,
+            );
+        }
+    }
+
+    out.sort_by_key(|d| !d.security_relevant);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    const UPSTREAM: &str = r#"
+pub const OWNER: Item<Addr> = Item::new("owner");
+pub const BALANCES: Map<&Addr, Uint128> = Map::new("balance");
+
+pub enum ExecuteMsg { Transfer { recipient: String, amount: Uint128 }, SetOwner { owner: String } }
+
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Transfer { recipient, amount } => transfer(deps, info, recipient, amount),
+        ExecuteMsg::SetOwner { owner } => set_owner(deps, info, owner),
+    }
+}
+
+fn transfer(deps: DepsMut, info: MessageInfo, recipient: String, amount: Uint128) -> StdResult<Response> {
+    if amount.is_zero() {
+        return Err(StdError::generic_err("zero"));
+    }
+    BALANCES.save(deps.storage, &info.sender, &amount)?;
+    Ok(Response::new())
+}
+
+fn set_owner(deps: DepsMut, info: MessageInfo, owner: String) -> StdResult<Response> {
+    if info.sender != OWNER.load(deps.storage)? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    OWNER.save(deps.storage, &deps.api.addr_validate(&owner)?)?;
+    Ok(Response::new())
+}
+"#;
+
+    fn divergences(fork: &str) -> Vec<Divergence> {
+        let build = |source: &str| {
+            let ast = parse_source(source).unwrap();
+            let contract = ContractVisitor::extract(PathBuf::from("contract.rs"), ast);
+            let ir = IrBuilder::build_contract(&contract);
+            (contract, ir)
+        };
+        let (fork_contract, fork_ir) = build(fork);
+        let (upstream_contract, upstream_ir) = build(UPSTREAM);
+        let sources = HashMap::new();
+        let fork_ctx = AnalysisContext::new(&fork_contract, &fork_ir, &sources);
+        let upstream_ctx = AnalysisContext::new(&upstream_contract, &upstream_ir, &sources);
+        compare(&fork_ctx, &upstream_ctx)
+    }
+
+    #[test]
+    fn test_identical_fork_has_no_divergences() {
+        assert!(divergences(UPSTREAM).is_empty());
+    }
+
+    #[test]
+    fn test_removed_check_and_added_privileged_variant() {
+        let fork = UPSTREAM
+            .replace(
+                "    if info.sender != OWNER.load(deps.storage)? {\n        return Err(StdError::generic_err(\"unauthorized\"));\n    }\n",
+                "",
+            )
+            .replace(
+                "SetOwner { owner: String } }",
+                "SetOwner { owner: String }, ForceOwner { owner: String } }",
+            )
+            .replace(
+                "        ExecuteMsg::SetOwner { owner } => set_owner(deps, info, owner),\n",
+                "        ExecuteMsg::SetOwner { owner } => set_owner(deps, info, owner),\n        ExecuteMsg::ForceOwner { owner } => set_owner(deps, info, owner),\n",
+            )
+            .replace("Map::new(\"balance\")", "Map::new(\"balances\")");
+        let found = divergences(&fork);
+        let has = |kind, subject: &str| {
+            found
+                .iter()
+                .any(|d| d.kind == kind && d.subject == subject && d.security_relevant)
+        };
+        assert!(has(DivergenceKind::VariantAdded, "ExecuteMsg::ForceOwner"));
+        assert!(has(
+            DivergenceKind::RoleCheckRemoved,
+            "ExecuteMsg::SetOwner"
+        ));
+        assert!(has(DivergenceKind::GuardsRemoved, "ExecuteMsg::SetOwner"));
+        assert!(has(DivergenceKind::StateChanged, "BALANCES"));
+        assert!(!found.iter().any(|d| d.subject == "ExecuteMsg::Transfer"));
+    }
+}
@@ -0,0 +1,194 @@
+//! Example code: files under a crate's `examples/` and the Rust code blocks
+//! of its doc comments. None of it ships in the contract, but integrators
+//! copy it, so `--include-examples` runs the detectors over it as well.
+//!
+//! Doc-test blocks are laid out at the lines and columns they have in their
+//! file, with the comment markers blanked, so findings point into the doc
+//! comment itself.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::ast::discovery::{discover_rs_files, DiscoveryOptions};
+
+/// Fence attributes of blocks rustdoc compiles as Rust
+const RUST_FENCE_ATTRS: &[&str] = &[
+    "rust",
+    "ignore",
+    "no_run",
+    "should_panic",
+    "test_harness",
+    "edition2015",
+    "edition2018",
+    "edition2021",
+    "edition2024",
+];
+
+/// Example sources of the crate at `crate_root`: its `examples/` files as
+/// they are, and for each of `sources` with doc-test blocks, the code of
+/// those blocks at their original positions
+pub fn collect(
+    crate_root: &Path,
+    sources: &HashMap<PathBuf, String>,
+    discovery: &DiscoveryOptions,
+) -> Vec<(PathBuf, String)> {
+    let mut examples = Vec::new();
+    let dir = crate_root.join("examples");
+    if dir.is_dir() {
+        let discovery = DiscoveryOptions {
+            exclude: discovery.exclude.clone(),
+            respect_gitignore: discovery.respect_gitignore,
+            follow_symlinks: discovery.follow_symlinks,
+            ..Default::default()
+        };
+        // An `examples/` directory without .rs files has nothing to scan
+        let mut files = discover_rs_files(&dir, &discovery).unwrap_or_default();
+        files.sort();
+        examples.extend(files.into_iter().filter_map(|file| {
+            let source = std::fs::read_to_string(&file).ok()?;
+            Some((file, source))
+        }));
+    }
+
+    let mut files: Vec<&PathBuf> = sources.keys().collect();
+    files.sort();
+    for file in files {
+        if let Some(code) = doc_tests(&sources[file]) {
+            examples.push((file.clone(), code));
+        }
+    }
+    examples
+}
+
+/// The doc-test blocks of `source`, each at its original lines with every
+/// other line blank, or `None` when it has none that parse. Blocks without
+/// a `fn main` are wrapped in a function, as rustdoc does, using the fence
+/// lines for the signature and closing brace.
+pub fn doc_tests(source: &str) -> Option<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut out = vec![String::new(); lines.len()];
+    let mut found = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let Some((_, text)) = doc_line(lines[i]) else {
+            i += 1;
+            continue;
+        };
+        let Some(info) = text.trim_start().strip_prefix("```") else {
+            i += 1;
+            continue;
+        };
+        let Some(close) = (i + 1..lines.len()).find(|&j| {
+            doc_line(lines[j]).is_none_or(|(_, text)| text.trim_start().starts_with("```"))
+        }) else {
+            break;
+        };
+        let closed = doc_line(lines[close]).is_some();
+        if closed && is_rust_fence(info) {
+            let code: Vec<String> = lines[i + 1..close].iter().map(|l| code_line(l)).collect();
+            let body = code.join("\n");
+            let wrap = !body.contains("fn main");
+            let (open, end) = if wrap {
+                (format!("fn doc_test_line_{}() {{", i + 1), "}".to_string())
+            } else {
+                (String::new(), String::new())
+            };
+            if syn::parse_file(&format!("{open}\n{body}\n{end}")).is_ok() {
+                out[i] = open;
+                out[close] = end;
+                for (j, line) in code.into_iter().enumerate() {
+                    out[i + 1 + j] = line;
+                }
+                found = true;
+            }
+        }
+        i = close + 1;
+    }
+    found.then(|| out.join("\n") + "\n")
+}
+
+/// Column of the text after the `///` or `//!` marker of a doc comment line,
+/// and that text
+fn doc_line(line: &str) -> Option<(usize, &str)> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    let text = rest
+        .strip_prefix("///")
+        .filter(|t| !t.starts_with('/'))
+        .or_else(|| rest.strip_prefix("//!"))?;
+    Some((indent + 3, text))
+}
+
+/// A doc-test line with its comment marker, and the `# ` of lines rustdoc
+/// hides, replaced by spaces so columns are unchanged
+fn code_line(line: &str) -> String {
+    let Some((start, text)) = doc_line(line) else {
+        return String::new();
+    };
+    let indent = text.len() - text.trim_start().len();
+    let trimmed = &text[indent..];
+    let hidden = if trimmed == "#" {
+        1
+    } else if trimmed.starts_with("# ") {
+        2
+    } else if trimmed.starts_with("##") {
+        // `##` escapes a line that starts with `#`
+        1
+    } else {
+        0
+    };
+    format!(
+        "{}{}",
+        " ".repeat(start + indent + hidden),
+        &trimmed[hidden..]
+    )
+}
+
+/// Whether a fence's info string marks a block rustdoc compiles as Rust
+fn is_rust_fence(info: &str) -> bool {
+    info.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|attr| !attr.is_empty())
+        .all(|attr| RUST_FENCE_ATTRS.contains(&attr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doc_tests_keep_original_positions() {
+        let source = "\
+/// Transfers funds.
+///
+/// ```
+/// # use cosmwasm_std::Addr;
+/// let amount = parse(input).unwrap();
+/// ```
+///
+/// ```text
+/// not rust
+/// ```
+pub fn transfer() {}
+";
+        let code = doc_tests(source).unwrap();
+        let lines: Vec<&str> = code.lines().collect();
+        assert_eq!(lines.len(), source.lines().count());
+        assert_eq!(lines[2], "fn doc_test_line_3() {");
+        assert_eq!(lines[3], "      use cosmwasm_std::Addr;");
+        assert_eq!(lines[4], "    let amount = parse(input).unwrap();");
+        assert_eq!(lines[5], "}");
+        assert!(lines[8].is_empty());
+        assert!(lines[10].is_empty());
+        assert!(syn::parse_file(&code).is_ok());
+    }
+
+    #[test]
+    fn test_doc_tests_skip_other_languages_and_unparseable_blocks() {
+        assert!(doc_tests("//! ```toml\n//! [package]\n//! ```\n").is_none());
+        assert!(doc_tests("/// ```compile_fail\n/// let x: u8 = 256;\n/// ```\n").is_none());
+        assert!(doc_tests("/// ```ignore\n/// let = ;\n/// ```\n").is_none());
+        // Unterminated blocks are not code
+        assert!(doc_tests("/// ```\n/// let x = 1;\nfn f() {}\n").is_none());
+        assert!(doc_tests("//! ```rust,no_run\n//! fn main() {}\n//! ```\n").is_some());
+    }
+}
@@ -2,3 +2,32 @@ pub mod display;
 pub mod types;
 
 pub use types::*;
+
+/// A finding at `line` of `test.rs`, titled after its line, for unit tests.
+#[cfg(test)]
+pub(crate) fn test_finding(
+    detector: &str,
+    severity: Severity,
+    confidence: Confidence,
+    line: usize,
+) -> Finding {
+    Finding {
+        detector_name: detector.to_string(),
+        title: format!("line {line}"),
+        description: String::new(),
+        severity,
+        confidence,
+        locations: vec![SourceLocation {
+            file: std::path::PathBuf::from("test.rs"),
+            start_line: line,
+            end_line: line,
+            start_col: 0,
+            end_col: 0,
+            snippet: None,
+            byte_range: None,
+        }],
+        recommendation: None,
+        fix: None,
+        ..Default::default()
+    }
+}
@@ -1,13 +1,20 @@
+use std::ops::Range;
 use std::path::PathBuf;
 
 use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::ast::spans::byte_offset;
 
 /// Severity levels ordered from most to least severe.
-/// IMPORTANT: Variant order matters — derived Ord puts High < Medium < Low < Info,
+/// IMPORTANT: Variant order matters — derived Ord puts Critical < High < Medium < Low < Info,
 /// which is used for filtering (retain findings where severity <= threshold).
 /// Do NOT reorder these variants.
 #[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Severity {
+    /// Lets an attacker take funds or the contract outright; also reached
+    /// when findings combine (see [`crate::correlation`])
+    Critical,
     High,
     Medium,
     Low,
@@ -17,6 +24,7 @@ pub enum Severity {
 impl std::fmt::Display for Severity {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Severity::Critical => write!(f, "Critical"),
             Severity::High => write!(f, "High"),
             Severity::Medium => write!(f, "Medium"),
             Severity::Low => write!(f, "Low"),
@@ -32,6 +40,24 @@ pub enum Confidence {
     Low,
 }
 
+impl Confidence {
+    /// One level higher, saturating at High
+    pub fn raised(&self) -> Self {
+        match self {
+            Confidence::High | Confidence::Medium => Confidence::High,
+            Confidence::Low => Confidence::Medium,
+        }
+    }
+
+    /// One level lower, saturating at Low
+    pub fn lowered(&self) -> Self {
+        match self {
+            Confidence::High => Confidence::Medium,
+            Confidence::Medium | Confidence::Low => Confidence::Low,
+        }
+    }
+}
+
 impl std::fmt::Display for Confidence {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -42,6 +68,8 @@ impl std::fmt::Display for Confidence {
     }
 }
 
+/// Where a finding points. Lines are 1-based and columns count characters
+/// from 0, so a multi-byte character is one column.
 #[derive(Debug, Clone, Serialize)]
 pub struct SourceLocation {
     pub file: PathBuf,
@@ -50,17 +78,119 @@ pub struct SourceLocation {
     pub start_col: usize,
     pub end_col: usize,
     pub snippet: Option<String>,
+    /// Byte offsets of the start and end into the file's UTF-8 text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub byte_range: Option<Range<usize>>,
 }
 
+impl SourceLocation {
+    /// Fill in `byte_range` from the lines and columns, given the file's text
+    pub fn resolve_byte_range(&mut self, source: &str) {
+        if self.byte_range.is_some() {
+            return;
+        }
+        let start = byte_offset(source, self.start_line, self.start_col);
+        let end = byte_offset(source, self.end_line, self.end_col);
+        if let (Some(start), Some(end)) = (start, end) {
+            self.byte_range = Some(start..end.max(start));
+        }
+    }
+}
+
+/// Replace the text at `location` with `replacement_text`; an empty
+/// location inserts
+#[derive(Debug, Clone, Serialize)]
+pub struct FixEdit {
+    pub location: SourceLocation,
+    pub replacement_text: String,
+}
+
+/// Edits that resolve a finding together, possibly across files (an import
+/// and the check using it). They are applied all or none, in order.
 #[derive(Debug, Clone, Serialize)]
 pub struct FixSuggestion {
     pub description: String,
-    pub replacement_text: String,
-    pub location: SourceLocation,
+    pub edits: Vec<FixEdit>,
+}
+
+impl FixSuggestion {
+    /// A fix made of a single replacement
+    pub fn replace(
+        description: impl Into<String>,
+        location: SourceLocation,
+        replacement_text: impl Into<String>,
+    ) -> Self {
+        Self {
+            description: description.into(),
+            edits: vec![FixEdit {
+                location,
+                replacement_text: replacement_text.into(),
+            }],
+        }
+    }
+}
+
+/// Evidence that moved a finding's confidence during recalibration
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ConfidenceSignal {
+    /// Human-readable evidence, e.g. "also reported by `unsafe-unwrap`"
+    pub reason: String,
+    /// +1 raises confidence one level, -1 lowers it
+    pub delta: i8,
+}
+
+/// What a [`TraceStep`] shows
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceStepKind {
+    /// Where the value comes from, e.g. a message field or a query response
+    Source,
+    /// An assignment or call carrying the value on
+    Propagation,
+    /// A check the analysis relied on
+    Check,
+    /// Where the value is used in a way that matters
+    Sink,
+    /// A check that no path is guaranteed to run
+    MissingGuard,
+}
+
+impl std::fmt::Display for TraceStepKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraceStepKind::Source => write!(f, "source"),
+            TraceStepKind::Propagation => write!(f, "flows to"),
+            TraceStepKind::Check => write!(f, "check"),
+            TraceStepKind::Sink => write!(f, "sink"),
+            TraceStepKind::MissingGuard => write!(f, "missing guard"),
+        }
+    }
+}
+
+/// One link of the evidence chain behind a dataflow finding
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceStep {
+    pub kind: TraceStepKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+}
+
+/// Published advisory or incident writeup about a finding's vulnerability
+/// class (see [`crate::advisories`])
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct AdvisoryLink {
+    /// e.g. `CWA-2024-002`
+    pub id: String,
+    pub url: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Finding {
+    /// Short stable identifier taken by `analyze --explain-finding`, set when
+    /// the report is built
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     pub detector_name: String,
     pub title: String,
     pub description: String,
@@ -70,4 +200,81 @@ pub struct Finding {
     pub recommendation: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fix: Option<FixSuggestion>,
+    /// Owning team from the config's `[[owners]]` rules, set when the report is built
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// 1-based position after exploitability ranking (`analyze --rank`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<usize>,
+    /// Signals applied by confidence recalibration, shown with `--verbose`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub confidence_signals: Vec<ConfidenceSignal>,
+    /// Entry points whose call graph reaches the function containing the
+    /// finding; empty for dead or internal-only code, unset for findings
+    /// outside functions (message types, state declarations)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reachable_from: Option<Vec<String>>,
+    /// Cargo package containing the finding, set when the report is built;
+    /// tells apart findings of the contracts of one workspace
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crate_name: Option<String>,
+    /// Organizational level of the severity from the config's
+    /// `[severity_levels]`, e.g. `P1`, set when the report is built
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity_level: Option<String>,
+    /// Audited upstream release the enclosing function is identical to, e.g.
+    /// `cw-plus v2.0.0 cw20-base::execute_transfer` (see [`crate::audited`])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inherited_from: Option<String>,
+    /// Evidence chain of dataflow findings: source, propagation steps and
+    /// sink, or the guard that does not dominate the flagged block
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trace: Vec<TraceStep>,
+    /// Advisories and incident writeups about the detector's vulnerability
+    /// class, set when the report is built
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub advisories: Vec<AdvisoryLink>,
+}
+
+/// Blank finding for struct update syntax: detectors set the fields they own
+/// and leave the ones the report pipeline fills in (`id`, `owner`, `rank`...)
+/// to this
+impl Default for Finding {
+    fn default() -> Self {
+        Self {
+            id: None,
+            detector_name: String::new(),
+            title: String::new(),
+            description: String::new(),
+            severity: Severity::Informational,
+            confidence: Confidence::Low,
+            locations: Vec::new(),
+            recommendation: None,
+            fix: None,
+            owner: None,
+            rank: None,
+            confidence_signals: Vec::new(),
+            reachable_from: None,
+            crate_name: None,
+            severity_level: None,
+            inherited_from: None,
+            trace: Vec::new(),
+            advisories: Vec::new(),
+        }
+    }
+}
+
+impl Finding {
+    /// Identifier derived from the detector, primary location and title, so
+    /// it stays the same across runs over the same code at the same reported
+    /// path
+    pub fn stable_id(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.detector_name.as_bytes());
+        if let Some(loc) = self.locations.first() {
+            hasher.update(format!("|{}:{}|", loc.file.display(), loc.start_line).as_bytes());
+        }
+        hasher.update(self.title.as_bytes());
+        format!("{:x}", hasher.finalize())[..8].to_string()
+    }
 }
@@ -0,0 +1,377 @@
+//! Applying the fixes of findings to their source files.
+//!
+//! Each fix is a transaction: its edits, possibly in several files, are
+//! planned together or not at all. Findings are taken in order, and a fix
+//! with an edit overlapping one an earlier fix already planned is skipped as
+//! a conflict; running the analysis again afterwards picks it up against the
+//! updated text. An edit identical to a planned one (two findings adding the
+//! same import) is shared rather than a conflict. [`FixPlan::verify`] rolls
+//! back fixes that would leave a file unparsable.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::finding::{Finding, FixSuggestion};
+
+/// An edit resolved to a byte range of its file
+#[derive(Debug, Clone)]
+struct PlannedEdit {
+    range: Range<usize>,
+    text: String,
+    /// Index of the finding whose fix it belongs to
+    finding: usize,
+}
+
+/// Reason given for fixes rolled back by [`FixPlan::verify`]
+pub const BREAKS_PARSING: &str = "the fixed code no longer parses";
+
+/// A fix left out of the plan
+#[derive(Debug, Clone)]
+pub struct SkippedFix {
+    /// Index of the finding in the slice given to [`plan`]
+    pub finding: usize,
+    pub reason: String,
+}
+
+/// Fixes that can be applied together
+#[derive(Debug, Default)]
+pub struct FixPlan {
+    edits: BTreeMap<PathBuf, Vec<PlannedEdit>>,
+    /// Indices of the findings whose fixes are planned, in order
+    pub applied: Vec<usize>,
+    pub skipped: Vec<SkippedFix>,
+}
+
+/// Whether edits of two different fixes cannot both apply: their ranges
+/// overlap, or one inserts where the other starts, so the order of the
+/// two is ambiguous
+fn conflicts(a: &Range<usize>, b: &Range<usize>) -> bool {
+    (a.start < b.end && b.start < a.end) || (a.start == b.start && (a.is_empty() || b.is_empty()))
+}
+
+/// `source` with `edits` applied
+fn render(edits: &[PlannedEdit], source: &str) -> String {
+    // Stable: insertions at one offset keep their planned order and
+    // go before a replacement starting there
+    let mut edits: Vec<&PlannedEdit> = edits.iter().collect();
+    edits.sort_by_key(|e| (e.range.start, e.range.end));
+    let mut text = String::with_capacity(source.len());
+    let mut pos = 0;
+    for edit in edits {
+        text.push_str(&source[pos..edit.range.start]);
+        text.push_str(&edit.text);
+        pos = edit.range.end;
+    }
+    text.push_str(&source[pos..]);
+    text
+}
+
+/// Plan the fixes of `findings` against the analyzed `sources`
+pub fn plan(findings: &[Finding], sources: &HashMap<PathBuf, String>) -> FixPlan {
+    let mut plan = FixPlan::default();
+    for (idx, finding) in findings.iter().enumerate() {
+        let Some(fix) = &finding.fix else {
+            continue;
+        };
+        match plan.admit(idx, fix, findings, sources) {
+            Ok(edits) => {
+                for (file, edit) in edits {
+                    plan.edits.entry(file).or_default().push(edit);
+                }
+                plan.applied.push(idx);
+            }
+            Err(reason) => plan.skipped.push(SkippedFix {
+                finding: idx,
+                reason,
+            }),
+        }
+    }
+    plan
+}
+
+impl FixPlan {
+    /// Edits of the fix of finding `idx` not planned yet, or why it can't apply
+    fn admit(
+        &self,
+        idx: usize,
+        fix: &FixSuggestion,
+        findings: &[Finding],
+        sources: &HashMap<PathBuf, String>,
+    ) -> Result<Vec<(PathBuf, PlannedEdit)>, String> {
+        if fix.edits.is_empty() {
+            return Err("the fix has no edits".to_string());
+        }
+        let mut admitted: Vec<(PathBuf, PlannedEdit)> = Vec::new();
+        for edit in &fix.edits {
+            let file = &edit.location.file;
+            let Some(source) = sources.get(file) else {
+                return Err(format!("{} was not analyzed", file.display()));
+            };
+            let mut location = edit.location.clone();
+            location.resolve_byte_range(source);
+            let Some(range) = location.byte_range.filter(|r| {
+                r.start <= r.end
+                    && r.end <= source.len()
+                    && source.is_char_boundary(r.start)
+                    && source.is_char_boundary(r.end)
+            }) else {
+                return Err(format!(
+                    "{}:{} is outside the file",
+                    file.display(),
+                    location.start_line
+                ));
+            };
+            let planned = PlannedEdit {
+                range,
+                text: edit.replacement_text.clone(),
+                finding: idx,
+            };
+
+            let mut shared = false;
+            for other in self.edits.get(file).into_iter().flatten() {
+                if other.range == planned.range && other.text == planned.text {
+                    shared = true;
+                } else if conflicts(&other.range, &planned.range) {
+                    return Err(format!(
+                        "{}:{} overlaps the fix for `{}`",
+                        file.display(),
+                        location.start_line,
+                        findings[other.finding].title
+                    ));
+                }
+            }
+            // Edits of one fix are ordered, so they may insert at one offset
+            for (_, own) in admitted.iter().filter(|(f, _)| f == file) {
+                if own.range.start < planned.range.end && planned.range.start < own.range.end {
+                    return Err(format!(
+                        "{}:{} overlaps another edit of the same fix",
+                        file.display(),
+                        location.start_line
+                    ));
+                }
+            }
+            if !shared {
+                admitted.push((file.clone(), planned));
+            }
+        }
+        Ok(admitted)
+    }
+
+    /// Files the plan changes
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.edits.keys().map(PathBuf::as_path)
+    }
+
+    /// Roll back fixes after which a file they edit no longer parses. The
+    /// fixes are replayed in order, so of two that only break in
+    /// combination the earlier one is kept.
+    pub fn verify(&mut self, findings: &[Finding], sources: &HashMap<PathBuf, String>) {
+        if self
+            .apply(sources)
+            .values()
+            .all(|text| syn::parse_file(text).is_ok())
+        {
+            return;
+        }
+        let mut verified = FixPlan {
+            skipped: std::mem::take(&mut self.skipped),
+            ..Default::default()
+        };
+        for &idx in &self.applied {
+            let Some(fix) = &findings[idx].fix else {
+                continue;
+            };
+            // Admitted before alongside more edits, so it still is now
+            let Ok(edits) = verified.admit(idx, fix, findings, sources) else {
+                continue;
+            };
+            let mut files: Vec<PathBuf> = edits.iter().map(|(file, _)| file.clone()).collect();
+            files.sort();
+            files.dedup();
+            for (file, edit) in edits {
+                verified.edits.entry(file).or_default().push(edit);
+            }
+            let parses = files.iter().all(|file| {
+                let text = render(&verified.edits[file], &sources[file]);
+                syn::parse_file(&text).is_ok()
+            });
+            if parses {
+                verified.applied.push(idx);
+                continue;
+            }
+            for file in &files {
+                if let Some(edits) = verified.edits.get_mut(file) {
+                    edits.retain(|e| e.finding != idx);
+                    if edits.is_empty() {
+                        verified.edits.remove(file);
+                    }
+                }
+            }
+            verified.skipped.push(SkippedFix {
+                finding: idx,
+                reason: BREAKS_PARSING.to_string(),
+            });
+        }
+        *self = verified;
+    }
+
+    /// New text of every file the plan changes
+    pub fn apply(&self, sources: &HashMap<PathBuf, String>) -> BTreeMap<PathBuf, String> {
+        self.edits
+            .iter()
+            .filter_map(|(file, edits)| {
+                let source = sources.get(file)?;
+                Some((file.clone(), render(edits, source)))
+            })
+            .collect()
+    }
+}
+
+/// Write fixed files. Every file is staged next to the original first, so
+/// a failure leaves all of them untouched.
+pub fn write(fixed: &BTreeMap<PathBuf, String>) -> Result<()> {
+    let mut staged: Vec<(PathBuf, &Path)> = Vec::new();
+    for (file, text) in fixed {
+        let mut tmp = file.clone().into_os_string();
+        tmp.push(".cosmwasm-guard-fix");
+        let tmp = PathBuf::from(tmp);
+        if let Err(err) = std::fs::write(&tmp, text) {
+            for (tmp, _) in &staged {
+                let _ = std::fs::remove_file(tmp);
+            }
+            let _ = std::fs::remove_file(&tmp);
+            return Err(err).with_context(|| format!("Failed to write {}", tmp.display()));
+        }
+        staged.push((tmp, file));
+    }
+    for (tmp, file) in staged {
+        std::fs::rename(&tmp, file)
+            .with_context(|| format!("Failed to replace {}", file.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finding::{test_finding, Confidence, FixEdit, Severity, SourceLocation};
+
+    fn at(file: &str, range: Range<usize>) -> SourceLocation {
+        SourceLocation {
+            file: PathBuf::from(file),
+            start_line: 1,
+            end_line: 1,
+            start_col: 0,
+            end_col: 0,
+            snippet: None,
+            byte_range: Some(range),
+        }
+    }
+
+    fn finding(title: &str, edits: Vec<(SourceLocation, &str)>) -> Finding {
+        Finding {
+            title: title.to_string(),
+            locations: Vec::new(),
+            fix: Some(FixSuggestion {
+                description: String::new(),
+                edits: edits
+                    .into_iter()
+                    .map(|(location, text)| FixEdit {
+                        location,
+                        replacement_text: text.to_string(),
+                    })
+                    .collect(),
+            }),
+            ..test_finding("test", Severity::Low, Confidence::High, 0)
+        }
+    }
+
+    fn sources() -> HashMap<PathBuf, String> {
+        HashMap::from([
+            (
+                PathBuf::from("lib.rs"),
+                "fn f() { a.unwrap(); b.unwrap(); }".to_string(),
+            ),
+            (PathBuf::from("error.rs"), "enum E {}".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_multi_file_fix_applies_in_order() {
+        let findings = vec![finding(
+            "unwrap",
+            vec![
+                (at("lib.rs", 0..0), "use x;\n"),
+                (at("lib.rs", 0..0), "use y;\n"),
+                (at("lib.rs", 10..19), "?"),
+                (at("error.rs", 8..8), "X"),
+            ],
+        )];
+        let plan = plan(&findings, &sources());
+        assert_eq!(plan.applied, vec![0]);
+        let fixed = plan.apply(&sources());
+        assert_eq!(
+            fixed[Path::new("lib.rs")],
+            "use x;\nuse y;\nfn f() { a?; b.unwrap(); }"
+        );
+        assert_eq!(fixed[Path::new("error.rs")], "enum E {X}");
+    }
+
+    #[test]
+    fn test_overlapping_fix_is_skipped_whole() {
+        let findings = vec![
+            finding("first", vec![(at("lib.rs", 10..19), "?")]),
+            // Its error.rs edit alone would apply, but the fix is one unit
+            finding(
+                "second",
+                vec![(at("error.rs", 8..8), "X"), (at("lib.rs", 11..19), "")],
+            ),
+            // An edit identical to an earlier fix's is shared, not a conflict
+            finding("third", vec![(at("lib.rs", 22..31), "?")]),
+            finding(
+                "fourth",
+                vec![
+                    (at("lib.rs", 22..31), "?"),
+                    (at("lib.rs", 0..0), "use x;\n"),
+                ],
+            ),
+        ];
+        let plan = plan(&findings, &sources());
+        assert_eq!(plan.applied, vec![0, 2, 3]);
+        assert_eq!(plan.skipped.len(), 1);
+        assert_eq!(plan.skipped[0].finding, 1);
+        assert!(plan.skipped[0]
+            .reason
+            .contains("overlaps the fix for `first`"));
+        assert_eq!(plan.files().count(), 1);
+        assert_eq!(
+            plan.apply(&sources())[Path::new("lib.rs")],
+            "use x;\nfn f() { a?; b?; }"
+        );
+    }
+
+    #[test]
+    fn test_verify_rolls_back_fix_breaking_parsing() {
+        let findings = vec![
+            finding("first", vec![(at("lib.rs", 10..19), "?")]),
+            // Rolled back in every file, not only the one that breaks
+            finding(
+                "second",
+                vec![(at("error.rs", 8..8), "X"), (at("lib.rs", 33..34), "")],
+            ),
+            finding("third", vec![(at("lib.rs", 22..31), "?")]),
+        ];
+        let mut plan = plan(&findings, &sources());
+        assert_eq!(plan.applied, vec![0, 1, 2]);
+        plan.verify(&findings, &sources());
+        assert_eq!(plan.applied, vec![0, 2]);
+        assert_eq!(plan.skipped[0].finding, 1);
+        assert_eq!(plan.skipped[0].reason, BREAKS_PARSING);
+        let fixed = plan.apply(&sources());
+        assert_eq!(fixed.len(), 1);
+        assert_eq!(fixed[Path::new("lib.rs")], "fn f() { a?; b?; }");
+    }
+}
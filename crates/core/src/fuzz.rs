@@ -0,0 +1,385 @@
+//! Fuzz harness scaffolding: generates a proptest suite that builds random
+//! `ExecuteMsg`/`QueryMsg` values from the extracted variants and replays
+//! them against the contract in `cw-multi-test`.
+
+use std::fmt::Write;
+
+use crate::ast::{ContractInfo, FieldInfo, MessageEnum, MessageKind};
+use crate::repro::{module_path, snake_case, Harness};
+
+/// How deep contract-defined structs are expanded into field strategies
+/// before falling back to `any::<T>()`
+const MAX_STRUCT_DEPTH: usize = 3;
+
+/// Field names holding addresses, drawn from a small pool of known accounts so
+/// messages pass validation and hit the owner's code paths
+const ADDRESS_FIELDS: &[&str] = &[
+    "addr",
+    "address",
+    "admin",
+    "owner",
+    "recipient",
+    "receiver",
+    "sender",
+    "operator",
+    "spender",
+    "contract",
+    "to",
+    "from",
+    "minter",
+    "user",
+    "delegate",
+];
+
+/// Generated fuzz harness
+#[derive(Debug, Clone)]
+pub struct FuzzHarness {
+    pub source: String,
+    /// Number of execute and query variants covered
+    pub execute_variants: usize,
+    pub query_variants: usize,
+}
+
+fn is_address_field(name: &str) -> bool {
+    ADDRESS_FIELDS
+        .iter()
+        .any(|f| name == *f || name.ends_with(&format!("_{f}")))
+}
+
+/// Inner type of `Wrapper<T>`, e.g. `Option<Uint128>` -> `Uint128`
+fn generic_arg<'a>(ty: &'a str, wrapper: &str) -> Option<&'a str> {
+    ty.strip_prefix(wrapper)?
+        .strip_prefix('<')?
+        .strip_suffix('>')
+}
+
+/// Proptest strategy expression generating values of `ty` for field `name`
+fn strategy(
+    ty: &str,
+    name: &str,
+    contract: &ContractInfo,
+    crate_name: &str,
+    depth: usize,
+) -> String {
+    if let Some(inner) = generic_arg(ty, "Option") {
+        let inner = strategy(inner, name, contract, crate_name, depth);
+        return format!("proptest::option::of({inner})");
+    }
+    if let Some(inner) = generic_arg(ty, "Vec") {
+        if inner == "u8" {
+            return "proptest::collection::vec(any::<u8>(), 0..64)".to_string();
+        }
+        let inner = strategy(inner, name, contract, crate_name, depth);
+        return format!("proptest::collection::vec({inner}, 0..4)");
+    }
+    if let Some(inner) = generic_arg(ty, "Box") {
+        let inner = strategy(inner, name, contract, crate_name, depth);
+        return format!("{inner}.prop_map(Box::new)");
+    }
+
+    let base = ty.rsplit("::").next().unwrap_or(ty);
+    match base {
+        "String" if is_address_field(name) => "address()".to_string(),
+        "String" => "\"[a-z0-9]{0,16}\"".to_string(),
+        "Addr" => "sender()".to_string(),
+        "Uint128" | "Uint64" => {
+            let raw = if base == "Uint128" { "u128" } else { "u64" };
+            format!("any::<{raw}>().prop_map(cosmwasm_std::{base}::new)")
+        }
+        "Decimal" => "any::<u128>().prop_map(cosmwasm_std::Decimal::raw)".to_string(),
+        "Binary" => {
+            "proptest::collection::vec(any::<u8>(), 0..64).prop_map(cosmwasm_std::Binary::from)"
+                .to_string()
+        }
+        "Coin" => "(\"[a-z]{3,8}\", any::<u128>())\
+                   .prop_map(|(denom, amount)| cosmwasm_std::coin(amount, denom))"
+            .to_string(),
+        "bool" | "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64" | "i128" => {
+            format!("any::<{base}>()")
+        }
+        _ => {
+            let defined = contract.structs.iter().find(|s| s.name == base);
+            match defined {
+                Some(s) if depth < MAX_STRUCT_DEPTH => {
+                    let fields: Vec<FieldInfo> = s
+                        .fields
+                        .iter()
+                        .map(|f| FieldInfo {
+                            name: f.name.clone(),
+                            type_name: f.type_name.clone(),
+                        })
+                        .collect();
+                    let path = format!("{}::{}", module_path(crate_name, &s.span.file), s.name);
+                    constructor(&path, &fields, contract, crate_name, depth + 1)
+                }
+                // Needs a proptest `Arbitrary` impl for the type
+                _ => format!("any::<{ty}>()"),
+            }
+        }
+    }
+}
+
+/// Strategy building `path` from strategies for its fields, e.g.
+/// `any::<u64>().prop_map(|fee_bps| ExecuteMsg::SetFee { fee_bps })`
+fn constructor(
+    path: &str,
+    fields: &[FieldInfo],
+    contract: &ContractInfo,
+    crate_name: &str,
+    depth: usize,
+) -> String {
+    if fields.is_empty() {
+        return format!("Just({path} {{}})");
+    }
+    let strategies: Vec<String> = fields
+        .iter()
+        .map(|f| strategy(&f.type_name, &f.name, contract, crate_name, depth))
+        .collect();
+    let names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+    // Tuple variants and structs have fields `_0`, `_1`, ...
+    let value = if names[0].starts_with('_') {
+        format!("{path}({})", names.join(", "))
+    } else {
+        format!("{path} {{ {} }}", names.join(", "))
+    };
+    if fields.len() == 1 {
+        format!("{}.prop_map(|{}| {value})", strategies[0], names[0])
+    } else {
+        format!(
+            "({}).prop_map(|({})| {value})",
+            strategies.join(", "),
+            names.join(", ")
+        )
+    }
+}
+
+/// `fn <name>() -> impl Strategy<Value = Msg>` choosing among every variant
+fn message_strategy(message: &MessageEnum, contract: &ContractInfo, crate_name: &str) -> String {
+    let path = format!(
+        "{}::{}",
+        module_path(crate_name, &message.span.file),
+        message.name
+    );
+    let mut out = String::new();
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "fn {}() -> impl Strategy<Value = {path}> {{",
+        snake_case(&message.name)
+    );
+    let _ = writeln!(out, "    prop_oneof![");
+    for variant in &message.variants {
+        let _ = writeln!(
+            out,
+            "        {},",
+            constructor(
+                &format!("{path}::{}", variant.name),
+                &variant.fields,
+                contract,
+                crate_name,
+                0
+            )
+        );
+    }
+    let _ = writeln!(out, "    ]");
+    let _ = writeln!(out, "}}");
+    out
+}
+
+/// Generate a proptest harness for the contract's execute and query messages;
+/// `crate_name` is the contract's library crate as imported from its tests.
+/// Returns `None` when the contract declares neither message enum.
+pub fn generate(contract: &ContractInfo, crate_name: &str) -> Option<FuzzHarness> {
+    let find = |kind: MessageKind| {
+        contract
+            .message_enums
+            .iter()
+            .find(|e| e.kind == kind && !e.variants.is_empty())
+    };
+    let execute = find(MessageKind::Execute);
+    let query = find(MessageKind::Query);
+    if execute.is_none() && query.is_none() {
+        return None;
+    }
+    let harness = Harness::new(contract, crate_name, "guard-fuzz");
+
+    let mut source = String::new();
+    let _ = writeln!(
+        source,
+        "//! Property tests generated by `cosmwasm-guard scaffold fuzz`: random"
+    );
+    let _ = writeln!(
+        source,
+        "//! message sequences from a few accounts must never panic the contract."
+    );
+    let _ = writeln!(source, "//!");
+    let _ = writeln!(
+        source,
+        "//! Replace the `todo!()` placeholders in `setup`, add `proptest` and"
+    );
+    let _ = writeln!(
+        source,
+        "//! `cw-multi-test` to `[dev-dependencies]` and run `cargo test`. Types"
+    );
+    let _ = writeln!(
+        source,
+        "//! without a built-in strategy use `any::<T>()` and need a proptest"
+    );
+    let _ = writeln!(
+        source,
+        "//! `Arbitrary` impl. Add the contract's invariants where marked."
+    );
+    let _ = writeln!(source);
+    let _ = writeln!(source, "use cosmwasm_std::testing::MockApi;");
+    source.push_str(&harness.imports);
+    let _ = writeln!(source, "use proptest::prelude::*;");
+    source.push_str(&harness.helpers);
+
+    let _ = writeln!(source);
+    let _ = writeln!(
+        source,
+        "/// Accounts messages are sent from; `owner` instantiated the contract"
+    );
+    let _ = writeln!(
+        source,
+        "fn sender() -> impl Strategy<Value = cosmwasm_std::Addr> {{"
+    );
+    let _ = writeln!(
+        source,
+        "    prop::sample::select(vec![\"owner\", \"user\", \"attacker\"])"
+    );
+    let _ = writeln!(
+        source,
+        "        .prop_map(|name| MockApi::default().addr_make(name))"
+    );
+    let _ = writeln!(source, "}}");
+    let _ = writeln!(source);
+    let _ = writeln!(source, "fn address() -> impl Strategy<Value = String> {{");
+    let _ = writeln!(source, "    sender().prop_map(|addr| addr.to_string())");
+    let _ = writeln!(source, "}}");
+
+    for message in execute.iter().chain(&query) {
+        source.push_str(&message_strategy(message, contract, crate_name));
+    }
+
+    let _ = writeln!(source);
+    let _ = writeln!(source, "proptest! {{");
+    let _ = writeln!(
+        source,
+        "    #![proptest_config(ProptestConfig::with_cases(256))]"
+    );
+    if let Some(execute) = execute {
+        let _ = writeln!(source);
+        let _ = writeln!(source, "    #[test]");
+        let _ = writeln!(source, "    fn execute_sequences_do_not_panic(");
+        let _ = writeln!(
+            source,
+            "        msgs in proptest::collection::vec((sender(), {}()), 1..8)",
+            snake_case(&execute.name)
+        );
+        let _ = writeln!(source, "    ) {{");
+        let _ = writeln!(source, "        let mut app = App::default();");
+        let _ = writeln!(source, "        let contract_addr = setup(&mut app);");
+        let _ = writeln!(source, "        for (sender, msg) in msgs {{");
+        let _ = writeln!(
+            source,
+            "            // Rejected messages are expected; panics are not"
+        );
+        let _ = writeln!(
+            source,
+            "            let _ = app.execute_contract(sender, contract_addr.clone(), &msg, &[]);"
+        );
+        let _ = writeln!(source, "        }}");
+        let _ = writeln!(
+            source,
+            "        // TODO: assert the contract's invariants, e.g. balances sum to the supply"
+        );
+        let _ = writeln!(source, "    }}");
+    }
+    if let Some(query) = query {
+        let _ = writeln!(source);
+        let _ = writeln!(source, "    #[test]");
+        let _ = writeln!(
+            source,
+            "    fn queries_do_not_panic(msg in {}()) {{",
+            snake_case(&query.name)
+        );
+        let _ = writeln!(source, "        let mut app = App::default();");
+        let _ = writeln!(source, "        let contract_addr = setup(&mut app);");
+        let _ = writeln!(
+            source,
+            "        // Any response type will do: only a panic fails the test"
+        );
+        let _ = writeln!(
+            source,
+            "        let _ = app.wrap().query_wasm_smart::<Empty>(&contract_addr, &msg);"
+        );
+        let _ = writeln!(source, "    }}");
+    }
+    let _ = writeln!(source, "}}");
+
+    Some(FuzzHarness {
+        source,
+        execute_variants: execute.map_or(0, |e| e.variants.len()),
+        query_variants: query.map_or(0, |q| q.variants.len()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use std::path::PathBuf;
+
+    fn contract(source: &str) -> ContractInfo {
+        let ast = parse_source(source).unwrap();
+        ContractVisitor::extract(PathBuf::from("vault/src/msg.rs"), ast)
+    }
+
+    #[test]
+    fn test_generates_message_strategies() {
+        let contract = contract(
+            r#"
+            pub struct InstantiateMsg { pub admin: String }
+            pub struct Route { pub pool: u64, pub denom: String }
+            pub enum ExecuteMsg {
+                Deposit {},
+                Transfer { recipient: String, amount: Uint128 },
+                Swap { routes: Vec<Route>, min_out: Option<Uint128> },
+                Raw(Binary),
+            }
+            pub enum QueryMsg { Balance { address: String } }
+        "#,
+        );
+        let harness = generate(&contract, "vault").unwrap();
+        assert_eq!(harness.execute_variants, 4);
+        assert_eq!(harness.query_variants, 1);
+
+        let source = &harness.source;
+        assert!(
+            source.contains("fn execute_msg() -> impl Strategy<Value = vault::msg::ExecuteMsg>")
+        );
+        assert!(source.contains("Just(vault::msg::ExecuteMsg::Deposit {}),"));
+        assert!(source.contains(
+            "(address(), any::<u128>().prop_map(cosmwasm_std::Uint128::new))\
+             .prop_map(|(recipient, amount)| vault::msg::ExecuteMsg::Transfer { recipient, amount }),"
+        ));
+        assert!(source.contains(
+            "proptest::collection::vec((any::<u64>(), \"[a-z0-9]{0,16}\")\
+             .prop_map(|(pool, denom)| vault::msg::Route { pool, denom }), 0..4)"
+        ));
+        assert!(source.contains("proptest::option::of(any::<u128>()"));
+        assert!(source.contains(".prop_map(|_0| vault::msg::ExecuteMsg::Raw(_0)),"));
+        assert!(source
+            .contains("address().prop_map(|address| vault::msg::QueryMsg::Balance { address }),"));
+        assert!(source.contains("vault::msg::InstantiateMsg { admin: todo!(\"String\") }"));
+        assert!(source.contains("fn execute_sequences_do_not_panic("));
+        assert!(source.contains("fn queries_do_not_panic(msg in query_msg())"));
+    }
+
+    #[test]
+    fn test_no_messages() {
+        let contract = contract("pub struct Config { pub owner: Addr }");
+        assert!(generate(&contract, "vault").is_none());
+    }
+}
@@ -0,0 +1,170 @@
+//! Numeric invariants between state items, declared in code with a
+//! `// guard-invariant: TOTAL_SUPPLY == sum(BALANCES)` comment (or
+//! `// cosmwasm-guard-invariant: ..`) or under `[invariants]` in the config.
+//!
+//! Only the state items an invariant relates matter to the analysis: its
+//! identifiers are matched case-insensitively against the contract's state
+//! items (`total` does not match `TOTAL_SUPPLY`, `total_supply` does), and
+//! the rest of the expression is kept as written for reports.
+
+use std::path::Path;
+
+use crate::ast::{ContractInfo, SourceSpan};
+use crate::config::Config;
+
+/// An invariant relating two or more state items
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invariant {
+    /// Key under `[invariants]`; none for ones declared in comments
+    pub name: Option<String>,
+    /// As written, e.g. `TOTAL_SUPPLY == sum(BALANCES)`
+    pub expr: String,
+    /// State items it relates, in order of appearance
+    pub items: Vec<String>,
+    /// Items under `sum(..)`: writing two of their entries can move value
+    /// between them without changing the sum
+    pub summed: Vec<String>,
+    /// The declaring comment; none for ones from the config
+    pub span: Option<SourceSpan>,
+}
+
+impl Invariant {
+    /// Resolve `expr` against the contract's state items. `None` when it
+    /// relates fewer than two of them, so there is nothing to keep in sync.
+    pub fn parse(name: Option<String>, expr: &str, contract: &ContractInfo) -> Option<Self> {
+        let mut items: Vec<String> = Vec::new();
+        let mut summed: Vec<String> = Vec::new();
+        let mut in_sum = false;
+        let mut rest = expr;
+        while let Some(start) = rest.find(|c: char| c.is_alphabetic() || c == '_') {
+            let tail = &rest[start..];
+            let end = tail
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(tail.len());
+            let ident = &tail[..end];
+            rest = &tail[end..];
+            if ident == "sum" && rest.trim_start().starts_with('(') {
+                in_sum = true;
+                continue;
+            }
+            let Some(item) = contract
+                .state_items
+                .iter()
+                .find(|item| item.name.eq_ignore_ascii_case(ident))
+            else {
+                continue;
+            };
+            if !items.contains(&item.name) {
+                items.push(item.name.clone());
+            }
+            if in_sum && !summed.contains(&item.name) {
+                summed.push(item.name.clone());
+            }
+            in_sum = false;
+        }
+        (items.len() >= 2).then(|| Self {
+            name,
+            expr: expr.trim().to_string(),
+            items,
+            summed,
+            span: None,
+        })
+    }
+
+    /// `supply` (`TOTAL_SUPPLY == sum(BALANCES)`), or just the expression
+    pub fn display(&self) -> String {
+        match &self.name {
+            Some(name) => format!("`{name}` (`{}`)", self.expr),
+            None => format!("`{}`", self.expr),
+        }
+    }
+}
+
+/// Expression of an invariant comment line, if it is one
+fn invariant_comment(line: &str) -> Option<&str> {
+    let comment = line.trim().strip_prefix("//")?.trim();
+    let comment = comment.strip_prefix("cosmwasm-").unwrap_or(comment);
+    Some(comment.strip_prefix("guard-invariant:")?.trim())
+}
+
+/// Invariants declared in `source` comments
+pub fn from_comments(file: &Path, source: &str, contract: &ContractInfo) -> Vec<Invariant> {
+    let mut invariants = Vec::new();
+    let mut offset = 0;
+    for (idx, line) in source.lines().enumerate() {
+        if let Some(expr) = invariant_comment(line) {
+            if let Some(mut invariant) = Invariant::parse(None, expr, contract) {
+                let indent = line.len() - line.trim_start().len();
+                invariant.span = Some(SourceSpan {
+                    file: file.to_path_buf(),
+                    start_line: idx + 1,
+                    end_line: idx + 1,
+                    start_col: indent,
+                    end_col: line.len(),
+                    start_byte: offset + indent,
+                    end_byte: offset + line.len(),
+                });
+                invariants.push(invariant);
+            }
+        }
+        offset += line.len() + 1;
+    }
+    invariants
+}
+
+/// Invariants of the config's `[invariants]` table, by name
+pub fn from_config(config: &Config, contract: &ContractInfo) -> Vec<Invariant> {
+    config
+        .invariants
+        .iter()
+        .filter_map(|(name, expr)| Invariant::parse(Some(name.clone()), expr, contract))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use std::path::PathBuf;
+
+    const SOURCE: &str = r#"
+pub const CONFIG: Item<Config> = Item::new("config");
+// guard-invariant: total_supply == sum(balances)
+pub const TOTAL_SUPPLY: Item<Uint128> = Item::new("total_supply");
+pub const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
+    // cosmwasm-guard-invariant: CONFIG.reserve >= 0
+"#;
+
+    fn contract() -> ContractInfo {
+        ContractVisitor::extract(PathBuf::from("state.rs"), parse_source(SOURCE).unwrap())
+    }
+
+    #[test]
+    fn test_parse_resolves_state_items() {
+        let contract = contract();
+        let invariant = Invariant::parse(
+            None,
+            "TOTAL_SUPPLY == sum(Balances) + config.fees",
+            &contract,
+        )
+        .unwrap();
+        assert_eq!(invariant.items, vec!["TOTAL_SUPPLY", "BALANCES", "CONFIG"]);
+        assert_eq!(invariant.summed, vec!["BALANCES"]);
+        // A single item has nothing to stay in sync with
+        assert!(Invariant::parse(None, "sum(BALANCES) >= 0", &contract).is_none());
+    }
+
+    #[test]
+    fn test_comment_invariants() {
+        let contract = contract();
+        let invariants = from_comments(Path::new("state.rs"), SOURCE, &contract);
+        assert_eq!(invariants.len(), 1);
+        assert_eq!(invariants[0].expr, "total_supply == sum(balances)");
+        let span = invariants[0].span.as_ref().unwrap();
+        assert_eq!(span.start_line, 3);
+        assert_eq!(
+            &SOURCE[span.start_byte..span.end_byte],
+            SOURCE.lines().nth(2).unwrap()
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::cfg::DefUse;
+use super::instruction::{Instruction, SsaVar};
+use super::summary::FunctionSummary;
+use super::types::FunctionIr;
+
+/// Analyses of one function that depend on its own IR only, so they can be
+/// cached with the file it lives in and reused while that file is unchanged.
+///
+/// Everything that depends on other files is left out and applied when the
+/// contract is analyzed: call targets are resolved against the functions
+/// known at that point, and access-control helpers (configured or annotated
+/// with `cosmwasm_guard::assume` anywhere in the crate) and fund-moving
+/// sinks are applied to the summary by [`FunctionSummary::trusting`] and
+/// [`FunctionSummary::sending`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FunctionAnalyses {
+    /// Storage effects, emitted messages and guards, before auth helpers
+    pub summary: FunctionSummary,
+    pub def_use: HashMap<SsaVar, DefUse>,
+    /// Callee names on their last path segment, resolved or not
+    pub calls: BTreeSet<String>,
+}
+
+impl FunctionAnalyses {
+    pub fn compute(func: &FunctionIr) -> Self {
+        Self {
+            summary: FunctionSummary::from_ir(func),
+            def_use: func.cfg.def_use_chains(),
+            calls: direct_calls(func),
+        }
+    }
+}
+
+/// Names of the functions `func` calls, on their last path segment
+/// (`helpers::check` -> `check`)
+pub fn direct_calls(func: &FunctionIr) -> BTreeSet<String> {
+    func.cfg
+        .blocks
+        .iter()
+        .flat_map(|b| &b.instructions)
+        .filter_map(|inst| match inst {
+            Instruction::Call { func: callee, .. } => {
+                Some(callee.rsplit("::").next().unwrap_or(callee).to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
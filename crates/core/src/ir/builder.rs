@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::ast::{ContractInfo, FunctionInfo};
 
-use super::cfg::{BlockId, Cfg};
+use super::cfg::{instruction_def, BlockId, Cfg};
 use super::instruction::*;
 use super::types::{ContractIr, FunctionIr};
 
@@ -43,11 +43,70 @@ fn classify_path(path: &syn::ExprPath, known_vars: &HashMap<String, u32>) -> Pat
     PathKind::Variable
 }
 
+/// Collect the identifiers bound by a pattern, e.g. `(addr, amount)` -> ["addr", "amount"]
+fn pattern_idents(pat: &syn::Pat) -> Vec<String> {
+    match pat {
+        syn::Pat::Ident(ident) => vec![ident.ident.to_string()],
+        syn::Pat::Tuple(tuple) => tuple.elems.iter().flat_map(pattern_idents).collect(),
+        syn::Pat::TupleStruct(ts) => ts.elems.iter().flat_map(pattern_idents).collect(),
+        syn::Pat::Struct(st) => st.fields.iter().flat_map(|f| pattern_idents(&f.pat)).collect(),
+        syn::Pat::Slice(slice) => slice.elems.iter().flat_map(pattern_idents).collect(),
+        syn::Pat::Reference(r) => pattern_idents(&r.pat),
+        syn::Pat::Type(t) => pattern_idents(&t.pat),
+        _ => Vec::new(),
+    }
+}
+
+/// Message families that become chain messages, e.g. `BankMsg::Send { .. }`
+const COSMOS_MSG_TYPES: &[&str] = &[
+    "BankMsg",
+    "WasmMsg",
+    "StakingMsg",
+    "DistributionMsg",
+    "IbcMsg",
+    "GovMsg",
+    "CosmosMsg",
+];
+
+fn is_cosmos_msg_path(path: &str) -> bool {
+    path.split("::").any(|seg| COSMOS_MSG_TYPES.contains(&seg))
+}
+
+/// Whether an operand reads a `sender` field (`info.sender`, `msg.sender`)
+fn operand_mentions_sender(operand: &Operand) -> bool {
+    match operand {
+        Operand::FieldAccess { base, field } => field == "sender" || operand_mentions_sender(base),
+        _ => false,
+    }
+}
+
+/// Whether an operand is `deps.querier` (or a `querier` local)
+fn is_querier(operand: &Operand) -> bool {
+    match operand {
+        Operand::FieldAccess { field, .. } => field == "querier",
+        Operand::Var(var) => var.name == "querier",
+        Operand::Literal(_) => false,
+    }
+}
+
+/// Whether a path names a struct or enum variant (`QueryMsg::Price`) rather
+/// than a function (`Uint128::new`)
+fn is_constructor_path(path: &str) -> bool {
+    path.rsplit("::")
+        .next()
+        .is_some_and(|last| last.starts_with(char::is_uppercase))
+}
+
+fn is_err_constructor(call: &syn::ExprCall) -> bool {
+    matches!(call.func.as_ref(), syn::Expr::Path(p) if p.path.is_ident("Err"))
+}
+
 /// Transforms syn AST function bodies into SSA-form IR
 pub struct IrBuilder {
     current_block: BlockId,
     cfg: Cfg,
-    var_counter: HashMap<String, u32>,
+    /// Latest version written of each variable
+    versions: HashMap<String, u32>,
     temp_counter: u32,
 }
 
@@ -58,7 +117,7 @@ impl IrBuilder {
         Self {
             current_block: entry,
             cfg,
-            var_counter: HashMap::new(),
+            versions: HashMap::new(),
             temp_counter: 0,
         }
     }
@@ -96,7 +155,7 @@ impl IrBuilder {
         let params: Vec<SsaVar> = func
             .params
             .iter()
-            .map(|p| builder.new_ssa_var(&p.name))
+            .map(|p| builder.write_var(&p.name))
             .collect();
 
         // Lower each statement in the function body
@@ -125,25 +184,36 @@ impl IrBuilder {
             cfg: builder.cfg,
             is_entry_point,
             source_span: func.span.clone(),
+            analyses: None,
         }
     }
 
-    /// Create a new SSA variable with incremented version
-    fn new_ssa_var(&mut self, name: &str) -> SsaVar {
-        let version = self.var_counter.entry(name.to_string()).or_insert(0);
-        let var = SsaVar {
+    /// Define the next version of `name`; reads after this refer to it
+    fn write_var(&mut self, name: &str) -> SsaVar {
+        let version = self.versions.get(name).map_or(0, |v| v + 1);
+        self.versions.insert(name.to_string(), version);
+        SsaVar {
             name: name.to_string(),
-            version: *version,
-        };
-        *version += 1;
-        var
+            version,
+        }
+    }
+
+    /// The latest version of `name`. A name never written (a constant, a
+    /// global, a binding the IR does not model) reads as version 0, which
+    /// no instruction defines
+    fn read_var(&mut self, name: &str) -> SsaVar {
+        let version = *self.versions.entry(name.to_string()).or_insert(0);
+        SsaVar {
+            name: name.to_string(),
+            version,
+        }
     }
 
     /// Create a temporary SSA variable
     fn new_temp(&mut self) -> SsaVar {
         let name = format!("_t{}", self.temp_counter);
         self.temp_counter += 1;
-        self.new_ssa_var(&name)
+        self.write_var(&name)
     }
 
     /// Create a new basic block and return its ID
@@ -156,6 +226,34 @@ impl IrBuilder {
         self.cfg.blocks[self.current_block].instructions.push(inst);
     }
 
+    /// Struct or variant `operand` was built from in this function, following
+    /// copies: `Msg::Price {}` and `let msg = Msg::Price;` are `Msg::Price`
+    fn constructor_of(&self, operand: &Operand) -> Option<String> {
+        match operand {
+            Operand::Literal(LiteralValue::String(path))
+                if path.contains("::") && is_constructor_path(path) =>
+            {
+                Some(path.clone())
+            }
+            Operand::Var(var) => {
+                let def = self
+                    .cfg
+                    .blocks
+                    .iter()
+                    .flat_map(|b| &b.instructions)
+                    .find(|inst| instruction_def(inst) == Some(var))?;
+                match def {
+                    Instruction::Assign { value, .. } => self.constructor_of(value),
+                    Instruction::Call { func, .. } if is_constructor_path(func) => {
+                        Some(func.clone())
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
     /// Lower a syn statement to IR instructions
     fn lower_stmt(&mut self, stmt: &syn::Stmt) {
         match stmt {
@@ -168,19 +266,31 @@ impl IrBuilder {
         }
     }
 
-    /// Lower a let binding
+    /// Lower a let binding. The initializer is lowered first: in
+    /// `let amount = amount - fee` it reads the previous `amount`. A pattern
+    /// other than a plain name binds each of its names from the whole value.
     fn lower_local(&mut self, local: &syn::Local) {
-        let var_name = if let syn::Pat::Ident(ident) = &local.pat {
-            ident.ident.to_string()
-        } else {
-            format!("_pat{}", self.temp_counter)
-        };
+        // `let x;` defines nothing until `x` is assigned
+        let Some(init) = &local.init else { return };
+        let value = self.lower_expr(&init.expr);
 
-        let dest = self.new_ssa_var(&var_name);
-
-        if let Some(init) = &local.init {
-            let value = self.lower_expr(&init.expr);
-            self.emit(Instruction::Assign { dest, value });
+        let (var_name, destructures) = match &local.pat {
+            syn::Pat::Ident(ident) => (ident.ident.to_string(), false),
+            _ => (format!("_pat{}", self.temp_counter), true),
+        };
+        let dest = self.write_var(&var_name);
+        self.emit(Instruction::Assign {
+            dest: dest.clone(),
+            value,
+        });
+        if destructures {
+            for name in pattern_idents(&local.pat) {
+                let var = self.write_var(&name);
+                self.emit(Instruction::Assign {
+                    dest: var,
+                    value: Operand::Var(dest.clone()),
+                });
+            }
         }
     }
 
@@ -201,6 +311,13 @@ impl IrBuilder {
             syn::Expr::Try(try_expr) => self.lower_try(try_expr),
             syn::Expr::Reference(ref_expr) => self.lower_expr(&ref_expr.expr),
             syn::Expr::Paren(paren) => self.lower_expr(&paren.expr),
+            syn::Expr::Cast(cast) => self.lower_expr(&cast.expr),
+            syn::Expr::ForLoop(for_loop) => self.lower_for_loop(for_loop),
+            syn::Expr::While(while_loop) => self.lower_while(while_loop),
+            syn::Expr::Loop(loop_expr) => self.lower_loop(loop_expr),
+            syn::Expr::Macro(mac) => self.lower_macro(&mac.mac),
+            syn::Expr::Struct(expr_struct) => self.lower_struct(expr_struct),
+            syn::Expr::Assign(assign) => self.lower_assign(assign),
             _ => {
                 // For unhandled expressions, emit a generic opaque operand
                 let temp = self.new_temp();
@@ -213,6 +330,19 @@ impl IrBuilder {
         }
     }
 
+    /// Lower `x = value`: a plain name gets a new version, any other place
+    /// (a field, an index) only has its value evaluated
+    fn lower_assign(&mut self, assign: &syn::ExprAssign) -> Operand {
+        let value = self.lower_expr(&assign.right);
+        if let syn::Expr::Path(path) = assign.left.as_ref() {
+            if let Some(ident) = path.path.get_ident() {
+                let dest = self.write_var(&ident.to_string());
+                self.emit(Instruction::Assign { dest, value });
+            }
+        }
+        Operand::Literal(LiteralValue::Unit)
+    }
+
     fn lower_lit(&mut self, lit: &syn::ExprLit) -> Operand {
         match &lit.lit {
             syn::Lit::Str(s) => Operand::Literal(LiteralValue::String(s.value())),
@@ -226,12 +356,13 @@ impl IrBuilder {
                 }
             }
             syn::Lit::Bool(b) => Operand::Literal(LiteralValue::Bool(b.value)),
+            syn::Lit::Float(f) => Operand::Literal(LiteralValue::Float(f.to_string())),
             _ => Operand::Literal(LiteralValue::Unit),
         }
     }
 
     fn lower_path(&mut self, path: &syn::ExprPath) -> Operand {
-        match classify_path(path, &self.var_counter) {
+        match classify_path(path, &self.versions) {
             PathKind::TypeOrVariant => {
                 // Enum variants and type paths produce a literal marker,
                 // not an SSA variable, to avoid polluting def-use chains.
@@ -246,16 +377,7 @@ impl IrBuilder {
             }
             PathKind::Variable => {
                 let ident = path.path.segments[0].ident.to_string();
-                if let Some(&version) = self.var_counter.get(&ident) {
-                    Operand::Var(SsaVar {
-                        name: ident,
-                        version: version.saturating_sub(1),
-                    })
-                } else {
-                    // Unknown variable — create fresh SSA var
-                    let var = self.new_ssa_var(&ident);
-                    Operand::Var(var)
-                }
+                Operand::Var(self.read_var(&ident))
             }
         }
     }
@@ -285,6 +407,23 @@ impl IrBuilder {
             _ => BinaryOp::Unknown,
         };
 
+        // `info.sender == owner` style comparisons are recorded as sender checks
+        if matches!(op, BinaryOp::Eq | BinaryOp::Ne) {
+            let sender_check = if operand_mentions_sender(&left) {
+                Some((left.clone(), right.clone()))
+            } else if operand_mentions_sender(&right) {
+                Some((right.clone(), left.clone()))
+            } else {
+                None
+            };
+            if let Some((sender_var, expected)) = sender_check {
+                self.emit(Instruction::CheckSender {
+                    sender_var,
+                    expected,
+                });
+            }
+        }
+
         let dest = self.new_temp();
         self.emit(Instruction::BinaryOp {
             dest: dest.clone(),
@@ -331,6 +470,25 @@ impl IrBuilder {
             return Operand::Var(dest);
         }
 
+        if let Some(kind) = QueryKind::from_method(&method).filter(|_| is_querier(&receiver)) {
+            let mut args = args.into_iter();
+            let target = args.next().unwrap_or(Operand::Literal(LiteralValue::Unit));
+            let args: Vec<Operand> = args.collect();
+            let msg_type = match kind {
+                QueryKind::WasmSmart => args.first().and_then(|msg| self.constructor_of(msg)),
+                _ => None,
+            };
+            let dest = self.new_temp();
+            self.emit(Instruction::QueryExternal {
+                dest: dest.clone(),
+                kind,
+                target,
+                msg_type,
+                args,
+            });
+            return Operand::Var(dest);
+        }
+
         if method == "save" || method == "update" {
             // Storage store pattern: ITEM.save(storage, &value) or MAP.save(storage, key, &value)
             if let Operand::Var(ref recv_var) = receiver {
@@ -493,6 +651,93 @@ impl IrBuilder {
         Operand::Literal(LiteralValue::Unit)
     }
 
+    /// Lower `for pat in iter { body }` as header (`iter.next()` + branch), body
+    /// with a back edge to the header, and an exit block
+    fn lower_for_loop(&mut self, for_loop: &syn::ExprForLoop) -> Operand {
+        let iter = self.lower_expr(&for_loop.expr);
+        let header = self.new_block();
+        let body = self.new_block();
+        let exit = self.new_block();
+
+        self.emit(Instruction::Jump { target: header });
+        self.cfg.add_edge(self.current_block, header);
+
+        self.current_block = header;
+        let item = self.new_temp();
+        self.emit(Instruction::MethodCall {
+            dest: Some(item.clone()),
+            receiver: iter,
+            method: "next".to_string(),
+            args: Vec::new(),
+        });
+        for name in pattern_idents(&for_loop.pat) {
+            let dest = self.write_var(&name);
+            self.emit(Instruction::Assign {
+                dest,
+                value: Operand::Var(item.clone()),
+            });
+        }
+        self.emit(Instruction::Branch {
+            condition: Operand::Var(item),
+            true_block: body,
+            false_block: exit,
+        });
+        self.cfg.add_edge(header, body);
+        self.cfg.add_edge(header, exit);
+
+        self.lower_loop_body(&for_loop.body, body, header);
+        self.current_block = exit;
+        Operand::Literal(LiteralValue::Unit)
+    }
+
+    fn lower_while(&mut self, while_loop: &syn::ExprWhile) -> Operand {
+        let header = self.new_block();
+        let body = self.new_block();
+        let exit = self.new_block();
+
+        self.emit(Instruction::Jump { target: header });
+        self.cfg.add_edge(self.current_block, header);
+
+        self.current_block = header;
+        let condition = self.lower_expr(&while_loop.cond);
+        self.emit(Instruction::Branch {
+            condition,
+            true_block: body,
+            false_block: exit,
+        });
+        self.cfg.add_edge(self.current_block, body);
+        self.cfg.add_edge(self.current_block, exit);
+
+        self.lower_loop_body(&while_loop.body, body, header);
+        self.current_block = exit;
+        Operand::Literal(LiteralValue::Unit)
+    }
+
+    /// `loop { }` only exits through `break`, which is not modeled; an edge from the
+    /// header to the exit block keeps the code after the loop reachable
+    fn lower_loop(&mut self, loop_expr: &syn::ExprLoop) -> Operand {
+        let header = self.new_block();
+        let exit = self.new_block();
+
+        self.emit(Instruction::Jump { target: header });
+        self.cfg.add_edge(self.current_block, header);
+        self.cfg.add_edge(header, exit);
+
+        self.lower_loop_body(&loop_expr.body, header, header);
+        self.current_block = exit;
+        Operand::Literal(LiteralValue::Unit)
+    }
+
+    /// Lower a loop body starting at `body`, closing it with a back edge to `header`
+    fn lower_loop_body(&mut self, block: &syn::Block, body: BlockId, header: BlockId) {
+        self.current_block = body;
+        for stmt in &block.stmts {
+            self.lower_stmt(stmt);
+        }
+        self.emit(Instruction::Jump { target: header });
+        self.cfg.add_edge(self.current_block, header);
+    }
+
     fn lower_block_expr(&mut self, block: &syn::ExprBlock) -> Operand {
         let mut last = Operand::Literal(LiteralValue::Unit);
         for stmt in &block.block.stmts {
@@ -511,6 +756,12 @@ impl IrBuilder {
 
     fn lower_return(&mut self, ret: &syn::ExprReturn) -> Operand {
         let value = ret.expr.as_ref().map(|e| self.lower_expr(e));
+        if let Some(syn::Expr::Call(call)) = ret.expr.as_deref() {
+            if is_err_constructor(call) {
+                let error = value.clone().unwrap_or(Operand::Literal(LiteralValue::Unit));
+                self.emit(Instruction::ErrorReturn { error });
+            }
+        }
         self.emit(Instruction::Return { value });
         Operand::Literal(LiteralValue::Unit)
     }
@@ -526,21 +777,83 @@ impl IrBuilder {
     }
 
     fn lower_macro_stmt(&mut self, mac: &syn::StmtMacro) {
-        // Recognize common macros like ensure!, bail!
+        self.lower_macro(&mac.mac);
+    }
+
+    /// Lower a macro invocation (ensure!, bail!, etc.) to a `macro!name` call.
+    /// Comma-separated expression arguments are lowered so conditions like
+    /// `ensure!(fee <= MAX, ...)` show up as comparisons in the IR.
+    fn lower_macro(&mut self, mac: &syn::Macro) -> Operand {
         let macro_name = mac
-            .mac
             .path
             .segments
             .last()
             .map(|s| s.ident.to_string())
             .unwrap_or_default();
 
+        let parsed = mac.parse_body_with(
+            syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated,
+        );
+        let args: Vec<Operand> = parsed
+            .map(|exprs| exprs.iter().map(|e| self.lower_expr(e)).collect())
+            .unwrap_or_default();
+
         let dest = self.new_temp();
         self.emit(Instruction::Call {
-            dest: Some(dest),
+            dest: Some(dest.clone()),
             func: format!("macro!{macro_name}"),
-            args: Vec::new(),
+            args,
         });
+        if macro_name == "bail" {
+            self.emit(Instruction::ErrorReturn {
+                error: Operand::Var(dest.clone()),
+            });
+            self.emit(Instruction::Return {
+                value: Some(Operand::Var(dest.clone())),
+            });
+        }
+        Operand::Var(dest)
+    }
+
+    /// Lower a struct literal as a constructor call taking the field values,
+    /// so data flowing into stored structs stays visible in def-use chains
+    fn lower_struct(&mut self, expr_struct: &syn::ExprStruct) -> Operand {
+        let func = expr_struct
+            .path
+            .segments
+            .iter()
+            .map(|s| s.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::");
+        let fields: Vec<(String, Operand)> = expr_struct
+            .fields
+            .iter()
+            .map(|f| {
+                let name = match &f.member {
+                    syn::Member::Named(ident) => ident.to_string(),
+                    syn::Member::Unnamed(idx) => format!("_{}", idx.index),
+                };
+                (name, self.lower_expr(&f.expr))
+            })
+            .collect();
+        let mut args: Vec<Operand> = fields.iter().map(|(_, op)| op.clone()).collect();
+        if let Some(rest) = &expr_struct.rest {
+            args.push(self.lower_expr(rest));
+        }
+
+        let dest = self.new_temp();
+        self.emit(Instruction::Call {
+            dest: Some(dest.clone()),
+            func: func.clone(),
+            args,
+        });
+        if is_cosmos_msg_path(&func) {
+            self.emit(Instruction::SendMsg {
+                msg_type: func,
+                fields,
+            });
+        }
+        Operand::Var(dest)
     }
 }
 
@@ -600,6 +913,25 @@ mod tests {
         assert!(func.cfg.blocks.len() >= 5);
     }
 
+    #[test]
+    fn test_cyclomatic_complexity() {
+        let source = r#"
+            fn straight() -> u32 { 1 }
+            fn branchy(x: u32, y: bool) -> u32 {
+                if y { 1 } else { 2 }
+                match x {
+                    1 => {},
+                    2 => {},
+                    _ => {},
+                }
+            }
+        "#;
+        let ir = build_ir(source);
+        assert_eq!(ir.get_function("straight").unwrap().cfg.cyclomatic_complexity(), 1);
+        // one if (+1) and a three-arm match (+2)
+        assert_eq!(ir.get_function("branchy").unwrap().cfg.cyclomatic_complexity(), 4);
+    }
+
     #[test]
     fn test_entry_point_detected() {
         let source = r#"
@@ -631,6 +963,154 @@ mod tests {
         assert!(has_addr_validate);
     }
 
+    #[test]
+    fn test_querier_calls_lowered_as_external_queries() {
+        let source = r#"
+            fn check(deps: Deps, oracle: Addr, msg: QueryMsg, pool: Pool) {
+                let price: PriceResponse =
+                    deps.querier.query_wasm_smart(&oracle, &OracleQueryMsg::Price { denom: "uatom" })?;
+                let query = OracleQueryMsg::Config;
+                let config: ConfigResponse = deps.querier.query_wasm_smart(&oracle, &query)?;
+                let passed: Response = deps.querier.query_wasm_smart(&oracle, &msg)?;
+                let balance = deps.querier.query_balance(&oracle, "uatom")?;
+                let own = pool.query_balance(&oracle);
+            }
+        "#;
+        let ir = build_ir(source);
+        let queries: Vec<(QueryKind, Option<&str>, usize)> = ir.functions[0]
+            .cfg
+            .blocks
+            .iter()
+            .flat_map(|b| &b.instructions)
+            .filter_map(|i| match i {
+                Instruction::QueryExternal {
+                    kind,
+                    target: Operand::Var(target),
+                    msg_type,
+                    args,
+                    ..
+                } => {
+                    assert_eq!(target.name, "oracle");
+                    Some((*kind, msg_type.as_deref(), args.len()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            queries,
+            vec![
+                (QueryKind::WasmSmart, Some("OracleQueryMsg::Price"), 1),
+                (QueryKind::WasmSmart, Some("OracleQueryMsg::Config"), 1),
+                // A message passed in is not resolvable
+                (QueryKind::WasmSmart, None, 1),
+                (QueryKind::Balance, None, 1),
+            ]
+        );
+        // Only calls on the querier are queries
+        let own_query = |i: &Instruction| match i {
+            Instruction::MethodCall { method, .. } => method == "query_balance",
+            _ => false,
+        };
+        assert!(ir.functions[0]
+            .cfg
+            .blocks
+            .iter()
+            .flat_map(|b| &b.instructions)
+            .any(own_query));
+    }
+
+    #[test]
+    fn test_dominators_of_if() {
+        let source = r#"
+            fn check(x: bool) -> u32 {
+                if x { 1 } else { 2 }
+            }
+        "#;
+        let ir = build_ir(source);
+        let cfg = &ir.functions[0].cfg;
+        let doms = cfg.dominators();
+        // Entry dominates every block; neither branch dominates the merge block
+        let merge = cfg.blocks.len() - 1;
+        assert!(doms[&merge].contains(&cfg.entry_block));
+        assert!(!doms[&merge].contains(&1));
+        assert!(!doms[&merge].contains(&2));
+        assert!(doms[&1].contains(&1));
+    }
+
+    #[test]
+    fn test_for_loop_has_back_edge() {
+        let source = r#"
+            fn save_all(deps: DepsMut, items: Vec<Item>) {
+                for (key, item) in items.iter() {
+                    ITEMS.save(deps.storage, key, item);
+                }
+            }
+        "#;
+        let ir = build_ir(source);
+        let cfg = &ir.functions[0].cfg;
+        let loops = cfg.natural_loops();
+        assert_eq!(loops.len(), 1);
+        let header = &cfg.blocks[loops[0].header];
+        assert!(matches!(
+            &header.instructions[0],
+            Instruction::MethodCall { method, .. } if method == "next"
+        ));
+        let store_in_loop = loops[0].blocks.iter().any(|&b| {
+            cfg.blocks[b]
+                .instructions
+                .iter()
+                .any(|i| matches!(i, Instruction::StorageStore { .. }))
+        });
+        assert!(store_in_loop);
+    }
+
+    #[test]
+    fn test_while_and_loop_lowered() {
+        let source = r#"
+            fn spin(mut n: u32) {
+                while n > 0 { n -= 1; }
+                loop { break; }
+            }
+        "#;
+        let ir = build_ir(source);
+        assert_eq!(ir.functions[0].cfg.natural_loops().len(), 2);
+    }
+
+    #[test]
+    fn test_macro_args_lowered() {
+        let source = r#"
+            fn check(fee: u64) -> StdResult<()> {
+                ensure!(fee <= 10_000, StdError::generic_err("fee"));
+                Ok(())
+            }
+        "#;
+        let ir = build_ir(source);
+        let func = &ir.functions[0];
+        let insts: Vec<_> = func.cfg.blocks.iter().flat_map(|b| &b.instructions).collect();
+        assert!(insts
+            .iter()
+            .any(|i| matches!(i, Instruction::BinaryOp { op: BinaryOp::Le, .. })));
+        assert!(insts.iter().any(|i| matches!(
+            i,
+            Instruction::Call { func, args, .. } if func == "macro!ensure" && args.len() == 2
+        )));
+    }
+
+    #[test]
+    fn test_struct_literal_lowered_as_constructor() {
+        let source = r#"
+            fn build(fee: u64) {
+                let config = Config { fee, owner: "x".to_string() };
+            }
+        "#;
+        let ir = build_ir(source);
+        let func = &ir.functions[0];
+        let has_ctor = func.cfg.blocks.iter().flat_map(|b| &b.instructions).any(|i| {
+            matches!(i, Instruction::Call { func, args, .. } if func == "Config" && args.len() == 2)
+        });
+        assert!(has_ctor);
+    }
+
     // --- H1 regression: enum variants and type paths should NOT create SSA vars ---
 
     #[test]
@@ -673,4 +1153,190 @@ mod tests {
         });
         assert!(has_count_var, "H1: local variable should still be an SSA var");
     }
+
+    /// SSA invariants of `func`: every variable is defined once, and every
+    /// use refers to a parameter, a definition earlier in its block or in
+    /// another block, or a free variable (version 0, never defined)
+    fn assert_ssa(func: &FunctionIr) {
+        use crate::ir::{instruction_def, instruction_uses};
+
+        let mut defs: HashMap<&SsaVar, (BlockId, usize)> = HashMap::new();
+        for block in &func.cfg.blocks {
+            for (idx, inst) in block.instructions.iter().enumerate() {
+                if let Some(var) = instruction_def(inst) {
+                    let first = defs.insert(var, (block.id, idx));
+                    assert!(first.is_none(), "{}: `{var}` defined twice", func.name);
+                }
+            }
+        }
+        for var in &func.params {
+            let first = defs.insert(var, (func.cfg.entry_block, 0));
+            assert!(
+                first.is_none(),
+                "{}: parameter `{var}` redefined",
+                func.name
+            );
+        }
+        for block in &func.cfg.blocks {
+            for (idx, inst) in block.instructions.iter().enumerate() {
+                for var in instruction_uses(inst) {
+                    match defs.get(var) {
+                        Some(&(def_block, def_idx)) if !func.params.contains(var) => assert!(
+                            def_block != block.id || def_idx < idx,
+                            "{}: `{var}` used at {}:{idx} before its definition",
+                            func.name,
+                            block.id
+                        ),
+                        Some(_) => {}
+                        None => assert_eq!(
+                            var.version, 0,
+                            "{}: `{var}` used without a definition",
+                            func.name
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_let_reads_previous_version() {
+        let source = r#"
+            fn pay(amount: Uint128, fee: Uint128) {
+                let amount = amount - fee;
+                let (net, rest): (Uint128, Uint128) = split(amount);
+                send(net);
+            }
+        "#;
+        let ir = build_ir(source);
+        let func = &ir.functions[0];
+        assert_ssa(func);
+        let insts: Vec<&Instruction> = func.cfg.blocks[0].instructions.iter().collect();
+        let var = |name: &str, version| {
+            Operand::Var(SsaVar {
+                name: name.to_string(),
+                version,
+            })
+        };
+        // The shadowing binding reads the parameter, not itself
+        assert!(
+            matches!(insts[0], Instruction::BinaryOp { left, .. } if *left == var("amount", 0))
+        );
+        assert!(
+            matches!(insts[1], Instruction::Assign { dest, .. } if dest.name == "amount" && dest.version == 1)
+        );
+        assert!(matches!(insts[2], Instruction::Call { args, .. } if args[0] == var("amount", 1)));
+        // Destructured names are defined from the whole value
+        let net_def = insts
+            .iter()
+            .position(|i| matches!(i, Instruction::Assign { dest, .. } if dest.name == "net"));
+        let send = insts.iter().position(
+            |i| matches!(i, Instruction::Call { func, args, .. } if func == "send" && args[0] == var("net", 0)),
+        );
+        assert!(net_def.unwrap() < send.unwrap());
+    }
+
+    #[test]
+    fn test_assignment_defines_new_version() {
+        let source = r#"
+            fn withdraw(resp: Response, msg: BankMsg, mut config: Config, sender: Addr) {
+                resp = resp.add_message(msg);
+                config.owner = sender;
+                send(resp);
+            }
+        "#;
+        let ir = build_ir(source);
+        let func = &ir.functions[0];
+        assert_ssa(func);
+        let insts: Vec<&Instruction> = func.cfg.blocks[0].instructions.iter().collect();
+        let var = |name: &str, version| {
+            Operand::Var(SsaVar {
+                name: name.to_string(),
+                version,
+            })
+        };
+        // The right-hand side is lowered, reading the previous version
+        assert!(matches!(
+            insts[0],
+            Instruction::MethodCall { receiver, method, .. }
+                if method == "add_message" && *receiver == var("resp", 0)
+        ));
+        assert!(
+            matches!(insts[1], Instruction::Assign { dest, .. } if dest.name == "resp" && dest.version == 1)
+        );
+        // A field is not a variable of its own: nothing redefines `config`
+        assert!(!insts
+            .iter()
+            .any(|i| matches!(i, Instruction::Assign { dest, .. } if dest.name == "config")));
+        assert!(insts.iter().any(
+            |i| matches!(i, Instruction::Call { func, args, .. } if func == "send" && args[0] == var("resp", 1))
+        ));
+    }
+
+    #[test]
+    fn test_fixture_ir_is_well_formed() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../cli/tests/fixtures");
+        let mut dirs = vec![root];
+        let mut checked = 0;
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if path.extension().is_some_and(|e| e == "rs") {
+                    let ir = build_ir(&std::fs::read_to_string(&path).unwrap());
+                    ir.functions.iter().for_each(assert_ssa);
+                    checked += ir.functions.len();
+                }
+            }
+        }
+        assert!(checked > 50);
+    }
+
+    #[test]
+    fn test_generated_shadowing_links_latest_versions() {
+        // Deterministic pseudo-random sequences of `let v = a + b;` over a
+        // few names, checked against a model of the latest version of each
+        let names = ["a", "b", "c"];
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = |n: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % n as u64) as usize
+        };
+        for _ in 0..50 {
+            let mut latest: HashMap<&str, u32> = names.iter().map(|n| (*n, 0)).collect();
+            let mut body = String::new();
+            let mut expected = Vec::new();
+            for _ in 0..12 {
+                let (dest, left, right) = (names[next(3)], names[next(3)], names[next(3)]);
+                body.push_str(&format!("let {dest} = {left} + {right};\n"));
+                let operands = (latest[left], latest[right]);
+                *latest.get_mut(dest).unwrap() += 1;
+                expected.push((operands, latest[dest]));
+            }
+            let source = format!("fn f(a: u64, b: u64, c: u64) {{\n{body}}}");
+            let ir = build_ir(&source);
+            let func = &ir.functions[0];
+            assert_ssa(func);
+
+            let mut actual = Vec::new();
+            for pair in func.cfg.blocks[0].instructions.chunks(2) {
+                let (
+                    Instruction::BinaryOp {
+                        left: Operand::Var(left),
+                        right: Operand::Var(right),
+                        ..
+                    },
+                    Instruction::Assign { dest, .. },
+                ) = (&pair[0], &pair[1])
+                else {
+                    panic!("unexpected lowering of:\n{source}");
+                };
+                actual.push(((left.version, right.version), dest.version));
+            }
+            assert_eq!(actual, expected, "{source}");
+        }
+    }
 }
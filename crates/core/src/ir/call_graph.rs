@@ -0,0 +1,101 @@
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use super::analyses::direct_calls;
+use super::types::ContractIr;
+
+/// Direct call edges between the contract's own functions.
+/// Calls are resolved on their last path segment (`helpers::check` -> `check`);
+/// calls to functions outside the contract are dropped. Call edges cached
+/// with a function's file are reused and resolved against the current set of
+/// functions, so a function added in another file is picked up.
+#[derive(Debug, Clone, Default)]
+pub struct CallGraph {
+    callees: HashMap<String, BTreeSet<String>>,
+}
+
+impl CallGraph {
+    pub fn build(ir: &ContractIr) -> Self {
+        let known: HashSet<&str> = ir.functions.iter().map(|f| f.name.as_str()).collect();
+        let mut callees: HashMap<String, BTreeSet<String>> = HashMap::new();
+
+        for func in &ir.functions {
+            let edges = callees.entry(func.name.clone()).or_default();
+            let calls = match &func.analyses {
+                Some(analyses) => Cow::Borrowed(&analyses.calls),
+                None => Cow::Owned(direct_calls(func)),
+            };
+            edges.extend(
+                calls
+                    .iter()
+                    .filter(|callee| known.contains(callee.as_str()))
+                    .cloned(),
+            );
+        }
+
+        Self { callees }
+    }
+
+    /// Functions called directly by `function`
+    pub fn callees(&self, function: &str) -> impl Iterator<Item = &str> {
+        self.callees
+            .get(function)
+            .into_iter()
+            .flat_map(|c| c.iter().map(String::as_str))
+    }
+
+    /// Functions that call `function` directly
+    pub fn callers(&self, function: &str) -> Vec<&str> {
+        let mut callers: Vec<&str> = self
+            .callees
+            .iter()
+            .filter(|(_, callees)| callees.contains(function))
+            .map(|(caller, _)| caller.as_str())
+            .collect();
+        callers.sort_unstable();
+        callers
+    }
+
+    /// Names of functions reachable from `roots` (roots included)
+    pub fn reachable_from(&self, roots: &[&str]) -> HashSet<String> {
+        let mut reachable = HashSet::new();
+        let mut queue: VecDeque<&str> = roots.iter().copied().collect();
+        while let Some(name) = queue.pop_front() {
+            if reachable.insert(name.to_string()) {
+                queue.extend(self.callees(name));
+            }
+        }
+        reachable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::ir::builder::IrBuilder;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_call_graph_edges_and_reachability() {
+        let source = r#"
+            fn execute(deps: DepsMut) { execute_a(deps); }
+            fn execute_a(deps: DepsMut) { helpers::check(deps); to_json_binary(&1); }
+            fn check(deps: DepsMut) {}
+            fn unused() { check(); }
+        "#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let graph = CallGraph::build(&IrBuilder::build_contract(&contract));
+
+        assert_eq!(
+            graph.callees("execute_a").collect::<Vec<_>>(),
+            vec!["check"]
+        );
+        assert_eq!(graph.callers("check"), vec!["execute_a", "unused"]);
+
+        let reachable = graph.reachable_from(&["execute"]);
+        assert!(reachable.contains("check"));
+        assert!(!reachable.contains("unused"));
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
@@ -7,7 +7,7 @@ use super::instruction::{Instruction, Operand, SsaVar};
 pub type BlockId = usize;
 
 /// A basic block in the CFG
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BasicBlock {
     pub id: BlockId,
     pub instructions: Vec<Instruction>,
@@ -27,15 +27,23 @@ impl BasicBlock {
 }
 
 /// Def-use information for a single SSA variable
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DefUse {
     pub def_block: BlockId,
     pub def_instruction_idx: usize,
     pub uses: Vec<(BlockId, usize)>,
 }
 
+/// A natural loop: a header block plus every block that can reach a back edge
+/// into the header without passing through it
+#[derive(Debug, Clone)]
+pub struct NaturalLoop {
+    pub header: BlockId,
+    pub blocks: BTreeSet<BlockId>,
+}
+
 /// Control flow graph for a single function
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Cfg {
     pub function_name: String,
     pub blocks: Vec<BasicBlock>,
@@ -130,6 +138,77 @@ impl Cfg {
         chains
     }
 
+    /// McCabe cyclomatic complexity: E - N + 2 over the blocks of this CFG
+    pub fn cyclomatic_complexity(&self) -> usize {
+        let edges: usize = self.blocks.iter().map(|b| b.successors.len()).sum();
+        (edges + 2).saturating_sub(self.blocks.len()).max(1)
+    }
+
+    /// Compute the dominator set of every block reachable from the entry block.
+    /// Block `d` dominates `b` when every path from the entry to `b` passes through `d`.
+    pub fn dominators(&self) -> HashMap<BlockId, HashSet<BlockId>> {
+        let order = self.reverse_postorder();
+        let all: HashSet<BlockId> = order.iter().copied().collect();
+        let mut doms: HashMap<BlockId, HashSet<BlockId>> = order
+            .iter()
+            .map(|&b| (b, all.clone()))
+            .collect();
+        doms.insert(self.entry_block, HashSet::from([self.entry_block]));
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &block in order.iter().skip(1) {
+                let mut new_set: Option<HashSet<BlockId>> = None;
+                for pred in &self.blocks[block].predecessors {
+                    let Some(pred_doms) = doms.get(pred) else { continue };
+                    new_set = Some(match new_set {
+                        None => pred_doms.clone(),
+                        Some(acc) => acc.intersection(pred_doms).copied().collect(),
+                    });
+                }
+                let mut new_set = new_set.unwrap_or_default();
+                new_set.insert(block);
+                if doms[&block] != new_set {
+                    doms.insert(block, new_set);
+                    changed = true;
+                }
+            }
+        }
+        doms
+    }
+
+    /// Find natural loops from back edges (edges whose target dominates their source).
+    /// Loops sharing a header are merged.
+    pub fn natural_loops(&self) -> Vec<NaturalLoop> {
+        let doms = self.dominators();
+        let mut loops: Vec<NaturalLoop> = Vec::new();
+
+        for block in &self.blocks {
+            for &header in &block.successors {
+                let is_back_edge = doms.get(&block.id).is_some_and(|d| d.contains(&header));
+                if !is_back_edge {
+                    continue;
+                }
+                let mut body = BTreeSet::from([header]);
+                let mut stack = vec![block.id];
+                while let Some(b) = stack.pop() {
+                    if body.insert(b) {
+                        stack.extend(self.blocks[b].predecessors.iter().copied());
+                    }
+                }
+                match loops.iter_mut().find(|l| l.header == header) {
+                    Some(existing) => existing.blocks.extend(body),
+                    None => loops.push(NaturalLoop {
+                        header,
+                        blocks: body,
+                    }),
+                }
+            }
+        }
+        loops
+    }
+
     /// Iterate blocks in reverse postorder (useful for dataflow analysis)
     pub fn reverse_postorder(&self) -> Vec<BlockId> {
         let mut visited = HashSet::new();
@@ -157,8 +236,22 @@ impl Cfg {
     }
 }
 
+/// True when the instruction at `guard` executes before the one at `target`
+/// on every path, given the `Cfg::dominators` of their function.
+/// Positions are (block, instruction index).
+pub fn instruction_dominates(
+    doms: &HashMap<BlockId, HashSet<BlockId>>,
+    guard: (BlockId, usize),
+    target: (BlockId, usize),
+) -> bool {
+    if guard.0 == target.0 {
+        return guard.1 < target.1;
+    }
+    doms.get(&target.0).is_some_and(|d| d.contains(&guard.0))
+}
+
 /// Extract the defined variable from an instruction (if any)
-fn instruction_def(inst: &Instruction) -> Option<&SsaVar> {
+pub fn instruction_def(inst: &Instruction) -> Option<&SsaVar> {
     match inst {
         Instruction::Assign { dest, .. }
         | Instruction::BinaryOp { dest, .. }
@@ -166,6 +259,7 @@ fn instruction_def(inst: &Instruction) -> Option<&SsaVar> {
         | Instruction::Phi { dest, .. }
         | Instruction::StorageLoad { dest, .. }
         | Instruction::AddrValidate { dest, .. }
+        | Instruction::QueryExternal { dest, .. }
         | Instruction::ResultUnwrap { dest, .. } => Some(dest),
         Instruction::Call { dest, .. } | Instruction::MethodCall { dest, .. } => dest.as_ref(),
         _ => None,
@@ -173,7 +267,7 @@ fn instruction_def(inst: &Instruction) -> Option<&SsaVar> {
 }
 
 /// Extract all used variables from an instruction
-fn instruction_uses(inst: &Instruction) -> Vec<&SsaVar> {
+pub fn instruction_uses(inst: &Instruction) -> Vec<&SsaVar> {
     let mut uses = Vec::new();
     match inst {
         Instruction::Assign { value, .. } => collect_operand_vars(value, &mut uses),
@@ -212,6 +306,12 @@ fn instruction_uses(inst: &Instruction) -> Vec<&SsaVar> {
         Instruction::AddrValidate { address, .. } => {
             collect_operand_vars(address, &mut uses);
         }
+        Instruction::QueryExternal { target, args, .. } => {
+            collect_operand_vars(target, &mut uses);
+            for arg in args {
+                collect_operand_vars(arg, &mut uses);
+            }
+        }
         Instruction::Branch { condition, .. } => collect_operand_vars(condition, &mut uses),
         Instruction::Return { value } => {
             if let Some(v) = value {
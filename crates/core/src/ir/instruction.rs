@@ -16,7 +16,7 @@ impl std::fmt::Display for SsaVar {
 }
 
 /// IR instructions — normalized operations
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Instruction {
     // Core operations
     Assign {
@@ -75,6 +75,18 @@ pub enum Instruction {
         sender_var: Operand,
         expected: Operand,
     },
+    /// Query to another contract or the bank module through `deps.querier`
+    QueryExternal {
+        dest: SsaVar,
+        kind: QueryKind,
+        /// Queried contract or account address
+        target: Operand,
+        /// Query message, e.g. `OracleQueryMsg::Price`, when built in the
+        /// function from a struct or variant
+        msg_type: Option<String>,
+        /// Arguments after the target: message, raw key or denom
+        args: Vec<Operand>,
+    },
 
     // Control flow
     Branch {
@@ -99,8 +111,99 @@ pub enum Instruction {
     },
 }
 
+/// What a [`Instruction::QueryExternal`] asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueryKind {
+    /// `query_wasm_smart(contract, &msg)`
+    WasmSmart,
+    /// `query_wasm_raw(contract, key)`
+    WasmRaw,
+    /// `query_balance(address, denom)`
+    Balance,
+    /// `query_all_balances(address)`
+    AllBalances,
+}
+
+impl QueryKind {
+    /// Kind of a querier method
+    pub fn from_method(method: &str) -> Option<Self> {
+        match method {
+            "query_wasm_smart" => Some(Self::WasmSmart),
+            "query_wasm_raw" => Some(Self::WasmRaw),
+            "query_balance" => Some(Self::Balance),
+            "query_all_balances" => Some(Self::AllBalances),
+            _ => None,
+        }
+    }
+
+    /// Whether the query reads another contract's state rather than a bank balance
+    pub fn is_wasm(self) -> bool {
+        matches!(self, Self::WasmSmart | Self::WasmRaw)
+    }
+}
+
+/// cw-storage-plus iteration methods taking `(storage, min, max, order)`
+const RANGE_METHODS: &[&str] = &["range", "range_raw", "keys", "keys_raw"];
+
+/// Bound and order arguments of a `range(storage, min, max, order)` call
+#[derive(Debug, Clone, Copy)]
+pub struct RangeArgs<'a> {
+    pub min: &'a Operand,
+    pub max: &'a Operand,
+    pub order: &'a Operand,
+}
+
+impl Instruction {
+    /// Operands the instruction reads
+    pub fn operands(&self) -> Vec<&Operand> {
+        match self {
+            Instruction::Assign { value, .. }
+            | Instruction::ResultUnwrap { value, .. }
+            | Instruction::UnaryOp { operand: value, .. }
+            | Instruction::AddrValidate { address: value, .. }
+            | Instruction::Branch {
+                condition: value, ..
+            }
+            | Instruction::ErrorReturn { error: value } => vec![value],
+            Instruction::BinaryOp { left, right, .. } => vec![left, right],
+            Instruction::Call { args, .. } => args.iter().collect(),
+            Instruction::MethodCall { receiver, args, .. } => {
+                std::iter::once(receiver).chain(args).collect()
+            }
+            Instruction::StorageLoad { key, .. } => key.iter().collect(),
+            Instruction::StorageStore { key, value, .. } => key.iter().chain([value]).collect(),
+            Instruction::SendMsg { fields, .. } => fields.iter().map(|(_, v)| v).collect(),
+            Instruction::CheckSender {
+                sender_var,
+                expected,
+            } => vec![sender_var, expected],
+            Instruction::QueryExternal { target, args, .. } => {
+                std::iter::once(target).chain(args).collect()
+            }
+            Instruction::Return { value } => value.iter().collect(),
+            Instruction::Phi { .. } | Instruction::Jump { .. } => Vec::new(),
+        }
+    }
+
+    /// Arguments of a `Map`/`Prefix` range or keys iteration, by position
+    pub fn range_args(&self) -> Option<RangeArgs<'_>> {
+        match self {
+            Instruction::MethodCall { method, args, .. }
+                if RANGE_METHODS.contains(&method.as_str()) && args.len() == 4 =>
+            {
+                Some(RangeArgs {
+                    min: &args[1],
+                    max: &args[2],
+                    order: &args[3],
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Operand — values used in instructions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Operand {
     Var(SsaVar),
     Literal(LiteralValue),
@@ -108,7 +211,7 @@ pub enum Operand {
 }
 
 /// Binary operations
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BinaryOp {
     Add,
     Sub,
@@ -132,7 +235,7 @@ pub enum BinaryOp {
 }
 
 /// Unary operations
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum UnaryOp {
     Not,
     Neg,
@@ -142,11 +245,13 @@ pub enum UnaryOp {
 }
 
 /// Literal values
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LiteralValue {
     Int(i128),
     Uint(u128),
     String(String),
     Bool(bool),
+    /// Floating-point literal as written, e.g. `0.5` or `1e3f32`
+    Float(String),
     Unit,
 }
@@ -1,8 +1,23 @@
+pub mod analyses;
 pub mod builder;
+pub mod call_graph;
 pub mod cfg;
 pub mod instruction;
+pub mod query;
+pub mod slice;
+pub mod summary;
+pub mod taint;
 pub mod types;
 
-pub use cfg::{BasicBlock, BlockId, Cfg};
-pub use instruction::{BinaryOp, Instruction, LiteralValue, Operand, SsaVar, UnaryOp};
+pub use analyses::FunctionAnalyses;
+pub use call_graph::CallGraph;
+pub use cfg::{
+    instruction_def, instruction_dominates, instruction_uses, BasicBlock, BlockId, Cfg, DefUse,
+    NaturalLoop,
+};
+pub use instruction::{
+    BinaryOp, Instruction, LiteralValue, Operand, QueryKind, RangeArgs, SsaVar, UnaryOp,
+};
+pub use summary::{EmittedMessage, FunctionSummary, Guard, GuardKind, StorageAccess};
+pub use taint::{helper_results, operand_tainted, propagate_taint, taint_path};
 pub use types::{ContractIr, FunctionIr};
@@ -0,0 +1,365 @@
+//! Instruction patterns over a function's CFG, so detectors can ask for
+//! "a `StorageLoad` of `CONFIG` whose value flows into a division" instead of
+//! walking blocks and chasing definitions by hand:
+//!
+//! ```
+//! use cosmwasm_guard::ir::query::{find, Pattern};
+//! use cosmwasm_guard::ir::BinaryOp;
+//! # fn check(cfg: &cosmwasm_guard::ir::Cfg) {
+//! let pattern = Pattern::storage_load()
+//!     .named("CONFIG")
+//!     .flows_into(Pattern::binary(BinaryOp::Div));
+//! for m in find(cfg, &pattern) {
+//!     let division = m.sink.as_ref().unwrap();
+//!     println!("block {} divides by a loaded value", division.block);
+//! }
+//! # }
+//! ```
+
+use std::collections::HashSet;
+
+use super::cfg::{instruction_def, instruction_uses, BlockId, Cfg};
+use super::instruction::{BinaryOp, Instruction, Operand, SsaVar};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Any,
+    StorageLoad,
+    StorageStore,
+    Call,
+    MethodCall,
+    Binary(BinaryOp),
+    AddrValidate,
+    SendMsg,
+    QueryExternal,
+}
+
+/// Description of the instructions to find, built from one of the
+/// constructors and narrowed with [`Pattern::named`] and [`Pattern::flows_into`]
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    kind: Kind,
+    name: Option<String>,
+    flows_into: Option<Box<Pattern>>,
+}
+
+impl Pattern {
+    fn new(kind: Kind) -> Self {
+        Self {
+            kind,
+            name: None,
+            flows_into: None,
+        }
+    }
+
+    /// Any instruction
+    pub fn any() -> Self {
+        Self::new(Kind::Any)
+    }
+
+    /// A load of a state item
+    pub fn storage_load() -> Self {
+        Self::new(Kind::StorageLoad)
+    }
+
+    /// A store to a state item
+    pub fn storage_store() -> Self {
+        Self::new(Kind::StorageStore)
+    }
+
+    /// A call of a free function
+    pub fn call() -> Self {
+        Self::new(Kind::Call)
+    }
+
+    /// A method call
+    pub fn method_call() -> Self {
+        Self::new(Kind::MethodCall)
+    }
+
+    /// A binary operation with operator `op`
+    pub fn binary(op: BinaryOp) -> Self {
+        Self::new(Kind::Binary(op))
+    }
+
+    /// An `addr_validate` call
+    pub fn addr_validate() -> Self {
+        Self::new(Kind::AddrValidate)
+    }
+
+    /// A chain message built in the function
+    pub fn send_msg() -> Self {
+        Self::new(Kind::SendMsg)
+    }
+
+    /// A query to another contract or the bank module
+    pub fn query_external() -> Self {
+        Self::new(Kind::QueryExternal)
+    }
+
+    /// Narrow to instructions on `name`: the state item of a load or store,
+    /// the callee's last path segment (`helpers::check` is `check`), the
+    /// method, or a message or query message type or family (`BankMsg`
+    /// matches `BankMsg::Send`). Ignored by the other kinds.
+    pub fn named(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Narrow to instructions whose result flows into an instruction matching
+    /// `sink`: the value, or a value computed from it by copies, unwraps,
+    /// operators or method calls on it, is one of the sink's operands.
+    /// Arguments of calls do not carry the value: `hash(x)` is a new value.
+    pub fn flows_into(mut self, sink: Pattern) -> Self {
+        self.flows_into = Some(Box::new(sink));
+        self
+    }
+
+    /// Whether `inst` itself matches, ignoring `flows_into`
+    fn matches(&self, inst: &Instruction) -> bool {
+        let name = self.name.as_deref();
+        let named = |actual: &str| name.is_none_or(|n| n == actual);
+        match (&self.kind, inst) {
+            (Kind::Any, _) => true,
+            (Kind::StorageLoad, Instruction::StorageLoad { storage_item, .. })
+            | (Kind::StorageStore, Instruction::StorageStore { storage_item, .. }) => {
+                named(storage_item)
+            }
+            (Kind::Call, Instruction::Call { func, .. }) => {
+                named(func.rsplit("::").next().unwrap_or(func))
+            }
+            (Kind::MethodCall, Instruction::MethodCall { method, .. }) => named(method),
+            (Kind::Binary(expected), Instruction::BinaryOp { op, .. }) => expected == op,
+            (Kind::AddrValidate, Instruction::AddrValidate { .. }) => true,
+            (Kind::SendMsg, Instruction::SendMsg { msg_type, .. }) => {
+                name.is_none_or(|n| in_family(msg_type, n))
+            }
+            (Kind::QueryExternal, Instruction::QueryExternal { msg_type, .. }) => {
+                name.is_none_or(|n| msg_type.as_deref().is_some_and(|t| in_family(t, n)))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// `msg_type` is `name` or one of its variants
+fn in_family(msg_type: &str, name: &str) -> bool {
+    msg_type == name
+        || msg_type
+            .strip_prefix(name)
+            .is_some_and(|rest| rest.starts_with("::"))
+}
+
+/// An instruction matching a pattern
+#[derive(Debug, Clone)]
+pub struct Match<'a> {
+    pub block: BlockId,
+    /// Position of the instruction in its block
+    pub index: usize,
+    pub instruction: &'a Instruction,
+    /// First instruction (in block order) the value flows into, for patterns
+    /// built with [`Pattern::flows_into`]
+    pub sink: Option<Box<Match<'a>>>,
+}
+
+/// Instructions of `cfg` matching `pattern`, in block order
+pub fn find<'a>(cfg: &'a Cfg, pattern: &Pattern) -> Vec<Match<'a>> {
+    positions(cfg)
+        .filter_map(|(block, index, inst)| matched(cfg, pattern, block, index, inst))
+        .collect()
+}
+
+fn positions(cfg: &Cfg) -> impl Iterator<Item = (BlockId, usize, &Instruction)> {
+    cfg.blocks.iter().flat_map(|b| {
+        b.instructions
+            .iter()
+            .enumerate()
+            .map(move |(index, inst)| (b.id, index, inst))
+    })
+}
+
+fn matched<'a>(
+    cfg: &'a Cfg,
+    pattern: &Pattern,
+    block: BlockId,
+    index: usize,
+    inst: &'a Instruction,
+) -> Option<Match<'a>> {
+    if !pattern.matches(inst) {
+        return None;
+    }
+    let sink = match &pattern.flows_into {
+        None => None,
+        Some(sink) => {
+            let flowing = flow_from(cfg, instruction_def(inst)?);
+            let found = positions(cfg)
+                .filter(|(b, i, _)| (*b, *i) != (block, index))
+                .filter(|(_, _, candidate)| {
+                    instruction_uses(candidate)
+                        .iter()
+                        .any(|v| flowing.contains(*v))
+                })
+                .find_map(|(b, i, candidate)| matched(cfg, sink, b, i, candidate))?;
+            Some(Box::new(found))
+        }
+    };
+    Some(Match {
+        block,
+        index,
+        instruction: inst,
+        sink,
+    })
+}
+
+/// Operands of `inst` whose value its result carries
+fn carried(inst: &Instruction) -> Vec<&Operand> {
+    match inst {
+        Instruction::Assign { value, .. }
+        | Instruction::ResultUnwrap { value, .. }
+        | Instruction::UnaryOp { operand: value, .. }
+        | Instruction::MethodCall {
+            receiver: value, ..
+        } => vec![value],
+        Instruction::BinaryOp { left, right, .. } => vec![left, right],
+        _ => Vec::new(),
+    }
+}
+
+fn carries(operand: &Operand, flowing: &HashSet<&SsaVar>) -> bool {
+    match operand {
+        Operand::Var(var) => flowing.contains(var),
+        Operand::FieldAccess { base, .. } => carries(base, flowing),
+        Operand::Literal(_) => false,
+    }
+}
+
+/// `source` and every variable carrying its value
+fn flow_from<'a>(cfg: &'a Cfg, source: &'a SsaVar) -> HashSet<&'a SsaVar> {
+    let mut flowing = HashSet::from([source]);
+    // Loops put uses before definitions in block order, so iterate to a fixpoint
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (_, _, inst) in positions(cfg) {
+            let Some(dest) = instruction_def(inst) else {
+                continue;
+            };
+            let carried_in = match inst {
+                Instruction::Phi { sources, .. } => {
+                    sources.iter().any(|(v, _)| flowing.contains(v))
+                }
+                _ => carried(inst).into_iter().any(|op| carries(op, &flowing)),
+            };
+            if carried_in && flowing.insert(dest) {
+                changed = true;
+            }
+        }
+    }
+    flowing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::ir::builder::IrBuilder;
+    use crate::ir::FunctionIr;
+    use std::path::PathBuf;
+
+    fn build(source: &str) -> FunctionIr {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        IrBuilder::build_contract(&contract).functions.remove(0)
+    }
+
+    #[test]
+    fn test_load_flowing_into_division() {
+        let func = build(
+            r#"
+            fn share(deps: Deps, amount: Uint128) -> Uint128 {
+                let state = STATE.load(deps.storage)?;
+                let config = CONFIG.load(deps.storage)?;
+                let scaled = amount * config.scale;
+                let total = state.total.u128();
+                hash(total);
+                scaled / total
+            }
+        "#,
+        );
+        let divisors = find(
+            &func.cfg,
+            &Pattern::storage_load().flows_into(Pattern::binary(BinaryOp::Div)),
+        );
+        let items: Vec<&str> = divisors
+            .iter()
+            .map(|m| match m.instruction {
+                Instruction::StorageLoad { storage_item, .. } => storage_item.as_str(),
+                _ => unreachable!(),
+            })
+            .collect();
+        // CONFIG reaches the division through the multiplication too
+        assert_eq!(items, vec!["STATE", "CONFIG"]);
+        let sink = divisors[0].sink.as_ref().unwrap();
+        assert!(matches!(
+            sink.instruction,
+            Instruction::BinaryOp {
+                op: BinaryOp::Div,
+                ..
+            }
+        ));
+
+        let named = find(&func.cfg, &Pattern::storage_load().named("STATE"));
+        assert_eq!(named.len(), 1);
+        // A call argument does not carry the value into the call's result
+        let hashed = Pattern::call().named("hash").flows_into(Pattern::any());
+        assert!(find(&func.cfg, &hashed).is_empty());
+    }
+
+    #[test]
+    fn test_message_family_and_nested_flows() {
+        let func = build(
+            r#"
+            fn pay(deps: DepsMut, to: String) -> StdResult<Response> {
+                let config = CONFIG.load(deps.storage)?;
+                let recipient = deps.api.addr_validate(&config.treasury)?;
+                let msg = BankMsg::Send { to_address: recipient.to_string(), amount: vec![] };
+                Ok(Response::new().add_message(msg))
+            }
+        "#,
+        );
+        assert_eq!(
+            find(&func.cfg, &Pattern::send_msg().named("BankMsg")).len(),
+            1
+        );
+        assert!(find(&func.cfg, &Pattern::send_msg().named("Bank")).is_empty());
+
+        let revalidated = Pattern::storage_load()
+            .named("CONFIG")
+            .flows_into(Pattern::addr_validate());
+        let found = find(&func.cfg, &revalidated);
+        assert_eq!(found.len(), 1);
+        assert!(matches!(
+            found[0].sink.as_ref().unwrap().instruction,
+            Instruction::AddrValidate { .. }
+        ));
+    }
+
+    #[test]
+    fn test_query_by_message_family() {
+        let func = build(
+            r#"
+            fn value(deps: Deps, oracle: Addr, amount: Uint128) -> StdResult<Uint128> {
+                let price: PriceResponse =
+                    deps.querier.query_wasm_smart(&oracle, &OracleQueryMsg::Price {})?;
+                let balance = deps.querier.query_balance(&oracle, "uatom")?;
+                Ok(amount * price.rate + balance.amount)
+            }
+        "#,
+        );
+        assert_eq!(find(&func.cfg, &Pattern::query_external()).len(), 2);
+        let priced = Pattern::query_external()
+            .named("OracleQueryMsg")
+            .flows_into(Pattern::binary(BinaryOp::Mul));
+        assert_eq!(find(&func.cfg, &priced).len(), 1);
+    }
+}
@@ -0,0 +1,197 @@
+//! Backward slices within one function: the operands a value is computed
+//! from, found by following each variable to its defining instruction.
+//!
+//! ```
+//! use cosmwasm_guard::ir::slice::Defs;
+//! use cosmwasm_guard::ir::{FunctionIr, Instruction, Operand};
+//! # fn check(func: &FunctionIr, operand: &Operand) {
+//! let defs = Defs::new(func);
+//! let from_storage = defs.slice(operand).any(|op| {
+//!     matches!(op, Operand::Var(v) if matches!(defs.get(v), Some(Instruction::StorageLoad { .. })))
+//! });
+//! # }
+//! ```
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use super::cfg::instruction_def;
+use super::instruction::{Instruction, Operand, SsaVar};
+use super::types::FunctionIr;
+
+/// Handler context names shared by unrelated values
+pub const CONTEXT_NAMES: &[&str] = &["deps", "env", "info", "msg", "storage", "api", "querier"];
+
+/// Operands the result of `inst` is computed from: the assigned value, both
+/// sides of an operation, and the receiver and arguments of a call or query
+pub fn value_inputs(inst: &Instruction) -> Vec<&Operand> {
+    match inst {
+        Instruction::Assign { value, .. }
+        | Instruction::ResultUnwrap { value, .. }
+        | Instruction::UnaryOp { operand: value, .. } => vec![value],
+        Instruction::BinaryOp { left, right, .. } => vec![left, right],
+        Instruction::Call { args, .. } => args.iter().collect(),
+        Instruction::MethodCall { receiver, args, .. } => {
+            std::iter::once(receiver).chain(args).collect()
+        }
+        Instruction::QueryExternal { target, args, .. } => {
+            std::iter::once(target).chain(args).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Defining instruction of each SSA variable of a function
+pub struct Defs<'a> {
+    defs: HashMap<&'a SsaVar, &'a Instruction>,
+}
+
+impl<'a> Defs<'a> {
+    pub fn new(func: &'a FunctionIr) -> Self {
+        let defs = func
+            .cfg
+            .blocks
+            .iter()
+            .flat_map(|b| &b.instructions)
+            .filter_map(|inst| instruction_def(inst).map(|v| (v, inst)))
+            .collect();
+        Self { defs }
+    }
+
+    /// Instruction defining `var`; None for parameters
+    pub fn get(&self, var: &SsaVar) -> Option<&'a Instruction> {
+        self.defs.get(var).copied()
+    }
+
+    /// `operand` and every operand its value is computed from, through the
+    /// bases of field accesses and the [`value_inputs`] of definitions
+    pub fn slice<'d>(
+        &'d self,
+        operand: &'a Operand,
+    ) -> Slice<'d, 'a, impl FnMut(&'a Instruction) -> bool> {
+        self.slice_through(operand, |_| true)
+    }
+
+    /// Variable, field and storage item names in the slice of `operand`
+    pub fn names(&self, operand: &'a Operand) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        for operand in self.slice(operand) {
+            match operand {
+                Operand::Var(var) => {
+                    names.insert(var.name.clone());
+                    if let Some(Instruction::StorageLoad { storage_item, .. }) = self.get(var) {
+                        names.insert(storage_item.clone());
+                    }
+                }
+                Operand::FieldAccess { field, .. } => {
+                    names.insert(field.clone());
+                }
+                Operand::Literal(_) => {}
+            }
+        }
+        names
+    }
+
+    /// [`Defs::slice`] following only the definitions `follow` accepts; the
+    /// variables they define are still yielded
+    pub fn slice_through<'d, F: FnMut(&'a Instruction) -> bool>(
+        &'d self,
+        operand: &'a Operand,
+        follow: F,
+    ) -> Slice<'d, 'a, F> {
+        Slice {
+            defs: self,
+            queue: vec![operand],
+            visited: HashSet::new(),
+            follow,
+        }
+    }
+}
+
+/// Iterator over a backward slice, each variable yielded once; see [`Defs::slice`]
+pub struct Slice<'d, 'a, F> {
+    defs: &'d Defs<'a>,
+    queue: Vec<&'a Operand>,
+    visited: HashSet<&'a SsaVar>,
+    follow: F,
+}
+
+impl<'a, F: FnMut(&'a Instruction) -> bool> Iterator for Slice<'_, 'a, F> {
+    type Item = &'a Operand;
+
+    fn next(&mut self) -> Option<&'a Operand> {
+        while let Some(operand) = self.queue.pop() {
+            match operand {
+                Operand::Literal(_) => {}
+                Operand::FieldAccess { base, .. } => self.queue.push(base),
+                Operand::Var(var) => {
+                    if !self.visited.insert(var) {
+                        continue;
+                    }
+                    if let Some(inst) = self.defs.get(var).filter(|inst| (self.follow)(inst)) {
+                        self.queue.extend(value_inputs(inst));
+                    }
+                }
+            }
+            return Some(operand);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::ir::builder::IrBuilder;
+    use std::path::PathBuf;
+
+    fn names<'a>(slice: impl Iterator<Item = &'a Operand>) -> Vec<String> {
+        let mut names: Vec<String> = slice
+            .filter_map(|op| match op {
+                Operand::Var(var) if !var.name.starts_with('_') => Some(var.name.clone()),
+                Operand::FieldAccess { field, .. } => Some(field.clone()),
+                _ => None,
+            })
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    #[test]
+    fn test_slice_follows_definitions() {
+        let source = r#"
+            fn execute(deps: DepsMut, msg: Msg) {
+                let balance = BALANCES.load(deps.storage, &msg.owner)?;
+                let fee = msg.amount * RATE;
+                let net = balance - fee;
+                let capped = net.min(cap);
+            }
+        "#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let func = &ir.functions[0];
+        let defs = Defs::new(func);
+        let capped = func
+            .cfg
+            .blocks
+            .iter()
+            .flat_map(|b| &b.instructions)
+            .filter_map(instruction_def)
+            .find(|var| var.name == "capped")
+            .map(|var| Operand::Var(var.clone()))
+            .unwrap();
+
+        assert_eq!(
+            names(defs.slice(&capped)),
+            ["RATE", "amount", "balance", "cap", "capped", "fee", "msg", "net"]
+        );
+        // Stopping at the subtraction keeps what it is computed from out
+        let through = defs.slice_through(&capped, |inst| {
+            !matches!(inst, Instruction::BinaryOp { .. })
+        });
+        assert_eq!(names(through), ["cap", "capped", "net"]);
+        assert!(defs.names(&capped).contains("BALANCES"));
+    }
+}
@@ -0,0 +1,269 @@
+use serde::{Deserialize, Serialize};
+
+use crate::auth::AuthRegistry;
+
+use super::cfg::BlockId;
+use super::instruction::{Instruction, Operand};
+use super::types::FunctionIr;
+
+/// Macros that abort the handler when their condition does not hold
+const ASSERT_MACROS: &[&str] = &[
+    "ensure",
+    "ensure_eq",
+    "ensure_ne",
+    "assert",
+    "assert_eq",
+    "assert_ne",
+];
+
+/// A storage load or store of a named state item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageAccess {
+    pub item: String,
+    pub key: Option<Operand>,
+    pub block: BlockId,
+}
+
+/// A chain message constructed in the function (`BankMsg::Send { .. }`, ...)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmittedMessage {
+    pub msg_type: String,
+    pub fields: Vec<(String, Operand)>,
+    pub block: BlockId,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GuardKind {
+    /// Compares the message sender against an expected address
+    Sender,
+    /// `ensure!`-style assertion or an `if` whose branch returns an error
+    Condition,
+}
+
+/// A check that aborts execution when it fails
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Guard {
+    pub kind: GuardKind,
+    pub operands: Vec<Operand>,
+    pub block: BlockId,
+}
+
+/// Per-function effects and guards, computed once from the IR
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FunctionSummary {
+    pub storage_reads: Vec<StorageAccess>,
+    pub storage_writes: Vec<StorageAccess>,
+    pub emitted_messages: Vec<EmittedMessage>,
+    pub guards: Vec<Guard>,
+}
+
+impl FunctionSummary {
+    pub fn from_ir(func: &FunctionIr) -> Self {
+        let mut summary = Self::default();
+
+        for block in &func.cfg.blocks {
+            for inst in &block.instructions {
+                match inst {
+                    Instruction::StorageLoad {
+                        storage_item, key, ..
+                    } => summary.storage_reads.push(StorageAccess {
+                        item: storage_item.clone(),
+                        key: key.clone(),
+                        block: block.id,
+                    }),
+                    Instruction::StorageStore {
+                        storage_item, key, ..
+                    } => summary.storage_writes.push(StorageAccess {
+                        item: storage_item.clone(),
+                        key: key.clone(),
+                        block: block.id,
+                    }),
+                    Instruction::SendMsg { msg_type, fields } => {
+                        summary.emitted_messages.push(EmittedMessage {
+                            msg_type: msg_type.clone(),
+                            fields: fields.clone(),
+                            block: block.id,
+                        })
+                    }
+                    Instruction::CheckSender {
+                        sender_var,
+                        expected,
+                    } => summary.guards.push(Guard {
+                        kind: GuardKind::Sender,
+                        operands: vec![sender_var.clone(), expected.clone()],
+                        block: block.id,
+                    }),
+                    Instruction::Call {
+                        func: callee, args, ..
+                    } => {
+                        let is_assert = callee
+                            .strip_prefix("macro!")
+                            .is_some_and(|m| ASSERT_MACROS.contains(&m));
+                        if is_assert && !args.is_empty() {
+                            summary.guards.push(Guard {
+                                kind: GuardKind::Condition,
+                                operands: args.clone(),
+                                block: block.id,
+                            });
+                        }
+                    }
+                    Instruction::Branch {
+                        condition,
+                        true_block,
+                        false_block,
+                    } => {
+                        let aborts = |b: &BlockId| {
+                            func.cfg.blocks[*b]
+                                .instructions
+                                .iter()
+                                .any(|i| matches!(i, Instruction::ErrorReturn { .. }))
+                        };
+                        if aborts(true_block) || aborts(false_block) {
+                            summary.guards.push(Guard {
+                                kind: GuardKind::Condition,
+                                operands: vec![condition.clone()],
+                                block: block.id,
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        summary
+    }
+
+    /// Record calls to fund-moving helpers (`[taint] sinks`) in `func` as
+    /// `BankMsg::Send` messages, their arguments as positional fields
+    pub fn sending(mut self, func: &FunctionIr, sinks: &[String]) -> Self {
+        if sinks.is_empty() {
+            return self;
+        }
+        for block in &func.cfg.blocks {
+            for inst in &block.instructions {
+                let (name, args) = match inst {
+                    Instruction::Call { func, args, .. } => {
+                        (func.rsplit("::").next().unwrap_or(func), args)
+                    }
+                    Instruction::MethodCall { method, args, .. } => (method.as_str(), args),
+                    _ => continue,
+                };
+                if sinks.iter().any(|s| s == name) {
+                    self.emitted_messages.push(EmittedMessage {
+                        msg_type: "BankMsg::Send".to_string(),
+                        fields: args
+                            .iter()
+                            .enumerate()
+                            .map(|(i, arg)| (format!("arg{i}"), arg.clone()))
+                            .collect(),
+                        block: block.id,
+                    });
+                }
+            }
+        }
+        self
+    }
+
+    /// Record calls to access-control helpers in `func` as sender guards
+    pub fn trusting(mut self, func: &FunctionIr, auth: &AuthRegistry) -> Self {
+        for block in &func.cfg.blocks {
+            for inst in &block.instructions {
+                let (is_helper, args) = match inst {
+                    Instruction::Call { func, args, .. } => {
+                        (auth.is_helper_call(func), args.clone())
+                    }
+                    Instruction::MethodCall {
+                        receiver,
+                        method,
+                        args,
+                        ..
+                    } => (
+                        auth.is_helper_method(method),
+                        std::iter::once(receiver).chain(args).cloned().collect(),
+                    ),
+                    _ => continue,
+                };
+                if is_helper {
+                    self.guards.push(Guard {
+                        kind: GuardKind::Sender,
+                        operands: args,
+                        block: block.id,
+                    });
+                }
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::ir::builder::IrBuilder;
+    use std::path::PathBuf;
+
+    fn summarize(source: &str) -> FunctionSummary {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        FunctionSummary::from_ir(&ir.functions[0])
+    }
+
+    #[test]
+    fn test_summary_collects_effects_and_guards() {
+        let summary = summarize(
+            r#"
+            fn execute_withdraw(deps: DepsMut, info: MessageInfo, amount: Uint128) -> Result<Response, ContractError> {
+                let config = CONFIG.load(deps.storage)?;
+                if info.sender != config.owner {
+                    return Err(ContractError::Unauthorized {});
+                }
+                ensure!(amount > Uint128::zero(), ContractError::ZeroAmount {});
+                BALANCE.save(deps.storage, &amount)?;
+                let msg = BankMsg::Send { to_address: info.sender.to_string(), amount: coins(1, "uatom") };
+                Ok(Response::new().add_message(msg))
+            }
+            "#,
+        );
+
+        assert_eq!(summary.storage_reads.len(), 1);
+        assert_eq!(summary.storage_reads[0].item, "CONFIG");
+        assert_eq!(summary.storage_writes.len(), 1);
+        assert_eq!(summary.storage_writes[0].item, "BALANCE");
+        assert_eq!(summary.emitted_messages.len(), 1);
+        assert_eq!(summary.emitted_messages[0].msg_type, "BankMsg::Send");
+        assert_eq!(summary.emitted_messages[0].fields[0].0, "to_address");
+
+        let kinds: Vec<&GuardKind> = summary.guards.iter().map(|g| &g.kind).collect();
+        assert_eq!(
+            kinds.iter().filter(|k| ***k == GuardKind::Sender).count(),
+            1
+        );
+        // The `if ... return Err` branch and the ensure! both count as conditions
+        assert_eq!(
+            kinds
+                .iter()
+                .filter(|k| ***k == GuardKind::Condition)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_unguarded_function_has_no_guards() {
+        let summary = summarize(
+            r#"
+            fn set(deps: DepsMut, value: u64) -> StdResult<Response> {
+                if value > 10 {
+                    VALUE.save(deps.storage, &value)?;
+                }
+                Ok(Response::new())
+            }
+            "#,
+        );
+        assert!(summary.guards.is_empty());
+        assert_eq!(summary.storage_writes.len(), 1);
+    }
+}
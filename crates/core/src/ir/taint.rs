@@ -0,0 +1,190 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::cfg::{instruction_def, instruction_uses};
+use super::instruction::{Instruction, Operand, SsaVar};
+use super::types::FunctionIr;
+
+/// Forward taint propagation over a function's instructions.
+/// Starting from `sources`, any variable defined from a tainted operand becomes
+/// tainted. Values loaded from storage are never tainted: they were written by
+/// an earlier, separately checked transaction.
+pub fn propagate_taint(func: &FunctionIr, sources: &HashSet<SsaVar>) -> HashSet<SsaVar> {
+    let mut tainted = sources.clone();
+    let instructions: Vec<&Instruction> = func
+        .cfg
+        .blocks
+        .iter()
+        .flat_map(|b| &b.instructions)
+        .collect();
+
+    // Loops make uses appear before defs in block order, so iterate to a fixpoint
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for inst in &instructions {
+            let Some(dest) = instruction_def(inst) else {
+                continue;
+            };
+            if tainted.contains(dest) || matches!(inst, Instruction::StorageLoad { .. }) {
+                continue;
+            }
+            if instruction_uses(inst).iter().any(|v| tainted.contains(*v)) {
+                tainted.insert(dest.clone());
+                changed = true;
+            }
+        }
+    }
+    tainted
+}
+
+/// Results of calls to `helpers` (by last path segment, or method name) in
+/// `func`, e.g. a project's message-parsing helpers configured as sources
+pub fn helper_results(func: &FunctionIr, helpers: &[String]) -> HashSet<SsaVar> {
+    let is_helper = |name: &str| helpers.iter().any(|h| h == name);
+    func.cfg
+        .blocks
+        .iter()
+        .flat_map(|b| &b.instructions)
+        .filter_map(|inst| match inst {
+            Instruction::Call {
+                dest: Some(dest),
+                func,
+                ..
+            } if is_helper(func.rsplit("::").next().unwrap_or(func)) => Some(dest.clone()),
+            Instruction::MethodCall {
+                dest: Some(dest),
+                method,
+                ..
+            } if is_helper(method) => Some(dest.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Shortest chain of variables through which taint from `sources` reaches
+/// `sink`, source first. Follows the same edges as [`propagate_taint`], so it
+/// is found exactly when `sink` is tainted.
+pub fn taint_path(
+    func: &FunctionIr,
+    sources: &HashSet<SsaVar>,
+    sink: &SsaVar,
+) -> Option<Vec<SsaVar>> {
+    let defs: HashMap<&SsaVar, &Instruction> = func
+        .cfg
+        .blocks
+        .iter()
+        .flat_map(|b| &b.instructions)
+        .filter_map(|inst| instruction_def(inst).map(|d| (d, inst)))
+        .collect();
+
+    let mut came_from: HashMap<&SsaVar, Option<&SsaVar>> = HashMap::from([(sink, None)]);
+    let mut queue = VecDeque::from([sink]);
+    while let Some(var) = queue.pop_front() {
+        if sources.contains(var) {
+            let mut path = vec![var.clone()];
+            let mut current = var;
+            while let Some(Some(next)) = came_from.get(current) {
+                path.push((*next).clone());
+                current = next;
+            }
+            return Some(path);
+        }
+        let Some(inst) = defs.get(var) else {
+            continue;
+        };
+        if matches!(inst, Instruction::StorageLoad { .. }) {
+            continue;
+        }
+        for used in instruction_uses(inst) {
+            if !came_from.contains_key(used) {
+                came_from.insert(used, Some(var));
+                queue.push_back(used);
+            }
+        }
+    }
+    None
+}
+
+/// Check whether an operand reads a tainted variable
+pub fn operand_tainted(operand: &Operand, tainted: &HashSet<SsaVar>) -> bool {
+    match operand {
+        Operand::Var(v) => tainted.contains(v),
+        Operand::FieldAccess { base, .. } => operand_tainted(base, tainted),
+        Operand::Literal(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::ir::builder::IrBuilder;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_taint_flows_through_calls_but_not_storage() {
+        let source = r#"
+            fn handle(deps: DepsMut, items: Vec<Item>) {
+                let iter = items.iter();
+                let stored = CONFIG.load(deps.storage);
+                let combined = merge(iter, stored);
+            }
+        "#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let func = &ir.functions[0];
+
+        let sources: HashSet<SsaVar> = func
+            .params
+            .iter()
+            .filter(|p| p.name == "items")
+            .cloned()
+            .collect();
+        let tainted = propagate_taint(func, &sources);
+        let names: HashSet<&str> = tainted.iter().map(|v| v.name.as_str()).collect();
+        assert!(names.contains("iter"));
+        assert!(names.contains("combined"));
+        assert!(!names.contains("stored"));
+    }
+
+    #[test]
+    fn test_taint_path_leads_from_source_to_sink() {
+        let source = r#"
+            fn handle(deps: DepsMut, items: Vec<Item>) {
+                let iter = items.iter();
+                let stored = CONFIG.load(deps.storage);
+                let combined = merge(iter, stored);
+            }
+        "#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let func = &ir.functions[0];
+        let var = |name: &str| {
+            func.cfg
+                .blocks
+                .iter()
+                .flat_map(|b| &b.instructions)
+                .filter_map(instruction_def)
+                .find(|v| v.name == name)
+                .unwrap()
+                .clone()
+        };
+
+        let sources: HashSet<SsaVar> = func
+            .params
+            .iter()
+            .filter(|p| p.name == "items")
+            .cloned()
+            .collect();
+        let path = taint_path(func, &sources, &var("combined")).unwrap();
+        let names: Vec<&str> = path
+            .iter()
+            .map(|v| v.name.as_str())
+            .filter(|n| !n.starts_with('_'))
+            .collect();
+        assert_eq!(names, vec!["items", "iter", "combined"]);
+        assert!(taint_path(func, &sources, &var("stored")).is_none());
+    }
+}
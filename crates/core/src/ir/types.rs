@@ -2,11 +2,12 @@ use serde::{Deserialize, Serialize};
 
 use crate::ast::SourceSpan;
 
+use super::analyses::FunctionAnalyses;
 use super::cfg::Cfg;
 use super::instruction::SsaVar;
 
 /// IR representation of an entire contract
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContractIr {
     pub functions: Vec<FunctionIr>,
     pub entry_points: Vec<String>,
@@ -38,11 +39,15 @@ impl Default for ContractIr {
 }
 
 /// IR for a single function
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionIr {
     pub name: String,
     pub params: Vec<SsaVar>,
     pub cfg: Cfg,
     pub is_entry_point: bool,
     pub source_span: SourceSpan,
+    /// File-local analyses restored from the cache, reused instead of
+    /// recomputing them. Cached separately, see `CachedFileArtifact`
+    #[serde(skip)]
+    pub analyses: Option<FunctionAnalyses>,
 }
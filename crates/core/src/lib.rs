@@ -1,7 +1,31 @@
+pub mod advisories;
 pub mod ast;
+pub mod audited;
+pub mod auth;
+pub mod baseline;
+pub mod blind_spots;
 pub mod cache;
+pub mod codes;
+pub mod confidence;
 pub mod config;
+pub mod correlation;
 pub mod detector;
+pub mod drift;
+pub mod examples;
 pub mod finding;
+pub mod fix;
+pub mod fuzz;
+pub mod invariants;
 pub mod ir;
+pub mod owners;
+pub mod packs;
+pub mod pipeline;
+pub mod ranking;
 pub mod report;
+pub mod repro;
+pub mod roles;
+pub mod schema;
+pub mod snippets;
+pub mod storage_metrics;
+pub mod surface;
+pub mod trace;
@@ -0,0 +1,109 @@
+//! `CODEOWNERS`-style routing of findings to the team that owns the file.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// One `[[owners]]` rule: files matching `pattern` belong to `team`.
+///
+/// Patterns follow `CODEOWNERS`: a leading `/` anchors the pattern to the start
+/// of the reported path, otherwise it may match from any directory; a trailing
+/// `/` covers everything below that directory. When several rules match, the
+/// last one wins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnerRule {
+    pub pattern: String,
+    pub team: String,
+}
+
+/// `*` and `?` stay within one path component, as in `CODEOWNERS`
+const OWNER_MATCH: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+impl OwnerRule {
+    fn matches(&self, path: &str) -> bool {
+        let (pattern, anchored) = match self.pattern.strip_prefix('/') {
+            Some(rest) => (rest, true),
+            None => (self.pattern.as_str(), false),
+        };
+        let pattern = if pattern.ends_with('/') {
+            format!("{pattern}**")
+        } else {
+            pattern.to_string()
+        };
+        let Ok(pattern) = glob::Pattern::new(&pattern) else {
+            return false;
+        };
+        if anchored {
+            return pattern.matches_with(path, OWNER_MATCH);
+        }
+        // Unanchored: try every suffix that starts at a component boundary
+        std::iter::once(path)
+            .chain(path.match_indices('/').map(|(i, _)| &path[i + 1..]))
+            .any(|suffix| pattern.matches_with(suffix, OWNER_MATCH))
+    }
+}
+
+/// Team owning `file` according to `rules`, if any rule matches
+pub fn owner_of<'a>(rules: &'a [OwnerRule], file: &Path) -> Option<&'a str> {
+    let path = crate::ast::discovery::normalize_separators(file);
+    let path = path.trim_start_matches("./");
+    rules
+        .iter()
+        .rev()
+        .find(|rule| rule.matches(path))
+        .map(|rule| rule.team.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, team: &str) -> OwnerRule {
+        OwnerRule {
+            pattern: pattern.to_string(),
+            team: team.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_last_matching_rule_wins() {
+        let rules = vec![
+            rule("*.rs", "@org/platform"),
+            rule("contracts/vault/", "@org/vault"),
+            rule("/contracts/vault/src/migrate.rs", "@org/upgrades"),
+        ];
+        assert_eq!(
+            owner_of(&rules, Path::new("contracts/vault/src/contract.rs")),
+            Some("@org/vault")
+        );
+        assert_eq!(
+            owner_of(&rules, Path::new("./contracts/vault/src/migrate.rs")),
+            Some("@org/upgrades")
+        );
+        assert_eq!(
+            owner_of(&rules, Path::new("contracts/dex/src/lib.rs")),
+            Some("@org/platform")
+        );
+        assert_eq!(owner_of(&rules, Path::new("README.md")), None);
+    }
+
+    #[test]
+    fn test_unanchored_patterns_match_below_any_prefix() {
+        let rules = vec![
+            rule("contracts/vault/", "@org/vault"),
+            rule("/src/*.rs", "@org/root"),
+        ];
+        assert_eq!(
+            owner_of(&rules, Path::new("/work/repo/contracts/vault/src/lib.rs")),
+            Some("@org/vault")
+        );
+        // Anchored patterns only match from the start, and `*` stays in one component
+        assert_eq!(owner_of(&rules, Path::new("/work/repo/src/lib.rs")), None);
+        assert_eq!(owner_of(&rules, Path::new("src/state/mod.rs")), None);
+        assert_eq!(owner_of(&rules, Path::new("src/lib.rs")), Some("@org/root"));
+    }
+}
@@ -0,0 +1,237 @@
+//! Rule packs: named, curated bundles of detectors with option presets,
+//! selected with `--packs defi,ibc` or `packs = [...]` under `[global]`.
+//! Selecting packs runs the union of their detectors, whatever their maturity,
+//! as naming them with `--detectors` would.
+
+use anyhow::bail;
+
+#[derive(Debug)]
+pub struct RulePack {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Detector names; `*` stands for every detector, custom ones included
+    pub detectors: &'static [&'static str],
+    /// Option presets as `[<detector>]` tables, beneath the config's own
+    /// `[detectors.<detector>]` options
+    pub presets: &'static str,
+}
+
+/// Built-in packs, in the order `list` shows them
+pub const PACKS: &[RulePack] = &[
+    RulePack {
+        name: "core",
+        description: "Stable, high-signal checks for any contract",
+        detectors: &[
+            "missing-addr-validate",
+            "missing-access-control",
+            "unbounded-iteration",
+            "storage-key-collision",
+            "unsafe-unwrap",
+            "arithmetic-overflow",
+            "missing-error-propagation",
+            "submessage-reply-unvalidated",
+            "nondeterministic-iteration",
+            "incorrect-permission-hierarchy",
+            "missing-funds-validation",
+            "uninitialized-state-access",
+            "missing-migration-version",
+            "instantiate-msg-validation",
+            "entry-point-signature",
+            "reply-result-unchecked",
+            "stored-type-serde",
+            "query-response-mismatch",
+            "test-code-in-contract",
+            "floating-point",
+        ],
+        presets: "",
+    },
+    RulePack {
+        name: "defi",
+        description: "Funds, fees, token receipts and arithmetic of vaults and DEXes",
+        detectors: &[
+            "missing-funds-validation",
+            "arithmetic-overflow",
+            "unchecked-fee-bounds",
+            "unverified-cw20-receive",
+            "unguarded-parameter-update",
+            "pause-consistency",
+            "rounding-direction",
+            "unchecked-subtraction",
+            "share-inflation",
+            "oracle-staleness",
+            "missing-status-check",
+            "invariant-drift",
+            "query-toctou",
+            "reentrancy-state-after-msg",
+        ],
+        presets: r#"
+[unchecked-fee-bounds]
+field_patterns = ["fee", "commission", "bps", "rate", "spread", "slippage"]
+
+[unchecked-subtraction]
+field_patterns = [
+    "balance", "deposit", "stake", "share", "supply", "locked", "escrow",
+    "allowance", "collateral", "reserve", "liquidity", "debt", "borrow", "principal",
+]
+"#,
+    },
+    RulePack {
+        name: "nft",
+        description: "Ownership, approvals and token listings of cw721-style contracts",
+        detectors: &[
+            "missing-addr-validate",
+            "missing-access-control",
+            "addr-stored-as-string",
+            "role-check-bypass",
+            "unguarded-parameter-update",
+            "unbounded-iteration",
+            "pagination-bound-order",
+            "storage-collect-in-execute",
+        ],
+        presets: "",
+    },
+    RulePack {
+        name: "ibc",
+        description: "Replies, submessages and deterministic handling of cross-chain packets",
+        detectors: &[
+            "submessage-reply-unvalidated",
+            "reply-result-unchecked",
+            "missing-error-propagation",
+            "nondeterministic-iteration",
+            "entry-point-signature",
+            "unbounded-message-loop",
+            "unbounded-message-field",
+            "foreign-address-prefix",
+        ],
+        presets: "",
+    },
+    RulePack {
+        name: "governance",
+        description: "Voting power, proposal execution and admin controls of DAOs",
+        detectors: &[
+            "voting-power-snapshot",
+            "missing-access-control",
+            "role-check-bypass",
+            "incorrect-permission-hierarchy",
+            "unguarded-parameter-update",
+            "unbounded-iteration",
+            "submessage-reply-unvalidated",
+            "missing-status-check",
+        ],
+        presets: "",
+    },
+    RulePack {
+        name: "staking",
+        description: "Bonding, unbonding periods, claims and reward accounting",
+        detectors: &[
+            "unchecked-claim-maturity",
+            "invariant-drift",
+            "missing-funds-validation",
+            "unchecked-subtraction",
+            "arithmetic-overflow",
+            "rounding-direction",
+            "share-inflation",
+            "unbounded-iteration",
+            "missing-access-control",
+        ],
+        presets: r#"
+[unchecked-subtraction]
+field_patterns = ["stake", "bond", "unbond", "delegation", "reward", "claim", "share", "balance"]
+"#,
+    },
+    RulePack {
+        name: "gas",
+        description: "Unbounded loops, loads and payloads that can exhaust gas",
+        detectors: &[
+            "unbounded-iteration",
+            "storage-collect-in-execute",
+            "repeated-storage-load",
+            "unbounded-message-loop",
+            "unbounded-message-field",
+            "pagination-bound-order",
+            "complexity-metrics",
+            "dead-code",
+        ],
+        presets: r#"
+[storage-collect-in-execute]
+max_collect_limit = 30
+"#,
+    },
+    RulePack {
+        name: "strict",
+        description: "Every detector, Experimental ones included, with no exemptions",
+        detectors: &["*"],
+        presets: r#"
+[missing-access-control]
+permissionless = []
+
+[storage-collect-in-execute]
+max_collect_limit = 30
+"#,
+    },
+];
+
+impl RulePack {
+    /// Whether the pack runs the detector `name`
+    pub fn includes(&self, name: &str) -> bool {
+        self.detectors.iter().any(|&d| d == "*" || d == name)
+    }
+
+    /// Preset of `key` for `detector`, if the pack sets one
+    pub fn preset(&self, detector: &str, key: &str) -> Option<toml::Value> {
+        let presets: toml::Table = toml::from_str(self.presets).ok()?;
+        presets.get(detector)?.get(key).cloned()
+    }
+}
+
+/// The packs called `names`, in order; fails on an unknown name
+pub fn resolve(names: &[String]) -> anyhow::Result<Vec<&'static RulePack>> {
+    names
+        .iter()
+        .map(
+            |name| match PACKS.iter().find(|p| p.name == name.as_str()) {
+                Some(pack) => Ok(pack),
+                None => {
+                    let known: Vec<&str> = PACKS.iter().map(|p| p.name).collect();
+                    bail!(
+                        "Unknown rule pack `{name}` (available: {})",
+                        known.join(", ")
+                    )
+                }
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes;
+
+    #[test]
+    fn test_packs_name_known_detectors() {
+        for pack in PACKS {
+            for name in pack.detectors.iter().filter(|&&d| d != "*") {
+                assert!(codes::code_of(name).is_some(), "{}: {name}", pack.name);
+            }
+            let presets: toml::Table = toml::from_str(pack.presets).unwrap();
+            for detector in presets.keys() {
+                assert!(pack.includes(detector), "{}: {detector}", pack.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        let packs = resolve(&["defi".to_string(), "strict".to_string()]).unwrap();
+        assert!(packs[0].includes("unchecked-fee-bounds"));
+        assert!(!packs[0].includes("dead-code"));
+        assert!(packs[1].includes("my-custom-detector"));
+        assert_eq!(
+            packs[1].preset("storage-collect-in-execute", "max_collect_limit"),
+            Some(toml::Value::Integer(30))
+        );
+        let err = resolve(&["nfts".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("Unknown rule pack `nfts`"));
+    }
+}
@@ -0,0 +1,210 @@
+//! The analysis pipeline shared by every front end: detector selection, then
+//! suppression, re-tiering, correlation and the annotations and filters that
+//! turn raw detector output into a report. Detectors are passed in, since
+//! they live in a crate of their own.
+
+use std::path::PathBuf;
+
+use crate::ast::CrateAnalysis;
+use crate::config::{self, Config};
+use crate::detector::{AnalysisContext, Detector, DetectorRegistry};
+use crate::finding::{Finding, Severity};
+use crate::report::{self, AnalysisError, AnalysisReport};
+use crate::{
+    advisories, audited, blind_spots, codes, confidence, correlation, ranking, schema, surface,
+};
+
+/// Detector selection and finding filters for one analysis
+pub struct AnalysisOptions {
+    /// Run only these detectors; naming one runs it whatever its maturity
+    pub detectors: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub min_severity: Severity,
+    pub experimental: bool,
+    /// Directory of `cosmwasm-schema` JSON files to check message types against
+    pub schema_dir: Option<PathBuf>,
+    /// Order findings within each severity by exploitability and set their `rank`
+    pub rank: bool,
+    /// Report only findings inside this function and the functions it calls
+    pub function: Option<String>,
+    /// Drop findings in functions no entry point reaches
+    pub only_reachable: bool,
+    /// Report only findings in the Cargo package with this name
+    pub filter_crate: Option<String>,
+}
+
+/// Detectors of `all_dets` to run after applying config, selection and
+/// maturity filters
+pub fn select_detectors(
+    mut all_dets: Vec<Box<dyn Detector>>,
+    config: &Config,
+    options: &AnalysisOptions,
+) -> Vec<Box<dyn Detector>> {
+    // Apply config-based detector filtering
+    all_dets.retain(|d| config.is_detector_enabled(d.name()));
+
+    // Names and codes (`CWG0005`) select the same detector; rule packs select
+    // their detectors likewise, unless detectors are named
+    let packs = config.packs();
+    if let Some(ref names) = options.detectors {
+        all_dets.retain(|d| names.iter().any(|n| codes::resolve(n) == d.name()));
+    } else if !packs.is_empty() {
+        all_dets.retain(|d| packs.iter().any(|pack| pack.includes(d.name())));
+    } else {
+        all_dets.retain(|d| config.allows_maturity(d.name(), d.maturity(), options.experimental));
+    }
+    if let Some(ref names) = options.exclude {
+        all_dets.retain(|d| !names.iter().any(|n| codes::resolve(n) == d.name()));
+    }
+    all_dets
+}
+
+/// Run the detectors of `all_dets` selected for an analyzed crate and build
+/// the filtered report
+pub fn build_report(
+    analysis: &CrateAnalysis,
+    config: &Config,
+    options: &AnalysisOptions,
+    all_dets: Vec<Box<dyn Detector>>,
+) -> AnalysisReport {
+    let mut registry = DetectorRegistry::new();
+    registry.register_all(select_detectors(all_dets, config, options));
+
+    let ctx = AnalysisContext::new(&analysis.contract, &analysis.ir, &analysis.source_map)
+        .with_config(config);
+    let mut all_findings = registry.run_all(&ctx);
+    let mut errors = analysis.errors.clone();
+
+    // Compare message types with the checked-in schema
+    if let Some(dir) = &options.schema_dir {
+        match schema::check_schema_drift(&analysis.contract, dir) {
+            Ok(findings) => all_findings.extend(findings),
+            Err(e) => errors.push(AnalysisError {
+                file: dir.clone(),
+                message: format!("schema check failed: {e:#}"),
+                line: None,
+                column: None,
+            }),
+        }
+    }
+
+    // Enrich findings with source snippets, limited and redacted per config,
+    // and byte offsets
+    for finding in &mut all_findings {
+        for loc in &mut finding.locations {
+            if let Some(source) = analysis.source_map.get(&loc.file) {
+                if loc.snippet.is_none() {
+                    loc.snippet = get_snippet(source, loc.start_line, loc.end_line);
+                }
+                loc.resolve_byte_range(source);
+            }
+            loc.snippet = loc
+                .snippet
+                .as_deref()
+                .and_then(|s| config.snippets.apply(s));
+        }
+        for edit in finding.fix.iter_mut().flat_map(|fix| &mut fix.edits) {
+            if let Some(source) = analysis.source_map.get(&edit.location.file) {
+                edit.location.resolve_byte_range(source);
+            }
+        }
+    }
+
+    // Apply inline suppressions
+    let inline_suppressions = config::parse_inline_suppressions(&analysis.source_map);
+    all_findings = config::apply_suppressions(all_findings, config, &inline_suppressions);
+
+    // Re-tier findings per `[detectors.<name>] severity`
+    for finding in &mut all_findings {
+        if let Some(severity) = config.severity_override(&finding.detector_name) {
+            finding.severity = severity;
+        }
+    }
+
+    // Escalate findings that combine into something worse in one handler
+    correlation::escalate(&mut all_findings, &ctx, &config.correlations);
+
+    // Filter by severity
+    all_findings.retain(|f| f.severity <= options.min_severity);
+
+    // Scope to one function and its callees
+    if let Some(function) = &options.function {
+        let scope = ctx.call_graph().reachable_from(&[function.as_str()]);
+        all_findings.retain(|f| {
+            ranking::enclosing_function(f, &ctx).is_some_and(|name| scope.contains(name))
+        });
+    }
+
+    // Label findings in code copied unchanged from audited upstream releases
+    audited::annotate(&mut all_findings, &ctx);
+    if config.suppressions.hide_inherited {
+        all_findings.retain(|f| f.inherited_from.is_none());
+    }
+
+    // Link findings to published advisories about their vulnerability class
+    advisories::annotate(&mut all_findings);
+
+    // Note which entry points reach each finding
+    surface::annotate_reachability(&mut all_findings, &ctx);
+    if options.only_reachable {
+        all_findings.retain(|f| f.reachable_from.as_ref().is_none_or(|e| !e.is_empty()));
+    }
+
+    // Raise or lower confidence from corroborating signals
+    confidence::recalibrate(&mut all_findings, &ctx);
+
+    if options.rank {
+        ranking::rank_findings(&mut all_findings, &ctx);
+    }
+
+    // Route findings to owning teams, note the crate they are in, map their
+    // severity to the organization's levels and give them stable ids
+    let files: Vec<PathBuf> = analysis.source_map.keys().cloned().collect();
+    let packages = report::packages::group_by_package(&files);
+    assign_owners(&mut all_findings, config);
+    for finding in &mut all_findings {
+        let file = finding.locations.first().map(|loc| loc.file.as_path());
+        finding.crate_name = file
+            .and_then(|f| report::packages::package_of(&packages, f))
+            .map(|i| packages[i].name.clone());
+        finding.severity_level = config
+            .severity_levels
+            .level_of(&finding.severity)
+            .map(String::from);
+        finding.id = Some(finding.stable_id());
+    }
+    if let Some(name) = &options.filter_crate {
+        all_findings.retain(|f| f.crate_name.as_deref().is_some_and(|c| same_crate(c, name)));
+    }
+
+    let mut report = AnalysisReport::from_findings(files, all_findings);
+    report.analysis_errors = errors;
+    report.analysis_warnings = blind_spots::collect(&ctx);
+    report.packages = packages;
+    report
+}
+
+/// Route each finding to the team owning its primary location. Anchored
+/// `[[owners]]` patterns match from the start of the reported path, so call
+/// this again once paths are rewritten.
+pub fn assign_owners(findings: &mut [Finding], config: &Config) {
+    for finding in findings {
+        let file = finding.locations.first().map(|loc| loc.file.as_path());
+        finding.owner = file.and_then(|f| config.owner_of(f)).map(String::from);
+    }
+}
+
+/// Cargo treats `-` and `_` in package names alike
+pub fn same_crate(a: &str, b: &str) -> bool {
+    a.replace('-', "_") == b.replace('-', "_")
+}
+
+fn get_snippet(source: &str, start_line: usize, end_line: usize) -> Option<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let start = start_line.saturating_sub(1);
+    let end = end_line.min(lines.len());
+    if start >= lines.len() {
+        return None;
+    }
+    Some(lines[start..end].join("\n"))
+}
@@ -0,0 +1,193 @@
+//! Exploitability ranking: orders findings within a severity so the ones an
+//! attacker can most plausibly reach and profit from come first.
+
+use std::cmp::Reverse;
+use std::collections::{HashSet, VecDeque};
+
+use crate::ast::{EntryPointKind, FunctionInfo};
+use crate::detector::AnalysisContext;
+use crate::finding::Finding;
+use crate::ir::GuardKind;
+
+/// Parameter types supplied by the chain rather than by the caller
+const TRUSTED_PARAM_TYPES: &[&str] = &["Deps", "DepsMut", "Env", "Storage", "Api", "Querier"];
+
+/// Exploitability signals of the function a finding sits in
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Exploitability {
+    /// Reachable from a user-callable entry point without passing a sender check
+    pub unauthenticated: bool,
+    /// Takes caller-controlled parameters (message fields, `MessageInfo`)
+    pub attacker_input: bool,
+    /// Sends chain messages (funds, contract calls), directly or via callees
+    pub funds_impact: bool,
+    /// Writes contract state, directly or via callees
+    pub state_impact: bool,
+}
+
+impl Exploitability {
+    pub fn score(&self) -> u32 {
+        4 * u32::from(self.unauthenticated)
+            + 2 * u32::from(self.attacker_input)
+            + 2 * u32::from(self.funds_impact)
+            + u32::from(self.state_impact)
+    }
+}
+
+/// Sort findings by severity, then by descending exploitability score, and
+/// number them in that order. Ties keep detector order.
+pub fn rank_findings(findings: &mut [Finding], ctx: &AnalysisContext) {
+    let unguarded = unguarded_reach(ctx);
+    findings.sort_by_cached_key(|f| {
+        let score =
+            enclosing_function(f, ctx).map_or(0, |name| signals(name, &unguarded, ctx).score());
+        (f.severity.clone(), Reverse(score))
+    });
+    for (i, finding) in findings.iter_mut().enumerate() {
+        finding.rank = Some(i + 1);
+    }
+}
+
+/// Signals for `function`, given the set of functions reachable without auth
+pub fn signals(
+    function: &str,
+    unguarded: &HashSet<String>,
+    ctx: &AnalysisContext,
+) -> Exploitability {
+    let reachable = ctx.call_graph().reachable_from(&[function]);
+    let attacker_input = ctx
+        .contract
+        .functions
+        .iter()
+        .find(|f| f.name == function)
+        .is_some_and(|f| {
+            f.params.iter().any(|p| {
+                !TRUSTED_PARAM_TYPES
+                    .iter()
+                    .any(|t| p.type_name.trim_start_matches(['&', ' ']) == *t)
+            })
+        });
+    Exploitability {
+        unauthenticated: unguarded.contains(function),
+        attacker_input,
+        funds_impact: reachable
+            .iter()
+            .any(|f| !ctx.emitted_messages(f).is_empty()),
+        state_impact: reachable.iter().any(|f| !ctx.storage_writes(f).is_empty()),
+    }
+}
+
+/// Functions reachable from user-callable entry points along call paths that
+/// never enter a function checking the sender
+pub fn unguarded_reach(ctx: &AnalysisContext) -> HashSet<String> {
+    let mut reached = HashSet::new();
+    let mut queue: VecDeque<&str> = ctx
+        .contract
+        .entry_points
+        .iter()
+        .filter(|ep| {
+            !matches!(
+                ep.kind,
+                EntryPointKind::Migrate | EntryPointKind::Sudo | EntryPointKind::Reply
+            )
+        })
+        .map(|ep| ep.name.as_str())
+        .collect();
+    let call_graph = ctx.call_graph();
+    while let Some(name) = queue.pop_front() {
+        if reached.contains(name) || ctx.guards(name).iter().any(|g| g.kind == GuardKind::Sender) {
+            continue;
+        }
+        reached.insert(name.to_string());
+        queue.extend(call_graph.callees(name));
+    }
+    reached
+}
+
+/// Innermost function whose signature-to-closing-brace range contains the
+/// finding's primary location
+pub fn enclosing_function<'c>(finding: &Finding, ctx: &'c AnalysisContext) -> Option<&'c str> {
+    enclosing_function_info(finding, ctx).map(|f| f.name.as_str())
+}
+
+/// Like [`enclosing_function`], with the whole function
+pub fn enclosing_function_info<'c>(
+    finding: &Finding,
+    ctx: &'c AnalysisContext,
+) -> Option<&'c FunctionInfo> {
+    let loc = finding.locations.first()?;
+    ctx.contract
+        .functions
+        .iter()
+        .filter(|f| f.span.file == loc.file)
+        .filter_map(|f| {
+            let end = f.body_span.as_ref()?.end_line;
+            (f.span.start_line..=end)
+                .contains(&loc.start_line)
+                .then_some((f, end - f.span.start_line))
+        })
+        .min_by_key(|(_, len)| *len)
+        .map(|(f, _)| f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::finding::{test_finding, Confidence, Severity};
+    use crate::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_unguarded_fund_movers_rank_first_within_severity() {
+        let source = r#"
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Withdraw { amount } => withdraw(deps, info, amount),
+        ExecuteMsg::SetFee { fee } => set_fee(deps, info, fee),
+    }
+}
+
+fn withdraw(deps: DepsMut, info: MessageInfo, amount: Uint128) -> Result<Response, ContractError> {
+    let msg = BankMsg::Send { to_address: info.sender.to_string(), amount: coins(amount.u128(), "uatom") };
+    Ok(Response::new().add_message(msg))
+}
+
+fn set_fee(deps: DepsMut, info: MessageInfo, fee: u64) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    CONFIG.save(deps.storage, &fee)?;
+    Ok(Response::new())
+}
+
+fn format_amount(amount: Uint128) -> String {
+    amount.to_string()
+}
+"#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([(PathBuf::from("test.rs"), source.to_string())]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+
+        let unguarded = unguarded_reach(&ctx);
+        assert!(unguarded.contains("withdraw"));
+        assert!(!unguarded.contains("set_fee"));
+
+        let mut findings = vec![
+            test_finding("test", Severity::Medium, Confidence::Medium, 24), // format_amount: unreachable, no effects
+            test_finding("test", Severity::Medium, Confidence::Medium, 16), // set_fee: guarded state write
+            test_finding("test", Severity::High, Confidence::Medium, 25),
+            test_finding("test", Severity::Medium, Confidence::Medium, 11), // withdraw: unguarded, sends funds
+        ];
+        rank_findings(&mut findings, &ctx);
+
+        let order: Vec<usize> = findings.iter().map(|f| f.locations[0].start_line).collect();
+        assert_eq!(order, vec![25, 11, 16, 24]);
+        assert_eq!(findings[1].rank, Some(2));
+    }
+}
@@ -0,0 +1,88 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::bail;
+
+/// Builds web URLs for source locations from a template such as
+/// `https://github.com/org/repo/blob/{rev}/{path}#L{line}`, so findings in
+/// terminal, Markdown, HTML or chat outputs link straight to the code.
+///
+/// `{path}` is the file relative to the repository root, with `/` separators;
+/// paths that don't resolve under the root (e.g. already rewritten with
+/// `--path-prefix-strip`) are used as they are.
+#[derive(Debug, Clone)]
+pub struct LinkTemplate {
+    template: String,
+    rev: String,
+    root: Option<PathBuf>,
+}
+
+impl LinkTemplate {
+    /// Template with `{path}` and optionally `{rev}` and `{line}` placeholders
+    pub fn new(template: &str, rev: &str, root: Option<PathBuf>) -> anyhow::Result<Self> {
+        if !template.contains("{path}") {
+            bail!("link template must contain `{{path}}`: `{template}`");
+        }
+        Ok(Self {
+            template: template.to_string(),
+            rev: rev.to_string(),
+            root: root.map(|r| r.canonicalize().unwrap_or(r)),
+        })
+    }
+
+    pub fn url(&self, file: &Path, line: usize) -> String {
+        let relative = self
+            .root
+            .as_ref()
+            .and_then(|root| {
+                let file = file.canonicalize().ok()?;
+                file.strip_prefix(root).ok().map(Path::to_path_buf)
+            })
+            .unwrap_or_else(|| file.to_path_buf());
+        let path = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        self.template
+            .replace("{rev}", &self.rev)
+            .replace("{path}", &path)
+            .replace("{line}", &line.to_string())
+    }
+}
+
+/// `file://` URL of a local file, for terminal hyperlinks without a template
+pub fn file_url(file: &Path) -> String {
+    let absolute = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    format!("file://{}", absolute.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_template_expands_repo_relative_path() {
+        let dir = std::env::temp_dir().join("cosmwasm-guard-test-links");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("contracts/vault/src")).unwrap();
+        std::fs::write(dir.join("contracts/vault/src/lib.rs"), "").unwrap();
+
+        let links = LinkTemplate::new(
+            "https://github.com/org/repo/blob/{rev}/{path}#L{line}",
+            "abc123",
+            Some(dir.clone()),
+        )
+        .unwrap();
+        assert_eq!(
+            links.url(&dir.join("contracts/vault/src/lib.rs"), 42),
+            "https://github.com/org/repo/blob/abc123/contracts/vault/src/lib.rs#L42"
+        );
+        // Already repo-relative (rewritten) paths are used as they are
+        assert_eq!(
+            links.url(Path::new("src/contract.rs"), 7),
+            "https://github.com/org/repo/blob/abc123/src/contract.rs#L7"
+        );
+
+        assert!(LinkTemplate::new("https://example.com/{line}", "HEAD", None).is_err());
+    }
+}
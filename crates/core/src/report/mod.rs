@@ -1,3 +1,9 @@
+pub mod links;
+pub mod packages;
+pub mod paths;
 pub mod types;
 
+pub use links::LinkTemplate;
+pub use packages::Package;
+pub use paths::PathRewriter;
 pub use types::*;
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Analyzed files belonging to one Cargo package, so outputs such as SARIF can
+/// report each contract crate of a workspace as its own analysis category
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub name: String,
+    pub root: PathBuf,
+    pub files: Vec<PathBuf>,
+}
+
+/// Group `files` by the package whose `Cargo.toml` is nearest above them.
+/// Files outside any package are left out; packages are sorted by name.
+pub fn group_by_package(files: &[PathBuf]) -> Vec<Package> {
+    let mut manifests: HashMap<PathBuf, Option<String>> = HashMap::new();
+    let mut packages: Vec<Package> = Vec::new();
+    for file in files {
+        let Some((root, name)) = file
+            .ancestors()
+            .skip(1)
+            .find_map(|dir| package_name(dir, &mut manifests).map(|n| (dir, n)))
+        else {
+            continue;
+        };
+        match packages.iter_mut().find(|p| p.root == root) {
+            Some(package) => package.files.push(file.clone()),
+            None => packages.push(Package {
+                name,
+                root: root.to_path_buf(),
+                files: vec![file.clone()],
+            }),
+        }
+    }
+    packages.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.root.cmp(&b.root)));
+    packages
+}
+
+/// Index of the package with the deepest root containing `file`
+pub fn package_of(packages: &[Package], file: &Path) -> Option<usize> {
+    packages
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| file.starts_with(&p.root))
+        .max_by_key(|(_, p)| p.root.components().count())
+        .map(|(i, _)| i)
+}
+
+/// `package.name` from `dir/Cargo.toml`; None for missing manifests and
+/// virtual workspace manifests
+fn package_name(dir: &Path, manifests: &mut HashMap<PathBuf, Option<String>>) -> Option<String> {
+    manifests
+        .entry(dir.to_path_buf())
+        .or_insert_with(|| {
+            let content = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+            let manifest: toml::Table = toml::from_str(&content).ok()?;
+            manifest
+                .get("package")?
+                .get("name")?
+                .as_str()
+                .map(String::from)
+        })
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_files_grouped_by_nearest_package() {
+        let dir = std::env::temp_dir().join("cosmwasm-guard-test-packages");
+        let _ = std::fs::remove_dir_all(&dir);
+        for (file, content) in [
+            ("Cargo.toml", "[workspace]\nmembers = [\"contracts/*\"]\n"),
+            (
+                "contracts/vault/Cargo.toml",
+                "[package]\nname = \"vault\"\n",
+            ),
+            ("contracts/vault/src/lib.rs", ""),
+            ("contracts/vault/src/state.rs", ""),
+            ("contracts/dex/Cargo.toml", "[package]\nname = \"dex\"\n"),
+            ("contracts/dex/src/lib.rs", ""),
+            ("scripts/gen.rs", ""),
+        ] {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let files: Vec<PathBuf> = [
+            "contracts/vault/src/lib.rs",
+            "contracts/dex/src/lib.rs",
+            "contracts/vault/src/state.rs",
+            "scripts/gen.rs",
+        ]
+        .iter()
+        .map(|f| dir.join(f))
+        .collect();
+        let packages = group_by_package(&files);
+
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["dex", "vault"]);
+        assert_eq!(packages[1].root, dir.join("contracts/vault"));
+        assert_eq!(packages[1].files.len(), 2);
+    }
+}
@@ -0,0 +1,85 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::bail;
+
+/// Rewrites reported file paths, e.g. to turn absolute paths produced inside a
+/// container (`/work/repo/src/contract.rs`) back into repo-relative ones
+/// (`src/contract.rs`) that code scanning can attach annotations to.
+///
+/// Prefix maps are tried before prefix strips; the first matching rule wins.
+/// Prefixes match whole path components, so `/work/re` does not match `/work/repo`.
+#[derive(Debug, Clone, Default)]
+pub struct PathRewriter {
+    maps: Vec<(PathBuf, PathBuf)>,
+    strips: Vec<PathBuf>,
+}
+
+impl PathRewriter {
+    pub fn new(strips: Vec<PathBuf>, maps: Vec<(PathBuf, PathBuf)>) -> Self {
+        Self { maps, strips }
+    }
+
+    /// Parse a `FROM=TO` prefix mapping
+    pub fn parse_map(spec: &str) -> anyhow::Result<(PathBuf, PathBuf)> {
+        match spec.split_once('=') {
+            Some((from, to)) if !from.is_empty() => Ok((PathBuf::from(from), PathBuf::from(to))),
+            _ => bail!("expected FROM=TO, got `{spec}`"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.maps.is_empty() && self.strips.is_empty()
+    }
+
+    pub fn rewrite(&self, path: &Path) -> PathBuf {
+        for (from, to) in &self.maps {
+            if let Ok(rest) = path.strip_prefix(from) {
+                return to.join(rest);
+            }
+        }
+        for prefix in &self.strips {
+            if let Ok(rest) = path.strip_prefix(prefix) {
+                return rest.to_path_buf();
+            }
+        }
+        path.to_path_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_and_map() {
+        let rewriter = PathRewriter::new(
+            vec![PathBuf::from("/work/repo")],
+            vec![PathBuf::from("/work/repo/vendor")]
+                .into_iter()
+                .map(|p| (p, PathBuf::from("third_party")))
+                .collect(),
+        );
+        assert_eq!(
+            rewriter.rewrite(Path::new("/work/repo/src/contract.rs")),
+            PathBuf::from("src/contract.rs")
+        );
+        assert_eq!(
+            rewriter.rewrite(Path::new("/work/repo/vendor/cw20/lib.rs")),
+            PathBuf::from("third_party/cw20/lib.rs")
+        );
+        // Component-wise: a partial directory name is not a prefix
+        assert_eq!(
+            rewriter.rewrite(Path::new("/work/repository/lib.rs")),
+            PathBuf::from("/work/repository/lib.rs")
+        );
+    }
+
+    #[test]
+    fn test_parse_map() {
+        let (from, to) = PathRewriter::parse_map("/src=app").unwrap();
+        assert_eq!(from, PathBuf::from("/src"));
+        assert_eq!(to, PathBuf::from("app"));
+        assert!(PathRewriter::parse_map("no-separator").is_err());
+        assert!(PathRewriter::parse_map("=x").is_err());
+    }
+}
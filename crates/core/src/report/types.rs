@@ -1,51 +1,232 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use serde::Serialize;
 
+use super::packages::Package;
+use super::paths::PathRewriter;
 use crate::finding::{Finding, Severity};
 
 #[derive(Debug, Serialize)]
 pub struct SeverityCounts {
+    pub critical: usize,
     pub high: usize,
     pub medium: usize,
     pub low: usize,
     pub informational: usize,
 }
 
+impl SeverityCounts {
+    pub fn from_findings<'a>(findings: impl IntoIterator<Item = &'a Finding>) -> Self {
+        let mut counts = Self {
+            critical: 0,
+            high: 0,
+            medium: 0,
+            low: 0,
+            informational: 0,
+        };
+        for finding in findings {
+            match finding.severity {
+                Severity::Critical => counts.critical += 1,
+                Severity::High => counts.high += 1,
+                Severity::Medium => counts.medium += 1,
+                Severity::Low => counts.low += 1,
+                Severity::Informational => counts.informational += 1,
+            }
+        }
+        counts
+    }
+
+    pub fn total(&self) -> usize {
+        self.critical + self.high + self.medium + self.low + self.informational
+    }
+}
+
+/// Key used for findings no `[[owners]]` rule matched
+pub const UNOWNED: &str = "(unowned)";
+
+/// A file that could not be read or parsed and was skipped
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisError {
+    pub file: PathBuf,
+    pub message: String,
+    /// 1-based line of the parse error, when known
+    pub line: Option<usize>,
+    /// 0-based column of the parse error, when known
+    pub column: Option<usize>,
+}
+
+/// What part of the code the analysis could not see into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// No `match` arm dispatches a message variant
+    UnresolvedHandler,
+    /// A closure body, opaque to IR-based detectors
+    ClosureSkipped,
+    /// A macro whose arguments do not parse as expressions
+    MacroUnanalyzed,
+}
+
+impl WarningKind {
+    /// Whether `--strict` treats the blind spot as a failure. Closures are
+    /// everywhere and mostly hold small adapters, so they are only reported.
+    pub fn fails_strict(self) -> bool {
+        self != WarningKind::ClosureSkipped
+    }
+}
+
+/// A blind spot of the analysis: code it ran over without looking into, so
+/// a clean result does not vouch for it
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisWarning {
+    pub kind: WarningKind,
+    pub message: String,
+    pub file: Option<PathBuf>,
+    /// 1-based line, when known
+    pub line: Option<usize>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct AnalysisReport {
     pub files_analyzed: Vec<PathBuf>,
     pub total_findings: usize,
     pub findings_by_severity: SeverityCounts,
     pub findings: Vec<Finding>,
+    /// Files skipped because they failed to read or parse
+    pub analysis_errors: Vec<AnalysisError>,
+    /// Code the analysis could not see into
+    pub analysis_warnings: Vec<AnalysisWarning>,
+    /// Cargo packages the analyzed files belong to, for per-crate SARIF runs
+    #[serde(skip)]
+    pub packages: Vec<Package>,
 }
 
 impl AnalysisReport {
     pub fn from_findings(files: Vec<PathBuf>, findings: Vec<Finding>) -> Self {
-        let counts = SeverityCounts {
-            high: findings
-                .iter()
-                .filter(|f| f.severity == Severity::High)
-                .count(),
-            medium: findings
-                .iter()
-                .filter(|f| f.severity == Severity::Medium)
-                .count(),
-            low: findings
-                .iter()
-                .filter(|f| f.severity == Severity::Low)
-                .count(),
-            informational: findings
-                .iter()
-                .filter(|f| f.severity == Severity::Informational)
-                .count(),
-        };
+        let counts = SeverityCounts::from_findings(&findings);
         let total = findings.len();
         Self {
             files_analyzed: files,
             total_findings: total,
             findings_by_severity: counts,
             findings,
+            analysis_errors: Vec::new(),
+            analysis_warnings: Vec::new(),
+            packages: Vec::new(),
+        }
+    }
+
+    /// One report covering several analyses, e.g. of each path given to
+    /// `analyze`. Files and packages analyzed more than once are listed once.
+    pub fn merge(reports: impl IntoIterator<Item = AnalysisReport>) -> Self {
+        let mut files: Vec<PathBuf> = Vec::new();
+        let mut findings = Vec::new();
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+        let mut packages: Vec<Package> = Vec::new();
+        for report in reports {
+            for file in report.files_analyzed {
+                if !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+            findings.extend(report.findings);
+            errors.extend(report.analysis_errors);
+            warnings.extend(report.analysis_warnings);
+            for package in report.packages {
+                if !packages.iter().any(|p| p.root == package.root) {
+                    packages.push(package);
+                }
+            }
+        }
+        packages.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.root.cmp(&b.root)));
+        let mut merged = Self::from_findings(files, findings);
+        merged.analysis_errors = errors;
+        merged.analysis_warnings = warnings;
+        merged.packages = packages;
+        merged
+    }
+
+    /// Files that failed to parse and blind spots `--strict` fails on
+    pub fn unverified(&self) -> usize {
+        self.analysis_errors.len()
+            + self
+                .analysis_warnings
+                .iter()
+                .filter(|w| w.kind.fails_strict())
+                .count()
+    }
+
+    /// Severity counts per owning team; unowned findings are grouped under [`UNOWNED`]
+    pub fn counts_by_owner(&self) -> BTreeMap<&str, SeverityCounts> {
+        let mut by_owner: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+        for finding in &self.findings {
+            by_owner
+                .entry(finding.owner.as_deref().unwrap_or(UNOWNED))
+                .or_default()
+                .push(finding);
+        }
+        by_owner
+            .into_iter()
+            .map(|(owner, findings)| (owner, SeverityCounts::from_findings(findings)))
+            .collect()
+    }
+
+    /// Severity counts per Cargo package, for workspaces analyzed together;
+    /// findings outside any package are left out
+    pub fn counts_by_crate(&self) -> BTreeMap<&str, SeverityCounts> {
+        let mut by_crate: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+        for finding in &self.findings {
+            if let Some(name) = &finding.crate_name {
+                by_crate.entry(name).or_default().push(finding);
+            }
+        }
+        by_crate
+            .into_iter()
+            .map(|(name, findings)| (name, SeverityCounts::from_findings(findings)))
+            .collect()
+    }
+
+    /// Rewrite every file path in the report (analyzed files, errors, warnings,
+    /// finding, trace and fix locations). Finding ids are derived again from
+    /// the rewritten paths, so they do not depend on where the code was
+    /// checked out.
+    pub fn rewrite_paths(&mut self, rewriter: &PathRewriter) {
+        for file in &mut self.files_analyzed {
+            *file = rewriter.rewrite(file);
+        }
+        for package in &mut self.packages {
+            package.root = rewriter.rewrite(&package.root);
+            for file in &mut package.files {
+                *file = rewriter.rewrite(file);
+            }
+        }
+        for error in &mut self.analysis_errors {
+            error.file = rewriter.rewrite(&error.file);
+        }
+        for warning in &mut self.analysis_warnings {
+            if let Some(file) = &mut warning.file {
+                *file = rewriter.rewrite(file);
+            }
+        }
+        for finding in &mut self.findings {
+            for loc in &mut finding.locations {
+                loc.file = rewriter.rewrite(&loc.file);
+            }
+            for loc in finding
+                .trace
+                .iter_mut()
+                .filter_map(|step| step.location.as_mut())
+            {
+                loc.file = rewriter.rewrite(&loc.file);
+            }
+            for edit in finding.fix.iter_mut().flat_map(|fix| &mut fix.edits) {
+                edit.location.file = rewriter.rewrite(&edit.location.file);
+            }
+            if finding.id.is_some() {
+                finding.id = Some(finding.stable_id());
+            }
         }
     }
 }
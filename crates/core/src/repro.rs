@@ -0,0 +1,393 @@
+//! Reproduction harnesses: turns findings of selected detectors into
+//! skeleton `cw-multi-test` tests that fail while the issue is present.
+
+use std::fmt::Write;
+use std::path::{Component, Path};
+
+use crate::ast::{ContractInfo, EntryPointKind, FieldInfo, MessageKind};
+use crate::finding::Finding;
+
+/// Detectors whose findings can be reproduced
+pub const SUPPORTED_DETECTORS: &[&str] = &[
+    "missing-access-control",
+    "unguarded-parameter-update",
+    "uninitialized-state-access",
+];
+
+/// A generated test file
+#[derive(Debug, Clone)]
+pub struct ReproCase {
+    /// File name inside the output directory, e.g. `missing_access_control_1.rs`
+    pub file_name: String,
+    pub source: String,
+}
+
+/// Rust path of the module defining `file`, e.g. `src/contract.rs` in crate
+/// `vault` -> `vault::contract`
+pub(crate) fn module_path(crate_name: &str, file: &Path) -> String {
+    let parts: Vec<String> = file
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(s) => Some(s.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    let after_src = parts
+        .iter()
+        .rposition(|p| p == "src")
+        .map_or(&parts[parts.len().saturating_sub(1)..], |i| &parts[i + 1..]);
+    let mut path = vec![crate_name.to_string()];
+    for (i, part) in after_src.iter().enumerate() {
+        let part = if i + 1 == after_src.len() {
+            part.trim_end_matches(".rs")
+        } else {
+            part
+        };
+        if !matches!(part, "lib" | "main" | "mod") {
+            path.push(part.to_string());
+        }
+    }
+    path.join("::")
+}
+
+/// Struct-literal fields with `todo!()` placeholders, e.g. ` { amount: todo!() }`
+fn placeholder_fields(fields: &[FieldInfo]) -> String {
+    if fields.is_empty() {
+        return " {}".to_string();
+    }
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|f| format!("{}: todo!(\"{}\")", f.name, f.type_name))
+        .collect();
+    format!(" {{ {} }}", fields.join(", "))
+}
+
+pub(crate) fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            out.push('_');
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+/// Imports, contract wrapper and instantiate helper shared by every case
+pub(crate) struct Harness {
+    pub imports: String,
+    pub helpers: String,
+}
+
+impl Harness {
+    /// `label` names the instantiated contract, e.g. `guard-repro`
+    pub fn new(contract: &ContractInfo, crate_name: &str, label: &str) -> Self {
+        let entry = |kind: EntryPointKind| {
+            contract
+                .entry_points
+                .iter()
+                .find(|ep| ep.kind == kind)
+                .map(|ep| format!("{}::{}", module_path(crate_name, &ep.span.file), ep.name))
+        };
+        let instantiate = entry(EntryPointKind::Instantiate)
+            .unwrap_or_else(|| format!("{crate_name}::contract::instantiate"));
+        let execute = entry(EntryPointKind::Execute)
+            .unwrap_or_else(|| format!("{crate_name}::contract::execute"));
+        let query = entry(EntryPointKind::Query)
+            .unwrap_or_else(|| format!("{crate_name}::contract::query"));
+
+        let instantiate_msg = contract.structs.iter().find(|s| s.name == "InstantiateMsg");
+        let instantiate_msg_path = instantiate_msg.map_or_else(
+            || format!("{crate_name}::msg::InstantiateMsg"),
+            |s| format!("{}::InstantiateMsg", module_path(crate_name, &s.span.file)),
+        );
+        let instantiate_fields = instantiate_msg.map_or_else(
+            || " { /* TODO */ }".to_string(),
+            |s| {
+                let fields: Vec<FieldInfo> = s
+                    .fields
+                    .iter()
+                    .map(|f| FieldInfo {
+                        name: f.name.clone(),
+                        type_name: f.type_name.clone(),
+                    })
+                    .collect();
+                placeholder_fields(&fields)
+            },
+        );
+
+        let mut imports = String::new();
+        let _ = writeln!(imports, "use cosmwasm_std::Empty;");
+        let _ = writeln!(
+            imports,
+            "use cw_multi_test::{{App, Contract, ContractWrapper, Executor}};"
+        );
+
+        let mut helpers = String::new();
+        let _ = writeln!(helpers);
+        let _ = writeln!(helpers, "fn contract() -> Box<dyn Contract<Empty>> {{");
+        let _ = writeln!(helpers, "    Box::new(ContractWrapper::new(");
+        for handler in [execute, instantiate, query] {
+            let _ = writeln!(helpers, "        {handler},");
+        }
+        let _ = writeln!(helpers, "    ))");
+        let _ = writeln!(helpers, "}}");
+        let _ = writeln!(helpers);
+        let _ = writeln!(
+            helpers,
+            "/// Store and instantiate the contract as `owner`, returning its address"
+        );
+        let _ = writeln!(helpers, "fn setup(app: &mut App) -> cosmwasm_std::Addr {{");
+        let _ = writeln!(helpers, "    let code_id = app.store_code(contract());");
+        let _ = writeln!(helpers, "    let owner = app.api().addr_make(\"owner\");");
+        let _ = writeln!(
+            helpers,
+            "    let msg = {instantiate_msg_path}{instantiate_fields};"
+        );
+        let _ = writeln!(
+            helpers,
+            "    app.instantiate_contract(code_id, owner, &msg, &[], \"{label}\", None)"
+        );
+        let _ = writeln!(helpers, "        .unwrap()");
+        let _ = writeln!(helpers, "}}");
+        Self { imports, helpers }
+    }
+
+    fn file(&self, finding: &Finding, tests: &str) -> String {
+        let mut source = String::new();
+        let _ = writeln!(
+            source,
+            "//! Reproduction of `{}`: {}",
+            finding.detector_name, finding.title
+        );
+        if let Some(loc) = finding.locations.first() {
+            let _ = writeln!(
+                source,
+                "//! Reported at {}:{}",
+                loc.file.display(),
+                loc.start_line
+            );
+        }
+        let _ = writeln!(source, "//!");
+        let _ = writeln!(
+            source,
+            "//! Generated by `cosmwasm-guard analyze --gen-repro`. Replace the `todo!()`"
+        );
+        let _ = writeln!(
+            source,
+            "//! placeholders and copy the file into the contract's `tests/` directory;"
+        );
+        let _ = writeln!(source, "//! it fails until the finding is fixed.");
+        let _ = writeln!(source);
+        source.push_str(&self.imports);
+        source.push_str(&self.helpers);
+        source.push_str(tests);
+        source
+    }
+}
+
+/// Test that `msg_path::variant` is rejected when sent by a random address
+fn unauthorized_test(enum_path: &str, variant: &str, fields: &[FieldInfo]) -> String {
+    let mut test = String::new();
+    let _ = writeln!(test);
+    let _ = writeln!(test, "#[test]");
+    let _ = writeln!(
+        test,
+        "fn {}_rejects_random_sender() {{",
+        snake_case(variant)
+    );
+    let _ = writeln!(test, "    let mut app = App::default();");
+    let _ = writeln!(test, "    let contract_addr = setup(&mut app);");
+    let _ = writeln!(
+        test,
+        "    let attacker = app.api().addr_make(\"attacker\");"
+    );
+    let _ = writeln!(
+        test,
+        "    let msg = {enum_path}::{variant}{};",
+        placeholder_fields(fields)
+    );
+    let _ = writeln!(
+        test,
+        "    let res = app.execute_contract(attacker, contract_addr, &msg, &[]);"
+    );
+    let _ = writeln!(
+        test,
+        "    assert!(res.is_err(), \"a random sender could execute {variant}\");"
+    );
+    let _ = writeln!(test, "}}");
+    test
+}
+
+/// Generate reproduction tests for the supported findings; `crate_name` is the
+/// contract's library crate as imported from its integration tests
+pub fn generate(findings: &[Finding], contract: &ContractInfo, crate_name: &str) -> Vec<ReproCase> {
+    let harness = Harness::new(contract, crate_name, "guard-repro");
+    let execute_enums: Vec<_> = contract
+        .message_enums
+        .iter()
+        .filter(|e| e.kind == MessageKind::Execute)
+        .collect();
+
+    let mut cases = Vec::new();
+    for finding in findings {
+        if !SUPPORTED_DETECTORS.contains(&finding.detector_name.as_str()) {
+            continue;
+        }
+        let quoted: Vec<&str> = finding.title.split('`').skip(1).step_by(2).collect();
+
+        let tests = match finding.detector_name.as_str() {
+            // Every variant the unguarded handler dispatches
+            "missing-access-control" => execute_enums
+                .iter()
+                .flat_map(|e| {
+                    let path = format!("{}::{}", module_path(crate_name, &e.span.file), e.name);
+                    e.variants
+                        .iter()
+                        .map(move |v| unauthorized_test(&path, &v.name, &v.fields))
+                })
+                .collect::<String>(),
+            // The one variant named in the title, `ExecuteMsg::SetFee`
+            "unguarded-parameter-update" => {
+                let Some((enum_name, variant)) = quoted.first().and_then(|q| q.split_once("::"))
+                else {
+                    continue;
+                };
+                let Some(message) = execute_enums.iter().find(|e| e.name == enum_name) else {
+                    continue;
+                };
+                let Some(v) = message.variants.iter().find(|v| v.name == variant) else {
+                    continue;
+                };
+                let path = format!(
+                    "{}::{}",
+                    module_path(crate_name, &message.span.file),
+                    message.name
+                );
+                unauthorized_test(&path, &v.name, &v.fields)
+            }
+            // The state item named in the title is present after instantiate
+            "uninitialized-state-access" => {
+                let Some(item) = quoted
+                    .first()
+                    .and_then(|name| contract.state_items.iter().find(|s| s.name == *name))
+                else {
+                    continue;
+                };
+                let mut test = String::new();
+                let _ = writeln!(test);
+                let _ = writeln!(test, "#[test]");
+                let _ = writeln!(
+                    test,
+                    "fn {}_initialized_by_instantiate() {{",
+                    item.name.to_lowercase()
+                );
+                let _ = writeln!(test, "    let mut app = App::default();");
+                let _ = writeln!(test, "    let contract_addr = setup(&mut app);");
+                let _ = writeln!(
+                    test,
+                    "    let storage = app.contract_storage(&contract_addr);"
+                );
+                let _ = writeln!(
+                    test,
+                    "    let value = {}::{}.may_load(&*storage).unwrap();",
+                    module_path(crate_name, &item.span.file),
+                    item.name
+                );
+                let _ = writeln!(
+                    test,
+                    "    assert!(value.is_some(), \"{} is not saved by instantiate\");",
+                    item.name
+                );
+                let _ = writeln!(test, "}}");
+                test
+            }
+            _ => continue,
+        };
+        if tests.is_empty() {
+            continue;
+        }
+
+        cases.push(ReproCase {
+            file_name: format!(
+                "{}_{}.rs",
+                finding.detector_name.replace('-', "_"),
+                cases.len() + 1
+            ),
+            source: harness.file(finding, &tests),
+        });
+    }
+    cases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::finding::{test_finding, Confidence, Severity};
+    use std::path::PathBuf;
+
+    fn finding(detector: &str, title: &str) -> Finding {
+        let mut finding = test_finding(detector, Severity::High, Confidence::Medium, 12);
+        finding.title = title.to_string();
+        finding.locations[0].file = PathBuf::from("vault/src/contract.rs");
+        finding
+    }
+
+    #[test]
+    fn test_module_path() {
+        assert_eq!(module_path("vault", Path::new("vault/src/lib.rs")), "vault");
+        assert_eq!(
+            module_path("vault", Path::new("vault/src/contract.rs")),
+            "vault::contract"
+        );
+        assert_eq!(
+            module_path("vault", Path::new("/x/src/execute/mod.rs")),
+            "vault::execute"
+        );
+    }
+
+    #[test]
+    fn test_generates_unauthorized_and_initialization_cases() {
+        let source = r#"
+            pub const CONFIG: Item<Config> = Item::new("config");
+            pub struct InstantiateMsg { pub admin: String }
+            pub enum ExecuteMsg { SetFee { fee_bps: u64 }, Pause {} }
+            #[entry_point]
+            pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg)
+                -> StdResult<Response> { Ok(Response::new()) }
+            #[entry_point]
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> StdResult<Response> { Ok(Response::new()) }
+        "#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("vault/src/contract.rs"), ast);
+        let findings = vec![
+            finding(
+                "unguarded-parameter-update",
+                "`ExecuteMsg::SetFee` can be called by anyone",
+            ),
+            finding(
+                "uninitialized-state-access",
+                "State `CONFIG` loaded but may not be initialized",
+            ),
+            finding("unsafe-unwrap", "Unwrap in `execute`"),
+        ];
+        let cases = generate(&findings, &contract, "vault");
+        assert_eq!(cases.len(), 2);
+
+        assert_eq!(cases[0].file_name, "unguarded_parameter_update_1.rs");
+        let setfee = &cases[0].source;
+        assert!(setfee.contains(
+            "ContractWrapper::new(\n        vault::contract::execute,\n        \
+             vault::contract::instantiate,\n        vault::contract::query,\n    ))"
+        ));
+        assert!(setfee.contains("vault::contract::InstantiateMsg { admin: todo!(\"String\") }"));
+        assert!(setfee.contains("fn set_fee_rejects_random_sender()"));
+        assert!(setfee.contains("vault::contract::ExecuteMsg::SetFee { fee_bps: todo!(\"u64\") }"));
+        assert!(!setfee.contains("Pause"));
+
+        let config = &cases[1].source;
+        assert!(config.contains("vault::contract::CONFIG.may_load(&*storage)"));
+    }
+}
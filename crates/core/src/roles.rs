@@ -0,0 +1,637 @@
+//! Role-based access model: infers the roles a contract distinguishes (owner,
+//! minter, operator maps, whitelists) from its state items and sender checks,
+//! and maps each execute variant to the roles allowed to call it.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use syn::punctuated::Punctuated;
+use syn::visit::Visit;
+
+use crate::ast::{MessageKind, SourceSpan, StorageType};
+use crate::auth::AuthRegistry;
+use crate::detector::AnalysisContext;
+
+/// Name fragments of state that grants or holds a role
+const ROLE_PATTERNS: &[&str] = &[
+    "owner",
+    "admin",
+    "minter",
+    "operator",
+    "whitelist",
+    "allowlist",
+    "governance",
+    "gov",
+    "manager",
+    "guardian",
+    "keeper",
+    "executor",
+    "controller",
+    "pauser",
+    "member",
+    "role",
+    "blacklist",
+    "blocklist",
+    "denylist",
+];
+
+/// Name fragments of protocol configuration besides role state
+const CONFIG_PATTERNS: &[&str] = &["config", "param", "setting"];
+
+/// Methods checking whether a key, e.g. the sender, is in a map or list
+const MEMBERSHIP_METHODS: &[&str] = &["has", "load", "may_load", "contains", "contains_key"];
+
+/// Methods writing a state item
+const WRITE_METHODS: &[&str] = &["save", "update", "remove"];
+
+/// Prefixes stripped from helper names to name their role (`only_gov` -> `gov`)
+const HELPER_PREFIXES: &[&str] = &["assert_", "only_", "is_", "check_", "validate_", "ensure_"];
+
+fn is_role_name(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    ROLE_PATTERNS.iter().any(|p| lower.contains(p))
+}
+
+fn is_addr_type(type_name: &str) -> bool {
+    type_name.contains("Addr")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoleKind {
+    /// A single address: `OWNER: Item<Addr>` or `config.owner`
+    Address,
+    /// A set of addresses: `OPERATORS: Map<&Addr, Empty>`, `whitelist: Vec<Addr>`
+    Membership,
+    /// Enforced by an access-control helper whose storage is not visible
+    Helper,
+}
+
+/// A role the contract checks `info.sender` against
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Role {
+    /// Lowercase name, e.g. `owner` or `operators`
+    pub name: String,
+    pub kind: RoleKind,
+    /// State item holding the role, when it is stored in contract state
+    pub item: Option<String>,
+}
+
+/// Who may call one execute message variant
+#[derive(Debug, Clone, Serialize)]
+pub struct VariantPermission {
+    /// Message enum, e.g. `ExecuteMsg`
+    pub message: String,
+    pub variant: String,
+    /// Function whose `match` dispatches the variant, if found
+    pub dispatcher: Option<String>,
+    /// Location of the dispatching match arm
+    pub span: Option<SourceSpan>,
+    /// Lines of the whole arm, from its pattern to the end of its body
+    pub arm: Option<SourceSpan>,
+    /// Functions the arm calls, directly or through other calls
+    pub handlers: Vec<String>,
+    /// Roles checked by the arm or the handlers it calls; empty means anyone
+    pub roles: Vec<String>,
+    /// State items the arm or the handlers it calls write
+    pub writes: Vec<String>,
+    /// Privileged state items (role and config state) the variant writes
+    pub privileged_writes: Vec<String>,
+}
+
+impl VariantPermission {
+    pub fn callable_by_anyone(&self) -> bool {
+        self.roles.is_empty()
+    }
+
+    /// Callable by anyone while mutating privileged state
+    pub fn is_flagged(&self) -> bool {
+        self.callable_by_anyone() && !self.privileged_writes.is_empty()
+    }
+}
+
+/// Roles of a contract and the roles allowed to call each execute variant
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PermissionMatrix {
+    pub roles: Vec<Role>,
+    pub variants: Vec<VariantPermission>,
+}
+
+impl PermissionMatrix {
+    pub fn build(ctx: &AnalysisContext) -> Self {
+        let mut roles: BTreeSet<Role> = state_roles(ctx).into_iter().collect();
+        let privileged: BTreeSet<String> = ctx
+            .contract
+            .state_items
+            .iter()
+            .filter(|item| {
+                let lower = item.name.to_lowercase();
+                is_role_name(&lower) || CONFIG_PATTERNS.iter().any(|p| lower.contains(p))
+            })
+            .map(|item| item.name.clone())
+            .chain(roles.iter().filter_map(|r| r.item.clone()))
+            .collect();
+
+        let execute_enums: Vec<&str> = ctx
+            .contract
+            .message_enums
+            .iter()
+            .filter(|e| e.kind == MessageKind::Execute)
+            .map(|e| e.name.as_str())
+            .collect();
+
+        let mut variants = Vec::new();
+        for message in ctx
+            .contract
+            .message_enums
+            .iter()
+            .filter(|e| e.kind == MessageKind::Execute)
+        {
+            for variant in &message.variants {
+                variants.push(VariantPermission {
+                    message: message.name.clone(),
+                    variant: variant.name.clone(),
+                    dispatcher: None,
+                    span: None,
+                    arm: None,
+                    handlers: Vec::new(),
+                    roles: Vec::new(),
+                    writes: Vec::new(),
+                    privileged_writes: Vec::new(),
+                });
+            }
+        }
+
+        for func in &ctx.contract.functions {
+            let Some(body) = &func.body else { continue };
+            let mut arms = ArmCollector {
+                enums: &execute_enums,
+                arms: Vec::new(),
+            };
+            arms.visit_block(body);
+
+            for (message, variant, arm) in arms.arms {
+                let mut arm_roles = RoleCollector::new(ctx.auth());
+                arm_roles.visit_expr(&arm.body);
+                let mut found = arm_roles.roles;
+                let mut writes = arm_roles.writes;
+
+                let roots: Vec<&str> = arm_roles.calls.iter().map(String::as_str).collect();
+                let reachable = ctx.call_graph().reachable_from(&roots);
+                for callee in ctx
+                    .contract
+                    .functions
+                    .iter()
+                    .filter(|f| reachable.contains(&f.name))
+                {
+                    if let Some(body) = &callee.body {
+                        let mut callee_roles = RoleCollector::new(ctx.auth());
+                        callee_roles.visit_block(body);
+                        found.extend(callee_roles.roles);
+                    }
+                    writes.extend(
+                        ctx.storage_writes(&callee.name)
+                            .iter()
+                            .map(|w| w.item.clone()),
+                    );
+                }
+
+                let names: Vec<String> = found.iter().map(|r| r.name.clone()).collect();
+                for role in found {
+                    if !roles.iter().any(|r| r.name == role.name) {
+                        roles.insert(role);
+                    }
+                }
+                let privileged_writes: Vec<String> = writes
+                    .iter()
+                    .filter(|w| privileged.contains(*w))
+                    .cloned()
+                    .collect();
+
+                let entry = match variants
+                    .iter_mut()
+                    .find(|v| v.message == message && v.variant == variant)
+                {
+                    Some(entry) => entry,
+                    None => {
+                        variants.push(VariantPermission {
+                            message: message.clone(),
+                            variant: variant.clone(),
+                            dispatcher: None,
+                            span: None,
+                            arm: None,
+                            handlers: Vec::new(),
+                            roles: Vec::new(),
+                            writes: Vec::new(),
+                            privileged_writes: Vec::new(),
+                        });
+                        variants.last_mut().unwrap()
+                    }
+                };
+                if entry.dispatcher.is_none() {
+                    entry.dispatcher = Some(func.name.clone());
+                    if let (Some(pat), Some(body)) = (ctx.span_of(&arm.pat), ctx.span_of(&arm.body))
+                    {
+                        entry.span = Some(SourceSpan {
+                            end_line: pat.start_line,
+                            end_col: pat.start_col,
+                            end_byte: pat.start_byte,
+                            ..pat.clone()
+                        });
+                        entry.arm = Some(SourceSpan {
+                            end_line: body.end_line,
+                            end_col: body.end_col,
+                            end_byte: body.end_byte,
+                            ..pat
+                        });
+                    }
+                }
+                merge_sorted(&mut entry.handlers, reachable);
+                merge_sorted(&mut entry.roles, names);
+                merge_sorted(&mut entry.writes, writes);
+                merge_sorted(&mut entry.privileged_writes, privileged_writes);
+            }
+        }
+
+        Self {
+            roles: roles.into_iter().collect(),
+            variants,
+        }
+    }
+
+    /// Variants callable by anyone that mutate privileged state
+    pub fn flagged(&self) -> impl Iterator<Item = &VariantPermission> {
+        self.variants.iter().filter(|v| v.is_flagged())
+    }
+
+    /// Kind of the role named `name`
+    pub fn role_kind(&self, name: &str) -> Option<RoleKind> {
+        self.roles.iter().find(|r| r.name == name).map(|r| r.kind)
+    }
+}
+
+fn merge_sorted(into: &mut Vec<String>, from: impl IntoIterator<Item = String>) {
+    into.extend(from);
+    into.sort();
+    into.dedup();
+}
+
+/// Roles declared by state: address items, address-keyed maps and address
+/// fields of stored config structs with role-like names
+fn state_roles(ctx: &AnalysisContext) -> Vec<Role> {
+    let mut roles = Vec::new();
+    for item in &ctx.contract.state_items {
+        let name = item.name.to_lowercase();
+        match item.storage_type {
+            StorageType::Item if is_addr_type(&item.value_type) => roles.push(Role {
+                name,
+                kind: RoleKind::Address,
+                item: Some(item.name.clone()),
+            }),
+            StorageType::Item => {
+                let Some(stored) = ctx
+                    .contract
+                    .structs
+                    .iter()
+                    .find(|s| s.name == item.value_type)
+                else {
+                    continue;
+                };
+                for field in &stored.fields {
+                    if !is_addr_type(&field.type_name) || !is_role_name(&field.name) {
+                        continue;
+                    }
+                    let kind = if field.type_name.contains("Vec") || field.type_name.contains("Set")
+                    {
+                        RoleKind::Membership
+                    } else {
+                        RoleKind::Address
+                    };
+                    roles.push(Role {
+                        name: field.name.to_lowercase(),
+                        kind,
+                        item: Some(item.name.clone()),
+                    });
+                }
+            }
+            StorageType::Map | StorageType::IndexedMap | StorageType::SnapshotMap
+                if is_role_name(&name) && item.key_type.as_deref().is_some_and(is_addr_type) =>
+            {
+                roles.push(Role {
+                    name,
+                    kind: RoleKind::Membership,
+                    item: Some(item.name.clone()),
+                })
+            }
+            _ => {}
+        }
+    }
+    roles
+}
+
+/// `Enum::Variant` pattern -> (enum, variant)
+fn variant_of(pat: &syn::Pat) -> Option<(String, String)> {
+    let path = match pat {
+        syn::Pat::Struct(p) => &p.path,
+        syn::Pat::TupleStruct(p) => &p.path,
+        syn::Pat::Path(p) => &p.path,
+        _ => return None,
+    };
+    let n = path.segments.len();
+    if n < 2 {
+        return None;
+    }
+    Some((
+        path.segments[n - 2].ident.to_string(),
+        path.segments[n - 1].ident.to_string(),
+    ))
+}
+
+/// Match arms on variants of the message enums `enums`
+pub(crate) struct ArmCollector<'a, 'ast> {
+    pub enums: &'a [&'a str],
+    /// (enum, variant, arm)
+    pub arms: Vec<(String, String, &'ast syn::Arm)>,
+}
+
+impl<'ast> Visit<'ast> for ArmCollector<'_, 'ast> {
+    fn visit_arm(&mut self, node: &'ast syn::Arm) {
+        if let Some((enum_name, variant)) = variant_of(&node.pat) {
+            if self.enums.contains(&enum_name.as_str()) {
+                self.arms.push((enum_name, variant, node));
+            }
+        }
+        syn::visit::visit_arm(self, node);
+    }
+}
+
+/// Whether an expression reads `*.sender`
+fn mentions_sender(expr: &syn::Expr) -> bool {
+    struct Search(bool);
+    impl<'ast> Visit<'ast> for Search {
+        fn visit_expr_field(&mut self, node: &'ast syn::ExprField) {
+            if matches!(&node.member, syn::Member::Named(ident) if ident == "sender") {
+                self.0 = true;
+            }
+            syn::visit::visit_expr_field(self, node);
+        }
+    }
+    let mut search = Search(false);
+    search.visit_expr(expr);
+    search.0
+}
+
+/// Role named by the expression a sender is compared with: `config.owner`
+/// -> `owner`, `OWNER.load(..)?` -> `owner`
+fn role_of(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Field(field) => match &field.member {
+            syn::Member::Named(ident) => Some(ident.to_string().to_lowercase()),
+            syn::Member::Unnamed(_) => role_of(&field.base),
+        },
+        syn::Expr::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|s| s.ident.to_string().to_lowercase()),
+        syn::Expr::MethodCall(call) => role_of(&call.receiver),
+        syn::Expr::Reference(r) => role_of(&r.expr),
+        syn::Expr::Paren(p) => role_of(&p.expr),
+        syn::Expr::Try(t) => role_of(&t.expr),
+        syn::Expr::Unary(u) => role_of(&u.expr),
+        _ => None,
+    }
+}
+
+/// Role enforced by an access-control helper: `assert_owner` -> `owner`
+fn helper_role(path: &str) -> String {
+    if path.split("::").any(|s| s == "cw_ownable") {
+        return "owner".to_string();
+    }
+    let name = path.rsplit("::").next().unwrap_or(path);
+    HELPER_PREFIXES
+        .iter()
+        .find_map(|p| name.strip_prefix(p))
+        .unwrap_or(name)
+        .to_string()
+}
+
+/// Roles checked, functions called and state items written in a body
+struct RoleCollector<'a> {
+    auth: &'a AuthRegistry,
+    roles: Vec<Role>,
+    calls: Vec<String>,
+    writes: Vec<String>,
+}
+
+impl<'a> RoleCollector<'a> {
+    fn new(auth: &'a AuthRegistry) -> Self {
+        Self {
+            auth,
+            roles: Vec::new(),
+            calls: Vec::new(),
+            writes: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, name: String, kind: RoleKind) {
+        if name == "sender" || self.roles.iter().any(|r| r.name == name) {
+            return;
+        }
+        self.roles.push(Role {
+            name,
+            kind,
+            item: None,
+        });
+    }
+
+    /// `sender == expected` in either order
+    fn comparison(&mut self, left: &syn::Expr, right: &syn::Expr) {
+        let expected = if mentions_sender(left) {
+            right
+        } else if mentions_sender(right) {
+            left
+        } else {
+            return;
+        };
+        if let Some(role) = role_of(expected) {
+            self.push(role, RoleKind::Address);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for RoleCollector<'_> {
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        if matches!(node.op, syn::BinOp::Eq(_) | syn::BinOp::Ne(_)) {
+            self.comparison(&node.left, &node.right);
+        }
+        syn::visit::visit_expr_binary(self, node);
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(path) = node.func.as_ref() {
+            let full_path = path
+                .path
+                .segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::");
+            if self.auth.is_helper_call(&full_path) {
+                self.push(helper_role(&full_path), RoleKind::Helper);
+            }
+            if let Some(last) = path.path.segments.last() {
+                self.calls.push(last.ident.to_string());
+            }
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        let method = node.method.to_string();
+        if self.auth.is_helper_method(&method) {
+            self.push(helper_role(&method), RoleKind::Helper);
+        }
+        // OPERATORS.has(deps.storage, &info.sender), config.admins.contains(&info.sender)
+        if MEMBERSHIP_METHODS.contains(&method.as_str()) && node.args.iter().any(mentions_sender) {
+            if let Some(role) = role_of(&node.receiver).filter(|r| is_role_name(r)) {
+                self.push(role, RoleKind::Membership);
+            }
+        }
+        if WRITE_METHODS.contains(&method.as_str()) {
+            if let syn::Expr::Path(path) = node.receiver.as_ref() {
+                if let Some(last) = path.path.segments.last() {
+                    self.writes.push(last.ident.to_string());
+                }
+            }
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        let Ok(args) =
+            node.parse_body_with(Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated)
+        else {
+            return;
+        };
+        let name = node
+            .path
+            .segments
+            .last()
+            .map(|s| s.ident.to_string())
+            .unwrap_or_default();
+        if self.auth.is_assert_macro(&name) && name.ends_with("_eq") && args.len() >= 2 {
+            self.comparison(&args[0], &args[1]);
+        }
+        for arg in &args {
+            self.visit_expr(arg);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn matrix(source: &str) -> PermissionMatrix {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::new();
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        PermissionMatrix::build(&ctx)
+    }
+
+    const CONTRACT: &str = r#"
+        pub struct Config { pub owner: Addr, pub fee_bps: u64 }
+        pub const CONFIG: Item<Config> = Item::new("config");
+        pub const MINTER: Item<Addr> = Item::new("minter");
+        pub const OPERATORS: Map<&Addr, Empty> = Map::new("operators");
+
+        #[cw_serde]
+        pub enum ExecuteMsg {
+            Mint { amount: Uint128 },
+            SetFee { fee_bps: u64 },
+            AddOperator { operator: String },
+            Deposit {},
+        }
+
+        #[entry_point]
+        pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+            -> Result<Response, ContractError> {
+            match msg {
+                ExecuteMsg::Mint { amount } => execute_mint(deps, info, amount),
+                ExecuteMsg::SetFee { fee_bps } => {
+                    let mut config = CONFIG.load(deps.storage)?;
+                    config.fee_bps = fee_bps;
+                    CONFIG.save(deps.storage, &config)?;
+                    Ok(Response::new())
+                }
+                ExecuteMsg::AddOperator { operator } => execute_add_operator(deps, info, operator),
+                ExecuteMsg::Deposit {} => execute_deposit(deps, info),
+            }
+        }
+
+        fn execute_mint(deps: DepsMut, info: MessageInfo, amount: Uint128)
+            -> Result<Response, ContractError> {
+            let minter = MINTER.load(deps.storage)?;
+            ensure_eq!(info.sender, minter, ContractError::Unauthorized {});
+            Ok(Response::new())
+        }
+
+        fn execute_add_operator(deps: DepsMut, info: MessageInfo, operator: String)
+            -> Result<Response, ContractError> {
+            let config = CONFIG.load(deps.storage)?;
+            if info.sender != config.owner {
+                return Err(ContractError::Unauthorized {});
+            }
+            let operator = deps.api.addr_validate(&operator)?;
+            OPERATORS.save(deps.storage, &operator, &Empty {})?;
+            Ok(Response::new())
+        }
+
+        fn execute_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+            if !OPERATORS.has(deps.storage, &info.sender) {
+                return Err(ContractError::Unauthorized {});
+            }
+            Ok(Response::new())
+        }
+    "#;
+
+    #[test]
+    fn test_infers_roles_from_state() {
+        let matrix = matrix(CONTRACT);
+        let roles: Vec<(&str, RoleKind)> = matrix
+            .roles
+            .iter()
+            .map(|r| (r.name.as_str(), r.kind))
+            .collect();
+        assert!(roles.contains(&("owner", RoleKind::Address)));
+        assert!(roles.contains(&("minter", RoleKind::Address)));
+        assert!(roles.contains(&("operators", RoleKind::Membership)));
+    }
+
+    #[test]
+    fn test_maps_variants_to_roles() {
+        let matrix = matrix(CONTRACT);
+        let roles_of = |variant: &str| {
+            matrix
+                .variants
+                .iter()
+                .find(|v| v.variant == variant)
+                .map(|v| v.roles.clone())
+                .unwrap()
+        };
+        assert_eq!(roles_of("Mint"), ["minter"]);
+        assert_eq!(roles_of("AddOperator"), ["owner"]);
+        assert_eq!(roles_of("Deposit"), ["operators"]);
+        assert!(roles_of("SetFee").is_empty());
+
+        let flagged: Vec<&str> = matrix.flagged().map(|v| v.variant.as_str()).collect();
+        assert_eq!(flagged, ["SetFee"]);
+    }
+}
@@ -0,0 +1,313 @@
+//! Compare extracted message types against checked-in `cosmwasm-schema` JSON
+//! files, to catch schemas that were not regenerated after message changes.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::ast::{ContractInfo, SourceSpan};
+use crate::finding::{Confidence, Finding, Severity, SourceLocation};
+
+/// Detector name used for schema drift findings
+pub const SCHEMA_DRIFT: &str = "schema-drift";
+
+/// Message shape as written in a schema or in code: variant (or `""` for a
+/// struct message) -> field names
+type MessageShape = BTreeMap<String, BTreeSet<String>>;
+
+/// Serde's `rename_all = "snake_case"`, which `#[cw_serde]` applies to variants
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, ch) in name.char_indices() {
+        if i > 0 && ch.is_uppercase() {
+            snake.push('_');
+        }
+        snake.push(ch.to_ascii_lowercase());
+    }
+    snake
+}
+
+fn field_names(schema: &Value) -> BTreeSet<String> {
+    schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|props| props.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Shape of a message schema: `oneOf` variants for enums, `properties` for structs
+fn schema_shape(schema: &Value) -> MessageShape {
+    let mut shape = MessageShape::new();
+    let Some(variants) = schema.get("oneOf").and_then(Value::as_array) else {
+        shape.insert(String::new(), field_names(schema));
+        return shape;
+    };
+    for variant in variants {
+        // Unit variants: `{"type": "string", "enum": ["a", "b"]}`
+        if let Some(names) = variant.get("enum").and_then(Value::as_array) {
+            for name in names.iter().filter_map(Value::as_str) {
+                shape.insert(name.to_string(), BTreeSet::new());
+            }
+            continue;
+        }
+        // Struct variants: `{"type": "object", "properties": {"name": {..}}}`
+        if let Some(props) = variant.get("properties").and_then(Value::as_object) {
+            for (name, inner) in props {
+                shape.insert(name.clone(), field_names(inner));
+            }
+        }
+    }
+    shape
+}
+
+/// Message schemas in `dir` and its `raw/` subdirectory, keyed by title
+/// (the Rust type name). Reads both combined `<contract>.json` files and
+/// per-message files.
+fn load_schemas(dir: &Path) -> Result<BTreeMap<String, (PathBuf, Value)>> {
+    let mut schemas = BTreeMap::new();
+    let mut files = Vec::new();
+    for d in [dir.to_path_buf(), dir.join("raw")] {
+        let Ok(entries) = std::fs::read_dir(&d) else {
+            continue;
+        };
+        files.extend(
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json")),
+        );
+    }
+    if files.is_empty() {
+        anyhow::bail!("No schema .json files found in: {}", dir.display());
+    }
+    files.sort();
+
+    for file in files {
+        let content = std::fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read: {}", file.display()))?;
+        let json: Value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse: {}", file.display()))?;
+        // Combined files nest one schema per entry point
+        let nested = ["instantiate", "execute", "query", "migrate", "sudo"]
+            .iter()
+            .filter_map(|key| json.get(*key))
+            .filter(|v| v.is_object());
+        for schema in std::iter::once(&json).chain(nested) {
+            if let Some(title) = schema.get("title").and_then(Value::as_str) {
+                schemas.insert(title.to_string(), (file.clone(), schema.clone()));
+            }
+        }
+    }
+    Ok(schemas)
+}
+
+/// Message types of the contract that a schema may describe, with their shape
+fn code_messages(contract: &ContractInfo) -> Vec<(String, MessageShape, &SourceSpan)> {
+    let mut messages = Vec::new();
+    for msg in &contract.message_enums {
+        let shape = msg
+            .variants
+            .iter()
+            .map(|v| {
+                let fields = v
+                    .fields
+                    .iter()
+                    .filter(|f| !f.name.starts_with('_'))
+                    .map(|f| f.name.trim_start_matches("r#").to_string())
+                    .collect();
+                (snake_case(&v.name), fields)
+            })
+            .collect();
+        messages.push((msg.name.clone(), shape, &msg.span));
+    }
+    for def in &contract.structs {
+        if def.name.ends_with("Msg") {
+            let fields = def
+                .fields
+                .iter()
+                .map(|f| f.name.trim_start_matches("r#").to_string())
+                .collect();
+            messages.push((
+                def.name.clone(),
+                MessageShape::from([(String::new(), fields)]),
+                &def.span,
+            ));
+        }
+    }
+    messages
+}
+
+fn drift_finding(message: &str, summary: String, schema_file: &Path, span: &SourceSpan) -> Finding {
+    Finding {
+        detector_name: SCHEMA_DRIFT.to_string(),
+        title: format!("Schema for `{message}` is out of date: {summary}"),
+        description: format!(
+            "`{message}` no longer matches `{}`: {summary}. Clients, indexers and \
+             frontends generated from the schema will send or expect the wrong messages.",
+            schema_file.display()
+        ),
+        severity: Severity::Medium,
+        confidence: Confidence::High,
+        locations: vec![SourceLocation {
+            file: span.file.clone(),
+            start_line: span.start_line,
+            end_line: span.end_line,
+            start_col: span.start_col,
+            end_col: span.end_col,
+            snippet: None,
+            byte_range: None,
+        }],
+        recommendation: Some(
+            "Regenerate the schema (e.g. `cargo schema`) and commit the result.".to_string(),
+        ),
+        fix: None,
+        ..Default::default()
+    }
+}
+
+/// Compare the contract's message types against the schemas in `dir`. Message
+/// types without a schema of the same title are not checked.
+pub fn check_schema_drift(contract: &ContractInfo, dir: &Path) -> Result<Vec<Finding>> {
+    let schemas = load_schemas(dir)?;
+    let mut findings = Vec::new();
+
+    for (name, code, span) in code_messages(contract) {
+        let Some((file, schema)) = schemas.get(&name) else {
+            continue;
+        };
+        let documented = schema_shape(schema);
+
+        for variant in code.keys().filter(|v| !documented.contains_key(*v)) {
+            findings.push(drift_finding(
+                &name,
+                format!("variant `{variant}` is missing from the schema"),
+                file,
+                span,
+            ));
+        }
+        for variant in documented.keys().filter(|v| !code.contains_key(*v)) {
+            findings.push(drift_finding(
+                &name,
+                format!("schema lists removed variant `{variant}`"),
+                file,
+                span,
+            ));
+        }
+        for (variant, fields) in &code {
+            let Some(schema_fields) = documented.get(variant) else {
+                continue;
+            };
+            let location = if variant.is_empty() {
+                String::new()
+            } else {
+                format!(" of `{variant}`")
+            };
+            for field in fields.difference(schema_fields) {
+                findings.push(drift_finding(
+                    &name,
+                    format!("field `{field}`{location} is missing from the schema"),
+                    file,
+                    span,
+                ));
+            }
+            for field in schema_fields.difference(fields) {
+                findings.push(drift_finding(
+                    &name,
+                    format!("schema lists removed field `{field}`{location}"),
+                    file,
+                    span,
+                ));
+            }
+        }
+    }
+
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cosmwasm-guard-test-{name}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn contract(source: &str) -> ContractInfo {
+        ContractVisitor::extract(PathBuf::from("msg.rs"), parse_source(source).unwrap())
+    }
+
+    const SOURCE: &str = r#"
+        pub struct InstantiateMsg {
+            pub owner: String,
+        }
+        pub enum ExecuteMsg {
+            TransferFrom { owner: String, recipient: String, amount: Uint128 },
+            Pause {},
+            Burn { amount: Uint128 },
+        }
+    "#;
+
+    #[test]
+    fn test_combined_schema_drift() {
+        let dir = scratch_dir("schema-drift");
+        let schema = serde_json::json!({
+            "contract_name": "token",
+            "instantiate": {
+                "title": "InstantiateMsg",
+                "type": "object",
+                "properties": { "owner": {}, "cw20_code_id": {} }
+            },
+            "execute": {
+                "title": "ExecuteMsg",
+                "oneOf": [
+                    { "type": "object", "properties": {
+                        "transfer_from": { "properties": { "owner": {}, "recipient": {}, "amount": {} } }
+                    } },
+                    { "type": "object", "properties": { "pause": { "properties": {} } } },
+                    { "type": "object", "properties": { "mint": { "properties": { "amount": {} } } } }
+                ]
+            },
+            "query": null
+        });
+        std::fs::write(dir.join("token.json"), schema.to_string()).unwrap();
+
+        let findings = check_schema_drift(&contract(SOURCE), &dir).unwrap();
+        let titles: Vec<&str> = findings.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(findings.len(), 3, "{titles:?}");
+        assert!(titles
+            .iter()
+            .any(|t| t.contains("`InstantiateMsg`") && t.contains("removed field `cw20_code_id`")));
+        assert!(titles
+            .iter()
+            .any(|t| t.contains("variant `burn` is missing")));
+        assert!(titles.iter().any(|t| t.contains("removed variant `mint`")));
+    }
+
+    #[test]
+    fn test_up_to_date_raw_schemas() {
+        let dir = scratch_dir("schema-current");
+        std::fs::create_dir_all(dir.join("raw")).unwrap();
+        let execute = serde_json::json!({
+            "title": "ExecuteMsg",
+            "oneOf": [
+                { "type": "string", "enum": ["pause"] },
+                { "type": "object", "properties": {
+                    "transfer_from": { "properties": { "owner": {}, "recipient": {}, "amount": {} } },
+                } },
+                { "type": "object", "properties": { "burn": { "properties": { "amount": {} } } } }
+            ]
+        });
+        std::fs::write(dir.join("raw/execute.json"), execute.to_string()).unwrap();
+
+        assert!(check_schema_drift(&contract(SOURCE), &dir)
+            .unwrap()
+            .is_empty());
+        assert!(check_schema_drift(&contract(SOURCE), &dir.join("missing")).is_err());
+    }
+}
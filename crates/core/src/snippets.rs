@@ -0,0 +1,171 @@
+//! Limits and redaction applied to the source snippets embedded in findings,
+//! configured under `[snippets]`. Reports are often uploaded to third-party
+//! services, and test fixtures can hold keys or seed phrases in string literals.
+
+use serde::{Deserialize, Serialize};
+
+/// Replaces the contents of redacted string literals
+pub const REDACTED: &str = "<redacted>";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnippetConfig {
+    /// Keep at most this many lines of each snippet; 0 drops snippets
+    pub max_lines: Option<usize>,
+    /// Cut longer lines to this many characters
+    pub max_line_length: Option<usize>,
+    /// Replace the contents of string literals with `<redacted>`
+    pub redact_strings: bool,
+}
+
+impl SnippetConfig {
+    /// `snippet` with the configured redaction and limits applied; None when
+    /// snippets are disabled
+    pub fn apply(&self, snippet: &str) -> Option<String> {
+        let text = if self.redact_strings {
+            redact_string_literals(snippet)
+        } else {
+            snippet.to_string()
+        };
+        let total = text.lines().count();
+        let keep = self.max_lines.unwrap_or(total);
+        if keep == 0 {
+            return None;
+        }
+        let mut lines: Vec<String> = text
+            .lines()
+            .take(keep)
+            .map(|line| match self.max_line_length {
+                Some(max) if line.chars().count() > max => {
+                    let mut cut: String = line.chars().take(max).collect();
+                    cut.push('…');
+                    cut
+                }
+                _ => line.to_string(),
+            })
+            .collect();
+        if total > keep {
+            lines.push(format!("… {} more lines", total - keep));
+        }
+        Some(lines.join("\n"))
+    }
+}
+
+/// `source` with the contents of every string literal (plain, byte, C and
+/// raw) replaced by [`REDACTED`]. Comments and char literals are left as-is;
+/// a literal left open at the end of the text is redacted to the end.
+pub fn redact_string_literals(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::with_capacity(source.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        // Line comments may contain quotes that open no literal
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| c == '\n')
+                .map_or(chars.len(), |n| i + n);
+            out.extend(&chars[i..end]);
+            i = end;
+            continue;
+        }
+        // Char literals such as '"'; a quote not closed two or three chars on
+        // is a lifetime
+        if c == '\'' {
+            let len = match chars.get(i + 1) {
+                Some('\\') => chars
+                    .get(i + 3..)
+                    .and_then(|rest| rest.iter().take(10).position(|&c| c == '\''))
+                    .map(|n| n + 4),
+                Some(_) if chars.get(i + 2) == Some(&'\'') => Some(3),
+                _ => None,
+            };
+            let end = i + len.unwrap_or(1);
+            out.extend(&chars[i..end]);
+            i = end;
+            continue;
+        }
+        // Raw strings: r"..", r#".."#, br".."
+        let prefix = usize::from(matches!(c, 'b' | 'c') && chars.get(i + 1) == Some(&'r'));
+        if chars[i + prefix] == 'r' && !continues_ident(&chars, i) {
+            let hashes = chars[i + prefix + 1..]
+                .iter()
+                .take_while(|&&c| c == '#')
+                .count();
+            let open = i + prefix + 1 + hashes;
+            if chars.get(open) == Some(&'"') {
+                let closing: Vec<char> = std::iter::once('"')
+                    .chain(std::iter::repeat_n('#', hashes))
+                    .collect();
+                let close = (open + 1..chars.len())
+                    .find(|&j| chars[j..].starts_with(&closing))
+                    .unwrap_or(chars.len());
+                out.extend(&chars[i..=open]);
+                out.push_str(REDACTED);
+                if close < chars.len() {
+                    out.extend(&closing);
+                }
+                i = close + closing.len();
+                continue;
+            }
+        }
+        if c == '"' {
+            let mut j = i + 1;
+            while j < chars.len() && chars[j] != '"' {
+                j += if chars[j] == '\\' { 2 } else { 1 };
+            }
+            out.push('"');
+            out.push_str(REDACTED);
+            if j < chars.len() {
+                out.push('"');
+            }
+            i = j + 1;
+            continue;
+        }
+        out.push(c);
+        i += 1;
+    }
+    out
+}
+
+/// Whether the character at `i` continues an identifier, as the `r` in `bar"`
+fn continues_ident(chars: &[char], i: usize) -> bool {
+    i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacts_string_literals() {
+        let source = r####"let seed = "abandon \"ability\" able"; // "kept"
+let raw = r#"key "x""#; let c = '"'; let q = '\''; let b = b"bytes"; fn f<'a>(s: &'a str) {}"####;
+        assert_eq!(
+            redact_string_literals(source),
+            r####"let seed = "<redacted>"; // "kept"
+let raw = r#"<redacted>"#; let c = '"'; let q = '\''; let b = b"<redacted>"; fn f<'a>(s: &'a str) {}"####
+        );
+        assert_eq!(redact_string_literals("\"open\nliteral"), "\"<redacted>");
+    }
+
+    #[test]
+    fn test_limits_lines_and_length() {
+        let config = SnippetConfig {
+            max_lines: Some(2),
+            max_line_length: Some(5),
+            redact_strings: false,
+        };
+        assert_eq!(
+            config.apply("first line\nsé\nthird\nfourth").unwrap(),
+            "first…\nsé\n… 2 more lines"
+        );
+        let none = SnippetConfig {
+            max_lines: Some(0),
+            ..SnippetConfig::default()
+        };
+        assert_eq!(none.apply("code"), None);
+        assert_eq!(SnippetConfig::default().apply("a\nb").unwrap(), "a\nb");
+    }
+}
@@ -0,0 +1,323 @@
+//! Storage access hot spots: for every state item, the handlers that load and
+//! store it, how many load and store instructions touch it and through how
+//! many distinct code paths. An item every handler touches (a single `CONFIG`
+//! holding unrelated settings) is a refactoring candidate, and each load of
+//! it is gas paid on every call.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use serde::Serialize;
+use syn::visit::Visit;
+
+use crate::ast::MessageKind;
+use crate::detector::AnalysisContext;
+use crate::roles::ArmCollector;
+use crate::surface;
+
+/// Accesses of one state item across the contract's handlers
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemAccess {
+    pub item: String,
+    /// Handlers loading the item: execute and query variants
+    /// (`ExecuteMsg::Transfer`), and entry points for their code outside
+    /// any variant's handler
+    pub readers: Vec<String>,
+    /// Handlers storing the item
+    pub writers: Vec<String>,
+    /// Load instructions of the item in the whole contract
+    pub loads: usize,
+    /// Store instructions (`save`, `update`) of the item
+    pub stores: usize,
+    /// Distinct (handler, accessing function) routes to the item
+    pub paths: usize,
+}
+
+impl ItemAccess {
+    /// Handlers loading or storing the item
+    pub fn handlers(&self) -> usize {
+        self.readers
+            .iter()
+            .chain(&self.writers)
+            .collect::<BTreeSet<_>>()
+            .len()
+    }
+}
+
+/// Load and store methods, as the IR builder recognizes them
+const LOADS: &[&str] = &["load", "may_load"];
+const STORES: &[&str] = &["save", "update"];
+
+/// One load (`true`) or store of an item by a function, on behalf of a handler
+type Access = (String, String, bool);
+
+/// Loads and stores of state items written out in a match arm
+struct InlineAccesses(Vec<(String, bool)>);
+
+impl<'ast> Visit<'ast> for InlineAccesses {
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        let method = node.method.to_string();
+        let is_load = LOADS.contains(&method.as_str());
+        if is_load || STORES.contains(&method.as_str()) {
+            if let syn::Expr::Path(path) = node.receiver.as_ref() {
+                if let Some(last) = path.path.segments.last() {
+                    self.0.push((last.ident.to_string(), is_load));
+                }
+            }
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+}
+
+fn function_accesses(ctx: &AnalysisContext, function: &str) -> Vec<Access> {
+    let reads = ctx.storage_reads(function).iter().map(|r| (&r.item, true));
+    let writes = ctx.storage_writes(function).iter().map(|w| (&w.item, false));
+    reads
+        .chain(writes)
+        .map(|(item, is_load)| (function.to_string(), item.clone(), is_load))
+        .collect()
+}
+
+/// Handlers in declaration order with their accesses: execute and query
+/// variants with those of their match arm and the functions it calls, then
+/// entry points with the rest of the code they reach
+fn handlers(ctx: &AnalysisContext) -> Vec<(String, Vec<Access>)> {
+    let messages: Vec<_> = ctx
+        .contract
+        .message_enums
+        .iter()
+        .filter(|e| matches!(e.kind, MessageKind::Execute | MessageKind::Query))
+        .collect();
+    let enums: Vec<&str> = messages.iter().map(|e| e.name.as_str()).collect();
+    let mut variants: Vec<(String, Vec<Access>)> = messages
+        .iter()
+        .flat_map(|e| e.variants.iter().map(|v| (format!("{}::{}", e.name, v.name), Vec::new())))
+        .collect();
+    // Functions and arm accesses credited to a variant, not its entry point
+    let mut claimed: HashSet<String> = HashSet::new();
+    let mut inlined: Vec<Access> = Vec::new();
+
+    for (handler, functions) in surface::coverage(&[], ctx)
+        .into_iter()
+        .filter(|v| v.resolved())
+        .map(|v| (v.variant, v.handlers))
+    {
+        if let Some((_, accesses)) = variants.iter_mut().find(|(name, _)| *name == handler) {
+            for function in &functions {
+                accesses.extend(function_accesses(ctx, function));
+            }
+        }
+        claimed.extend(functions);
+    }
+    for func in &ctx.contract.functions {
+        let Some(body) = &func.body else { continue };
+        let mut arms = ArmCollector {
+            enums: &enums,
+            arms: Vec::new(),
+        };
+        arms.visit_block(body);
+        for (message, variant, arm) in arms.arms {
+            let name = format!("{message}::{variant}");
+            let mut inline = InlineAccesses(Vec::new());
+            inline.visit_expr(&arm.body);
+            let inline = inline
+                .0
+                .into_iter()
+                .map(|(item, is_load)| (func.name.clone(), item, is_load));
+            if let Some((_, accesses)) = variants.iter_mut().find(|(n, _)| *n == name) {
+                let inline: Vec<Access> = inline.collect();
+                inlined.extend(inline.iter().cloned());
+                accesses.extend(inline);
+            }
+        }
+    }
+
+    let mut handlers: Vec<(String, Vec<Access>)> = variants
+        .into_iter()
+        .filter(|(_, accesses)| !accesses.is_empty())
+        .collect();
+    for ep in &ctx.contract.entry_points {
+        let mut reach: Vec<String> = ctx
+            .call_graph()
+            .reachable_from(&[ep.name.as_str()])
+            .into_iter()
+            .filter(|f| !claimed.contains(f))
+            .collect();
+        reach.sort();
+        let mut accesses = Vec::new();
+        for function in reach {
+            for access in function_accesses(ctx, &function) {
+                // Accesses in a variant's arm belong to the variant
+                match inlined.iter().position(|a| *a == access) {
+                    Some(i) => {
+                        inlined.swap_remove(i);
+                    }
+                    None => accesses.push(access),
+                }
+            }
+        }
+        handlers.push((ep.name.clone(), accesses));
+    }
+    handlers
+}
+
+/// Accesses of every state item: declared items in declaration order, then
+/// items only seen in loads and stores
+pub fn storage_access(ctx: &AnalysisContext) -> Vec<ItemAccess> {
+    let mut items: BTreeMap<&str, ItemAccess> = BTreeMap::new();
+    let mut order: Vec<&str> = ctx
+        .contract
+        .state_items
+        .iter()
+        .map(|item| item.name.as_str())
+        .collect();
+    for name in &order {
+        items.insert(name, new_access(name));
+    }
+
+    for func in &ctx.ir.functions {
+        for read in ctx.storage_reads(&func.name) {
+            if !items.contains_key(read.item.as_str()) {
+                order.push(&read.item);
+            }
+            items
+                .entry(&read.item)
+                .or_insert_with(|| new_access(&read.item))
+                .loads += 1;
+        }
+        for write in ctx.storage_writes(&func.name) {
+            if !items.contains_key(write.item.as_str()) {
+                order.push(&write.item);
+            }
+            items
+                .entry(&write.item)
+                .or_insert_with(|| new_access(&write.item))
+                .stores += 1;
+        }
+    }
+
+    for (handler, accesses) in handlers(ctx) {
+        let mut routes: BTreeSet<(&str, &str)> = BTreeSet::new();
+        for (function, item, is_load) in &accesses {
+            let Some(access) = items.get_mut(item.as_str()) else {
+                continue;
+            };
+            let list = if *is_load {
+                &mut access.readers
+            } else {
+                &mut access.writers
+            };
+            if list.last() != Some(&handler) {
+                list.push(handler.clone());
+            }
+            if routes.insert((item, function)) {
+                access.paths += 1;
+            }
+        }
+    }
+
+    order
+        .into_iter()
+        .filter_map(|name| items.remove(name))
+        .collect()
+}
+
+fn new_access(item: &str) -> ItemAccess {
+    ItemAccess {
+        item: item.to_string(),
+        readers: Vec::new(),
+        writers: Vec::new(),
+        loads: 0,
+        stores: 0,
+        paths: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_item_access_per_handler() {
+        let source = r#"
+pub enum ExecuteMsg { Transfer { to: String }, Pause {} }
+pub enum QueryMsg { Config {} }
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
+pub const UNUSED: Item<u64> = Item::new("unused");
+
+#[entry_point]
+pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg) -> StdResult<Response> {
+    CONFIG.save(deps.storage, &Config::default())?;
+    Ok(Response::new())
+}
+
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Transfer { to } => transfer(deps, to),
+        ExecuteMsg::Pause {} => {
+            let mut config = CONFIG.load(deps.storage)?;
+            config.paused = true;
+            CONFIG.save(deps.storage, &config)?;
+            Ok(Response::new())
+        }
+    }
+}
+
+fn transfer(deps: DepsMut, to: String) -> StdResult<Response> {
+    ensure_not_paused(deps.as_ref())?;
+    let balance = BALANCES.load(deps.storage, &to)?;
+    BALANCES.save(deps.storage, &to, &balance)?;
+    Ok(Response::new())
+}
+
+fn ensure_not_paused(deps: Deps) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(())
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<Config> {
+    CONFIG.load(deps.storage)
+}
+"#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([(PathBuf::from("test.rs"), source.to_string())]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+
+        let items = storage_access(&ctx);
+        let names: Vec<&str> = items.iter().map(|i| i.item.as_str()).collect();
+        assert_eq!(names, ["CONFIG", "BALANCES", "UNUSED"]);
+
+        let config = &items[0];
+        assert_eq!(
+            config.readers,
+            ["ExecuteMsg::Transfer", "ExecuteMsg::Pause", "QueryMsg::Config"]
+        );
+        assert_eq!(config.writers, ["ExecuteMsg::Pause", "instantiate"]);
+        assert_eq!((config.loads, config.stores), (3, 2));
+        // ensure_not_paused, the Pause arm in execute, query_config, instantiate
+        assert_eq!(config.paths, 4);
+        assert_eq!(config.handlers(), 4);
+
+        let balances = &items[1];
+        assert_eq!(balances.readers, ["ExecuteMsg::Transfer"]);
+        assert_eq!(balances.writers, ["ExecuteMsg::Transfer"]);
+        assert_eq!(balances.paths, 1);
+
+        assert_eq!(items[2].handlers(), 0);
+    }
+}
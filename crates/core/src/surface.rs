@@ -0,0 +1,433 @@
+//! Findings grouped by the externally callable surface they affect: the entry
+//! point reaching them and, for execute messages, the variant whose handler
+//! contains them. Audit reports are organized around this surface, not files,
+//! and findings in code no entry point reaches are mostly noise.
+//!
+//! Coverage turns this around: for every execute and query variant, which
+//! detectors found something in its handler, and which variants have no
+//! handler the analysis could resolve at all.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+use syn::visit::Visit;
+
+use crate::ast::{MessageKind, SourceSpan};
+use crate::detector::AnalysisContext;
+use crate::finding::Finding;
+use crate::ranking::enclosing_function;
+use crate::roles::ArmCollector;
+
+/// Findings under one entry point, or one message variant it dispatches
+#[derive(Debug, Clone, Serialize)]
+pub struct SurfaceGroup {
+    /// Entry point function; None for findings no entry point reaches
+    pub entry_point: Option<String>,
+    /// Message variant, e.g. `ExecuteMsg::Transfer`; None for code the entry
+    /// point reaches outside any variant's handler
+    pub variant: Option<String>,
+    /// Indices into the grouped findings, in their original order
+    pub findings: Vec<usize>,
+}
+
+/// `text` mentions `name` as a whole path, e.g. `ExecuteMsg::Send` but not
+/// `ExecuteMsg::SendAll`
+fn mentions(text: &str, name: &str) -> bool {
+    text.match_indices(name).any(|(i, _)| {
+        !text[i + name.len()..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_')
+    })
+}
+
+/// Whether `finding` is about the variant `name`: its title names it, it lies
+/// in the dispatching `arm`, or in one of the `handlers` the arm calls
+fn affects(
+    finding: &Finding,
+    function: Option<&str>,
+    name: &str,
+    arm: Option<&SourceSpan>,
+    handlers: &[String],
+) -> bool {
+    let loc = finding.locations.first();
+    mentions(&finding.title, name)
+        || arm.zip(loc).is_some_and(|(arm, loc)| {
+            arm.file == loc.file && (arm.start_line..=arm.end_line).contains(&loc.start_line)
+        })
+        || function.is_some_and(|f| handlers.iter().any(|h| h == f))
+}
+
+/// Group `findings` by entry point and execute variant. A finding in code
+/// shared by several variants or entry points is listed under each.
+pub fn group_by_entry_point(findings: &[Finding], ctx: &AnalysisContext) -> Vec<SurfaceGroup> {
+    let matrix = ctx.permissions();
+    let entries = &ctx.contract.entry_points;
+    let reach: Vec<HashSet<String>> = entries
+        .iter()
+        .map(|ep| ctx.call_graph().reachable_from(&[ep.name.as_str()]))
+        .collect();
+    // Entry point dispatching each variant
+    let variant_entry: Vec<Option<usize>> = matrix
+        .variants
+        .iter()
+        .map(|v| {
+            let dispatcher = v.dispatcher.as_ref()?;
+            reach.iter().position(|r| r.contains(dispatcher))
+        })
+        .collect();
+
+    // (entry point, variant) -> findings
+    let mut groups: BTreeMap<(Option<usize>, Option<usize>), Vec<usize>> = BTreeMap::new();
+    for (index, finding) in findings.iter().enumerate() {
+        let function = enclosing_function(finding, ctx);
+
+        let variants: Vec<usize> = matrix
+            .variants
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| {
+                let name = format!("{}::{}", v.message, v.variant);
+                affects(finding, function, &name, v.arm.as_ref(), &v.handlers)
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut keys: Vec<(Option<usize>, Option<usize>)> = variants
+            .iter()
+            .map(|&v| (variant_entry[v], Some(v)))
+            .collect();
+        if keys.is_empty() {
+            keys = reach
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| function.is_some_and(|f| r.contains(f)))
+                .map(|(i, _)| (Some(i), None))
+                .collect();
+        }
+        if keys.is_empty() {
+            keys.push((None, None));
+        }
+        for key in keys {
+            groups.entry(key).or_default().push(index);
+        }
+    }
+
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    // Entry points in declaration order, unreached code last; within an entry
+    // point, variants first and its other code after them
+    groups.sort_by_key(|((entry, variant), _)| {
+        (entry.is_none(), *entry, variant.is_none(), *variant)
+    });
+    groups
+        .into_iter()
+        .map(|((entry, variant), findings)| SurfaceGroup {
+            entry_point: entry.map(|i| entries[i].name.clone()),
+            variant: variant.map(|i| {
+                let v = &matrix.variants[i];
+                format!("{}::{}", v.message, v.variant)
+            }),
+            findings,
+        })
+        .collect()
+}
+
+/// Detectors that found something in one execute or query variant's handler
+#[derive(Debug, Clone, Serialize)]
+pub struct VariantCoverage {
+    /// e.g. `QueryMsg::Balance`
+    pub variant: String,
+    /// Function whose `match` dispatches the variant; None when no match arm
+    /// for it was found, a gap in what the analysis looked at
+    pub dispatcher: Option<String>,
+    /// Contract functions the arm calls, directly or through other calls
+    pub handlers: Vec<String>,
+    /// Findings per detector against the arm or its handlers
+    pub detectors: BTreeMap<String, usize>,
+}
+
+impl VariantCoverage {
+    pub fn resolved(&self) -> bool {
+        self.dispatcher.is_some()
+    }
+}
+
+/// Names of the functions a body calls
+struct CallCollector(Vec<String>);
+
+impl<'ast> Visit<'ast> for CallCollector {
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(path) = node.func.as_ref() {
+            if let Some(last) = path.path.segments.last() {
+                self.0.push(last.ident.to_string());
+            }
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+}
+
+/// Every execute and query variant, in declaration order, with the detectors
+/// that produced `findings` against its handler
+pub fn coverage(findings: &[Finding], ctx: &AnalysisContext) -> Vec<VariantCoverage> {
+    let messages: Vec<_> = ctx
+        .contract
+        .message_enums
+        .iter()
+        .filter(|e| matches!(e.kind, MessageKind::Execute | MessageKind::Query))
+        .collect();
+    let enums: Vec<&str> = messages.iter().map(|e| e.name.as_str()).collect();
+    let mut variants: Vec<(VariantCoverage, Option<SourceSpan>)> = messages
+        .iter()
+        .flat_map(|e| {
+            e.variants.iter().map(|v| {
+                let coverage = VariantCoverage {
+                    variant: format!("{}::{}", e.name, v.name),
+                    dispatcher: None,
+                    handlers: Vec::new(),
+                    detectors: BTreeMap::new(),
+                };
+                (coverage, None)
+            })
+        })
+        .collect();
+
+    for func in &ctx.contract.functions {
+        let Some(body) = &func.body else { continue };
+        let mut arms = ArmCollector {
+            enums: &enums,
+            arms: Vec::new(),
+        };
+        arms.visit_block(body);
+        for (message, variant, arm) in arms.arms {
+            let name = format!("{message}::{variant}");
+            let Some((entry, span)) = variants.iter_mut().find(|(v, _)| v.variant == name) else {
+                continue;
+            };
+            if entry.dispatcher.is_none() {
+                entry.dispatcher = Some(func.name.clone());
+                *span = ctx.span_of(arm);
+            }
+            let mut calls = CallCollector(Vec::new());
+            calls.visit_expr(&arm.body);
+            let roots: Vec<&str> = calls.0.iter().map(String::as_str).collect();
+            let reachable = ctx.call_graph().reachable_from(&roots);
+            entry.handlers.extend(
+                ctx.contract
+                    .functions
+                    .iter()
+                    .filter(|f| reachable.contains(&f.name))
+                    .map(|f| f.name.clone()),
+            );
+            entry.handlers.sort();
+            entry.handlers.dedup();
+        }
+    }
+
+    for finding in findings {
+        let function = enclosing_function(finding, ctx);
+        for (entry, arm) in &mut variants {
+            if affects(
+                finding,
+                function,
+                &entry.variant,
+                arm.as_ref(),
+                &entry.handlers,
+            ) {
+                *entry
+                    .detectors
+                    .entry(finding.detector_name.clone())
+                    .or_default() += 1;
+            }
+        }
+    }
+    variants.into_iter().map(|(coverage, _)| coverage).collect()
+}
+
+/// Record on each finding the entry points whose call graph reaches the
+/// function containing it
+pub fn annotate_reachability(findings: &mut [Finding], ctx: &AnalysisContext) {
+    let reach: Vec<(&str, HashSet<String>)> = ctx
+        .contract
+        .entry_points
+        .iter()
+        .map(|ep| {
+            let name = ep.name.as_str();
+            (name, ctx.call_graph().reachable_from(&[name]))
+        })
+        .collect();
+    for finding in findings {
+        let Some(function) = enclosing_function(finding, ctx) else {
+            continue;
+        };
+        let mut entries: Vec<String> = reach
+            .iter()
+            .filter(|(_, r)| r.contains(function))
+            .map(|(name, _)| name.to_string())
+            .collect();
+        entries.dedup();
+        finding.reachable_from = Some(entries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{parse_source, ContractVisitor};
+    use crate::finding::{test_finding, Confidence, Severity};
+    use crate::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn finding(title: &str, line: usize) -> Finding {
+        Finding {
+            title: title.to_string(),
+            ..test_finding("test", Severity::Medium, Confidence::Medium, line)
+        }
+    }
+
+    #[test]
+    fn test_groups_by_entry_point_and_variant() {
+        let source = r#"
+pub enum ExecuteMsg { Send { to: String }, SendAll {}, Burn {} }
+
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    log(&env);
+    match msg {
+        ExecuteMsg::Send { to } => send(deps, to),
+        ExecuteMsg::SendAll {} => send(deps, info.sender.to_string()),
+        ExecuteMsg::Burn {} => {
+            let supply = SUPPLY.load(deps.storage).unwrap();
+            Ok(Response::new())
+        }
+    }
+}
+
+fn send(deps: DepsMut, to: String) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn log(env: &Env) {}
+
+fn unused() {}
+"#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([(PathBuf::from("test.rs"), source.to_string())]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+
+        let findings = vec![
+            finding("Unvalidated address: `to` in ExecuteMsg::Send", 2),
+            finding("Unwrap in burn arm", 11),
+            finding("Shared send helper", 18),
+            finding("Logging", 21),
+            finding("Dead code", 23),
+        ];
+        let groups = group_by_entry_point(&findings, &ctx);
+        let summary: Vec<(Option<&str>, Option<&str>, &[usize])> = groups
+            .iter()
+            .map(|g| {
+                (
+                    g.entry_point.as_deref(),
+                    g.variant.as_deref(),
+                    g.findings.as_slice(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Some("execute"), Some("ExecuteMsg::Send"), &[0, 2][..]),
+                (Some("execute"), Some("ExecuteMsg::SendAll"), &[2][..]),
+                (Some("execute"), Some("ExecuteMsg::Burn"), &[1][..]),
+                (Some("execute"), None, &[3][..]),
+                (None, None, &[4][..]),
+            ]
+        );
+
+        let mut findings = findings;
+        annotate_reachability(&mut findings, &ctx);
+        assert_eq!(findings[0].reachable_from, None);
+        assert_eq!(
+            findings[2].reachable_from,
+            Some(vec!["execute".to_string()])
+        );
+        assert_eq!(findings[4].reachable_from, Some(vec![]));
+    }
+
+    #[test]
+    fn test_coverage_per_variant() {
+        let source = r#"
+pub enum ExecuteMsg { Transfer { to: String }, Burn {}, Freeze {} }
+pub enum QueryMsg { Balance { address: String } }
+
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Transfer { to } => transfer(deps, to),
+        ExecuteMsg::Burn {} => Ok(Response::new()),
+    }
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Balance { address } => to_json_binary(&balance(deps, address)?),
+    }
+}
+
+fn transfer(deps: DepsMut, to: String) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn balance(deps: Deps, address: String) -> StdResult<Uint128> {
+    BALANCES.load(deps.storage, &address)
+}
+"#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([(PathBuf::from("test.rs"), source.to_string())]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+
+        let mut unwrap = finding("Unwrap in transfer", 22);
+        unwrap.detector_name = "unsafe-unwrap".to_string();
+        let findings = vec![
+            finding("Unvalidated address: `to` in ExecuteMsg::Transfer", 2),
+            unwrap,
+            finding("Balance lookup", 26),
+        ];
+        let coverage = coverage(&findings, &ctx);
+        let summary: Vec<(&str, Option<&str>, Vec<String>)> = coverage
+            .iter()
+            .map(|c| {
+                let detectors = c.detectors.iter().map(|(d, n)| format!("{d}={n}"));
+                (
+                    c.variant.as_str(),
+                    c.dispatcher.as_deref(),
+                    detectors.collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "ExecuteMsg::Transfer",
+                    Some("execute"),
+                    vec!["test=1".to_string(), "unsafe-unwrap=1".to_string()]
+                ),
+                ("ExecuteMsg::Burn", Some("execute"), vec![]),
+                ("ExecuteMsg::Freeze", None, vec![]),
+                (
+                    "QueryMsg::Balance",
+                    Some("query"),
+                    vec!["test=1".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(coverage[0].handlers, vec!["transfer".to_string()]);
+        assert!(!coverage[2].resolved());
+    }
+}
@@ -0,0 +1,125 @@
+//! Evidence chains of dataflow findings, shown by `analyze --explain-finding`.
+//! The IR carries no spans, so steps are located by scanning the function's
+//! source lines for the variable or expression they describe.
+
+use crate::detector::AnalysisContext;
+use crate::finding::{SourceLocation, TraceStep, TraceStepKind};
+use crate::ir::{BlockId, FunctionIr, SsaVar};
+
+/// Whether `line` mentions `name` as a whole identifier
+fn mentions(line: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(name).any(|(start, _)| {
+        let before = line[..start].chars().next_back();
+        let after = line[start + name.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+/// First line of `func`, from its name to the end of its body, satisfying
+/// `matches`
+pub fn find_line(
+    ctx: &AnalysisContext,
+    func: &FunctionIr,
+    matches: impl Fn(&str) -> bool,
+) -> Option<SourceLocation> {
+    let span = &func.source_span;
+    let end_line = ctx
+        .contract
+        .functions
+        .iter()
+        .find(|f| f.name == func.name && f.span.file == span.file)
+        .and_then(|f| f.body_span.as_ref())
+        .map_or(span.end_line, |body| body.end_line);
+    (span.start_line..=end_line)
+        .find(|&line| ctx.get_line(&span.file, line).is_some_and(&matches))
+        .map(|line| SourceLocation {
+            file: span.file.clone(),
+            start_line: line,
+            end_line: line,
+            start_col: 0,
+            end_col: 0,
+            snippet: None,
+            byte_range: None,
+        })
+}
+
+/// Line of `func` binding `name`: a `let`, a parameter or a `for` pattern,
+/// else its first mention (e.g. a destructured message field)
+pub fn locate(ctx: &AnalysisContext, func: &FunctionIr, name: &str) -> Option<SourceLocation> {
+    let binds = |line: &str| {
+        [
+            format!("let {name}"),
+            format!("let mut {name}"),
+            format!("for {name} in"),
+            format!("{name}:"),
+        ]
+        .iter()
+        .any(|pattern| line.contains(pattern.as_str()))
+            && mentions(line, name)
+    };
+    find_line(ctx, func, binds).or_else(|| find_line(ctx, func, |line| mentions(line, name)))
+}
+
+/// Source and propagation steps of a chain from [`crate::ir::taint_path`],
+/// skipping compiler temporaries. `origin` says where the source comes from,
+/// e.g. "is supplied by the sender".
+pub fn taint_steps(
+    ctx: &AnalysisContext,
+    func: &FunctionIr,
+    path: &[SsaVar],
+    origin: &str,
+) -> Vec<TraceStep> {
+    let mut names: Vec<&str> = path
+        .iter()
+        .map(|v| v.name.as_str())
+        .filter(|n| !n.starts_with('_'))
+        .collect();
+    names.dedup();
+
+    let mut steps = Vec::new();
+    for (i, name) in names.iter().enumerate() {
+        let (kind, message) = match i {
+            0 => (TraceStepKind::Source, format!("`{name}` {origin}")),
+            _ => (
+                TraceStepKind::Propagation,
+                format!("`{name}` is derived from `{}`", names[i - 1]),
+            ),
+        };
+        steps.push(TraceStep {
+            kind,
+            message,
+            location: locate(ctx, func, name),
+        });
+    }
+    steps
+}
+
+/// Step recording that no `guard` runs on every path to `block`
+pub fn missing_guard(
+    func: &FunctionIr,
+    block: BlockId,
+    guard: &str,
+    location: Option<SourceLocation>,
+) -> TraceStep {
+    TraceStep {
+        kind: TraceStepKind::MissingGuard,
+        message: format!(
+            "no {guard} dominates block {block} of `{}`, so a path reaches it unchecked",
+            func.name
+        ),
+        location,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mentions_whole_identifiers_only() {
+        assert!(mentions("let amount = msg.amount;", "amount"));
+        assert!(!mentions("let amount_due = 1;", "amount"));
+        assert!(!mentions("let total_amount = 1;", "amount"));
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::HashSet;
+
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::query::{self, Pattern};
+use cosmwasm_guard::ir::Instruction;
+
+/// Flags addresses persisted as `String` instead of `Addr`, and functions that
+/// re-validate an address every time it is loaded from storage. A `String`
+/// skips validation and normalization at write time, so comparisons against
+/// `info.sender` can silently fail; re-validating on load wastes gas.
+pub struct AddrStoredAsString;
+
+/// Name fragments that mark a field or storage item as holding an address
+const ADDRESS_HINTS: &[&str] = &[
+    "addr",
+    "owner",
+    "admin",
+    "recipient",
+    "receiver",
+    "sender",
+    "minter",
+    "operator",
+    "treasury",
+    "beneficiary",
+    "creator",
+    "manager",
+    "pauser",
+];
+
+fn is_address_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    ADDRESS_HINTS.iter().any(|hint| name.contains(hint)) || name.ends_with("_contract")
+}
+
+/// Whether a rendered type is `String`, `Option<String>` or `Vec<String>`
+fn is_string_type(type_name: &str) -> bool {
+    matches!(type_name, "String" | "Option<String>" | "Vec<String>")
+}
+
+impl AddrStoredAsString {
+    fn finding(&self, title: String, description: String, location: SourceLocation) -> Finding {
+        Finding {
+            detector_name: self.name().to_string(),
+            title,
+            description,
+            severity: self.severity(),
+            confidence: self.confidence(),
+            locations: vec![location],
+            recommendation: Some(
+                "Validate once with `deps.api.addr_validate` when the address enters the \
+                 contract and store the resulting `Addr`; loaded `Addr` values need no \
+                 re-validation."
+                    .to_string(),
+            ),
+            fix: None,
+            ..Default::default()
+        }
+    }
+}
+
+impl Detector for AddrStoredAsString {
+    fn name(&self) -> &str {
+        "addr-stored-as-string"
+    }
+
+    fn description(&self) -> &str {
+        "Detects addresses stored as String instead of Addr, and stored addresses re-validated on load"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Low
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        // State items whose value is a bare string address: `Item<String>`
+        for item in &ctx.contract.state_items {
+            if item.value_type.replace(' ', "") == "String" && is_address_name(&item.name) {
+                findings.push(self.finding(
+                    format!("Address stored as String in `{}`", item.name),
+                    format!(
+                        "Storage item `{}` holds an address as `String`. Unvalidated or \
+                         non-normalized strings make later comparisons with `Addr` values \
+                         unreliable.",
+                        item.name
+                    ),
+                    SourceLocation {
+                        file: item.span.file.clone(),
+                        start_line: item.span.start_line,
+                        end_line: item.span.end_line,
+                        start_col: item.span.start_col,
+                        end_col: item.span.end_col,
+                        snippet: None,
+                        byte_range: None,
+                    },
+                ));
+            }
+        }
+
+        // Address-like String fields of structs kept in storage, directly or nested
+        let stored_types = ctx.contract.stored_type_names();
+        for def in &ctx.contract.structs {
+            if !stored_types.contains(&def.name) {
+                continue;
+            }
+            for field in &def.fields {
+                if !is_address_name(&field.name) || !is_string_type(&field.type_name) {
+                    continue;
+                }
+                findings.push(self.finding(
+                    format!("Address stored as String in `{}.{}`", def.name, field.name),
+                    format!(
+                        "Field `{}` of stored type `{}` is `{}`. Store addresses as `Addr` \
+                         so they are validated once, when written.",
+                        field.name, def.name, field.type_name
+                    ),
+                    SourceLocation {
+                        file: field.span.file.clone(),
+                        start_line: field.span.start_line,
+                        end_line: field.span.end_line,
+                        start_col: field.span.start_col,
+                        end_col: field.span.end_col,
+                        snippet: None,
+                        byte_range: None,
+                    },
+                ));
+            }
+        }
+
+        // Addresses validated again after being loaded from storage
+        let revalidated = Pattern::storage_load().flows_into(Pattern::addr_validate());
+        for func in &ctx.ir.functions {
+            let mut reported = HashSet::new();
+            for found in query::find(&func.cfg, &revalidated) {
+                let Instruction::StorageLoad {
+                    storage_item: item, ..
+                } = found.instruction
+                else {
+                    continue;
+                };
+                if !reported.insert(item) {
+                    continue;
+                }
+                findings.push(self.finding(
+                    format!("Stored address re-validated in `{}`", func.name),
+                    format!(
+                        "`{}` calls `addr_validate` on an address loaded from `{}`. \
+                         Validation belongs at write time; repeating it on every load \
+                         spends gas and suggests the value is stored as `String`.",
+                        func.name, item
+                    ),
+                    SourceLocation {
+                        file: func.source_span.file.clone(),
+                        start_line: func.source_span.start_line,
+                        end_line: func.source_span.end_line,
+                        start_col: func.source_span.start_col,
+                        end_col: func.source_span.end_col,
+                        snippet: None,
+                        byte_range: None,
+                    },
+                ));
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        AddrStoredAsString.detect(&ctx)
+    }
+
+    #[test]
+    fn test_detects_string_address_fields_and_items() {
+        let source = r#"
+            pub struct Config {
+                pub owner: String,
+                pub fee_recipient: Option<String>,
+                pub denom: String,
+                pub admin: Addr,
+            }
+            pub const CONFIG: Item<Config> = Item::new("config");
+            pub const PENDING_OWNER: Item<String> = Item::new("pending_owner");
+        "#;
+        let findings = analyze(source);
+        let titles: Vec<&str> = findings.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(findings.len(), 3, "{titles:?}");
+        assert!(titles.iter().any(|t| t.contains("PENDING_OWNER")));
+        assert!(titles.iter().any(|t| t.contains("Config.owner")));
+        assert!(titles.iter().any(|t| t.contains("Config.fee_recipient")));
+    }
+
+    #[test]
+    fn test_ignores_unstored_structs() {
+        let source = r#"
+            pub struct InstantiateMsg {
+                pub owner: String,
+            }
+            pub struct Config {
+                pub owner: Addr,
+            }
+            pub const CONFIG: Item<Config> = Item::new("config");
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+
+    #[test]
+    fn test_detects_revalidation_on_load() {
+        let source = r#"
+            pub fn execute_withdraw(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+                let config = CONFIG.load(deps.storage)?;
+                let owner = deps.api.addr_validate(&config.owner)?;
+                if info.sender != owner {
+                    return Err(ContractError::Unauthorized {});
+                }
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("re-validated"));
+    }
+
+    #[test]
+    fn test_validating_message_input_is_fine() {
+        let source = r#"
+            pub fn execute_update(deps: DepsMut, new_owner: String) -> Result<Response, ContractError> {
+                let owner = deps.api.addr_validate(&new_owner)?;
+                OWNER.save(deps.storage, &owner)?;
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+}
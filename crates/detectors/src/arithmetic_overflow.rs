@@ -16,17 +16,19 @@ const WRAPPING_METHODS: &[&str] = &[
     "overflowing_mul",
 ];
 
-struct OverflowSearcher {
+struct OverflowSearcher<'c> {
+    ctx: &'c AnalysisContext<'c>,
     findings: Vec<(usize, usize, String)>,
 }
 
-impl<'ast> Visit<'ast> for OverflowSearcher {
+impl<'ast> Visit<'ast> for OverflowSearcher<'_> {
     fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
         let method = node.method.to_string();
         if WRAPPING_METHODS.contains(&method.as_str()) {
-            let span = node.method.span();
-            self.findings
-                .push((span.start().line, span.start().column, method));
+            if let Some(span) = self.ctx.span_of(&node.method) {
+                self.findings
+                    .push((span.start_line, span.start_col, method));
+            }
         }
         syn::visit::visit_expr_method_call(self, node);
     }
@@ -54,6 +56,7 @@ impl Detector for ArithmeticOverflow {
 
         for (path, ast) in ctx.raw_asts() {
             let mut searcher = OverflowSearcher {
+                ctx,
                 findings: Vec::new(),
             };
             syn::visit::visit_file(&mut searcher, ast);
@@ -77,12 +80,14 @@ impl Detector for ArithmeticOverflow {
                         start_col: *col,
                         end_col: *col,
                         snippet: None,
+                        byte_range: None,
                     }],
                     recommendation: Some(format!(
                         "Use checked arithmetic (e.g. `.checked_{}()`) instead.",
                         method.strip_prefix("wrapping_").unwrap_or(method)
                     )),
                     fix: None,
+                    ..Default::default()
                 });
             }
         }
@@ -0,0 +1,302 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use cosmwasm_guard::ast::utils::is_test_attr;
+use cosmwasm_guard::ast::FunctionInfo;
+use cosmwasm_guard::detector::{AnalysisContext, Detector};
+use cosmwasm_guard::finding::*;
+use syn::visit::Visit;
+
+/// Reports functions whose size or complexity exceeds the `[metrics]` thresholds.
+/// Not a vulnerability — helps auditors decide where to spend review effort.
+pub struct ComplexityMetrics;
+
+/// Visitor that records the largest match expression (by arm count) in a body
+struct MatchArmCounter {
+    max_arms: usize,
+}
+
+impl<'ast> Visit<'ast> for MatchArmCounter {
+    fn visit_expr_match(&mut self, node: &'ast syn::ExprMatch) {
+        self.max_arms = self.max_arms.max(node.arms.len());
+        syn::visit::visit_expr_match(self, node);
+    }
+}
+
+/// Where the bodies of test functions start: those marked as tests and all
+/// functions of `#[cfg(test)]` modules
+struct TestBodies<'a> {
+    ctx: &'a AnalysisContext<'a>,
+    in_test: bool,
+    starts: HashSet<(PathBuf, usize, usize)>,
+}
+
+impl TestBodies<'_> {
+    fn record(&mut self, attrs: &[syn::Attribute], block: &syn::Block) {
+        if self.in_test || attrs.iter().any(is_test_attr) {
+            if let Some(span) = self.ctx.span_of(block) {
+                self.starts
+                    .insert((span.file, span.start_line, span.start_col));
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for TestBodies<'_> {
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        let outer = self.in_test;
+        self.in_test |= node.attrs.iter().any(is_test_attr);
+        syn::visit::visit_item_mod(self, node);
+        self.in_test = outer;
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+        let outer = self.in_test;
+        self.in_test |= node.attrs.iter().any(is_test_attr);
+        syn::visit::visit_item_impl(self, node);
+        self.in_test = outer;
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        self.record(&node.attrs, &node.block);
+        syn::visit::visit_item_fn(self, node);
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        self.record(&node.attrs, &node.block);
+        syn::visit::visit_impl_item_fn(self, node);
+    }
+}
+
+/// Number of source lines spanned by a function body, braces included
+fn body_line_count(func: &FunctionInfo) -> usize {
+    func.body_span
+        .as_ref()
+        .map_or(0, |span| span.end_line.saturating_sub(span.start_line) + 1)
+}
+
+fn max_match_arms(body: &syn::Block) -> usize {
+    let mut counter = MatchArmCounter { max_arms: 0 };
+    syn::visit::visit_block(&mut counter, body);
+    counter.max_arms
+}
+
+/// Collect "metric (value > threshold)" descriptions for every exceeded threshold
+fn exceeded_metrics(ctx: &AnalysisContext, func: &FunctionInfo, body: &syn::Block) -> Vec<String> {
+    let thresholds = &ctx.config().metrics;
+    let mut exceeded = Vec::new();
+
+    if let Some(func_ir) = ctx.ir.get_function(&func.name) {
+        let complexity = func_ir.cfg.cyclomatic_complexity();
+        if complexity > thresholds.max_cyclomatic_complexity {
+            exceeded.push(format!(
+                "cyclomatic complexity {} (> {})",
+                complexity, thresholds.max_cyclomatic_complexity
+            ));
+        }
+
+        let writes = ctx.storage_writes(&func.name).len();
+        if writes > thresholds.max_storage_writes {
+            exceeded.push(format!(
+                "{} storage writes (> {})",
+                writes, thresholds.max_storage_writes
+            ));
+        }
+    }
+
+    let lines = body_line_count(func);
+    if lines > thresholds.max_function_lines {
+        exceeded.push(format!(
+            "{} lines (> {})",
+            lines, thresholds.max_function_lines
+        ));
+    }
+
+    let arms = max_match_arms(body);
+    if arms > thresholds.max_match_arms {
+        exceeded.push(format!(
+            "match with {} arms (> {})",
+            arms, thresholds.max_match_arms
+        ));
+    }
+
+    exceeded
+}
+
+impl Detector for ComplexityMetrics {
+    fn name(&self) -> &str {
+        "complexity-metrics"
+    }
+
+    fn description(&self) -> &str {
+        "Reports functions exceeding size and complexity thresholds to prioritize review"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Informational
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::High
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut tests = HashSet::new();
+        for (_, ast) in ctx.raw_asts() {
+            let mut bodies = TestBodies {
+                ctx,
+                in_test: false,
+                starts: HashSet::new(),
+            };
+            bodies.visit_file(ast);
+            tests.extend(bodies.starts);
+        }
+
+        let mut findings = Vec::new();
+        for func in &ctx.contract.functions {
+            let Some(body) = &func.body else { continue };
+            // Test code is not deployed, however long a test runs
+            let is_test = func.body_span.as_ref().is_some_and(|span| {
+                tests.contains(&(span.file.clone(), span.start_line, span.start_col))
+            });
+            if is_test {
+                continue;
+            }
+
+            let exceeded = exceeded_metrics(ctx, func, body);
+            if exceeded.is_empty() {
+                continue;
+            }
+
+            findings.push(Finding {
+                detector_name: self.name().to_string(),
+                title: format!("Function `{}` exceeds complexity thresholds", func.name),
+                description: format!(
+                    "Function `{}` has {}. Large or highly branched functions are harder \
+                     to audit and more likely to hide authorization or state bugs.",
+                    func.name,
+                    exceeded.join(", ")
+                ),
+                severity: Severity::Informational,
+                confidence: Confidence::High,
+                locations: vec![SourceLocation {
+                    file: func.span.file.clone(),
+                    start_line: func.span.start_line,
+                    end_line: func.span.end_line,
+                    start_col: func.span.start_col,
+                    end_col: func.span.end_col,
+                    snippet: None,
+                    byte_range: None,
+                }],
+                recommendation: Some(
+                    "Prioritize this function during review, or split it into smaller \
+                     handlers. Thresholds are configurable under `[metrics]`."
+                        .to_string(),
+                ),
+                fix: None,
+                ..Default::default()
+            });
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::config::Config;
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze_with(source: &str, config: &Config) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources).with_config(config);
+        ComplexityMetrics.detect(&ctx)
+    }
+
+    const BRANCHY: &str = r#"
+        fn execute_update(deps: DepsMut, a: bool, b: bool, c: u32) -> StdResult<Response> {
+            if a { CONFIG.save(deps.storage, &1)?; }
+            if b { STATE.save(deps.storage, &2)?; }
+            match c {
+                1 => {},
+                2 => {},
+                _ => {},
+            }
+            Ok(Response::new())
+        }
+    "#;
+
+    #[test]
+    fn test_no_finding_under_default_thresholds() {
+        let findings = analyze_with(BRANCHY, &Config::default());
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn test_reports_exceeded_thresholds() {
+        let mut config = Config::default();
+        config.metrics.max_cyclomatic_complexity = 2;
+        config.metrics.max_storage_writes = 1;
+        config.metrics.max_match_arms = 2;
+        let findings = analyze_with(BRANCHY, &config);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Informational);
+        let desc = &findings[0].description;
+        assert!(desc.contains("cyclomatic complexity"), "{desc}");
+        assert!(desc.contains("2 storage writes"), "{desc}");
+        assert!(desc.contains("match with 3 arms"), "{desc}");
+    }
+
+    #[test]
+    fn test_reports_long_function() {
+        let mut config = Config::default();
+        config.metrics.max_function_lines = 5;
+        let findings = analyze_with(BRANCHY, &config);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].description.contains("lines (> 5)"));
+    }
+
+    #[test]
+    fn test_skips_test_functions() {
+        let source = format!(
+            r#"
+            {BRANCHY}
+
+            #[cfg(test)]
+            mod tests {{
+                use super::*;
+
+                #[test]
+                fn test_update() {{
+                    let (a, b) = (true, false);
+                    if a {{ setup(); }}
+                    if b {{ setup(); }}
+                    execute_update(mock_dependencies().as_mut(), a, b, 1).unwrap();
+                }}
+            }}
+
+            #[test]
+            fn test_update_again() {{
+                if true {{ setup(); }}
+                if false {{ setup(); }}
+            }}
+        "#
+        );
+        let mut config = Config::default();
+        config.metrics.max_cyclomatic_complexity = 2;
+        let findings = analyze_with(&source, &config);
+        let titles: Vec<&str> = findings.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["Function `execute_update` exceeds complexity thresholds"]
+        );
+    }
+}
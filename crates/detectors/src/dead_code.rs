@@ -0,0 +1,342 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use cosmwasm_guard::ast::utils::{infer_entry_point_kind, is_test_attr};
+use cosmwasm_guard::ast::EntryPointKind;
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use syn::visit::Visit;
+
+/// Detects free functions no entry point reaches. Dead handlers confuse
+/// reviewers, who audit them as if they were live, and often keep unsafe
+/// patterns that were fixed in the code that replaced them.
+pub struct DeadCode;
+
+/// A free function that could be reported
+struct Candidate {
+    name: String,
+    file: PathBuf,
+    line: usize,
+    col: usize,
+}
+
+/// Collects free functions outside test code, and for every function the
+/// names it uses outside direct calls: function pointers, method calls and
+/// macro arguments, none of which the call graph records
+struct FunctionCollector<'c> {
+    ctx: &'c AnalysisContext<'c>,
+    file: PathBuf,
+    candidates: Vec<Candidate>,
+    /// Function -> names referenced outside direct calls
+    references: BTreeMap<String, HashSet<String>>,
+}
+
+fn allows_dead_code(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("allow")
+            && attr
+                .meta
+                .require_list()
+                .is_ok_and(|list| list.tokens.to_string().contains("dead_code"))
+    })
+}
+
+/// Names one function body uses outside direct calls
+#[derive(Default)]
+struct ReferenceCollector {
+    names: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for ReferenceCollector {
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        // The callee is a call graph edge; only its arguments are references
+        if !matches!(node.func.as_ref(), syn::Expr::Path(_)) {
+            self.visit_expr(&node.func);
+        }
+        for arg in &node.args {
+            self.visit_expr(arg);
+        }
+    }
+
+    fn visit_expr_path(&mut self, node: &'ast syn::ExprPath) {
+        if let Some(segment) = node.path.segments.last() {
+            self.names.insert(segment.ident.to_string());
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        self.names.insert(node.method.to_string());
+        syn::visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        self.names.extend(
+            node.tokens
+                .to_string()
+                .split(|c: char| !c.is_alphanumeric() && c != '_')
+                .filter(|s| !s.is_empty())
+                .map(str::to_string),
+        );
+    }
+}
+
+impl FunctionCollector<'_> {
+    fn record(&mut self, name: String, block: &syn::Block) {
+        let mut refs = ReferenceCollector::default();
+        refs.visit_block(block);
+        self.references.entry(name).or_default().extend(refs.names);
+    }
+}
+
+impl<'ast> Visit<'ast> for FunctionCollector<'_> {
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        if node.attrs.iter().any(is_test_attr) {
+            return;
+        }
+        syn::visit::visit_item_mod(self, node);
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        if node.attrs.iter().any(is_test_attr) {
+            return;
+        }
+        let name = node.sig.ident.to_string();
+        let span = self.ctx.span_of(&node.sig.ident);
+        if let (false, Some(span)) = (allows_dead_code(&node.attrs), span) {
+            self.candidates.push(Candidate {
+                name: name.clone(),
+                file: self.file.clone(),
+                line: span.start_line,
+                col: span.start_col,
+            });
+        }
+        self.record(name, &node.block);
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        // Methods are reached through method calls and trait dispatch, which
+        // the call graph does not resolve; they are never reported
+        if node.attrs.iter().any(is_test_attr) {
+            return;
+        }
+        self.record(node.sig.ident.to_string(), &node.block);
+    }
+}
+
+impl Detector for DeadCode {
+    fn name(&self) -> &str {
+        "dead-code"
+    }
+
+    fn description(&self) -> &str {
+        "Detects functions no entry point reaches"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Informational
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        // A package without entry points is a library: everything in it is
+        // reached from other crates
+        if ctx.contract.entry_points.is_empty() {
+            return Vec::new();
+        }
+
+        let mut collector = FunctionCollector {
+            ctx,
+            file: PathBuf::new(),
+            candidates: Vec::new(),
+            references: BTreeMap::new(),
+        };
+        for (file, ast) in ctx.raw_asts() {
+            collector.file = file.clone();
+            collector.visit_file(ast);
+        }
+
+        // Entry points behind `cfg_attr` are not marked; their names are
+        let mut roots: Vec<&str> = ctx
+            .contract
+            .entry_points
+            .iter()
+            .map(|ep| ep.name.as_str())
+            .collect();
+        roots.extend(
+            collector
+                .candidates
+                .iter()
+                .filter(|c| infer_entry_point_kind(&c.name) != EntryPointKind::Unknown)
+                .map(|c| c.name.as_str()),
+        );
+
+        // Call graph reachability, widened with the references it misses
+        // until nothing new is reached
+        let mut reachable = ctx.call_graph().reachable_from(&roots);
+        loop {
+            let referenced: Vec<&str> = reachable
+                .iter()
+                .filter_map(|f| collector.references.get(f))
+                .flatten()
+                .filter(|name| {
+                    !reachable.contains(*name) && collector.references.contains_key(*name)
+                })
+                .map(String::as_str)
+                .collect();
+            if referenced.is_empty() {
+                break;
+            }
+            let more = ctx.call_graph().reachable_from(&referenced);
+            reachable.extend(more);
+        }
+
+        collector
+            .candidates
+            .iter()
+            .filter(|c| !reachable.contains(&c.name))
+            .map(|c| Finding {
+                detector_name: self.name().to_string(),
+                title: format!("Function `{}` is never reached from an entry point", c.name),
+                description: format!(
+                    "No entry point calls `{}`, directly or through other functions. \
+                     Reviewers auditing it as live code waste effort, and dead handlers \
+                     often keep patterns that were fixed in the code that replaced them.",
+                    c.name
+                ),
+                severity: Severity::Informational,
+                confidence: Confidence::Medium,
+                locations: vec![SourceLocation {
+                    file: c.file.clone(),
+                    start_line: c.line,
+                    end_line: c.line,
+                    start_col: c.col,
+                    end_col: c.col + c.name.len(),
+                    snippet: None,
+                    byte_range: None,
+                }],
+                recommendation: Some(format!(
+                    "Remove `{}`, or mark it `#[allow(dead_code)]` if it is kept on purpose.",
+                    c.name
+                )),
+                fix: None,
+                ..Default::default()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        DeadCode.detect(&ctx)
+    }
+
+    #[test]
+    fn test_detects_unreachable_handler() {
+        let source = r#"
+            #[entry_point]
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> StdResult<Response> {
+                match msg {
+                    ExecuteMsg::Withdraw {} => execute_withdraw(deps, info),
+                }
+            }
+
+            fn execute_withdraw(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+                let amount = helpers::balance(&deps, &info.sender);
+                Ok(Response::new().add_attribute("amount", amount.to_string()))
+            }
+
+            fn execute_withdraw_v1(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+                let amount = old_balance(&deps, &info.sender);
+                Ok(Response::new())
+            }
+
+            fn old_balance(deps: &DepsMut, addr: &Addr) -> Uint128 {
+                Uint128::zero()
+            }
+
+            fn balance(deps: &DepsMut, addr: &Addr) -> Uint128 {
+                Uint128::zero()
+            }
+
+            #[cfg(not(test))]
+            fn execute_withdraw_v0(deps: DepsMut) -> StdResult<Response> {
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        let names: Vec<&str> = findings.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Function `execute_withdraw_v1` is never reached from an entry point",
+                "Function `old_balance` is never reached from an entry point",
+                "Function `execute_withdraw_v0` is never reached from an entry point",
+            ]
+        );
+        assert_eq!(findings[0].locations[0].start_line, 15);
+        assert_eq!(findings[0].severity, Severity::Informational);
+    }
+
+    #[test]
+    fn test_ignores_tests_references_and_packages() {
+        let source = r#"
+            #[cfg_attr(not(feature = "library"), entry_point)]
+            pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+                let total = CONFIG.load(deps.storage).map(total_supply)?;
+                let state = State::load(deps)?;
+                to_json_binary(&format!("{}", describe(&state)))
+            }
+
+            #[entry_point]
+            pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg)
+                -> StdResult<Response> {
+                Ok(Response::new())
+            }
+
+            fn total_supply(config: Config) -> Uint128 { config.supply }
+            fn describe(state: &State) -> String { String::new() }
+            fn load_inner(deps: Deps) -> StdResult<State> { Ok(State {}) }
+
+            impl State {
+                fn load(deps: Deps) -> StdResult<Self> { load_inner(deps) }
+                fn unused_method(&self) {}
+            }
+
+            #[allow(dead_code)]
+            fn kept_for_later() {}
+
+            #[cfg(test)]
+            mod tests {
+                fn setup() {}
+                #[test]
+                fn test_query() { setup(); }
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+
+        let package = r#"
+            pub fn validate_fee(fee: Decimal) -> bool { fee <= Decimal::one() }
+        "#;
+        assert!(analyze(package).is_empty());
+    }
+}
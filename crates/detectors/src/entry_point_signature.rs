@@ -0,0 +1,274 @@
+use cosmwasm_guard::ast::{EntryPoint, EntryPointKind};
+use cosmwasm_guard::detector::{AnalysisContext, Detector};
+use cosmwasm_guard::finding::*;
+
+/// Verifies `#[entry_point]` signatures against what the CosmWasm VM expects:
+/// parameter count and order, `Deps` vs `DepsMut` per kind, and the response
+/// type. A mismatch still compiles but the contract fails validation on upload
+/// or when the entry point is first called.
+pub struct EntryPointSignature;
+
+/// Expected parameter types (base names) and response types for an entry point kind
+fn expected_signature(
+    kind: &EntryPointKind,
+) -> Option<(&'static [&'static str], &'static [&'static str])> {
+    const RESPONSE: &[&str] = &["Response"];
+    const BINARY: &[&str] = &["Binary", "QueryResponse"];
+    match kind {
+        EntryPointKind::Instantiate | EntryPointKind::Execute => {
+            Some((&["DepsMut", "Env", "MessageInfo", "*"], RESPONSE))
+        }
+        EntryPointKind::Query => Some((&["Deps", "Env", "*"], BINARY)),
+        EntryPointKind::Migrate | EntryPointKind::Sudo => {
+            Some((&["DepsMut", "Env", "*"], RESPONSE))
+        }
+        EntryPointKind::Reply => Some((&["DepsMut", "Env", "Reply"], RESPONSE)),
+        EntryPointKind::Unknown => None,
+    }
+}
+
+/// Type name without generics or references: `&DepsMut<'_, C>` -> "DepsMut"
+fn base_type(type_name: &str) -> &str {
+    let trimmed = type_name.trim_start_matches('&').trim_start_matches("mut");
+    let end = trimmed.find('<').unwrap_or(trimmed.len());
+    let path = &trimmed[..end];
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+/// Split `Result<Response<C>, ContractError>` into ("Result", ["Response<C>", "ContractError"])
+fn split_generic(type_name: &str) -> (&str, Vec<&str>) {
+    let Some(open) = type_name.find('<') else {
+        return (type_name, Vec::new());
+    };
+    let inner = type_name[open + 1..]
+        .strip_suffix('>')
+        .unwrap_or(&type_name[open + 1..]);
+    let mut args = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                args.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < inner.len() {
+        args.push(&inner[start..]);
+    }
+    (&type_name[..open], args)
+}
+
+fn is_known_response(name: &str) -> bool {
+    matches!(name, "Response" | "Binary" | "QueryResponse")
+}
+
+/// Describe every way the entry point deviates from the expected signature
+fn signature_problems(ep: &EntryPoint, return_type: Option<&str>) -> Vec<String> {
+    let Some((params, responses)) = expected_signature(&ep.kind) else {
+        return Vec::new();
+    };
+    let mut problems = Vec::new();
+
+    // CosmWasm 2.2 added an optional `MigrateInfo` parameter to migrate
+    let actual: Vec<&str> = ep.params.iter().map(|p| base_type(&p.type_name)).collect();
+    let extra_allowed = ep.kind == EntryPointKind::Migrate
+        && actual.len() == params.len() + 1
+        && actual.last() == Some(&"MigrateInfo");
+    if actual.len() != params.len() && !extra_allowed {
+        problems.push(format!(
+            "takes {} parameters, expected {}",
+            actual.len(),
+            params.len()
+        ));
+    }
+    for (i, (expected, found)) in params.iter().zip(&actual).enumerate() {
+        if *expected != "*" && expected != found {
+            problems.push(format!(
+                "parameter {} is `{}`, expected `{}`",
+                i + 1,
+                ep.params[i].type_name,
+                expected
+            ));
+        }
+    }
+
+    match return_type {
+        None => problems.push("returns `()`, expected a `Result`".to_string()),
+        Some(ret) => {
+            let (outer, args) = split_generic(ret);
+            let outer = base_type(outer);
+            // Aliases without generics (e.g. `ExecuteResult`) cannot be resolved here
+            if outer.ends_with("Result") {
+                if let Some(ok) = args.first() {
+                    let ok = base_type(ok);
+                    if !responses.contains(&ok) {
+                        problems.push(format!(
+                            "returns `{}`, expected `{}` in the `Ok` variant",
+                            ok, responses[0]
+                        ));
+                    }
+                }
+            } else if !args.is_empty() || is_known_response(outer) {
+                problems.push(format!("returns `{}`, expected a `Result`", ret));
+            }
+        }
+    }
+
+    problems
+}
+
+impl Detector for EntryPointSignature {
+    fn name(&self) -> &str {
+        "entry-point-signature"
+    }
+
+    fn description(&self) -> &str {
+        "Detects #[entry_point] functions whose signature does not match CosmWasm expectations"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::High
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for ep in &ctx.contract.entry_points {
+            let return_type = ctx
+                .contract
+                .functions
+                .iter()
+                .find(|f| f.name == ep.name)
+                .and_then(|f| f.return_type.as_deref());
+
+            let problems = signature_problems(ep, return_type);
+            if problems.is_empty() {
+                continue;
+            }
+
+            findings.push(Finding {
+                detector_name: self.name().to_string(),
+                title: format!("Entry point `{}` has an invalid signature", ep.name),
+                description: format!(
+                    "The {:?} entry point `{}` {}. The contract compiles, but the VM rejects \
+                     it when the wasm is validated or the entry point is called.",
+                    ep.kind,
+                    ep.name,
+                    problems.join("; ")
+                ),
+                severity: Severity::Medium,
+                confidence: Confidence::High,
+                locations: vec![SourceLocation {
+                    file: ep.span.file.clone(),
+                    start_line: ep.span.start_line,
+                    end_line: ep.span.end_line,
+                    start_col: ep.span.start_col,
+                    end_col: ep.span.end_col,
+                    snippet: None,
+                    byte_range: None,
+                }],
+                recommendation: Some(
+                    "Use the standard signature, e.g. `execute(deps: DepsMut, env: Env, info: \
+                     MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError>` or \
+                     `query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary>`."
+                        .to_string(),
+                ),
+                fix: None,
+                ..Default::default()
+            });
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        EntryPointSignature.detect(&ctx)
+    }
+
+    #[test]
+    fn test_valid_signatures_pass() {
+        let source = r#"
+            #[entry_point]
+            pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg) -> Result<Response, ContractError> { todo!() }
+            #[entry_point]
+            pub fn execute(deps: DepsMut<MyQuery>, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response<MyMsg>> { todo!() }
+            #[entry_point]
+            pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> { todo!() }
+            #[entry_point]
+            pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg, info: MigrateInfo) -> ContractResult<Response> { todo!() }
+            #[entry_point]
+            pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> { todo!() }
+            #[entry_point]
+            pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ExecuteResult { todo!() }
+        "#;
+        let findings = analyze(source);
+        assert!(
+            findings.is_empty(),
+            "{:?}",
+            findings.iter().map(|f| &f.description).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_execute_with_deps_flagged() {
+        let source = r#"
+            #[entry_point]
+            pub fn execute(deps: Deps, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> { todo!() }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0]
+            .description
+            .contains("parameter 1 is `Deps`, expected `DepsMut`"));
+    }
+
+    #[test]
+    fn test_query_returning_response_flagged() {
+        let source = r#"
+            #[entry_point]
+            pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Response> { todo!() }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0]
+            .description
+            .contains("returns `Response`, expected `Binary`"));
+    }
+
+    #[test]
+    fn test_wrong_arity_and_missing_result() {
+        let source = r#"
+            #[entry_point]
+            pub fn instantiate(deps: DepsMut, msg: InstantiateMsg) -> Response { todo!() }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        let desc = &findings[0].description;
+        assert!(desc.contains("takes 2 parameters, expected 4"), "{desc}");
+        assert!(desc.contains("expected a `Result`"), "{desc}");
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::{BTreeSet, HashSet};
+use std::path::PathBuf;
+
+use cosmwasm_guard::ast::utils::{infer_entry_point_kind, is_test_attr};
+use cosmwasm_guard::ast::EntryPointKind;
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::instruction::{LiteralValue, Operand};
+use syn::visit::Visit;
+
+/// Detects `f32`/`f64` in struct fields, type aliases, constants and the
+/// signatures and bodies of functions reachable from an entry point. Float
+/// instructions make the wasm nondeterministic across VMs, and chains running
+/// wasmd reject contracts containing them on upload.
+pub struct FloatingPoint;
+
+/// Whether the IR of `name` loads a floating-point literal
+fn has_float_literal(ctx: &AnalysisContext, name: &str) -> bool {
+    ctx.ir.get_function(name).is_some_and(|func| {
+        func.cfg
+            .blocks
+            .iter()
+            .flat_map(|block| &block.instructions)
+            .flat_map(|inst| inst.operands())
+            .any(|op| matches!(op, Operand::Literal(LiteralValue::Float(_))))
+    })
+}
+
+/// Float types and literals, by file, line and column, with the item they
+/// appear in
+struct FloatCollector<'c> {
+    ctx: &'c AnalysisContext<'c>,
+    file: PathBuf,
+    /// Functions reachable from an entry point, `None` when the crate has none
+    reachable: Option<&'c HashSet<String>>,
+    /// Item being visited, e.g. "struct `Config`"
+    item: String,
+    /// Whether the current function's IR has float literals to locate
+    literals: bool,
+    hits: BTreeSet<(PathBuf, usize, usize, String, String)>,
+}
+
+impl FloatCollector<'_> {
+    fn record(&mut self, node: &impl quote::ToTokens, what: String) {
+        if let Some(span) = self.ctx.span_of(node) {
+            self.hits.insert((
+                self.file.clone(),
+                span.start_line,
+                span.start_col,
+                what,
+                self.item.clone(),
+            ));
+        }
+    }
+
+    fn enter(&mut self, item: String, visit: impl FnOnce(&mut Self)) {
+        let outer = std::mem::replace(&mut self.item, item);
+        visit(self);
+        self.item = outer;
+    }
+
+    fn enter_function(&mut self, name: String, kind: &str, visit: impl FnOnce(&mut Self)) {
+        let literals = std::mem::replace(&mut self.literals, has_float_literal(self.ctx, &name));
+        self.enter(format!("{kind} `{name}`"), visit);
+        self.literals = literals;
+    }
+}
+
+impl<'ast> Visit<'ast> for FloatCollector<'_> {
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        if !node.attrs.iter().any(is_test_attr) {
+            syn::visit::visit_item_mod(self, node);
+        }
+    }
+
+    fn visit_item_struct(&mut self, node: &'ast syn::ItemStruct) {
+        if !node.attrs.iter().any(is_test_attr) {
+            self.enter(format!("struct `{}`", node.ident), |v| {
+                syn::visit::visit_item_struct(v, node)
+            });
+        }
+    }
+
+    fn visit_item_enum(&mut self, node: &'ast syn::ItemEnum) {
+        if !node.attrs.iter().any(is_test_attr) {
+            self.enter(format!("enum `{}`", node.ident), |v| {
+                syn::visit::visit_item_enum(v, node)
+            });
+        }
+    }
+
+    fn visit_item_type(&mut self, node: &'ast syn::ItemType) {
+        if !node.attrs.iter().any(is_test_attr) {
+            self.enter(format!("type alias `{}`", node.ident), |v| {
+                syn::visit::visit_item_type(v, node)
+            });
+        }
+    }
+
+    fn visit_item_const(&mut self, node: &'ast syn::ItemConst) {
+        if !node.attrs.iter().any(is_test_attr) {
+            self.enter(format!("constant `{}`", node.ident), |v| {
+                syn::visit::visit_item_const(v, node)
+            });
+        }
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        let name = node.sig.ident.to_string();
+        if node.attrs.iter().any(is_test_attr)
+            || self
+                .reachable
+                .is_some_and(|reachable| !reachable.contains(&name))
+        {
+            return;
+        }
+        self.enter_function(name, "function", |v| syn::visit::visit_item_fn(v, node));
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+        if !node.attrs.iter().any(is_test_attr) {
+            syn::visit::visit_item_impl(self, node);
+        }
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        // Method calls are not resolved in the call graph, so every method counts
+        if !node.attrs.iter().any(is_test_attr) {
+            let name = node.sig.ident.to_string();
+            self.enter_function(name, "method", |v| syn::visit::visit_impl_item_fn(v, node));
+        }
+    }
+
+    fn visit_type_path(&mut self, node: &'ast syn::TypePath) {
+        if node.qself.is_none() {
+            if let Some(ty) = ["f32", "f64"].into_iter().find(|ty| node.path.is_ident(ty)) {
+                self.record(node, format!("type `{ty}`"));
+            }
+        }
+        syn::visit::visit_type_path(self, node);
+    }
+
+    fn visit_lit_float(&mut self, node: &'ast syn::LitFloat) {
+        if self.literals {
+            self.record(node, format!("literal `{node}`"));
+        }
+    }
+}
+
+impl Detector for FloatingPoint {
+    fn name(&self) -> &str {
+        "floating-point"
+    }
+
+    fn description(&self) -> &str {
+        "Detects f32/f64 types and float literals in code compiled into the contract"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::High
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut roots: Vec<&str> = ctx
+            .contract
+            .entry_points
+            .iter()
+            .map(|ep| ep.name.as_str())
+            .collect();
+        roots.extend(
+            ctx.contract
+                .functions
+                .iter()
+                .filter(|f| infer_entry_point_kind(&f.name) != EntryPointKind::Unknown)
+                .map(|f| f.name.as_str()),
+        );
+        // Libraries without entry points: everything may end up in a contract
+        let reachable = (!roots.is_empty()).then(|| ctx.call_graph().reachable_from(&roots));
+
+        let mut collector = FloatCollector {
+            ctx,
+            file: PathBuf::new(),
+            reachable: reachable.as_ref(),
+            item: String::new(),
+            literals: false,
+            hits: BTreeSet::new(),
+        };
+        for (file, ast) in ctx.raw_asts() {
+            if ast.attrs.iter().any(is_test_attr) {
+                continue;
+            }
+            collector.file = file.clone();
+            collector.visit_file(ast);
+        }
+
+        // One finding per line: `let x: f64 = 0.5;` is a single problem
+        let mut lines = HashSet::new();
+        let mut findings = Vec::new();
+        for (file, line, col, what, item) in collector.hits {
+            if !lines.insert((file.clone(), line)) {
+                continue;
+            }
+            findings.push(Finding {
+                detector_name: self.name().to_string(),
+                title: format!("Floating-point {what} in {item}"),
+                description: format!(
+                    "Floating-point {what} in {item} compiles to wasm float instructions. Their \
+                     results (NaN bit patterns in particular) are not guaranteed identical across \
+                     validators, and wasmd and most CosmWasm chains reject contracts that \
+                     contain them on upload."
+                ),
+                severity: Severity::Medium,
+                confidence: Confidence::High,
+                locations: vec![SourceLocation {
+                    file,
+                    start_line: line,
+                    end_line: line,
+                    start_col: col,
+                    end_col: col,
+                    snippet: None,
+                    byte_range: None,
+                }],
+                recommendation: Some(
+                    "Use fixed-point `Decimal` or `Decimal256` from cosmwasm_std, or integer math \
+                     on `Uint128` with an explicit scale."
+                        .to_string(),
+                ),
+                fix: None,
+                ..Default::default()
+            });
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let contract =
+            ContractVisitor::extract(PathBuf::from("src/lib.rs"), parse_source(source).unwrap());
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([(PathBuf::from("src/lib.rs"), source.to_string())]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        FloatingPoint.detect(&ctx)
+    }
+
+    #[test]
+    fn test_flags_float_types_and_literals_in_reachable_code() {
+        let source = r#"
+pub struct Config {
+    pub fee_ratio: f64,
+}
+
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    let fee = apply_fee(100);
+    Ok(Response::new())
+}
+
+fn apply_fee(amount: u128) -> u128 {
+    let scaled = amount as f32;
+    (scaled * 0.997) as u128
+}
+
+fn unused(x: f64) -> f64 { x }
+
+#[cfg(test)]
+mod tests {
+    fn ratio() -> f64 { 0.5 }
+}
+"#;
+        let findings = analyze(source);
+        let titles: Vec<(&str, usize)> = findings
+            .iter()
+            .map(|f| (f.title.as_str(), f.locations[0].start_line))
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                ("Floating-point type `f64` in struct `Config`", 3),
+                ("Floating-point type `f32` in function `apply_fee`", 13),
+                ("Floating-point literal `0.997` in function `apply_fee`", 14),
+            ]
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use cosmwasm_guard::ast::utils::is_test_attr;
+use cosmwasm_guard::detector::{AnalysisContext, Detector};
+use cosmwasm_guard::finding::*;
+use syn::visit::Visit;
+
+/// Detects bech32 addresses hard-coded with another chain's prefix than the
+/// one configured as `prefix` under `[detectors.foreign-address-prefix]`:
+/// constants and config defaults that `addr_validate` rejects on the target
+/// chain, comparisons with validated addresses that can never match, and
+/// `starts_with` checks for a foreign prefix. Falls back to the `prefix` of
+/// the `--target-chain` profile; reports nothing until a prefix is configured.
+pub struct ForeignAddressPrefix;
+
+const CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Suffixes the SDK appends to a chain's prefix for validator and consensus
+/// addresses and public keys
+const KEY_SUFFIXES: &[&str] = &["pub", "valoper", "valoperpub", "valcons", "valconspub"];
+
+fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GEN: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut chk = 1u32;
+    for v in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ u32::from(v);
+        for (i, g) in GEN.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+/// Prefix of `s` when it is a lowercase bech32 (or bech32m) string with a
+/// valid checksum and at least an address's worth of data
+pub fn bech32_prefix(s: &str) -> Option<&str> {
+    if s.len() > 90 || s.bytes().any(|b| !b.is_ascii_lowercase() && !b.is_ascii_digit()) {
+        return None;
+    }
+    let (hrp, data) = s.rsplit_once('1')?;
+    // 20-byte account address: 32 data characters plus the 6-character checksum
+    if hrp.is_empty() || data.len() < 38 {
+        return None;
+    }
+    let data: Vec<u8> = data
+        .chars()
+        .map(|c| CHARSET.find(c).map(|i| i as u8))
+        .collect::<Option<_>>()?;
+    let expanded = hrp
+        .bytes()
+        .map(|b| b >> 5)
+        .chain([0])
+        .chain(hrp.bytes().map(|b| b & 31))
+        .chain(data);
+    matches!(polymod(expanded), 1 | 0x2bc830a3).then_some(hrp)
+}
+
+/// Whether an address with prefix `hrp` belongs to the chain of `prefix`
+fn same_chain(hrp: &str, prefix: &str) -> bool {
+    hrp.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || KEY_SUFFIXES.contains(&rest))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Use {
+    /// A literal address, e.g. a default admin or fee collector
+    Literal,
+    /// Compared with `==`/`!=`
+    Comparison,
+    /// Argument of `starts_with`
+    PrefixCheck,
+}
+
+/// Foreign-prefix literals by file, line and column, with the prefix and use
+struct PrefixCollector<'c> {
+    ctx: &'c AnalysisContext<'c>,
+    file: PathBuf,
+    prefix: &'c str,
+    in_comparison: bool,
+    hits: BTreeSet<(PathBuf, usize, usize, String, Use)>,
+}
+
+impl PrefixCollector<'_> {
+    fn record(&mut self, lit: &syn::LitStr, hrp: &str, used: Use) {
+        if let Some(span) = self.ctx.span_of(lit) {
+            self.hits.insert((
+                self.file.clone(),
+                span.start_line,
+                span.start_col,
+                hrp.to_string(),
+                used,
+            ));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for PrefixCollector<'_> {
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        if !node.attrs.iter().any(is_test_attr) {
+            syn::visit::visit_item_mod(self, node);
+        }
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        if !node.attrs.iter().any(is_test_attr) {
+            syn::visit::visit_item_fn(self, node);
+        }
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        if !node.attrs.iter().any(is_test_attr) {
+            syn::visit::visit_impl_item_fn(self, node);
+        }
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        let comparison = matches!(node.op, syn::BinOp::Eq(_) | syn::BinOp::Ne(_));
+        let outer = std::mem::replace(&mut self.in_comparison, comparison);
+        syn::visit::visit_expr_binary(self, node);
+        self.in_comparison = outer;
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if node.method == "starts_with" {
+            if let Some(syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit),
+                ..
+            })) = node.args.first()
+            {
+                // `starts_with("juno1")`: a prefix followed by the separator
+                let value = lit.value();
+                if let Some(hrp) = value
+                    .strip_suffix('1')
+                    .filter(|hrp| !hrp.is_empty() && hrp.bytes().all(|b| b.is_ascii_lowercase()))
+                {
+                    if !same_chain(hrp, self.prefix) {
+                        self.record(lit, hrp, Use::PrefixCheck);
+                    }
+                }
+            }
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_lit_str(&mut self, node: &'ast syn::LitStr) {
+        let value = node.value();
+        if let Some(hrp) = bech32_prefix(&value).filter(|hrp| !same_chain(hrp, self.prefix)) {
+            let used = if self.in_comparison {
+                Use::Comparison
+            } else {
+                Use::Literal
+            };
+            self.record(node, hrp, used);
+        }
+    }
+}
+
+impl Detector for ForeignAddressPrefix {
+    fn name(&self) -> &str {
+        "foreign-address-prefix"
+    }
+
+    fn description(&self) -> &str {
+        "Detects hard-coded addresses and prefix checks for another chain than the configured bech32 prefix"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::High
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let Some(prefix) = ctx
+            .config()
+            .detector_option::<String>(self.name(), "prefix")
+            .or_else(|| {
+                let (_, profile) = ctx.config().target_chain()?;
+                profile.prefix.clone()
+            })
+        else {
+            return Vec::new();
+        };
+
+        let mut collector = PrefixCollector {
+            ctx,
+            file: PathBuf::new(),
+            prefix: &prefix,
+            in_comparison: false,
+            hits: BTreeSet::new(),
+        };
+        for (file, ast) in ctx.raw_asts() {
+            if ast.attrs.iter().any(is_test_attr) {
+                continue;
+            }
+            collector.file = file.clone();
+            collector.visit_file(ast);
+        }
+
+        let mut findings = Vec::new();
+        for (file, line, col, hrp, used) in collector.hits {
+            let (title, description, recommendation) = match used {
+                Use::Literal => (
+                    format!("Hard-coded `{hrp}` address (chain prefix is `{prefix}`)"),
+                    format!(
+                        "This address has the `{hrp}` prefix, but the contract targets a chain \
+                         whose addresses start with `{prefix}`. `addr_validate` rejects it there, \
+                         and funds sent to it are lost or stuck."
+                    ),
+                    format!(
+                        "Take the address from the instantiate message and validate it, or use \
+                         the `{prefix}` address of the intended account."
+                    ),
+                ),
+                Use::Comparison => (
+                    format!("Comparison with a `{hrp}` address never matches (chain prefix is `{prefix}`)"),
+                    format!(
+                        "Addresses validated on a `{prefix}` chain never equal this `{hrp}` \
+                         literal, so the check is always false (or always true with `!=`)."
+                    ),
+                    format!(
+                        "Compare against a stored, validated address, or the `{prefix}` address \
+                         of the intended account."
+                    ),
+                ),
+                Use::PrefixCheck => (
+                    format!("Check for the `{hrp}` prefix never matches (chain prefix is `{prefix}`)"),
+                    format!(
+                        "Addresses on the target chain start with `{prefix}1`, so this check for \
+                         `{hrp}1` never matches a validated address."
+                    ),
+                    "Validate addresses with `deps.api.addr_validate`, which checks the chain's \
+                     prefix, instead of matching prefixes by hand."
+                        .to_string(),
+                ),
+            };
+            findings.push(Finding {
+                detector_name: self.name().to_string(),
+                title,
+                description,
+                severity: Severity::Medium,
+                confidence: Confidence::High,
+                locations: vec![SourceLocation {
+                    file,
+                    start_line: line,
+                    end_line: line,
+                    start_col: col,
+                    end_col: col,
+                    snippet: None,
+                    byte_range: None,
+                }],
+                recommendation: Some(recommendation),
+                fix: None,
+                ..Default::default()
+            });
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::config::Config;
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+
+    const OSMO: &str = "osmo1tkhcz26v44ajppc0myzq5shwdz24glwyg9umw4";
+    const JUNO: &str = "juno155qj34vlfq4gv9cnjn4h5tgcyn0hchf85ce4g2";
+
+    fn analyze_with(source: &str, config: &Config) -> Vec<Finding> {
+        let contract =
+            ContractVisitor::extract(PathBuf::from("src/lib.rs"), parse_source(source).unwrap());
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([(PathBuf::from("src/lib.rs"), source.to_string())]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources).with_config(config);
+        ForeignAddressPrefix.detect(&ctx)
+    }
+
+    fn osmosis() -> Config {
+        toml::from_str("[detectors.foreign-address-prefix]\nprefix = \"osmo\"\n").unwrap()
+    }
+
+    #[test]
+    fn test_bech32_prefix_checks_checksum() {
+        assert_eq!(bech32_prefix(OSMO), Some("osmo"));
+        assert_eq!(bech32_prefix(JUNO), Some("juno"));
+        // One character changed
+        assert_eq!(bech32_prefix(&JUNO.replace("155q", "155p")), None);
+        assert_eq!(bech32_prefix("juno1"), None);
+        assert_eq!(bech32_prefix(&JUNO.to_uppercase()), None);
+    }
+
+    #[test]
+    fn test_flags_foreign_literals_comparisons_and_prefix_checks() {
+        let source = format!(
+            r#"
+const FEE_COLLECTOR: &str = "{JUNO}";
+const TREASURY: &str = "{OSMO}";
+
+pub fn execute_withdraw(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {{
+    let owner = deps.api.addr_validate(info.sender.as_str())?;
+    if owner == "{JUNO}" {{
+        return Ok(Response::new());
+    }}
+    if owner.as_str().starts_with("juno1") || owner.as_str().starts_with("osmovaloper1") {{
+        return Ok(Response::new());
+    }}
+    Ok(Response::new())
+}}
+
+#[cfg(test)]
+mod tests {{
+    const USER: &str = "{JUNO}";
+}}
+"#
+        );
+        let findings = analyze_with(&source, &osmosis());
+        let titles: Vec<(&str, usize)> = findings
+            .iter()
+            .map(|f| (f.title.as_str(), f.locations[0].start_line))
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                ("Hard-coded `juno` address (chain prefix is `osmo`)", 2),
+                (
+                    "Comparison with a `juno` address never matches (chain prefix is `osmo`)",
+                    7
+                ),
+                (
+                    "Check for the `juno` prefix never matches (chain prefix is `osmo`)",
+                    10
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_silent_without_configured_prefix() {
+        let source = format!("const FEE_COLLECTOR: &str = \"{JUNO}\";\n");
+        assert!(analyze_with(&source, &Config::default()).is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 use cosmwasm_guard::ast::EntryPointKind;
+use cosmwasm_guard::auth::AuthRegistry;
 use cosmwasm_guard::detector::{AnalysisContext, Detector};
 use cosmwasm_guard::finding::*;
 use syn::visit::Visit;
@@ -12,16 +13,38 @@ pub struct IncorrectPermissionHierarchy;
 const ADMIN_STORAGE_PATTERNS: &[&str] = &["config", "admin", "owner", "governance"];
 
 /// Visitor that checks for storage writes to admin items and sender verification
-struct PermissionSearcher {
+struct PermissionSearcher<'a> {
     writes_admin_storage: bool,
     checks_stored_admin: bool,
     admin_item_names: Vec<String>,
+    auth: &'a AuthRegistry,
 }
 
-impl<'ast> Visit<'ast> for PermissionSearcher {
+impl<'ast> Visit<'ast> for PermissionSearcher<'_> {
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        // Access-control helpers verify the stored admin themselves
+        if let syn::Expr::Path(path) = node.func.as_ref() {
+            let full_path = path
+                .path
+                .segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::");
+            if self.auth.is_helper_call(&full_path) {
+                self.checks_stored_admin = true;
+            }
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+
     fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
         let method = node.method.to_string();
 
+        if self.auth.is_helper_method(&method) {
+            self.checks_stored_admin = true;
+        }
+
         // Check for writes to admin-like storage items
         if method == "save" || method == "update" {
             if let syn::Expr::Path(path) = node.receiver.as_ref() {
@@ -85,6 +108,7 @@ impl Detector for IncorrectPermissionHierarchy {
                 writes_admin_storage: false,
                 checks_stored_admin: false,
                 admin_item_names: Vec::new(),
+                auth: ctx.auth(),
             };
             syn::visit::visit_block(&mut searcher, body);
 
@@ -111,6 +135,7 @@ impl Detector for IncorrectPermissionHierarchy {
                         start_col: ep.span.start_col,
                         end_col: ep.span.end_col,
                         snippet: None,
+                        byte_range: None,
                     }],
                     recommendation: Some(
                         "Load the current admin/config and verify `info.sender` \
@@ -118,6 +143,7 @@ impl Detector for IncorrectPermissionHierarchy {
                             .to_string(),
                     ),
                     fix: None,
+                    ..Default::default()
                 });
             }
         }
@@ -130,20 +156,25 @@ impl Detector for IncorrectPermissionHierarchy {
 mod tests {
     use super::*;
     use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::config::Config;
     use cosmwasm_guard::ir::builder::IrBuilder;
     use std::collections::HashMap;
     use std::path::PathBuf;
 
-    fn analyze(source: &str) -> Vec<Finding> {
+    fn analyze_with(source: &str, config: &Config) -> Vec<Finding> {
         let ast = parse_source(source).unwrap();
         let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
         let ir = IrBuilder::build_contract(&contract);
         let mut sources = HashMap::new();
         sources.insert(PathBuf::from("test.rs"), source.to_string());
-        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources).with_config(config);
         IncorrectPermissionHierarchy.detect(&ctx)
     }
 
+    fn analyze(source: &str) -> Vec<Finding> {
+        analyze_with(source, &Config::default())
+    }
+
     #[test]
     fn test_detects_unverified_admin_write() {
         let source = r#"
@@ -190,4 +221,20 @@ mod tests {
         let findings = analyze(source);
         assert!(findings.is_empty());
     }
+
+    #[test]
+    fn test_configured_auth_helper_counts_as_admin_check() {
+        let source = r#"
+            #[entry_point]
+            pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> StdResult<Response> {
+                only_gov(deps.as_ref(), &info.sender)?;
+                CONFIG.save(deps.storage, &new_config)?;
+                Ok(Response::new())
+            }
+        "#;
+        assert_eq!(analyze(source).len(), 1);
+        let config: Config = toml::from_str("[auth]\nhelpers = [\"only_gov\"]").unwrap();
+        assert!(analyze_with(source, &config).is_empty());
+    }
 }
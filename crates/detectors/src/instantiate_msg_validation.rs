@@ -0,0 +1,588 @@
+use std::collections::HashSet;
+
+use cosmwasm_guard::ast::{EntryPointKind, SourceSpan};
+use cosmwasm_guard::detector::{AnalysisContext, Detector};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::slice::Defs;
+use cosmwasm_guard::ir::{FunctionIr, Instruction};
+use syn::visit::Visit;
+
+use crate::missing_addr_validate::is_address_field_name;
+
+/// Detects InstantiateMsg fields (addresses, fee/bps limits) that instantiate
+/// stores without validation. Genesis config is permanent, so a bad value
+/// cannot be corrected without a migration.
+///
+/// Limits are unsigned, so only an upper bound counts as validating one: a
+/// check like `fee > 0` still lets any fee through.
+pub struct InstantiateMsgValidation;
+
+/// Name fragments of numeric fields that are expected to be range checked
+const LIMIT_PATTERNS: &[&str] = &["fee", "bps", "commission", "rate", "percent", "ratio"];
+
+/// Numeric types that can carry an out-of-range limit
+const NUMERIC_TYPES: &[&str] = &[
+    "Decimal",
+    "Decimal256",
+    "Uint64",
+    "Uint128",
+    "Uint256",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+];
+
+#[derive(Debug, PartialEq)]
+enum FieldKind {
+    Address,
+    Limit,
+}
+
+/// A field of InstantiateMsg that needs validation
+struct MsgField {
+    name: String,
+    kind: FieldKind,
+    span: SourceSpan,
+}
+
+fn classify_field(name: &str, ty: &syn::Type) -> Option<FieldKind> {
+    let ty = type_ident_string(ty);
+    let lower = name.to_lowercase();
+    if is_address_field_name(name) && ty.contains("String") {
+        return Some(FieldKind::Address);
+    }
+    let is_numeric = NUMERIC_TYPES
+        .iter()
+        .any(|t| ty.split(['<', '>']).any(|s| s == *t));
+    if is_numeric
+        && !is_address_field_name(name)
+        && LIMIT_PATTERNS.iter().any(|p| lower.contains(p))
+    {
+        return Some(FieldKind::Limit);
+    }
+    None
+}
+
+/// Render a type as its path identifiers, e.g. `Option<String>` -> "Option<String>"
+fn type_ident_string(ty: &syn::Type) -> String {
+    match ty {
+        syn::Type::Path(tp) => tp
+            .path
+            .segments
+            .iter()
+            .map(|s| {
+                let args = match &s.arguments {
+                    syn::PathArguments::AngleBracketed(a) => a
+                        .args
+                        .iter()
+                        .filter_map(|arg| match arg {
+                            syn::GenericArgument::Type(t) => Some(type_ident_string(t)),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    _ => String::new(),
+                };
+                if args.is_empty() {
+                    s.ident.to_string()
+                } else {
+                    format!("{}<{}>", s.ident, args)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("::"),
+        syn::Type::Reference(r) => type_ident_string(&r.elem),
+        _ => String::new(),
+    }
+}
+
+/// Visitor that collects the fields of `InstantiateMsg` (struct or enum variants)
+struct InstantiateMsgCollector<'c> {
+    ctx: &'c AnalysisContext<'c>,
+    fields: Vec<MsgField>,
+}
+
+impl InstantiateMsgCollector<'_> {
+    fn collect_fields(&mut self, fields: &syn::Fields) {
+        let syn::Fields::Named(named) = fields else {
+            return;
+        };
+        for field in &named.named {
+            let Some(ident) = &field.ident else { continue };
+            let name = ident.to_string();
+            let Some(kind) = classify_field(&name, &field.ty) else {
+                continue;
+            };
+            if let Some(span) = self.ctx.span_of(ident) {
+                self.fields.push(MsgField { name, kind, span });
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for InstantiateMsgCollector<'_> {
+    fn visit_item_struct(&mut self, node: &'ast syn::ItemStruct) {
+        if node.ident == "InstantiateMsg" {
+            self.collect_fields(&node.fields);
+        }
+        syn::visit::visit_item_struct(self, node);
+    }
+
+    fn visit_item_enum(&mut self, node: &'ast syn::ItemEnum) {
+        if node.ident == "InstantiateMsg" {
+            for variant in &node.variants {
+                self.collect_fields(&variant.fields);
+            }
+        }
+        syn::visit::visit_item_enum(self, node);
+    }
+}
+
+/// Collect every identifier referenced inside an expression, including macro tokens
+struct IdentCollector {
+    idents: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for IdentCollector {
+    fn visit_ident(&mut self, node: &'ast syn::Ident) {
+        self.idents.insert(node.to_string());
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        self.idents.extend(macro_idents(node));
+        syn::visit::visit_macro(self, node);
+    }
+}
+
+fn expr_idents(expr: &syn::Expr) -> HashSet<String> {
+    let mut collector = IdentCollector {
+        idents: HashSet::new(),
+    };
+    collector.visit_expr(expr);
+    collector.idents
+}
+
+fn macro_idents(mac: &syn::Macro) -> HashSet<String> {
+    mac.tokens
+        .to_string()
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Records which identifiers the instantiate body passes to address
+/// validation, and which it checks against an upper bound
+#[derive(Default)]
+struct ValidationSearcher {
+    addr_validated: HashSet<String>,
+    upper_bounded: HashSet<String>,
+    called_functions: Vec<String>,
+}
+
+impl ValidationSearcher {
+    /// Note the identifiers `cond` bounds from above. `holds_when_valid` is
+    /// whether `cond` is asserted (`ensure!(fee <= MAX)`) rather than
+    /// rejected (`if fee > MAX { return Err(..) }`).
+    fn bound(&mut self, cond: &syn::Expr, holds_when_valid: bool) {
+        match cond {
+            syn::Expr::Paren(paren) => self.bound(&paren.expr, holds_when_valid),
+            syn::Expr::Unary(unary) if matches!(unary.op, syn::UnOp::Not(_)) => {
+                self.bound(&unary.expr, !holds_when_valid)
+            }
+            syn::Expr::Binary(binary) => {
+                let (small, big) = match binary.op {
+                    syn::BinOp::Lt(_) | syn::BinOp::Le(_) => (&binary.left, &binary.right),
+                    syn::BinOp::Gt(_) | syn::BinOp::Ge(_) => (&binary.right, &binary.left),
+                    syn::BinOp::And(_) | syn::BinOp::Or(_) => {
+                        self.bound(&binary.left, holds_when_valid);
+                        self.bound(&binary.right, holds_when_valid);
+                        return;
+                    }
+                    _ => return,
+                };
+                let bounded = if holds_when_valid { small } else { big };
+                self.upper_bounded.extend(expr_idents(bounded));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for ValidationSearcher {
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        let method = node.method.to_string();
+        if method == "addr_validate" || method == "addr_canonicalize" {
+            for arg in &node.args {
+                self.addr_validated.extend(expr_idents(arg));
+            }
+        } else if is_validation_fn(&method) {
+            for arg in &node.args {
+                self.upper_bounded.extend(expr_idents(arg));
+            }
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(path) = node.func.as_ref() {
+            if let Some(last) = path.path.segments.last() {
+                let name = last.ident.to_string();
+                if is_validation_fn(&name) {
+                    for arg in &node.args {
+                        self.upper_bounded.extend(expr_idents(arg));
+                    }
+                }
+                self.called_functions.push(name);
+            }
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+
+    fn visit_expr_if(&mut self, node: &'ast syn::ExprIf) {
+        let mut rejects = Rejects::default();
+        rejects.visit_block(&node.then_branch);
+        if rejects.0 {
+            self.bound(&node.cond, false);
+        }
+        syn::visit::visit_expr_if(self, node);
+    }
+
+    /// `ensure!(cond, err)` and `assert!(cond)` assert their first argument
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        let name = node
+            .path
+            .segments
+            .last()
+            .map(|s| s.ident.to_string())
+            .unwrap_or_default();
+        if let Ok(args) = node.parse_body_with(
+            syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated,
+        ) {
+            if name == "ensure" || name.starts_with("assert") {
+                if let Some(cond) = args.first() {
+                    self.bound(cond, true);
+                }
+            }
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+        syn::visit::visit_macro(self, node);
+    }
+}
+
+/// Whether a block returns early or fails: `return`, `Err(..)`, `bail!`, `panic!`
+#[derive(Default)]
+struct Rejects(bool);
+
+impl<'ast> Visit<'ast> for Rejects {
+    fn visit_expr_return(&mut self, _: &'ast syn::ExprReturn) {
+        self.0 = true;
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if matches!(node.func.as_ref(), syn::Expr::Path(p) if p.path.is_ident("Err")) {
+            self.0 = true;
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        if node
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| matches!(s.ident.to_string().as_str(), "bail" | "panic"))
+        {
+            self.0 = true;
+        }
+    }
+}
+
+/// Variable and field names whose values flow into a storage write in `func`
+fn stored_names(func: &FunctionIr) -> HashSet<String> {
+    let defs = Defs::new(func);
+    func.cfg
+        .blocks
+        .iter()
+        .flat_map(|b| &b.instructions)
+        .filter_map(|inst| match inst {
+            Instruction::StorageStore { key, value, .. } => Some(key.iter().chain([value])),
+            _ => None,
+        })
+        .flatten()
+        .flat_map(|operand| defs.names(operand))
+        .collect()
+}
+
+fn is_validation_fn(name: &str) -> bool {
+    name.starts_with("validate") || name.starts_with("check") || name.starts_with("assert")
+}
+
+impl Detector for InstantiateMsgValidation {
+    fn name(&self) -> &str {
+        "instantiate-msg-validation"
+    }
+
+    fn description(&self) -> &str {
+        "Detects InstantiateMsg addresses and fee limits stored without validation"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut collector = InstantiateMsgCollector {
+            ctx,
+            fields: Vec::new(),
+        };
+        for (_, ast) in ctx.raw_asts() {
+            syn::visit::visit_file(&mut collector, ast);
+        }
+        let fields = collector.fields;
+
+        let mut findings = Vec::new();
+        let mut reported = HashSet::new();
+        for ep in &ctx.contract.entry_points {
+            if ep.kind != EntryPointKind::Instantiate {
+                continue;
+            }
+            let func = ctx.contract.functions.iter().find(|f| f.name == ep.name);
+            let Some(body) = func.and_then(|f| f.body.as_ref()) else {
+                continue;
+            };
+
+            // Scan the instantiate body plus the helpers it calls directly
+            let mut searcher = ValidationSearcher::default();
+            syn::visit::visit_block(&mut searcher, body);
+            let callees = std::mem::take(&mut searcher.called_functions);
+            for callee in &callees {
+                if let Some(helper) = ctx
+                    .contract
+                    .functions
+                    .iter()
+                    .find(|f| &f.name == callee)
+                    .and_then(|f| f.body.as_ref())
+                {
+                    syn::visit::visit_block(&mut searcher, helper);
+                }
+            }
+            let stored: HashSet<String> = ctx
+                .ir
+                .functions
+                .iter()
+                .filter(|f| f.name == ep.name || callees.contains(&f.name))
+                .flat_map(stored_names)
+                .collect();
+
+            for (i, field) in fields.iter().enumerate() {
+                if !stored.contains(&field.name) {
+                    continue;
+                }
+                let (validated, what, fix) = match field.kind {
+                    FieldKind::Address => (
+                        searcher.addr_validated.contains(&field.name),
+                        "address",
+                        format!("deps.api.addr_validate(&msg.{})?", field.name),
+                    ),
+                    FieldKind::Limit => (
+                        searcher.upper_bounded.contains(&field.name),
+                        "fee/limit",
+                        format!(
+                            "ensure!(msg.{} <= MAX_{}, ContractError::InvalidConfig {{}})",
+                            field.name,
+                            field.name.to_uppercase()
+                        ),
+                    ),
+                };
+                if validated || !reported.insert(i) {
+                    continue;
+                }
+
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title: format!(
+                        "InstantiateMsg {} field `{}` stored without validation",
+                        what, field.name
+                    ),
+                    description: format!(
+                        "`{}` stores `InstantiateMsg.{}` but never validates it. \
+                         Configuration set at instantiate is permanent unless the contract \
+                         is migrated, so an invalid value cannot be corrected later.",
+                        ep.name, field.name
+                    ),
+                    severity: Severity::Medium,
+                    confidence: Confidence::Medium,
+                    locations: vec![SourceLocation {
+                        file: field.span.file.clone(),
+                        start_line: field.span.start_line,
+                        end_line: field.span.end_line,
+                        start_col: field.span.start_col,
+                        end_col: field.span.end_col,
+                        snippet: None,
+                        byte_range: None,
+                    }],
+                    recommendation: Some(format!("Validate before storing: `{fix}`")),
+                    fix: None,
+                    ..Default::default()
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        InstantiateMsgValidation.detect(&ctx)
+    }
+
+    #[test]
+    fn test_detects_unvalidated_fields() {
+        let source = r#"
+            pub struct InstantiateMsg {
+                pub owner: String,
+                pub fee_bps: u16,
+                pub name: String,
+            }
+
+            #[entry_point]
+            pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg)
+                -> StdResult<Response> {
+                let config = Config { owner: msg.owner, fee_bps: msg.fee_bps, name: msg.name };
+                CONFIG.save(deps.storage, &config)?;
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 2);
+        assert!(findings.iter().any(|f| f.title.contains("`owner`")));
+        assert!(findings.iter().any(|f| f.title.contains("`fee_bps`")));
+        // The location spans the field name
+        let owner = &findings[0].locations[0];
+        assert_eq!((owner.start_line, owner.end_col - owner.start_col), (3, 5));
+    }
+
+    #[test]
+    fn test_lower_bound_alone_is_not_validation() {
+        let source = r#"
+            pub struct InstantiateMsg {
+                pub fee_bps: u16,
+            }
+
+            #[entry_point]
+            pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg)
+                -> StdResult<Response> {
+                ensure!(msg.fee_bps > 0, ContractError::InvalidFee {});
+                CONFIG.save(deps.storage, &Config { fee_bps: msg.fee_bps })?;
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("`fee_bps`"));
+    }
+
+    #[test]
+    fn test_field_read_but_not_stored_not_flagged() {
+        let source = r#"
+            pub struct InstantiateMsg {
+                pub owner: String,
+            }
+
+            #[entry_point]
+            pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg)
+                -> StdResult<Response> {
+                let owner_len = msg.owner.len();
+                Ok(Response::new().add_attribute("owner_len", owner_len.to_string()))
+            }
+        "#;
+        let findings = analyze(source);
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn test_no_finding_when_validated() {
+        let source = r#"
+            pub struct InstantiateMsg {
+                pub owner: String,
+                pub fee_bps: u16,
+            }
+
+            #[entry_point]
+            pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg)
+                -> StdResult<Response> {
+                let owner = deps.api.addr_validate(&msg.owner)?;
+                ensure!(msg.fee_bps <= 10_000, ContractError::InvalidFee {});
+                CONFIG.save(deps.storage, &Config { owner, fee_bps: msg.fee_bps })?;
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn test_validation_in_helper_counts() {
+        let source = r#"
+            pub struct InstantiateMsg {
+                pub commission: Decimal,
+            }
+
+            #[entry_point]
+            pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg)
+                -> StdResult<Response> {
+                validate_config(&msg)?;
+                CONFIG.save(deps.storage, &msg.commission)?;
+                Ok(Response::new())
+            }
+
+            fn validate_config(msg: &InstantiateMsg) -> StdResult<()> {
+                if msg.commission > Decimal::one() {
+                    return Err(StdError::generic_err("commission too high"));
+                }
+                Ok(())
+            }
+        "#;
+        let findings = analyze(source);
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn test_unused_field_not_flagged() {
+        let source = r#"
+            pub struct InstantiateMsg {
+                pub admin: String,
+            }
+
+            #[entry_point]
+            pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg)
+                -> StdResult<Response> {
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert!(findings.is_empty());
+    }
+}
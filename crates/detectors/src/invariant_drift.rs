@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+
+use cosmwasm_guard::ast::{EntryPointKind, MessageKind, SourceSpan};
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::invariants::{self, Invariant};
+use syn::visit::Visit;
+
+use crate::pause_consistency::{CallCollector, ExecuteArmCollector};
+
+/// Checks declared invariants between state items (`// guard-invariant:
+/// TOTAL_SUPPLY == sum(BALANCES)` or `[invariants]` in the config): an execute
+/// handler that writes one side must update the other. Minting into a balance
+/// without bumping the total supply lets the accounting drift silently.
+///
+/// A handler writing a summed item twice is taken to move value between its
+/// entries (a transfer), which leaves the sum unchanged.
+pub struct InvariantDrift;
+
+/// `ITEM.save(..)` / `ITEM.update(..)` calls in an expression, by item
+struct WriteCounter<'a> {
+    items: &'a [String],
+    writes: HashMap<String, usize>,
+}
+
+impl<'ast> Visit<'ast> for WriteCounter<'_> {
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        let method = node.method.to_string();
+        if method == "save" || method == "update" {
+            if let syn::Expr::Path(p) = node.receiver.as_ref() {
+                if let Some(item) = self.items.iter().find(|i| p.path.is_ident(i.as_str())) {
+                    *self.writes.entry(item.clone()).or_default() += 1;
+                }
+            }
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+}
+
+impl InvariantDrift {
+    fn finding(
+        &self,
+        title: String,
+        description: String,
+        span: &SourceSpan,
+        rec: String,
+    ) -> Finding {
+        Finding {
+            detector_name: self.name().to_string(),
+            title,
+            description,
+            severity: Severity::Medium,
+            confidence: Confidence::Medium,
+            locations: vec![SourceLocation {
+                file: span.file.clone(),
+                start_line: span.start_line,
+                end_line: span.end_line,
+                start_col: span.start_col,
+                end_col: span.end_col,
+                snippet: None,
+                byte_range: None,
+            }],
+            recommendation: Some(rec),
+            fix: None,
+            ..Default::default()
+        }
+    }
+}
+
+/// Invariants declared in the config and in comments of the analyzed files
+fn declared(ctx: &AnalysisContext) -> Vec<Invariant> {
+    let mut declared = invariants::from_config(ctx.config(), ctx.contract);
+    for (file, _) in ctx.raw_asts() {
+        if let Some(source) = ctx.source_code(file) {
+            declared.extend(invariants::from_comments(file, source, ctx.contract));
+        }
+    }
+    declared
+}
+
+impl Detector for InvariantDrift {
+    fn name(&self) -> &str {
+        "invariant-drift"
+    }
+
+    fn description(&self) -> &str {
+        "Detects handlers that update one side of a declared storage invariant but not the other"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let declared = declared(ctx);
+        if declared.is_empty() {
+            return Vec::new();
+        }
+        let execute_enums: Vec<&str> = ctx
+            .contract
+            .message_enums
+            .iter()
+            .filter(|e| e.kind == MessageKind::Execute)
+            .map(|e| e.name.as_str())
+            .collect();
+
+        let mut findings = Vec::new();
+        for ep in ctx
+            .contract
+            .entry_points
+            .iter()
+            .filter(|ep| ep.kind == EntryPointKind::Execute)
+        {
+            let Some((file, body)) = ctx
+                .contract
+                .functions
+                .iter()
+                .find(|f| f.name == ep.name)
+                .and_then(|f| Some((&f.span.file, f.body.as_ref()?)))
+            else {
+                continue;
+            };
+            let mut collector = ExecuteArmCollector {
+                execute_enums: &execute_enums,
+                arms: Vec::new(),
+            };
+            collector.visit_block(body);
+
+            for (enum_name, variant, arm) in collector.arms {
+                let mut calls = CallCollector::default();
+                calls.visit_expr(&arm.body);
+                let roots: Vec<&str> = calls.calls.iter().map(String::as_str).collect();
+                let reachable = ctx.call_graph().reachable_from(&roots);
+
+                for invariant in &declared {
+                    let mut counter = WriteCounter {
+                        items: &invariant.items,
+                        writes: HashMap::new(),
+                    };
+                    counter.visit_arm(arm);
+                    let mut writes = counter.writes;
+                    for name in &reachable {
+                        for write in ctx.storage_writes(name) {
+                            if invariant.items.contains(&write.item) {
+                                *writes.entry(write.item.clone()).or_default() += 1;
+                            }
+                        }
+                    }
+
+                    // Transfers between entries of a summed item keep the sum
+                    let moves = |item: &String| {
+                        invariant.summed.contains(item) && writes.get(item).is_some_and(|n| *n >= 2)
+                    };
+                    let written: Vec<&String> = invariant
+                        .items
+                        .iter()
+                        .filter(|i| writes.contains_key(*i) && !moves(i))
+                        .collect();
+                    let missing: Vec<&String> = invariant
+                        .items
+                        .iter()
+                        .filter(|i| !writes.contains_key(*i))
+                        .collect();
+                    if written.is_empty() || missing.is_empty() {
+                        continue;
+                    }
+
+                    let Some(start) = ctx.span_of(&arm.pat) else {
+                        continue;
+                    };
+                    let span = SourceSpan {
+                        file: file.clone(),
+                        start_line: start.start_line,
+                        end_line: start.start_line,
+                        start_col: start.start_col,
+                        end_col: start.start_col,
+                        start_byte: start.start_byte,
+                        end_byte: start.start_byte,
+                    };
+                    let list = |items: &[&String]| {
+                        items
+                            .iter()
+                            .map(|i| format!("`{i}`"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    };
+                    let mut finding = self.finding(
+                        format!(
+                            "`{enum_name}::{variant}` updates {} but not {}",
+                            list(&written),
+                            list(&missing)
+                        ),
+                        format!(
+                            "The invariant {} relates {}, but the handler for \
+                             `{enum_name}::{variant}` writes {} without updating {}. \
+                             Each call lets the two sides drift further apart.",
+                            invariant.display(),
+                            list(&invariant.items.iter().collect::<Vec<_>>()),
+                            list(&written),
+                            list(&missing)
+                        ),
+                        &span,
+                        format!(
+                            "Update {} in the same handler, or save both sides through one \
+                             helper that keeps them in sync.",
+                            list(&missing)
+                        ),
+                    );
+                    if let Some(declared_at) = &invariant.span {
+                        finding.locations.push(SourceLocation {
+                            file: declared_at.file.clone(),
+                            start_line: declared_at.start_line,
+                            end_line: declared_at.end_line,
+                            start_col: declared_at.start_col,
+                            end_col: declared_at.end_col,
+                            snippet: None,
+                            byte_range: None,
+                        });
+                    }
+                    findings.push(finding);
+                }
+            }
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::config::Config;
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::path::PathBuf;
+
+    fn analyze_with(source: &str, config: &Config) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources).with_config(config);
+        InvariantDrift.detect(&ctx)
+    }
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        analyze_with(source, &Config::default())
+    }
+
+    const TOKEN: &str = r#"
+        // guard-invariant: total_supply == sum(balances)
+        pub const TOTAL_SUPPLY: Item<Uint128> = Item::new("total_supply");
+        pub const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
+
+        #[entry_point]
+        pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+            -> Result<Response, ContractError> {
+            match msg {
+                ExecuteMsg::Mint { amount } => execute_mint(deps, info, amount),
+                ExecuteMsg::Burn { amount } => execute_burn(deps, info, amount),
+                ExecuteMsg::Transfer { to, amount } => execute_transfer(deps, info, to, amount),
+            }
+        }
+
+        fn execute_mint(deps: DepsMut, info: MessageInfo, amount: Uint128)
+            -> Result<Response, ContractError> {
+            BALANCES.update(deps.storage, &info.sender, |b| -> StdResult<_> {
+                Ok(b.unwrap_or_default() + amount)
+            })?;
+            Ok(Response::new())
+        }
+
+        fn execute_burn(deps: DepsMut, info: MessageInfo, amount: Uint128)
+            -> Result<Response, ContractError> {
+            BALANCES.update(deps.storage, &info.sender, |b| -> StdResult<_> {
+                Ok(b.unwrap_or_default() - amount)
+            })?;
+            TOTAL_SUPPLY.update(deps.storage, |t| -> StdResult<_> { Ok(t - amount) })?;
+            Ok(Response::new())
+        }
+
+        fn execute_transfer(deps: DepsMut, info: MessageInfo, to: Addr, amount: Uint128)
+            -> Result<Response, ContractError> {
+            BALANCES.update(deps.storage, &info.sender, |b| -> StdResult<_> {
+                Ok(b.unwrap_or_default() - amount)
+            })?;
+            BALANCES.update(deps.storage, &to, |b| -> StdResult<_> {
+                Ok(b.unwrap_or_default() + amount)
+            })?;
+            Ok(Response::new())
+        }
+    "#;
+
+    #[test]
+    fn test_detects_mint_without_supply_update() {
+        let findings = analyze(TOKEN);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].title,
+            "`ExecuteMsg::Mint` updates `BALANCES` but not `TOTAL_SUPPLY`"
+        );
+        // Points at the handler and at the declaring comment
+        assert_eq!(findings[0].locations.len(), 2);
+        assert_eq!(findings[0].locations[1].start_line, 2);
+    }
+
+    #[test]
+    fn test_config_invariant() {
+        let source = TOKEN.replace("// guard-invariant:", "//");
+        assert!(analyze(&source).is_empty());
+        let config: Config =
+            toml::from_str("[invariants]\nsupply = \"TOTAL_SUPPLY == sum(BALANCES)\"").unwrap();
+        let findings = analyze_with(&source, &config);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].description.contains("`supply`"));
+        assert_eq!(findings[0].locations.len(), 1);
+    }
+
+    #[test]
+    fn test_inline_arm_writes_count() {
+        let source = TOKEN.replace(
+            "ExecuteMsg::Mint { amount } => execute_mint(deps, info, amount),",
+            "ExecuteMsg::Mint { amount } => {
+                    TOTAL_SUPPLY.update(deps.storage, |t| -> StdResult<_> { Ok(t + amount) })?;
+                    execute_mint(deps, info, amount)
+                }",
+        );
+        assert!(analyze(&source).is_empty());
+    }
+}
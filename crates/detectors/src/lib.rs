@@ -1,16 +1,47 @@
+pub mod addr_stored_as_string;
 pub mod arithmetic_overflow;
+pub mod complexity_metrics;
+pub mod dead_code;
+pub mod entry_point_signature;
+pub mod floating_point;
+pub mod foreign_address_prefix;
 pub mod incorrect_permission_hierarchy;
+pub mod instantiate_msg_validation;
+pub mod invariant_drift;
 pub mod missing_access_control;
 pub mod missing_addr_validate;
 pub mod missing_error_propagation;
 pub mod missing_funds_validation;
 pub mod missing_migration_version;
+pub mod missing_status_check;
 pub mod nondeterministic_iteration;
+pub mod oracle_staleness;
+pub mod pagination_bound_order;
+pub mod pause_consistency;
+pub mod query_response_mismatch;
+pub mod query_toctou;
+pub mod reentrancy_state_after_msg;
+pub mod repeated_storage_load;
+pub mod role_check_bypass;
+pub mod rounding_direction;
+pub mod share_inflation;
+pub mod storage_collect_in_execute;
 pub mod storage_key_collision;
+pub mod stored_type_serde;
 pub mod submessage_reply;
+pub mod test_code_in_contract;
 pub mod unbounded_iteration;
+pub mod unbounded_message_field;
+pub mod unbounded_message_loop;
+pub mod unchecked_claim_maturity;
+pub mod unchecked_fee_bounds;
+pub mod unchecked_subtraction;
+pub mod unguarded_parameter_update;
 pub mod uninitialized_state_access;
 pub mod unsafe_unwrap;
+pub mod unsupported_capability;
+pub mod unverified_cw20_receive;
+pub mod voting_power_snapshot;
 
 /// Returns all built-in detectors
 pub fn all_detectors() -> Vec<Box<dyn cosmwasm_guard::detector::Detector>> {
@@ -28,5 +59,97 @@ pub fn all_detectors() -> Vec<Box<dyn cosmwasm_guard::detector::Detector>> {
         Box::new(missing_funds_validation::MissingFundsValidation),
         Box::new(uninitialized_state_access::UninitializedStateAccess),
         Box::new(missing_migration_version::MissingMigrationVersion),
+        Box::new(instantiate_msg_validation::InstantiateMsgValidation),
+        Box::new(unchecked_fee_bounds::UncheckedFeeBounds),
+        Box::new(storage_collect_in_execute::StorageCollectInExecute),
+        Box::new(complexity_metrics::ComplexityMetrics),
+        Box::new(unbounded_message_loop::UnboundedMessageLoop),
+        Box::new(unbounded_message_field::UnboundedMessageField),
+        Box::new(entry_point_signature::EntryPointSignature),
+        Box::new(submessage_reply::ReplyResultUnchecked),
+        Box::new(addr_stored_as_string::AddrStoredAsString),
+        Box::new(stored_type_serde::StoredTypeSerde),
+        Box::new(query_response_mismatch::QueryResponseMismatch),
+        Box::new(unverified_cw20_receive::UnverifiedCw20Receive),
+        Box::new(unguarded_parameter_update::UnguardedParameterUpdate),
+        Box::new(pause_consistency::PauseConsistency),
+        Box::new(rounding_direction::RoundingDirection),
+        Box::new(unchecked_subtraction::UncheckedSubtraction),
+        Box::new(pagination_bound_order::PaginationBoundOrder),
+        Box::new(role_check_bypass::RoleCheckBypass),
+        Box::new(dead_code::DeadCode),
+        Box::new(share_inflation::ShareInflation),
+        Box::new(oracle_staleness::OracleStaleness),
+        Box::new(voting_power_snapshot::VotingPowerSnapshot),
+        Box::new(unchecked_claim_maturity::UncheckedClaimMaturity),
+        Box::new(missing_status_check::MissingStatusCheck),
+        Box::new(invariant_drift::InvariantDrift),
+        Box::new(query_toctou::QueryToctou),
+        Box::new(test_code_in_contract::TestCodeInContract),
+        Box::new(floating_point::FloatingPoint),
+        Box::new(foreign_address_prefix::ForeignAddressPrefix),
+        Box::new(unsupported_capability::UnsupportedCapability),
+        Box::new(repeated_storage_load::RepeatedStorageLoad),
+        Box::new(reentrancy_state_after_msg::ReentrancyStateAfterMsg),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::codes;
+    use cosmwasm_guard::detector::{AnalysisContext, DetectorRegistry};
+    use cosmwasm_guard::finding::Finding;
+    use cosmwasm_guard::ir::builder::IrBuilder;
+
+    #[test]
+    fn test_every_detector_has_a_code() {
+        for detector in super::all_detectors() {
+            assert!(
+                codes::code_of(detector.name()).is_some(),
+                "no code for {}",
+                detector.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_parallel_run_matches_sequential() {
+        let source = r#"
+            #[entry_point]
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+                let config = CONFIG.load(deps.storage).unwrap();
+                let _ = helper(deps.storage);
+                let total = BALANCES.range(deps.storage, None, None, Order::Ascending).collect::<Vec<_>>();
+                Ok(Response::new())
+            }
+        "#;
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([(PathBuf::from("test.rs"), source.to_string())]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+
+        let mut registry = DetectorRegistry::new();
+        registry.register_all(super::all_detectors());
+        let parallel = registry.run_all(&ctx);
+
+        let mut sequential: Vec<_> = super::all_detectors()
+            .iter()
+            .flat_map(|d| d.detect(&ctx))
+            .collect();
+        sequential.sort_by(|a, b| a.severity.cmp(&b.severity));
+        let key = |f: &Finding| {
+            let location = f.locations.first().map(|l| (l.start_line, l.start_col));
+            (f.detector_name.clone(), location)
+        };
+        assert!(!parallel.is_empty());
+        assert_eq!(
+            parallel.iter().map(key).collect::<Vec<_>>(),
+            sequential.iter().map(key).collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use cosmwasm_guard::ast::{EntryPointKind, FunctionInfo};
+use cosmwasm_guard::auth::AuthRegistry;
 use cosmwasm_guard::detector::{AnalysisContext, Detector};
 use cosmwasm_guard::finding::*;
 use syn::visit::Visit;
@@ -8,12 +9,20 @@ use syn::visit::Visit;
 /// via match arms, checks those handlers for sender checks too.
 pub struct MissingAccessControl;
 
+/// Execute variants callable by anyone by design, matched as name prefixes
+/// (`ClaimRewards` is a `Claim`). Overridable via
+/// `[detectors.missing-access-control] permissionless`
+const DEFAULT_PERMISSIONLESS: &[&str] = &[
+    "Deposit", "Claim", "Receive", "Vote", "Stake", "Bond", "Swap",
+];
+
 /// Visitor that searches for info.sender usage in expressions
-struct SenderCheckSearcher {
+struct SenderCheckSearcher<'a> {
     found_sender_check: bool,
+    auth: &'a AuthRegistry,
 }
 
-impl<'ast> Visit<'ast> for SenderCheckSearcher {
+impl<'ast> Visit<'ast> for SenderCheckSearcher<'_> {
     fn visit_expr_field(&mut self, node: &'ast syn::ExprField) {
         if let syn::Member::Named(ident) = &node.member {
             if ident == "sender" && is_info_expr(&node.base) {
@@ -24,8 +33,8 @@ impl<'ast> Visit<'ast> for SenderCheckSearcher {
     }
 
     fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
-        // Recognize ecosystem access-control helpers:
-        // assert_owner(), is_owner(), cw_ownable::assert_owner(), etc.
+        // Recognize access-control helpers: assert_owner(), cw_ownable::*,
+        // and project helpers from the registry
         if let syn::Expr::Path(path) = node.func.as_ref() {
             let full_path = path
                 .path
@@ -34,28 +43,15 @@ impl<'ast> Visit<'ast> for SenderCheckSearcher {
                 .map(|s| s.ident.to_string())
                 .collect::<Vec<_>>()
                 .join("::");
-            let last_segment = path.path.segments.last().map(|s| s.ident.to_string());
-            if let Some(name) = last_segment {
-                if name == "assert_owner"
-                    || name == "is_owner"
-                    || name == "check_owner"
-                    || name == "validate_owner"
-                    || full_path.contains("cw_ownable")
-                {
-                    self.found_sender_check = true;
-                }
+            if self.auth.is_helper_call(&full_path) {
+                self.found_sender_check = true;
             }
         }
         syn::visit::visit_expr_call(self, node);
     }
 
     fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
-        let method = node.method.to_string();
-        if method == "assert_owner"
-            || method == "is_owner"
-            || method == "check_owner"
-            || method == "validate_owner"
-        {
+        if self.auth.is_helper_method(&node.method.to_string()) {
             self.found_sender_check = true;
         }
         syn::visit::visit_expr_method_call(self, node);
@@ -68,11 +64,7 @@ impl<'ast> Visit<'ast> for SenderCheckSearcher {
             .last()
             .map(|s| s.ident.to_string())
             .unwrap_or_default();
-        if macro_name == "ensure_eq"
-            || macro_name == "ensure"
-            || macro_name == "require"
-            || macro_name == "assert_eq"
-        {
+        if self.auth.is_assert_macro(&macro_name) {
             let tokens = node.tokens.to_string();
             // Direct sender check: ensure_eq!(info.sender, ...)
             if tokens.contains("info") && tokens.contains("sender") {
@@ -115,15 +107,27 @@ fn is_info_expr(expr: &syn::Expr) -> bool {
     }
 }
 
-/// Check if a function body has an info.sender check
-fn has_sender_check(body: &syn::Block) -> bool {
+/// Check if a function body has an info.sender check or calls an
+/// access-control helper
+pub(crate) fn has_sender_check(body: &syn::Block, auth: &AuthRegistry) -> bool {
     let mut searcher = SenderCheckSearcher {
         found_sender_check: false,
+        auth,
     };
     syn::visit::visit_block(&mut searcher, body);
     searcher.found_sender_check
 }
 
+/// Check if an expression (e.g. a match arm body) has an info.sender check
+pub(crate) fn expr_has_sender_check(expr: &syn::Expr, auth: &AuthRegistry) -> bool {
+    let mut searcher = SenderCheckSearcher {
+        found_sender_check: false,
+        auth,
+    };
+    syn::visit::visit_expr(&mut searcher, expr);
+    searcher.found_sender_check
+}
+
 /// Extract function names called from match arms in a block (dispatch pattern)
 fn extract_dispatched_functions(body: &syn::Block) -> Vec<String> {
     let mut collector = DispatchCallCollector {
@@ -140,10 +144,40 @@ fn extract_dispatched_functions(body: &syn::Block) -> Vec<String> {
     collector.called_functions
 }
 
+/// Execute message variants matched at the top level of a block
+fn extract_dispatched_variants(body: &syn::Block) -> Vec<String> {
+    let mut variants = Vec::new();
+    for stmt in &body.stmts {
+        if let syn::Stmt::Expr(syn::Expr::Match(m), _) = stmt {
+            for arm in &m.arms {
+                let path = match &arm.pat {
+                    syn::Pat::Struct(p) => &p.path,
+                    syn::Pat::TupleStruct(p) => &p.path,
+                    syn::Pat::Path(p) => &p.path,
+                    _ => continue,
+                };
+                if path.segments.len() >= 2 {
+                    if let Some(last) = path.segments.last() {
+                        variants.push(last.ident.to_string());
+                    }
+                }
+            }
+        }
+    }
+    variants
+}
+
+fn is_permissionless(variant: &str, permissionless: &[String]) -> bool {
+    permissionless
+        .iter()
+        .any(|p| variant.starts_with(p.as_str()))
+}
+
 /// Check if dispatched handler functions have sender checks
 fn handlers_have_sender_checks(
     dispatched_fns: &[String],
     all_functions: &[FunctionInfo],
+    auth: &AuthRegistry,
 ) -> bool {
     if dispatched_fns.is_empty() {
         return false;
@@ -154,7 +188,7 @@ fn handlers_have_sender_checks(
             .iter()
             .find(|f| f.name == *fn_name)
             .and_then(|f| f.body.as_ref())
-            .is_some_and(has_sender_check)
+            .is_some_and(|body| has_sender_check(body, auth))
     })
 }
 
@@ -176,6 +210,16 @@ impl Detector for MissingAccessControl {
     }
 
     fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let permissionless: Vec<String> = ctx
+            .config()
+            .detector_option(self.name(), "permissionless")
+            .unwrap_or_else(|| {
+                DEFAULT_PERMISSIONLESS
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect()
+            });
+
         let mut findings = Vec::new();
 
         for ep in &ctx.contract.entry_points {
@@ -188,27 +232,69 @@ impl Detector for MissingAccessControl {
             let Some(body) = &func.body else { continue };
 
             // Direct check: does the execute function body itself check info.sender?
-            if has_sender_check(body) {
+            if has_sender_check(body, ctx.auth()) {
                 continue;
             }
 
             // Dispatch following: does execute() delegate to handler functions
             // that check info.sender?
             let dispatched = extract_dispatched_functions(body);
-            if handlers_have_sender_checks(&dispatched, &ctx.contract.functions) {
+            if handlers_have_sender_checks(&dispatched, &ctx.contract.functions, ctx.auth()) {
                 continue;
             }
 
+            // Variants that are permissionless by design need no sender check;
+            // when every variant is, report at Informational so the assumption
+            // stays auditable
+            let variants = extract_dispatched_variants(body);
+            let (open, restricted): (Vec<&String>, Vec<&String>) = variants
+                .iter()
+                .partition(|v| is_permissionless(v, &permissionless));
+            let all_open = !open.is_empty() && restricted.is_empty();
+            let open_note = if open.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    " Variants considered permissionless by design: {}.",
+                    open.iter()
+                        .map(|v| format!("`{v}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            };
+
+            let (title, description, severity) = if all_open {
+                (
+                    format!(
+                        "Execute handler `{}` has only permissionless variants",
+                        ep.name
+                    ),
+                    format!(
+                        "Execute handler `{}` does not check `info.sender`, but each of its \
+                         variants is a user-facing operation that is usually callable by \
+                         anyone.{} Confirm none of them needs a role.",
+                        ep.name, open_note
+                    ),
+                    Severity::Informational,
+                )
+            } else {
+                (
+                    format!("Missing access control in execute handler `{}`", ep.name),
+                    format!(
+                        "Execute handler `{}` does not check `info.sender` for authorization. \
+                         Any user can call this function, which may lead to unauthorized \
+                         state changes or fund transfers.{}",
+                        ep.name, open_note
+                    ),
+                    Severity::High,
+                )
+            };
+
             findings.push(Finding {
                 detector_name: self.name().to_string(),
-                title: format!("Missing access control in execute handler `{}`", ep.name),
-                description: format!(
-                    "Execute handler `{}` does not check `info.sender` for authorization. \
-                     Any user can call this function, which may lead to unauthorized \
-                     state changes or fund transfers.",
-                    ep.name
-                ),
-                severity: Severity::High,
+                title,
+                description,
+                severity,
                 confidence: Confidence::Medium,
                 locations: vec![SourceLocation {
                     file: ep.span.file.clone(),
@@ -217,13 +303,20 @@ impl Detector for MissingAccessControl {
                     start_col: ep.span.start_col,
                     end_col: ep.span.end_col,
                     snippet: None,
+                    byte_range: None,
                 }],
-                recommendation: Some(
+                recommendation: Some(if all_open {
+                    "If a variant must be restricted, add a sender check to its handler; \
+                     otherwise list it under `[detectors.missing-access-control] \
+                     permissionless`."
+                        .to_string()
+                } else {
                     "Add an authorization check: \
                      `if info.sender != config.owner { return Err(...); }`"
-                        .to_string(),
-                ),
+                        .to_string()
+                }),
                 fix: None,
+                ..Default::default()
             });
         }
 
@@ -235,20 +328,25 @@ impl Detector for MissingAccessControl {
 mod tests {
     use super::*;
     use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::config::Config;
     use cosmwasm_guard::ir::builder::IrBuilder;
     use std::collections::HashMap;
     use std::path::PathBuf;
 
-    fn analyze(source: &str) -> Vec<Finding> {
+    fn analyze_with(source: &str, config: &Config) -> Vec<Finding> {
         let ast = parse_source(source).unwrap();
         let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
         let ir = IrBuilder::build_contract(&contract);
         let mut sources = HashMap::new();
         sources.insert(PathBuf::from("test.rs"), source.to_string());
-        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources).with_config(config);
         MissingAccessControl.detect(&ctx)
     }
 
+    fn analyze(source: &str) -> Vec<Finding> {
+        analyze_with(source, &Config::default())
+    }
+
     #[test]
     fn test_detects_missing_access_control() {
         let source = r#"
@@ -387,4 +485,87 @@ mod tests {
             "H6: dispatch to handler without sender check should still flag"
         );
     }
+
+    #[test]
+    fn test_trusted_validators_count_as_access_control() {
+        let annotated = r#"
+            #[cfg_attr(cosmwasm_guard, cosmwasm_guard::assume(sender))]
+            fn assert_operator(deps: Deps, info: &MessageInfo) -> StdResult<()> {
+                let operator = OPERATOR.load(deps.storage)?;
+                ensure_eq!(operator, info.sender, StdError::generic_err("unauthorized"));
+                Ok(())
+            }
+
+            #[entry_point]
+            pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> StdResult<Response> {
+                assert_operator(deps.as_ref(), &info)?;
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(annotated).is_empty());
+
+        let configured = r#"
+            #[entry_point]
+            pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> StdResult<Response> {
+                auth::assert_operator(deps.as_ref(), &info)?;
+                Ok(Response::new())
+            }
+        "#;
+        assert_eq!(analyze(configured).len(), 1);
+
+        let config: Config = toml::from_str("[auth]\nhelpers = [\"assert_operator\"]").unwrap();
+        assert!(analyze_with(configured, &config).is_empty());
+    }
+
+    #[test]
+    fn test_permissionless_variants_reported_as_informational() {
+        let source = r#"
+            #[entry_point]
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> StdResult<Response> {
+                match msg {
+                    ExecuteMsg::Deposit {} => execute_deposit(deps, info),
+                    ExecuteMsg::ClaimRewards {} => execute_claim(deps, info),
+                }
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Informational);
+        assert!(findings[0]
+            .description
+            .contains("`Deposit`, `ClaimRewards`"));
+
+        let mixed = source.replace(
+            "ExecuteMsg::ClaimRewards {} => execute_claim(deps, info),",
+            "ExecuteMsg::ClaimRewards {} => execute_claim(deps, info),
+             ExecuteMsg::SetConfig { config } => execute_set_config(deps, config),",
+        );
+        let findings = analyze(&mixed);
+        assert_eq!(findings[0].severity, Severity::High);
+        assert!(findings[0].description.contains("permissionless by design"));
+    }
+
+    #[test]
+    fn test_permissionless_variants_configurable() {
+        let source = r#"
+            #[entry_point]
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> StdResult<Response> {
+                match msg {
+                    ExecuteMsg::Mint {} => execute_mint(deps, info),
+                }
+            }
+        "#;
+        assert_eq!(analyze(source)[0].severity, Severity::High);
+        let config: Config =
+            toml::from_str("[detectors.missing-access-control]\npermissionless = [\"Mint\"]")
+                .unwrap();
+        assert_eq!(
+            analyze_with(source, &config)[0].severity,
+            Severity::Informational
+        );
+    }
 }
@@ -20,7 +20,7 @@ const ADDRESS_PATTERNS: &[&str] = &[
     "guardian",
 ];
 
-fn is_address_field_name(name: &str) -> bool {
+pub(crate) fn is_address_field_name(name: &str) -> bool {
     let lower = name.to_lowercase();
     ADDRESS_PATTERNS.iter().any(|p| lower.contains(p))
 }
@@ -112,12 +112,14 @@ impl Detector for MissingAddrValidate {
                                     start_col: msg_enum.span.start_col,
                                     end_col: msg_enum.span.end_col,
                                     snippet: None,
+                                    byte_range: None,
                                 }],
                                 recommendation: Some(format!(
                                     "Validate the address with `deps.api.addr_validate(&{})?;`",
                                     field.name
                                 )),
                                 fix: None,
+                                ..Default::default()
                             });
                         }
                     }
@@ -1,3 +1,4 @@
+use cosmwasm_guard::ast::SourceSpan;
 use cosmwasm_guard::detector::{AnalysisContext, Detector};
 use cosmwasm_guard::finding::*;
 use syn::visit::Visit;
@@ -6,11 +7,13 @@ use syn::visit::Visit;
 /// Common source of bugs when Result values are ignored.
 pub struct MissingErrorPropagation;
 
-struct WildcardLetSearcher {
-    findings: Vec<(usize, usize)>,
+struct WildcardLetSearcher<'c> {
+    ctx: &'c AnalysisContext<'c>,
+    /// Line and column of the `_`, and the initializer `.ok()` can follow
+    findings: Vec<(usize, usize, Option<SourceSpan>)>,
 }
 
-impl<'ast> Visit<'ast> for WildcardLetSearcher {
+impl<'ast> Visit<'ast> for WildcardLetSearcher<'_> {
     fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
         // Skip #[cfg(test)] modules — test code legitimately discards Results
         let is_test = node.attrs.iter().any(|attr| {
@@ -35,8 +38,14 @@ impl<'ast> Visit<'ast> for WildcardLetSearcher {
             if let Some(init) = &node.init {
                 // Only flag if RHS is a function/method call (likely fallible)
                 if is_call_expr(&init.expr) {
-                    let span = wild.underscore_token.span;
-                    self.findings.push((span.start().line, span.start().column));
+                    if let Some(span) = self.ctx.span_of(wild) {
+                        // `expr?.ok()` would acknowledge something else
+                        let call = match init.expr.as_ref() {
+                            syn::Expr::Try(_) => None,
+                            expr => self.ctx.span_of(expr),
+                        };
+                        self.findings.push((span.start_line, span.start_col, call));
+                    }
                 }
             }
         }
@@ -73,11 +82,12 @@ impl Detector for MissingErrorPropagation {
 
         for (path, ast) in ctx.raw_asts() {
             let mut searcher = WildcardLetSearcher {
+                ctx,
                 findings: Vec::new(),
             };
             syn::visit::visit_file(&mut searcher, ast);
 
-            for (line, col) in &searcher.findings {
+            for (line, col, call) in &searcher.findings {
                 findings.push(Finding {
                     detector_name: self.name().to_string(),
                     title: "Silently discarded Result value".to_string(),
@@ -94,24 +104,28 @@ impl Detector for MissingErrorPropagation {
                         start_col: *col,
                         end_col: *col,
                         snippet: None,
+                        byte_range: None,
                     }],
                     recommendation: Some(
                         "Handle the error with `?` or explicitly ignore with `.ok()`."
                             .to_string(),
                     ),
-                    fix: Some(FixSuggestion {
-                        description: "Add `.ok()` to explicitly acknowledge the discarded Result"
-                            .to_string(),
-                        replacement_text: "let _ = /* expr */.ok();".to_string(),
-                        location: SourceLocation {
-                            file: path.clone(),
-                            start_line: *line,
-                            end_line: *line,
-                            start_col: *col,
-                            end_col: *col,
-                            snippet: None,
-                        },
+                    fix: call.as_ref().map(|call| {
+                        FixSuggestion::replace(
+                            "Add `.ok()` to explicitly acknowledge the discarded Result",
+                            SourceLocation {
+                                file: path.clone(),
+                                start_line: call.end_line,
+                                end_line: call.end_line,
+                                start_col: call.end_col,
+                                end_col: call.end_col,
+                                snippet: None,
+                                byte_range: Some(call.end_byte..call.end_byte),
+                            },
+                            ".ok()",
+                        )
                     }),
+                    ..Default::default()
                 });
             }
         }
@@ -148,6 +162,14 @@ mod tests {
         let findings = analyze(source);
         assert!(!findings.is_empty());
         assert_eq!(findings[0].detector_name, "missing-error-propagation");
+
+        let edit = &findings[0].fix.as_ref().unwrap().edits[0];
+        let mut fixed = source.to_string();
+        fixed.replace_range(
+            edit.location.byte_range.clone().unwrap(),
+            &edit.replacement_text,
+        );
+        assert!(fixed.contains("let _ = CONFIG.save(deps.storage, &config).ok();"));
     }
 
     #[test]
@@ -1,3 +1,4 @@
+use cosmwasm_guard::ast::TrustedCheck;
 use cosmwasm_guard::detector::{AnalysisContext, Detector};
 use cosmwasm_guard::finding::*;
 
@@ -24,6 +25,7 @@ impl Detector for MissingFundsValidation {
 
     fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
         let mut findings = Vec::new();
+        let trusted = ctx.trusted_validators(TrustedCheck::Funds);
 
         for ep in &ctx.contract.entry_points {
             // Only check execute entry points (they receive funds via MessageInfo)
@@ -38,7 +40,7 @@ impl Detector for MissingFundsValidation {
                 .iter()
                 .find(|f| f.name == ep.name)
                 .and_then(|f| f.body.as_ref())
-                .is_some_and(|body| body_references_funds(body));
+                .is_some_and(|body| body_references_funds(body, &trusted));
 
             if !has_funds_check {
                 findings.push(Finding {
@@ -60,6 +62,7 @@ impl Detector for MissingFundsValidation {
                         start_col: ep.span.start_col,
                         end_col: ep.span.end_col,
                         snippet: None,
+                        byte_range: None,
                     }],
                     recommendation: Some(
                         "Add `if !info.funds.is_empty() { return Err(...) }` for handlers \
@@ -67,6 +70,7 @@ impl Detector for MissingFundsValidation {
                             .to_string(),
                     ),
                     fix: None,
+                    ..Default::default()
                 });
             }
         }
@@ -76,14 +80,16 @@ impl Detector for MissingFundsValidation {
 }
 
 /// Check if a syn::Block references "funds" anywhere (field access, variable, etc.)
-fn body_references_funds(block: &syn::Block) -> bool {
+/// or calls one of the `trusted` funds validators
+fn body_references_funds(block: &syn::Block, trusted: &[String]) -> bool {
     use syn::visit::Visit;
 
-    struct FundsSearcher {
+    struct FundsSearcher<'a> {
         found: bool,
+        trusted: &'a [String],
     }
 
-    impl<'ast> Visit<'ast> for FundsSearcher {
+    impl<'ast> Visit<'ast> for FundsSearcher<'_> {
         fn visit_expr_field(&mut self, node: &'ast syn::ExprField) {
             if let syn::Member::Named(ident) = &node.member {
                 if ident == "funds" {
@@ -99,16 +105,30 @@ fn body_references_funds(block: &syn::Block) -> bool {
             if let syn::Expr::Path(path) = node.func.as_ref() {
                 if let Some(last) = path.path.segments.last() {
                     let name = last.ident.to_string();
-                    if name == "must_pay" || name == "nonpayable" || name == "one_coin" {
+                    if name == "must_pay"
+                        || name == "nonpayable"
+                        || name == "one_coin"
+                        || self.trusted.contains(&name)
+                    {
                         self.found = true;
                     }
                 }
             }
             syn::visit::visit_expr_call(self, node);
         }
+
+        fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+            if self.trusted.contains(&node.method.to_string()) {
+                self.found = true;
+            }
+            syn::visit::visit_expr_method_call(self, node);
+        }
     }
 
-    let mut searcher = FundsSearcher { found: false };
+    let mut searcher = FundsSearcher {
+        found: false,
+        trusted,
+    };
     syn::visit::visit_block(&mut searcher, block);
     searcher.found
 }
@@ -117,20 +137,25 @@ fn body_references_funds(block: &syn::Block) -> bool {
 mod tests {
     use super::*;
     use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::config::Config;
     use cosmwasm_guard::ir::builder::IrBuilder;
     use std::collections::HashMap;
     use std::path::PathBuf;
 
-    fn analyze(source: &str) -> Vec<Finding> {
+    fn analyze_with(source: &str, config: &Config) -> Vec<Finding> {
         let ast = parse_source(source).unwrap();
         let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
         let ir = IrBuilder::build_contract(&contract);
         let mut sources = HashMap::new();
         sources.insert(PathBuf::from("test.rs"), source.to_string());
-        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources).with_config(config);
         MissingFundsValidation.detect(&ctx)
     }
 
+    fn analyze(source: &str) -> Vec<Finding> {
+        analyze_with(source, &Config::default())
+    }
+
     #[test]
     fn test_detects_missing_funds_check() {
         let source = r#"
@@ -215,4 +240,20 @@ mod tests {
         let findings = analyze(source);
         assert!(findings.is_empty());
     }
+
+    #[test]
+    fn test_trusted_funds_validator_counts_as_validation() {
+        let source = r#"
+            #[entry_point]
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> Result<Response, ContractError> {
+                let amount = payments::check_payment(&info)?;
+                Ok(Response::new())
+            }
+        "#;
+        assert_eq!(analyze(source).len(), 1);
+
+        let config: Config = toml::from_str("[guards]\nfunds = [\"check_payment\"]").unwrap();
+        assert!(analyze_with(source, &config).is_empty());
+    }
 }
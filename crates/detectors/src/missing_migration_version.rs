@@ -39,7 +39,7 @@ impl Detector for MissingMigrationVersion {
                 .iter()
                 .find(|f| f.name == ep.name)
                 .and_then(|f| f.body.as_ref())
-                .is_some_and(|body| body_has_version_call(body));
+                .is_some_and(body_has_version_call);
 
             if !has_version_call {
                 findings.push(Finding {
@@ -62,6 +62,7 @@ impl Detector for MissingMigrationVersion {
                         start_col: ep.span.start_col,
                         end_col: ep.span.end_col,
                         snippet: None,
+                        byte_range: None,
                     }],
                     recommendation: Some(
                         "Add `cw2::set_contract_version(deps.storage, CONTRACT_NAME, \
@@ -70,6 +71,7 @@ impl Detector for MissingMigrationVersion {
                             .to_string(),
                     ),
                     fix: None,
+                    ..Default::default()
                 });
             }
         }
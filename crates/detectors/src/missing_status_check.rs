@@ -0,0 +1,565 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use cosmwasm_guard::ast::{EntryPointKind, MessageKind, SourceSpan};
+use cosmwasm_guard::config::StateMachine;
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::GuardKind;
+use quote::ToTokens;
+use syn::visit::Visit;
+
+use crate::pause_consistency::{CallCollector, ExecuteArmCollector};
+
+/// Checks the state machines declared under `[[state_machines]]`: the handler
+/// of each execute variant listed in `requires` must check the status before
+/// acting, and the check must rule out every state the variant may not run
+/// in. Missing transition guards ("Deposit into a Closed pool") are logic bugs
+/// no generic detector can know about.
+pub struct MissingStatusCheck;
+
+/// Idents of `node` that name a state
+fn states_in(node: &impl ToTokens, states: &HashSet<&str>, out: &mut BTreeSet<String>) {
+    fn walk(stream: proc_macro2::TokenStream, states: &HashSet<&str>, out: &mut BTreeSet<String>) {
+        for tree in stream {
+            match tree {
+                proc_macro2::TokenTree::Group(group) => walk(group.stream(), states, out),
+                proc_macro2::TokenTree::Ident(ident) => {
+                    let ident = ident.to_string();
+                    if states.contains(ident.as_str()) {
+                        out.insert(ident);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    walk(node.to_token_stream(), states, out);
+}
+
+/// Whether an expression reads the status: the status field, a load of the
+/// item when it is the status itself, or a local bound to either
+struct StatusRead<'a> {
+    machine: &'a StateMachine,
+    locals: &'a HashSet<String>,
+    found: bool,
+}
+
+impl<'ast> Visit<'ast> for StatusRead<'_> {
+    fn visit_expr_field(&mut self, node: &'ast syn::ExprField) {
+        if let (Some(field), syn::Member::Named(member)) = (&self.machine.field, &node.member) {
+            self.found |= member == field;
+        }
+        syn::visit::visit_expr_field(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if self.machine.field.is_none()
+            && matches!(node.method.to_string().as_str(), "load" | "may_load")
+            && matches!(node.receiver.as_ref(), syn::Expr::Path(p) if p.path.is_ident(&self.machine.item))
+        {
+            self.found = true;
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_expr_path(&mut self, node: &'ast syn::ExprPath) {
+        if let Some(ident) = node.path.get_ident() {
+            self.found |= self.locals.contains(&ident.to_string());
+        }
+    }
+}
+
+/// States a body compares the status against, in `==`/`!=`, `match`,
+/// `if let` and `matches!`
+struct StatusChecks<'a> {
+    machine: &'a StateMachine,
+    states: &'a HashSet<&'a str>,
+    /// Locals holding the status, or the struct it lives in
+    locals: HashSet<String>,
+    checked: BTreeSet<String>,
+}
+
+impl<'a> StatusChecks<'a> {
+    fn new(machine: &'a StateMachine, states: &'a HashSet<&'a str>) -> Self {
+        Self {
+            machine,
+            states,
+            locals: HashSet::new(),
+            checked: BTreeSet::new(),
+        }
+    }
+
+    fn reads_status(&self, expr: &syn::Expr) -> bool {
+        let mut read = StatusRead {
+            machine: self.machine,
+            locals: &self.locals,
+            found: false,
+        };
+        read.visit_expr(expr);
+        read.found
+    }
+
+    fn record(&mut self, node: &impl ToTokens) {
+        states_in(node, self.states, &mut self.checked);
+    }
+}
+
+fn pat_idents(pat: &syn::Pat, out: &mut HashSet<String>) {
+    match pat {
+        syn::Pat::Ident(p) => {
+            out.insert(p.ident.to_string());
+        }
+        syn::Pat::Type(p) => pat_idents(&p.pat, out),
+        syn::Pat::Reference(p) => pat_idents(&p.pat, out),
+        _ => {}
+    }
+}
+
+impl<'ast> Visit<'ast> for StatusChecks<'_> {
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        syn::visit::visit_local(self, node);
+        if let Some(init) = &node.init {
+            if self.reads_status(&init.expr) {
+                pat_idents(&node.pat, &mut self.locals);
+            }
+        }
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        if matches!(node.op, syn::BinOp::Eq(_) | syn::BinOp::Ne(_))
+            && (self.reads_status(&node.left) || self.reads_status(&node.right))
+        {
+            self.record(node);
+        }
+        syn::visit::visit_expr_binary(self, node);
+    }
+
+    fn visit_expr_match(&mut self, node: &'ast syn::ExprMatch) {
+        if self.reads_status(&node.expr) {
+            for arm in &node.arms {
+                self.record(&arm.pat);
+            }
+        }
+        syn::visit::visit_expr_match(self, node);
+    }
+
+    fn visit_expr_let(&mut self, node: &'ast syn::ExprLet) {
+        if self.reads_status(&node.expr) {
+            self.record(&node.pat);
+        }
+        syn::visit::visit_expr_let(self, node);
+    }
+
+    /// `matches!(status, ..)` takes a pattern; `ensure!` and friends hide
+    /// their comparisons in token streams
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        if node.path.is_ident("matches") {
+            let parsed = node.parse_body_with(|input: syn::parse::ParseStream| {
+                let scrutinee: syn::Expr = input.parse()?;
+                input.parse::<syn::Token![,]>()?;
+                let pattern: proc_macro2::TokenStream = input.parse()?;
+                Ok((scrutinee, pattern))
+            });
+            if let Ok((scrutinee, pattern)) = parsed {
+                if self.reads_status(&scrutinee) {
+                    self.record(&pattern);
+                }
+                self.visit_expr(&scrutinee);
+            }
+            return;
+        }
+        if let Ok(args) = node.parse_body_with(
+            syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated,
+        ) {
+            let name = node.path.segments.last().map(|s| s.ident.to_string());
+            let compares = matches!(
+                name.as_deref(),
+                Some("ensure_eq" | "ensure_ne" | "assert_eq" | "assert_ne")
+            );
+            if compares && args.iter().take(2).any(|a| self.reads_status(a)) {
+                for arg in args.iter().take(2) {
+                    self.record(arg);
+                }
+            }
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+}
+
+impl MissingStatusCheck {
+    /// States compared against in function `name`, if it aborts on a check
+    fn function_checks(
+        &self,
+        ctx: &AnalysisContext,
+        machine: &StateMachine,
+        states: &HashSet<&str>,
+        name: &str,
+    ) -> BTreeSet<String> {
+        if !has_condition_guard(ctx, name) {
+            return BTreeSet::new();
+        }
+        let Some(body) = ctx
+            .contract
+            .functions
+            .iter()
+            .find(|f| f.name == name)
+            .and_then(|f| f.body.as_ref())
+        else {
+            return BTreeSet::new();
+        };
+        let mut checks = StatusChecks::new(machine, states);
+        checks.visit_block(body);
+        checks.checked
+    }
+
+    fn finding(
+        &self,
+        title: String,
+        description: String,
+        span: &SourceSpan,
+        rec: String,
+    ) -> Finding {
+        Finding {
+            detector_name: self.name().to_string(),
+            title,
+            description,
+            severity: Severity::Medium,
+            confidence: Confidence::Medium,
+            locations: vec![SourceLocation {
+                file: span.file.clone(),
+                start_line: span.start_line,
+                end_line: span.end_line,
+                start_col: span.start_col,
+                end_col: span.end_col,
+                snippet: None,
+                byte_range: None,
+            }],
+            recommendation: Some(rec),
+            fix: None,
+            ..Default::default()
+        }
+    }
+}
+
+fn has_condition_guard(ctx: &AnalysisContext, function: &str) -> bool {
+    ctx.guards(function)
+        .iter()
+        .any(|g| g.kind == GuardKind::Condition)
+}
+
+/// `POOL.status`, or `POOL` when the item is the status
+fn status_display(machine: &StateMachine) -> String {
+    match &machine.field {
+        Some(field) => format!("`{}.{}`", machine.item, field),
+        None => format!("`{}`", machine.item),
+    }
+}
+
+fn state_list(states: &[&str]) -> String {
+    states
+        .iter()
+        .map(|s| format!("`{s}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Detector for MissingStatusCheck {
+    fn name(&self) -> &str {
+        "missing-status-check"
+    }
+
+    fn description(&self) -> &str {
+        "Detects handlers that skip the status checks of a state machine declared in config"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let machines = &ctx.config().state_machines;
+        if machines.is_empty() {
+            return Vec::new();
+        }
+        let execute_enums: Vec<&str> = ctx
+            .contract
+            .message_enums
+            .iter()
+            .filter(|e| e.kind == MessageKind::Execute)
+            .map(|e| e.name.as_str())
+            .collect();
+
+        let mut findings = Vec::new();
+        for machine in machines {
+            // Declaration order, then states only named under `requires`
+            let mut known: Vec<&str> = machine.states.iter().map(String::as_str).collect();
+            for state in machine.requires.values().flatten() {
+                if !known.contains(&state.as_str()) {
+                    known.push(state);
+                }
+            }
+            let states: HashSet<&str> = known.iter().copied().collect();
+            let mut cache: HashMap<String, BTreeSet<String>> = HashMap::new();
+
+            for ep in ctx
+                .contract
+                .entry_points
+                .iter()
+                .filter(|ep| ep.kind == EntryPointKind::Execute)
+            {
+                let Some((file, body)) = ctx
+                    .contract
+                    .functions
+                    .iter()
+                    .find(|f| f.name == ep.name)
+                    .and_then(|f| Some((&f.span.file, f.body.as_ref()?)))
+                else {
+                    continue;
+                };
+                let mut collector = ExecuteArmCollector {
+                    execute_enums: &execute_enums,
+                    arms: Vec::new(),
+                };
+                collector.visit_block(body);
+
+                // Checks in `execute` itself abort only if `execute` has a guard;
+                // the ones ahead of the match cover every arm
+                let guarded = has_condition_guard(ctx, &ep.name);
+                let mut dispatcher = StatusChecks::new(machine, &states);
+                for stmt in &body.stmts {
+                    if !matches!(stmt, syn::Stmt::Expr(syn::Expr::Match(_), _)) {
+                        dispatcher.visit_stmt(stmt);
+                    }
+                }
+
+                for (enum_name, variant, arm) in collector.arms {
+                    let Some(allowed) = machine.requires.get(&variant) else {
+                        continue;
+                    };
+                    let mut checked = BTreeSet::new();
+                    if guarded {
+                        let mut arm_checks = StatusChecks::new(machine, &states);
+                        arm_checks.locals = dispatcher.locals.clone();
+                        arm_checks.visit_arm(arm);
+                        checked.extend(arm_checks.checked);
+                        checked.extend(dispatcher.checked.iter().cloned());
+                    }
+                    let mut calls = CallCollector::default();
+                    calls.visit_expr(&arm.body);
+                    let roots: Vec<&str> = calls.calls.iter().map(String::as_str).collect();
+                    let mut reachable: Vec<String> = ctx
+                        .call_graph()
+                        .reachable_from(&roots)
+                        .into_iter()
+                        .collect();
+                    reachable.sort();
+                    for name in reachable {
+                        let checks = cache.entry(name).or_insert_with_key(|name| {
+                            self.function_checks(ctx, machine, &states, name)
+                        });
+                        checked.extend(checks.iter().cloned());
+                    }
+
+                    let disallowed: Vec<&str> = known
+                        .iter()
+                        .copied()
+                        .filter(|s| !allowed.iter().any(|a| a == s))
+                        .collect();
+                    let requires_allowed = allowed.iter().any(|s| checked.contains(s));
+                    let rejects_rest =
+                        !disallowed.is_empty() && disallowed.iter().all(|s| checked.contains(*s));
+                    if requires_allowed || rejects_rest {
+                        continue;
+                    }
+
+                    let Some(start) = ctx.span_of(&arm.pat) else {
+                        continue;
+                    };
+                    let span = SourceSpan {
+                        file: file.clone(),
+                        start_line: start.start_line,
+                        end_line: start.start_line,
+                        start_col: start.start_col,
+                        end_col: start.start_col,
+                        start_byte: start.start_byte,
+                        end_byte: start.start_byte,
+                    };
+                    let status = status_display(machine);
+                    let allowed: Vec<&str> = allowed.iter().map(String::as_str).collect();
+                    let title = if checked.is_empty() {
+                        format!("`{enum_name}::{variant}` runs without checking {status}")
+                    } else {
+                        let unchecked: Vec<&str> = disallowed
+                            .iter()
+                            .copied()
+                            .filter(|s| !checked.contains(*s))
+                            .collect();
+                        format!(
+                            "`{enum_name}::{variant}` does not reject {status} {}",
+                            state_list(&unchecked)
+                        )
+                    };
+                    findings.push(self.finding(
+                        title,
+                        format!(
+                            "The configured state machine allows `{variant}` only while {status} \
+                             is {}, but no aborting check on its handler path compares the status \
+                             against those states. Calling it in any other state skips a \
+                             transition the contract relies on.",
+                            state_list(&allowed)
+                        ),
+                        &span,
+                        format!(
+                            "Check the status before acting, e.g. \
+                             `ensure!({} == Status::{}, ContractError::InvalidStatus {{}})`.",
+                            match &machine.field {
+                                Some(field) => format!("pool.{field}"),
+                                None => "status".to_string(),
+                            },
+                            allowed[0]
+                        ),
+                    ));
+                }
+            }
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::config::Config;
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::path::PathBuf;
+
+    fn analyze_with(source: &str, config: &Config) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources).with_config(config);
+        MissingStatusCheck.detect(&ctx)
+    }
+
+    const POOL_MACHINE: &str = r#"
+[[state_machines]]
+item = "POOL"
+field = "status"
+states = ["Initialized", "Open", "Closed"]
+requires = { Deposit = ["Open"], Withdraw = ["Open", "Closed"], Start = ["Initialized"] }
+"#;
+
+    const POOL_CONTRACT: &str = r#"
+        pub const POOL: Item<Pool> = Item::new("pool");
+
+        #[entry_point]
+        pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+            -> Result<Response, ContractError> {
+            match msg {
+                ExecuteMsg::Deposit {} => execute_deposit(deps, info),
+                ExecuteMsg::Withdraw {} => execute_withdraw(deps, info),
+                ExecuteMsg::Start {} => execute_start(deps),
+            }
+        }
+
+        fn execute_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+            let mut pool = POOL.load(deps.storage)?;
+            pool.total += info.funds[0].amount;
+            POOL.save(deps.storage, &pool)?;
+            Ok(Response::new())
+        }
+
+        fn execute_withdraw(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+            let pool = POOL.load(deps.storage)?;
+            if pool.status == PoolStatus::Initialized {
+                return Err(ContractError::NotStarted {});
+            }
+            Ok(Response::new())
+        }
+
+        fn execute_start(deps: DepsMut) -> Result<Response, ContractError> {
+            let mut pool = POOL.load(deps.storage)?;
+            ensure!(matches!(pool.status, PoolStatus::Initialized), ContractError::Started {});
+            pool.status = PoolStatus::Open;
+            POOL.save(deps.storage, &pool)?;
+            Ok(Response::new())
+        }
+    "#;
+
+    #[test]
+    fn test_detects_handler_without_status_check() {
+        let config: Config = toml::from_str(POOL_MACHINE).unwrap();
+        let findings = analyze_with(POOL_CONTRACT, &config);
+        let titles: Vec<&str> = findings.iter().map(|f| f.title.as_str()).collect();
+        // Withdraw rejects the only disallowed state; Start requires its state
+        assert_eq!(
+            titles,
+            vec!["`ExecuteMsg::Deposit` runs without checking `POOL.status`"]
+        );
+        assert!(analyze_with(POOL_CONTRACT, &Config::default()).is_empty());
+    }
+
+    #[test]
+    fn test_partial_rejection_is_reported() {
+        let config: Config = toml::from_str(POOL_MACHINE).unwrap();
+        let source = POOL_CONTRACT.replace(
+            "            pool.total += info.funds[0].amount;",
+            "            if pool.status == PoolStatus::Closed {
+                return Err(ContractError::Closed {});
+            }
+            pool.total += info.funds[0].amount;",
+        );
+        let findings = analyze_with(&source, &config);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].title,
+            "`ExecuteMsg::Deposit` does not reject `POOL.status` `Initialized`"
+        );
+    }
+
+    #[test]
+    fn test_item_status_checked_in_dispatcher() {
+        let config: Config = toml::from_str(
+            "[[state_machines]]\nitem = \"PHASE\"\nrequires = { Claim = [\"Finished\"] }",
+        )
+        .unwrap();
+        let source = r#"
+            pub const PHASE: Item<Phase> = Item::new("phase");
+
+            #[entry_point]
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> Result<Response, ContractError> {
+                let phase = PHASE.load(deps.storage)?;
+                if phase != Phase::Finished {
+                    return Err(ContractError::NotFinished {});
+                }
+                match msg {
+                    ExecuteMsg::Claim {} => execute_claim(deps, info),
+                }
+            }
+
+            fn execute_claim(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze_with(source, &config).is_empty());
+
+        let unchecked = source.replace("phase != Phase::Finished", "info.funds.is_empty()");
+        let findings = analyze_with(&unchecked, &config);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("without checking `PHASE`"));
+    }
+}
@@ -8,13 +8,14 @@ pub struct NondeterministicIteration;
 
 const ITER_METHODS: &[&str] = &["iter", "keys", "values", "into_iter", "drain"];
 
-struct HashMapIterSearcher {
+struct HashMapIterSearcher<'c> {
+    ctx: &'c AnalysisContext<'c>,
     findings: Vec<(usize, usize)>,
     /// Variable names known to be HashMap from let bindings with type annotations
     hashmap_vars: std::collections::HashSet<String>,
 }
 
-impl<'ast> Visit<'ast> for HashMapIterSearcher {
+impl<'ast> Visit<'ast> for HashMapIterSearcher<'_> {
     // Collect variables declared with HashMap type annotations
     fn visit_local(&mut self, node: &'ast syn::Local) {
         if let syn::Pat::Ident(ident) = &node.pat {
@@ -43,14 +44,15 @@ impl<'ast> Visit<'ast> for HashMapIterSearcher {
     fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
         let method = node.method.to_string();
         if ITER_METHODS.contains(&method.as_str()) && self.receiver_is_hashmap(&node.receiver) {
-            let span = node.method.span();
-            self.findings.push((span.start().line, span.start().column));
+            if let Some(span) = self.ctx.span_of(&node.method) {
+                self.findings.push((span.start_line, span.start_col));
+            }
         }
         syn::visit::visit_expr_method_call(self, node);
     }
 }
 
-impl HashMapIterSearcher {
+impl HashMapIterSearcher<'_> {
     /// Check if receiver is a known HashMap variable or contains HashMap in path
     fn receiver_is_hashmap(&self, expr: &syn::Expr) -> bool {
         match expr {
@@ -111,6 +113,7 @@ impl Detector for NondeterministicIteration {
 
         for (path, ast) in ctx.raw_asts() {
             let mut searcher = HashMapIterSearcher {
+                ctx,
                 findings: Vec::new(),
                 hashmap_vars: std::collections::HashSet::new(),
             };
@@ -133,11 +136,13 @@ impl Detector for NondeterministicIteration {
                         start_col: *col,
                         end_col: *col,
                         snippet: None,
+                        byte_range: None,
                     }],
                     recommendation: Some(
                         "Use `BTreeMap` instead, or collect into a Vec and sort.".to_string(),
                     ),
                     fix: None,
+                    ..Default::default()
                 });
             }
         }
@@ -0,0 +1,500 @@
+use std::collections::HashSet;
+
+use cosmwasm_guard::ast::SourceSpan;
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use quote::ToTokens;
+use syn::visit::Visit;
+
+/// Detects prices queried from an oracle contract and used in arithmetic
+/// without comparing the response's update time against `env.block.time`.
+/// An oracle that stops updating keeps serving its last price, so
+/// liquidations, swaps and collateral checks run against a stale value that
+/// can be far from the market.
+pub struct OracleStaleness;
+
+/// Fragments of oracle query variant names, e.g. `OracleQueryMsg::Price`
+const ORACLE_QUERIES: &[&str] = &["Price", "ExchangeRate", "Twap"];
+
+/// Querier methods sending a query to another contract
+const QUERY_METHODS: &[&str] = &["query_wasm_smart", "query"];
+
+/// Methods doing arithmetic on their receiver and arguments
+const MATH_METHODS: &[&str] = &[
+    "checked_mul",
+    "checked_div",
+    "checked_add",
+    "checked_sub",
+    "saturating_mul",
+    "saturating_sub",
+    "multiply_ratio",
+    "checked_multiply_ratio",
+    "mul_floor",
+    "mul_ceil",
+    "checked_mul_floor",
+    "checked_mul_ceil",
+    "div_floor",
+    "div_ceil",
+    "checked_div_floor",
+    "checked_div_ceil",
+    "from_ratio",
+];
+
+/// Response types with no update time to check
+const BARE_TYPES: &[&str] = &[
+    "Decimal",
+    "Decimal256",
+    "Uint128",
+    "Uint256",
+    "Uint64",
+    "u128",
+    "u64",
+];
+
+/// Whether a response field holds the time the price was last updated
+fn is_time_field(name: &str) -> bool {
+    let name = name.to_lowercase();
+    ["updated", "timestamp", "publish_time", "last_update"]
+        .iter()
+        .any(|f| name.contains(f))
+        || name == "time"
+        || name.ends_with("_time")
+        || name.ends_with("_at")
+}
+
+/// Idents and punctuation of `node`, with groups flattened
+fn tokens(node: &impl ToTokens) -> Vec<String> {
+    fn flatten(stream: proc_macro2::TokenStream, out: &mut Vec<String>) {
+        for tree in stream {
+            match tree {
+                proc_macro2::TokenTree::Group(group) => flatten(group.stream(), out),
+                proc_macro2::TokenTree::Ident(ident) => out.push(ident.to_string()),
+                proc_macro2::TokenTree::Punct(punct) => out.push(punct.as_char().to_string()),
+                proc_macro2::TokenTree::Literal(_) => {}
+            }
+        }
+    }
+    let mut out = Vec::new();
+    flatten(node.to_token_stream(), &mut out);
+    out
+}
+
+/// Oracle query variant named in `tokens`, as `Enum::Variant`
+fn oracle_variant(tokens: &[String]) -> Option<String> {
+    tokens.windows(4).find_map(|w| {
+        let is_variant = w[1] == ":"
+            && w[2] == ":"
+            && w[0].starts_with(char::is_uppercase)
+            && ORACLE_QUERIES.iter().any(|q| w[3].contains(q));
+        is_variant.then(|| format!("{}::{}", w[0], w[3]))
+    })
+}
+
+/// Last path segment of a type or pattern path
+fn last_segment(path: &syn::Path) -> Option<String> {
+    path.segments.last().map(|s| s.ident.to_string())
+}
+
+/// An oracle response bound by a `let`
+struct OracleResponse {
+    /// `price` in `let price: PriceResponse = ..`
+    name: Option<String>,
+    /// Field bindings of `let PriceResponse { price, last_updated } = ..`
+    fields: Vec<(String, String)>,
+    /// Annotated or destructured type
+    ty: Option<String>,
+    variant: String,
+    span: Option<SourceSpan>,
+}
+
+impl OracleResponse {
+    /// Whether `tokens` read the update time of this response
+    fn reads_time(&self, tokens: &[String]) -> bool {
+        let time_bindings: Vec<&str> = self
+            .fields
+            .iter()
+            .filter(|(field, _)| is_time_field(field))
+            .map(|(_, binding)| binding.as_str())
+            .collect();
+        tokens.iter().enumerate().any(|(i, t)| {
+            time_bindings.contains(&t.as_str())
+                || (self.name.as_ref() == Some(t)
+                    && tokens.get(i + 1).is_some_and(|p| p == ".")
+                    && tokens.get(i + 2).is_some_and(|f| is_time_field(f)))
+        })
+    }
+
+    /// Whether `tokens` name the whole response, as in `check(&price, &env)`
+    fn passed_whole(&self, tokens: &[String]) -> bool {
+        tokens.iter().enumerate().any(|(i, t)| {
+            self.name.as_ref() == Some(t) && tokens.get(i + 1).is_none_or(|p| p != ".")
+        })
+    }
+
+    /// Whether `tokens` read the price itself rather than the update time
+    fn reads_value(&self, tokens: &[String]) -> bool {
+        let value_bindings: Vec<&str> = self
+            .fields
+            .iter()
+            .filter(|(field, _)| !is_time_field(field))
+            .map(|(_, binding)| binding.as_str())
+            .collect();
+        tokens.iter().enumerate().any(|(i, t)| {
+            value_bindings.contains(&t.as_str())
+                || (self.name.as_ref() == Some(t)
+                    && !(tokens.get(i + 1).is_some_and(|p| p == ".")
+                        && tokens.get(i + 2).is_some_and(|f| is_time_field(f))))
+        })
+    }
+}
+
+/// Oracle responses of a function body and how they are used
+struct OracleSearcher<'c> {
+    ctx: &'c AnalysisContext<'c>,
+    /// Locals holding an oracle query message
+    messages: HashSet<String>,
+    /// Locals holding the block time or height
+    block_times: HashSet<String>,
+    responses: Vec<OracleResponse>,
+    /// Comparisons and checking macros, as tokens
+    checks: Vec<Vec<String>>,
+    /// Arguments of calls, such as `check_staleness(&price, &env)`, as tokens
+    helper_args: Vec<Vec<String>>,
+    /// Arithmetic expressions, as tokens
+    math: Vec<Vec<String>>,
+}
+
+impl OracleSearcher<'_> {
+    fn mentions_block(&self, tokens: &[String]) -> bool {
+        tokens
+            .iter()
+            .any(|t| t == "block" || self.block_times.contains(t))
+    }
+
+    /// Oracle query variant sent by `init`, directly or through a local
+    fn oracle_query(&self, init: &syn::Expr) -> Option<String> {
+        let mut finder = QueryFinder { found: false };
+        finder.visit_expr(init);
+        if !finder.found {
+            return None;
+        }
+        let tokens = tokens(init);
+        oracle_variant(&tokens).or_else(|| {
+            tokens
+                .iter()
+                .find(|t| self.messages.contains(*t))
+                .map(|t| format!("`{t}`"))
+        })
+    }
+}
+
+/// Finds a querier call in an expression
+struct QueryFinder {
+    found: bool,
+}
+
+impl<'ast> Visit<'ast> for QueryFinder {
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if QUERY_METHODS.contains(&node.method.to_string().as_str()) {
+            self.found = true;
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+}
+
+impl<'ast> Visit<'ast> for OracleSearcher<'_> {
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        if let Some(init) = &node.init {
+            let init_tokens = tokens(&init.expr);
+            let (pat, ty) = match &node.pat {
+                syn::Pat::Type(typed) => (typed.pat.as_ref(), Some(typed.ty.as_ref())),
+                pat => (pat, None),
+            };
+            let name = match pat {
+                syn::Pat::Ident(ident) => Some(ident.ident.to_string()),
+                _ => None,
+            };
+            if let Some(variant) = self.oracle_query(&init.expr) {
+                let mut response = OracleResponse {
+                    name,
+                    fields: Vec::new(),
+                    ty: ty.and_then(|ty| match ty {
+                        syn::Type::Path(path) => last_segment(&path.path),
+                        _ => None,
+                    }),
+                    variant,
+                    span: self.ctx.span_of(node),
+                };
+                if let syn::Pat::Struct(pat) = pat {
+                    response.ty = last_segment(&pat.path);
+                    for field in &pat.fields {
+                        let syn::Member::Named(member) = &field.member else {
+                            continue;
+                        };
+                        if let syn::Pat::Ident(binding) = field.pat.as_ref() {
+                            response
+                                .fields
+                                .push((member.to_string(), binding.ident.to_string()));
+                        }
+                    }
+                }
+                self.responses.push(response);
+            } else if let Some(name) = name {
+                if oracle_variant(&init_tokens).is_some() {
+                    self.messages.insert(name);
+                } else if self.mentions_block(&init_tokens) {
+                    self.block_times.insert(name);
+                }
+            }
+        }
+        syn::visit::visit_local(self, node);
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        use syn::BinOp::*;
+        match node.op {
+            Lt(_) | Le(_) | Gt(_) | Ge(_) | Eq(_) | Ne(_) => self.checks.push(tokens(node)),
+            Add(_) | Sub(_) | Mul(_) | Div(_) | AddAssign(_) | SubAssign(_) | MulAssign(_)
+            | DivAssign(_) => self.math.push(tokens(node)),
+            _ => {}
+        }
+        syn::visit::visit_expr_binary(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if MATH_METHODS.contains(&node.method.to_string().as_str()) {
+            self.math.push(tokens(node));
+        }
+        self.helper_args.push(tokens(&node.args));
+        syn::visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(path) = node.func.as_ref() {
+            if last_segment(&path.path).is_some_and(|f| MATH_METHODS.contains(&f.as_str())) {
+                self.math.push(tokens(&node.args));
+            }
+        }
+        self.helper_args.push(tokens(&node.args));
+        syn::visit::visit_expr_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        // `ensure!`, `assert!` and friends
+        self.checks.push(tokens(&node.tokens));
+        syn::visit::visit_macro(self, node);
+    }
+}
+
+impl Detector for OracleStaleness {
+    fn name(&self) -> &str {
+        "oracle-staleness"
+    }
+
+    fn description(&self) -> &str {
+        "Detects oracle prices used in arithmetic without checking when they were last updated"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for func in &ctx.contract.functions {
+            let Some(body) = &func.body else { continue };
+            let mut searcher = OracleSearcher {
+                ctx,
+                messages: HashSet::new(),
+                block_times: HashSet::new(),
+                responses: Vec::new(),
+                checks: Vec::new(),
+                helper_args: Vec::new(),
+                math: Vec::new(),
+            };
+            searcher.visit_block(body);
+
+            for response in &searcher.responses {
+                // A type we can see that carries no update time has nothing to check
+                if let Some(ty) = &response.ty {
+                    let untimed = BARE_TYPES.contains(&ty.as_str())
+                        || ctx
+                            .contract
+                            .find_struct(ty)
+                            .is_some_and(|s| !s.fields.iter().any(|f| is_time_field(&f.name)));
+                    if untimed {
+                        continue;
+                    }
+                }
+                // Compared here, or handed to a helper along with the block
+                let checked =
+                    searcher.checks.iter().any(|tokens| {
+                        response.reads_time(tokens) && searcher.mentions_block(tokens)
+                    }) || searcher.helper_args.iter().any(|tokens| {
+                        (response.reads_time(tokens) || response.passed_whole(tokens))
+                            && (searcher.mentions_block(tokens)
+                                || tokens.iter().any(|t| t == "env"))
+                    });
+                if checked || !searcher.math.iter().any(|t| response.reads_value(t)) {
+                    continue;
+                }
+
+                let binding = response
+                    .name
+                    .as_deref()
+                    .or(response.ty.as_deref())
+                    .unwrap_or("the response");
+                let span = response.span.as_ref().unwrap_or(&func.span);
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title: format!(
+                        "Oracle price `{binding}` used without a staleness check in `{}`",
+                        func.name
+                    ),
+                    description: format!(
+                        "`{}` queries {} from an oracle and uses `{binding}` in arithmetic, \
+                         but never compares its update time (e.g. `last_updated`) with \
+                         `env.block.time`. If the oracle stops updating, the contract keeps \
+                         pricing liquidations, swaps or collateral at a stale value.",
+                        func.name,
+                        match response.variant.starts_with('`') {
+                            true => format!("the message in {}", response.variant),
+                            false => format!("`{}`", response.variant),
+                        },
+                    ),
+                    severity: Severity::Medium,
+                    confidence: Confidence::Medium,
+                    locations: vec![SourceLocation {
+                        file: span.file.clone(),
+                        start_line: span.start_line,
+                        end_line: span.end_line,
+                        start_col: span.start_col,
+                        end_col: span.end_col,
+                        snippet: None,
+                        byte_range: None,
+                    }],
+                    recommendation: Some(
+                        "Reject the price when `env.block.time.seconds() - \
+                         price.last_updated` exceeds a configured maximum age, before using \
+                         it."
+                        .to_string(),
+                    ),
+                    fix: None,
+                    ..Default::default()
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        OracleStaleness.detect(&ctx)
+    }
+
+    #[test]
+    fn test_detects_unchecked_price() {
+        let source = r#"
+            fn execute_liquidate(deps: DepsMut, env: Env, collateral: Uint128) -> StdResult<Response> {
+                let config = CONFIG.load(deps.storage)?;
+                let price: PriceResponse = deps.querier.query_wasm_smart(
+                    config.oracle,
+                    &OracleQueryMsg::Price { denom: "uatom".to_string() },
+                )?;
+                let value = collateral * price.price;
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("`price`"));
+        assert!(findings[0].description.contains("`OracleQueryMsg::Price`"));
+        assert_eq!(findings[0].locations[0].start_line, 4);
+    }
+
+    #[test]
+    fn test_checked_price_not_flagged() {
+        let source = r#"
+            fn execute_liquidate(deps: DepsMut, env: Env, collateral: Uint128) -> StdResult<Response> {
+                let msg = OracleQueryMsg::ExchangeRate { base: "uatom".to_string() };
+                let rate: RateResponse = deps.querier.query_wasm_smart(ORACLE, &msg)?;
+                let now = env.block.time.seconds();
+                if now - rate.last_updated > MAX_PRICE_AGE {
+                    return Err(StdError::generic_err("stale price"));
+                }
+                let value = collateral.checked_mul(rate.rate)?;
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+
+    #[test]
+    fn test_detects_destructured_without_timestamp() {
+        let source = r#"
+            fn execute_swap(deps: DepsMut, env: Env, offer: Uint128) -> StdResult<Response> {
+                let msg = OracleQueryMsg::Price { denom: "uatom".to_string() };
+                let PriceResponse { price, .. } = deps.querier.query_wasm_smart(ORACLE, &msg)?;
+                let ask = offer.mul_floor(price);
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].description.contains("the message in `msg`"));
+    }
+
+    #[test]
+    fn test_helper_check_and_untimed_types_not_flagged() {
+        let source = r#"
+            pub struct SpotPrice {
+                pub price: Decimal,
+            }
+
+            fn execute_swap(deps: DepsMut, env: Env, offer: Uint128) -> StdResult<Response> {
+                let price: PriceResponse = deps.querier.query_wasm_smart(ORACLE, &OracleQueryMsg::Price {})?;
+                ensure_fresh(&price, &env.block)?;
+                let spot: SpotPrice = deps.querier.query_wasm_smart(ORACLE, &OracleQueryMsg::SpotPrice {})?;
+                let ask = offer * price.price * spot.price;
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+
+    #[test]
+    fn test_unrelated_query_not_flagged() {
+        let source = r#"
+            fn execute_claim(deps: DepsMut, amount: Uint128) -> StdResult<Response> {
+                let info: TokenInfoResponse = deps.querier.query_wasm_smart(TOKEN, &Cw20QueryMsg::TokenInfo {})?;
+                let share = amount * info.total_supply;
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashSet;
+
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::slice::Defs;
+use cosmwasm_guard::ir::{LiteralValue, Operand};
+
+/// Detects paginated `range` queries whose `start_after` bound is on the
+/// wrong side for the iteration order. Descending iteration starts from the
+/// top, so the cursor must be the `max` bound; passed as `min` the query
+/// returns everything after the cursor in reverse, repeating pages or never
+/// terminating for clients following it.
+pub struct PaginationBoundOrder;
+
+/// Name fragments of a pagination cursor
+const CURSOR_NAMES: &[&str] = &["start", "after", "cursor"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Order {
+    Ascending,
+    Descending,
+}
+
+/// Cursor name the bound is built from, e.g. `start_after`
+fn cursor_name<'a>(defs: &Defs<'a>, bound: &'a Operand) -> Option<String> {
+    defs.slice(bound).find_map(|op| {
+        let name = match op {
+            Operand::Var(var) => &var.name,
+            Operand::FieldAccess { field, .. } => field,
+            Operand::Literal(_) => return None,
+        };
+        let lower = name.to_lowercase();
+        CURSOR_NAMES
+            .iter()
+            .any(|c| lower.contains(c))
+            .then(|| name.clone())
+    })
+}
+
+/// Iteration order when it is a constant `Order::` variant
+fn constant_order<'a>(defs: &Defs<'a>, order: &'a Operand) -> Option<Order> {
+    defs.slice(order).find_map(|op| match op {
+        Operand::Literal(LiteralValue::String(path)) if path.ends_with("Descending") => {
+            Some(Order::Descending)
+        }
+        Operand::Literal(LiteralValue::String(path)) if path.ends_with("Ascending") => {
+            Some(Order::Ascending)
+        }
+        _ => None,
+    })
+}
+
+impl Detector for PaginationBoundOrder {
+    fn name(&self) -> &str {
+        "pagination-bound-order"
+    }
+
+    fn description(&self) -> &str {
+        "Detects start_after pagination bounds on the wrong side for the range order"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Low
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for func in &ctx.ir.functions {
+            let defs = Defs::new(func);
+            let mut reported = HashSet::new();
+
+            for inst in func.cfg.blocks.iter().flat_map(|b| &b.instructions) {
+                let Some(range) = inst.range_args() else {
+                    continue;
+                };
+                let Some(order) = constant_order(&defs, range.order) else {
+                    continue;
+                };
+                let (cursor, wrong, right) = match order {
+                    Order::Descending => (cursor_name(&defs, range.min), "min", "max"),
+                    Order::Ascending => (cursor_name(&defs, range.max), "max", "min"),
+                };
+                let Some(cursor) = cursor else {
+                    continue;
+                };
+                if (order == Order::Descending && cursor_name(&defs, range.max).is_some())
+                    || (order == Order::Ascending && cursor_name(&defs, range.min).is_some())
+                    || !reported.insert(cursor.clone())
+                {
+                    continue;
+                }
+
+                let span = &func.source_span;
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title: format!(
+                        "`{}` bound passed as `{}` to a {:?} range in `{}`",
+                        cursor, wrong, order, func.name
+                    ),
+                    description: format!(
+                        "`{}` iterates in {:?} order but passes the pagination cursor \
+                         `{}` as the `{}` bound. The page then continues from the wrong end \
+                         of the cursor: clients following `start_after` get repeated or \
+                         skipped entries, or never reach the last page.",
+                        func.name, order, cursor, wrong
+                    ),
+                    severity: Severity::Low,
+                    confidence: Confidence::Medium,
+                    locations: vec![SourceLocation {
+                        file: span.file.clone(),
+                        start_line: span.start_line,
+                        end_line: span.end_line,
+                        start_col: span.start_col,
+                        end_col: span.end_col,
+                        snippet: None,
+                        byte_range: None,
+                    }],
+                    recommendation: Some(format!(
+                        "Pass `{}.map(Bound::exclusive)` as the `{}` bound for {:?} \
+                         iteration.",
+                        cursor, right, order
+                    )),
+                    fix: None,
+                    ..Default::default()
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        PaginationBoundOrder.detect(&ctx)
+    }
+
+    #[test]
+    fn test_detects_descending_with_min_cursor() {
+        let source = r#"
+            fn query_proposals(deps: Deps, start_after: Option<u64>, limit: Option<u32>)
+                -> StdResult<Vec<Proposal>> {
+                let min = start_after.map(Bound::exclusive);
+                PROPOSALS
+                    .range(deps.storage, min, None, Order::Descending)
+                    .take(limit.unwrap_or(10) as usize)
+                    .collect()
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0]
+            .title
+            .contains("`start_after` bound passed as `min`"));
+        assert!(findings[0].title.contains("Descending"));
+    }
+
+    #[test]
+    fn test_no_finding_for_correct_sides() {
+        let source = r#"
+            fn query_latest(deps: Deps, start_after: Option<u64>) -> StdResult<Vec<Proposal>> {
+                let max = start_after.map(Bound::exclusive);
+                PROPOSALS
+                    .range(deps.storage, None, max, Order::Descending)
+                    .collect()
+            }
+
+            fn query_all(deps: Deps, start_after: Option<String>) -> StdResult<Vec<Addr>> {
+                let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+                MEMBERS
+                    .keys(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+                    .collect()
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+
+    #[test]
+    fn test_detects_ascending_with_max_cursor() {
+        let source = r#"
+            fn query_all(deps: Deps, msg: ListMsg) -> StdResult<Vec<Addr>> {
+                MEMBERS
+                    .keys(deps.storage, None, msg.start_after.map(Bound::exclusive), Order::Ascending)
+                    .collect()
+            }
+        "#;
+        assert_eq!(analyze(source).len(), 1);
+    }
+}
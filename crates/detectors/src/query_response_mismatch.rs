@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+
+use cosmwasm_guard::ast::{utils, ContractInfo, MessageKind};
+use cosmwasm_guard::detector::{AnalysisContext, Detector};
+use cosmwasm_guard::finding::*;
+use syn::visit::Visit;
+
+/// Cross-checks each `QueryMsg` variant's `#[returns(T)]` against the type the
+/// query handler actually serializes for it. Schemas, indexers and generated
+/// clients trust `#[returns]`, so drift breaks them without a compile error.
+pub struct QueryResponseMismatch;
+
+/// Functions that serialize a query response
+const SERIALIZE_FNS: &[&str] = &["to_json_binary", "to_binary"];
+
+/// Drop module qualifiers: `crate::msg::Foo<cw20::Bar>` -> `Foo<Bar>`
+fn unqualified(ty: &str) -> String {
+    let mut out = String::new();
+    for (i, part) in ty.split("::").enumerate() {
+        if i > 0 {
+            while out.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+                out.pop();
+            }
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+/// Ok type of a rendered `StdResult<T>` / `Result<T, E>`, or the type itself
+fn ok_type(ty: &str) -> String {
+    let Some((outer, rest)) = ty.split_once('<') else {
+        return ty.to_string();
+    };
+    if !unqualified(outer).ends_with("Result") {
+        return ty.to_string();
+    }
+    let inner = rest.strip_suffix('>').unwrap_or(rest);
+    // First top-level generic argument
+    let mut depth = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth -= 1,
+            ',' if depth == 0 => return inner[..i].to_string(),
+            _ => {}
+        }
+    }
+    inner.to_string()
+}
+
+/// Finds the value serialized by the first `to_json_binary(&..)` in an arm,
+/// resolving `let` bindings made earlier in the same arm
+#[derive(Default)]
+struct SerializedValue<'ast> {
+    locals: HashMap<String, &'ast syn::Local>,
+    value: Option<&'ast syn::Expr>,
+}
+
+impl<'ast> Visit<'ast> for SerializedValue<'ast> {
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        let pat = match &node.pat {
+            syn::Pat::Type(typed) => typed.pat.as_ref(),
+            pat => pat,
+        };
+        if let syn::Pat::Ident(ident) = pat {
+            self.locals.insert(ident.ident.to_string(), node);
+        }
+        syn::visit::visit_local(self, node);
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if self.value.is_none() {
+            if let syn::Expr::Path(path) = node.func.as_ref() {
+                let is_serialize = path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|s| SERIALIZE_FNS.contains(&s.ident.to_string().as_str()));
+                if is_serialize {
+                    self.value = node.args.first();
+                    return;
+                }
+            }
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+}
+
+impl SerializedValue<'_> {
+    /// Static type of `expr`, when it is a struct literal, a call to a crate
+    /// function with a known return type, or a local bound to one of those
+    fn type_of(&self, expr: &syn::Expr, contract: &ContractInfo, depth: usize) -> Option<String> {
+        if depth > 8 {
+            return None;
+        }
+        match expr {
+            syn::Expr::Reference(r) => self.type_of(&r.expr, contract, depth + 1),
+            syn::Expr::Paren(p) => self.type_of(&p.expr, contract, depth + 1),
+            syn::Expr::Try(t) => self
+                .type_of(&t.expr, contract, depth + 1)
+                .map(|ty| ok_type(&ty)),
+            syn::Expr::Struct(s) => {
+                let path = &s.path;
+                Some(utils::type_to_string(&syn::parse_quote!(#path)))
+            }
+            syn::Expr::Call(call) => {
+                let syn::Expr::Path(path) = call.func.as_ref() else {
+                    return None;
+                };
+                let name = path.path.segments.last()?.ident.to_string();
+                contract
+                    .functions
+                    .iter()
+                    .find(|f| f.name == name)?
+                    .return_type
+                    .clone()
+            }
+            syn::Expr::Path(path) => {
+                let name = path.path.get_ident()?.to_string();
+                let local = self.locals.get(&name)?;
+                if let syn::Pat::Type(typed) = &local.pat {
+                    return Some(utils::type_to_string(&typed.ty));
+                }
+                let init = &local.init.as_ref()?.expr;
+                self.type_of(init, contract, depth + 1)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// `QueryMsg::Variant { .. }` pattern -> (enum, variant)
+fn variant_of(pat: &syn::Pat) -> Option<(String, String)> {
+    let path = match pat {
+        syn::Pat::Struct(p) => &p.path,
+        syn::Pat::TupleStruct(p) => &p.path,
+        syn::Pat::Path(p) => &p.path,
+        _ => return None,
+    };
+    let n = path.segments.len();
+    if n < 2 {
+        return None;
+    }
+    Some((
+        path.segments[n - 2].ident.to_string(),
+        path.segments[n - 1].ident.to_string(),
+    ))
+}
+
+/// Match arms on query message enums, across all function bodies
+struct QueryArmCollector<'ast> {
+    query_enums: Vec<&'ast str>,
+    arms: Vec<(String, String, &'ast syn::Arm)>,
+}
+
+impl<'ast> Visit<'ast> for QueryArmCollector<'ast> {
+    fn visit_arm(&mut self, node: &'ast syn::Arm) {
+        if let Some((enum_name, variant)) = variant_of(&node.pat) {
+            if self.query_enums.contains(&enum_name.as_str()) {
+                self.arms.push((enum_name, variant, node));
+            }
+        }
+        syn::visit::visit_arm(self, node);
+    }
+}
+
+impl Detector for QueryResponseMismatch {
+    fn name(&self) -> &str {
+        "query-response-mismatch"
+    }
+
+    fn description(&self) -> &str {
+        "Detects query handlers whose serialized response type differs from #[returns]"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let query_enums: Vec<_> = ctx
+            .contract
+            .message_enums
+            .iter()
+            .filter(|e| e.kind == MessageKind::Query)
+            .collect();
+        if query_enums
+            .iter()
+            .all(|e| e.variants.iter().all(|v| v.returns.is_none()))
+        {
+            return Vec::new();
+        }
+
+        let mut findings = Vec::new();
+        for func in &ctx.contract.functions {
+            let Some(body) = &func.body else { continue };
+            let mut collector = QueryArmCollector {
+                query_enums: query_enums.iter().map(|e| e.name.as_str()).collect(),
+                arms: Vec::new(),
+            };
+            collector.visit_block(body);
+
+            for (enum_name, variant_name, arm) in collector.arms {
+                let declared = query_enums
+                    .iter()
+                    .filter(|e| e.name == enum_name)
+                    .flat_map(|e| &e.variants)
+                    .find(|v| v.name == variant_name)
+                    .and_then(|v| v.returns.as_deref());
+                let Some(declared) = declared else { continue };
+
+                let mut serialized = SerializedValue::default();
+                serialized.visit_arm(arm);
+                let Some(actual) = serialized
+                    .value
+                    .and_then(|v| serialized.type_of(v, ctx.contract, 0))
+                else {
+                    continue;
+                };
+                if unqualified(&actual) == unqualified(declared) || actual == "Binary" {
+                    continue;
+                }
+
+                let Some(start) = ctx.span_of(&arm.pat) else {
+                    continue;
+                };
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title: format!(
+                        "`{enum_name}::{variant_name}` returns `{actual}`, declared `{declared}`"
+                    ),
+                    description: format!(
+                        "`{enum_name}::{variant_name}` is declared `#[returns({declared})]` but \
+                         `{}` serializes a `{actual}`. Clients and schemas generated from the \
+                         declaration will fail to decode the response.",
+                        func.name
+                    ),
+                    severity: Severity::Medium,
+                    confidence: Confidence::Medium,
+                    locations: vec![SourceLocation {
+                        file: func.span.file.clone(),
+                        start_line: start.start_line,
+                        end_line: start.start_line,
+                        start_col: start.start_col,
+                        end_col: start.start_col,
+                        snippet: None,
+                        byte_range: None,
+                    }],
+                    recommendation: Some(format!(
+                        "Return a `{}` from the handler, or update `#[returns(..)]` and regenerate the schema.",
+                        unqualified(declared)
+                    )),
+                    fix: None,
+                    ..Default::default()
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        QueryResponseMismatch.detect(&ctx)
+    }
+
+    const QUERY_MSG: &str = r#"
+        #[derive(QueryResponses)]
+        pub enum QueryMsg {
+            #[returns(BalanceResponse)]
+            Balance { address: String },
+            #[returns(crate::msg::ConfigResponse)]
+            Config {},
+            #[returns(Vec<Addr>)]
+            Admins {},
+        }
+    "#;
+
+    #[test]
+    fn test_detects_mismatched_response() {
+        let source = format!(
+            "{QUERY_MSG}{}",
+            r#"
+            #[entry_point]
+            pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+                match msg {
+                    QueryMsg::Balance { address } => to_json_binary(&query_balance(deps, address)?),
+                    QueryMsg::Config {} => {
+                        let config = CONFIG.load(deps.storage)?;
+                        let res = StateResponse { config };
+                        to_json_binary(&res)
+                    }
+                    QueryMsg::Admins {} => to_json_binary(&query_admins(deps)?),
+                }
+            }
+            fn query_balance(deps: Deps, address: String) -> StdResult<Uint128> {
+                Ok(Uint128::zero())
+            }
+            fn query_admins(deps: Deps) -> StdResult<Vec<Addr>> {
+                Ok(vec![])
+            }
+            "#
+        );
+        let findings = analyze(&source);
+        let titles: Vec<&str> = findings.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(findings.len(), 2, "{titles:?}");
+        assert!(titles[0].contains("QueryMsg::Balance` returns `Uint128`"));
+        assert!(titles[1].contains("QueryMsg::Config` returns `StateResponse`"));
+    }
+
+    #[test]
+    fn test_matching_responses_are_fine() {
+        let source = format!(
+            "{QUERY_MSG}{}",
+            r#"
+            #[entry_point]
+            pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+                match msg {
+                    QueryMsg::Balance { address } => to_json_binary(&query_balance(deps, address)?),
+                    QueryMsg::Config {} => to_json_binary(&msg::ConfigResponse { owner }),
+                    QueryMsg::Admins {} => {
+                        let admins: Vec<Addr> = ADMINS.load(deps.storage)?;
+                        to_json_binary(&admins)
+                    }
+                }
+            }
+            fn query_balance(deps: Deps, address: String) -> StdResult<BalanceResponse> {
+                Ok(BalanceResponse { balance: Uint128::zero() })
+            }
+            "#
+        );
+        assert!(analyze(&source).is_empty());
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+
+use cosmwasm_guard::ast::EntryPointKind;
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::{operand_tainted, propagate_taint, FunctionIr, GuardKind, Instruction};
+
+/// Detects execute handlers that query another contract, abort unless the
+/// response satisfies a condition, and then emit messages that rely on it.
+/// The messages run after the handler returns, and messages ahead of them
+/// (or the queried contract's own handlers) can change what the query saw:
+/// a balance checked here may be gone by the time the transfer executes.
+/// Storage writes of the handler itself happen before any of that and are
+/// not reported.
+pub struct QueryToctou;
+
+/// `SubMsg` constructors that route the result to `reply`
+const REPLY_METHODS: &[&str] = &["reply_on_success", "reply_always", "reply_on_error"];
+
+/// Whether `func` sends a submessage whose result comes back to `reply`
+fn requests_reply(func: &FunctionIr) -> bool {
+    func.cfg
+        .blocks
+        .iter()
+        .flat_map(|b| &b.instructions)
+        .any(|inst| match inst {
+            Instruction::Call { func, .. } => {
+                REPLY_METHODS.contains(&func.rsplit("::").next().unwrap_or(func))
+            }
+            Instruction::MethodCall { method, .. } => REPLY_METHODS.contains(&method.as_str()),
+            _ => false,
+        })
+}
+
+impl Detector for QueryToctou {
+    fn name(&self) -> &str {
+        "query-toctou"
+    }
+
+    fn description(&self) -> &str {
+        "Detects messages emitted on the strength of a cross-contract query checked before they run"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Low
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Low
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Experimental
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let roots: Vec<&str> = ctx
+            .contract
+            .entry_points
+            .iter()
+            .filter(|ep| ep.kind == EntryPointKind::Execute)
+            .map(|ep| ep.name.as_str())
+            .collect();
+        let reachable = ctx.call_graph().reachable_from(&roots);
+        let has_reply = ctx
+            .contract
+            .entry_points
+            .iter()
+            .any(|ep| ep.kind == EntryPointKind::Reply);
+
+        let mut findings = Vec::new();
+        for func in &ctx.ir.functions {
+            if !reachable.contains(&func.name) {
+                continue;
+            }
+            let responses: HashSet<_> = func
+                .cfg
+                .blocks
+                .iter()
+                .flat_map(|b| &b.instructions)
+                .filter_map(|inst| match inst {
+                    Instruction::QueryExternal { dest, kind, .. } if kind.is_wasm() => {
+                        Some(dest.clone())
+                    }
+                    _ => None,
+                })
+                .collect();
+            if responses.is_empty() {
+                continue;
+            }
+            let messages = ctx.emitted_messages(&func.name);
+            if messages.is_empty() || (has_reply && requests_reply(func)) {
+                continue;
+            }
+            let tainted = propagate_taint(func, &responses);
+            let checks_response = ctx.guards(&func.name).iter().any(|g| {
+                g.kind == GuardKind::Condition
+                    && g.operands.iter().any(|op| operand_tainted(op, &tainted))
+            });
+            if !checks_response {
+                continue;
+            }
+
+            let mut msg_types: Vec<&str> = messages.iter().map(|m| m.msg_type.as_str()).collect();
+            msg_types.sort_unstable();
+            msg_types.dedup();
+            let span = &func.source_span;
+            findings.push(Finding {
+                detector_name: self.name().to_string(),
+                title: format!(
+                    "`{}` checks a query response, then relies on it in {}",
+                    func.name,
+                    msg_types
+                        .iter()
+                        .map(|t| format!("`{t}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                description: format!(
+                    "`{}` queries another contract and aborts unless the response passes a \
+                     check, then emits messages that assume the check still holds. Those \
+                     messages execute after the handler returns; by then earlier messages or \
+                     the queried contract itself may have changed the state the query read \
+                     (time-of-check/time-of-use).",
+                    func.name
+                ),
+                severity: Severity::Low,
+                confidence: Confidence::Low,
+                locations: vec![SourceLocation {
+                    file: span.file.clone(),
+                    start_line: span.start_line,
+                    end_line: span.end_line,
+                    start_col: span.start_col,
+                    end_col: span.end_col,
+                    snippet: None,
+                    byte_range: None,
+                }],
+                recommendation: Some(
+                    "Send the action as a `SubMsg::reply_on_success` and re-query and \
+                     re-validate the condition in `reply`, or have the target contract enforce \
+                     the condition itself."
+                        .to_string(),
+                ),
+                fix: None,
+                ..Default::default()
+            });
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        QueryToctou.detect(&ctx)
+    }
+
+    const REDEEM: &str = r#"
+        #[entry_point]
+        pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+            -> Result<Response, ContractError> {
+            match msg {
+                ExecuteMsg::Redeem { amount } => execute_redeem(deps, info, amount),
+            }
+        }
+
+        fn execute_redeem(deps: DepsMut, info: MessageInfo, amount: Uint128)
+            -> Result<Response, ContractError> {
+            let vault = VAULT.load(deps.storage)?;
+            let balance: BalanceResponse = deps.querier.query_wasm_smart(
+                &vault,
+                &VaultQueryMsg::Balance { address: info.sender.to_string() },
+            )?;
+            if balance.balance < amount {
+                return Err(ContractError::InsufficientBalance {});
+            }
+            let msg = WasmMsg::Execute {
+                contract_addr: vault.to_string(),
+                msg: to_json_binary(&VaultExecuteMsg::Withdraw { amount })?,
+                funds: vec![],
+            };
+            Ok(Response::new().add_message(msg))
+        }
+    "#;
+
+    #[test]
+    fn test_detects_checked_query_before_message() {
+        let findings = analyze(REDEEM);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].title,
+            "`execute_redeem` checks a query response, then relies on it in `WasmMsg::Execute`"
+        );
+    }
+
+    #[test]
+    fn test_unchecked_query_not_flagged() {
+        let source = REDEEM.replace("balance.balance < amount", "amount.is_zero()");
+        assert!(analyze(&source).is_empty());
+    }
+
+    #[test]
+    fn test_revalidated_in_reply_not_flagged() {
+        let source = REDEEM
+            .replace(
+                "Ok(Response::new().add_message(msg))",
+                "Ok(Response::new().add_submessage(SubMsg::reply_on_success(msg, REDEEM_ID)))",
+            )
+            .replace(
+                "#[entry_point]",
+                "#[entry_point]
+        pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+            Ok(Response::new())
+        }
+
+        #[entry_point]",
+            );
+        assert!(analyze(&source).is_empty());
+        // Without a reply entry point the submessage is not revalidated
+        let no_reply = REDEEM.replace(
+            "Ok(Response::new().add_message(msg))",
+            "Ok(Response::new().add_submessage(SubMsg::reply_on_success(msg, REDEEM_ID)))",
+        );
+        assert_eq!(analyze(&no_reply).len(), 1);
+    }
+}
@@ -0,0 +1,442 @@
+use std::collections::{HashSet, VecDeque};
+
+use cosmwasm_guard::ast::{EntryPointKind, SourceSpan};
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::{BlockId, FunctionIr, Instruction, Operand};
+use syn::visit::Visit;
+
+/// Detects execute handlers writing state after a message was added to the
+/// Response: a `save`, `update` or `remove`, or a call to a function making
+/// one, on a CFG path from an `add_message`/`add_submessage` call or a call to
+/// a function making one. The message was built from state as it stood
+/// before the write; when it runs, and when a reply comes back, the contract's
+/// state no longer matches what the message assumed.
+pub struct ReentrancyStateAfterMsg;
+
+/// Response methods queueing a `BankMsg`, `WasmMsg` or `SubMsg`
+const DISPATCH_METHODS: &[&str] = &[
+    "add_message",
+    "add_messages",
+    "add_submessage",
+    "add_submessages",
+];
+
+/// Store methods, as the IR builder recognizes them
+const STORE_METHODS: &[&str] = &["save", "update"];
+
+/// Methods removing stored values, which the IR keeps as plain method calls
+const REMOVE_METHODS: &[&str] = &["remove", "clear"];
+
+/// What a write after a dispatch writes: an item, or a function that does
+#[derive(Debug, Clone, PartialEq)]
+enum Write {
+    Item(String),
+    Helper(String),
+}
+
+impl Write {
+    fn name(&self) -> &str {
+        match self {
+            Write::Item(name) | Write::Helper(name) => name,
+        }
+    }
+}
+
+fn callee(func: &str) -> &str {
+    func.rsplit("::").next().unwrap_or(func)
+}
+
+/// Writes of state on some path after a message is queued, in block order
+fn writes_after_dispatch(
+    func: &FunctionIr,
+    is_item: &impl Fn(&str) -> bool,
+    dispatches: &impl Fn(&str) -> bool,
+    writes: &impl Fn(&str) -> bool,
+) -> Vec<Write> {
+    let blocks = &func.cfg.blocks;
+    let write_of = |inst: &Instruction| match inst {
+        Instruction::StorageStore { storage_item, .. } => Some(Write::Item(storage_item.clone())),
+        Instruction::MethodCall {
+            receiver: Operand::Var(receiver),
+            method,
+            ..
+        } if REMOVE_METHODS.contains(&method.as_str()) && is_item(&receiver.name) => {
+            Some(Write::Item(receiver.name.clone()))
+        }
+        Instruction::Call { func, .. } if writes(callee(func)) => {
+            Some(Write::Helper(callee(func).to_string()))
+        }
+        _ => None,
+    };
+    let is_dispatch = |inst: &Instruction| match inst {
+        Instruction::MethodCall { method, .. } => DISPATCH_METHODS.contains(&method.as_str()),
+        Instruction::Call { func, .. } => dispatches(callee(func)),
+        _ => false,
+    };
+
+    let mut found: Vec<Write> = Vec::new();
+    let mut push = |write: Write| {
+        if !found.contains(&write) {
+            found.push(write);
+        }
+    };
+    // Blocks reachable from the successors of a block dispatching
+    let mut after: HashSet<BlockId> = HashSet::new();
+    let mut queue: VecDeque<BlockId> = VecDeque::new();
+    for block in blocks {
+        let Some(first) = block.instructions.iter().position(is_dispatch) else {
+            continue;
+        };
+        for write in block.instructions[first + 1..].iter().filter_map(write_of) {
+            push(write);
+        }
+        queue.extend(&block.successors);
+    }
+    while let Some(id) = queue.pop_front() {
+        if after.insert(id) {
+            queue.extend(&blocks[id].successors);
+        }
+    }
+    let mut order: Vec<BlockId> = after.into_iter().collect();
+    order.sort_unstable();
+    for id in order {
+        for write in blocks[id].instructions.iter().filter_map(write_of) {
+            push(write);
+        }
+    }
+    found
+}
+
+/// Dispatches and writes in a function body, with their source position
+struct Events<'a> {
+    ctx: &'a AnalysisContext<'a>,
+    is_item: &'a dyn Fn(&str) -> bool,
+    dispatches: &'a dyn Fn(&str) -> bool,
+    writes: &'a dyn Fn(&str) -> bool,
+    dispatched: Vec<SourceSpan>,
+    written: Vec<(String, SourceSpan)>,
+}
+
+impl<'ast> Visit<'ast> for Events<'_> {
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        syn::visit::visit_expr_method_call(self, node);
+        let method = node.method.to_string();
+        if DISPATCH_METHODS.contains(&method.as_str()) {
+            if let Some(span) = self.ctx.span_of(&node.method) {
+                self.dispatched.push(span);
+            }
+        } else if STORE_METHODS.contains(&method.as_str())
+            || REMOVE_METHODS.contains(&method.as_str())
+        {
+            if let syn::Expr::Path(path) = node.receiver.as_ref() {
+                if let Some(last) = path.path.segments.last() {
+                    let item = last.ident.to_string();
+                    if (self.is_item)(&item) {
+                        if let Some(span) = self.ctx.span_of(node) {
+                            self.written.push((item, span));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        syn::visit::visit_expr_call(self, node);
+        let syn::Expr::Path(path) = node.func.as_ref() else {
+            return;
+        };
+        let Some(last) = path.path.segments.last() else {
+            return;
+        };
+        let name = last.ident.to_string();
+        let Some(span) = self.ctx.span_of(node) else {
+            return;
+        };
+        if (self.writes)(&name) {
+            self.written.push((name.clone(), span.clone()));
+        }
+        if (self.dispatches)(&name) {
+            self.dispatched.push(span);
+        }
+    }
+}
+
+fn location(span: SourceSpan) -> SourceLocation {
+    SourceLocation {
+        file: span.file,
+        start_line: span.start_line,
+        end_line: span.end_line,
+        start_col: span.start_col,
+        end_col: span.end_col,
+        snippet: None,
+        byte_range: None,
+    }
+}
+
+impl Detector for ReentrancyStateAfterMsg {
+    fn name(&self) -> &str {
+        "reentrancy-state-after-msg"
+    }
+
+    fn description(&self) -> &str {
+        "Detects execute handlers writing state after adding a message to the Response"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Low
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let roots: Vec<&str> = ctx
+            .contract
+            .entry_points
+            .iter()
+            .filter(|ep| ep.kind == EntryPointKind::Execute)
+            .map(|ep| ep.name.as_str())
+            .collect();
+        let reachable = ctx.call_graph().reachable_from(&roots);
+        let items: HashSet<&str> = ctx
+            .contract
+            .state_items
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        let is_item = |name: &str| {
+            items.contains(name)
+                || name
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        };
+        // Whether a callee queues a message, or writes state, directly or
+        // through its own callees
+        let queues = |f: &FunctionIr| {
+            f.cfg.blocks.iter().flat_map(|b| &b.instructions).any(|i| {
+                matches!(i, Instruction::MethodCall { method, .. }
+                    if DISPATCH_METHODS.contains(&method.as_str()))
+            })
+        };
+        let dispatches = |callee: &str| {
+            let reach = ctx.call_graph().reachable_from(&[callee]);
+            ctx.ir
+                .functions
+                .iter()
+                .any(|f| reach.contains(&f.name) && queues(f))
+        };
+        let writes = |callee: &str| {
+            ctx.call_graph()
+                .reachable_from(&[callee])
+                .iter()
+                .any(|f| !ctx.storage_writes(f).is_empty())
+        };
+
+        let mut findings = Vec::new();
+        for func in &ctx.ir.functions {
+            if !reachable.contains(&func.name) {
+                continue;
+            }
+            let late = writes_after_dispatch(func, &is_item, &dispatches, &writes);
+            if late.is_empty() {
+                continue;
+            }
+
+            let mut events = Events {
+                ctx,
+                is_item: &is_item,
+                dispatches: &dispatches,
+                writes: &writes,
+                dispatched: Vec::new(),
+                written: Vec::new(),
+            };
+            if let Some(body) = ctx
+                .contract
+                .functions
+                .iter()
+                .find(|f| f.name == func.name && f.span.file == func.source_span.file)
+                .and_then(|f| f.body.as_ref())
+            {
+                events.visit_block(body);
+            }
+            let first_dispatch = events
+                .dispatched
+                .iter()
+                .min_by_key(|s| s.start_byte)
+                .cloned();
+
+            for write in late {
+                // The first write after the first dispatch in source order,
+                // or the first at all for one coming around a loop
+                let mut spans: Vec<&SourceSpan> = events
+                    .written
+                    .iter()
+                    .filter(|(name, _)| name == write.name())
+                    .map(|(_, span)| span)
+                    .collect();
+                spans.sort_by_key(|s| s.start_byte);
+                let after = first_dispatch
+                    .as_ref()
+                    .and_then(|d| spans.iter().find(|s| s.start_byte > d.start_byte))
+                    .or(spans.first());
+                let mut locations = vec![location(
+                    after.map_or_else(|| func.source_span.clone(), |s| (*s).clone()),
+                )];
+                if let Some(dispatch) = &first_dispatch {
+                    locations.push(location(dispatch.clone()));
+                }
+
+                let (written, what) = match &write {
+                    Write::Item(item) => (format!("writes `{item}`"), format!("`{item}`")),
+                    Write::Helper(helper) => (
+                        format!("calls `{helper}`, which writes state,"),
+                        "the state it writes".to_string(),
+                    ),
+                };
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title: format!(
+                        "`{}` {written} after adding a message to the Response",
+                        func.name
+                    ),
+                    description: format!(
+                        "`{}` {written} on a path after a `BankMsg`, `WasmMsg` or `SubMsg` is \
+                         added to the Response. Messages run once the handler returns, but \
+                         they were built from {what} as it stood before the write: the funds \
+                         or amounts they carry can disagree with the state committed \
+                         alongside them, and a contract called by the message, or this \
+                         contract's `reply` handler, re-entering the contract sees the new \
+                         state while the message acts on the old. Checks-effects-interactions \
+                         ordering keeps the two consistent.",
+                        func.name
+                    ),
+                    severity: self.severity(),
+                    confidence: self.confidence(),
+                    locations,
+                    recommendation: Some(
+                        "Perform all checks, then save every state change, and only then \
+                         build the messages and add them to the Response."
+                            .to_string(),
+                    ),
+                    fix: None,
+                    ..Default::default()
+                });
+            }
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let contract =
+            ContractVisitor::extract(PathBuf::from("test.rs"), parse_source(source).unwrap());
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([(PathBuf::from("test.rs"), source.to_string())]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        ReentrancyStateAfterMsg.detect(&ctx)
+    }
+
+    #[test]
+    fn test_flags_state_written_after_message() {
+        let findings = analyze(
+            r#"
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Withdraw {} => execute_withdraw(deps, info),
+    }
+}
+
+fn execute_withdraw(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let balance = BALANCES.load(deps.storage, &info.sender)?;
+    let mut resp = Response::new();
+    resp = resp.add_message(BankMsg::Send {
+        to_address: info.sender.to_string(),
+        amount: coins(balance.u128(), "uatom"),
+    });
+    BALANCES.remove(deps.storage, &info.sender);
+    record_withdrawal(deps.storage)?;
+    Ok(resp)
+}
+
+fn record_withdrawal(storage: &mut dyn Storage) -> StdResult<()> {
+    TOTAL.update(storage, |t| -> StdResult<_> { Ok(t + 1) })?;
+    Ok(())
+}
+"#,
+        );
+        let titles: Vec<(&str, usize, usize)> = findings
+            .iter()
+            .map(|f| {
+                (
+                    f.title.as_str(),
+                    f.locations[0].start_line,
+                    f.locations[1].start_line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                (
+                    "`execute_withdraw` writes `BALANCES` after adding a message to the Response",
+                    16,
+                    12
+                ),
+                (
+                    "`execute_withdraw` calls `record_withdrawal`, which writes state, after \
+                     adding a message to the Response",
+                    17,
+                    12
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_state_saved_before_messages_is_not_flagged() {
+        let findings = analyze(
+            r#"
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    let balance = BALANCES.load(deps.storage, &info.sender)?;
+    BALANCES.remove(deps.storage, &info.sender);
+    let msg = payout(&info, balance);
+    let mut pending = vec![1, 2];
+    pending.remove(0);
+    Ok(Response::new().add_message(msg))
+}
+
+fn payout(info: &MessageInfo, amount: Uint128) -> BankMsg {
+    BankMsg::Send {
+        to_address: info.sender.to_string(),
+        amount: coins(amount.u128(), "uatom"),
+    }
+}
+
+#[entry_point]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    let resp = Response::new().add_message(BankMsg::Burn { amount: vec![] });
+    CONFIG.save(deps.storage, &Config::default())?;
+    Ok(resp)
+}
+"#,
+        );
+        assert!(findings.is_empty(), "{findings:?}");
+    }
+}
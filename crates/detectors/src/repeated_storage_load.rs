@@ -0,0 +1,359 @@
+use std::collections::{HashMap, HashSet};
+
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::{BlockId, FunctionIr, Instruction, Operand};
+use quote::ToTokens;
+use syn::visit::Visit;
+
+/// Detects a function loading the same `Item`, or the same `Map` key, again
+/// while the value it already loaded is still current: every path to the
+/// second load passes the first with no store, `remove` or call to a function
+/// writing the item in between. Each load is a storage read paid in gas, and
+/// two copies of one value invite updating one and saving the other.
+pub struct RepeatedStorageLoad;
+
+/// Methods removing stored values, which the IR keeps as plain method calls
+const REMOVE_METHODS: &[&str] = &["remove", "clear"];
+
+/// A loaded value: the item and the key it was loaded at
+type Load = (String, Option<Operand>);
+
+/// Loads current at some point, or None before any path reached it
+type Available = Option<Vec<Load>>;
+
+fn meet(into: &mut Available, from: &[Load]) {
+    match into {
+        None => *into = Some(from.to_vec()),
+        Some(loads) => loads.retain(|load| from.contains(load)),
+    }
+}
+
+/// Apply one instruction to the loads current before it
+fn transfer(inst: &Instruction, loads: &mut Vec<Load>, writes: &impl Fn(&str) -> HashSet<String>) {
+    match inst {
+        Instruction::StorageLoad {
+            storage_item, key, ..
+        } => {
+            let load = (storage_item.clone(), key.clone());
+            if !loads.contains(&load) {
+                loads.push(load);
+            }
+        }
+        Instruction::StorageStore { storage_item, .. } => {
+            loads.retain(|(item, _)| item != storage_item);
+        }
+        Instruction::MethodCall {
+            receiver: Operand::Var(receiver),
+            method,
+            ..
+        } if REMOVE_METHODS.contains(&method.as_str()) => {
+            loads.retain(|(item, _)| *item != receiver.name);
+        }
+        Instruction::Call { func, .. } => {
+            let written = writes(func.rsplit("::").next().unwrap_or(func));
+            loads.retain(|(item, _)| !written.contains(item));
+        }
+        _ => {}
+    }
+}
+
+/// Loads of each item repeated while the first is still current, with how
+/// often, in order of first repetition
+fn repeated_loads(
+    func: &FunctionIr,
+    writes: &impl Fn(&str) -> HashSet<String>,
+) -> Vec<(Load, usize)> {
+    let cfg = &func.cfg;
+    let order = cfg.reverse_postorder();
+    let mut outs: HashMap<BlockId, Vec<Load>> = HashMap::new();
+    let mut ins: HashMap<BlockId, Vec<Load>> = HashMap::new();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &block in &order {
+            let mut available: Available = None;
+            if block == cfg.entry_block {
+                available = Some(Vec::new());
+            }
+            for pred in &cfg.blocks[block].predecessors {
+                if let Some(out) = outs.get(pred) {
+                    meet(&mut available, out);
+                }
+            }
+            let entry = available.unwrap_or_default();
+            let mut loads = entry.clone();
+            for inst in &cfg.blocks[block].instructions {
+                transfer(inst, &mut loads, writes);
+            }
+            ins.insert(block, entry);
+            if outs.get(&block) != Some(&loads) {
+                outs.insert(block, loads);
+                changed = true;
+            }
+        }
+    }
+
+    let mut repeated: Vec<(Load, usize)> = Vec::new();
+    for &block in &order {
+        let mut loads = ins.remove(&block).unwrap_or_default();
+        for inst in &cfg.blocks[block].instructions {
+            if let Instruction::StorageLoad {
+                storage_item, key, ..
+            } = inst
+            {
+                let load = (storage_item.clone(), key.clone());
+                if loads.contains(&load) {
+                    match repeated.iter_mut().find(|(l, _)| *l == load) {
+                        Some((_, count)) => *count += 1,
+                        None => repeated.push((load, 1)),
+                    }
+                }
+            }
+            transfer(inst, &mut loads, writes);
+        }
+    }
+    repeated
+}
+
+/// `ITEM.load(..)` and `ITEM.may_load(..)` calls in source order, with the
+/// rendered key argument
+struct LoadCalls<'ast>(Vec<(String, String, &'ast syn::ExprMethodCall)>);
+
+impl<'ast> Visit<'ast> for LoadCalls<'ast> {
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if node.method == "load" || node.method == "may_load" {
+            if let syn::Expr::Path(path) = node.receiver.as_ref() {
+                if let Some(last) = path.path.segments.last() {
+                    let key = node
+                        .args
+                        .iter()
+                        .nth(1)
+                        .map(|k| k.to_token_stream().to_string())
+                        .unwrap_or_default();
+                    self.0.push((last.ident.to_string(), key, node));
+                }
+            }
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+}
+
+impl Detector for RepeatedStorageLoad {
+    fn name(&self) -> &str {
+        "repeated-storage-load"
+    }
+
+    fn description(&self) -> &str {
+        "Detects the same storage item or map key loaded twice in a function with no write in between"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Low
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        // Items a callee writes, directly or through its own callees
+        let writes = |callee: &str| -> HashSet<String> {
+            ctx.call_graph()
+                .reachable_from(&[callee])
+                .iter()
+                .flat_map(|f| ctx.storage_writes(f))
+                .map(|w| w.item.clone())
+                .collect()
+        };
+
+        let mut findings = Vec::new();
+        for func in &ctx.ir.functions {
+            let repeated = repeated_loads(func, &writes);
+            if repeated.is_empty() {
+                continue;
+            }
+            let calls = ctx
+                .contract
+                .functions
+                .iter()
+                .find(|f| f.name == func.name && f.span.file == func.source_span.file)
+                .and_then(|f| f.body.as_ref())
+                .map(|body| {
+                    let mut calls = LoadCalls(Vec::new());
+                    calls.visit_block(body);
+                    calls.0
+                })
+                .unwrap_or_default();
+
+            // Keys loaded more than once of each item, in source order: the
+            // n-th repeated load of an item is matched with its n-th such key
+            let mut groups: Vec<(&str, &str, Vec<&syn::ExprMethodCall>)> = Vec::new();
+            for (name, key, call) in &calls {
+                match groups.iter_mut().find(|(n, k, _)| n == name && k == key) {
+                    Some((_, _, group)) => group.push(call),
+                    None => groups.push((name, key, vec![call])),
+                }
+            }
+            groups.retain(|(_, _, group)| group.len() > 1);
+            let mut matched: HashMap<String, usize> = HashMap::new();
+
+            for ((item, key), count) in repeated {
+                let nth = matched.entry(item.clone()).or_default();
+                let group = groups.iter().filter(|(n, _, _)| *n == item).nth(*nth);
+                *nth += 1;
+                // The last load of the value, and the first
+                let pair = group.and_then(|(_, _, group)| {
+                    Some((ctx.span_of(*group.last()?)?, ctx.span_of(group[0])?))
+                });
+                let locations = match pair {
+                    Some((last, first)) => vec![last, first],
+                    None => vec![func.source_span.clone()],
+                };
+
+                let loaded = match key {
+                    Some(_) => format!("the same `{item}` entry"),
+                    None => format!("`{item}`"),
+                };
+                let times = if count == 1 {
+                    "again".to_string()
+                } else {
+                    format!("{} more times", count)
+                };
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title: format!("`{}` loads {loaded} {times}", func.name),
+                    description: format!(
+                        "`{}` loads {loaded} {times} after a load that is still current on \
+                         every path: nothing stores or removes it, and no function writing it \
+                         is called, in between. Each load reads and deserializes the value \
+                         again at the storage gas cost, and two copies of the same state \
+                         invite modifying one and saving or checking the other.",
+                        func.name
+                    ),
+                    severity: self.severity(),
+                    confidence: self.confidence(),
+                    locations: locations
+                        .into_iter()
+                        .map(|span| SourceLocation {
+                            file: span.file,
+                            start_line: span.start_line,
+                            end_line: span.end_line,
+                            start_col: span.start_col,
+                            end_col: span.end_col,
+                            snippet: None,
+                            byte_range: None,
+                        })
+                        .collect(),
+                    recommendation: Some(
+                        "Load the value once into a local and reuse it, passing it to helpers \
+                         that need it instead of having them load it again."
+                            .to_string(),
+                    ),
+                    fix: None,
+                    ..Default::default()
+                });
+            }
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let contract =
+            ContractVisitor::extract(PathBuf::from("test.rs"), parse_source(source).unwrap());
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([(PathBuf::from("test.rs"), source.to_string())]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        RepeatedStorageLoad.detect(&ctx)
+    }
+
+    #[test]
+    fn test_flags_loads_repeated_without_a_write() {
+        let findings = analyze(
+            r#"
+fn execute_withdraw(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(!config.paused, ContractError::Paused {});
+    let balance = BALANCES.load(deps.storage, &info.sender)?;
+    if balance.is_zero() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+    let denom = CONFIG.load(deps.storage)?.denom;
+    let again = BALANCES.load(deps.storage, &info.sender)?;
+    Ok(Response::new())
+}
+"#,
+        );
+        let titles: Vec<(&str, usize, usize)> = findings
+            .iter()
+            .map(|f| {
+                (
+                    f.title.as_str(),
+                    f.locations[0].start_line,
+                    f.locations[1].start_line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                ("`execute_withdraw` loads `CONFIG` again", 9, 3),
+                (
+                    "`execute_withdraw` loads the same `BALANCES` entry again",
+                    10,
+                    5
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_writes_branches_and_other_keys_keep_loads_apart() {
+        let findings = analyze(
+            r#"
+fn execute_transfer(deps: DepsMut, info: MessageInfo, to: Addr) -> StdResult<Response> {
+    let from_balance = BALANCES.load(deps.storage, &info.sender)?;
+    let to_balance = BALANCES.load(deps.storage, &to)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.transfers += 1;
+    CONFIG.save(deps.storage, &config)?;
+    let config = CONFIG.load(deps.storage)?;
+    bump_nonce(deps.storage)?;
+    let nonce = NONCE.may_load(deps.storage)?;
+    Ok(Response::new())
+}
+
+fn bump_nonce(storage: &mut dyn Storage) -> StdResult<()> {
+    NONCE.save(storage, &1)?;
+    Ok(())
+}
+
+fn read_state(deps: Deps, flag: bool) -> StdResult<State> {
+    let nonce = NONCE.load(deps.storage)?;
+    bump_nonce(deps.storage)?;
+    let nonce = NONCE.load(deps.storage)?;
+    if flag {
+        let a = STATE.load(deps.storage)?;
+    } else {
+        let b = STATE.load(deps.storage)?;
+    }
+    STATE.load(deps.storage)
+}
+"#,
+        );
+        let titles: Vec<&str> = findings.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(titles, vec!["`read_state` loads `STATE` again"]);
+    }
+}
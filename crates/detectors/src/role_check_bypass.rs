@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::roles::{RoleKind, VariantPermission};
+
+/// Detects execute variants that write the same state item as a
+/// role-checked variant but check no role themselves. A whitelist enforced on
+/// `Deposit` means nothing if `Transfer` updates the same balances for anyone:
+/// the second code path bypasses the check that single-handler analysis sees.
+pub struct RoleCheckBypass;
+
+/// A guarded variant writing `item`, and the roles it checks
+struct GuardedWrite<'a> {
+    item: &'a str,
+    variant: &'a VariantPermission,
+}
+
+impl Detector for RoleCheckBypass {
+    fn name(&self) -> &str {
+        "role-check-bypass"
+    }
+
+    fn description(&self) -> &str {
+        "Detects variants writing state another variant guards with a role check, without that check"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Low
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let matrix = ctx.permissions();
+
+        // Item -> variants writing it
+        let mut writers: BTreeMap<&str, Vec<&VariantPermission>> = BTreeMap::new();
+        for variant in &matrix.variants {
+            for item in &variant.writes {
+                writers.entry(item).or_default().push(variant);
+            }
+        }
+
+        // Open variant -> the first guarded write it bypasses
+        let mut bypasses: Vec<(&VariantPermission, GuardedWrite)> = Vec::new();
+        for (item, variants) in &writers {
+            let privileged = variants
+                .iter()
+                .any(|v| v.privileged_writes.iter().any(|w| w == item));
+            // Outside role and config state, only membership checks (whitelists,
+            // blacklists, operator sets) gate who may write; owner-only variants
+            // writing user state are administrative overrides
+            let guarded = variants.iter().find(|v| {
+                !v.callable_by_anyone()
+                    && (privileged
+                        || v.roles
+                            .iter()
+                            .any(|r| matrix.role_kind(r) == Some(RoleKind::Membership)))
+            });
+            let Some(guarded) = guarded else { continue };
+            for open in variants.iter().filter(|v| v.callable_by_anyone()) {
+                if bypasses
+                    .iter()
+                    .any(|(v, _)| v.message == open.message && v.variant == open.variant)
+                {
+                    continue;
+                }
+                bypasses.push((
+                    open,
+                    GuardedWrite {
+                        item,
+                        variant: guarded,
+                    },
+                ));
+            }
+        }
+
+        let mut findings = Vec::new();
+        for (open, guarded) in bypasses {
+            let Some(span) = &open.span else { continue };
+            let roles = guarded
+                .variant
+                .roles
+                .iter()
+                .map(|r| format!("`{r}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            findings.push(Finding {
+                detector_name: self.name().to_string(),
+                title: format!(
+                    "`{}::{}` writes `{}` without the {} check of `{}::{}`",
+                    open.message,
+                    open.variant,
+                    guarded.item,
+                    roles,
+                    guarded.variant.message,
+                    guarded.variant.variant
+                ),
+                description: format!(
+                    "`{}::{}` restricts writes to `{}` to {}, but `{}::{}` writes the same \
+                     item without checking any role. Any account can take the unguarded \
+                     path to change what the check was meant to protect.",
+                    guarded.variant.message,
+                    guarded.variant.variant,
+                    guarded.item,
+                    roles,
+                    open.message,
+                    open.variant
+                ),
+                severity: Severity::Medium,
+                confidence: Confidence::Low,
+                locations: vec![SourceLocation {
+                    file: span.file.clone(),
+                    start_line: span.start_line,
+                    end_line: span.end_line,
+                    start_col: span.start_col,
+                    end_col: span.end_col,
+                    snippet: None,
+                    byte_range: None,
+                }],
+                recommendation: Some(format!(
+                    "Apply the same {} check in the `{}` handler, or route both variants \
+                     through one guarded function.",
+                    roles, open.variant
+                )),
+                fix: None,
+                ..Default::default()
+            });
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        RoleCheckBypass.detect(&ctx)
+    }
+
+    #[test]
+    fn test_detects_unguarded_second_path() {
+        let source = r#"
+            pub const WHITELIST: Map<&Addr, Empty> = Map::new("whitelist");
+            pub const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
+
+            #[cw_serde]
+            pub enum ExecuteMsg {
+                Deposit { amount: Uint128 },
+                DepositFor { recipient: String, amount: Uint128 },
+            }
+
+            #[entry_point]
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> StdResult<Response> {
+                match msg {
+                    ExecuteMsg::Deposit { amount } => execute_deposit(deps, info, amount),
+                    ExecuteMsg::DepositFor { recipient, amount } => {
+                        let recipient = deps.api.addr_validate(&recipient)?;
+                        BALANCES.save(deps.storage, &recipient, &amount)?;
+                        Ok(Response::new())
+                    }
+                }
+            }
+
+            fn execute_deposit(deps: DepsMut, info: MessageInfo, amount: Uint128)
+                -> StdResult<Response> {
+                if !WHITELIST.has(deps.storage, &info.sender) {
+                    return Err(StdError::generic_err("not whitelisted"));
+                }
+                BALANCES.save(deps.storage, &info.sender, &amount)?;
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0]
+            .title
+            .contains("`ExecuteMsg::DepositFor` writes `BALANCES`"));
+        assert!(findings[0].title.contains("`whitelist`"));
+    }
+
+    #[test]
+    fn test_owner_override_of_user_state_not_flagged() {
+        let source = r#"
+            pub const OWNER: Item<Addr> = Item::new("owner");
+            pub const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
+
+            #[cw_serde]
+            pub enum ExecuteMsg {
+                Deposit { amount: Uint128 },
+                ForceSet { user: String, amount: Uint128 },
+            }
+
+            #[entry_point]
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> StdResult<Response> {
+                match msg {
+                    ExecuteMsg::Deposit { amount } => {
+                        BALANCES.save(deps.storage, &info.sender, &amount)?;
+                        Ok(Response::new())
+                    }
+                    ExecuteMsg::ForceSet { user, amount } => {
+                        if info.sender != OWNER.load(deps.storage)? {
+                            return Err(StdError::generic_err("unauthorized"));
+                        }
+                        let user = deps.api.addr_validate(&user)?;
+                        BALANCES.save(deps.storage, &user, &amount)?;
+                        Ok(Response::new())
+                    }
+                }
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+}
@@ -0,0 +1,553 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::slice::Defs;
+use cosmwasm_guard::ir::{
+    instruction_def, instruction_uses, BinaryOp, FunctionIr, Instruction, Operand, SsaVar,
+};
+
+/// Reports the rounding direction of share/reward math such as
+/// `user_shares * total_rewards / total_shares`. Rounding must favor the
+/// protocol: amounts paid out round down, amounts charged round up. Rounding
+/// the other way lets a user repeat tiny operations and extract dust, and
+/// dividing before multiplying truncates the intermediate ratio.
+pub struct RoundingDirection;
+
+/// Name fragments marking share, supply and reward accounting
+const SHARE_NAMES: &[&str] = &[
+    "share",
+    "reward",
+    "supply",
+    "total",
+    "stake",
+    "index",
+    "liquidity",
+    "pool",
+];
+
+/// Name segments of amounts paid out to the user
+const CREDIT_NAMES: &[&str] = &[
+    "mint", "minted", "payout", "claim", "claimed", "reward", "rewards", "withdraw", "redeem",
+    "receive", "out", "return",
+];
+
+/// Name segments of amounts charged to the user
+const DEBIT_NAMES: &[&str] = &[
+    "burn", "burned", "cost", "debt", "owed", "repay", "fee", "fees", "required", "in", "price",
+    "charge", "deduct", "pay",
+];
+
+/// Methods dividing with truncation
+const FLOOR_METHODS: &[&str] = &[
+    "checked_div",
+    "div_floor",
+    "checked_div_floor",
+    "mul_floor",
+    "checked_mul_floor",
+    "multiply_ratio",
+    "checked_multiply_ratio",
+];
+
+/// Methods dividing with rounding up
+const CEIL_METHODS: &[&str] = &[
+    "div_ceil",
+    "checked_div_ceil",
+    "mul_ceil",
+    "checked_mul_ceil",
+];
+
+/// Methods multiplying their receiver by an argument
+const MUL_METHODS: &[&str] = &["checked_mul", "saturating_mul", "wrapping_mul", "mul"];
+
+const ADD_METHODS: &[&str] = &["checked_add", "saturating_add", "add"];
+
+const SUB_METHODS: &[&str] = &["checked_sub", "saturating_sub", "sub"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rounding {
+    Down,
+    Up,
+}
+
+/// Who receives the rounded amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flow {
+    /// Paid or minted to the user
+    Credit,
+    /// Charged to or burned from the user
+    Debit,
+}
+
+impl Flow {
+    fn favors_user(self, rounding: Rounding) -> bool {
+        matches!(
+            (self, rounding),
+            (Flow::Credit, Rounding::Up) | (Flow::Debit, Rounding::Down)
+        )
+    }
+}
+
+fn is_temp(name: &str) -> bool {
+    name.starts_with("_t") || name.starts_with("_pat")
+}
+
+fn is_share_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SHARE_NAMES.iter().any(|s| name.contains(s))
+}
+
+fn has_segment(name: &str, segments: &[&str]) -> bool {
+    name.to_lowercase()
+        .split('_')
+        .any(|segment| segments.contains(&segment))
+}
+
+fn mentions(operand: &Operand, var: &SsaVar) -> bool {
+    match operand {
+        Operand::Var(v) => v == var,
+        Operand::FieldAccess { base, .. } => mentions(base, var),
+        Operand::Literal(_) => false,
+    }
+}
+
+/// A division in share/reward math
+struct Division<'a> {
+    dest: &'a SsaVar,
+    rounding: Rounding,
+    /// Integer `/` or `checked_div`, which truncates an intermediate ratio
+    truncating: bool,
+    operands: Vec<&'a Operand>,
+}
+
+/// Where a value comes from and where it goes within one function
+struct Flows<'a> {
+    defs: Defs<'a>,
+    users: HashMap<&'a SsaVar, Vec<&'a Instruction>>,
+}
+
+impl<'a> Flows<'a> {
+    fn new(func: &'a FunctionIr) -> Self {
+        let mut users: HashMap<&SsaVar, Vec<&Instruction>> = HashMap::new();
+        for inst in func.cfg.blocks.iter().flat_map(|b| &b.instructions) {
+            for var in instruction_uses(inst) {
+                users.entry(var).or_default().push(inst);
+            }
+        }
+        Self {
+            defs: Defs::new(func),
+            users,
+        }
+    }
+
+    /// Variable, field and storage item names `operand` is computed from
+    fn sources(&self, operand: &'a Operand) -> BTreeSet<String> {
+        let mut names = self.defs.names(operand);
+        names.retain(|n| !is_temp(n));
+        names
+    }
+
+    /// Whether the value of `var` is paid to or charged from the user, and
+    /// the first name it is bound to. Names decide; otherwise adding the
+    /// value to a balance or sending it is a credit and subtracting it a debit.
+    fn flow(&self, var: &'a SsaVar) -> (Option<Flow>, Option<String>) {
+        let mut names = Vec::new();
+        let (mut credit, mut debit) = (false, false);
+        let mut queue = vec![var];
+        let mut seen = HashSet::new();
+        while let Some(var) = queue.pop() {
+            if !seen.insert(var) {
+                continue;
+            }
+            if !is_temp(&var.name) {
+                names.push(var.name.clone());
+            }
+            for inst in self.users.get(var).into_iter().flatten() {
+                match inst {
+                    Instruction::BinaryOp {
+                        op: BinaryOp::Add, ..
+                    } => credit = true,
+                    Instruction::BinaryOp {
+                        op: BinaryOp::Sub,
+                        right,
+                        ..
+                    } if mentions(right, var) => debit = true,
+                    Instruction::MethodCall { method, args, .. }
+                        if ADD_METHODS.contains(&method.as_str())
+                            && args.iter().any(|a| mentions(a, var)) =>
+                    {
+                        credit = true
+                    }
+                    Instruction::MethodCall { method, args, .. }
+                        if SUB_METHODS.contains(&method.as_str())
+                            && args.iter().any(|a| mentions(a, var)) =>
+                    {
+                        debit = true
+                    }
+                    Instruction::SendMsg { .. } => credit = true,
+                    Instruction::BinaryOp {
+                        op:
+                            BinaryOp::Eq
+                            | BinaryOp::Ne
+                            | BinaryOp::Lt
+                            | BinaryOp::Le
+                            | BinaryOp::Gt
+                            | BinaryOp::Ge,
+                        ..
+                    } => {}
+                    _ => queue.extend(instruction_def(inst)),
+                }
+            }
+        }
+
+        let by_name = |segments: &[&str]| names.iter().any(|n| has_segment(n, segments));
+        let flow = match (by_name(CREDIT_NAMES), by_name(DEBIT_NAMES)) {
+            (true, false) => Some(Flow::Credit),
+            (false, true) => Some(Flow::Debit),
+            (true, true) => None,
+            (false, false) => match (credit, debit) {
+                (true, false) => Some(Flow::Credit),
+                (false, true) => Some(Flow::Debit),
+                _ => None,
+            },
+        };
+        (flow, names.into_iter().next())
+    }
+
+    /// Multiplication the truncated result of `var` feeds into, following
+    /// plain assignments
+    fn multiplied(&self, var: &'a SsaVar) -> Option<&'a SsaVar> {
+        let mut queue = vec![var];
+        let mut seen = HashSet::new();
+        while let Some(var) = queue.pop() {
+            if !seen.insert(var) {
+                continue;
+            }
+            for inst in self.users.get(var).into_iter().flatten() {
+                match inst {
+                    Instruction::BinaryOp {
+                        op: BinaryOp::Mul,
+                        dest,
+                        ..
+                    } => return Some(dest),
+                    Instruction::MethodCall {
+                        method,
+                        dest: Some(dest),
+                        ..
+                    } if MUL_METHODS.contains(&method.as_str()) => return Some(dest),
+                    Instruction::Assign { dest, .. } | Instruction::ResultUnwrap { dest, .. } => {
+                        queue.push(dest)
+                    }
+                    Instruction::MethodCall {
+                        method,
+                        dest: Some(dest),
+                        ..
+                    } if method == "unwrap" || method == "into" => queue.push(dest),
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Divisions in `func`, with the operands they divide
+fn divisions(func: &FunctionIr) -> Vec<Division<'_>> {
+    let mut divisions = Vec::new();
+    for inst in func.cfg.blocks.iter().flat_map(|b| &b.instructions) {
+        let division = match inst {
+            Instruction::BinaryOp {
+                op: BinaryOp::Div,
+                dest,
+                left,
+                right,
+            } => Division {
+                dest,
+                rounding: Rounding::Down,
+                truncating: true,
+                operands: vec![left, right],
+            },
+            Instruction::MethodCall {
+                dest: Some(dest),
+                receiver,
+                method,
+                args,
+            } => {
+                let rounding = if FLOOR_METHODS.contains(&method.as_str()) {
+                    Rounding::Down
+                } else if CEIL_METHODS.contains(&method.as_str()) {
+                    Rounding::Up
+                } else {
+                    continue;
+                };
+                Division {
+                    dest,
+                    rounding,
+                    truncating: method == "checked_div",
+                    operands: std::iter::once(receiver).chain(args).collect(),
+                }
+            }
+            _ => continue,
+        };
+        divisions.push(division);
+    }
+    divisions
+}
+
+impl RoundingDirection {
+    fn finding(
+        &self,
+        func: &FunctionIr,
+        severity: Severity,
+        title: String,
+        description: String,
+        recommendation: &str,
+    ) -> Finding {
+        let span = &func.source_span;
+        Finding {
+            detector_name: self.name().to_string(),
+            title,
+            description,
+            severity,
+            confidence: Confidence::Low,
+            locations: vec![SourceLocation {
+                file: span.file.clone(),
+                start_line: span.start_line,
+                end_line: span.end_line,
+                start_col: span.start_col,
+                end_col: span.end_col,
+                snippet: None,
+                byte_range: None,
+            }],
+            recommendation: Some(recommendation.to_string()),
+            fix: None,
+            ..Default::default()
+        }
+    }
+}
+
+impl Detector for RoundingDirection {
+    fn name(&self) -> &str {
+        "rounding-direction"
+    }
+
+    fn description(&self) -> &str {
+        "Detects share/reward math that rounds in the user's favor or divides before multiplying"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Low
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Experimental
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for func in &ctx.ir.functions {
+            let divisions = divisions(func);
+            if divisions.is_empty() {
+                continue;
+            }
+            let flows = Flows::new(func);
+            let mut reported: HashSet<(String, bool)> = HashSet::new();
+
+            for division in divisions {
+                let sources: BTreeSet<String> = division
+                    .operands
+                    .iter()
+                    .flat_map(|op| flows.sources(op))
+                    .collect();
+                if !sources.iter().any(|s| is_share_name(s)) {
+                    continue;
+                }
+                let inputs = sources
+                    .iter()
+                    .map(|s| format!("`{s}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                // 1. A truncated ratio multiplied afterwards loses precision
+                let product = division
+                    .truncating
+                    .then(|| flows.multiplied(division.dest))
+                    .flatten();
+                if let Some(product) = product {
+                    let (flow, name) = flows.flow(product);
+                    let name = name.unwrap_or_else(|| "the result".to_string());
+                    if reported.insert((name.clone(), false)) {
+                        let favoring = match flow {
+                            Some(flow) => format!(
+                                " The error rounds `{name}` down, which favors {}.",
+                                if flow.favors_user(Rounding::Down) {
+                                    "the user"
+                                } else {
+                                    "the protocol"
+                                }
+                            ),
+                            None => String::new(),
+                        };
+                        findings.push(self.finding(
+                            func,
+                            Severity::Low,
+                            format!("Division before multiplication in `{}`", func.name),
+                            format!(
+                                "`{}` divides share/reward math over {inputs} before \
+                                 multiplying, truncating the intermediate ratio. Small \
+                                 positions get a zero or reduced `{name}`.{favoring}",
+                                func.name
+                            ),
+                            "Multiply first and divide last, e.g. \
+                             `amount.multiply_ratio(total_rewards, total_shares)`, or keep \
+                             the ratio in a `Decimal`.",
+                        ));
+                    }
+                    continue;
+                }
+
+                // 2. Rounding that favors the user
+                let (Some(flow), name) = flows.flow(division.dest) else {
+                    continue;
+                };
+                if !flow.favors_user(division.rounding) {
+                    continue;
+                }
+                let name = name.unwrap_or_else(|| "the result".to_string());
+                if !reported.insert((name.clone(), true)) {
+                    continue;
+                }
+                let (direction, role, fix) = match division.rounding {
+                    Rounding::Down => (
+                        "down",
+                        "charged to",
+                        "Round amounts charged to the user up (e.g. `mul_ceil`, \
+                         `checked_div_ceil`) and reject operations where they round to zero.",
+                    ),
+                    Rounding::Up => (
+                        "up",
+                        "paid to",
+                        "Round amounts paid to the user down (e.g. `mul_floor`, \
+                         `multiply_ratio`).",
+                    ),
+                };
+                findings.push(self.finding(
+                    func,
+                    Severity::Medium,
+                    format!(
+                        "`{name}` rounds {direction} in the user's favor in `{}`",
+                        func.name
+                    ),
+                    format!(
+                        "`{name}` is computed from {inputs} with rounding {direction} and is \
+                         {role} the user, so rounding favors the user. Repeating the \
+                         operation with small amounts extracts the rounding error from the \
+                         pool each time (dust extraction)."
+                    ),
+                    fix,
+                ));
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        RoundingDirection.detect(&ctx)
+    }
+
+    #[test]
+    fn test_detects_burn_rounded_down() {
+        let source = r#"
+            fn execute_withdraw(deps: DepsMut, info: MessageInfo, amount: Uint128)
+                -> StdResult<Response> {
+                let total_shares = TOTAL_SHARES.load(deps.storage)?;
+                let total_assets = TOTAL_ASSETS.load(deps.storage)?;
+                let burn_shares = amount * total_shares / total_assets;
+                TOTAL_SHARES.save(deps.storage, &(total_shares - burn_shares))?;
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("`burn_shares` rounds down"));
+        assert!(findings[0].description.contains("favors the user"));
+    }
+
+    #[test]
+    fn test_payout_rounded_down_not_flagged() {
+        let source = r#"
+            fn execute_claim(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+                let stake = STAKES.load(deps.storage, &info.sender)?;
+                let total_stake = TOTAL_STAKE.load(deps.storage)?;
+                let payout = stake.multiply_ratio(TOTAL_REWARDS.load(deps.storage)?, total_stake);
+                Ok(Response::new().add_message(BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: coins(payout.u128(), "uatom"),
+                }))
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+
+    #[test]
+    fn test_detects_minted_shares_rounded_up() {
+        let source = r#"
+            fn execute_deposit(deps: DepsMut, amount: Uint128) -> StdResult<Response> {
+                let total_supply = SUPPLY.load(deps.storage)?;
+                let minted = amount.mul_ceil(Decimal::from_ratio(total_supply, POOL.load(deps.storage)?));
+                SUPPLY.save(deps.storage, &(total_supply + minted))?;
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("`minted` rounds up"));
+    }
+
+    #[test]
+    fn test_detects_division_before_multiplication() {
+        let source = r#"
+            fn pending_reward(deps: Deps, user_shares: Uint128) -> StdResult<Uint128> {
+                let reward_per_share = TOTAL_REWARDS.load(deps.storage)? / TOTAL_SHARES.load(deps.storage)?;
+                let reward = user_shares * reward_per_share;
+                Ok(reward)
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Low);
+        assert!(findings[0].title.contains("Division before multiplication"));
+        assert!(findings[0].description.contains("favors the protocol"));
+    }
+
+    #[test]
+    fn test_unrelated_division_not_flagged() {
+        let source = r#"
+            fn average(a: u64, b: u64) -> u64 {
+                let burn_window = (a + b) / 2;
+                burn_window
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+}
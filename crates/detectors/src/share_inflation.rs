@@ -0,0 +1,516 @@
+use std::collections::BTreeSet;
+
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::slice::Defs;
+use cosmwasm_guard::ir::{
+    BinaryOp, FunctionIr, Instruction, LiteralValue, Operand, QueryKind, SsaVar,
+};
+
+/// Detects share minting computed as `amount * total_shares / total_deposits`
+/// without a minimum-liquidity lock. The first depositor mints a single share,
+/// then inflates `total_deposits` (a donation to the contract's balance, or a
+/// deposit that rounds to zero shares), so later depositors' shares round down
+/// to nothing and their deposits accrue to the attacker. A special case for a
+/// zero total alone does not help when the total is a queried balance anyone
+/// can donate to.
+pub struct ShareInflation;
+
+/// Name fragments marking share or LP token supply
+const SHARE_NAMES: &[&str] = &["share", "supply"];
+
+/// Name fragments marking pooled assets backing the shares
+const ASSET_NAMES: &[&str] = &[
+    "deposit",
+    "asset",
+    "balance",
+    "reserve",
+    "liquidity",
+    "underlying",
+    "pool",
+    "tvl",
+    "total",
+];
+
+/// Name fragments of minimum-liquidity locks and virtual offsets, which keep
+/// the first share from being priced arbitrarily high
+const LOCK_NAMES: &[&str] = &[
+    "minimum_liquidity",
+    "min_liquidity",
+    "minimum_shares",
+    "min_shares",
+    "dead_shares",
+    "locked_shares",
+    "virtual_shares",
+    "virtual_assets",
+    "share_offset",
+    "decimals_offset",
+];
+
+/// Name segments of shares burned rather than minted
+const BURN_NAMES: &[&str] = &[
+    "burn", "redeem", "withdraw", "unbond", "unstake", "remove", "exit",
+];
+
+/// Methods computing `receiver * numerator / denominator`
+const RATIO_METHODS: &[&str] = &["multiply_ratio", "checked_multiply_ratio"];
+
+/// Methods dividing their receiver by an argument
+const DIV_METHODS: &[&str] = &["checked_div", "div_floor", "checked_div_floor"];
+
+fn is_temp(name: &str) -> bool {
+    name.starts_with("_t") || name.starts_with("_pat")
+}
+
+fn matches_any(name: &str, fragments: &[&str]) -> bool {
+    let name = name.to_lowercase();
+    fragments.iter().any(|f| name.contains(f))
+}
+
+fn has_segment(name: &str, segments: &[&str]) -> bool {
+    name.to_lowercase()
+        .split(['_', ':'])
+        .any(|segment| segments.contains(&segment))
+}
+
+fn is_zero(operand: &Operand, defs: &Defs) -> bool {
+    match operand {
+        Operand::Literal(LiteralValue::Int(0) | LiteralValue::Uint(0)) => true,
+        Operand::Var(var) => matches!(
+            defs.get(var),
+            Some(Instruction::Call { func, .. }) if func.ends_with("::zero")
+        ),
+        _ => false,
+    }
+}
+
+/// What a value is computed from
+#[derive(Default)]
+struct Origins {
+    /// Variable, field and storage item names
+    names: BTreeSet<String>,
+    /// Read from storage or queried
+    state: bool,
+    /// Includes a queried balance, which donations raise
+    donatable: bool,
+}
+
+impl Origins {
+    fn any_name(&self, fragments: &[&str]) -> bool {
+        self.names.iter().any(|n| matches_any(n, fragments))
+    }
+}
+
+/// What `operands` are computed from, stopping at storage reads and queries
+fn origins<'a>(defs: &Defs<'a>, operands: &[&'a Operand]) -> Origins {
+    let mut origins = Origins::default();
+    let not_query = |inst: &Instruction| !matches!(inst, Instruction::QueryExternal { .. });
+    for operand in operands {
+        for source in defs.slice_through(operand, not_query) {
+            let var = match source {
+                Operand::Literal(_) => continue,
+                Operand::FieldAccess { field, .. } => {
+                    origins.names.insert(field.clone());
+                    continue;
+                }
+                Operand::Var(var) => var,
+            };
+            if !is_temp(&var.name) {
+                origins.names.insert(var.name.clone());
+            }
+            match defs.get(var) {
+                Some(Instruction::StorageLoad { storage_item, .. }) => {
+                    origins.names.insert(storage_item.clone());
+                    origins.state = true;
+                }
+                // Bank and token balances anyone can raise by sending funds
+                Some(Instruction::QueryExternal { kind, .. }) if *kind != QueryKind::WasmRaw => {
+                    origins.state = true;
+                    origins.donatable = true;
+                }
+                _ => {}
+            }
+        }
+    }
+    origins
+}
+
+/// Whether `func` compares a share or asset total against zero
+fn checks_zero_total<'a>(defs: &Defs<'a>, func: &'a FunctionIr) -> bool {
+    let totals = |operands: &[&'a Operand]| {
+        let origins = origins(defs, operands);
+        origins.any_name(SHARE_NAMES) || origins.any_name(ASSET_NAMES)
+    };
+    func.cfg
+        .blocks
+        .iter()
+        .flat_map(|b| &b.instructions)
+        .any(|inst| match inst {
+            Instruction::MethodCall {
+                receiver, method, ..
+            } if method == "is_zero" => totals(&[receiver]),
+            Instruction::BinaryOp {
+                op: BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Le | BinaryOp::Gt,
+                left,
+                right,
+                ..
+            } => {
+                (is_zero(right, defs) && totals(&[left]))
+                    || (is_zero(left, defs) && totals(&[right]))
+            }
+            _ => false,
+        })
+}
+
+/// `numerator / denominator` computed by `inst`, with its result
+fn ratio(inst: &Instruction) -> Option<(Vec<&Operand>, &Operand, &SsaVar)> {
+    match inst {
+        Instruction::BinaryOp {
+            op: BinaryOp::Div,
+            dest,
+            left,
+            right,
+        } => Some((vec![left], right, dest)),
+        Instruction::MethodCall {
+            dest: Some(dest),
+            receiver,
+            method,
+            args,
+        } if RATIO_METHODS.contains(&method.as_str()) => match args.as_slice() {
+            [numerator, denominator] => Some((vec![receiver, numerator], denominator, dest)),
+            _ => None,
+        },
+        // `Decimal::from_ratio(total_shares, total_deposits)`, applied to the
+        // amount with `mul_floor`
+        Instruction::Call {
+            dest: Some(dest),
+            func,
+            args,
+        } if func.ends_with("from_ratio") => match args.as_slice() {
+            [numerator, denominator] => Some((vec![numerator], denominator, dest)),
+            _ => None,
+        },
+        Instruction::MethodCall {
+            dest: Some(dest),
+            receiver,
+            method,
+            args,
+        } if DIV_METHODS.contains(&method.as_str()) => Some((vec![receiver], args.first()?, dest)),
+        _ => None,
+    }
+}
+
+/// Name the result of `var` is first bound to, following temporaries
+fn bound_name<'a>(func: &'a FunctionIr, var: &'a SsaVar) -> Option<&'a str> {
+    let mut current = var;
+    for _ in 0..8 {
+        if !is_temp(&current.name) {
+            return Some(&current.name);
+        }
+        current = func
+            .cfg
+            .blocks
+            .iter()
+            .flat_map(|b| &b.instructions)
+            .find_map(|inst| match inst {
+                Instruction::Assign {
+                    dest,
+                    value: Operand::Var(v),
+                }
+                | Instruction::ResultUnwrap {
+                    dest,
+                    value: Operand::Var(v),
+                } if v == current => Some(dest),
+                Instruction::MethodCall {
+                    dest: Some(dest),
+                    receiver: Operand::Var(v),
+                    method,
+                    ..
+                } if v == current && (method == "unwrap" || method == "into") => Some(dest),
+                _ => None,
+            })?;
+    }
+    None
+}
+
+/// Whether any function of the contract names a minimum-liquidity lock
+fn has_liquidity_lock(ctx: &AnalysisContext) -> bool {
+    let mentions = |operand: &Operand| {
+        let mut operand = operand;
+        loop {
+            match operand {
+                Operand::Var(var) => return matches_any(&var.name, LOCK_NAMES),
+                Operand::FieldAccess { base, field } => {
+                    if matches_any(field, LOCK_NAMES) {
+                        return true;
+                    }
+                    operand = base;
+                }
+                Operand::Literal(_) => return false,
+            }
+        }
+    };
+    ctx.ir
+        .functions
+        .iter()
+        .flat_map(|f| &f.cfg.blocks)
+        .flat_map(|b| &b.instructions)
+        .any(|inst| match inst {
+            Instruction::Assign { value, .. } => mentions(value),
+            Instruction::BinaryOp { left, right, .. } => mentions(left) || mentions(right),
+            Instruction::Call { func, args, .. } => {
+                matches_any(func, LOCK_NAMES) || args.iter().any(mentions)
+            }
+            Instruction::MethodCall { receiver, args, .. } => {
+                mentions(receiver) || args.iter().any(mentions)
+            }
+            Instruction::StorageStore { storage_item, .. } => matches_any(storage_item, LOCK_NAMES),
+            _ => false,
+        })
+}
+
+impl ShareInflation {
+    fn finding(&self, func: &FunctionIr, title: String, description: String) -> Finding {
+        let span = &func.source_span;
+        Finding {
+            detector_name: self.name().to_string(),
+            title,
+            description,
+            severity: self.severity(),
+            confidence: self.confidence(),
+            locations: vec![SourceLocation {
+                file: span.file.clone(),
+                start_line: span.start_line,
+                end_line: span.end_line,
+                start_col: span.start_col,
+                end_col: span.end_col,
+                snippet: None,
+                byte_range: None,
+            }],
+            recommendation: Some(
+                "Lock a minimum amount of liquidity on the first deposit (mint \
+                 `MINIMUM_LIQUIDITY` shares to the contract itself, as Uniswap V2 does) \
+                 or add virtual shares and assets to both totals, and reject deposits \
+                 that mint zero shares. Track deposits in storage rather than reading \
+                 the contract's balance, which anyone can donate to."
+                    .to_string(),
+            ),
+            fix: None,
+            ..Default::default()
+        }
+    }
+}
+
+impl Detector for ShareInflation {
+    fn name(&self) -> &str {
+        "share-inflation"
+    }
+
+    fn description(&self) -> &str {
+        "Detects share minting open to the first-depositor share inflation attack"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Critical
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Low
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Experimental
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        if has_liquidity_lock(ctx) {
+            return Vec::new();
+        }
+        let mut findings = Vec::new();
+
+        for func in &ctx.ir.functions {
+            if has_segment(&func.name, BURN_NAMES) {
+                continue;
+            }
+            let defs = Defs::new(func);
+            let minted = func
+                .cfg
+                .blocks
+                .iter()
+                .flat_map(|b| &b.instructions)
+                .filter_map(ratio)
+                .find_map(|(numerator, denominator, dest)| {
+                    let num = origins(&defs, &numerator);
+                    let den = origins(&defs, &[denominator]);
+                    let is_mint = num.any_name(SHARE_NAMES)
+                        && den.any_name(ASSET_NAMES)
+                        && !den.any_name(SHARE_NAMES)
+                        && (num.state || den.state);
+                    let name = bound_name(func, dest);
+                    (is_mint && !name.is_some_and(|n| has_segment(n, BURN_NAMES)))
+                        .then_some((den, name))
+                });
+            let Some((den, name)) = minted else {
+                continue;
+            };
+
+            let result = name.map_or_else(|| "shares".to_string(), |n| format!("`{n}`"));
+            let totals = den
+                .names
+                .iter()
+                .filter(|n| !is_temp(n))
+                .map(|n| format!("`{n}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let (title, why) = match (checks_zero_total(&defs, func), den.donatable) {
+                (false, _) => (
+                    format!(
+                        "Share minting in `{}` has no zero-supply case or minimum liquidity lock",
+                        func.name
+                    ),
+                    "Nothing special-cases the first deposit or locks initial liquidity.",
+                ),
+                (true, true) => (
+                    format!(
+                        "First depositor can inflate the share price in `{}`",
+                        func.name
+                    ),
+                    "The zero-supply case does not help: the total is a queried balance, \
+                     which anyone can raise by sending funds to the contract.",
+                ),
+                (true, false) => continue,
+            };
+            findings.push(self.finding(
+                func,
+                title,
+                format!(
+                    "`{}` mints {result} in proportion to the deposit over {totals}. {why} \
+                     The first depositor can mint one share and inflate the total backing \
+                     it, so later deposits round down to zero shares and accrue to the \
+                     attacker.",
+                    func.name
+                ),
+            ));
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        ShareInflation.detect(&ctx)
+    }
+
+    #[test]
+    fn test_detects_unguarded_share_minting() {
+        let source = r#"
+            fn execute_deposit(deps: DepsMut, info: MessageInfo, amount: Uint128)
+                -> StdResult<Response> {
+                let total_shares = TOTAL_SHARES.load(deps.storage)?;
+                let total_deposits = TOTAL_DEPOSITS.load(deps.storage)?;
+                let shares = amount * total_shares / total_deposits;
+                TOTAL_SHARES.save(deps.storage, &(total_shares + shares))?;
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("no zero-supply case"));
+        assert!(findings[0].description.contains("mints `shares`"));
+    }
+
+    #[test]
+    fn test_detects_zero_case_over_queried_balance() {
+        let source = r#"
+            fn execute_deposit(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+                let amount = must_pay(&info, "uatom")?;
+                let total_supply = SUPPLY.load(deps.storage)?;
+                let balance = deps.querier.query_balance(env.contract.address, "uatom")?.amount;
+                let minted = if total_supply.is_zero() {
+                    amount
+                } else {
+                    amount.multiply_ratio(total_supply, balance - amount)
+                };
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("First depositor can inflate"));
+    }
+
+    #[test]
+    fn test_detects_ratio_decimal() {
+        let source = r#"
+            fn execute_bond(deps: DepsMut, amount: Uint128) -> StdResult<Response> {
+                let supply = LP_SUPPLY.load(deps.storage)?;
+                let minted = amount.mul_floor(Decimal::from_ratio(supply, POOL.load(deps.storage)?));
+                Ok(Response::new())
+            }
+        "#;
+        assert_eq!(analyze(source).len(), 1);
+    }
+
+    #[test]
+    fn test_zero_case_over_tracked_deposits_not_flagged() {
+        let source = r#"
+            fn execute_deposit(deps: DepsMut, amount: Uint128) -> StdResult<Response> {
+                let total_shares = TOTAL_SHARES.load(deps.storage)?;
+                let total_assets = TOTAL_ASSETS.load(deps.storage)?;
+                let shares = if total_shares == Uint128::zero() {
+                    amount
+                } else {
+                    amount.multiply_ratio(total_shares, total_assets)
+                };
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+
+    #[test]
+    fn test_minimum_liquidity_lock_not_flagged() {
+        let source = r#"
+            const MINIMUM_LIQUIDITY: u128 = 1000;
+
+            fn execute_deposit(deps: DepsMut, env: Env, amount: Uint128) -> StdResult<Response> {
+                let total_supply = SUPPLY.load(deps.storage)?;
+                let reserve = deps.querier.query_balance(env.contract.address, "uatom")?.amount;
+                let minted = if total_supply.is_zero() {
+                    amount - Uint128::from(MINIMUM_LIQUIDITY)
+                } else {
+                    amount.multiply_ratio(total_supply, reserve)
+                };
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+
+    #[test]
+    fn test_redemption_not_flagged() {
+        let source = r#"
+            fn execute_withdraw(deps: DepsMut, shares: Uint128) -> StdResult<Response> {
+                let total_shares = TOTAL_SHARES.load(deps.storage)?;
+                let total_assets = TOTAL_ASSETS.load(deps.storage)?;
+                let amount = shares * total_assets / total_shares;
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+}
@@ -0,0 +1,458 @@
+use std::collections::{HashMap, HashSet};
+
+use cosmwasm_guard::ast::{EntryPointKind, StorageType};
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use syn::visit::Visit;
+
+/// Detects execute paths that `.collect()` a storage Map range into memory when
+/// the range is bounded only by a large limit, or when the collected Vec is then
+/// re-iterated to write storage. Both patterns scale gas with the map size.
+pub struct StorageCollectInExecute;
+
+/// Default largest `.take(N)` considered safe to collect in one transaction
+const DEFAULT_MAX_COLLECT_LIMIT: u64 = 100;
+
+/// Storage methods that mutate state
+const WRITE_METHODS: &[&str] = &["save", "update", "remove"];
+
+#[derive(Debug, PartialEq)]
+enum CollectIssue {
+    LargeLimit(u64),
+    ReiteratedWithWrites(String),
+}
+
+/// Visitor that finds range-to-collect chains and loops writing over the result
+struct CollectSearcher<'a> {
+    ctx: &'a AnalysisContext<'a>,
+    storage_map_names: &'a HashSet<String>,
+    constants: &'a HashMap<String, u64>,
+    max_limit: u64,
+    /// `let` bindings holding a collected storage range: name -> (line, col)
+    collected_vars: HashMap<String, (usize, usize)>,
+    issues: Vec<(usize, usize, CollectIssue)>,
+}
+
+impl CollectSearcher<'_> {
+    /// If `node` is a `MAP.range(..)...collect()` chain, return its span and take() limit
+    fn storage_collect(&self, node: &syn::ExprMethodCall) -> Option<(usize, usize, Option<u64>)> {
+        if node.method != "collect" {
+            return None;
+        }
+        let mut has_range = false;
+        let mut limit = None;
+        let mut current: &syn::Expr = &node.receiver;
+        while let syn::Expr::MethodCall(mc) = current {
+            if mc.method == "range" || mc.method == "range_raw" || mc.method == "keys" {
+                has_range = true;
+            }
+            if mc.method == "take" {
+                limit = mc.args.first().and_then(|a| self.resolve_limit(a));
+            }
+            current = &mc.receiver;
+        }
+        let syn::Expr::Path(base) = current else {
+            return None;
+        };
+        let base_name = base.path.segments.last()?.ident.to_string();
+        if !has_range || !self.storage_map_names.contains(&base_name) {
+            return None;
+        }
+        let span = self.ctx.span_of(&node.method)?;
+        Some((span.start_line, span.start_col, limit))
+    }
+
+    /// Resolve a take() argument to a number: literal, known constant, or `as` cast of either
+    fn resolve_limit(&self, expr: &syn::Expr) -> Option<u64> {
+        match expr {
+            syn::Expr::Lit(lit) => match &lit.lit {
+                syn::Lit::Int(i) => i.base10_parse().ok(),
+                _ => None,
+            },
+            syn::Expr::Path(p) => {
+                let name = p.path.segments.last()?.ident.to_string();
+                self.constants.get(&name).copied()
+            }
+            syn::Expr::Cast(c) => self.resolve_limit(&c.expr),
+            syn::Expr::Paren(p) => self.resolve_limit(&p.expr),
+            _ => None,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CollectSearcher<'_> {
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        if let (syn::Pat::Ident(ident), Some(init)) = (strip_pat_type(&node.pat), &node.init) {
+            let collected = match strip_try(&init.expr) {
+                syn::Expr::MethodCall(call) => self.storage_collect(call),
+                _ => None,
+            };
+            if let Some((line, col, _)) = collected {
+                self.collected_vars
+                    .insert(ident.ident.to_string(), (line, col));
+            }
+        }
+        syn::visit::visit_local(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if let Some((line, col, Some(limit))) = self.storage_collect(node) {
+            if limit > self.max_limit {
+                self.issues
+                    .push((line, col, CollectIssue::LargeLimit(limit)));
+            }
+        }
+
+        // collected.iter().for_each(|x| MAP.save(..))
+        if matches!(
+            node.method.to_string().as_str(),
+            "for_each" | "try_for_each"
+        ) {
+            if let Some(name) = chain_base_ident(&node.receiver) {
+                if let Some(&(line, col)) = self.collected_vars.get(&name) {
+                    if node.args.iter().any(expr_writes_storage) {
+                        self.issues
+                            .push((line, col, CollectIssue::ReiteratedWithWrites(name)));
+                    }
+                }
+            }
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_expr_for_loop(&mut self, node: &'ast syn::ExprForLoop) {
+        if let Some(name) = chain_base_ident(&node.expr) {
+            if let Some(&(line, col)) = self.collected_vars.get(&name) {
+                if block_writes_storage(&node.body) {
+                    self.issues
+                        .push((line, col, CollectIssue::ReiteratedWithWrites(name)));
+                }
+            }
+        }
+        syn::visit::visit_expr_for_loop(self, node);
+    }
+}
+
+/// Unwrap `expr?`, `expr.unwrap()` and `expr.expect(..)` to the collected expression
+fn strip_try(expr: &syn::Expr) -> &syn::Expr {
+    match expr {
+        syn::Expr::Try(t) => strip_try(&t.expr),
+        syn::Expr::MethodCall(mc) if mc.method == "unwrap" || mc.method == "expect" => {
+            strip_try(&mc.receiver)
+        }
+        other => other,
+    }
+}
+
+fn strip_pat_type(pat: &syn::Pat) -> &syn::Pat {
+    match pat {
+        syn::Pat::Type(pt) => &pt.pat,
+        other => other,
+    }
+}
+
+/// Base identifier of `items`, `&items`, `items.iter()`, `items.into_iter().enumerate()`
+fn chain_base_ident(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Path(p) if p.path.segments.len() == 1 => {
+            Some(p.path.segments[0].ident.to_string())
+        }
+        syn::Expr::Reference(r) => chain_base_ident(&r.expr),
+        syn::Expr::MethodCall(mc) => chain_base_ident(&mc.receiver),
+        syn::Expr::Paren(p) => chain_base_ident(&p.expr),
+        _ => None,
+    }
+}
+
+struct WriteSearcher {
+    found: bool,
+}
+
+impl<'ast> Visit<'ast> for WriteSearcher {
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if WRITE_METHODS.contains(&node.method.to_string().as_str()) {
+            self.found = true;
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+}
+
+fn block_writes_storage(block: &syn::Block) -> bool {
+    let mut searcher = WriteSearcher { found: false };
+    searcher.visit_block(block);
+    searcher.found
+}
+
+fn expr_writes_storage(expr: &syn::Expr) -> bool {
+    let mut searcher = WriteSearcher { found: false };
+    searcher.visit_expr(expr);
+    searcher.found
+}
+
+/// Collect `const NAME: T = <int>;` values so `.take(MAX_LIMIT)` can be resolved
+fn collect_int_constants(ctx: &AnalysisContext) -> HashMap<String, u64> {
+    struct ConstCollector {
+        constants: HashMap<String, u64>,
+    }
+
+    impl<'ast> Visit<'ast> for ConstCollector {
+        fn visit_item_const(&mut self, node: &'ast syn::ItemConst) {
+            if let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(i),
+                ..
+            }) = node.expr.as_ref()
+            {
+                if let Ok(value) = i.base10_parse() {
+                    self.constants.insert(node.ident.to_string(), value);
+                }
+            }
+        }
+    }
+
+    let mut collector = ConstCollector {
+        constants: HashMap::new(),
+    };
+    for (_, ast) in ctx.raw_asts() {
+        collector.visit_file(ast);
+    }
+    collector.constants
+}
+
+/// Names of functions reachable from execute entry points via direct calls
+fn execute_reachable_functions(ctx: &AnalysisContext) -> HashSet<String> {
+    let roots: Vec<&str> = ctx
+        .contract
+        .entry_points
+        .iter()
+        .filter(|ep| ep.kind == EntryPointKind::Execute)
+        .map(|ep| ep.name.as_str())
+        .collect();
+    ctx.call_graph().reachable_from(&roots)
+}
+
+impl Detector for StorageCollectInExecute {
+    fn name(&self) -> &str {
+        "storage-collect-in-execute"
+    }
+
+    fn description(&self) -> &str {
+        "Detects execute paths collecting large storage ranges into memory or writing while re-iterating them"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let storage_map_names: HashSet<String> = ctx
+            .contract
+            .state_items
+            .iter()
+            .filter(|s| {
+                matches!(
+                    s.storage_type,
+                    StorageType::Map | StorageType::IndexedMap | StorageType::SnapshotMap
+                )
+            })
+            .map(|s| s.name.clone())
+            .collect();
+        if storage_map_names.is_empty() {
+            return Vec::new();
+        }
+
+        let max_limit = ctx
+            .config()
+            .detector_option(self.name(), "max_collect_limit")
+            .unwrap_or(DEFAULT_MAX_COLLECT_LIMIT);
+        let constants = collect_int_constants(ctx);
+        let reachable = execute_reachable_functions(ctx);
+
+        let mut findings = Vec::new();
+        for func in &ctx.contract.functions {
+            if !reachable.contains(&func.name) {
+                continue;
+            }
+            let Some(body) = &func.body else { continue };
+
+            let mut searcher = CollectSearcher {
+                ctx,
+                storage_map_names: &storage_map_names,
+                constants: &constants,
+                max_limit,
+                collected_vars: HashMap::new(),
+                issues: Vec::new(),
+            };
+            searcher.visit_block(body);
+            searcher.issues.dedup();
+
+            for (line, col, issue) in searcher.issues {
+                let (title, detail) = match &issue {
+                    CollectIssue::LargeLimit(limit) => (
+                        format!(
+                            "Storage range collected with large limit {} in `{}`",
+                            limit, func.name
+                        ),
+                        format!(
+                            "`{}` collects up to {} storage entries into memory during execute. \
+                             Even with a limit, gas cost grows with the collected size and can \
+                             make the handler unexecutable.",
+                            func.name, limit
+                        ),
+                    ),
+                    CollectIssue::ReiteratedWithWrites(var) => (
+                        format!(
+                            "Collected storage range `{}` re-iterated with writes in `{}`",
+                            var, func.name
+                        ),
+                        format!(
+                            "`{}` collects a storage range into `{}` and then writes storage while \
+                             iterating it. The whole map is loaded and rewritten in a single \
+                             transaction, which fails once the map grows large.",
+                            func.name, var
+                        ),
+                    ),
+                };
+
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title,
+                    description: detail,
+                    severity: Severity::Medium,
+                    confidence: Confidence::Medium,
+                    locations: vec![SourceLocation {
+                        file: func.span.file.clone(),
+                        start_line: line,
+                        end_line: line,
+                        start_col: col,
+                        end_col: col,
+                        snippet: None,
+                        byte_range: None,
+                    }],
+                    recommendation: Some(
+                        "Process entries as a stream instead of collecting them, or paginate the \
+                         write operation with a `start_after` cursor across transactions."
+                            .to_string(),
+                    ),
+                    fix: None,
+                    ..Default::default()
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        StorageCollectInExecute.detect(&ctx)
+    }
+
+    const PREAMBLE: &str = r#"
+        const STAKES: Map<&Addr, Uint128> = Map::new("stakes");
+        const MAX_LIMIT: u32 = 1000;
+
+        #[entry_point]
+        pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+            -> StdResult<Response> {
+            match msg {
+                ExecuteMsg::Distribute {} => execute_distribute(deps),
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_detects_large_limit_collect() {
+        let source = format!(
+            "{PREAMBLE}{}",
+            r#"
+            fn execute_distribute(deps: DepsMut) -> StdResult<Response> {
+                let stakes: Vec<_> = STAKES
+                    .range(deps.storage, None, None, Order::Ascending)
+                    .take(MAX_LIMIT as usize)
+                    .collect::<StdResult<_>>()?;
+                Ok(Response::new())
+            }
+            "#
+        );
+        let findings = analyze(&source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("large limit 1000"));
+    }
+
+    #[test]
+    fn test_detects_collect_then_write_loop() {
+        let source = format!(
+            "{PREAMBLE}{}",
+            r#"
+            fn execute_distribute(deps: DepsMut) -> StdResult<Response> {
+                let stakes: Vec<(Addr, Uint128)> = STAKES
+                    .range(deps.storage, None, None, Order::Ascending)
+                    .take(10)
+                    .collect::<StdResult<_>>()?;
+                for (addr, stake) in stakes.iter() {
+                    STAKES.save(deps.storage, addr, &(stake + Uint128::one()))?;
+                }
+                Ok(Response::new())
+            }
+            "#
+        );
+        let findings = analyze(&source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("re-iterated with writes"));
+    }
+
+    #[test]
+    fn test_small_limit_read_only_not_flagged() {
+        let source = format!(
+            "{PREAMBLE}{}",
+            r#"
+            fn execute_distribute(deps: DepsMut) -> StdResult<Response> {
+                let stakes: Vec<_> = STAKES
+                    .range(deps.storage, None, None, Order::Ascending)
+                    .take(30)
+                    .collect::<StdResult<_>>()?;
+                Ok(Response::new().add_attribute("count", stakes.len().to_string()))
+            }
+            "#
+        );
+        assert!(analyze(&source).is_empty());
+    }
+
+    #[test]
+    fn test_query_paths_not_flagged() {
+        let source = r#"
+            const STAKES: Map<&Addr, Uint128> = Map::new("stakes");
+
+            #[entry_point]
+            pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+                let all: Vec<_> = STAKES
+                    .range(deps.storage, None, None, Order::Ascending)
+                    .take(5000)
+                    .collect::<StdResult<_>>()?;
+                to_json_binary(&all)
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+}
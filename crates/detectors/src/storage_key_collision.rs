@@ -53,11 +53,13 @@ impl Detector for StorageKeyCollision {
                         start_col: item.span.start_col,
                         end_col: item.span.end_col,
                         snippet: None,
+                        byte_range: None,
                     }],
                     recommendation: Some(
                         "Use unique storage key strings for each state item.".to_string(),
                     ),
                     fix: None,
+                    ..Default::default()
                 });
             } else {
                 seen.insert(key, &item.name);
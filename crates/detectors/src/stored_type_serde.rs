@@ -0,0 +1,316 @@
+use std::collections::HashSet;
+
+use cosmwasm_guard::ast::{utils, SourceSpan};
+use cosmwasm_guard::detector::{AnalysisContext, Detector};
+use cosmwasm_guard::finding::*;
+use syn::visit::Visit;
+
+/// Checks serde attributes of types kept in storage: missing
+/// `Serialize`/`Deserialize` derives, `#[serde(skip)]` fields that are lost on
+/// every save/load round-trip, and `#[serde(untagged)]` enums whose variants
+/// can deserialize ambiguously.
+pub struct StoredTypeSerde;
+
+/// Field options that drop the value in at least one direction
+const LOSSY_SERDE_OPTIONS: &[&str] = &["skip", "skip_serializing", "skip_deserializing"];
+
+/// A stored enum declared in the crate
+struct StoredEnum {
+    name: String,
+    derives: Vec<String>,
+    untagged: bool,
+    span: SourceSpan,
+}
+
+/// Collects stored enums and types with hand-written serde impls
+struct SerdeItemCollector<'a> {
+    ctx: &'a AnalysisContext<'a>,
+    stored_types: &'a HashSet<String>,
+    enums: Vec<StoredEnum>,
+    manual_impls: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for SerdeItemCollector<'_> {
+    fn visit_item_enum(&mut self, node: &'ast syn::ItemEnum) {
+        let name = node.ident.to_string();
+        let span = self.ctx.span_of(&node.ident);
+        if let (true, Some(span)) = (self.stored_types.contains(&name), span) {
+            self.enums.push(StoredEnum {
+                name,
+                derives: utils::extract_derives(&node.attrs),
+                untagged: utils::extract_serde_attrs(&node.attrs)
+                    .iter()
+                    .any(|o| o == "untagged"),
+                span,
+            });
+        }
+        syn::visit::visit_item_enum(self, node);
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+        let is_serde_impl = node.trait_.as_ref().is_some_and(|(_, path, _)| {
+            path.segments
+                .last()
+                .is_some_and(|s| s.ident == "Serialize" || s.ident == "Deserialize")
+        });
+        if let (true, syn::Type::Path(self_ty)) = (is_serde_impl, node.self_ty.as_ref()) {
+            if let Some(last) = self_ty.path.segments.last() {
+                self.manual_impls.insert(last.ident.to_string());
+            }
+        }
+        syn::visit::visit_item_impl(self, node);
+    }
+}
+
+impl StoredTypeSerde {
+    fn finding(
+        &self,
+        title: String,
+        description: String,
+        recommendation: &str,
+        severity: Severity,
+        confidence: Confidence,
+        span: &SourceSpan,
+    ) -> Finding {
+        Finding {
+            detector_name: self.name().to_string(),
+            title,
+            description,
+            severity,
+            confidence,
+            locations: vec![SourceLocation {
+                file: span.file.clone(),
+                start_line: span.start_line,
+                end_line: span.start_line,
+                start_col: span.start_col,
+                end_col: span.start_col,
+                snippet: None,
+                byte_range: None,
+            }],
+            recommendation: Some(recommendation.to_string()),
+            fix: None,
+            ..Default::default()
+        }
+    }
+}
+
+/// Serde derives a stored type lacks, unless it implements them by hand
+fn missing_derives(
+    derives: &[String],
+    name: &str,
+    manual_impls: &HashSet<String>,
+) -> Vec<&'static str> {
+    if manual_impls.contains(name) {
+        return Vec::new();
+    }
+    ["Serialize", "Deserialize"]
+        .into_iter()
+        .filter(|d| !derives.iter().any(|have| have == d))
+        .collect()
+}
+
+impl Detector for StoredTypeSerde {
+    fn name(&self) -> &str {
+        "stored-type-serde"
+    }
+
+    fn description(&self) -> &str {
+        "Detects stored types with missing serde derives, skipped fields, or untagged enums"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let stored_types = ctx.contract.stored_type_names();
+        let mut collector = SerdeItemCollector {
+            ctx,
+            stored_types: &stored_types,
+            enums: Vec::new(),
+            manual_impls: HashSet::new(),
+        };
+        for (_, ast) in ctx.raw_asts() {
+            collector.visit_file(ast);
+        }
+
+        let mut findings = Vec::new();
+        for def in &ctx.contract.structs {
+            if !stored_types.contains(&def.name) {
+                continue;
+            }
+
+            let missing = missing_derives(&def.derives, &def.name, &collector.manual_impls);
+            if !missing.is_empty() {
+                findings.push(self.finding(
+                    format!(
+                        "Stored type `{}` does not derive {}",
+                        def.name,
+                        missing.join("/")
+                    ),
+                    format!(
+                        "`{}` is persisted in storage but derives neither `#[cw_serde]` nor \
+                         `{}`. Storage encoding then depends on a hand-written or foreign impl.",
+                        def.name,
+                        missing.join(", ")
+                    ),
+                    "Annotate stored types with `#[cw_serde]`, or derive `Serialize, Deserialize`.",
+                    Severity::Low,
+                    Confidence::Low,
+                    &def.span,
+                ));
+            }
+
+            for field in &def.fields {
+                let Some(option) = field
+                    .serde
+                    .iter()
+                    .find(|o| LOSSY_SERDE_OPTIONS.contains(&o.as_str()))
+                else {
+                    continue;
+                };
+                findings.push(self.finding(
+                    format!(
+                        "Field `{}.{}` is `#[serde({option})]`",
+                        def.name, field.name
+                    ),
+                    format!(
+                        "`{}` is persisted in storage, but field `{}` is marked \
+                         `#[serde({option})]`. Its value is silently replaced by the default \
+                         on every save/load round-trip.",
+                        def.name, field.name
+                    ),
+                    "Store the field, or move transient data out of the stored type.",
+                    Severity::Medium,
+                    Confidence::High,
+                    &field.span,
+                ));
+            }
+        }
+
+        for stored in &collector.enums {
+            let missing = missing_derives(&stored.derives, &stored.name, &collector.manual_impls);
+            if !missing.is_empty() {
+                findings.push(self.finding(
+                    format!(
+                        "Stored type `{}` does not derive {}",
+                        stored.name,
+                        missing.join("/")
+                    ),
+                    format!(
+                        "`{}` is persisted in storage but derives neither `#[cw_serde]` nor \
+                         `{}`.",
+                        stored.name,
+                        missing.join(", ")
+                    ),
+                    "Annotate stored types with `#[cw_serde]`, or derive `Serialize, Deserialize`.",
+                    Severity::Low,
+                    Confidence::Low,
+                    &stored.span,
+                ));
+            }
+            if stored.untagged {
+                findings.push(self.finding(
+                    format!("Untagged enum `{}` kept in storage", stored.name),
+                    format!(
+                        "`{}` is `#[serde(untagged)]`. Deserialization picks the first variant \
+                         whose shape matches, so variants with compatible fields load as the \
+                         wrong variant.",
+                        stored.name
+                    ),
+                    "Use an externally or internally tagged representation for stored enums.",
+                    Severity::Low,
+                    Confidence::Medium,
+                    &stored.span,
+                ));
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        StoredTypeSerde.detect(&ctx)
+    }
+
+    #[test]
+    fn test_detects_skipped_field_in_nested_stored_type() {
+        let source = r#"
+            #[cw_serde]
+            pub struct Config {
+                pub owner: Addr,
+                pub limits: Limits,
+            }
+            #[derive(Serialize, Deserialize)]
+            pub struct Limits {
+                pub max: Uint128,
+                #[serde(skip)]
+                pub cached_total: Uint128,
+            }
+            pub const CONFIG: Item<Config> = Item::new("config");
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("Limits.cached_total"));
+        assert_eq!(findings[0].severity, Severity::Medium);
+    }
+
+    #[test]
+    fn test_detects_untagged_enum_and_missing_derives() {
+        let source = r#"
+            #[derive(Serialize, Deserialize)]
+            #[serde(untagged)]
+            pub enum Status {
+                Active { since: u64 },
+                Paused { since: u64 },
+            }
+            #[derive(Clone)]
+            pub struct Record {
+                pub status: Status,
+            }
+            pub const RECORDS: Map<&str, Record> = Map::new("records");
+        "#;
+        let findings = analyze(source);
+        let titles: Vec<&str> = findings.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(findings.len(), 2, "{titles:?}");
+        assert!(titles
+            .iter()
+            .any(|t| t.contains("`Record` does not derive")));
+        assert!(titles.iter().any(|t| t.contains("Untagged enum `Status`")));
+    }
+
+    #[test]
+    fn test_ignores_unstored_types_and_manual_impls() {
+        let source = r#"
+            pub struct Raw(pub Vec<u8>);
+            impl Serialize for Raw {}
+            impl<'de> Deserialize<'de> for Raw {}
+            #[derive(Serialize)]
+            pub struct QueryResponse {
+                #[serde(skip)]
+                pub debug: String,
+            }
+            pub const DATA: Item<Raw> = Item::new("data");
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+}
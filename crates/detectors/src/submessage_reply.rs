@@ -1,4 +1,4 @@
-use cosmwasm_guard::ast::EntryPointKind;
+use cosmwasm_guard::ast::{EntryPointKind, SourceSpan};
 use cosmwasm_guard::detector::{AnalysisContext, Detector};
 use cosmwasm_guard::finding::*;
 use syn::visit::Visit;
@@ -88,6 +88,7 @@ impl Detector for SubmessageReplyUnvalidated {
                         start_col: ep.span.start_col,
                         end_col: ep.span.end_col,
                         snippet: None,
+                        byte_range: None,
                     }],
                     recommendation: Some(
                         "Add `match msg.id { REPLY_ID => ..., id => Err(...) }` \
@@ -95,6 +96,243 @@ impl Detector for SubmessageReplyUnvalidated {
                             .to_string(),
                     ),
                     fix: None,
+                    ..Default::default()
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+/// Detects reply handlers that unwrap the submessage result or reply data, or
+/// match on `SubMsgResult` without an error branch.
+pub struct ReplyResultUnchecked;
+
+/// Helpers from `cw-utils` that decode reply data and can fail
+const PARSE_REPLY_FNS: &[&str] = &["parse_reply_instantiate_data", "parse_reply_execute_data"];
+
+/// Methods that panic or silently discard the error case
+const PANICKING_METHODS: &[&str] = &["unwrap", "expect", "unwrap_or_default"];
+
+enum ReplyIssue {
+    /// `msg.result.unwrap()` and friends
+    ResultUnwrapped,
+    /// `parse_reply_*_data(..).unwrap()`
+    ParseUnwrapped(String),
+    /// `match msg.result { SubMsgResult::Ok(..) => .. }` with no `Err` or catch-all arm
+    MissingErrBranch,
+}
+
+/// Collects unchecked uses of the reply result in a function body
+struct ReplyResultSearcher<'c> {
+    ctx: &'c AnalysisContext<'c>,
+    issues: Vec<(usize, usize, ReplyIssue)>,
+}
+
+/// Whether `expr` is the reply's `result` field, possibly via `into_result()`
+fn reads_reply_result(expr: &syn::Expr) -> bool {
+    match expr {
+        syn::Expr::Field(field) => {
+            matches!(&field.member, syn::Member::Named(ident) if ident == "result")
+        }
+        syn::Expr::MethodCall(call) => {
+            matches!(
+                call.method.to_string().as_str(),
+                "into_result" | "as_ref" | "clone"
+            ) && reads_reply_result(&call.receiver)
+        }
+        syn::Expr::Paren(paren) => reads_reply_result(&paren.expr),
+        syn::Expr::Reference(reference) => reads_reply_result(&reference.expr),
+        _ => false,
+    }
+}
+
+/// Name of the `parse_reply_*_data` helper `expr` calls, if any
+fn parse_reply_call(expr: &syn::Expr) -> Option<String> {
+    let syn::Expr::Call(call) = expr else {
+        return None;
+    };
+    let syn::Expr::Path(path) = call.func.as_ref() else {
+        return None;
+    };
+    let name = path.path.segments.last()?.ident.to_string();
+    PARSE_REPLY_FNS.contains(&name.as_str()).then_some(name)
+}
+
+/// Last path segment of a variant pattern like `SubMsgResult::Ok(..)`
+fn variant_name(pat: &syn::Pat) -> Option<String> {
+    let path = match pat {
+        syn::Pat::TupleStruct(p) => &p.path,
+        syn::Pat::Struct(p) => &p.path,
+        syn::Pat::Path(p) => &p.path,
+        _ => return None,
+    };
+    path.segments.last().map(|s| s.ident.to_string())
+}
+
+/// Whether a match arm pattern covers the error case
+fn handles_error(pat: &syn::Pat) -> bool {
+    match pat {
+        syn::Pat::Wild(_) | syn::Pat::Ident(_) => true,
+        syn::Pat::Or(or) => or.cases.iter().any(handles_error),
+        _ => variant_name(pat).is_some_and(|v| v == "Err"),
+    }
+}
+
+impl ReplyResultSearcher<'_> {
+    fn report(&mut self, span: Option<SourceSpan>, issue: ReplyIssue) {
+        if let Some(span) = span {
+            self.issues.push((span.start_line, span.start_col, issue));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for ReplyResultSearcher<'_> {
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        let method = node.method.to_string();
+        if PANICKING_METHODS.contains(&method.as_str()) {
+            let issue = if reads_reply_result(&node.receiver) {
+                Some(ReplyIssue::ResultUnwrapped)
+            } else {
+                parse_reply_call(&node.receiver).map(ReplyIssue::ParseUnwrapped)
+            };
+            if let Some(issue) = issue {
+                self.report(self.ctx.span_of(&node.method), issue);
+            }
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_expr_match(&mut self, node: &'ast syn::ExprMatch) {
+        if reads_reply_result(&node.expr)
+            && node
+                .arms
+                .iter()
+                .any(|arm| variant_name(&arm.pat).is_some_and(|v| v == "Ok"))
+            && !node.arms.iter().any(|arm| handles_error(&arm.pat))
+        {
+            self.report(
+                self.ctx.span_of(&node.match_token),
+                ReplyIssue::MissingErrBranch,
+            );
+        }
+        syn::visit::visit_expr_match(self, node);
+    }
+
+    fn visit_expr_if(&mut self, node: &'ast syn::ExprIf) {
+        // `if let SubMsgResult::Ok(..) = msg.result { .. }` with no else drops errors
+        if let syn::Expr::Let(let_expr) = node.cond.as_ref() {
+            if node.else_branch.is_none()
+                && reads_reply_result(&let_expr.expr)
+                && variant_name(&let_expr.pat).is_some_and(|v| v == "Ok")
+            {
+                self.report(
+                    self.ctx.span_of(&node.if_token),
+                    ReplyIssue::MissingErrBranch,
+                );
+            }
+        }
+        syn::visit::visit_expr_if(self, node);
+    }
+}
+
+impl Detector for ReplyResultUnchecked {
+    fn name(&self) -> &str {
+        "reply-result-unchecked"
+    }
+
+    fn description(&self) -> &str {
+        "Detects reply handlers that unwrap SubMsgResult or reply data, or ignore the error branch"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let roots: Vec<&str> = ctx
+            .contract
+            .entry_points
+            .iter()
+            .filter(|ep| ep.kind == EntryPointKind::Reply)
+            .map(|ep| ep.name.as_str())
+            .collect();
+        if roots.is_empty() {
+            return Vec::new();
+        }
+        // Reply handlers commonly dispatch to per-id helpers
+        let reachable = ctx.call_graph().reachable_from(&roots);
+
+        let mut findings = Vec::new();
+        for func in &ctx.contract.functions {
+            if !reachable.contains(&func.name) {
+                continue;
+            }
+            let Some(body) = &func.body else { continue };
+
+            let mut searcher = ReplyResultSearcher {
+                ctx,
+                issues: Vec::new(),
+            };
+            syn::visit::visit_block(&mut searcher, body);
+
+            for (line, col, issue) in searcher.issues {
+                let (title, description, recommendation) = match issue {
+                    ReplyIssue::ResultUnwrapped => (
+                        format!("Submessage result unwrapped in `{}`", func.name),
+                        format!(
+                            "`{}` unwraps the `SubMsgResult` of a reply. A failed \
+                             submessage then aborts the whole transaction with a panic \
+                             instead of being handled or reported as a contract error.",
+                            func.name
+                        ),
+                        "Match on the result and handle `SubMsgResult::Err`, or use \
+                         `msg.result.into_result().map_err(..)?` to propagate a typed error.",
+                    ),
+                    ReplyIssue::ParseUnwrapped(helper) => (
+                        format!("Unchecked `{helper}` in `{}`", func.name),
+                        format!(
+                            "`{}` unwraps the result of `{helper}`. Malformed or missing \
+                             reply data panics instead of returning an error.",
+                            func.name
+                        ),
+                        "Propagate the parse error with `?` or map it into a contract error.",
+                    ),
+                    ReplyIssue::MissingErrBranch => (
+                        format!("SubMsgResult error branch not handled in `{}`", func.name),
+                        format!(
+                            "`{}` only handles `SubMsgResult::Ok`. An `Err` reply falls \
+                             through silently, so the contract can continue as if the \
+                             submessage had succeeded.",
+                            func.name
+                        ),
+                        "Add a `SubMsgResult::Err(err)` arm that returns an error or \
+                         explicitly handles the failure.",
+                    ),
+                };
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title,
+                    description,
+                    severity: Severity::Medium,
+                    confidence: Confidence::Medium,
+                    locations: vec![SourceLocation {
+                        file: func.span.file.clone(),
+                        start_line: line,
+                        end_line: line,
+                        start_col: col,
+                        end_col: col,
+                        snippet: None,
+                        byte_range: None,
+                    }],
+                    recommendation: Some(recommendation.to_string()),
+                    fix: None,
+                    ..Default::default()
                 });
             }
         }
@@ -161,4 +399,67 @@ mod tests {
         let findings = analyze(source);
         assert!(findings.is_empty());
     }
+
+    fn analyze_result(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        ReplyResultUnchecked.detect(&ctx)
+    }
+
+    #[test]
+    fn test_detects_unwrapped_result_and_reply_data() {
+        let source = r#"
+            #[entry_point]
+            pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+                match msg.id {
+                    INSTANTIATE_ID => handle_instantiate(deps, msg),
+                    _ => Err(StdError::generic_err("unknown reply")),
+                }
+            }
+
+            fn handle_instantiate(deps: DepsMut, msg: Reply) -> StdResult<Response> {
+                let res = msg.result.clone().unwrap();
+                let data = parse_reply_instantiate_data(msg).unwrap();
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze_result(source);
+        assert_eq!(findings.len(), 2);
+        assert!(findings[0].title.contains("result unwrapped"));
+        assert!(findings[1].title.contains("parse_reply_instantiate_data"));
+    }
+
+    #[test]
+    fn test_detects_missing_err_branch() {
+        let source = r#"
+            #[entry_point]
+            pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+                match msg.result {
+                    SubMsgResult::Ok(res) => Ok(Response::new()),
+                }
+            }
+        "#;
+        let findings = analyze_result(source);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].detector_name, "reply-result-unchecked");
+    }
+
+    #[test]
+    fn test_no_finding_when_errors_handled() {
+        let source = r#"
+            #[entry_point]
+            pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+                let data = parse_reply_execute_data(msg.clone())?;
+                match msg.result {
+                    SubMsgResult::Ok(res) => Ok(Response::new()),
+                    SubMsgResult::Err(err) => Err(StdError::generic_err(err)),
+                }
+            }
+        "#;
+        assert!(analyze_result(source).is_empty());
+    }
 }
@@ -0,0 +1,355 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use cosmwasm_guard::ast::utils::is_test_attr;
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use syn::visit::Visit;
+
+/// Detects `cosmwasm_std::testing` helpers and `println!`/`dbg!` in code that
+/// ships in the wasm. Mocks compile into the contract, bloat it and behave
+/// nothing like the chain (`MockApi` accepts addresses no chain would), and
+/// printing does nothing on chain but costs gas and code size.
+pub struct TestCodeInContract;
+
+/// Helpers of `cosmwasm_std::testing` that only make sense in tests
+const MOCK_NAMES: &[&str] = &[
+    "mock_dependencies",
+    "mock_dependencies_with_balance",
+    "mock_dependencies_with_balances",
+    "mock_env",
+    "mock_info",
+    "MockApi",
+    "MockStorage",
+    "MockQuerier",
+];
+
+const PRINT_MACROS: &[&str] = &["println", "print", "eprintln", "eprint", "dbg"];
+
+/// What a hit is, for its title
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Hit {
+    Testing,
+    Mock(String),
+    Print(String),
+}
+
+/// Test-only items outside `#[cfg(test)]` code, by file and line
+struct HitCollector<'c> {
+    ctx: &'c AnalysisContext<'c>,
+    file: PathBuf,
+    hits: BTreeSet<(PathBuf, usize, usize, Hit)>,
+    /// `(directory, name)` of `#[cfg(test)] mod name;` declarations, whose
+    /// files are test code
+    test_mods: Vec<(PathBuf, String)>,
+}
+
+impl HitCollector<'_> {
+    fn record(&mut self, node: &impl quote::ToTokens, hit: Hit) {
+        if let Some(span) = self.ctx.span_of(node) {
+            self.hits
+                .insert((self.file.clone(), span.start_line, span.start_col, hit));
+        }
+    }
+
+    /// Whether `path` names `cosmwasm_std::testing`
+    fn is_testing_path<'a>(segments: impl Iterator<Item = &'a syn::Ident>) -> bool {
+        let names: Vec<String> = segments.map(|s| s.to_string()).collect();
+        names
+            .windows(2)
+            .any(|w| w[0] == "cosmwasm_std" && w[1] == "testing")
+    }
+}
+
+/// Flattened paths of a `use` tree
+fn use_paths<'a>(
+    tree: &'a syn::UseTree,
+    prefix: &mut Vec<&'a syn::Ident>,
+    out: &mut Vec<Vec<&'a syn::Ident>>,
+) {
+    match tree {
+        syn::UseTree::Path(p) => {
+            prefix.push(&p.ident);
+            use_paths(&p.tree, prefix, out);
+            prefix.pop();
+        }
+        syn::UseTree::Name(n) => {
+            let mut path = prefix.clone();
+            path.push(&n.ident);
+            out.push(path);
+        }
+        syn::UseTree::Rename(r) => {
+            let mut path = prefix.clone();
+            path.push(&r.ident);
+            out.push(path);
+        }
+        syn::UseTree::Glob(_) => out.push(prefix.clone()),
+        syn::UseTree::Group(g) => {
+            for item in &g.items {
+                use_paths(item, prefix, out);
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for HitCollector<'_> {
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        if node.attrs.iter().any(is_test_attr) {
+            if node.content.is_none() {
+                let dir = module_dir(&self.file);
+                self.test_mods.push((dir, node.ident.to_string()));
+            }
+            return;
+        }
+        syn::visit::visit_item_mod(self, node);
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        if !node.attrs.iter().any(is_test_attr) {
+            syn::visit::visit_item_fn(self, node);
+        }
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+        if !node.attrs.iter().any(is_test_attr) {
+            syn::visit::visit_item_impl(self, node);
+        }
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        if !node.attrs.iter().any(is_test_attr) {
+            syn::visit::visit_impl_item_fn(self, node);
+        }
+    }
+
+    fn visit_item_use(&mut self, node: &'ast syn::ItemUse) {
+        if node.attrs.iter().any(is_test_attr) {
+            return;
+        }
+        let mut paths = Vec::new();
+        use_paths(&node.tree, &mut Vec::new(), &mut paths);
+        if paths
+            .iter()
+            .any(|path| Self::is_testing_path(path.iter().copied()))
+        {
+            self.record(node, Hit::Testing);
+        }
+    }
+
+    fn visit_path(&mut self, node: &'ast syn::Path) {
+        if let Some(last) = node.segments.last() {
+            let name = last.ident.to_string();
+            if Self::is_testing_path(node.segments.iter().map(|s| &s.ident)) {
+                self.record(node, Hit::Testing);
+            } else if MOCK_NAMES.contains(&name.as_str()) {
+                self.record(node, Hit::Mock(name));
+            }
+        }
+        syn::visit::visit_path(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        if let Some(last) = node.path.segments.last() {
+            let name = last.ident.to_string();
+            if PRINT_MACROS.contains(&name.as_str()) {
+                self.record(node, Hit::Print(name));
+            }
+        }
+    }
+}
+
+/// Directory holding the files of the modules `file` declares
+fn module_dir(file: &Path) -> PathBuf {
+    let parent = file.parent().unwrap_or(Path::new(""));
+    match file.file_stem().and_then(|s| s.to_str()) {
+        Some("lib" | "main" | "mod") | None => parent.to_path_buf(),
+        Some(stem) => parent.join(stem),
+    }
+}
+
+/// Files compiled only for tests: in the crate's `tests`, `benches` or
+/// `examples` directory, declared by a `#[cfg(test)] mod`, or gated by
+/// `#![cfg(test)]`
+fn is_test_file(
+    file: &Path,
+    crate_path: &Path,
+    ast: &syn::File,
+    test_mods: &[(PathBuf, String)],
+) -> bool {
+    let relative = file.strip_prefix(crate_path).unwrap_or(file);
+    relative.components().any(|c| {
+        matches!(
+            c.as_os_str().to_str(),
+            Some("tests" | "benches" | "examples")
+        )
+    }) || ast.attrs.iter().any(is_test_attr)
+        || test_mods.iter().any(|(dir, name)| {
+            file == dir.join(format!("{name}.rs")) || file.starts_with(dir.join(name))
+        })
+}
+
+impl Detector for TestCodeInContract {
+    fn name(&self) -> &str {
+        "test-code-in-contract"
+    }
+
+    fn description(&self) -> &str {
+        "Detects cosmwasm_std::testing mocks and println!/dbg! outside test code"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Low
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::High
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let mut collector = HitCollector {
+            ctx,
+            file: PathBuf::new(),
+            hits: BTreeSet::new(),
+            test_mods: Vec::new(),
+        };
+        let mut per_file = Vec::new();
+        for (file, ast) in ctx.raw_asts() {
+            collector.file = file.clone();
+            collector.visit_file(ast);
+            per_file.push((file, ast, std::mem::take(&mut collector.hits)));
+        }
+
+        let mut findings = Vec::new();
+        for (file, ast, hits) in per_file {
+            if is_test_file(file, &ctx.contract.crate_path, ast, &collector.test_mods) {
+                continue;
+            }
+            // One finding per line: `use` and call of the same mock overlap
+            let mut lines = BTreeSet::new();
+            for (file, line, col, hit) in hits {
+                if !lines.insert(line) {
+                    continue;
+                }
+                let (title, description, recommendation) = match &hit {
+                    Hit::Testing => (
+                        "`cosmwasm_std::testing` used outside test code".to_string(),
+                        "Contract code uses `cosmwasm_std::testing`. The mocks compile into \
+                         the wasm, bloat it, and behave nothing like the chain: `MockApi` \
+                         validates addresses no chain would accept."
+                            .to_string(),
+                        "Move the code into a `#[cfg(test)]` module, or gate the import with \
+                         `#[cfg(test)]`.",
+                    ),
+                    Hit::Mock(name) => (
+                        format!("Test helper `{name}` used outside test code"),
+                        format!(
+                            "`{name}` from `cosmwasm_std::testing` is used in code that ships \
+                             in the wasm. Mocks bloat the contract and behave nothing like \
+                             the chain's `Deps`, `Env` and `MessageInfo`."
+                        ),
+                        "Move the code into a `#[cfg(test)]` module, or take the real \
+                         `Deps`/`Env`/`MessageInfo` from the entry point.",
+                    ),
+                    Hit::Print(name) => (
+                        format!("`{name}!` in contract code"),
+                        format!(
+                            "`{name}!` outside test code does nothing on chain, but still costs \
+                             gas and code size, and often leaks debugging left in by mistake."
+                        ),
+                        "Remove it, or use `deps.api.debug` for output that only appears in \
+                         local simulations.",
+                    ),
+                };
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title,
+                    description,
+                    severity: Severity::Low,
+                    confidence: Confidence::High,
+                    locations: vec![SourceLocation {
+                        file,
+                        start_line: line,
+                        end_line: line,
+                        start_col: col,
+                        end_col: col,
+                        snippet: None,
+                        byte_range: None,
+                    }],
+                    recommendation: Some(recommendation.to_string()),
+                    fix: None,
+                    ..Default::default()
+                });
+            }
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+
+    fn analyze(lib: &str, module: (&str, &str)) -> Vec<Finding> {
+        let mut contract =
+            ContractVisitor::extract(PathBuf::from("src/lib.rs"), parse_source(lib).unwrap());
+        contract
+            .raw_asts
+            .push((PathBuf::from(module.0), parse_source(module.1).unwrap()));
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([
+            (PathBuf::from("src/lib.rs"), lib.to_string()),
+            (PathBuf::from(module.0), module.1.to_string()),
+        ]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        TestCodeInContract.detect(&ctx)
+    }
+
+    #[test]
+    fn test_flags_mocks_and_prints_outside_tests() {
+        let lib = r#"
+use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+#[cfg(test)]
+mod multitest;
+
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    let env = mock_env();
+    dbg!(&msg);
+    Ok(Response::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn works() {
+        let deps = mock_dependencies();
+        println!("{:?}", deps);
+    }
+}
+"#;
+        let multitest =
+            "use cosmwasm_std::testing::MockApi;\nfn app() { let api = MockApi::default(); }";
+        let findings = analyze(lib, ("src/multitest.rs", multitest));
+        let titles: Vec<(&str, usize)> = findings
+            .iter()
+            .map(|f| (f.title.as_str(), f.locations[0].start_line))
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                ("`cosmwasm_std::testing` used outside test code", 2),
+                ("Test helper `mock_env` used outside test code", 8),
+                ("`dbg!` in contract code", 9),
+            ]
+        );
+    }
+}
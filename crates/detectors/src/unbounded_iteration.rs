@@ -9,7 +9,8 @@ use syn::visit::Visit;
 pub struct UnboundedIteration;
 
 /// Visitor that finds .range() calls and checks for .take() in the method chain
-struct RangeCallSearcher {
+struct RangeCallSearcher<'c> {
+    ctx: &'c AnalysisContext<'c>,
     unbounded_ranges: Vec<UnboundedRange>,
     file_path: std::path::PathBuf,
     /// Known storage Map/IndexedMap names to qualify .range() calls
@@ -21,7 +22,7 @@ struct UnboundedRange {
     col: usize,
 }
 
-impl<'ast> Visit<'ast> for RangeCallSearcher {
+impl<'ast> Visit<'ast> for RangeCallSearcher<'_> {
     fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
         let method = node.method.to_string();
 
@@ -40,11 +41,12 @@ impl<'ast> Visit<'ast> for RangeCallSearcher {
                     .is_some_and(|name| self.storage_map_names.contains(name));
 
                 if is_storage_map {
-                    let span = node.method.span();
-                    self.unbounded_ranges.push(UnboundedRange {
-                        line: span.start().line,
-                        col: span.start().column,
-                    });
+                    if let Some(span) = self.ctx.span_of(&node.method) {
+                        self.unbounded_ranges.push(UnboundedRange {
+                            line: span.start_line,
+                            col: span.start_col,
+                        });
+                    }
                 }
             }
         }
@@ -112,12 +114,18 @@ impl Detector for UnboundedIteration {
             .contract
             .state_items
             .iter()
-            .filter(|s| matches!(s.storage_type, StorageType::Map | StorageType::IndexedMap))
+            .filter(|s| {
+                matches!(
+                    s.storage_type,
+                    StorageType::Map | StorageType::IndexedMap | StorageType::SnapshotMap
+                )
+            })
             .map(|s| s.name.clone())
             .collect();
 
         for (path, ast) in ctx.raw_asts() {
             let mut searcher = RangeCallSearcher {
+                ctx,
                 unbounded_ranges: Vec::new(),
                 file_path: path.clone(),
                 storage_map_names: storage_map_names.clone(),
@@ -142,6 +150,7 @@ impl Detector for UnboundedIteration {
                         start_col: range_call.col,
                         end_col: range_call.col,
                         snippet: None,
+                        byte_range: None,
                     }],
                     recommendation: Some(
                         "Add `.take(limit)` after `.range()` to bound iteration, e.g.: \
@@ -149,6 +158,7 @@ impl Detector for UnboundedIteration {
                             .to_string(),
                     ),
                     fix: None,
+                    ..Default::default()
                 });
             }
         }
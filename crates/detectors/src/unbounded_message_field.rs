@@ -0,0 +1,440 @@
+use std::collections::{HashMap, HashSet};
+
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::{instruction_def, BinaryOp, FunctionIr, Instruction, Operand, SsaVar};
+use syn::visit::Visit;
+
+/// Flags `Vec<_>`/`String` message fields that are looped over or stored without
+/// any explicit length check. Complements `unbounded-message-loop` at the API
+/// level: every such field should have a documented maximum size.
+pub struct UnboundedMessageField;
+
+/// How an unbounded field is used
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldUse {
+    Looped,
+    Stored,
+}
+
+/// A `Vec`/`String` field of a message type
+struct MsgField {
+    message: String,
+    variant: Option<String>,
+    name: String,
+    type_name: String,
+    line: usize,
+    col: usize,
+}
+
+impl MsgField {
+    /// `Variant.field` (or just `field` for struct messages), as used in the allowlist
+    fn qualified_name(&self) -> String {
+        match &self.variant {
+            Some(v) => format!("{}.{}", v, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Unsized collection types whose length the sender controls
+fn is_unbounded_type(ty: &syn::Type) -> Option<String> {
+    let syn::Type::Path(tp) = ty else {
+        return None;
+    };
+    let last = tp.path.segments.last()?;
+    match last.ident.to_string().as_str() {
+        "Vec" | "String" => Some(last.ident.to_string()),
+        "Option" => {
+            let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+                return None;
+            };
+            args.args.iter().find_map(|arg| match arg {
+                syn::GenericArgument::Type(inner) => is_unbounded_type(inner),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Visitor collecting `Vec`/`String` fields of sender-controlled message types.
+/// Query messages are skipped: queries are bounded by the node's query gas limit.
+struct MessageFieldCollector<'c> {
+    ctx: &'c AnalysisContext<'c>,
+    fields: Vec<MsgField>,
+}
+
+impl MessageFieldCollector<'_> {
+    fn is_message_type(ident: &syn::Ident) -> bool {
+        let name = ident.to_string();
+        (name.ends_with("Msg") || name.ends_with("Message")) && !name.contains("Query")
+    }
+
+    fn collect(
+        &mut self,
+        message: &syn::Ident,
+        variant: Option<&syn::Ident>,
+        fields: &syn::Fields,
+    ) {
+        let syn::Fields::Named(named) = fields else {
+            return;
+        };
+        for field in &named.named {
+            let Some(ident) = &field.ident else { continue };
+            let Some(type_name) = is_unbounded_type(&field.ty) else {
+                continue;
+            };
+            let Some(span) = self.ctx.span_of(ident) else {
+                continue;
+            };
+            self.fields.push(MsgField {
+                message: message.to_string(),
+                variant: variant.map(|v| v.to_string()),
+                name: ident.to_string(),
+                type_name,
+                line: span.start_line,
+                col: span.start_col,
+            });
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for MessageFieldCollector<'_> {
+    fn visit_item_struct(&mut self, node: &'ast syn::ItemStruct) {
+        if Self::is_message_type(&node.ident) {
+            self.collect(&node.ident, None, &node.fields);
+        }
+        syn::visit::visit_item_struct(self, node);
+    }
+
+    fn visit_item_enum(&mut self, node: &'ast syn::ItemEnum) {
+        if Self::is_message_type(&node.ident) {
+            for variant in &node.variants {
+                self.collect(&node.ident, Some(&variant.ident), &variant.fields);
+            }
+        }
+        syn::visit::visit_item_enum(self, node);
+    }
+}
+
+/// Name of the value an operand ultimately comes from, following copies,
+/// references, unwraps and method chains: `&msg.items.iter()` -> "items"
+fn root_name(operand: &Operand, defs: &HashMap<&SsaVar, &Instruction>) -> Option<String> {
+    let mut current = operand;
+    loop {
+        match current {
+            Operand::FieldAccess { field, .. } => return Some(field.clone()),
+            Operand::Literal(_) => return None,
+            Operand::Var(var) => match defs.get(var) {
+                Some(Instruction::Assign { value, .. })
+                | Some(Instruction::ResultUnwrap { value, .. })
+                | Some(Instruction::UnaryOp { operand: value, .. })
+                | Some(Instruction::MethodCall {
+                    receiver: value, ..
+                }) => current = value,
+                Some(_) => return None,
+                None => return Some(var.name.clone()),
+            },
+        }
+    }
+}
+
+/// Field names used as loop iterables or stored, and field names length-checked,
+/// across every function of the contract
+#[derive(Default)]
+struct FieldUsage {
+    uses: HashMap<String, FieldUse>,
+    length_checked: HashSet<String>,
+}
+
+impl FieldUsage {
+    fn record(&mut self, func: &FunctionIr) {
+        let defs: HashMap<&SsaVar, &Instruction> = func
+            .cfg
+            .blocks
+            .iter()
+            .flat_map(|b| &b.instructions)
+            .filter_map(|inst| instruction_def(inst).map(|d| (d, inst)))
+            .collect();
+
+        for lp in func.cfg.natural_loops() {
+            if let Some(Instruction::MethodCall {
+                receiver, method, ..
+            }) = func.cfg.blocks[lp.header].instructions.first()
+            {
+                if method == "next" {
+                    if let Some(name) = root_name(receiver, &defs) {
+                        self.uses.insert(name, FieldUse::Looped);
+                    }
+                }
+            }
+        }
+
+        let is_len_of = |operand: &Operand| -> Option<String> {
+            let Operand::Var(v) = operand else {
+                return None;
+            };
+            match defs.get(v) {
+                Some(Instruction::MethodCall {
+                    receiver, method, ..
+                }) if method == "len" || method == "is_empty" => root_name(receiver, &defs),
+                _ => None,
+            }
+        };
+
+        for inst in func.cfg.blocks.iter().flat_map(|b| &b.instructions) {
+            match inst {
+                Instruction::StorageStore { value, .. } => {
+                    for name in stored_names(value, &defs) {
+                        self.uses.entry(name).or_insert(FieldUse::Stored);
+                    }
+                }
+                Instruction::BinaryOp {
+                    op: BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge,
+                    left,
+                    right,
+                    ..
+                } => {
+                    self.length_checked.extend(is_len_of(left));
+                    self.length_checked.extend(is_len_of(right));
+                }
+                Instruction::Call { func, args, .. } => {
+                    let callee = func.rsplit("::").next().unwrap_or(func);
+                    if callee.starts_with("validate") || callee.starts_with("check") {
+                        self.length_checked
+                            .extend(args.iter().filter_map(|a| root_name(a, &defs)));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Names stored by a `save`: the value itself, or the arguments of a struct
+/// literal / constructor call building it
+fn stored_names(value: &Operand, defs: &HashMap<&SsaVar, &Instruction>) -> Vec<String> {
+    let mut current = value;
+    while let Operand::Var(v) = current {
+        match defs.get(v) {
+            Some(Instruction::UnaryOp { operand, .. }) => current = operand,
+            Some(Instruction::Call { args, .. }) => {
+                return args.iter().filter_map(|a| root_name(a, defs)).collect();
+            }
+            _ => break,
+        }
+    }
+    root_name(current, defs).into_iter().collect()
+}
+
+impl Detector for UnboundedMessageField {
+    fn name(&self) -> &str {
+        "unbounded-message-field"
+    }
+
+    fn description(&self) -> &str {
+        "Detects Vec/String message fields that are looped over or stored without a length check"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Low
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Low
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Experimental
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let allowlist: HashSet<String> = ctx
+            .config()
+            .detector_option::<Vec<String>>(self.name(), "allowlist")
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+
+        let mut usage = FieldUsage::default();
+        for func in &ctx.ir.functions {
+            usage.record(func);
+        }
+
+        let mut findings = Vec::new();
+        for (path, ast) in ctx.raw_asts() {
+            let mut collector = MessageFieldCollector {
+                ctx,
+                fields: Vec::new(),
+            };
+            collector.visit_file(ast);
+
+            for field in &collector.fields {
+                let Some(field_use) = usage.uses.get(&field.name) else {
+                    continue;
+                };
+                if usage.length_checked.contains(&field.name)
+                    || allowlist.contains(&field.name)
+                    || allowlist.contains(&field.qualified_name())
+                {
+                    continue;
+                }
+
+                let action = match field_use {
+                    FieldUse::Looped => "iterated over",
+                    FieldUse::Stored => "written to storage",
+                };
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title: format!(
+                        "`{}::{}` has no length bound",
+                        field.message,
+                        field.qualified_name()
+                    ),
+                    description: format!(
+                        "Field `{}` of `{}` is a `{}` that is {} but its length is never \
+                         checked. The sender decides its size, so gas use and state growth \
+                         are unbounded.",
+                        field.name, field.message, field.type_name, action
+                    ),
+                    severity: Severity::Low,
+                    confidence: Confidence::Low,
+                    locations: vec![SourceLocation {
+                        file: path.clone(),
+                        start_line: field.line,
+                        end_line: field.line,
+                        start_col: field.col,
+                        end_col: field.col + field.name.len(),
+                        snippet: None,
+                        byte_range: None,
+                    }],
+                    recommendation: Some(format!(
+                        "Enforce a maximum, e.g. `ensure!({}.len() <= MAX_LEN, ...)`, and \
+                         document it in the message schema. Intentionally unbounded fields \
+                         can be listed in `[detectors.unbounded-message-field] allowlist`.",
+                        field.name
+                    )),
+                    fix: None,
+                    ..Default::default()
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::config::Config;
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::path::PathBuf;
+
+    fn analyze_with(source: &str, config: &Config) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources).with_config(config);
+        UnboundedMessageField.detect(&ctx)
+    }
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        analyze_with(source, &Config::default())
+    }
+
+    const MESSAGES: &str = r#"
+        pub enum ExecuteMsg {
+            Register { names: Vec<String>, memo: String, label: String },
+        }
+
+        pub enum QueryMsg {
+            Lookup { names: Vec<String> },
+        }
+    "#;
+
+    #[test]
+    fn test_flags_looped_and_stored_fields() {
+        let source = format!(
+            "{MESSAGES}{}",
+            r#"
+            fn execute_register(deps: DepsMut, names: Vec<String>, memo: String) -> StdResult<Response> {
+                for name in names.iter() {
+                    NAMES.save(deps.storage, name, &true)?;
+                }
+                MEMO.save(deps.storage, &memo)?;
+                Ok(Response::new())
+            }
+            "#
+        );
+        let findings = analyze(&source);
+        let titles: Vec<&str> = findings.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(findings.len(), 2, "{titles:?}");
+        assert!(titles
+            .iter()
+            .any(|t| t.contains("ExecuteMsg::Register.names")));
+        assert!(titles
+            .iter()
+            .any(|t| t.contains("ExecuteMsg::Register.memo")));
+    }
+
+    #[test]
+    fn test_length_checked_fields_not_flagged() {
+        let source = format!(
+            "{MESSAGES}{}",
+            r#"
+            fn execute_register(deps: DepsMut, names: Vec<String>, memo: String) -> StdResult<Response> {
+                ensure!(names.len() <= 20, ContractError::TooMany {});
+                validate_memo(&memo)?;
+                for name in names.iter() {
+                    NAMES.save(deps.storage, name, &true)?;
+                }
+                MEMO.save(deps.storage, &memo)?;
+                Ok(Response::new())
+            }
+            "#
+        );
+        assert!(analyze(&source).is_empty());
+    }
+
+    #[test]
+    fn test_field_stored_via_struct_literal() {
+        let source = format!(
+            "{MESSAGES}{}",
+            r#"
+            fn execute_register(deps: DepsMut, msg: ExecuteMsg, label: String) -> StdResult<Response> {
+                CONFIG.save(deps.storage, &Config { label: label.clone(), owner: info.sender })?;
+                Ok(Response::new())
+            }
+            "#
+        );
+        let findings = analyze(&source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("label"));
+    }
+
+    #[test]
+    fn test_allowlist_suppresses_field() {
+        let source = format!(
+            "{MESSAGES}{}",
+            r#"
+            fn execute_register(deps: DepsMut, memo: String) -> StdResult<Response> {
+                MEMO.save(deps.storage, &memo)?;
+                Ok(Response::new())
+            }
+            "#
+        );
+        let config: Config = toml::from_str(
+            r#"
+            [detectors.unbounded-message-field]
+            allowlist = ["Register.memo"]
+            "#,
+        )
+        .unwrap();
+        assert!(analyze_with(&source, &config).is_empty());
+    }
+}
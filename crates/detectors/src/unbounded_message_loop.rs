@@ -0,0 +1,512 @@
+use std::collections::{HashMap, HashSet};
+
+use cosmwasm_guard::ast::{EntryPointKind, FunctionInfo};
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::{
+    helper_results, instruction_def, instruction_uses, operand_tainted, propagate_taint,
+    taint_path, BinaryOp, FunctionIr, Instruction, NaturalLoop, Operand, SsaVar,
+};
+use cosmwasm_guard::trace;
+
+/// Detects storage writes inside loops over collections taken from the message
+/// (e.g. `for item in msg.items { MAP.save(..) }`) with no length check before
+/// the loop. A sender can submit a huge list and make the contract perform an
+/// unbounded number of writes.
+pub struct UnboundedMessageLoop;
+
+/// Parameter types that come from the chain rather than from the sender
+const ENV_TYPES: &[&str] = &[
+    "Deps",
+    "DepsMut",
+    "Env",
+    "MessageInfo",
+    "Storage",
+    "Api",
+    "Querier",
+];
+
+fn is_env_type(type_name: &str) -> bool {
+    ENV_TYPES.iter().any(|t| type_name.contains(t))
+}
+
+/// Map every variable to the instruction defining it
+fn def_map(func: &FunctionIr) -> HashMap<&SsaVar, &Instruction> {
+    func.cfg
+        .blocks
+        .iter()
+        .flat_map(|b| &b.instructions)
+        .filter_map(|inst| instruction_def(inst).map(|d| (d, inst)))
+        .collect()
+}
+
+/// User-controlled starting points: non-environment parameters, plus names that
+/// are read but never defined, which are pattern bindings such as the fields of
+/// `ExecuteMsg::Batch { items }`. Results of `[taint] sources` helpers are
+/// added by the caller.
+fn taint_sources(
+    func: &FunctionIr,
+    info: Option<&FunctionInfo>,
+    defs: &HashMap<&SsaVar, &Instruction>,
+    state_items: &HashSet<&str>,
+) -> HashSet<SsaVar> {
+    let mut sources = HashSet::new();
+    for param in &func.params {
+        let env_like = info
+            .and_then(|i| i.params.iter().find(|p| p.name == param.name))
+            .is_some_and(|p| is_env_type(&p.type_name));
+        if !env_like {
+            sources.insert(param.clone());
+        }
+    }
+    for inst in func.cfg.blocks.iter().flat_map(|b| &b.instructions) {
+        for var in instruction_uses(inst) {
+            let is_binding = !defs.contains_key(var)
+                && !func.params.contains(var)
+                && !state_items.contains(var.name.as_str())
+                && var.name.starts_with(|c: char| c.is_ascii_lowercase());
+            if is_binding {
+                sources.insert(var.clone());
+            }
+        }
+    }
+    sources
+}
+
+/// The iterator a `for` loop header advances, if the loop was lowered from `for`
+fn loop_iterable<'a>(func: &'a FunctionIr, lp: &NaturalLoop) -> Option<&'a Operand> {
+    match func.cfg.blocks[lp.header].instructions.first()? {
+        Instruction::MethodCall {
+            receiver, method, ..
+        } if method == "next" => Some(receiver),
+        _ => None,
+    }
+}
+
+/// Walk back through adapter calls (`iter()`, `into_iter()`, ...) and copies to
+/// the collection variable. Returns `None` if a `.take(..)` bounds the iteration.
+fn collection_root<'a>(
+    operand: &'a Operand,
+    defs: &HashMap<&SsaVar, &'a Instruction>,
+) -> Option<&'a Operand> {
+    let mut current = operand;
+    loop {
+        let Operand::Var(var) = current else {
+            return Some(current);
+        };
+        match defs.get(var) {
+            Some(Instruction::MethodCall {
+                receiver, method, ..
+            }) => {
+                if method == "take" {
+                    return None;
+                }
+                current = receiver;
+            }
+            Some(Instruction::Assign { value, .. })
+            | Some(Instruction::ResultUnwrap { value, .. }) => current = value,
+            _ => return Some(current),
+        }
+    }
+}
+
+/// Variable an operand reads, through field accesses
+fn base_var(operand: &Operand) -> Option<&SsaVar> {
+    match operand {
+        Operand::Var(v) => Some(v),
+        Operand::FieldAccess { base, .. } => base_var(base),
+        Operand::Literal(_) => None,
+    }
+}
+
+fn operand_name(operand: &Operand) -> String {
+    match operand {
+        Operand::Var(v) => v.name.clone(),
+        Operand::FieldAccess { base, field } => format!("{}.{}", operand_name(base), field),
+        Operand::Literal(_) => "<literal>".to_string(),
+    }
+}
+
+/// Whether any block of the loop writes storage
+fn loop_writes_storage(func: &FunctionIr, lp: &NaturalLoop, state_items: &HashSet<&str>) -> bool {
+    lp.blocks
+        .iter()
+        .flat_map(|&b| &func.cfg.blocks[b].instructions)
+        .any(|inst| match inst {
+            Instruction::StorageStore { .. } => true,
+            Instruction::MethodCall {
+                receiver: Operand::Var(v),
+                method,
+                ..
+            } => method == "remove" && state_items.contains(v.name.as_str()),
+            _ => false,
+        })
+}
+
+/// Whether a comparison on a tainted `.len()` executes on every path to the loop
+fn has_length_guard(
+    func: &FunctionIr,
+    lp: &NaturalLoop,
+    tainted: &HashSet<SsaVar>,
+    defs: &HashMap<&SsaVar, &Instruction>,
+) -> bool {
+    let is_tainted_len = |operand: &Operand| {
+        let Operand::Var(v) = operand else {
+            return false;
+        };
+        matches!(
+            defs.get(v),
+            Some(Instruction::MethodCall { receiver, method, .. })
+                if (method == "len" || method == "is_empty") && operand_tainted(receiver, tainted)
+        )
+    };
+
+    let dominators = func.cfg.dominators();
+    let Some(header_doms) = dominators.get(&lp.header) else {
+        return false;
+    };
+    header_doms
+        .iter()
+        .filter(|b| !lp.blocks.contains(b))
+        .flat_map(|&b| &func.cfg.blocks[b].instructions)
+        .any(|inst| match inst {
+            Instruction::BinaryOp {
+                op: BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge,
+                left,
+                right,
+                ..
+            } => is_tainted_len(left) || is_tainted_len(right),
+            _ => false,
+        })
+}
+
+impl Detector for UnboundedMessageLoop {
+    fn name(&self) -> &str {
+        "unbounded-message-loop"
+    }
+
+    fn description(&self) -> &str {
+        "Detects storage writes in loops over message-supplied collections without a length bound"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Experimental
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let roots: Vec<&str> = ctx
+            .contract
+            .entry_points
+            .iter()
+            .filter(|ep| ep.kind == EntryPointKind::Execute)
+            .map(|ep| ep.name.as_str())
+            .collect();
+        let reachable = ctx.call_graph().reachable_from(&roots);
+        let state_items: HashSet<&str> = ctx
+            .contract
+            .state_items
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+
+        let mut findings = Vec::new();
+        for func in &ctx.ir.functions {
+            if !reachable.contains(&func.name) {
+                continue;
+            }
+            let loops = func.cfg.natural_loops();
+            if loops.is_empty() {
+                continue;
+            }
+
+            let info = ctx.contract.functions.iter().find(|f| f.name == func.name);
+            let defs = def_map(func);
+            let mut sources = taint_sources(func, info, &defs, &state_items);
+            sources.extend(helper_results(func, &ctx.config().taint.sources));
+            let tainted = propagate_taint(func, &sources);
+
+            for lp in &loops {
+                let Some(iterable) = loop_iterable(func, lp) else {
+                    continue;
+                };
+                let Some(root) = collection_root(iterable, &defs) else {
+                    continue;
+                };
+                if !operand_tainted(iterable, &tainted)
+                    || !loop_writes_storage(func, lp, &state_items)
+                    || has_length_guard(func, lp, &tainted, &defs)
+                {
+                    continue;
+                }
+
+                let collection = operand_name(root);
+                let path = base_var(iterable).and_then(|var| taint_path(func, &sources, var));
+                let mut trace = path
+                    .as_ref()
+                    .map(|path| trace::taint_steps(ctx, func, path, "is supplied by the sender"))
+                    .unwrap_or_default();
+                let loop_line = trace::find_line(ctx, func, |line| {
+                    line.trim_start().starts_with("for ")
+                        && std::iter::once(collection.as_str())
+                            .chain(path.iter().flatten().map(|v| v.name.as_str()))
+                            .any(|name| !name.starts_with('_') && line.contains(name))
+                });
+                trace.push(trace::missing_guard(
+                    func,
+                    lp.header,
+                    &format!("`{collection}.len()` comparison"),
+                    loop_line.clone(),
+                ));
+                trace.push(TraceStep {
+                    kind: TraceStepKind::Sink,
+                    message: format!(
+                        "the loop over `{collection}` writes storage on every iteration"
+                    ),
+                    location: loop_line,
+                });
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title: format!(
+                        "Unbounded loop over `{}` writes storage in `{}`",
+                        collection, func.name
+                    ),
+                    description: format!(
+                        "Function `{}` writes storage on each iteration over `{}`, which comes \
+                         from the message and is never length-checked. A sender can submit a \
+                         very large list to run up gas for every write, or to bloat state.",
+                        func.name, collection
+                    ),
+                    severity: Severity::Medium,
+                    confidence: Confidence::Medium,
+                    locations: vec![SourceLocation {
+                        file: func.source_span.file.clone(),
+                        start_line: func.source_span.start_line,
+                        end_line: func.source_span.end_line,
+                        start_col: func.source_span.start_col,
+                        end_col: func.source_span.end_col,
+                        snippet: None,
+                        byte_range: None,
+                    }],
+                    recommendation: Some(format!(
+                        "Reject oversized input before the loop, e.g. \
+                         `ensure!({}.len() <= MAX_ITEMS, ContractError::TooManyItems {{}})`.",
+                        collection
+                    )),
+                    fix: None,
+                    trace,
+                    ..Default::default()
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::config::Config;
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::path::PathBuf;
+
+    fn analyze_with(source: &str, config: &Config) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources).with_config(config);
+        UnboundedMessageLoop.detect(&ctx)
+    }
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        analyze_with(source, &Config::default())
+    }
+
+    const PRELUDE: &str = r#"
+        const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
+
+        #[entry_point]
+        pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
+            match msg {
+                ExecuteMsg::Airdrop { recipients } => execute_airdrop(deps, recipients),
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_detects_unbounded_loop_with_writes() {
+        let source = format!(
+            "{PRELUDE}{}",
+            r#"
+            fn execute_airdrop(deps: DepsMut, recipients: Vec<Addr>) -> Result<Response, ContractError> {
+                for addr in recipients.iter() {
+                    BALANCES.save(deps.storage, addr, &Uint128::one())?;
+                }
+                Ok(Response::new())
+            }
+            "#
+        );
+        let findings = analyze(&source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("recipients"));
+        assert!(findings[0].title.contains("execute_airdrop"));
+    }
+
+    #[test]
+    fn test_trace_follows_message_list_into_loop() {
+        let source = format!(
+            "{PRELUDE}{}",
+            r#"
+            fn execute_airdrop(deps: DepsMut, recipients: Vec<Addr>) -> Result<Response, ContractError> {
+                let targets = recipients;
+                for addr in targets.iter() {
+                    BALANCES.save(deps.storage, addr, &Uint128::one())?;
+                }
+                Ok(Response::new())
+            }
+            "#
+        );
+        let findings = analyze(&source);
+        let steps: Vec<(TraceStepKind, usize)> = findings[0]
+            .trace
+            .iter()
+            .map(|s| (s.kind, s.location.as_ref().unwrap().start_line))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (TraceStepKind::Source, 11),
+                (TraceStepKind::Propagation, 12),
+                (TraceStepKind::MissingGuard, 13),
+                (TraceStepKind::Sink, 13),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detects_loop_over_match_binding() {
+        let source = r#"
+            const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
+
+            #[entry_point]
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
+                match msg {
+                    ExecuteMsg::Clear { addrs } => {
+                        for addr in addrs {
+                            BALANCES.remove(deps.storage, &addr);
+                        }
+                        Ok(Response::new())
+                    }
+                }
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("addrs"));
+    }
+
+    #[test]
+    fn test_no_finding_with_length_check() {
+        let source = format!(
+            "{PRELUDE}{}",
+            r#"
+            fn execute_airdrop(deps: DepsMut, recipients: Vec<Addr>) -> Result<Response, ContractError> {
+                if recipients.len() > MAX_RECIPIENTS {
+                    return Err(ContractError::TooMany {});
+                }
+                for addr in recipients.iter() {
+                    BALANCES.save(deps.storage, addr, &Uint128::one())?;
+                }
+                Ok(Response::new())
+            }
+            "#
+        );
+        assert!(analyze(&source).is_empty());
+    }
+
+    #[test]
+    fn test_no_finding_with_ensure_or_take() {
+        let source = format!(
+            "{PRELUDE}{}",
+            r#"
+            fn execute_airdrop(deps: DepsMut, recipients: Vec<Addr>) -> Result<Response, ContractError> {
+                ensure!(recipients.len() <= 50, ContractError::TooMany {});
+                for addr in recipients.iter() {
+                    BALANCES.save(deps.storage, addr, &Uint128::one())?;
+                }
+                for addr in recipients.iter().take(10) {
+                    BALANCES.save(deps.storage, addr, &Uint128::zero())?;
+                }
+                Ok(Response::new())
+            }
+            "#
+        );
+        assert!(analyze(&source).is_empty());
+    }
+
+    #[test]
+    fn test_no_finding_for_storage_derived_collection() {
+        let source = format!(
+            "{PRELUDE}{}",
+            r#"
+            fn execute_airdrop(deps: DepsMut, recipients: Vec<Addr>) -> Result<Response, ContractError> {
+                let holders = HOLDERS.load(deps.storage)?;
+                for addr in holders.iter() {
+                    BALANCES.save(deps.storage, addr, &Uint128::one())?;
+                }
+                Ok(Response::new())
+            }
+            "#
+        );
+        assert!(analyze(&source).is_empty());
+    }
+
+    #[test]
+    fn test_no_finding_without_writes() {
+        let source = format!(
+            "{PRELUDE}{}",
+            r#"
+            fn execute_airdrop(deps: DepsMut, recipients: Vec<Addr>) -> Result<Response, ContractError> {
+                let mut total = 0;
+                for addr in recipients.iter() {
+                    total += 1;
+                }
+                Ok(Response::new())
+            }
+            "#
+        );
+        assert!(analyze(&source).is_empty());
+    }
+
+    #[test]
+    fn test_configured_taint_source() {
+        let source = format!(
+            "{PRELUDE}{}",
+            r#"
+            fn execute_airdrop(deps: DepsMut, recipients: Vec<Addr>) -> Result<Response, ContractError> {
+                let batch = load_batch(deps.storage)?;
+                for addr in batch.iter() {
+                    BALANCES.save(deps.storage, addr, &Uint128::one())?;
+                }
+                Ok(Response::new())
+            }
+            "#
+        );
+        assert!(analyze(&source).is_empty());
+        let config: Config = toml::from_str("[taint]\nsources = [\"load_batch\"]").unwrap();
+        let findings = analyze_with(&source, &config);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("execute_airdrop"));
+    }
+}
@@ -0,0 +1,312 @@
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::slice::Defs;
+use cosmwasm_guard::ir::{BinaryOp, FunctionIr, Instruction, Operand};
+
+/// Detects claim and withdraw handlers of staking-like contracts that pay out
+/// an entry carrying a release or maturity time without ever comparing that
+/// time with `env.block`. Unbonding periods and vesting locks are then
+/// unenforced: a user can unbond and claim in the same block.
+pub struct UncheckedClaimMaturity;
+
+/// Name segments of handlers releasing matured funds
+const RELEASE_NAMES: &[&str] = &[
+    "claim",
+    "claims",
+    "withdraw",
+    "unbond",
+    "undelegate",
+    "unstake",
+];
+
+/// Name fragments of stored times funds become available at
+const MATURITY_NAMES: &[&str] = &[
+    "release",
+    "unlock",
+    "matur",
+    "expir",
+    "unbond",
+    "claimable",
+    "lock_until",
+    "locked_until",
+    "ready_at",
+    "available_at",
+];
+
+/// Types holding a point in time
+const TIME_TYPES: &[&str] = &["Expiration", "Timestamp", "u64", "Scheduled"];
+
+fn has_segment(name: &str, segments: &[&str]) -> bool {
+    name.split('_')
+        .any(|s| segments.contains(&s.to_lowercase().as_str()))
+}
+
+fn is_maturity(name: &str, type_name: &str) -> bool {
+    let name = name.to_lowercase();
+    MATURITY_NAMES.iter().any(|m| name.contains(m))
+        && !name.contains("period")
+        && !name.contains("duration")
+        && type_name
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|t| TIME_TYPES.contains(&t))
+}
+
+/// Whether `operand` is `env.block`, `block`, or a field of one of them
+fn is_block(operand: &Operand) -> bool {
+    match operand {
+        Operand::FieldAccess { base, field } => field == "block" || is_block(base),
+        Operand::Var(var) => var.name == "block",
+        Operand::Literal(_) => false,
+    }
+}
+
+/// Whether `func` compares the block time or height with anything, or
+/// hands the block (or `env`) to a check such as `is_expired(&env.block)`
+fn checks_block(func: &FunctionIr) -> bool {
+    let defs = Defs::new(func);
+    let hands_block = |args: &[Operand]| {
+        args.iter()
+            .any(|a| is_block(a) || matches!(a, Operand::Var(v) if v.name == "env"))
+    };
+    func.cfg
+        .blocks
+        .iter()
+        .flat_map(|b| &b.instructions)
+        .any(|inst| match inst {
+            Instruction::BinaryOp {
+                op: BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge,
+                left,
+                right,
+                ..
+            } => defs.slice(left).any(is_block) || defs.slice(right).any(is_block),
+            Instruction::Call { args, .. } | Instruction::MethodCall { args, .. } => {
+                hands_block(args)
+            }
+            _ => false,
+        })
+}
+
+impl UncheckedClaimMaturity {
+    /// Maturity field of the entries stored in `item`, described for a finding,
+    /// or the item itself when it stores a bare time
+    fn maturity_of(ctx: &AnalysisContext, item: &str) -> Option<String> {
+        let state = ctx.contract.state_items.iter().find(|s| s.name == item)?;
+        if is_maturity(&state.name, &state.value_type) {
+            return Some(format!("`{}`", state.name));
+        }
+        state
+            .value_type
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter_map(|t| ctx.contract.find_struct(t))
+            .flat_map(|s| &s.fields)
+            .find(|f| is_maturity(&f.name, &f.type_name))
+            .map(|f| format!("`{}` of `{}`", f.name, state.name))
+    }
+}
+
+impl Detector for UncheckedClaimMaturity {
+    fn name(&self) -> &str {
+        "unchecked-claim-maturity"
+    }
+
+    fn description(&self) -> &str {
+        "Detects claim/withdraw handlers releasing funds without checking their stored maturity time against the block time"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::High
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Experimental
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let handlers: Vec<&FunctionIr> = ctx
+            .ir
+            .functions
+            .iter()
+            .filter(|f| has_segment(&f.name, RELEASE_NAMES))
+            .collect();
+        let mut findings = Vec::new();
+
+        for func in &handlers {
+            // Helpers of another handler are reported through that handler
+            let called_by_handler = handlers.iter().any(|h| {
+                h.name != func.name
+                    && ctx
+                        .call_graph()
+                        .reachable_from(&[h.name.as_str()])
+                        .contains(&func.name)
+            });
+            if called_by_handler {
+                continue;
+            }
+            let reach = ctx.call_graph().reachable_from(&[func.name.as_str()]);
+            let reached: Vec<&FunctionIr> = ctx
+                .ir
+                .functions
+                .iter()
+                .filter(|f| reach.contains(&f.name))
+                .collect();
+
+            let releases = reached.iter().any(|f| {
+                ctx.emitted_messages(&f.name)
+                    .iter()
+                    .any(|m| m.msg_type.starts_with("BankMsg") || m.msg_type.starts_with("WasmMsg"))
+            });
+            let maturity = reached
+                .iter()
+                .flat_map(|f| ctx.storage_reads(&f.name))
+                .find_map(|read| Self::maturity_of(ctx, &read.item));
+            let Some(maturity) = maturity.filter(|_| releases) else {
+                continue;
+            };
+            if reached.iter().any(|f| checks_block(f)) {
+                continue;
+            }
+
+            let span = &func.source_span;
+            findings.push(Finding {
+                detector_name: self.name().to_string(),
+                title: format!(
+                    "`{}` releases funds without checking their maturity",
+                    func.name
+                ),
+                description: format!(
+                    "`{}` loads {maturity} and pays out, but never compares it with \
+                     `env.block.time` or `env.block.height`. Funds can be claimed before \
+                     the unbonding period or lock ends, e.g. in the same block they were \
+                     unbonded.",
+                    func.name
+                ),
+                severity: Severity::High,
+                confidence: Confidence::Medium,
+                locations: vec![SourceLocation {
+                    file: span.file.clone(),
+                    start_line: span.start_line,
+                    end_line: span.end_line,
+                    start_col: span.start_col,
+                    end_col: span.end_col,
+                    snippet: None,
+                    byte_range: None,
+                }],
+                recommendation: Some(
+                    "Release only entries whose time has passed, e.g. \
+                     `claim.release_at.is_expired(&env.block)`, and keep the rest stored; \
+                     `cw_controllers::Claims::claim_tokens` does this for you."
+                        .to_string(),
+                ),
+                fix: None,
+                ..Default::default()
+            });
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        UncheckedClaimMaturity.detect(&ctx)
+    }
+
+    const STATE: &str = r#"
+        pub struct Unbonding {
+            pub amount: Uint128,
+            pub release_at: Timestamp,
+        }
+
+        const UNBONDING: Map<&Addr, Unbonding> = Map::new("unbonding");
+    "#;
+
+    #[test]
+    fn test_detects_claim_without_time_check() {
+        let source = format!(
+            "{STATE}{}",
+            r#"
+            pub fn execute_claim(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+                let entry = UNBONDING.load(deps.storage, &info.sender)?;
+                UNBONDING.remove(deps.storage, &info.sender);
+                let msg = BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: coins(entry.amount.u128(), "ustake"),
+                };
+                Ok(Response::new().add_message(msg))
+            }
+        "#
+        );
+        let findings = analyze(&source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("`execute_claim`"));
+        assert!(findings[0]
+            .description
+            .contains("`release_at` of `UNBONDING`"));
+    }
+
+    #[test]
+    fn test_checked_claims_not_flagged() {
+        let source = format!(
+            "{STATE}{}",
+            r#"
+            pub fn execute_claim(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+                let entry = UNBONDING.load(deps.storage, &info.sender)?;
+                let now = env.block.time;
+                if now < entry.release_at {
+                    return Err(StdError::generic_err("not matured"));
+                }
+                let msg = BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: coins(entry.amount.u128(), "ustake"),
+                };
+                Ok(Response::new().add_message(msg))
+            }
+
+            pub fn execute_withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+                let entry = UNBONDING.load(deps.storage, &info.sender)?;
+                ensure_matured(&entry, &env.block)?;
+                let msg = BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: coins(entry.amount.u128(), "ustake"),
+                };
+                Ok(Response::new().add_message(msg))
+            }
+        "#
+        );
+        assert!(analyze(&source).is_empty());
+    }
+
+    #[test]
+    fn test_withdraw_without_maturity_not_flagged() {
+        let source = r#"
+            const DEPOSITS: Map<&Addr, Uint128> = Map::new("deposits");
+
+            pub fn execute_withdraw(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+                let amount = DEPOSITS.load(deps.storage, &info.sender)?;
+                let msg = BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: coins(amount.u128(), "ustake"),
+                };
+                Ok(Response::new().add_message(msg))
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+}
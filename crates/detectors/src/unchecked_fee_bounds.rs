@@ -0,0 +1,407 @@
+use std::collections::{BTreeSet, HashSet};
+
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::slice::{value_inputs, Defs};
+use cosmwasm_guard::ir::{
+    instruction_dominates, BinaryOp, BlockId, FunctionIr, Instruction, Operand, SsaVar,
+};
+
+/// Detects fee/commission/bps values that are stored or multiplied without a
+/// dominating upper-bound comparison. A misconfigured 10000% fee drains users.
+pub struct UncheckedFeeBounds;
+
+/// Default name fragments, overridable via `[detectors.unchecked-fee-bounds] field_patterns`
+const DEFAULT_PATTERNS: &[&str] = &["fee", "commission", "bps", "rate"];
+
+/// Methods that multiply their receiver by an argument
+const MUL_METHODS: &[&str] = &[
+    "checked_mul",
+    "saturating_mul",
+    "wrapping_mul",
+    "mul",
+    "mul_floor",
+    "mul_ceil",
+    "checked_mul_floor",
+    "checked_mul_ceil",
+    "multiply_ratio",
+];
+
+/// Methods whose result is already bounded by their argument
+const CLAMP_METHODS: &[&str] = &["min", "clamp"];
+
+fn is_fee_name(name: &str, patterns: &[String]) -> bool {
+    name.to_lowercase().split('_').any(|segment| {
+        patterns
+            .iter()
+            .any(|p| segment == p || segment.strip_suffix('s') == Some(p.as_str()))
+    })
+}
+
+/// Per-function view used to trace where fee-like values come from
+struct FeeTracer<'a> {
+    defs: Defs<'a>,
+    patterns: &'a [String],
+}
+
+impl<'a> FeeTracer<'a> {
+    fn new(func: &'a FunctionIr, patterns: &'a [String]) -> Self {
+        Self {
+            defs: Defs::new(func),
+            patterns,
+        }
+    }
+
+    /// Fee-like names flowing into `operand` from caller-controlled inputs.
+    /// Returns None when the value is derived from storage (set by a trusted path).
+    fn fee_names(&self, operand: &Operand) -> Option<BTreeSet<String>> {
+        self.trace(operand, &mut HashSet::new())
+    }
+
+    fn trace(
+        &self,
+        operand: &'a Operand,
+        visited: &mut HashSet<&'a SsaVar>,
+    ) -> Option<BTreeSet<String>> {
+        match operand {
+            Operand::Literal(_) => Some(BTreeSet::new()),
+            Operand::FieldAccess { base, field } => {
+                let mut names = self.trace(base, visited)?;
+                if is_fee_name(field, self.patterns) {
+                    names.insert(field.clone());
+                }
+                Some(names)
+            }
+            Operand::Var(var) => {
+                if !visited.insert(var) {
+                    return Some(BTreeSet::new());
+                }
+                let mut names = match self.defs.get(var) {
+                    None => BTreeSet::new(),
+                    Some(inst) => self.trace_inst(inst, visited)?,
+                };
+                if is_fee_name(&var.name, self.patterns) {
+                    names.insert(var.name.clone());
+                }
+                Some(names)
+            }
+        }
+    }
+
+    fn trace_inst(
+        &self,
+        inst: &'a Instruction,
+        visited: &mut HashSet<&'a SsaVar>,
+    ) -> Option<BTreeSet<String>> {
+        let sources: Vec<&Operand> = match inst {
+            Instruction::StorageLoad { .. } => return None,
+            Instruction::AddrValidate { .. } => Vec::new(),
+            Instruction::MethodCall { method, .. } if CLAMP_METHODS.contains(&method.as_str()) => {
+                Vec::new()
+            }
+            _ => value_inputs(inst),
+        };
+
+        // A value built from storage and fresh input still carries the fresh input
+        let mut names = BTreeSet::new();
+        let mut all_from_storage = !sources.is_empty();
+        for source in sources {
+            if let Some(found) = self.trace(source, visited) {
+                all_from_storage = false;
+                names.extend(found);
+            }
+        }
+        if all_from_storage {
+            None
+        } else {
+            Some(names)
+        }
+    }
+
+    fn union(&self, operands: &[&Operand]) -> BTreeSet<String> {
+        operands
+            .iter()
+            .filter_map(|op| self.fee_names(op))
+            .flatten()
+            .collect()
+    }
+}
+
+fn is_validation_call(func: &str) -> bool {
+    let name = func.rsplit("::").next().unwrap_or(func);
+    name.starts_with("validate") || name.starts_with("check") || name.starts_with("assert")
+}
+
+/// Collect (position, fee names) of comparisons and validation calls
+fn collect_guards(
+    func: &FunctionIr,
+    tracer: &FeeTracer,
+) -> Vec<((BlockId, usize), BTreeSet<String>)> {
+    let mut guards = Vec::new();
+    for block in &func.cfg.blocks {
+        for (idx, inst) in block.instructions.iter().enumerate() {
+            let names = match inst {
+                Instruction::BinaryOp {
+                    op: BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge,
+                    left,
+                    right,
+                    ..
+                } => tracer.union(&[left, right]),
+                Instruction::Call { func, args, .. } if is_validation_call(func) => {
+                    tracer.union(&args.iter().collect::<Vec<_>>())
+                }
+                _ => continue,
+            };
+            if !names.is_empty() {
+                guards.push(((block.id, idx), names));
+            }
+        }
+    }
+    guards
+}
+
+/// Collect (position, fee names, usage) of stores and multiplications
+fn collect_sinks(
+    func: &FunctionIr,
+    tracer: &FeeTracer,
+) -> Vec<((BlockId, usize), BTreeSet<String>, &'static str)> {
+    let mut sinks = Vec::new();
+    for block in &func.cfg.blocks {
+        for (idx, inst) in block.instructions.iter().enumerate() {
+            let (names, usage) = match inst {
+                Instruction::StorageStore { value, .. } => (tracer.union(&[value]), "stored"),
+                Instruction::BinaryOp {
+                    op: BinaryOp::Mul,
+                    left,
+                    right,
+                    ..
+                } => (tracer.union(&[left, right]), "used in multiplication"),
+                Instruction::MethodCall {
+                    receiver,
+                    method,
+                    args,
+                    ..
+                } if MUL_METHODS.contains(&method.as_str()) => {
+                    let mut ops = vec![receiver];
+                    ops.extend(args);
+                    (tracer.union(&ops), "used in multiplication")
+                }
+                _ => continue,
+            };
+            if !names.is_empty() {
+                sinks.push(((block.id, idx), names, usage));
+            }
+        }
+    }
+    sinks
+}
+
+impl Detector for UncheckedFeeBounds {
+    fn name(&self) -> &str {
+        "unchecked-fee-bounds"
+    }
+
+    fn description(&self) -> &str {
+        "Detects fee/bps parameters stored or multiplied without an upper-bound check"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let patterns: Vec<String> = ctx
+            .config()
+            .detector_option(self.name(), "field_patterns")
+            .unwrap_or_else(|| DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect());
+
+        let mut findings = Vec::new();
+
+        for func in &ctx.ir.functions {
+            let tracer = FeeTracer::new(func, &patterns);
+            let sinks = collect_sinks(func, &tracer);
+            if sinks.is_empty() {
+                continue;
+            }
+            let guards = collect_guards(func, &tracer);
+            let doms = func.cfg.dominators();
+
+            let mut reported: HashSet<String> = HashSet::new();
+            for (pos, names, usage) in sinks {
+                for name in names {
+                    let guarded = guards.iter().any(|(guard_pos, guard_names)| {
+                        guard_names.contains(&name) && instruction_dominates(&doms, *guard_pos, pos)
+                    });
+                    if guarded || !reported.insert(name.clone()) {
+                        continue;
+                    }
+
+                    let span = &func.source_span;
+                    findings.push(Finding {
+                        detector_name: self.name().to_string(),
+                        title: format!(
+                            "Fee parameter `{}` {} without upper-bound check in `{}`",
+                            name, usage, func.name
+                        ),
+                        description: format!(
+                            "`{}` is {} in `{}` without a preceding comparison against an \
+                             upper bound. A misconfigured or malicious value (e.g. a fee above \
+                             100%) can drain user funds or brick the contract.",
+                            name, usage, func.name
+                        ),
+                        severity: Severity::Medium,
+                        confidence: Confidence::Medium,
+                        locations: vec![SourceLocation {
+                            file: span.file.clone(),
+                            start_line: span.start_line,
+                            end_line: span.end_line,
+                            start_col: span.start_col,
+                            end_col: span.end_col,
+                            snippet: None,
+                            byte_range: None,
+                        }],
+                        recommendation: Some(format!(
+                            "Reject out-of-range values before use, e.g. \
+                             `ensure!({} <= MAX_FEE, ContractError::InvalidFee {{}})`.",
+                            name
+                        )),
+                        fix: None,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::config::Config;
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze_with(source: &str, config: &Config) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources).with_config(config);
+        UncheckedFeeBounds.detect(&ctx)
+    }
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        analyze_with(source, &Config::default())
+    }
+
+    #[test]
+    fn test_detects_stored_fee_without_bound() {
+        let source = r#"
+            fn execute_update_fee(deps: DepsMut, info: MessageInfo, fee_bps: u16)
+                -> StdResult<Response> {
+                let mut config = CONFIG.load(deps.storage)?;
+                CONFIG.save(deps.storage, &Config { fee_bps, owner: config.owner })?;
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("`fee_bps` stored"));
+    }
+
+    #[test]
+    fn test_no_finding_with_if_guard() {
+        let source = r#"
+            fn execute_update_fee(deps: DepsMut, fee_bps: u16) -> StdResult<Response> {
+                if fee_bps > MAX_FEE_BPS {
+                    return Err(StdError::generic_err("fee too high"));
+                }
+                CONFIG.save(deps.storage, &Config { fee_bps })?;
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+
+    #[test]
+    fn test_no_finding_with_ensure_guard() {
+        let source = r#"
+            fn execute_update_fee(deps: DepsMut, msg: UpdateMsg) -> StdResult<Response> {
+                ensure!(msg.commission <= Decimal::one(), ContractError::InvalidFee {});
+                CONFIG.save(deps.storage, &Config { commission: msg.commission })?;
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+
+    #[test]
+    fn test_guard_in_one_branch_does_not_dominate() {
+        let source = r#"
+            fn execute_update_fee(deps: DepsMut, strict: bool, fee: Decimal) -> StdResult<Response> {
+                if strict {
+                    ensure!(fee <= Decimal::one(), ContractError::InvalidFee {});
+                }
+                CONFIG.save(deps.storage, &fee)?;
+                Ok(Response::new())
+            }
+        "#;
+        assert_eq!(analyze(source).len(), 1);
+    }
+
+    #[test]
+    fn test_detects_unbounded_fee_multiplication() {
+        let source = r#"
+            fn swap(amount: Uint128, fee_rate: Decimal) -> Uint128 {
+                amount.mul_floor(fee_rate)
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("used in multiplication"));
+    }
+
+    #[test]
+    fn test_stored_fee_multiplication_not_flagged() {
+        let source = r#"
+            fn swap(deps: Deps, amount: Uint128) -> StdResult<Uint128> {
+                let config = CONFIG.load(deps.storage)?;
+                Ok(amount * config.fee_rate)
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+
+    #[test]
+    fn test_configurable_patterns() {
+        let source = r#"
+            fn set_spread(deps: DepsMut, max_spread: Decimal) -> StdResult<Response> {
+                SPREAD.save(deps.storage, &max_spread)?;
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+
+        let config: Config = toml::from_str(
+            r#"
+[detectors.unchecked-fee-bounds]
+field_patterns = ["spread"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(analyze_with(source, &config).len(), 1);
+    }
+}
@@ -0,0 +1,342 @@
+use std::collections::{BTreeSet, HashSet};
+
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use cosmwasm_guard::ir::slice::{Defs, CONTEXT_NAMES};
+use cosmwasm_guard::ir::{
+    instruction_dominates, BinaryOp, BlockId, FunctionIr, Instruction, Operand,
+};
+use cosmwasm_guard::trace;
+
+/// Detects `balance - amount` on balance-like values without a dominating
+/// comparison of the two. `Uint128` subtraction panics (cosmwasm-std 1.x) or
+/// errors on underflow, so a caller asking for more than they hold aborts the
+/// transaction with an opaque error instead of a contract error, and shared
+/// totals that can be driven below an amount brick every user's withdrawal.
+pub struct UncheckedSubtraction;
+
+/// Default name fragments, overridable via `[detectors.unchecked-subtraction] field_patterns`
+const DEFAULT_PATTERNS: &[&str] = &[
+    "balance",
+    "deposit",
+    "stake",
+    "share",
+    "supply",
+    "locked",
+    "escrow",
+    "allowance",
+    "collateral",
+    "reserve",
+    "liquidity",
+];
+
+/// Methods bounding their receiver by an argument
+const CLAMP_METHODS: &[&str] = &["min", "clamp"];
+
+/// Variable, field and storage item names `operand` is computed from,
+/// without temporaries and handler context
+fn names<'a>(defs: &Defs<'a>, operand: &'a Operand) -> BTreeSet<String> {
+    let mut names = defs.names(operand);
+    names.retain(|n| !n.starts_with('_') && !CONTEXT_NAMES.contains(&n.as_str()));
+    names
+}
+
+/// Whether `operand` was clamped with `min`/`clamp` against a value named
+/// in `bound`, e.g. `amount.min(balance)`
+fn clamped_by<'a>(defs: &Defs<'a>, operand: &'a Operand, bound: &BTreeSet<String>) -> bool {
+    let copies = |inst: &Instruction| {
+        matches!(
+            inst,
+            Instruction::Assign { .. } | Instruction::ResultUnwrap { .. }
+        )
+    };
+    defs.slice_through(operand, copies)
+        .filter_map(|op| match op {
+            Operand::Var(var) => defs.get(var),
+            _ => None,
+        })
+        .any(|inst| match inst {
+            Instruction::MethodCall {
+                receiver,
+                method,
+                args,
+                ..
+            } if CLAMP_METHODS.contains(&method.as_str()) => std::iter::once(receiver)
+                .chain(args)
+                .any(|op| !names(defs, op).is_disjoint(bound)),
+            _ => false,
+        })
+}
+
+fn is_balance_name(name: &str, patterns: &[String]) -> bool {
+    let name = name.to_lowercase();
+    patterns.iter().any(|p| name.contains(p.as_str()))
+}
+
+/// Collect (position, names compared) of ordering comparisons
+fn collect_comparisons<'a>(
+    func: &'a FunctionIr,
+    defs: &Defs<'a>,
+) -> Vec<((BlockId, usize), BTreeSet<String>)> {
+    let mut comparisons = Vec::new();
+    for block in &func.cfg.blocks {
+        for (idx, inst) in block.instructions.iter().enumerate() {
+            if let Instruction::BinaryOp {
+                op: BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge,
+                left,
+                right,
+                ..
+            } = inst
+            {
+                let mut compared = names(defs, left);
+                compared.extend(names(defs, right));
+                comparisons.push(((block.id, idx), compared));
+            }
+        }
+    }
+    comparisons
+}
+
+impl Detector for UncheckedSubtraction {
+    fn name(&self) -> &str {
+        "unchecked-subtraction"
+    }
+
+    fn description(&self) -> &str {
+        "Detects balance subtractions without a preceding bounds check or checked_sub"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let patterns: Vec<String> = ctx
+            .config()
+            .detector_option(self.name(), "field_patterns")
+            .unwrap_or_else(|| DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect());
+
+        let mut findings = Vec::new();
+
+        for func in &ctx.ir.functions {
+            let defs = Defs::new(func);
+            let mut comparisons = None;
+            let mut doms = None;
+            let mut reported: HashSet<String> = HashSet::new();
+
+            for block in &func.cfg.blocks {
+                for (idx, inst) in block.instructions.iter().enumerate() {
+                    let Instruction::BinaryOp {
+                        op: BinaryOp::Sub,
+                        left,
+                        right,
+                        ..
+                    } = inst
+                    else {
+                        continue;
+                    };
+                    if matches!(right, Operand::Literal(_)) {
+                        continue;
+                    }
+                    let minuend = names(&defs, left);
+                    // Prefer the local variable over the storage item it came from
+                    let mut matching = minuend.iter().filter(|n| is_balance_name(n, &patterns));
+                    let Some(name) = matching
+                        .clone()
+                        .find(|n| n.chars().any(|c| c.is_lowercase()))
+                        .or_else(|| matching.next())
+                        .cloned()
+                    else {
+                        continue;
+                    };
+                    let subtrahend = names(&defs, right);
+                    if clamped_by(&defs, right, &minuend) {
+                        continue;
+                    }
+
+                    let comparisons =
+                        comparisons.get_or_insert_with(|| collect_comparisons(func, &defs));
+                    let doms = doms.get_or_insert_with(|| func.cfg.dominators());
+                    let checked = comparisons.iter().any(|(pos, names)| {
+                        !names.is_disjoint(&minuend)
+                            && !names.is_disjoint(&subtrahend)
+                            && instruction_dominates(doms, *pos, (block.id, idx))
+                    });
+                    if checked || !reported.insert(name.clone()) {
+                        continue;
+                    }
+
+                    let amount = subtrahend
+                        .iter()
+                        .find(|n| n.chars().any(|c| c.is_lowercase()))
+                        .map_or("the amount".to_string(), |n| format!("`{n}`"));
+                    let sub_line = trace::find_line(ctx, func, |line| {
+                        line.contains(name.as_str())
+                            && (line.contains(" - ") || line.contains("-="))
+                    });
+                    let trace = vec![
+                        TraceStep {
+                            kind: TraceStepKind::Source,
+                            message: format!("`{name}` holds a balance-like value"),
+                            location: trace::locate(ctx, func, &name),
+                        },
+                        trace::missing_guard(
+                            func,
+                            block.id,
+                            &format!("comparison of `{name}` with {amount}"),
+                            sub_line.clone(),
+                        ),
+                        TraceStep {
+                            kind: TraceStepKind::Sink,
+                            message: format!(
+                                "{amount} is subtracted from `{name}`, which fails on underflow"
+                            ),
+                            location: sub_line,
+                        },
+                    ];
+
+                    let span = &func.source_span;
+                    findings.push(Finding {
+                        detector_name: self.name().to_string(),
+                        title: format!("Unchecked subtraction from `{}` in `{}`", name, func.name),
+                        description: format!(
+                            "`{}` subtracts from `{}` without first checking that it covers \
+                             the amount. `Uint128` subtraction panics (cosmwasm-std 1.x) or \
+                             errors on underflow, aborting the transaction with an opaque \
+                             error; a shared total that can be driven below the amount blocks \
+                             every caller.",
+                            func.name, name
+                        ),
+                        severity: Severity::Medium,
+                        confidence: Confidence::Medium,
+                        locations: vec![SourceLocation {
+                            file: span.file.clone(),
+                            start_line: span.start_line,
+                            end_line: span.end_line,
+                            start_col: span.start_col,
+                            end_col: span.end_col,
+                            snippet: None,
+                            byte_range: None,
+                        }],
+                        recommendation: Some(format!(
+                            "Use `{}.checked_sub(amount)?` and map the overflow to a contract \
+                             error, or `ensure!` that `{}` covers the amount first.",
+                            name, name
+                        )),
+                        fix: None,
+                        trace,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        UncheckedSubtraction.detect(&ctx)
+    }
+
+    #[test]
+    fn test_detects_unchecked_balance_subtraction() {
+        let source = r#"
+            fn execute_withdraw(deps: DepsMut, info: MessageInfo, amount: Uint128)
+                -> StdResult<Response> {
+                let balance = BALANCES.load(deps.storage, &info.sender)?;
+                BALANCES.save(deps.storage, &info.sender, &(balance - amount))?;
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("`balance`"));
+        let steps: Vec<(TraceStepKind, usize)> = findings[0]
+            .trace
+            .iter()
+            .map(|s| (s.kind, s.location.as_ref().unwrap().start_line))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (TraceStepKind::Source, 4),
+                (TraceStepKind::MissingGuard, 5),
+                (TraceStepKind::Sink, 5),
+            ]
+        );
+        assert!(findings[0].trace[1]
+            .message
+            .starts_with("no comparison of `balance` with `amount` dominates block"));
+    }
+
+    #[test]
+    fn test_no_finding_with_dominating_check() {
+        let source = r#"
+            fn execute_withdraw(deps: DepsMut, info: MessageInfo, amount: Uint128)
+                -> Result<Response, ContractError> {
+                let balance = BALANCES.load(deps.storage, &info.sender)?;
+                if amount > balance {
+                    return Err(ContractError::InsufficientFunds {});
+                }
+                BALANCES.save(deps.storage, &info.sender, &(balance - amount))?;
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+
+    #[test]
+    fn test_check_in_one_branch_does_not_dominate() {
+        let source = r#"
+            fn execute_withdraw(deps: DepsMut, strict: bool, amount: Uint128)
+                -> Result<Response, ContractError> {
+                let mut total_staked = TOTAL_STAKED.load(deps.storage)?;
+                if strict {
+                    ensure!(total_staked >= amount, ContractError::InsufficientFunds {});
+                }
+                total_staked -= amount;
+                TOTAL_STAKED.save(deps.storage, &total_staked)?;
+                Ok(Response::new())
+            }
+        "#;
+        assert_eq!(analyze(source).len(), 1);
+    }
+
+    #[test]
+    fn test_checked_sub_and_clamp_not_flagged() {
+        let source = r#"
+            fn execute_unstake(deps: DepsMut, info: MessageInfo, amount: Uint128)
+                -> StdResult<Response> {
+                let stake = STAKES.load(deps.storage, &info.sender)?;
+                let remaining = stake.checked_sub(amount)?;
+                let unlocked = amount.min(stake);
+                STAKES.save(deps.storage, &info.sender, &(stake - unlocked))?;
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+}
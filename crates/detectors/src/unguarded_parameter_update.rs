@@ -0,0 +1,296 @@
+use std::sync::OnceLock;
+
+use cosmwasm_guard::ast::MessageKind;
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use regex::Regex;
+use syn::visit::Visit;
+
+use crate::missing_access_control::{expr_has_sender_check, has_sender_check};
+
+/// Detects execute variants whose name says they change protocol parameters or
+/// halt the contract (`SetFee`, `UpdateOracle`, `SetPrice`, `Pause`, ...) but
+/// whose handler never checks `info.sender`. Unlike `missing-access-control`,
+/// which only asks whether *some* handler is guarded, this looks at the
+/// variants whose intent makes an open door critical.
+pub struct UnguardedParameterUpdate;
+
+/// Default variant-name regex, overridable via
+/// `[detectors.unguarded-parameter-update] variant_pattern`
+const DEFAULT_PATTERN: &str = r"^(?:(?:Set|Update|Change)\w*(?:Fee|Oracle|Price|Rate|Param|Config)\w*|Pause|Unpause|Halt|Resume|Freeze|Unfreeze)$";
+
+fn default_regex() -> &'static Regex {
+    static DEFAULT: OnceLock<Regex> = OnceLock::new();
+    DEFAULT.get_or_init(|| Regex::new(DEFAULT_PATTERN).expect("default pattern is valid"))
+}
+
+/// `Enum::Variant` pattern -> (enum, variant)
+fn variant_of(pat: &syn::Pat) -> Option<(String, String)> {
+    let path = match pat {
+        syn::Pat::Struct(p) => &p.path,
+        syn::Pat::TupleStruct(p) => &p.path,
+        syn::Pat::Path(p) => &p.path,
+        _ => return None,
+    };
+    let n = path.segments.len();
+    if n < 2 {
+        return None;
+    }
+    Some((
+        path.segments[n - 2].ident.to_string(),
+        path.segments[n - 1].ident.to_string(),
+    ))
+}
+
+/// Match arms on execute message variants whose name matches the pattern
+struct SensitiveArmCollector<'a, 'ast> {
+    execute_enums: &'a [&'a str],
+    pattern: &'a Regex,
+    arms: Vec<(String, String, &'ast syn::Arm)>,
+}
+
+impl<'ast> Visit<'ast> for SensitiveArmCollector<'_, 'ast> {
+    fn visit_arm(&mut self, node: &'ast syn::Arm) {
+        if let Some((enum_name, variant)) = variant_of(&node.pat) {
+            let is_execute = self.execute_enums.contains(&enum_name.as_str())
+                || enum_name.ends_with("ExecuteMsg");
+            if is_execute && self.pattern.is_match(&variant) {
+                self.arms.push((enum_name, variant, node));
+            }
+        }
+        syn::visit::visit_arm(self, node);
+    }
+}
+
+/// Functions called from a match arm
+#[derive(Default)]
+struct CallCollector {
+    calls: Vec<String>,
+}
+
+impl<'ast> Visit<'ast> for CallCollector {
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(path) = node.func.as_ref() {
+            if let Some(last) = path.path.segments.last() {
+                self.calls.push(last.ident.to_string());
+            }
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+}
+
+impl UnguardedParameterUpdate {
+    /// Configured variant pattern, checked when the config is loaded
+    fn pattern(&self, ctx: &AnalysisContext) -> Regex {
+        let configured: Option<String> =
+            ctx.config().detector_option(self.name(), "variant_pattern");
+        configured
+            .and_then(|p| Regex::new(&p).ok())
+            .unwrap_or_else(|| default_regex().clone())
+    }
+}
+
+impl Detector for UnguardedParameterUpdate {
+    fn name(&self) -> &str {
+        "unguarded-parameter-update"
+    }
+
+    fn description(&self) -> &str {
+        "Detects fee/oracle/price/pause execute variants whose handlers lack an owner check"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::High
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let pattern = self.pattern(ctx);
+        let execute_enums: Vec<&str> = ctx
+            .contract
+            .message_enums
+            .iter()
+            .filter(|e| e.kind == MessageKind::Execute)
+            .map(|e| e.name.as_str())
+            .collect();
+
+        let mut findings = Vec::new();
+        for func in &ctx.contract.functions {
+            let Some(body) = &func.body else { continue };
+            let mut collector = SensitiveArmCollector {
+                execute_enums: &execute_enums,
+                pattern: &pattern,
+                arms: Vec::new(),
+            };
+            collector.visit_block(body);
+
+            for (enum_name, variant, arm) in collector.arms {
+                if expr_has_sender_check(&arm.body, ctx.auth()) {
+                    continue;
+                }
+                let mut calls = CallCollector::default();
+                calls.visit_expr(&arm.body);
+                let roots: Vec<&str> = calls.calls.iter().map(String::as_str).collect();
+                let reachable = ctx.call_graph().reachable_from(&roots);
+                let guarded = ctx
+                    .contract
+                    .functions
+                    .iter()
+                    .filter(|f| reachable.contains(&f.name))
+                    .filter_map(|f| f.body.as_ref())
+                    .any(|body| has_sender_check(body, ctx.auth()));
+                if guarded {
+                    continue;
+                }
+
+                let Some(start) = ctx.span_of(&arm.pat) else {
+                    continue;
+                };
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title: format!("`{enum_name}::{variant}` can be called by anyone"),
+                    description: format!(
+                        "The handler for `{enum_name}::{variant}` in `{}` never checks \
+                         `info.sender`. Its name suggests it changes protocol parameters or \
+                         halts the contract, so any account can reconfigure fees, prices or \
+                         oracles, or freeze user funds.",
+                        func.name
+                    ),
+                    severity: Severity::High,
+                    confidence: Confidence::Medium,
+                    locations: vec![SourceLocation {
+                        file: func.span.file.clone(),
+                        start_line: start.start_line,
+                        end_line: start.start_line,
+                        start_col: start.start_col,
+                        end_col: start.start_col,
+                        snippet: None,
+                        byte_range: None,
+                    }],
+                    recommendation: Some(
+                        "Restrict the handler to the owner or admin, e.g. \
+                         `if info.sender != config.owner { return Err(ContractError::Unauthorized {}) }` \
+                         or `cw_ownable::assert_owner(deps.storage, &info.sender)?`."
+                            .to_string(),
+                    ),
+                    fix: None,
+                    ..Default::default()
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::config::Config;
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze_with(source: &str, config: &Config) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources).with_config(config);
+        UnguardedParameterUpdate.detect(&ctx)
+    }
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        analyze_with(source, &Config::default())
+    }
+
+    const CONTRACT: &str = r#"
+        #[entry_point]
+        pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+            -> Result<Response, ContractError> {
+            match msg {
+                ExecuteMsg::Deposit {} => execute_deposit(deps, info),
+                ExecuteMsg::SetFee { fee } => execute_set_fee(deps, fee),
+                ExecuteMsg::UpdateOracle { oracle } => execute_update_oracle(deps, info, oracle),
+                ExecuteMsg::Pause {} => {
+                    PAUSED.save(deps.storage, &true)?;
+                    Ok(Response::new())
+                }
+            }
+        }
+
+        fn execute_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+            Ok(Response::new())
+        }
+
+        fn execute_set_fee(deps: DepsMut, fee: Decimal) -> Result<Response, ContractError> {
+            FEE.save(deps.storage, &fee)?;
+            Ok(Response::new())
+        }
+
+        fn execute_update_oracle(deps: DepsMut, info: MessageInfo, oracle: String)
+            -> Result<Response, ContractError> {
+            assert_admin(deps.as_ref(), &info)?;
+            ORACLE.save(deps.storage, &oracle)?;
+            Ok(Response::new())
+        }
+
+        fn assert_admin(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+            if info.sender != ADMIN.load(deps.storage)? {
+                return Err(ContractError::Unauthorized {});
+            }
+            Ok(())
+        }
+    "#;
+
+    #[test]
+    fn test_detects_unguarded_sensitive_variants() {
+        let findings = analyze(CONTRACT);
+        let titles: Vec<&str> = findings.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(findings.len(), 2, "{titles:?}");
+        assert!(titles[0].contains("`ExecuteMsg::SetFee`"));
+        assert!(titles[1].contains("`ExecuteMsg::Pause`"));
+        assert!(findings.iter().all(|f| f.severity == Severity::High));
+    }
+
+    #[test]
+    fn test_guard_in_arm_body_counts() {
+        let source = r#"
+            #[entry_point]
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> Result<Response, ContractError> {
+                match msg {
+                    ExecuteMsg::SetPrice { price } => {
+                        ensure_eq!(info.sender, OWNER.load(deps.storage)?, ContractError::Unauthorized {});
+                        PRICE.save(deps.storage, &price)?;
+                        Ok(Response::new())
+                    }
+                }
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+
+    #[test]
+    fn test_configurable_variant_pattern() {
+        let config: Config = toml::from_str(
+            r#"
+[detectors.unguarded-parameter-update]
+variant_pattern = "^Deposit$"
+"#,
+        )
+        .unwrap();
+        let findings = analyze_with(CONTRACT, &config);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("`ExecuteMsg::Deposit`"));
+    }
+}
@@ -61,7 +61,7 @@ impl Detector for UninitializedStateAccess {
             }
             if let Some(func) = ctx.contract.functions.iter().find(|f| f.name == ep.name) {
                 if let Some(body) = &func.body {
-                    let loads = collect_load_calls(body);
+                    let loads = collect_load_calls(ctx, body);
                     for (name, line, col) in loads {
                         if state_names.contains(&name)
                             && !initialized_in_instantiate.contains(&name)
@@ -87,6 +87,7 @@ impl Detector for UninitializedStateAccess {
                                     start_col: col,
                                     end_col: col,
                                     snippet: None,
+                                    byte_range: None,
                                 }],
                                 recommendation: Some(format!(
                                     "Ensure `{}.save(...)` is called in the instantiate handler, \
@@ -94,6 +95,7 @@ impl Detector for UninitializedStateAccess {
                                     name
                                 )),
                                 fix: None,
+                                ..Default::default()
                             });
                         }
                     }
@@ -131,26 +133,29 @@ fn collect_save_calls(block: &syn::Block) -> HashSet<String> {
 }
 
 /// Collect (name, line, col) of state items that have .load() called on them
-fn collect_load_calls(block: &syn::Block) -> Vec<(String, usize, usize)> {
-    struct LoadSearcher {
+fn collect_load_calls(ctx: &AnalysisContext, block: &syn::Block) -> Vec<(String, usize, usize)> {
+    struct LoadSearcher<'c> {
+        ctx: &'c AnalysisContext<'c>,
         loads: Vec<(String, usize, usize)>,
     }
 
-    impl<'ast> Visit<'ast> for LoadSearcher {
+    impl<'ast> Visit<'ast> for LoadSearcher<'_> {
         fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
             let method = node.method.to_string();
             if method == "load" {
-                if let Some(name) = extract_receiver_name(&node.receiver) {
-                    let span = node.method.span();
-                    self.loads
-                        .push((name, span.start().line, span.start().column));
+                let receiver = extract_receiver_name(&node.receiver);
+                if let (Some(name), Some(span)) = (receiver, self.ctx.span_of(&node.method)) {
+                    self.loads.push((name, span.start_line, span.start_col));
                 }
             }
             syn::visit::visit_expr_method_call(self, node);
         }
     }
 
-    let mut searcher = LoadSearcher { loads: Vec::new() };
+    let mut searcher = LoadSearcher {
+        ctx,
+        loads: Vec::new(),
+    };
     syn::visit::visit_block(&mut searcher, block);
     searcher.loads
 }
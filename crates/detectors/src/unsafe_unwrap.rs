@@ -1,3 +1,4 @@
+use cosmwasm_guard::ast::SourceSpan;
 use cosmwasm_guard::detector::{AnalysisContext, Detector};
 use cosmwasm_guard::finding::*;
 use syn::visit::Visit;
@@ -6,11 +7,12 @@ use syn::visit::Visit;
 /// Panics in CosmWasm contracts cause chain-halting errors.
 pub struct UnsafeUnwrap;
 
-struct UnwrapSearcher {
-    findings: Vec<(usize, usize, String)>, // (line, col, method)
+struct UnwrapSearcher<'c> {
+    ctx: &'c AnalysisContext<'c>,
+    findings: Vec<(usize, usize, String, Option<SourceSpan>)>, // (line, col, method, `.method(..)`)
 }
 
-impl<'ast> Visit<'ast> for UnwrapSearcher {
+impl<'ast> Visit<'ast> for UnwrapSearcher<'_> {
     fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
         // Skip #[cfg(test)] modules
         let is_test = node.attrs.iter().any(|attr| {
@@ -36,9 +38,20 @@ impl<'ast> Visit<'ast> for UnwrapSearcher {
             return;
         }
         if method == "unwrap" || method == "expect" {
-            let span = node.method.span();
-            self.findings
-                .push((span.start().line, span.start().column, method));
+            if let Some(span) = self.ctx.span_of(&node.method) {
+                // From the dot to the closing paren: the text `?` replaces
+                let call = match (self.ctx.span_of(&node.dot_token), self.ctx.span_of(node)) {
+                    (Some(dot), Some(whole)) => Some(SourceSpan {
+                        end_line: whole.end_line,
+                        end_col: whole.end_col,
+                        end_byte: whole.end_byte,
+                        ..dot
+                    }),
+                    _ => None,
+                };
+                self.findings
+                    .push((span.start_line, span.start_col, method, call));
+            }
         }
         syn::visit::visit_expr_method_call(self, node);
     }
@@ -66,11 +79,12 @@ impl Detector for UnsafeUnwrap {
 
         for (path, ast) in ctx.raw_asts() {
             let mut searcher = UnwrapSearcher {
+                ctx,
                 findings: Vec::new(),
             };
             syn::visit::visit_file(&mut searcher, ast);
 
-            for (line, col, method) in &searcher.findings {
+            for (line, col, method, call) in &searcher.findings {
                 findings.push(Finding {
                     detector_name: self.name().to_string(),
                     title: format!("Unsafe .{}() call", method),
@@ -88,23 +102,28 @@ impl Detector for UnsafeUnwrap {
                         start_col: *col,
                         end_col: *col,
                         snippet: None,
+                        byte_range: None,
                     }],
                     recommendation: Some(
                         "Replace `.unwrap()` with `?` or handle the error explicitly."
                             .to_string(),
                     ),
-                    fix: Some(FixSuggestion {
-                        description: format!("Replace `.{}()` with `?`", method),
-                        replacement_text: "?".to_string(),
-                        location: SourceLocation {
-                            file: path.clone(),
-                            start_line: *line,
-                            end_line: *line,
-                            start_col: *col,
-                            end_col: *col,
-                            snippet: None,
-                        },
+                    fix: call.as_ref().map(|call| {
+                        FixSuggestion::replace(
+                            format!("Replace `.{}()` with `?`", method),
+                            SourceLocation {
+                                file: path.clone(),
+                                start_line: call.start_line,
+                                end_line: call.end_line,
+                                start_col: call.start_col,
+                                end_col: call.end_col,
+                                snippet: None,
+                                byte_range: Some(call.start_byte..call.end_byte),
+                            },
+                            "?",
+                        )
                     }),
+                    ..Default::default()
                 });
             }
         }
@@ -143,6 +162,20 @@ mod tests {
         assert_eq!(findings[0].detector_name, "unsafe-unwrap");
     }
 
+    #[test]
+    fn test_fix_replaces_call_after_multibyte_text() {
+        let source = "fn f() { let _ = (\"é\", CONFIG.load(deps.storage).expect(\"ü\")); }";
+        let findings = analyze(source);
+        let fix = &findings[0].fix.as_ref().unwrap().edits[0];
+        let bytes = fix.location.byte_range.clone().unwrap();
+        assert_eq!(&source[bytes.clone()], ".expect(\"ü\")");
+        assert_eq!(fix.location.start_col, source[..bytes.start].chars().count());
+
+        let mut fixed = source.to_string();
+        fixed.replace_range(bytes, &fix.replacement_text);
+        assert!(fixed.contains("CONFIG.load(deps.storage)?)"));
+    }
+
     #[test]
     fn test_no_finding_with_question_mark() {
         let source = r#"
@@ -0,0 +1,341 @@
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use cosmwasm_guard::ast::utils::is_test_attr;
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use syn::visit::Visit;
+
+/// Detects APIs the target chain's runtime lacks, per the `[chains.<name>]`
+/// profile selected with `--target-chain`: storage iteration without
+/// `iterator`, staking and distribution messages and queries without
+/// `staking`, Stargate messages and queries and IBC without `stargate`, and
+/// messages and queries newer than the chain's CosmWasm version. The same
+/// source can be valid on one chain and fail to upload on another. Reports
+/// nothing until a target chain is selected.
+pub struct UnsupportedCapability;
+
+/// `(enum, variant, capability)`; `*` matches every variant
+const VARIANTS: &[(&str, &str, &str)] = &[
+    ("CosmosMsg", "Stargate", "stargate"),
+    ("QueryRequest", "Stargate", "stargate"),
+    ("CosmosMsg", "Ibc", "stargate"),
+    ("IbcMsg", "*", "stargate"),
+    ("QueryRequest", "Ibc", "stargate"),
+    ("IbcQuery", "*", "stargate"),
+    ("CosmosMsg", "Staking", "staking"),
+    ("StakingMsg", "*", "staking"),
+    ("CosmosMsg", "Distribution", "staking"),
+    ("DistributionMsg", "*", "staking"),
+    ("QueryRequest", "Staking", "staking"),
+    ("StakingQuery", "*", "staking"),
+    ("BankQuery", "Supply", "cosmwasm_1_1"),
+    ("WasmMsg", "Instantiate2", "cosmwasm_1_2"),
+    ("WasmQuery", "CodeInfo", "cosmwasm_1_2"),
+    ("GovMsg", "VoteWeighted", "cosmwasm_1_2"),
+    ("BankQuery", "DenomMetadata", "cosmwasm_1_3"),
+    ("BankQuery", "AllDenomMetadata", "cosmwasm_1_3"),
+    ("DistributionMsg", "FundCommunityPool", "cosmwasm_1_3"),
+    ("QueryRequest", "Distribution", "cosmwasm_1_3"),
+    ("DistributionQuery", "DelegatorWithdrawAddress", "cosmwasm_1_3"),
+    ("DistributionQuery", "DelegationRewards", "cosmwasm_1_4"),
+    ("DistributionQuery", "DelegationTotalRewards", "cosmwasm_1_4"),
+    ("DistributionQuery", "DelegatorValidators", "cosmwasm_1_4"),
+    ("CosmosMsg", "Any", "cosmwasm_2_0"),
+    ("QueryRequest", "Grpc", "cosmwasm_2_0"),
+];
+
+/// `QuerierWrapper` helpers and the capability they need
+const QUERIER_METHODS: &[(&str, &str)] = &[
+    ("query_bonded_denom", "staking"),
+    ("query_all_validators", "staking"),
+    ("query_validator", "staking"),
+    ("query_all_delegations", "staking"),
+    ("query_delegation", "staking"),
+    ("query_supply", "cosmwasm_1_1"),
+    ("query_wasm_code_info", "cosmwasm_1_2"),
+    ("query_denom_metadata", "cosmwasm_1_3"),
+    ("query_all_denom_metadata", "cosmwasm_1_3"),
+    ("query_delegator_withdraw_address", "cosmwasm_1_3"),
+    ("query_delegation_rewards", "cosmwasm_1_4"),
+    ("query_delegation_total_rewards", "cosmwasm_1_4"),
+    ("query_delegator_validators", "cosmwasm_1_4"),
+];
+
+/// Storage and cw-storage-plus methods iterating over keys, recognized by
+/// their trailing `Order` argument
+const ITERATOR_METHODS: &[&str] = &[
+    "range",
+    "range_raw",
+    "keys",
+    "keys_raw",
+    "prefix_range",
+    "prefix_range_raw",
+];
+
+/// "the `staking` capability", or "CosmWasm 1.3" for `cosmwasm_1_3`
+fn describe(capability: &str) -> String {
+    match capability.strip_prefix("cosmwasm_") {
+        Some(version) => format!("CosmWasm {}", version.replace('_', ".")),
+        None => format!("the `{capability}` capability"),
+    }
+}
+
+/// Uses of capability-gated APIs by file, line and column, with the API and
+/// the capability it needs
+struct ApiCollector<'c> {
+    ctx: &'c AnalysisContext<'c>,
+    file: PathBuf,
+    hits: BTreeSet<(PathBuf, usize, usize, String, &'static str)>,
+}
+
+impl ApiCollector<'_> {
+    fn record(&mut self, node: &impl quote::ToTokens, api: String, capability: &'static str) {
+        if let Some(span) = self.ctx.span_of(node) {
+            self.hits.insert((
+                self.file.clone(),
+                span.start_line,
+                span.start_col,
+                api,
+                capability,
+            ));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for ApiCollector<'_> {
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        if !node.attrs.iter().any(is_test_attr) {
+            syn::visit::visit_item_mod(self, node);
+        }
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        if !node.attrs.iter().any(is_test_attr) {
+            syn::visit::visit_item_fn(self, node);
+        }
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        if !node.attrs.iter().any(is_test_attr) {
+            syn::visit::visit_impl_item_fn(self, node);
+        }
+    }
+
+    fn visit_path(&mut self, node: &'ast syn::Path) {
+        let segments: Vec<String> = node.segments.iter().map(|s| s.ident.to_string()).collect();
+        for pair in segments.windows(2) {
+            for (enum_name, variant, capability) in VARIANTS {
+                if pair[0] == *enum_name && (*variant == "*" || pair[1] == *variant) {
+                    self.record(node, format!("{}::{}", pair[0], pair[1]), capability);
+                }
+            }
+        }
+        syn::visit::visit_path(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        let method = node.method.to_string();
+        if let Some((_, capability)) = QUERIER_METHODS.iter().find(|(m, _)| *m == method) {
+            self.record(&node.method, format!("{method}()"), capability);
+        }
+        let ordered = node.args.last().is_some_and(|arg| {
+            quote::ToTokens::to_token_stream(arg)
+                .into_iter()
+                .any(|t| t.to_string() == "Order")
+        });
+        if ordered && ITERATOR_METHODS.contains(&method.as_str()) {
+            self.record(&node.method, format!("{method}()"), "iterator");
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+}
+
+impl Detector for UnsupportedCapability {
+    fn name(&self) -> &str {
+        "unsupported-capability"
+    }
+
+    fn description(&self) -> &str {
+        "Detects APIs and entry points the selected target chain's CosmWasm runtime does not support"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::High
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let Some((chain, profile)) = ctx.config().target_chain() else {
+            return Vec::new();
+        };
+
+        let mut collector = ApiCollector {
+            ctx,
+            file: PathBuf::new(),
+            hits: BTreeSet::new(),
+        };
+        for (file, ast) in ctx.raw_asts() {
+            if ast.attrs.iter().any(is_test_attr) {
+                continue;
+            }
+            collector.file = file.clone();
+            collector.visit_file(ast);
+        }
+        for ep in ctx
+            .contract
+            .entry_points
+            .iter()
+            .filter(|ep| ep.name.starts_with("ibc_"))
+        {
+            collector.hits.insert((
+                ep.span.file.clone(),
+                ep.span.start_line,
+                ep.span.start_col,
+                format!("IBC entry point `{}`", ep.name),
+                "stargate",
+            ));
+        }
+
+        // One finding per line and capability: `CosmosMsg::Staking(StakingMsg::Delegate ..)`
+        // is a single use
+        let mut seen = BTreeSet::new();
+        let mut findings = Vec::new();
+        for (file, line, col, api, capability) in collector.hits {
+            if profile.supports(capability) || !seen.insert((file.clone(), line, capability)) {
+                continue;
+            }
+            let api = if api.starts_with("IBC") {
+                api
+            } else {
+                format!("`{api}`")
+            };
+            let needed = describe(capability);
+            findings.push(Finding {
+                detector_name: self.name().to_string(),
+                title: format!("{api} needs {needed}, which chain `{chain}` lacks"),
+                description: format!(
+                    "{api} needs {needed}, but the `{chain}` profile does not provide it. \
+                     wasmd rejects code requiring capabilities the chain lacks when it is \
+                     stored, and calls into missing modules fail at runtime, so the contract \
+                     cannot be deployed or used there as written."
+                ),
+                severity: Severity::Medium,
+                confidence: Confidence::High,
+                locations: vec![SourceLocation {
+                    file,
+                    start_line: line,
+                    end_line: line,
+                    start_col: col,
+                    end_col: col,
+                    snippet: None,
+                    byte_range: None,
+                }],
+                recommendation: Some(format!(
+                    "Gate this code behind a cargo feature left off for `{chain}`, or drop it; \
+                     if the chain does provide it, add it to the `[chains.{chain}]` profile."
+                )),
+                fix: None,
+                ..Default::default()
+            });
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::config::Config;
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+
+    fn analyze_with(source: &str, config: &Config) -> Vec<Finding> {
+        let contract =
+            ContractVisitor::extract(PathBuf::from("src/lib.rs"), parse_source(source).unwrap());
+        let ir = IrBuilder::build_contract(&contract);
+        let sources = HashMap::from([(PathBuf::from("src/lib.rs"), source.to_string())]);
+        let ctx = AnalysisContext::new(&contract, &ir, &sources).with_config(config);
+        UnsupportedCapability.detect(&ctx)
+    }
+
+    const SOURCE: &str = r#"
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    let holders: Vec<_> = BALANCES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    let delegate = CosmosMsg::Staking(StakingMsg::Delegate { validator, amount });
+    let raw = CosmosMsg::Stargate { type_url, value };
+    let supply = deps.querier.query_supply("uatom")?;
+    Ok(Response::new().add_message(delegate).add_message(raw))
+}
+
+#[entry_point]
+pub fn ibc_channel_open(deps: DepsMut, env: Env, msg: IbcChannelOpenMsg) -> StdResult<IbcChannelOpenResponse> {
+    Ok(None)
+}
+"#;
+
+    fn titles(config: &str) -> Vec<(String, usize)> {
+        let config: Config = toml::from_str(config).unwrap();
+        analyze_with(SOURCE, &config)
+            .into_iter()
+            .map(|f| (f.title, f.locations[0].start_line))
+            .collect()
+    }
+
+    #[test]
+    fn test_flags_apis_the_target_chain_lacks() {
+        let titles = titles(
+            "[global]\ntarget_chain = \"minimal\"\n\
+             [chains.minimal]\ncosmwasm = \"1.0\"\ncapabilities = []\n",
+        );
+        assert_eq!(
+            titles,
+            vec![
+                (
+                    "`keys()` needs the `iterator` capability, which chain `minimal` lacks".into(),
+                    5
+                ),
+                (
+                    "`CosmosMsg::Staking` needs the `staking` capability, which chain `minimal` lacks"
+                        .into(),
+                    7
+                ),
+                (
+                    "`CosmosMsg::Stargate` needs the `stargate` capability, which chain `minimal` lacks"
+                        .into(),
+                    8
+                ),
+                (
+                    "`query_supply()` needs CosmWasm 1.1, which chain `minimal` lacks".into(),
+                    9
+                ),
+                (
+                    "IBC entry point `ibc_channel_open` needs the `stargate` capability, which chain `minimal` lacks"
+                        .into(),
+                    14
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_supported_capabilities_and_no_target_are_silent() {
+        assert!(titles(
+            "[global]\ntarget_chain = \"full\"\n\
+             [chains.full]\ncosmwasm = \"1.5\"\ncapabilities = [\"iterator\", \"staking\", \"stargate\"]\n",
+        )
+        .is_empty());
+        assert!(titles("[chains.full]\ncapabilities = []\n").is_empty());
+    }
+}
@@ -0,0 +1,430 @@
+use cosmwasm_guard::ast::MessageKind;
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use syn::visit::Visit;
+
+/// Detects cw20 `Receive(Cw20ReceiveMsg)` hooks that act on the wrapped
+/// `sender`/`amount` without checking that `info.sender` is an expected token
+/// contract. Anyone can call the hook from a fake token contract and claim a
+/// deposit that never happened.
+pub struct UnverifiedCw20Receive;
+
+/// Methods that look the caller up in a whitelist (`TOKENS.has(storage, &info.sender)`)
+const LOOKUP_METHODS: &[&str] = &["has", "load", "may_load", "contains", "contains_key"];
+
+/// Struct fields that identify which token an amount is denominated in
+const TOKEN_FIELDS: &[&str] = &["address", "token", "contract", "denom"];
+
+/// Macros whose condition aborts the handler
+const CHECK_MACROS: &[&str] = &[
+    "ensure",
+    "ensure_eq",
+    "ensure_ne",
+    "assert",
+    "assert_eq",
+    "require",
+];
+
+/// `Enum::Variant` pattern -> (enum, variant)
+fn variant_of(pat: &syn::Pat) -> Option<(String, String)> {
+    let path = match pat {
+        syn::Pat::Struct(p) => &p.path,
+        syn::Pat::TupleStruct(p) => &p.path,
+        syn::Pat::Path(p) => &p.path,
+        _ => return None,
+    };
+    let n = path.segments.len();
+    if n < 2 {
+        return None;
+    }
+    Some((
+        path.segments[n - 2].ident.to_string(),
+        path.segments[n - 1].ident.to_string(),
+    ))
+}
+
+fn is_info_sender(expr: &syn::Expr) -> bool {
+    match expr {
+        syn::Expr::Field(field) => {
+            matches!(&field.member, syn::Member::Named(m) if m == "sender")
+                && matches!(field.base.as_ref(), syn::Expr::Path(p) if p.path.is_ident("info"))
+        }
+        syn::Expr::Reference(r) => is_info_sender(&r.expr),
+        syn::Expr::Paren(p) => is_info_sender(&p.expr),
+        syn::Expr::MethodCall(m) => is_info_sender(&m.receiver),
+        _ => false,
+    }
+}
+
+/// Whether an expression names a configured token address (`config.cw20_addr`, `token`)
+fn names_token(expr: &syn::Expr) -> bool {
+    struct TokenIdent(bool);
+    impl<'ast> Visit<'ast> for TokenIdent {
+        fn visit_ident(&mut self, ident: &'ast syn::Ident) {
+            let name = ident.to_string().to_lowercase();
+            self.0 |= name.contains("token") || name.contains("cw20");
+        }
+    }
+    let mut finder = TokenIdent(false);
+    finder.visit_expr(expr);
+    finder.0
+}
+
+/// Match arms on a cw20 receive variant, across a function body
+struct ReceiveArmCollector<'a, 'ast> {
+    variants: &'a [(String, String)],
+    arms: Vec<&'ast syn::Arm>,
+}
+
+impl<'ast> Visit<'ast> for ReceiveArmCollector<'_, 'ast> {
+    fn visit_arm(&mut self, node: &'ast syn::Arm) {
+        if variant_of(&node.pat).is_some_and(|v| self.variants.contains(&v)) {
+            self.arms.push(node);
+        }
+        syn::visit::visit_arm(self, node);
+    }
+}
+
+/// Crate functions called from a block
+#[derive(Default)]
+struct CallCollector {
+    calls: Vec<String>,
+}
+
+impl<'ast> Visit<'ast> for CallCollector {
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(path) = node.func.as_ref() {
+            if let Some(last) = path.path.segments.last() {
+                self.calls.push(last.ident.to_string());
+            }
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+}
+
+/// How the receive handling code treats the hook's caller and payload
+#[derive(Default)]
+struct HookUsage {
+    /// Reads the wrapped `sender` or `amount` of the `Cw20ReceiveMsg`
+    uses_payload: bool,
+    /// Compares `info.sender` with a token address, looks it up in a whitelist,
+    /// or records it as the token the amount is denominated in
+    verifies_token: bool,
+}
+
+impl<'ast> Visit<'ast> for HookUsage {
+    fn visit_expr_field(&mut self, node: &'ast syn::ExprField) {
+        if let syn::Member::Named(member) = &node.member {
+            let is_info =
+                matches!(node.base.as_ref(), syn::Expr::Path(p) if p.path.is_ident("info"));
+            if (member == "sender" || member == "amount") && !is_info {
+                self.uses_payload = true;
+            }
+        }
+        syn::visit::visit_expr_field(self, node);
+    }
+
+    fn visit_pat_struct(&mut self, node: &'ast syn::PatStruct) {
+        let is_receive_msg = node
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "Cw20ReceiveMsg");
+        if is_receive_msg {
+            self.uses_payload = true;
+        }
+        syn::visit::visit_pat_struct(self, node);
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        if matches!(node.op, syn::BinOp::Eq(_) | syn::BinOp::Ne(_)) {
+            let (left, right) = (node.left.as_ref(), node.right.as_ref());
+            if is_info_sender(left)
+                || is_info_sender(right)
+                || names_token(left)
+                || names_token(right)
+            {
+                self.verifies_token = true;
+            }
+        }
+        syn::visit::visit_expr_binary(self, node);
+    }
+
+    fn visit_field_value(&mut self, node: &'ast syn::FieldValue) {
+        // `Cw20Coin { address: info.sender.to_string(), amount }`: the credit is
+        // in whatever token called, so a fake token only mints fake credit
+        if let syn::Member::Named(member) = &node.member {
+            let name = member.to_string();
+            if TOKEN_FIELDS.iter().any(|f| name.contains(f)) && is_info_sender(&node.expr) {
+                self.verifies_token = true;
+            }
+        }
+        syn::visit::visit_field_value(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if LOOKUP_METHODS.contains(&node.method.to_string().as_str())
+            && node.args.iter().any(is_info_sender)
+        {
+            self.verifies_token = true;
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        let is_check = node
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| CHECK_MACROS.contains(&s.ident.to_string().as_str()));
+        if is_check {
+            let tokens = node.tokens.to_string().to_lowercase();
+            if tokens.contains("info . sender")
+                || tokens.contains("token")
+                || tokens.contains("cw20")
+            {
+                self.verifies_token = true;
+            }
+        }
+    }
+}
+
+impl Detector for UnverifiedCw20Receive {
+    fn name(&self) -> &str {
+        "unverified-cw20-receive"
+    }
+
+    fn description(&self) -> &str {
+        "Detects cw20 Receive hooks that trust the payload without checking the calling token contract"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Critical
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Beta
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let variants: Vec<(String, String)> = ctx
+            .contract
+            .message_enums
+            .iter()
+            .filter(|e| e.kind == MessageKind::Execute)
+            .flat_map(|e| {
+                e.variants
+                    .iter()
+                    .filter(|v| {
+                        v.fields
+                            .iter()
+                            .any(|f| f.type_name.contains("Cw20ReceiveMsg"))
+                    })
+                    .map(|v| (e.name.clone(), v.name.clone()))
+            })
+            .collect();
+        if variants.is_empty() {
+            return Vec::new();
+        }
+
+        let mut findings = Vec::new();
+        for func in &ctx.contract.functions {
+            let Some(body) = &func.body else { continue };
+            let mut collector = ReceiveArmCollector {
+                variants: &variants,
+                arms: Vec::new(),
+            };
+            collector.visit_block(body);
+
+            for arm in collector.arms {
+                // The arm plus every crate function it (transitively) calls
+                let mut calls = CallCollector::default();
+                calls.visit_arm(arm);
+                let roots: Vec<&str> = calls.calls.iter().map(String::as_str).collect();
+                let reachable = ctx.call_graph().reachable_from(&roots);
+
+                let mut usage = HookUsage::default();
+                usage.visit_arm(arm);
+                for handler in ctx
+                    .contract
+                    .functions
+                    .iter()
+                    .filter(|f| reachable.contains(&f.name))
+                {
+                    if let Some(body) = &handler.body {
+                        usage.visit_block(body);
+                    }
+                }
+                if !usage.uses_payload || usage.verifies_token {
+                    continue;
+                }
+
+                let (enum_name, variant) = variant_of(&arm.pat).unwrap_or_default();
+                let Some(start) = ctx.span_of(&arm.pat) else {
+                    continue;
+                };
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title: format!(
+                        "cw20 hook `{enum_name}::{variant}` does not verify the token contract"
+                    ),
+                    description: format!(
+                        "`{enum_name}::{variant}` acts on the `sender`/`amount` of a \
+                         `Cw20ReceiveMsg` without checking that `info.sender` is an accepted \
+                         cw20 contract. Anyone can deploy a fake token that calls this hook \
+                         and credit themselves with tokens that were never transferred."
+                    ),
+                    severity: Severity::Critical,
+                    confidence: Confidence::Medium,
+                    locations: vec![SourceLocation {
+                        file: func.span.file.clone(),
+                        start_line: start.start_line,
+                        end_line: start.start_line,
+                        start_col: start.start_col,
+                        end_col: start.start_col,
+                        snippet: None,
+                        byte_range: None,
+                    }],
+                    recommendation: Some(
+                        "Compare `info.sender` with the token address stored in config \
+                         (`if info.sender != config.cw20_addr { return Err(..) }`), or look it \
+                         up in a whitelist of accepted tokens."
+                            .to_string(),
+                    ),
+                    fix: None,
+                    ..Default::default()
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        UnverifiedCw20Receive.detect(&ctx)
+    }
+
+    fn contract(receive_body: &str) -> String {
+        format!(
+            r#"
+            pub enum ExecuteMsg {{
+                Receive(Cw20ReceiveMsg),
+                Withdraw {{}},
+            }}
+
+            #[entry_point]
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> Result<Response, ContractError> {{
+                match msg {{
+                    ExecuteMsg::Receive(wrapper) => execute_receive(deps, info, wrapper),
+                    ExecuteMsg::Withdraw {{}} => Ok(Response::new()),
+                }}
+            }}
+
+            fn execute_receive(deps: DepsMut, info: MessageInfo, wrapper: Cw20ReceiveMsg)
+                -> Result<Response, ContractError> {{
+                {receive_body}
+                let user = deps.api.addr_validate(&wrapper.sender)?;
+                BALANCES.update(deps.storage, &user, |b| -> StdResult<_> {{
+                    Ok(b.unwrap_or_default() + wrapper.amount)
+                }})?;
+                Ok(Response::new())
+            }}
+            "#
+        )
+    }
+
+    #[test]
+    fn test_detects_unverified_receive_hook() {
+        let findings = analyze(&contract(""));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Critical);
+        assert!(findings[0].title.contains("`ExecuteMsg::Receive`"));
+    }
+
+    #[test]
+    fn test_sender_compared_with_config_token() {
+        let check = r#"
+            let config = CONFIG.load(deps.storage)?;
+            if info.sender != config.cw20_addr {
+                return Err(ContractError::Unauthorized {});
+            }
+        "#;
+        assert!(analyze(&contract(check)).is_empty());
+    }
+
+    #[test]
+    fn test_ensure_eq_and_whitelist_count_as_verification() {
+        let ensure =
+            "ensure_eq!(info.sender, TOKEN.load(deps.storage)?, ContractError::Unauthorized {});";
+        assert!(analyze(&contract(ensure)).is_empty());
+
+        let whitelist = r#"
+            if !ACCEPTED_TOKENS.has(deps.storage, &info.sender) {
+                return Err(ContractError::UnknownToken {});
+            }
+        "#;
+        assert!(analyze(&contract(whitelist)).is_empty());
+    }
+
+    #[test]
+    fn test_amount_denominated_in_calling_token_is_fine() {
+        let record = r#"
+            let amount = Amount::Cw20(Cw20Coin {
+                address: info.sender.to_string(),
+                amount: wrapper.amount,
+            });
+        "#;
+        assert!(analyze(&contract(record)).is_empty());
+    }
+
+    #[test]
+    fn test_check_in_called_helper_is_followed() {
+        let source = contract("assert_token(deps.as_ref(), &info)?;")
+            + r#"
+            fn assert_token(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+                if info.sender != CONFIG.load(deps.storage)?.token {
+                    return Err(ContractError::Unauthorized {});
+                }
+                Ok(())
+            }
+            "#;
+        assert!(analyze(&source).is_empty());
+    }
+
+    #[test]
+    fn test_hook_ignoring_payload_is_fine() {
+        let source = r#"
+            pub enum ExecuteMsg {
+                Receive(Cw20ReceiveMsg),
+            }
+            #[entry_point]
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg)
+                -> Result<Response, ContractError> {
+                match msg {
+                    ExecuteMsg::Receive(_) => Ok(Response::new().add_attribute("action", "ping")),
+                }
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::HashSet;
+
+use cosmwasm_guard::ast::{EntryPointKind, SourceSpan, StorageType};
+use cosmwasm_guard::detector::{AnalysisContext, Detector, Maturity};
+use cosmwasm_guard::finding::*;
+use syn::visit::Visit;
+
+/// Detects votes weighted by the voter's current balance, stake or
+/// membership weight, loaded from a plain `Map` (or the latest value of a
+/// `SnapshotMap`) at vote time. Power that can be acquired after the
+/// proposal opens, e.g. with a flash loan or by moving tokens between
+/// accounts, then counts toward the tally; it should be read as of the
+/// proposal's start height instead.
+pub struct VotingPowerSnapshot;
+
+/// Name fragments of state items holding voting power
+const POWER_NAMES: &[&str] = &[
+    "weight",
+    "power",
+    "balance",
+    "stake",
+    "member",
+    "voter",
+    "voting",
+    "share",
+    "deposit",
+    "delegation",
+];
+
+/// Name segments of state items holding votes or proposals, not power
+const TALLY_NAMES: &[&str] = &[
+    "ballot",
+    "ballots",
+    "vote",
+    "votes",
+    "proposal",
+    "proposals",
+];
+
+/// Methods reading the current value of an item
+const LIVE_LOADS: &[&str] = &["load", "may_load"];
+
+fn segments(name: &str) -> impl Iterator<Item = String> + '_ {
+    name.split('_').map(str::to_lowercase)
+}
+
+fn is_vote_fn(name: &str) -> bool {
+    segments(name).any(|s| s == "vote")
+}
+
+fn is_power_item(name: &str) -> bool {
+    !segments(name).any(|s| TALLY_NAMES.contains(&s.as_str()))
+        && segments(name).any(|s| POWER_NAMES.iter().any(|p| s.contains(p)))
+}
+
+/// Live loads of state items in a function body, as (item, span)
+struct LoadFinder<'c> {
+    ctx: &'c AnalysisContext<'c>,
+    loads: Vec<(String, Option<SourceSpan>)>,
+}
+
+impl<'ast> Visit<'ast> for LoadFinder<'_> {
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if LIVE_LOADS.contains(&node.method.to_string().as_str()) {
+            if let syn::Expr::Path(path) = node.receiver.as_ref() {
+                if let Some(ident) = path.path.get_ident() {
+                    self.loads.push((ident.to_string(), self.ctx.span_of(node)));
+                }
+            }
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+}
+
+impl Detector for VotingPowerSnapshot {
+    fn name(&self) -> &str {
+        "voting-power-snapshot"
+    }
+
+    fn description(&self) -> &str {
+        "Detects votes weighted by live balances or weights instead of a snapshot taken at proposal creation"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::High
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn maturity(&self) -> Maturity {
+        Maturity::Experimental
+    }
+
+    fn detect(&self, ctx: &AnalysisContext) -> Vec<Finding> {
+        let entries_of = |setup: bool| -> Vec<&str> {
+            ctx.contract
+                .entry_points
+                .iter()
+                .filter(|ep| (ep.kind == EntryPointKind::Instantiate) == setup)
+                .map(|ep| ep.name.as_str())
+                .collect()
+        };
+        // Writes only instantiate can reach fix the weights for good, as in
+        // a fixed multisig; those need no snapshot
+        let later = ctx.call_graph().reachable_from(&entries_of(false));
+        let setup_only: HashSet<String> = ctx
+            .call_graph()
+            .reachable_from(&entries_of(true))
+            .into_iter()
+            .filter(|f| !later.contains(f))
+            .collect();
+        let mutable: HashSet<&str> = ctx
+            .contract
+            .functions
+            .iter()
+            .filter(|f| !setup_only.contains(&f.name))
+            .flat_map(|f| ctx.storage_writes(&f.name))
+            .map(|w| w.item.as_str())
+            .collect();
+
+        let vote_fns: Vec<&str> = ctx
+            .contract
+            .functions
+            .iter()
+            .filter(|f| is_vote_fn(&f.name))
+            .map(|f| f.name.as_str())
+            .collect();
+        let in_vote = ctx.call_graph().reachable_from(&vote_fns);
+
+        let mut findings = Vec::new();
+        let mut seen = HashSet::new();
+        for func in &ctx.contract.functions {
+            if !in_vote.contains(&func.name) {
+                continue;
+            }
+            let Some(body) = &func.body else { continue };
+            let mut finder = LoadFinder {
+                ctx,
+                loads: Vec::new(),
+            };
+            finder.visit_block(body);
+
+            for (name, span) in finder.loads {
+                let Some(item) = ctx.contract.state_items.iter().find(|s| s.name == name) else {
+                    continue;
+                };
+                if !is_power_item(&item.name)
+                    || !mutable.contains(item.name.as_str())
+                    || !seen.insert((func.name.clone(), item.name.clone()))
+                {
+                    continue;
+                }
+                let snapshot = matches!(
+                    item.storage_type,
+                    StorageType::SnapshotMap | StorageType::SnapshotItem
+                );
+                let span = span.as_ref().unwrap_or(&func.span);
+                findings.push(Finding {
+                    detector_name: self.name().to_string(),
+                    title: format!(
+                        "Voting power read from live `{}` in `{}`",
+                        item.name, func.name
+                    ),
+                    description: format!(
+                        "`{}` weighs a vote with the current value of `{}`{}. Balances or \
+                         weights acquired after the proposal opened, for example with a flash \
+                         loan or by moving tokens to a fresh account that votes again, count \
+                         toward the tally.",
+                        func.name,
+                        item.name,
+                        match snapshot {
+                            true => " rather than its snapshot at the proposal's start height",
+                            false => ", a plain map with no history",
+                        },
+                    ),
+                    severity: Severity::High,
+                    confidence: Confidence::Medium,
+                    locations: vec![SourceLocation {
+                        file: span.file.clone(),
+                        start_line: span.start_line,
+                        end_line: span.end_line,
+                        start_col: span.start_col,
+                        end_col: span.end_col,
+                        snippet: None,
+                        byte_range: None,
+                    }],
+                    recommendation: Some(
+                        "Store voting power in a `SnapshotMap` and read it with \
+                         `may_load_at_height(storage, voter, proposal.start_height)`, recording \
+                         the start height when the proposal is created."
+                            .to_string(),
+                    ),
+                    fix: None,
+                    ..Default::default()
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_guard::ast::{parse_source, ContractVisitor};
+    use cosmwasm_guard::ir::builder::IrBuilder;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn analyze(source: &str) -> Vec<Finding> {
+        let ast = parse_source(source).unwrap();
+        let contract = ContractVisitor::extract(PathBuf::from("test.rs"), ast);
+        let ir = IrBuilder::build_contract(&contract);
+        let mut sources = HashMap::new();
+        sources.insert(PathBuf::from("test.rs"), source.to_string());
+        let ctx = AnalysisContext::new(&contract, &ir, &sources);
+        VotingPowerSnapshot.detect(&ctx)
+    }
+
+    #[test]
+    fn test_detects_live_balance_in_vote() {
+        let source = r#"
+            const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
+            const BALLOTS: Map<(u64, &Addr), Uint128> = Map::new("ballots");
+
+            pub fn execute_stake(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+                BALANCES.update(deps.storage, &info.sender, |b| -> StdResult<_> {
+                    Ok(b.unwrap_or_default() + info.funds[0].amount)
+                })?;
+                Ok(Response::new())
+            }
+
+            pub fn execute_vote(deps: DepsMut, info: MessageInfo, proposal_id: u64) -> StdResult<Response> {
+                let power = BALANCES.load(deps.storage, &info.sender)?;
+                let ballot = BALLOTS.may_load(deps.storage, (proposal_id, &info.sender))?;
+                BALLOTS.save(deps.storage, (proposal_id, &info.sender), &power)?;
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("`BALANCES`"));
+        assert!(findings[0].description.contains("plain map"));
+        assert_eq!(findings[0].locations[0].start_line, 13);
+    }
+
+    #[test]
+    fn test_detects_latest_snapshot_value_in_helper() {
+        let source = r#"
+            const STAKED: SnapshotMap<&Addr, Uint128> = SnapshotMap::new("staked", "staked__cp", "staked__cl", Strategy::EveryBlock);
+
+            pub fn execute_bond(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+                STAKED.save(deps.storage, &info.sender, &info.funds[0].amount, env.block.height)?;
+                Ok(Response::new())
+            }
+
+            fn voting_power(deps: Deps, voter: &Addr) -> StdResult<Uint128> {
+                Ok(STAKED.may_load(deps.storage, voter)?.unwrap_or_default())
+            }
+
+            pub fn cast_vote(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+                let power = voting_power(deps.as_ref(), &info.sender)?;
+                Ok(Response::new())
+            }
+        "#;
+        let findings = analyze(source);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].title.contains("in `voting_power`"));
+        assert!(findings[0].description.contains("start height"));
+    }
+
+    #[test]
+    fn test_snapshot_at_height_not_flagged() {
+        let source = r#"
+            const STAKED: SnapshotMap<&Addr, Uint128> = SnapshotMap::new("staked", "staked__cp", "staked__cl", Strategy::EveryBlock);
+
+            pub fn execute_bond(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+                STAKED.save(deps.storage, &info.sender, &info.funds[0].amount, env.block.height)?;
+                Ok(Response::new())
+            }
+
+            pub fn execute_vote(deps: DepsMut, info: MessageInfo, proposal_id: u64) -> StdResult<Response> {
+                let proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+                let power = STAKED.may_load_at_height(deps.storage, &info.sender, proposal.start_height)?;
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+
+    #[test]
+    fn test_fixed_weights_not_flagged() {
+        let source = r#"
+            const VOTERS: Map<&Addr, u64> = Map::new("voters");
+
+            #[entry_point]
+            pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg) -> StdResult<Response> {
+                for voter in msg.voters {
+                    VOTERS.save(deps.storage, &voter.addr, &voter.weight)?;
+                }
+                Ok(Response::new())
+            }
+
+            pub fn execute_vote(deps: DepsMut, info: MessageInfo, proposal_id: u64) -> StdResult<Response> {
+                let weight = VOTERS.may_load(deps.storage, &info.sender)?;
+                Ok(Response::new())
+            }
+        "#;
+        assert!(analyze(source).is_empty());
+    }
+}